@@ -0,0 +1,56 @@
+/*
+ * C API for the analysis core of the `music` crate.
+ *
+ * Link against `libmusic_ffi` (built by `cargo build -p music-ffi --release`).
+ *
+ * Every function that produces text writes it into a caller-owned buffer with
+ * snprintf semantics: the output is always NUL-terminated and truncated to fit
+ * `cap`, and the return value is the full length of the result, excluding the NUL.
+ * A return value >= cap therefore signals truncation, and passing a NULL buffer
+ * with cap == 0 queries the required length.
+ * Negative return values are the MUSIC_ERR_* error codes below.
+ */
+#ifndef MUSIC_H
+#define MUSIC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A required pointer argument was NULL. */
+#define MUSIC_ERR_NULL_POINTER (-1)
+/* An argument could not be parsed, e.g. a pitch class above 11 or an unknown note name. */
+#define MUSIC_ERR_INVALID_INPUT (-2)
+/* The input is valid, but no chord name could be inferred for it. */
+#define MUSIC_ERR_UNNAMED (-3)
+
+/* The version of the library, as a static NUL-terminated string. */
+const char *music_version(void);
+
+/*
+ * Names the chord formed by `n` pitch classes (0 = C, ..., 11 = B).
+ * The first pitch class is taken as the root, and spelled the way it's
+ * most commonly written as the root of a chord, e.g. "Bb" rather than "A#".
+ */
+int32_t music_name_chord(const uint8_t *pcs, size_t n, char *out, size_t cap);
+
+/*
+ * Names the chord formed by a space-separated list of note names,
+ * e.g. "Eb G Bb D". The first note is taken as the root.
+ */
+int32_t music_name_notes(const char *notes, char *out, size_t cap);
+
+/*
+ * Spells `n` pitch classes, given relative to a root note (0 = the root itself),
+ * as a space-separated list of note names starting from the root.
+ */
+int32_t music_spell(const char *root, const uint8_t *pcs, size_t n, char *out, size_t cap);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MUSIC_H */
@@ -0,0 +1,222 @@
+//! A stable C API over the analysis core of the `music` crate, for embedding
+//! in hosts that can't take a Rust toolchain (e.g. DAW plugins).
+//!
+//! The matching header is `include/music.h`. Every function writes its result into a
+//! caller-owned buffer with `snprintf` semantics: the output is always NUL-terminated
+//! and truncated to fit `cap`, and the return value is the full length of the result,
+//! excluding the NUL. A return value `>= cap` therefore signals truncation, and passing
+//! a NULL buffer with `cap == 0` queries the required length.
+//! Negative return values are the `MUSIC_ERR_*` error codes.
+use std::ffi::{c_char, CStr};
+use std::slice;
+use music::note::{Note, Pc};
+use music::note_collections::chord_name::ChordName;
+use music::note_collections::spelling::spell_pc_set;
+use music::note_collections::{NoteSet, PcSet};
+
+/// A required pointer argument was NULL.
+pub const MUSIC_ERR_NULL_POINTER: i32 = -1;
+/// An argument could not be parsed, e.g. a pitch class above 11 or an unknown note name.
+pub const MUSIC_ERR_INVALID_INPUT: i32 = -2;
+/// The input is valid, but no chord name could be inferred for it.
+pub const MUSIC_ERR_UNNAMED: i32 = -3;
+
+/// The version of the library, as a static NUL-terminated string.
+#[no_mangle]
+pub extern "C" fn music_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Names the chord formed by `n` pitch classes (0 = C, ..., 11 = B).
+/// The first pitch class is taken as the root, and spelled the way it's
+/// most commonly written as the root of a chord, e.g. "Bb" rather than "A#".
+///
+/// # Safety
+/// `pcs` must point to `n` readable bytes, and `out` to `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn music_name_chord(
+    pcs: *const u8,
+    n: usize,
+    out: *mut c_char,
+    cap: usize,
+) -> i32 {
+    if pcs.is_null() {
+        return MUSIC_ERR_NULL_POINTER;
+    }
+    let pcs = slice::from_raw_parts(pcs, n);
+    if pcs.is_empty() || pcs.iter().any(|pc| *pc > 11) {
+        return MUSIC_ERR_INVALID_INPUT;
+    }
+    let root_pc = Pc::from(&pcs[0]);
+    let root = common_root_spelling(root_pc);
+    let pc_set = PcSet::new(pcs.iter()
+        .map(|pc| Pc::from(&root_pc.distance_up_to(&Pc::from(pc))))
+        .collect());
+    let notes = match spell_pc_set(&root, &pc_set) {
+        Ok(notes) => NoteSet::starting_from_first_note(notes),
+        Err(_) => return MUSIC_ERR_INVALID_INPUT,
+    };
+    name_and_write(&notes, out, cap)
+}
+
+/// Names the chord formed by a NUL-terminated, space-separated list of note names,
+/// e.g. `"Eb G Bb D"`. The first note is taken as the root.
+///
+/// # Safety
+/// `notes` must be a valid NUL-terminated string, and `out` must point to `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn music_name_notes(
+    notes: *const c_char,
+    out: *mut c_char,
+    cap: usize,
+) -> i32 {
+    let notes = match parse_notes(notes) {
+        Ok(notes) => notes,
+        Err(code) => return code,
+    };
+    name_and_write(&NoteSet::starting_from_first_note(notes), out, cap)
+}
+
+/// Spells `n` pitch classes, given relative to a root note (0 = the root itself),
+/// as a space-separated list of note names starting from the root.
+///
+/// # Safety
+/// `root` must be a valid NUL-terminated string, `pcs` must point to `n` readable bytes,
+/// and `out` to `cap` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn music_spell(
+    root: *const c_char,
+    pcs: *const u8,
+    n: usize,
+    out: *mut c_char,
+    cap: usize,
+) -> i32 {
+    if pcs.is_null() {
+        return MUSIC_ERR_NULL_POINTER;
+    }
+    let root = match parse_notes(root) {
+        Ok(notes) if notes.len() == 1 => notes[0],
+        Ok(_) => return MUSIC_ERR_INVALID_INPUT,
+        Err(code) => return code,
+    };
+    let pcs = slice::from_raw_parts(pcs, n);
+    if pcs.iter().any(|pc| *pc > 11) {
+        return MUSIC_ERR_INVALID_INPUT;
+    }
+    let pc_set = PcSet::new(pcs.iter().map(Pc::from).collect());
+    match pc_set.try_spell(&root) {
+        Ok(notes) => write_out(&join(&notes), out, cap),
+        Err(_) => MUSIC_ERR_INVALID_INPUT,
+    }
+}
+
+fn name_and_write(notes: &NoteSet, out: *mut c_char, cap: usize) -> i32 {
+    match ChordName::from_note_set(notes) {
+        Some(name) => write_out(&name.symbol(None), out, cap),
+        None => MUSIC_ERR_UNNAMED,
+    }
+}
+
+unsafe fn parse_notes(notes: *const c_char) -> Result<Vec<Note>, i32> {
+    if notes.is_null() {
+        return Err(MUSIC_ERR_NULL_POINTER);
+    }
+    let notes = CStr::from_ptr(notes).to_str().map_err(|_| MUSIC_ERR_INVALID_INPUT)?;
+    let notes: Vec<Note> = notes.split_whitespace()
+        .map(|note| note.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| MUSIC_ERR_INVALID_INPUT)?;
+    if notes.is_empty() {
+        return Err(MUSIC_ERR_INVALID_INPUT);
+    }
+    Ok(notes)
+}
+
+fn join(notes: &[Note]) -> String {
+    notes.iter().map(Note::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// The spelling of each pitch class as it usually appears in a chord symbol.
+fn common_root_spelling(pc: Pc) -> Note {
+    match pc {
+        Pc::Pc1 => Note::Des,
+        Pc::Pc3 => Note::Ees,
+        Pc::Pc6 => Note::Fis,
+        Pc::Pc8 => Note::Aes,
+        Pc::Pc10 => Note::Bes,
+        _ => pc.notes()[0],
+    }
+}
+
+/// Writes `s` into `out` with `snprintf` semantics, returning the length of `s`.
+fn write_out(s: &str, out: *mut c_char, cap: usize) -> i32 {
+    let len = i32::try_from(s.len()).unwrap_or(i32::MAX);
+    if cap == 0 {
+        return len;
+    }
+    if out.is_null() {
+        return MUSIC_ERR_NULL_POINTER;
+    }
+    let written = s.len().min(cap - 1);
+    // SAFETY: the caller guarantees `out` points to `cap` writable bytes,
+    // and we write at most `cap - 1` bytes plus the NUL terminator.
+    unsafe {
+        std::ptr::copy_nonoverlapping(s.as_ptr(), out as *mut u8, written);
+        *out.add(written) = 0;
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(f: impl Fn(*mut c_char, usize) -> i32) -> (i32, String) {
+        let mut buf = [0 as c_char; 32];
+        let code = f(buf.as_mut_ptr(), buf.len());
+        let s = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap().to_string();
+        (code, s)
+    }
+
+    #[test]
+    fn c_api() {
+        let pcs = [10u8, 2, 5, 9];
+        let (code, name) = call(|out, cap| unsafe {
+            music_name_chord(pcs.as_ptr(), pcs.len(), out, cap)
+        });
+        assert_eq!(name, "BbMaj7");
+        assert_eq!(code, 6);
+
+        let (_, name) = call(|out, cap| unsafe {
+            music_name_notes(c"Eb G Bb D".as_ptr(), out, cap)
+        });
+        assert_eq!(name, "EbMaj7");
+
+        let pcs = [0u8, 3, 7];
+        let (_, spelled) = call(|out, cap| unsafe {
+            music_spell(c"F#".as_ptr(), pcs.as_ptr(), pcs.len(), out, cap)
+        });
+        assert_eq!(spelled, "F# A C#");
+
+        // Truncation, and querying the required length.
+        let (code, name) = call(|out, _| unsafe {
+            music_name_notes(c"C E G B".as_ptr(), out, 3)
+        });
+        assert_eq!((code, name.as_str()), (5, "CM"));
+        let code = unsafe { music_name_notes(c"C E G B".as_ptr(), std::ptr::null_mut(), 0) };
+        assert_eq!(code, 5);
+
+        let pcs = [0u8, 12];
+        let code = unsafe { music_name_chord(pcs.as_ptr(), pcs.len(), std::ptr::null_mut(), 0) };
+        assert_eq!(code, MUSIC_ERR_INVALID_INPUT);
+        let code = unsafe { music_name_notes(c"C C# D".as_ptr(), std::ptr::null_mut(), 0) };
+        assert_eq!(code, MUSIC_ERR_UNNAMED);
+        // Two notes are named as an interval.
+        let (_, name) = call(|out, cap| unsafe {
+            music_name_notes(c"C D".as_ptr(), out, cap)
+        });
+        assert_eq!(name, "C(M2)");
+        let code = unsafe { music_name_notes(std::ptr::null(), std::ptr::null_mut(), 0) };
+        assert_eq!(code, MUSIC_ERR_NULL_POINTER);
+    }
+}
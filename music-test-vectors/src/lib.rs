@@ -0,0 +1,256 @@
+//! Expected results shared between the test suites of `music` and `musical-combinatorics`,
+//! so that the two crates can't drift apart in how they name, identify, and spell the same
+//! pitch class sets without a test failing on one side or the other.
+//!
+//! Everything is written as plain strings and numbers, rather than the crates' own types,
+//! so this crate doesn't depend on either of them.
+
+/// The roots every vector is spelled from, as in [ChordVector::spellings].
+pub const ROOTS: [&str; 4] = ["C", "Eb", "F#", "A"];
+
+/// A chord of three or four notes, and what each crate makes of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChordVector {
+    /// The chord's pitch classes, zeroed and in order.
+    pub pcs: &'static [u8],
+    /// The chord's name with a root of C, as `music` displays it by default,
+    /// or `None` if it can't be named.
+    pub name: Option<&'static str>,
+    /// The name of the `musical-combinatorics` quality the chord is a mode of,
+    /// and which mode of it the chord is.
+    pub combinatorial: (&'static str, usize),
+    /// The chord spelled up from each of [ROOTS], in order.
+    pub spellings: [&'static [&'static str]; 4],
+}
+
+/// A seven note scale, and what each crate makes of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleVector {
+    /// The scale's pitch classes, zeroed and in order.
+    pub pcs: &'static [u8],
+    /// The scale's name in `music`'s named scale corpus, if it's there.
+    pub name: Option<&'static str>,
+    /// The name of the `musical-combinatorics` seven note scale quality.
+    pub combinatorial: &'static str,
+    /// The scale spelled up from each of [ROOTS], in order.
+    pub spellings: [&'static [&'static str]; 4],
+}
+
+pub const CHORDS: &[ChordVector] = &[
+    // Triads
+    ChordVector {
+        pcs: &[0, 4, 7],
+        name: Some("CMaj"),
+        combinatorial: ("Major", 0),
+        spellings: [&["C", "E", "G"], &["Eb", "G", "Bb"], &["F#", "A#", "C#"], &["A", "C#", "E"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 7],
+        name: Some("Cmin"),
+        combinatorial: ("Minor", 0),
+        spellings: [&["C", "Eb", "G"], &["Eb", "Gb", "Bb"], &["F#", "A", "C#"], &["A", "C", "E"]],
+    },
+    ChordVector {
+        pcs: &[0, 4, 8],
+        name: Some("CAug"),
+        combinatorial: ("Aug", 0),
+        spellings: [&["C", "E", "G#"], &["Eb", "G", "Cb"], &["F#", "A#", "D"], &["A", "C#", "E#"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 6],
+        name: Some("Cdim"),
+        combinatorial: ("Dim", 0),
+        spellings: [&["C", "Eb", "Gb"], &["Eb", "Gb", "A"], &["F#", "A", "C"], &["A", "C", "Eb"]],
+    },
+    ChordVector {
+        pcs: &[0, 5, 7],
+        name: Some("Csus4"),
+        combinatorial: ("PP", 0),
+        spellings: [&["C", "F", "G"], &["Eb", "Ab", "Bb"], &["F#", "B", "C#"], &["A", "D", "E"]],
+    },
+    ChordVector {
+        pcs: &[0, 2, 7],
+        name: Some("Csus2"),
+        combinatorial: ("PP", 2),
+        spellings: [&["C", "D", "G"], &["Eb", "F", "Bb"], &["F#", "G#", "C#"], &["A", "B", "E"]],
+    },
+    // A major triad in first inversion, zeroed on its third.
+    ChordVector {
+        pcs: &[0, 3, 8],
+        name: None,
+        combinatorial: ("Major", 2),
+        spellings: [&["C", "Eb", "Ab"], &["Eb", "Gb", "B"], &["F#", "A", "D"], &["A", "C", "F"]],
+    },
+    ChordVector {
+        pcs: &[0, 1, 2],
+        name: None,
+        combinatorial: ("HH", 0),
+        spellings: [&["C", "Db", "D"], &["Eb", "Fb", "F"], &["F#", "G", "G#"], &["A", "Bb", "B"]],
+    },
+    ChordVector {
+        pcs: &[0, 2, 4],
+        name: Some("Cadd9"),
+        combinatorial: ("WW", 0),
+        spellings: [&["C", "D", "E"], &["Eb", "F", "G"], &["F#", "G#", "A#"], &["A", "B", "C#"]],
+    },
+    // Sevenths
+    ChordVector {
+        pcs: &[0, 4, 7, 11],
+        name: Some("CMaj7"),
+        combinatorial: ("Maj7", 0),
+        spellings: [&["C", "E", "G", "B"], &["Eb", "G", "Bb", "D"], &["F#", "A#", "C#", "E#"], &["A", "C#", "E", "G#"]],
+    },
+    ChordVector {
+        pcs: &[0, 4, 7, 10],
+        name: Some("C7"),
+        combinatorial: ("Dom7", 0),
+        spellings: [&["C", "E", "G", "Bb"], &["Eb", "G", "Bb", "Db"], &["F#", "A#", "C#", "E"], &["A", "C#", "E", "G"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 7, 10],
+        name: Some("Cmin7"),
+        combinatorial: ("Min7", 0),
+        spellings: [&["C", "Eb", "G", "Bb"], &["Eb", "Gb", "Bb", "Db"], &["F#", "A", "C#", "E"], &["A", "C", "E", "G"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 7, 11],
+        name: Some("CminMaj7"),
+        combinatorial: ("MinMaj7", 0),
+        spellings: [&["C", "Eb", "G", "B"], &["Eb", "Gb", "Bb", "D"], &["F#", "A", "C#", "E#"], &["A", "C", "E", "G#"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 6, 9],
+        name: Some("Cdim7"),
+        combinatorial: ("Dim7", 0),
+        spellings: [&["C", "Eb", "Gb", "A"], &["Eb", "Gb", "A", "C"], &["F#", "A", "C", "D#"], &["A", "C", "Eb", "F#"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 6, 10],
+        name: Some("Cmin7b5"),
+        combinatorial: ("Min7Flat5", 0),
+        spellings: [&["C", "Eb", "Gb", "Bb"], &["Eb", "Gb", "A", "Db"], &["F#", "A", "C", "E"], &["A", "C", "Eb", "G"]],
+    },
+    ChordVector {
+        pcs: &[0, 4, 8, 10],
+        name: Some("C+7"),
+        combinatorial: ("Aug7", 0),
+        spellings: [&["C", "E", "G#", "Bb"], &["Eb", "G", "Cb", "Db"], &["F#", "A#", "D", "E"], &["A", "C#", "E#", "G"]],
+    },
+    ChordVector {
+        pcs: &[0, 4, 8, 11],
+        name: Some("C+Maj7"),
+        combinatorial: ("AugMaj7", 0),
+        spellings: [&["C", "E", "G#", "B"], &["Eb", "G", "Cb", "D"], &["F#", "A#", "D", "E#"], &["A", "C#", "E#", "G#"]],
+    },
+    ChordVector {
+        pcs: &[0, 4, 6, 10],
+        name: Some("C7 (#11)"),
+        combinatorial: ("Dom7Flat5", 0),
+        spellings: [&["C", "E", "Gb", "Bb"], &["Eb", "G", "A", "Db"], &["F#", "A#", "B#", "E"], &["A", "C#", "Eb", "G"]],
+    },
+    // Chords named by `music` as modes of other `musical-combinatorics` qualities.
+    ChordVector {
+        pcs: &[0, 4, 7, 9],
+        name: Some("C6"),
+        combinatorial: ("Min7", 3),
+        spellings: [&["C", "E", "G", "A"], &["Eb", "G", "Bb", "C"], &["F#", "A#", "C#", "D#"], &["A", "C#", "E", "F#"]],
+    },
+    ChordVector {
+        pcs: &[0, 5, 7, 10],
+        name: Some("C7sus"),
+        combinatorial: ("PPP", 2),
+        spellings: [&["C", "F", "G", "Bb"], &["Eb", "Ab", "Bb", "Db"], &["F#", "B", "C#", "E"], &["A", "D", "E", "G"]],
+    },
+    ChordVector {
+        pcs: &[0, 2, 4, 7],
+        name: Some("Cadd9"),
+        combinatorial: ("Maj9", 0),
+        spellings: [&["C", "D", "E", "G"], &["Eb", "F", "G", "Bb"], &["F#", "G#", "A#", "C#"], &["A", "B", "C#", "E"]],
+    },
+    ChordVector {
+        pcs: &[0, 3, 6, 8],
+        name: Some("Cdim (b13)"),
+        combinatorial: ("Dom7", 3),
+        spellings: [&["C", "Eb", "Gb", "Ab"], &["Eb", "Gb", "A", "B"], &["F#", "A", "C", "D"], &["A", "C", "Eb", "F"]],
+    },
+];
+
+pub const SCALES: &[ScaleVector] = &[
+    ScaleVector {
+        pcs: &[0, 2, 4, 5, 7, 9, 11],
+        name: Some("Ionian"),
+        combinatorial: "Major",
+        spellings: [
+            &["C", "D", "E", "F", "G", "A", "B"],
+            &["Eb", "F", "G", "Ab", "Bb", "C", "D"],
+            &["F#", "G#", "A#", "B", "C#", "D#", "E#"],
+            &["A", "B", "C#", "D", "E", "F#", "G#"],
+        ],
+    },
+    ScaleVector {
+        pcs: &[0, 2, 3, 5, 7, 9, 11],
+        name: Some("Melodic Minor"),
+        combinatorial: "MelodicMinor",
+        spellings: [
+            &["C", "D", "Eb", "F", "G", "A", "B"],
+            &["Eb", "F", "Gb", "Ab", "Bb", "C", "D"],
+            &["F#", "G#", "A", "B", "C#", "D#", "E#"],
+            &["A", "B", "C", "D", "E", "F#", "G#"],
+        ],
+    },
+    ScaleVector {
+        pcs: &[0, 2, 3, 5, 7, 8, 11],
+        name: Some("Harmonic Minor"),
+        combinatorial: "HarmonicMinor",
+        spellings: [
+            &["C", "D", "Eb", "F", "G", "Ab", "B"],
+            &["Eb", "F", "Gb", "Ab", "Bb", "Cb", "D"],
+            &["F#", "G#", "A", "B", "C#", "D", "E#"],
+            &["A", "B", "C", "D", "E", "F", "G#"],
+        ],
+    },
+    ScaleVector {
+        pcs: &[0, 2, 4, 5, 7, 8, 11],
+        name: Some("Harmonic Major"),
+        combinatorial: "HarmonicMajor",
+        spellings: [
+            &["C", "D", "E", "F", "G", "Ab", "B"],
+            &["Eb", "F", "G", "Ab", "Bb", "Cb", "D"],
+            &["F#", "G#", "A#", "B", "C#", "D", "E#"],
+            &["A", "B", "C#", "D", "E", "F", "G#"],
+        ],
+    },
+    ScaleVector {
+        pcs: &[0, 1, 4, 5, 7, 8, 11],
+        name: Some("Double Harmonic"),
+        combinatorial: "MajorFlat9Flat13",
+        spellings: [
+            &["C", "Db", "E", "F", "G", "Ab", "B"],
+            &["Eb", "Fb", "G", "Ab", "Bb", "Cb", "D"],
+            &["F#", "G", "A#", "B", "C#", "D", "E#"],
+            &["A", "Bb", "C#", "D", "E", "F", "G#"],
+        ],
+    },
+    ScaleVector {
+        pcs: &[0, 1, 4, 6, 7, 9, 11],
+        name: None,
+        combinatorial: "LydianFlat9",
+        spellings: [
+            &["C", "Db", "E", "F#", "G", "A", "B"],
+            &["Eb", "Fb", "G", "A", "Bb", "C", "D"],
+            &["F#", "G", "A#", "B#", "C#", "D#", "E#"],
+            &["A", "Bb", "C#", "D#", "E", "F#", "G#"],
+        ],
+    },
+    ScaleVector {
+        pcs: &[0, 3, 4, 6, 7, 9, 10],
+        name: None,
+        combinatorial: "MixolydianSharp9Sharp11",
+        spellings: [
+            &["C", "D#", "E", "F#", "G", "A", "Bb"],
+            &["Eb", "F#", "G", "A", "Bb", "C", "Db"],
+            &["F#", "A", "A#", "B#", "C#", "D#", "E"],
+            &["A", "B#", "C#", "D#", "E", "F#", "G"],
+        ],
+    },
+];
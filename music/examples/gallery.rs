@@ -0,0 +1,162 @@
+/// Regenerates the reference scores in `examples/gallery`, or writes them to the directory given
+/// as the first argument: a chord dictionary page, a scale shape sheet, a lead sheet, and a
+/// rhythm exercise, as Lilypond, VexTab, or plain text.
+///
+/// `cargo test` checks that the notation backends still produce exactly these files, so rerun
+/// this with `cargo run --example gallery --features lilypond` after changing them on purpose.
+use std::path::PathBuf;
+use itertools::Itertools;
+use music::{FrettedNote, LeadSheet, Note, NoteSet, Pc, Progression, STD_6STR_GTR};
+use music::error::MusicSemanticsError;
+use music::fretboard::fretboard_shape::chord_shape_search::chord_shapes_on_strings;
+use music::fretboard::fretboard_shape::melodic_shape_search::n_note_per_string_shape;
+use music::notation::clef::Clef;
+use music::notation::escape::lilypond_string;
+use music::notation::lilypond::ToLilypondString;
+use music::notation::lilypond::document::LilypondHeader;
+use music::notation::lilypond::document::drum_staff::LilypondDrumStaff;
+use music::notation::lilypond::document::staff::LilypondStaff;
+use music::notation::lilypond::document::tab_staff::LilypondTabStaff;
+use music::notation::lilypond::format::{format_lilypond, LilypondFormat};
+use music::notation::lilypond::fretboard_diagram::{fretboard_diagram, labelled_frets, DiagramLabels};
+use music::notation::lilypond::scoring::{markup, score};
+use music::notation::lilypond::staff_elements::LilypondVoiceElement;
+use music::notation::rhythm::RhythmicNotatedEvent;
+use music::notation::rhythm::duration::Duration;
+use music::notation::rhythm::meter::{Meter, MeterDenominator};
+use music::notation::rhythm::percussion::{PercussionPattern, PercussionStyle};
+use music::notation::vextab::ToVexTab;
+use music::note_collections::{Key, KeyMode};
+use music::note_collections::chord_name::ChordName;
+use music::note_collections::voicing::VoicingType;
+use music::progression::progression_to_vextab;
+
+/// The file name and contents of each reference artifact.
+fn artifacts() -> Result<Vec<(&'static str, String)>, MusicSemanticsError> {
+    let (chords_ly, chords_vextab) = chord_dictionary()?;
+    let (scale_ly, scale_vextab) = scale_shape_sheet()?;
+    let (chart, lead_sheet_vextab) = lead_sheet()?;
+    Ok(vec![
+        ("chord_dictionary.ly", chords_ly),
+        ("chord_dictionary.vextab", chords_vextab),
+        ("scale_shapes.ly", scale_ly),
+        ("scale_shapes.vextab", scale_vextab),
+        ("lead_sheet.txt", chart),
+        ("lead_sheet.vextab", lead_sheet_vextab),
+        ("rhythm_exercise.ly", rhythm_exercise()),
+    ])
+}
+
+/// A page of top-level blocks, laid out as a [music::notation::lilypond::document::LilypondBuilder] would.
+fn document(title: &str, blocks: Vec<String>) -> String {
+    let header = LilypondHeader::new().title(Some(title.to_string())).to_lilypond_string();
+    format_lilypond(&[header].into_iter().chain(blocks).join("\n"), LilypondFormat::Pretty)
+}
+
+/// The drop 2 grip of each seventh chord on C, on the middle four strings,
+/// as labelled fretboard diagrams, and as tab.
+fn chord_dictionary() -> Result<(String, String), MusicSemanticsError> {
+    let chords = [
+        vec![Note::C, Note::E, Note::G, Note::B],
+        vec![Note::C, Note::E, Note::G, Note::Bes],
+        vec![Note::C, Note::Ees, Note::G, Note::Bes],
+        vec![Note::C, Note::Ees, Note::Ges, Note::Bes],
+    ];
+    let mut blocks = vec![];
+    let mut bars = vec![];
+    for notes in chords {
+        let name = ChordName::from_note_set(&NoteSet::starting_from_first_note(notes.clone()))
+            .ok_or(MusicSemanticsError::UnnamedChord(notes.clone()))?
+            .symbol(None);
+        let shape = chord_shapes_on_strings(&notes, &*STD_6STR_GTR, &[1, 2, 3, 4])?
+            .filter_map(Result::ok)
+            .map(|(_, shape)| shape)
+            .find(|shape| shape.is_playable()
+                && shape.voicing_type() == Some(VoicingType::Drop2)
+                && Pc::from(&shape.range().0.note) == Pc::from(&notes[0]))
+            .ok_or(MusicSemanticsError::NoPlayableShape(notes.clone()))?;
+        blocks.push(markup(format!("\\bold {}", lilypond_string(&name))));
+        blocks.push(fretboard_diagram(labelled_frets(&shape, &notes, DiagramLabels::Degrees))
+            .expect("the fretboard diagram template renders"));
+        let sounded = shape.iter()
+            .filter_map(|note| match note {
+                FrettedNote::Sounded(sounded) => Some(sounded.clone()),
+                FrettedNote::Muted { .. } => None,
+            })
+            .collect();
+        bars.push(format!("{} ${}$", RhythmicNotatedEvent::fretted_many(sounded, Duration::WHOLE).to_vextab(), name));
+    }
+    let vextab = format!("tabstave notation=true\nnotes {}\n", bars.join(" | "));
+    Ok((document("Drop 2 Seventh Chords", blocks), vextab))
+}
+
+/// G major, three notes to a string from the G on the low E string, picked legato, in notation and tab.
+fn scale_shape_sheet() -> Result<(String, String), MusicSemanticsError> {
+    let g_major = vec![Note::G, Note::A, Note::B, Note::C, Note::D, Note::E, Note::Fis];
+    let shape = n_note_per_string_shape((3, 3), &g_major, &Note::G, &STD_6STR_GTR)?;
+    let events = shape.to_notated_events(Duration::EIGHTH, true);
+    let voice = || events.iter().cloned().map(Into::into).collect::<Vec<LilypondVoiceElement>>();
+    let staff = LilypondStaff::new()
+        .clef(Some(Clef::Treble8ba))
+        .meter(Some(Meter::new(4, MeterDenominator::Four, None)))
+        .add_voice(voice())
+        .to_lilypond_string();
+    let tab = LilypondTabStaff::new().add_voice(voice()).to_lilypond_string();
+    let music = score(format!("<<\n{}\n{}\n>>", staff, tab), false)
+        .expect("the score template renders");
+    let vextab = format!("tabstave notation=true time=4/4\nnotes {}\n", events.to_vextab());
+    Ok((document("G Major, Three Notes per String", vec![music]), vextab))
+}
+
+/// A I-vi-IV-V turnaround in C, as a chord chart and as tab.
+fn lead_sheet() -> Result<(String, String), MusicSemanticsError> {
+    let chord = |notes: &[Note]| NoteSet::starting_from_first_note(notes.to_vec());
+    let whole = Duration::WHOLE.ticks();
+    let sheet = LeadSheet {
+        key: Key::new(Note::C, KeyMode::Major)?,
+        meter: Meter::new(4, MeterDenominator::Four, None),
+        progression: Progression::new(vec![
+            chord(&[Note::C, Note::E, Note::G]),
+            chord(&[Note::A, Note::C, Note::E]),
+            chord(&[Note::F, Note::A, Note::C]),
+            chord(&[Note::G, Note::B, Note::D, Note::F]),
+            chord(&[Note::C, Note::E, Note::G]),
+        ]).with_durations(vec![whole, whole, whole / 2, whole / 2, 2 * whole]),
+    };
+    let chart = format!("{}\n", sheet.chart(4)?);
+    let vextab = progression_to_vextab(&sheet.progression, &STD_6STR_GTR, &sheet.meter)?;
+    Ok((chart, vextab))
+}
+
+/// Two bars of a basic rock beat, on a drum staff.
+fn rhythm_exercise() -> String {
+    let pattern = PercussionPattern::new(&Meter::new(4, MeterDenominator::Four, None), PercussionStyle::Kit, 2);
+    let staff = LilypondDrumStaff::new(&pattern).bar_numbers(true).to_lilypond_string();
+    document("Rock Beat", vec![score(staff, false).expect("the score template renders")])
+}
+
+fn main() {
+    let dir = std::env::args().nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/gallery"));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, contents) in artifacts().unwrap() {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        println!("Wrote {}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gallery_matches_reference() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/gallery");
+        for (name, contents) in artifacts().unwrap() {
+            let reference = std::fs::read_to_string(dir.join(name)).unwrap();
+            assert_eq!(contents, reference, "{} has changed, see the gallery example to regenerate it", name);
+        }
+    }
+}
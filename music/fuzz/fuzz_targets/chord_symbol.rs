@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use music::note_collections::chord_name::corpus::{
+    closest_named_chords, find_named_chord, named_chords, normalize_quality_symbol,
+};
+
+fuzz_target!(|s: &str| {
+    let _ = normalize_quality_symbol(s);
+    if let Some(chord) = find_named_chord(s) {
+        let _ = chord.pc_set();
+    }
+    let corpus = named_chords();
+    let _ = closest_named_chords(s, &corpus);
+});
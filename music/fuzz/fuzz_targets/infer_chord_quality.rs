@@ -0,0 +1,24 @@
+#![no_main]
+
+use std::collections::HashSet;
+use libfuzzer_sys::fuzz_target;
+use music::note::{Note, Pc};
+use music::note_collections::chord_name::naming_heuristics::infer_chord_quality;
+use music::note_collections::chord_name::ChordName;
+use music::note_collections::NoteSet;
+
+// Each of the low twelve bits of the mask picks a pitch class, and the byte picks
+// which of them, if any, to treat as the first note.
+fuzz_target!(|input: (u16, u8)| {
+    let (mask, start) = input;
+    let pcs: HashSet<Pc> = (0..12u8)
+        .filter(|pc| mask & (1 << pc) != 0)
+        .map(|pc| Pc::from(&pc))
+        .collect();
+    let _ = infer_chord_quality(&pcs);
+    let notes: Vec<Note> = pcs.iter().map(|pc| pc.notes()[0]).collect();
+    let start = notes.get(usize::from(start) % (notes.len() + 1));
+    if let Some(name) = ChordName::from_note_set(&NoteSet::new(notes.clone(), start)) {
+        let _ = name.to_string(None);
+    }
+});
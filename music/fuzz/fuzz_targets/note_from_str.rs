@@ -0,0 +1,26 @@
+#![no_main]
+
+use std::str::FromStr;
+use libfuzzer_sys::fuzz_target;
+use music::note::spelling::{Accidental, Letter};
+use music::note::{Note, NoteNameLocale, Spelling};
+
+fuzz_target!(|s: &str| {
+    let _ = Letter::from_str(s);
+    let _ = Accidental::from_str(s);
+    let _ = Spelling::from_str(s);
+    // Whatever parses should be displayed the same way it's parsed back.
+    if let Ok(note) = Note::from_str(s) {
+        assert_eq!(Note::from_str(&note.to_string()).unwrap(), note);
+    }
+    for locale in [
+        NoteNameLocale::English,
+        NoteNameLocale::German,
+        NoteNameLocale::NorthernEuropean,
+        NoteNameLocale::FixedDo,
+    ] {
+        if let Ok(note) = locale.parse(s) {
+            assert_eq!(locale.parse(&locale.name(&note)).unwrap(), note);
+        }
+    }
+});
@@ -0,0 +1,167 @@
+//! Stable integer codes for the crate's enums, for storing analyses in a database,
+//! or passing them over FFI.
+//!
+//! The codes are a wire format: each is written out below, rather than taken from the order
+//! the variants are declared in, and a code is never reused or changed once it's given out.
+//! New variants take the next free code. Every enum converts into its code with `u8::from`,
+//! and back with `TryFrom<u8>`, which fails with [MusicSemanticsError::InvalidCode]
+//! for a code that isn't in use.
+//!
+//! [Pc] and [IntervalClass] already convert to and from their number of semitones, which is
+//! their code. They convert back from any `u8`, wrapping around the octave.
+//!
+//! Enums that carry values, such as [ChordQuality] and its subtypes, only convert into the code
+//! of their variant. Their values are coded by the enums they're made of, e.g. an [Alt] by its
+//! [AltChoice]s.
+//!
+//! [Alt]: crate::note_collections::chord_name::quality::chord::Alt
+//! [Pc]: crate::note::pitch_class::Pc
+//! [IntervalClass]: crate::note_collections::interval_class::IntervalClass
+use crate::error::MusicSemanticsError;
+use crate::note::note::Note;
+use crate::note::spelling::{Accidental, Letter};
+use crate::note_collections::applied_chord::{AppliedFunction, Resolution};
+use crate::note_collections::chord_name::quality::chord::{
+    AltChoice, AugSubtype, ChordQuality, DimSubtype, Extension, MajorSubtype, MinorSubtype, Omission, SusSubtype,
+};
+use crate::note_collections::chord_name::quality::scale::{
+    Alt2nd, Alt2ndMinor, Alt4th, Alt4thMinor, Alt6thAugMaj7, Alt6thDom7, Alt6thMaj7, ScaleMode, ScaleQuality,
+};
+use crate::note_collections::geometry::symmetry::collections::SymmetricCollection;
+use crate::note_collections::harmonic_function::HarmonicFunction;
+use crate::note_collections::interval::IntervalQuality;
+use crate::note_collections::key::KeyMode;
+use crate::note_collections::voicing::VoicingType;
+
+/// Implements the conversions to and from the codes of an enum without values.
+macro_rules! stable_codes {
+    ($name:ident { $($variant:ident = $code:literal),+ $(,)? }) => {
+        impl From<&$name> for u8 {
+            fn from(value: &$name) -> Self {
+                match value {
+                    $($name::$variant => $code,)+
+                }
+            }
+        }
+
+        impl From<$name> for u8 {
+            fn from(value: $name) -> Self {
+                u8::from(&value)
+            }
+        }
+
+        impl TryFrom<u8> for $name {
+            type Error = MusicSemanticsError;
+
+            fn try_from(code: u8) -> Result<Self, Self::Error> {
+                match code {
+                    $($code => Ok($name::$variant),)+
+                    _ => Err(MusicSemanticsError::InvalidCode(stringify!($name), code)),
+                }
+            }
+        }
+    };
+}
+
+/// Implements the conversion to the code of the variant of an enum with values.
+macro_rules! variant_codes {
+    ($name:ident { $($variant:ident = $code:literal),+ $(,)? }) => {
+        impl From<&$name> for u8 {
+            fn from(value: &$name) -> Self {
+                match value {
+                    $($name::$variant { .. } => $code,)+
+                }
+            }
+        }
+    };
+}
+
+// Notes are coded in the order they're declared.
+stable_codes!(Note {
+    C = 0, Deses = 1, Cis = 2, Des = 3, Cisis = 4, D = 5, Eeses = 6, Dis = 7, Ees = 8, Disis = 9,
+    E = 10, Fes = 11, Eis = 12, F = 13, Geses = 14, Fis = 15, Ges = 16, Fisis = 17, G = 18, Aeses = 19,
+    Gis = 20, Aes = 21, Gisis = 22, A = 23, Beses = 24, Ais = 25, Bes = 26, Aisis = 27, B = 28, Ces = 29,
+    Bis = 30,
+});
+stable_codes!(Letter { A = 0, B = 1, C = 2, D = 3, E = 4, F = 5, G = 6 });
+stable_codes!(Accidental { Natural = 0, Sharp = 1, Flat = 2, DoubleSharp = 3, DoubleFlat = 4 });
+
+stable_codes!(KeyMode { Major = 0, Minor = 1 });
+stable_codes!(HarmonicFunction { Tonic = 0, Subdominant = 1, Dominant = 2 });
+stable_codes!(AppliedFunction { Dominant = 0, LeadingTone = 1 });
+stable_codes!(Resolution { Expected = 0, Deceptive = 1, Unresolved = 2 });
+stable_codes!(VoicingType { Closed = 0, Drop2 = 1, Drop3 = 2, Drop2And4 = 3, SpreadTriad = 4 });
+stable_codes!(SymmetricCollection { WholeTone = 0, Octatonic = 1, Hexatonic = 2 });
+
+stable_codes!(AltChoice {
+    FlatNine = 0, Nine = 1, SharpNine = 2, FlatEleven = 3, Eleven = 4, SharpEleven = 5,
+    FlatThirteenth = 6, Thirteenth = 7, SharpThirteenth = 8,
+});
+stable_codes!(Extension { Seventh = 0, Ninth = 1, Eleventh = 2, Thirteenth = 3 });
+stable_codes!(Omission { Third = 0, Fifth = 1 });
+
+stable_codes!(ScaleMode {
+    Major = 0, Dorian = 1, Phrygian = 2, Lydian = 3, Mixolydian = 4, NaturalMinor = 5, Locrian = 6,
+    IonianAug = 7, LydianAug = 8, MixolydianAug = 9, MelodicMinor = 10, HarmonicMajor = 11,
+    HarmonicMinor = 12, Altered = 13, WholeTone = 14, AugAH = 15, AugHA = 16, DimHW = 17, DimWH = 18,
+    BebopDominant = 19, BebopMajor = 20, BebopDorian = 21, BebopMelodicMinor = 22,
+});
+stable_codes!(Alt2nd { Sharp = 0, Natural = 1, Flat = 2 });
+stable_codes!(Alt2ndMinor { Natural = 0, Flat = 1 });
+stable_codes!(Alt4th { Sharp = 0, Natural = 1 });
+stable_codes!(Alt4thMinor { Sharp = 0, Natural = 1, Flat = 2 });
+stable_codes!(Alt6thMaj7 { Sharp = 0, Natural = 1, Flat = 2 });
+stable_codes!(Alt6thDom7 { Natural = 0, Flat = 1 });
+stable_codes!(Alt6thAugMaj7 { Sharp = 0, Natural = 1 });
+
+variant_codes!(ChordQuality { Major = 0, Minor = 1, Aug = 2, Dim = 3, Sus = 4, Interval = 5, SingleNote = 6 });
+variant_codes!(MajorSubtype { Maj = 0, Maj6 = 1, Add9 = 2, Add11 = 3, SixNine = 4, MajN = 5, N = 6 });
+variant_codes!(MinorSubtype { Min = 0, Min6 = 1, MinAdd9 = 2, MinAdd11 = 3, Min69 = 4, MinMajN = 5, MinN = 6 });
+variant_codes!(AugSubtype { Aug = 0, AugMajN = 1, AugN = 2 });
+variant_codes!(DimSubtype { Dim = 0, MinNb5 = 1, DimN = 2, DimMajN = 3 });
+variant_codes!(SusSubtype { Sus2 = 0, Sus4 = 1, DomNSus = 2, MajNSus = 3, SixNineSus = 4 });
+variant_codes!(ScaleQuality {
+    Major = 0, IonianAug = 1, Dorian = 2, Phrygian = 3, Lydian = 4, LydianAug = 5, Mixolydian = 6,
+    MixolydianAug = 7, NaturalMinor = 8, MelodicMinor = 9, HarmonicMajor = 10, HarmonicMinor = 11,
+    Locrian = 12, Altered = 13, WholeTone = 14, AugAH = 15, AugHA = 16, DimHW = 17, DimWH = 18,
+    BebopDominant = 19, BebopMajor = 20, BebopDorian = 21, BebopMelodicMinor = 22, AddedTone = 23,
+});
+variant_codes!(IntervalQuality { Perfect = 0, Major = 1, Minor = 2, Augmented = 3, Diminished = 4 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::note::pitch_class::Pc;
+    use crate::note_collections::interval_class::IntervalClass;
+    use crate::note_collections::chord_name::quality::chord::Alt;
+
+    #[test]
+    fn stable_codes() {
+        for (i, pc) in (0..12u8).map(|pc| Pc::from(&pc)).enumerate() {
+            for note in pc.notes() {
+                assert_eq!(Note::try_from(u8::from(note)).unwrap(), note);
+            }
+            let code: u8 = (&IntervalClass::from(&(i as u8))).into();
+            assert_eq!(usize::from(code), i);
+        }
+        for mode in ScaleMode::ALL {
+            assert_eq!(ScaleMode::try_from(u8::from(mode)).unwrap(), mode);
+        }
+        // The codes given out can't change.
+        assert_eq!(u8::from(Note::C), 0);
+        assert_eq!(u8::from(Note::Bes), 26);
+        assert_eq!(u8::from(Note::Bis), 30);
+        assert_eq!(u8::from(ScaleMode::BebopMelodicMinor), 22);
+        assert_eq!(u8::from(AltChoice::SharpEleven), 5);
+        assert_eq!(Letter::try_from(6).unwrap(), Letter::G);
+        assert!(matches!(Note::try_from(31), Err(MusicSemanticsError::InvalidCode("Note", 31))));
+        assert!(matches!(Accidental::try_from(5), Err(MusicSemanticsError::InvalidCode("Accidental", 5))));
+
+        let quality = ChordQuality::Dim(DimSubtype::MinNb5(vec![Extension::Seventh], Alt::empty()));
+        assert_eq!(u8::from(&quality), 3);
+        assert_eq!(u8::from(&DimSubtype::MinNb5(vec![], Alt::empty())), 1);
+        assert_eq!(u8::from(&ScaleQuality::AddedTone(ScaleMode::Major, Pc::Pc8)), 23);
+        assert_eq!(u8::from(&IntervalQuality::Augmented(2)), 3);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::prelude::*;
 use thiserror::Error;
+#[cfg(feature = "std")]
 use crate::fretboard::Fretboard;
 use crate::note::note::Note;
 use crate::note::pitch::Pitch;
@@ -22,8 +24,15 @@ pub enum MusicSemanticsError {
     FretBelowZero(Pitch, Pitch),
     #[error("The current fretted note's fret is less than {0}")]
     CantMoveDownFrets(u8),
+    #[cfg(feature = "std")]
     #[error("Index {0} too high for strings on fretboard {1:?}")]
     StringTooHighForFretboard(u8, Fretboard),
+    #[error("Index {0} too high for an instrument with {1} strings")]
+    StringTooHigh(u8, u8),
+    #[error("The note {0} cannot be found on string {1}")]
+    NoteNotOnString(Note, u8),
+    #[error("The pitch {0} cannot be found on string {1}")]
+    PitchNotOnString(Pitch, u8),
     #[error("Excessive accidental: cannot spell {0} with {1}")]
     ExcessiveAccidental(Letter, Accidental),
     #[error("Invalid or excessive accidental: {0}")]
@@ -52,5 +61,57 @@ pub enum MusicSemanticsError {
     #[error("Collection size is not the same: {0} != {1}")]
     MismatchedCollectionSize(usize, usize),
     #[error("The following voiceleading rules were broken: {0:?}")]
-    VoiceleadingViolation(Vec<String>)
+    VoiceleadingViolation(Vec<String>),
+    #[error("The chord name has no root note to spell from")]
+    NoChordRoot,
+    #[error("Could not name the chord {0:?}")]
+    UnnamedChord(Vec<Note>),
+    #[error("No playable shape found for the chord {0:?}")]
+    NoPlayableShape(Vec<Note>),
+    #[error("No scale has every note of the chord {0:?}")]
+    NoChordScale(Vec<Note>),
+    #[error("No octave of the scale on {0} can be played in the position at fret {1}")]
+    ScaleOutOfPosition(Note, u8),
+    #[error("Failed to write file: {0}")]
+    FileWriteFailure(String),
+    #[error("The search was cancelled")]
+    SearchCancelled,
+    #[error("An event of {0} ticks overflows a measure with {1} ticks remaining")]
+    MeasureOverflow(usize, usize),
+    #[error("{0} syllables of the lyrics have no note to be sung on")]
+    UnalignedLyrics(usize),
+    #[error("No pitch of the note {0} lies within the range {1} to {2}")]
+    NoteOutOfRange(Note, Pitch, Pitch),
+    #[error("The pitch {0} cannot be played on any string")]
+    PitchOutOfRange(Pitch),
+    #[error("Unrecognized note name: {0}")]
+    InvalidNoteName(String),
+    #[error("The key of {0} would need more than seven sharps or flats")]
+    TheoreticalKey(Note),
+    #[error("At least {0} distinct notes are needed, but only {1} were given")]
+    TooFewNotes(usize, usize),
+    #[error("At most {0} distinct notes can be used, but {1} were given")]
+    TooManyNotes(usize, usize),
+    #[error("Unrecognized picking pattern: {0}")]
+    InvalidPickingPattern(String),
+    #[error("A pattern of {0} ticks doesn't evenly divide a measure of {1} ticks")]
+    PatternDoesNotFitMeasure(usize, usize),
+    #[error("A duration of {0} of a whole note can't be scaled by {1}/{2} into whole ticks")]
+    UnscalableDuration(String, usize, usize),
+    #[error("Durations can't be scaled by {0}/{1}")]
+    ZeroScale(usize, usize),
+    #[error("Failed to read file: {0}")]
+    FileReadFailure(String),
+    #[error("Invalid MIDI file: {0}")]
+    InvalidMidiFile(String),
+    #[error("Unrecognized duration: {0}")]
+    InvalidDuration(String),
+    #[error("Invalid form, {0}")]
+    InvalidForm(String),
+    #[error("No chords could be found in the music")]
+    NoChordsFound,
+    #[error("Failed to send OSC: {0}")]
+    OscFailure(String),
+    #[error("{1} is not the code of any {0}")]
+    InvalidCode(&'static str, u8),
 }
\ No newline at end of file
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretted_note::SoundedNote;
+use crate::note::pitch::Pitch;
+
+/// The cost of moving the hand to a new position, on top of one per fret moved,
+/// for each kind of shift. See [ShiftKind]. A jump costs enough that it's never preferred
+/// to a guide finger shift, even though choosing its fingers can save it three frets of movement.
+const JUMP_COST: usize = 5;
+const GUIDE_FINGER_COST: usize = 1;
+const OPEN_STRING_COST: usize = 0;
+/// The cost of a finger hopping from one string to another at the same fret.
+const FINGER_HOP_COST: usize = 1;
+
+/// A note of a melody, and how the fretting hand plays it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingeredNote<'a, F: FrettedInstrument = Fretboard> {
+    pub note: SoundedNote<'a, F>,
+    /// The finger that frets the note, from 1 for the index finger to 4 for the little finger,
+    /// or `None` for an open string.
+    pub finger: Option<u8>,
+    /// The position of the hand, i.e. the fret under the index finger,
+    /// with each finger covering the next fret up. The hand stays put for open strings.
+    pub position: u8,
+}
+
+/// How the hand gets from one position to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShiftKind {
+    /// The same finger plays the notes either side of the shift, on the same string,
+    /// and guides the hand along it. Played as a slide when the notes are connected.
+    GuideFinger,
+    /// The hand moves while an open string rings, so the shift can be made at leisure.
+    OpenString,
+    /// The hand leaves the string and lands in the new position.
+    Jump,
+}
+
+/// A point in a melody where the hand changes position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PositionShift {
+    /// The index in the melody of the first note played in the new position.
+    pub index: usize,
+    pub from: u8,
+    pub to: u8,
+    /// The finger that lands on the first note in the new position.
+    pub finger: u8,
+    pub kind: ShiftKind,
+}
+
+impl Display for PositionShift {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            ShiftKind::GuideFinger => "guide finger",
+            ShiftKind::OpenString => "over an open string",
+            ShiftKind::Jump => "jump",
+        };
+        write!(f, "note {}: position {} to {}, finger {} ({})", self.index, self.from, self.to, self.finger, kind)
+    }
+}
+
+/// A fingering of a whole melody, as found by [plan_fingering].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingering<'a, F: FrettedInstrument = Fretboard> {
+    pub notes: Vec<FingeredNote<'a, F>>,
+    pub shifts: Vec<PositionShift>,
+    /// The total cost of the shifts, where lower is better.
+    pub cost: usize,
+}
+
+/// One way of playing a note of the melody, in the search of [plan_fingering].
+#[derive(Debug, Clone)]
+struct FingeringState<'a, F: FrettedInstrument> {
+    note: SoundedNote<'a, F>,
+    finger: Option<u8>,
+    /// Unknown until the first fretted note, when a melody starts on open strings.
+    position: Option<u8>,
+    /// The total cost so far, with the sum of the positions played in to break ties
+    /// in favour of lower positions.
+    cost: (usize, usize),
+    /// The index of the previous state, in the states of the previous note.
+    previous: Option<usize>,
+}
+
+/// The kind of shift, if any, from one way of playing a note to the next, and its cost.
+fn shift<F: FrettedInstrument>(from: &FingeringState<F>, to: &FingeringState<F>) -> (Option<ShiftKind>, usize) {
+    let (Some(from_position), Some(to_position)) = (from.position, to.position) else {
+        return (None, 0);
+    };
+    if from_position == to_position {
+        let hops = from.finger.is_some() && from.finger == to.finger && from.note.string != to.note.string;
+        return (None, if hops { FINGER_HOP_COST } else { 0 });
+    }
+    let (kind, cost) = match from.finger {
+        None => (ShiftKind::OpenString, OPEN_STRING_COST),
+        Some(_) if from.finger == to.finger && from.note.string == to.note.string => {
+            (ShiftKind::GuideFinger, GUIDE_FINGER_COST)
+        },
+        Some(_) => (ShiftKind::Jump, JUMP_COST),
+    };
+    (Some(kind), cost + usize::from(from_position.abs_diff(to_position)))
+}
+
+/// Finds the fingering of `melody` on `fretboard` with the cheapest position shifts.
+///
+/// Each note is fretted within a four fret position, one finger per fret, or played open.
+/// Shifts cost one per fret moved, plus a penalty depending on their [ShiftKind], so that
+/// e.g. a guide finger slide is preferred to a jump of the same distance. Fingerings of
+/// equal cost are decided in favour of lower positions.
+pub fn plan_fingering<'a, F: FrettedInstrument>(
+    melody: &[Pitch],
+    fretboard: &'a F,
+) -> Result<Fingering<'a, F>, MusicSemanticsError> {
+    if melody.is_empty() {
+        return Err(MusicSemanticsError::EmptySetOfNotes);
+    }
+    let mut layers: Vec<Vec<FingeringState<'a, F>>> = vec![];
+    for pitch in melody {
+        let mut candidates: Vec<FingeringState<'a, F>> = vec![];
+        for string in 0..fretboard.num_strings() {
+            let Ok(fret) = fretboard.fret_for_pitch(string, pitch) else {
+                continue;
+            };
+            let note = fretboard.sounded_note(string, fret)?;
+            let state = |finger, position| FingeringState {
+                note: note.clone(),
+                finger,
+                position,
+                cost: (0, 0),
+                previous: None,
+            };
+            if fret == 0 {
+                candidates.push(state(None, None));
+                continue;
+            }
+            candidates.extend((1..=4u8)
+                .filter(|finger| fret >= *finger)
+                .map(|finger| state(Some(finger), Some(fret + 1 - finger))));
+        }
+        if candidates.is_empty() {
+            return Err(MusicSemanticsError::PitchOutOfRange(*pitch));
+        }
+        let Some(previous_layer) = layers.last() else {
+            layers.push(candidates.into_iter()
+                .map(|state| FingeringState {
+                    cost: (0, usize::from(state.position.unwrap_or(0))),
+                    ..state
+                })
+                .collect());
+            continue;
+        };
+        // The cheapest way of arriving at each distinct way of playing the note.
+        let mut best: HashMap<(u8, Option<u8>, Option<u8>), FingeringState<'a, F>> = HashMap::new();
+        for candidate in &candidates {
+            for (i, previous) in previous_layer.iter().enumerate() {
+                let mut state = candidate.clone();
+                if state.finger.is_none() {
+                    state.position = previous.position;
+                }
+                let (_, cost) = shift(previous, &state);
+                state.cost = (
+                    previous.cost.0 + cost,
+                    previous.cost.1 + usize::from(state.position.unwrap_or(0)),
+                );
+                state.previous = Some(i);
+                let key = (state.note.string, state.finger, state.position);
+                if best.get(&key).is_none_or(|existing| state.cost < existing.cost) {
+                    best.insert(key, state);
+                }
+            }
+        }
+        let mut layer: Vec<FingeringState<'a, F>> = best.into_values().collect();
+        // Keep the search deterministic, whatever order the map yields states in.
+        layer.sort_by_key(|state| (state.cost, state.note.string, state.finger, state.position));
+        layers.push(layer);
+    }
+
+    // Walk back from the cheapest way of playing the last note.
+    let last = layers.last().unwrap();
+    let mut index = (0..last.len()).min_by_key(|i| last[*i].cost).unwrap();
+    let cost = last[index].cost.0;
+    let mut path: Vec<&FingeringState<'a, F>> = vec![];
+    for layer in layers.iter().rev() {
+        let state = &layer[index];
+        path.push(state);
+        index = state.previous.unwrap_or(0);
+    }
+    path.reverse();
+
+    let mut shifts = vec![];
+    for (i, (from, to)) in path.iter().zip(path.iter().skip(1)).enumerate() {
+        if let (Some(kind), _) = shift(from, to) {
+            shifts.push(PositionShift {
+                index: i + 1,
+                from: from.position.unwrap(),
+                to: to.position.unwrap(),
+                finger: to.finger.unwrap(),
+                kind,
+            });
+        }
+    }
+    // A melody that starts on open strings starts in the position of its first fretted note.
+    let first_position = path.iter().find_map(|state| state.position).unwrap_or(1);
+    let notes = path.iter()
+        .map(|state| FingeredNote {
+            note: state.note.clone(),
+            finger: state.finger,
+            position: state.position.unwrap_or(first_position),
+        })
+        .collect();
+    Ok(Fingering { notes, shifts, cost })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::note::note::Note;
+    use crate::pitch;
+
+    #[test]
+    fn position_shifts() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        // A C major scale fits in first position.
+        let scale: Vec<Pitch> = [
+            pitch!(c, 4), pitch!(d, 4), pitch!(e, 4), pitch!(f, 4),
+            pitch!(g, 4), pitch!(a, 4), pitch!(b, 4), pitch!(c, 5),
+        ].to_vec();
+        let fingering = plan_fingering(&scale, fretboard).unwrap();
+        assert!(fingering.shifts.is_empty());
+        assert_eq!(fingering.cost, 0);
+        assert!(fingering.notes.iter().all(|note| note.position == 1));
+        assert_eq!(fingering.notes[0].finger, Some(3));
+
+        let climb: Vec<Pitch> = scale.iter()
+            .chain([pitch!(d, 5), pitch!(e, 5), pitch!(f, 5), pitch!(g, 5), pitch!(a, 5), pitch!(b, 5), pitch!(c, 6)].iter())
+            .cloned()
+            .collect();
+        let fingering = plan_fingering(&climb, fretboard).unwrap();
+        assert!(fingering.shifts.is_empty());
+        assert!(fingering.notes.iter().all(|note| note.position == 5));
+
+        // F3 is only at the first fret, and A6 no lower than the 17th.
+        let fingering = plan_fingering(&[pitch!(f, 3), pitch!(a, 6)], fretboard).unwrap();
+        assert_eq!(
+            fingering.shifts,
+            vec![PositionShift { index: 1, from: 1, to: 14, finger: 4, kind: ShiftKind::Jump }],
+        );
+        assert_eq!(fingering.cost, 18);
+        // An open B string in between gives the hand time to move.
+        let fingering = plan_fingering(&[pitch!(f, 3), pitch!(b, 4), pitch!(a, 6)], fretboard).unwrap();
+        assert_eq!(fingering.notes[1].finger, None);
+        assert_eq!(fingering.notes[1].position, 1);
+        assert_eq!(fingering.shifts[0].kind, ShiftKind::OpenString);
+        assert_eq!(fingering.cost, 13);
+        // On a single string, a finger guides the hand up to the next note.
+        let one_string = Fretboard::new(vec![pitch!(e, 3)]);
+        let fingering = plan_fingering(&[pitch!(g, 3), pitch!(d, 4)], &one_string).unwrap();
+        assert_eq!(
+            fingering.shifts,
+            vec![PositionShift { index: 1, from: 1, to: 8, finger: 3, kind: ShiftKind::GuideFinger }],
+        );
+        assert_eq!(fingering.shifts[0].to_string(), "note 1: position 1 to 8, finger 3 (guide finger)");
+
+        assert!(matches!(plan_fingering(&[], fretboard), Err(MusicSemanticsError::EmptySetOfNotes)));
+        assert!(matches!(
+            plan_fingering(&[Pitch::new(Note::C, 1).unwrap()], fretboard),
+            Err(MusicSemanticsError::PitchOutOfRange(_)),
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt::{Display, Formatter};
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::melodic_shape_search::MelodicFretboardShape;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+
+/// The open chord shapes of the CAGED system, which between them cover the neck
+/// in standard tuning, each moved up the neck as a barre chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CagedForm {
+    C,
+    A,
+    G,
+    E,
+    D,
+}
+
+impl CagedForm {
+    pub const ALL: [CagedForm; 5] = [CagedForm::C, CagedForm::A, CagedForm::G, CagedForm::E, CagedForm::D];
+
+    /// The root of the open chord the form is named for.
+    fn root(&self) -> Note {
+        match self {
+            CagedForm::C => Note::C,
+            CagedForm::A => Note::A,
+            CagedForm::G => Note::G,
+            CagedForm::E => Note::E,
+            CagedForm::D => Note::D,
+        }
+    }
+
+    /// The fret, and semitones above the root, of the note on each string of the open major chord,
+    /// from the lowest string up, including the optional bass notes often added to it.
+    fn template(&self) -> [Option<(u8, u8)>; 6] {
+        match self {
+            CagedForm::C => [Some((0, 4)), Some((3, 0)), Some((2, 4)), Some((0, 7)), Some((1, 0)), Some((0, 4))],
+            CagedForm::A => [Some((0, 7)), Some((0, 0)), Some((2, 7)), Some((2, 0)), Some((2, 4)), Some((0, 7))],
+            CagedForm::G => [Some((3, 0)), Some((2, 4)), Some((0, 7)), Some((0, 0)), Some((0, 4)), Some((3, 0))],
+            CagedForm::E => [Some((0, 0)), Some((2, 7)), Some((2, 0)), Some((1, 4)), Some((0, 7)), Some((0, 0))],
+            CagedForm::D => [Some((2, 4)), Some((0, 7)), Some((0, 0)), Some((2, 7)), Some((3, 0)), Some((2, 4))],
+        }
+    }
+}
+
+impl Display for CagedForm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} shape", self)
+    }
+}
+
+/// Whether the fretboard is a six string guitar in standard tuning, in any octave.
+/// CAGED forms are only defined for standard tuning.
+pub fn is_standard_tuning(fretboard: &Fretboard) -> bool {
+    let strings = &fretboard.open_strings;
+    strings.len() == 6
+        && Pc::from(&strings[0].note) == Pc::from(&Note::E)
+        && strings.windows(2)
+            .map(|pair| pair[1].midi_note as i16 - pair[0].midi_note as i16)
+            .eq([5, 5, 5, 4, 5])
+}
+
+impl<'a> FretboardShape<'a, Fretboard> {
+    /// The CAGED forms that a major or minor chord shape with the given `root` belongs to,
+    /// in CAGED order. Minor chords are matched against the major forms with their thirds lowered,
+    /// e.g. x-0-2-2-1-0 is an A form.
+    ///
+    /// Every root, third, and fifth of the shape must lie where the form puts them, moved up
+    /// the neck to the shape's root, and the shape must have its root and at least three such notes.
+    /// Other notes, e.g. sevenths, are ignored, so that x-3-2-3-1-0 is still a C form.
+    /// Small shapes can belong to more than one form, and shapes on other tunings belong to none.
+    pub fn caged_forms(&self, root: &Note) -> Vec<CagedForm> {
+        if !is_standard_tuning(self.fretboard) {
+            return vec![];
+        }
+        let sounded: Vec<&SoundedNote<'a>> = self.fretted_notes.iter()
+            .filter_map(|fretted_note| match fretted_note {
+                FrettedNote::Sounded(sounded_note) => Some(sounded_note),
+                FrettedNote::Muted { .. } => None,
+            })
+            .collect();
+        let root = Pc::from(root);
+        CagedForm::ALL.into_iter()
+            .filter(|form| {
+                let template = form.template();
+                let t = Pc::from(&form.root()).distance_up_to(&root);
+                [t, t + 12, t + 24].into_iter().any(|transposition| {
+                    let mut matched = 0;
+                    let mut has_root = false;
+                    for note in &sounded {
+                        let (semitones, lowered) = match root.distance_up_to(&Pc::from(&note.pitch.note)) {
+                            3 => (4, 1),
+                            semitones @ (0 | 4 | 7) => (semitones, 0),
+                            _ => continue,
+                        };
+                        let Some((fret, degree)) = template[usize::from(note.string)] else {
+                            return false;
+                        };
+                        if degree != semitones || fret + transposition != note.fret + lowered {
+                            return false;
+                        }
+                        matched += 1;
+                        has_root |= semitones == 0;
+                    }
+                    has_root && matched >= 3
+                })
+            })
+            .collect()
+    }
+}
+
+impl<'a> MelodicFretboardShape<'a> {
+    /// The position of a scale shape, numbered as in the common systems of five pentatonic
+    /// positions, or seven three-notes-per-string shapes: position `n` starts on the `n`th
+    /// note of `scale`, counting from its first note, on the lowest string.
+    ///
+    /// So for A minor pentatonic, the position starting on the A of the low E string is position 1,
+    /// and the one starting on its C is position 2. Returns `None` if the shape doesn't start
+    /// on the lowest string, or starts on a note outside `scale`.
+    pub fn scale_position(&self, scale: &NoteSet) -> Option<usize> {
+        let lowest = self.shape.iter().min_by_key(|note| note.pitch.midi_note)?;
+        if lowest.string != 0 {
+            return None;
+        }
+        let pc = Pc::from(&lowest.pitch.note);
+        scale.iter()
+            .position(|note| Pc::from(note) == pc)
+            .map(|index| index + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::fretboard::fretboard_shape::melodic_shape_search::n_note_per_string_shape;
+    use crate::note::pitch::Pitch;
+
+    fn shape<'a>(frets: [Option<u8>; 6], fretboard: &'a Fretboard) -> FretboardShape<'a> {
+        FretboardShape {
+            fretted_notes: frets.iter()
+                .enumerate()
+                .map(|(string, fret)| match fret {
+                    Some(fret) => FrettedNote::fretted(string as u8, *fret, fretboard).unwrap(),
+                    None => FrettedNote::muted(string as u8, fretboard).unwrap(),
+                })
+                .collect(),
+            fretboard,
+        }
+    }
+
+    #[test]
+    fn caged_forms() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let forms = |frets, root| shape(frets, fretboard).caged_forms(&root);
+        assert_eq!(forms([None, Some(3), Some(2), Some(0), Some(1), Some(0)], Note::C), vec![CagedForm::C]);
+        // A barre F, and C moved up from the A and D shapes.
+        assert_eq!(forms([Some(1), Some(3), Some(3), Some(2), Some(1), Some(1)], Note::F), vec![CagedForm::E]);
+        assert_eq!(forms([None, Some(3), Some(5), Some(5), Some(5), Some(3)], Note::C), vec![CagedForm::A]);
+        assert_eq!(forms([None, None, Some(10), Some(12), Some(13), Some(12)], Note::C), vec![CagedForm::D]);
+        // Minor chords, and sevenths.
+        assert_eq!(forms([None, Some(0), Some(2), Some(2), Some(1), Some(0)], Note::A), vec![CagedForm::A]);
+        assert_eq!(forms([Some(3), Some(5), Some(5), Some(3), Some(3), Some(3)], Note::G), vec![CagedForm::E]);
+        assert_eq!(forms([None, Some(3), Some(2), Some(3), Some(1), Some(0)], Note::C), vec![CagedForm::C]);
+        // Not a chord of that root.
+        assert!(forms([None, Some(3), Some(2), Some(0), Some(1), Some(0)], Note::D).is_empty());
+
+        let drop_d = Fretboard::new(
+            [Note::D, Note::A, Note::D, Note::G, Note::B, Note::E].iter()
+                .zip([3, 3, 4, 4, 4, 5])
+                .map(|(note, octave)| Pitch::new(*note, octave).unwrap())
+                .collect()
+        );
+        assert!(!is_standard_tuning(&drop_d));
+        assert!(shape([Some(0), Some(0), Some(0), Some(2), Some(3), Some(2)], &drop_d).caged_forms(&Note::D).is_empty());
+    }
+
+    #[test]
+    fn scale_positions() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let a_minor = vec![Note::A, Note::B, Note::C, Note::D, Note::E, Note::F, Note::G];
+        let scale = NoteSet::new(a_minor.clone(), Some(&Note::A));
+        for (i, note) in scale.iter().enumerate() {
+            let shape = n_note_per_string_shape((3, 3), &a_minor, note, fretboard).unwrap();
+            assert_eq!(shape.scale_position(&scale), Some(i + 1));
+        }
+        let pentatonic = NoteSet::new(vec![Note::A, Note::C, Note::D, Note::E, Note::G], Some(&Note::A));
+        let shape = n_note_per_string_shape((2, 2), &pentatonic, &Note::C, fretboard).unwrap();
+        assert_eq!(shape.scale_position(&pentatonic), Some(2));
+    }
+}
@@ -1,36 +1,39 @@
 use std::collections::HashMap;
 use itertools::Itertools;
 use crate::error::MusicSemanticsError;
-use crate::note_collections::voicing::Voicing;
+use crate::note_collections::voicing::{Voicing, VoicingType};
 use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
 use crate::fretboard::fretboard_shape::{ChordShapeClassification, FretboardShape};
 use crate::fretboard::fretted_note::FrettedNote;
 use crate::notation::clef::Clef;
 use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
 use crate::note_collections::spelling::HasSpelling;
 
 /// Categorized results of a search for fretboard chord shapes.
 /// Each category is a `HashMap` indexed by voicing, equivocated over the octave.
 #[derive(Debug)]
-pub struct ChordShapeSearchResult<'a> {
+pub struct ChordShapeSearchResult<'a, F: FrettedInstrument = Fretboard> {
     /// Shapes deemed playable using _very_ charitable bounds on the term.
-    pub playable: HashMap<Voicing, Vec<FretboardShape<'a>>>,
+    pub playable: HashMap<Voicing, Vec<FretboardShape<'a, F>>>,
     /// Shapes also deemed playable, but which contain adjacent intervals
     /// wider than an octave.
-    pub wide_intervals: HashMap<Voicing, Vec<FretboardShape<'a>>>,
+    pub wide_intervals: HashMap<Voicing, Vec<FretboardShape<'a, F>>>,
     /// Shapes deemed playable, but which rely on open strings in a way
     /// that makes that deemed unplayable if transposed to a different root note.
-    pub nontransposable: HashMap<Voicing, Vec<FretboardShape<'a>>>,
-    /// Shapes deemed playable, but which reside entirely above the
+    pub nontransposable: HashMap<Voicing, Vec<FretboardShape<'a, F>>>,
+    /// Shapes deemed playable, but which reside entirely at or above the
     /// 12th fret and which therefore should be found elsewhere in the search results 12 frets down.
-    pub all_above_12th_fret: HashMap<Voicing, Vec<FretboardShape<'a>>>,
+    /// See [FretboardShape::transpose_down_octave_if_possible].
+    pub all_above_12th_fret: HashMap<Voicing, Vec<FretboardShape<'a, F>>>,
     /// Shapes deemed unplayable. The vast majority of these are entirely
     /// nonsensical considerations. But since they're already computed / considered,
     /// we keep them in this category for the sake of "better to have and not want".
-    pub unplayable: HashMap<Voicing, Vec<FretboardShape<'a>>>,
+    pub unplayable: HashMap<Voicing, Vec<FretboardShape<'a, F>>>,
 }
 
-impl<'a> ChordShapeSearchResult<'a> {
+impl<'a, F: FrettedInstrument> ChordShapeSearchResult<'a, F> {
     pub fn new() -> Self {
         Self {
             playable: HashMap::new(),
@@ -40,102 +43,315 @@ impl<'a> ChordShapeSearchResult<'a> {
             unplayable: HashMap::new(),
         }
     }
+
+    /// The playable shapes of some [VoicingType], e.g. only the drop-2 grips.
+    pub fn playable_of_type(&self, voicing_type: VoicingType) -> Vec<&FretboardShape<'a, F>> {
+        self.playable.values()
+            .flatten()
+            .filter(|shape| shape.voicing_type() == Some(voicing_type))
+            .collect()
+    }
 }
 
 /// Chord shapes are [FretboardShape]s where there is exactly one [FrettedNote] per string.
 /// If the string is not played in the chord, we denote it with a [FrettedNote::Muted].
-pub fn find_chord_shapes<'a>(
-    chord: &Vec<Note>,
-    fretboard: &'a Fretboard
-) -> Result<ChordShapeSearchResult<'a>, MusicSemanticsError> {
-    let chord_len = chord.len();
-    let num_strings: u8 = fretboard.num_strings();
-    // String groupings are e.g. 0x0000. Note that x0000x is distinct from 0000xx.
-    let string_groupings = (0u8..num_strings).combinations(chord_len);
+///
+/// Any [FrettedInstrument] can be searched. If a note of the chord can't be found
+/// on a given string, then no shapes are produced that would require it there.
+///
+/// This collects every candidate from [chord_shapes] into categories.
+pub fn find_chord_shapes<'a, F: FrettedInstrument>(
+    chord: &[Note],
+    fretboard: &'a F
+) -> Result<ChordShapeSearchResult<'a, F>, MusicSemanticsError> {
+    let strings: Vec<u8> = (0..fretboard.num_strings()).collect();
+    find_chord_shapes_on_strings(chord, fretboard, &strings)
+}
 
+/// Same as [find_chord_shapes], but only placing notes on the given `strings`,
+/// counting from zero at the lowest string, e.g. `&[1, 2, 3, 4]` for the middle four
+/// strings of a guitar. Other strings are muted.
+///
+/// Errors if any of the strings isn't on the fretboard.
+pub fn find_chord_shapes_on_strings<'a, F: FrettedInstrument>(
+    chord: &[Note],
+    fretboard: &'a F,
+    strings: &[u8],
+) -> Result<ChordShapeSearchResult<'a, F>, MusicSemanticsError> {
     let mut valid_shapes = ChordShapeSearchResult::new();
+    for candidate in chord_shapes_on_strings(chord, fretboard, strings)? {
+        let (key, shape) = candidate?;
+        // Classifying it, and indexing it into the search results.
+        match shape.classify() {
+            // The same shape is found an octave lower, so this one is set aside with the others above the 12th fret.
+            ChordShapeClassification::Playable if shape.down_an_octave().is_some() => {
+                valid_shapes.all_above_12th_fret
+                    .entry(key)
+                    .or_default()
+                    .push(shape);
+            },
+            ChordShapeClassification::Playable => {
+                if key.has_wide_intervals() {
+                    valid_shapes.wide_intervals
+                        .entry(key)
+                        .or_insert_with(|| vec![])
+                        .push(shape);
+                } else {
+                    valid_shapes.playable
+                        .entry(key)
+                        .or_insert_with(|| vec![])
+                        .push(shape);
+                }
+            },
+            ChordShapeClassification::AllAbove12thFret => {
+                valid_shapes.all_above_12th_fret
+                    .entry(key)
+                    .or_insert_with(|| vec![])
+                    .push(shape);
+            },
+            ChordShapeClassification::NonTransposable => {
+                valid_shapes.nontransposable
+                    .entry(key)
+                    .or_insert_with(|| vec![])
+                    .push(shape);
+            },
+            ChordShapeClassification::Unplayable => {
+                valid_shapes.unplayable
+                    .entry(key)
+                    .or_insert_with(|| vec![])
+                    .push(shape);
+            },
+        }
+    }
+    Ok(valid_shapes)
+}
 
-    for grouping in string_groupings {
-        // Ordered permutations of notes
-        for permutation in chord.iter().permutations(chord_len) {
+/// Playable chord shapes with their bass note on `bass_string`, grouped by inversion,
+/// as inversion studies are usually organized, e.g. root position on the 5th string of a guitar.
+///
+/// The result is indexed by which note of `chord` is in the bass, so `chord[0]` is taken
+/// as the root: index 0 holds the root position shapes, index 1 the first inversions, and so on.
+/// Only `bass_string` and the strings above it are played, and the note on `bass_string`
+/// must be the lowest sounded. Errors if `bass_string` isn't on the fretboard.
+pub fn find_inversions_on_bass_string<'a, F: FrettedInstrument>(
+    chord: &[Note],
+    fretboard: &'a F,
+    bass_string: u8,
+) -> Result<Vec<Vec<FretboardShape<'a, F>>>, MusicSemanticsError> {
+    let num_strings = fretboard.num_strings();
+    if bass_string >= num_strings {
+        return Err(MusicSemanticsError::StringTooHigh(bass_string, num_strings));
+    }
+    let mut inversions = vec![vec![]; chord.len()];
+    for candidate in string_set_chord_shapes(chord, fretboard, (bass_string..num_strings).collect()) {
+        let (key, shape) = candidate?;
+        if key.has_wide_intervals()
+            || !matches!(shape.classify(), ChordShapeClassification::Playable)
+            || shape.down_an_octave().is_some() {
+            continue;
+        }
+        let FrettedNote::Sounded(bass) = &shape[usize::from(bass_string)] else {
+            continue;
+        };
+        let is_lowest = shape.iter()
+            .filter_map(FrettedNote::pitch)
+            .all(|pitch| pitch.midi_note >= bass.pitch.midi_note);
+        let inversion = chord.iter().position(|note| Pc::from(note) == Pc::from(&bass.pitch.note));
+        if let (true, Some(inversion)) = (is_lowest, inversion) {
+            inversions[inversion].push(shape);
+        }
+    }
+    Ok(inversions)
+}
+
+/// Lazily produces every candidate chord shape considered by [find_chord_shapes],
+/// along with its voicing, equivocated over the octave.
+///
+/// Shapes are generated one at a time, so a caller who only needs a few
+/// (e.g. the first playable shape) can stop early without paying for the whole search.
+/// Candidates are not classified; see [FretboardShape::classify].
+pub fn chord_shapes<'a, 'c, F: FrettedInstrument>(
+    chord: &'c [Note],
+    fretboard: &'a F,
+) -> impl Iterator<Item = Result<(Voicing, FretboardShape<'a, F>), MusicSemanticsError>> + 'c
+    where 'a: 'c
+{
+    string_set_chord_shapes(chord, fretboard, (0..fretboard.num_strings()).collect())
+}
+
+/// Same as [chord_shapes], but only placing notes on the given `strings`.
+/// See [find_chord_shapes_on_strings].
+///
+/// Only groupings of the given strings are searched, so a narrow string set
+/// is much quicker to search than the whole fretboard.
+pub fn chord_shapes_on_strings<'a, 'c, F: FrettedInstrument>(
+    chord: &'c [Note],
+    fretboard: &'a F,
+    strings: &[u8],
+) -> Result<
+    impl Iterator<Item = Result<(Voicing, FretboardShape<'a, F>), MusicSemanticsError>> + 'c,
+    MusicSemanticsError,
+>
+    where 'a: 'c
+{
+    let num_strings = fretboard.num_strings();
+    if let Some(string) = strings.iter().find(|string| **string >= num_strings) {
+        return Err(MusicSemanticsError::StringTooHigh(*string, num_strings));
+    }
+    let mut strings = strings.to_vec();
+    strings.sort();
+    strings.dedup();
+    Ok(string_set_chord_shapes(chord, fretboard, strings))
+}
+
+fn string_set_chord_shapes<'a, 'c, F: FrettedInstrument>(
+    chord: &'c [Note],
+    fretboard: &'a F,
+    strings: Vec<u8>,
+) -> impl Iterator<Item = Result<(Voicing, FretboardShape<'a, F>), MusicSemanticsError>> + 'c
+    where 'a: 'c
+{
+    let chord_len = chord.len();
+    let num_strings: u8 = fretboard.num_strings();
+    // String groupings are e.g. 0x0000. Note that x0000x is distinct from 0000xx.
+    strings.into_iter()
+        .combinations(chord_len)
+        .flat_map(move |grouping| {
+            // Ordered permutations of notes
+            chord.iter()
+                .permutations(chord_len)
+                .map(move |permutation| (grouping.clone(), permutation))
+        })
+        .filter_map(move |(grouping, permutation)| {
             // Determine whether to test the voicing with a particular value moved up an octave.
             // This causes redundancies in the search, but in all practical circumstances
             // the loss is acceptable.
-            let frets: Vec<Vec<u8>> = permutation
+            let frets: Result<Vec<Vec<u8>>, MusicSemanticsError> = permutation
                 .iter()
                 .enumerate()
                 .map(|(i, note)| {
-                    let fret = fretboard.which_fret(note, grouping[i])?;
-                    if fret < 6 {
-                        return Ok::<_, MusicSemanticsError>(vec![fret, fret + 12]);
+                    let fretted_note = fretboard.note_on_string(note, grouping[i])?;
+                    let open_string = fretboard.get_string(grouping[i])?;
+                    if fretted_note.pitch.midi_note - open_string.midi_note < 6 {
+                        if let Ok(octave_up) = fretted_note.up_an_octave() {
+                            return Ok(vec![fretted_note.fret, octave_up.fret]);
+                        }
                     }
-                    Ok::<_, MusicSemanticsError>(vec![fret])
+                    Ok(vec![fretted_note.fret])
                 })
-                .into_iter()
-                .flatten()
                 .collect();
+            frets.ok().map(|frets| (grouping, frets))
+        })
+        .flat_map(move |(grouping, frets)| {
+            let chord = chord.to_vec();
             // Flip through each possible combination of octave choices on each string
-            for fret_shape in frets.iter().multi_cartesian_product() {
-                // Making a [FretboardShape]
-                let strings = (0u8..num_strings)
-                    .map(|i| {
-                        let index = grouping.iter().position(|item| *item == i);
-                        if let Some(index) = index {
-                            return Ok::<_, MusicSemanticsError>(FrettedNote::Sounded(
-                                fretboard.sounded_note(i, *fret_shape[index])?
-                            ));
-                        }
-                        Ok::<_, MusicSemanticsError>(FrettedNote::Muted {
-                            string: i,
-                            fretboard,
+            frets.into_iter()
+                .multi_cartesian_product()
+                .map(move |fret_shape| {
+                    // Making a [FretboardShape]
+                    let strings = (0u8..num_strings)
+                        .map(|i| {
+                            let index = grouping.iter().position(|item| *item == i);
+                            if let Some(index) = index {
+                                return Ok::<_, MusicSemanticsError>(FrettedNote::Sounded(
+                                    fretboard.sounded_note(i, fret_shape[index])?
+                                ));
+                            }
+                            Ok::<_, MusicSemanticsError>(FrettedNote::Muted {
+                                string: i,
+                                fretboard,
+                            })
                         })
-                    })
-                    .into_iter()
-                    .flatten()
-                    .collect();
-                let shape = FretboardShape {
-                    fretted_notes: strings,
-                    fretboard,
-                };
-                // Classifying it, and indexing it into the search results.
-                let key: Voicing = (&shape).into();
-                let key = key.normalize_register_to_clef(Clef::Treble).unwrap();
-                let key = key.spelled_as_in(chord)?;
-                match shape.classify() {
-                    ChordShapeClassification::Playable => {
-                        if key.has_wide_intervals() {
-                            valid_shapes.wide_intervals
-                                .entry(key)
-                                .or_insert_with(|| vec![])
-                                .push(shape);
-                        } else {
-                            valid_shapes.playable
-                                .entry(key)
-                                .or_insert_with(|| vec![])
-                                .push(shape);
-                        }
-                    },
-                    ChordShapeClassification::AllAbove12thFret => {
-                        valid_shapes.all_above_12th_fret
-                            .entry(key)
-                            .or_insert_with(|| vec![])
-                            .push(shape);
-                    },
-                    ChordShapeClassification::NonTransposable => {
-                        valid_shapes.nontransposable
-                            .entry(key)
-                            .or_insert_with(|| vec![])
-                            .push(shape);
-                    },
-                    ChordShapeClassification::Unplayable => {
-                        valid_shapes.unplayable
-                            .entry(key)
-                            .or_insert_with(|| vec![])
-                            .push(shape);
-                    },
-                }
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                    let shape = FretboardShape {
+                        fretted_notes: strings,
+                        fretboard,
+                    };
+                    let key: Voicing = (&shape).into();
+                    let key = key.normalize_register_to_clef(Clef::Treble).unwrap();
+                    let key = key.spelled_as_in(&chord)?;
+                    Ok((key, shape))
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fretboard::STD_6STR_GTR;
+    use super::*;
+
+    #[test]
+    fn lazy_chord_shapes() {
+        let chord = vec![Note::C, Note::E, Note::G];
+        let first_playable: Vec<FretboardShape> = chord_shapes(&chord, &*STD_6STR_GTR)
+            .filter_map(Result::ok)
+            .map(|(_, shape)| shape)
+            .filter(|shape| matches!(shape.classify(), ChordShapeClassification::Playable))
+            .take(3)
+            .collect();
+        assert_eq!(first_playable.len(), 3);
+
+        let all = find_chord_shapes(&chord, &*STD_6STR_GTR).unwrap();
+        let total: usize = [&all.playable, &all.wide_intervals, &all.nontransposable,
+            &all.all_above_12th_fret, &all.unplayable]
+            .iter()
+            .flat_map(|category| category.values())
+            .map(Vec::len)
+            .sum();
+        assert_eq!(chord_shapes(&chord, &*STD_6STR_GTR).count(), total);
+    }
+
+    #[test]
+    fn string_set_chord_shapes() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let chord = vec![Note::C, Note::E, Note::G, Note::B];
+        let middle_four = [4, 2, 3, 1];
+        let shapes: Vec<FretboardShape> = chord_shapes_on_strings(&chord, fretboard, &middle_four)
+            .unwrap()
+            .map(|candidate| candidate.unwrap().1)
+            .collect();
+        assert!(!shapes.is_empty());
+        assert!(shapes.iter().all(|shape| !shape[0].is_sounded() && !shape[5].is_sounded()));
+        // The same shapes as searching the whole fretboard and keeping those on the middle four.
+        let filtered = chord_shapes(&chord, fretboard)
+            .map(|candidate| candidate.unwrap().1)
+            .filter(|shape| !shape[0].is_sounded() && !shape[5].is_sounded())
+            .count();
+        assert_eq!(shapes.len(), filtered);
+        let found = find_chord_shapes_on_strings(&chord, fretboard, &middle_four).unwrap();
+        let drop_2 = found.playable_of_type(VoicingType::Drop2);
+        assert!(drop_2.iter().any(|shape| shape.to_string() == "x-3-5-4-5-x"));
+        assert!(drop_2.iter().all(|shape| shape.voicing_type() == Some(VoicingType::Drop2)));
+
+        assert!(matches!(
+            chord_shapes_on_strings(&chord, fretboard, &[3, 6]).err(),
+            Some(MusicSemanticsError::StringTooHigh(6, 6)),
+        ));
+    }
+
+    #[test]
+    fn inversions_on_bass_string() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let chord = vec![Note::C, Note::E, Note::G];
+        let names = |shapes: &Vec<FretboardShape>| shapes.iter().map(|shape| shape.to_string()).collect::<Vec<_>>();
+        let on_5th_string = find_inversions_on_bass_string(&chord, fretboard, 1).unwrap();
+        assert_eq!(on_5th_string.len(), 3);
+        assert!(names(&on_5th_string[0]).contains(&"x-3-2-0-x-x".to_string()));
+        assert!(names(&on_5th_string[1]).contains(&"x-7-5-5-x-x".to_string()));
+        let on_6th_string = find_inversions_on_bass_string(&chord, fretboard, 0).unwrap();
+        assert!(names(&on_6th_string[1]).contains(&"0-3-x-0-x-x".to_string()));
+        assert!(names(&on_6th_string[2]).contains(&"3-3-2-x-x-x".to_string()));
+        for (inversion, shapes) in on_6th_string.iter().enumerate() {
+            for shape in shapes {
+                assert_eq!(shape[0].pitch().map(|pitch| pitch.note), Some(chord[inversion]));
             }
         }
+
+        assert!(matches!(
+            find_inversions_on_bass_string(&chord, fretboard, 6),
+            Err(MusicSemanticsError::StringTooHigh(6, 6)),
+        ));
     }
-    Ok(valid_shapes)
 }
@@ -2,9 +2,15 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use itertools::Itertools;
 use crate::error::MusicSemanticsError;
-use crate::note_collections::NoteSet;
+use crate::note_collections::{MelodicSequence, NoteSet};
 use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::note_down_an_octave;
+use crate::fretboard::fretboard_shape::ranking::ShapeRanker;
+use crate::fretboard::fretboard_shape::search_options::{SearchBudget, SearchOptions};
 use crate::fretboard::fretted_note::SoundedNote;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::expression::Technique;
 use crate::note::note::Note;
 use crate::note::pitch::Pitch;
 
@@ -62,6 +68,41 @@ impl<'a> MelodicFretboardShape<'a> {
         (lowest, highest)
     }
 
+    /// The shape as a run of notes of equal duration, ready for tab output.
+    /// If `legato`, notes on the same string as the one before are hammered on
+    /// when ascending and pulled off when descending, rather than picked.
+    pub fn to_notated_events(&self, duration: Duration, legato: bool) -> Vec<RhythmicNotatedEvent<'a>> {
+        let mut events: Vec<RhythmicNotatedEvent<'a>> = self.shape.iter()
+            .map(|note| RhythmicNotatedEvent::fretted(note.clone(), duration))
+            .collect();
+        if legato {
+            for (i, (from, to)) in self.shape.iter().tuple_windows().enumerate() {
+                if from.string != to.string || from.fret == to.fret {
+                    continue;
+                }
+                let technique = if to.fret > from.fret {
+                    Technique::HammerOn
+                } else {
+                    Technique::PullOff
+                };
+                events[i].expression.techniques.push(technique);
+            }
+        }
+        events
+    }
+
+    /// The shape's notes reordered in a [MelodicSequence], e.g. to practice the shape
+    /// in diatonic thirds. The sequence is played over the notes in ascending order of pitch.
+    pub fn sequenced(&self, sequence: &MelodicSequence) -> Self {
+        let mut ascending = self.shape.clone();
+        ascending.sort_by_key(|note| note.pitch.midi_note);
+        Self {
+            shape: sequence.apply(&ascending),
+            score: self.score,
+            fretboard: self.fretboard,
+        }
+    }
+
     /// Returns a version of self where, if the outer strings are the same note,
     /// then we make sure their fret content matches.
     pub fn mirrored_outer_strings(&self) -> Self {
@@ -90,6 +131,29 @@ impl<'a> MelodicFretboardShape<'a> {
         }
     }
 
+    /// The same shape moved down the neck by as many octaves as it will go, keeping its spelling.
+    /// Shapes with a note in the lowest octave of its string are unchanged.
+    /// See [crate::fretboard::FretboardShape::transpose_down_octave_if_possible].
+    pub fn transpose_down_octave_if_possible(&self) -> Self {
+        let mut shape = self.shape.clone();
+        while !shape.is_empty() {
+            match shape.iter().map(note_down_an_octave).collect::<Option<Vec<_>>>() {
+                Some(lower) => shape = lower,
+                None => break,
+            }
+        }
+        Self { shape, ..self.clone() }
+    }
+
+    /// Whether the shapes play the same strings at frets an octave apart, in the same order.
+    pub fn is_octave_equivalent(&self, other: &MelodicFretboardShape) -> bool {
+        let frets = |shape: &MelodicFretboardShape| shape.transpose_down_octave_if_possible().shape
+            .iter()
+            .map(|note| (note.string, note.fret))
+            .collect::<Vec<_>>();
+        self.fretboard == other.fretboard && frets(self) == frets(other)
+    }
+
     /// Whether other is entirely contained in self.
     pub fn subsumes_other(&self, other: &MelodicFretboardShape) -> bool {
         self.fretboard == other.fretboard &&
@@ -135,6 +199,16 @@ impl<'a> ScaleShapeSearchResult<'a> {
     pub fn from_raw_search_result(
         chord: &Vec<Note>,
         fretboard: &'a Fretboard,
+    ) -> Result<Self, MusicSemanticsError> {
+        Self::from_raw_search_result_ranked(chord, fretboard, &ShapeRanker::default())
+    }
+
+    /// Same as [ScaleShapeSearchResult::from_raw_search_result], but the two best
+    /// shapes from each starting note are chosen by `ranker`, rather than by score.
+    pub fn from_raw_search_result_ranked(
+        chord: &Vec<Note>,
+        fretboard: &'a Fretboard,
+        ranker: &ShapeRanker<MelodicFretboardShape<'a>>,
     ) -> Result<Self, MusicSemanticsError> {
         let mut new_self_instance = Self::new(fretboard);
         // Calculate open shape
@@ -164,7 +238,7 @@ impl<'a> ScaleShapeSearchResult<'a> {
                         new_self_instance.n_per_string_3_3.insert(note, shape);
                     }
                 }
-                let (best_two, the_rest) = set_aside_best_two_shapes(shapes.clone());
+                let (best_two, the_rest) = ranker.best_n(shapes.clone(), 2);
                 for shape in best_two {
                     let shape = shape.mirrored_outer_strings();
                     let span = {
@@ -201,51 +275,18 @@ impl<'a> ScaleShapeSearchResult<'a> {
     }
 }
 
+/// Splits off the two lowest scoring shapes, best first. See [ShapeRanker::best_n].
 pub fn set_aside_best_two_shapes(
     shapes: Vec<MelodicFretboardShape>
 ) -> (Vec<MelodicFretboardShape>, Vec<MelodicFretboardShape>) {
-    let mut best_two = vec![];
-    let mut the_rest = vec![];
-    for shape in shapes.into_iter() {
-        if best_two.len() == 0 {
-            best_two.push(shape);
-        } else if best_two.len() == 1 {
-            let first = best_two.first().unwrap();
-            if first.score <= shape.score {
-                best_two.push(shape);
-            } else {
-                // strictly better score, new shape goes first
-                best_two = vec![shape, first.clone()];
-            }
-        } else {
-            // In this conditional branch, we need to compare the shape to both elements.
-            let first = best_two.first().unwrap();
-            if shape.score < first.score {
-                let second = best_two.last().unwrap();
-                the_rest.push(second.clone());
-                best_two = vec![shape, first.clone()];
-            } else {
-                let second = best_two.last().unwrap();
-                if shape.score < second.score {
-                    let last = best_two.pop().unwrap();
-                    the_rest.push(last);
-                    best_two.push(shape);
-                } else {
-                    the_rest.push(shape);
-                }
-            }
-        }
-    }
-    (best_two, the_rest)
+    ShapeRanker::default().best_n(shapes, 2)
 }
 
 pub fn find_open_scale_shape<'a>(
     chord: &Vec<Note>,
     fretboard: &'a Fretboard,
 ) -> Result<MelodicFretboardShape<'a>, MusicSemanticsError> {
-    if chord.is_empty() {
-        return Err(MusicSemanticsError::EmptySetOfNotes);
-    }
+    check_enough_notes(chord)?;
     let mut first_note = fretboard.sounded_note(0, 0)?;
     while !chord.contains(&first_note.pitch.note) {
         first_note = first_note.up_n_frets(1)?;
@@ -280,9 +321,7 @@ pub fn n_note_per_string_shape<'a>(
     starting_note: &Note,
     fretboard: &'a Fretboard,
 ) -> Result<MelodicFretboardShape<'a>, MusicSemanticsError> {
-    if chord.is_empty() {
-        return Err(MusicSemanticsError::EmptySetOfNotes);
-    }
+    check_enough_notes(chord)?;
     let (value_1, value_2) = n;
     if value_1 < 2 || value_2 < 2 {
         return Err(MusicSemanticsError::InvalidNNotesPerString(n));
@@ -293,7 +332,7 @@ pub fn n_note_per_string_shape<'a>(
     let mut using_value_1 = true;
     let mut shape = vec![];
     shape.push(first_fretted_note);
-    for _ in 0..fretboard.num_strings() {
+    for string in 0..fretboard.num_strings() {
         let mut num_notes_on_curr_str = 1;
         while (using_value_1 && num_notes_on_curr_str < value_1) || num_notes_on_curr_str < value_2 {
             let last_note = shape.last().unwrap();
@@ -301,12 +340,28 @@ pub fn n_note_per_string_shape<'a>(
             num_notes_on_curr_str += 1;
         }
         using_value_1 = !using_value_1;
-        let last_note = shape.last().unwrap();
-        shape.push(last_note.next_note_next_string(&chord)?)
+        // There's no next string after the highest.
+        if string + 1 < fretboard.num_strings() {
+            let last_note = shape.last().unwrap();
+            shape.push(last_note.next_note_next_string(&chord)?)
+        }
     }
     Ok(MelodicFretboardShape { shape, score: 0, fretboard, })
 }
 
+/// A melodic shape moves from note to note, so it needs at least two distinct notes
+/// to move between. With only one, the searches would never leave the starting pitch.
+fn check_enough_notes(chord: &[Note]) -> Result<(), MusicSemanticsError> {
+    if chord.is_empty() {
+        return Err(MusicSemanticsError::EmptySetOfNotes);
+    }
+    let distinct = NoteSet::new(chord.to_vec(), None).len();
+    if distinct < 2 {
+        return Err(MusicSemanticsError::TooFewNotes(2, distinct));
+    }
+    Ok(())
+}
+
 /// Finds scale shapes starting from each note.
 pub fn find_all_scale_shapes<'a>(
     chord: &Vec<Note>,
@@ -323,6 +378,28 @@ pub fn find_all_scale_shapes<'a>(
 
 }
 
+/// Same as [find_all_scale_shapes], but bounded by [SearchOptions].
+/// The budgets are shared across the searches from every starting note,
+/// so e.g. a time budget bounds the whole call.
+pub fn find_all_scale_shapes_with_options<'a>(
+    chord: &Vec<Note>,
+    fretboard: &'a Fretboard,
+    options: &SearchOptions,
+) -> Result<HashMap<Note, Vec<MelodicFretboardShape<'a>>>, MusicSemanticsError> {
+    let mut budget = SearchBudget::new(options);
+    let mut all_shapes = HashMap::new();
+    for note in chord {
+        match melodic_shapes_with_budget(chord, note, fretboard, &mut budget) {
+            Ok(shapes) => {
+                all_shapes.insert(*note, shapes);
+            },
+            Err(MusicSemanticsError::SearchCancelled) => return Err(MusicSemanticsError::SearchCancelled),
+            Err(_) => {},
+        }
+    }
+    Ok(all_shapes)
+}
+
 /// Meant to be cloned across different branches of the recursive search tree.
 ///
 /// We step through a recursive process according to many conditionals
@@ -351,36 +428,33 @@ struct RecursiveSearchParams<'a> {
     fretboard: &'a Fretboard,
 }
 
-/// Move a collection of [crate::fretboard::SoundedNote] down to their
-/// minimum possible octave.
-fn normalize_octave_register(
-    mut frets: Vec<SoundedNote>,
-) -> Vec<SoundedNote> {
-    while frets.iter().all(|note| note.fret >= 12) {
-        frets = frets.iter().map(|f| f.down_an_octave().unwrap()).collect();
-    }
-    frets
-}
-
 /// We never recurse many levels deep, because the anatomical restrictions of
 /// the hand force recursion to terminate early and often. There are many
 /// branches, but they are all shallow.
+///
+/// The [SearchBudget] is checked on every call, so that the search can be cut short.
 fn recursive_melodic_search<'a>(
     chord: &NoteSet,
     mut params: RecursiveSearchParams<'a>,
     shapes: &mut Vec<MelodicFretboardShape<'a>>,
     fretboard: &'a Fretboard,
+    budget: &mut SearchBudget,
 ) -> Result<(), MusicSemanticsError> {
+    if budget.is_exhausted()? {
+        return Ok(());
+    }
     let new_violations = tally_new_violations(&params.frets);
     params.score += new_violations.0 + new_violations.1;
-    // If we've completed 2 octaves, we're done.
-    if params.frets.len() > 2 * chord.len() {
-        let frets = normalize_octave_register(params.frets);
+    // If we've completed 2 octaves, or can't go any deeper, we're done.
+    if params.frets.len() > 2 * chord.len() || !budget.can_deepen(params.frets.len()) {
         let shape = MelodicFretboardShape {
-            shape: frets,
+            shape: params.frets,
             score: params.score,
             fretboard,
-        };
+        }.transpose_down_octave_if_possible();
+        if shape.is_complete() {
+            budget.record_result();
+        }
         shapes.push(shape);
         return Ok(());
     }
@@ -407,7 +481,7 @@ fn recursive_melodic_search<'a>(
         new_params.span_on_curr_string = span;
         new_params.notes_on_curr_string += 1;
         new_params.frets.push(next_note_same_string.clone());
-        recursive_melodic_search(chord, new_params, shapes, fretboard)?;
+        recursive_melodic_search(chord, new_params, shapes, fretboard, budget)?;
     }
     if params.fretboard.num_strings() > last_fret.string + 1 {
         let next_string = &params.fretboard.open_strings[last_fret.string as usize + 1];
@@ -434,7 +508,7 @@ fn recursive_melodic_search<'a>(
             new_params.span_on_curr_string = 0;
             new_params.notes_on_curr_string = 1;
             new_params.frets.push(next_note_next_str);
-            recursive_melodic_search(chord, new_params, shapes, fretboard)?;
+            recursive_melodic_search(chord, new_params, shapes, fretboard, budget)?;
         }
     }
     if distance_to_next_note >= 7 && params.fretboard.num_strings() > last_fret.string + 2 {
@@ -470,16 +544,18 @@ fn recursive_melodic_search<'a>(
             new_params.span_on_curr_string = 0;
             new_params.notes_on_curr_string = 1;
             new_params.frets.push(next_note);
-            recursive_melodic_search(chord, new_params, shapes, fretboard)?;
+            recursive_melodic_search(chord, new_params, shapes, fretboard, budget)?;
         }
     }
     if was_dead_end {
-        let frets = normalize_octave_register(params.frets);
         let shape = MelodicFretboardShape {
-            shape: frets,
+            shape: params.frets,
             score: params.score,
             fretboard,
-        };
+        }.transpose_down_octave_if_possible();
+        if shape.is_complete() {
+            budget.record_result();
+        }
         shapes.push(shape);
     }
     Ok(())
@@ -491,6 +567,31 @@ pub fn melodic_shapes_at_starting_note<'a>(
     starting_note: &Note,
     fretboard: &'a Fretboard,
 ) -> Result<Vec<MelodicFretboardShape<'a>>, MusicSemanticsError> {
+    melodic_shapes_at_starting_note_with_options(
+        chord,
+        starting_note,
+        fretboard,
+        &SearchOptions::default(),
+    )
+}
+
+/// Same as [melodic_shapes_at_starting_note], but bounded by [SearchOptions].
+pub fn melodic_shapes_at_starting_note_with_options<'a>(
+    chord: &Vec<Note>,
+    starting_note: &Note,
+    fretboard: &'a Fretboard,
+    options: &SearchOptions,
+) -> Result<Vec<MelodicFretboardShape<'a>>, MusicSemanticsError> {
+    melodic_shapes_with_budget(chord, starting_note, fretboard, &mut SearchBudget::new(options))
+}
+
+fn melodic_shapes_with_budget<'a>(
+    chord: &Vec<Note>,
+    starting_note: &Note,
+    fretboard: &'a Fretboard,
+    budget: &mut SearchBudget,
+) -> Result<Vec<MelodicFretboardShape<'a>>, MusicSemanticsError> {
+    check_enough_notes(chord)?;
     // TODO We're normalizing the spelling because this is done in the Python, is this necessary?
     let starting_note = starting_note.spelled_as_in(chord)?;
     let chord = NoteSet::new(chord.clone(), Some(&starting_note));
@@ -500,11 +601,11 @@ pub fn melodic_shapes_at_starting_note<'a>(
     // we would not run into the edge of the fretboard, thus killing off a search into shapes
     // that could have been explored and which are *perhaps* playable up twelve frets.
     if first_fretted_note.fret < 7 {
-        first_fretted_note = first_fretted_note.up_n_frets(12).unwrap();
+        first_fretted_note = first_fretted_note.up_n_frets(12)?;
     }
     let mut notes_on_curr_string = 1;
     let new_fret_same_str = first_fretted_note
-        .next_note_same_string(&chord).unwrap();
+        .next_note_same_string(&chord)?;
     let span = (new_fret_same_str.fret - first_fretted_note.fret) as usize;
     let frets = vec![first_fretted_note.clone(), new_fret_same_str.clone()];
     let mut shapes = vec![];
@@ -517,7 +618,7 @@ pub fn melodic_shapes_at_starting_note<'a>(
             score: 0,
             fretboard,
         };
-        recursive_melodic_search(&chord, params, &mut shapes, fretboard)?;
+        recursive_melodic_search(&chord, params, &mut shapes, fretboard, budget)?;
     }
     if fretboard.num_strings() > 1 {
         let this_string = fretboard.open_strings[first_fretted_note.string as usize];
@@ -534,7 +635,7 @@ pub fn melodic_shapes_at_starting_note<'a>(
                 score: 0,
                 fretboard,
             };
-            recursive_melodic_search(&chord, params, &mut shapes, fretboard)?;
+            recursive_melodic_search(&chord, params, &mut shapes, fretboard, budget)?;
         }
         if span >= 7 && fretboard.num_strings() > first_fretted_note.string + 2 {
             let next_string = &fretboard.open_strings[first_fretted_note.string as usize + 2];
@@ -572,7 +673,7 @@ pub fn melodic_shapes_at_starting_note<'a>(
                     score: 0,
                     fretboard,
                 };
-                recursive_melodic_search(&chord, params, &mut shapes, fretboard)?;
+                recursive_melodic_search(&chord, params, &mut shapes, fretboard, budget)?;
             }
         }
     }
@@ -605,7 +706,6 @@ pub fn melodic_shapes_at_starting_note<'a>(
             });
             shapes.extend(more_shapes);
         });
-
     Ok(shapes)
 }
 
@@ -662,6 +762,7 @@ fn tally_new_violations(frets: &Vec<SoundedNote>) -> (usize, usize) {
 #[cfg(test)]
 mod tests {
     use crate::fretboard::STD_6STR_GTR;
+    use crate::fretboard::fretboard_shape::search_options::CancellationToken;
     use super::*;
 
     #[test]
@@ -717,6 +818,32 @@ mod tests {
         // }
     }
 
+    #[test]
+    fn scale_search_budgets() {
+        let chord = vec![Note::C, Note::D, Note::E, Note::F, Note::G, Note::A, Note::B];
+        let search = |options: SearchOptions| melodic_shapes_at_starting_note_with_options(
+            &chord,
+            &Note::C,
+            &STD_6STR_GTR,
+            &options,
+        );
+        let unbounded = search(SearchOptions::default()).unwrap();
+        assert!(unbounded.iter().filter(|shape| shape.is_complete()).count() > 1);
+
+        let bounded = search(SearchOptions::default().max_results(1)).unwrap();
+        assert_eq!(bounded.iter().filter(|shape| shape.is_complete()).count(), 1);
+
+        let shallow = search(SearchOptions::default().max_depth(5)).unwrap();
+        assert!(shallow.iter().all(|shape| shape.shape.len() <= 5 && !shape.is_complete()));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(matches!(
+            search(SearchOptions::default().cancellation(token)),
+            Err(MusicSemanticsError::SearchCancelled),
+        ));
+    }
+
     #[test]
     fn best_two_melodic_shapes() {
         let shapes = vec![
@@ -777,6 +904,26 @@ mod tests {
         let should_be = "1:0(E) 1:1(F) 1:3(G) 2:0(A) 2:2(B) 2:3(C) \
         3:0(D) 3:2(E) 3:3(F) 4:0(G) 4:2(A) 5:0(B) 5:1(C) 5:3(D) 6:0(E) 6:1(F) 6:3(G) 6:5(A)";
         assert_eq!(format!("{}", shape), should_be);
+
+        // A single note, however many times it's given, has nowhere to move to.
+        assert!(matches!(
+            find_open_scale_shape(&vec![Note::E, Note::E], &STD_6STR_GTR),
+            Err(MusicSemanticsError::TooFewNotes(2, 1)),
+        ));
+        assert!(find_all_scale_shapes(&vec![Note::E], &STD_6STR_GTR).is_empty());
+        assert!(matches!(find_open_scale_shape(&vec![], &STD_6STR_GTR), Err(MusicSemanticsError::EmptySetOfNotes)));
+    }
+
+    #[test]
+    fn sequenced_shape() {
+        let chord = vec![Note::C, Note::D, Note::E, Note::F, Note::G, Note::A, Note::B];
+        let shape = find_open_scale_shape(&chord, &STD_6STR_GTR).unwrap();
+        let thirds = shape.sequenced(&MelodicSequence::Thirds);
+        assert_eq!(thirds.shape.len(), 2 * (shape.shape.len() - 2));
+        assert_eq!(
+            thirds.shape[..6].iter().map(|note| note.to_string()).join(" "),
+            "1:0(E) 1:3(G) 1:1(F) 2:0(A) 1:3(G) 2:2(B)",
+        );
     }
 
     #[test]
@@ -1,32 +1,38 @@
+pub mod caged;
 pub mod chord_shape_search;
 pub mod melodic_shape_search;
+pub mod open_chords;
+pub mod ranking;
+pub mod search_options;
+pub mod stitching;
 
 use std::fmt::{Display, Formatter};
 use std::iter::zip;
 use std::ops::Deref;
 use crate::error::MusicSemanticsError;
 use crate::note::pitch::Pitch;
-use crate::note_collections::voicing::{StackedIntervals, Voicing};
+use crate::note_collections::voicing::{StackedIntervals, Voicing, VoicingType};
 use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
 use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
 use crate::note::note::Note;
 
 /// Meant for vertically oriented fretboard shapes.
 #[derive(Debug, Clone)]
-pub struct FretboardShape<'a> {
-    pub fretted_notes: Vec<FrettedNote<'a>>,
-    pub fretboard: &'a Fretboard,
+pub struct FretboardShape<'a, F: FrettedInstrument = Fretboard> {
+    pub fretted_notes: Vec<FrettedNote<'a, F>>,
+    pub fretboard: &'a F,
 }
 
-impl<'a> Deref for FretboardShape<'a> {
-    type Target = Vec<FrettedNote<'a>>;
+impl<'a, F: FrettedInstrument> Deref for FretboardShape<'a, F> {
+    type Target = Vec<FrettedNote<'a, F>>;
 
     fn deref(&self) -> &Self::Target {
         &self.fretted_notes
     }
 }
 
-impl<'a> Display for FretboardShape<'a> {
+impl<'a, F: FrettedInstrument> Display for FretboardShape<'a, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s: Vec<String> = self.fretted_notes
             .iter()
@@ -43,7 +49,7 @@ impl<'a> Display for FretboardShape<'a> {
 }
 
 // TODO implement an easy constructor for Chord Shapes.
-impl<'a> FretboardShape<'a> {
+impl<'a, F: FrettedInstrument> FretboardShape<'a, F> {
 
     /// Creates a new [FretboardShape] where all the open strings are converted to muted strings.
     /// This is useful for analyzing playability, if you consider open strings negligibly costly to play.
@@ -54,17 +60,17 @@ impl<'a> FretboardShape<'a> {
                 .iter()
                 .map(|value| match &value {
                     FrettedNote::Muted { string, fretboard } => FrettedNote::Muted {
-                        string: *string, fretboard,
+                        string: *string, fretboard: *fretboard,
                     },
                     FrettedNote::Sounded(
                         SoundedNote { fret: 0, string, fretboard, .. }
                     ) => FrettedNote::Muted {
-                        string: *string, fretboard,
+                        string: *string, fretboard: *fretboard,
                     },
                     FrettedNote::Sounded(
                         SoundedNote { fret, pitch, string, fretboard }
                     ) => FrettedNote::Sounded(SoundedNote {
-                        fret: *fret, pitch: pitch.clone(), string: *string, fretboard,
+                        fret: *fret, pitch: pitch.clone(), string: *string, fretboard: *fretboard,
                     }),
                 })
                 .collect()
@@ -83,14 +89,38 @@ impl<'a> FretboardShape<'a> {
         })
     }
 
+    /// The fret at which the charitable fret-count bounds of [FretboardShape::is_playable]
+    /// are converted into physical stretch bounds.
+    const STRETCH_REFERENCE_FRET: u8 = 5;
+
+    /// Whether the shape can reasonably be fingered by one hand.
+    ///
+    /// By default, we bound the span of frets. If the fretboard has a scale length,
+    /// we instead bound the physical stretch, allowing as much stretch as the
+    /// fret-count bound would allow at [FretboardShape::STRETCH_REFERENCE_FRET].
     pub fn is_playable(&self) -> bool {
         let (min, max) = self.span();
         let span = max - min;
         let size = self.size();
-        if (size <= 3 && span > 4) || (size > 3 && span > 3) {
-            return false;
+        let max_span = if size <= 3 { 4 } else { 3 };
+        let reference = Self::STRETCH_REFERENCE_FRET;
+        if let (Some(stretch), Some(max_stretch)) = (
+            self.stretch_mm(),
+            self.fretboard.stretch_mm(reference, reference + max_span),
+        ) {
+            return stretch <= max_stretch;
+        }
+        span <= max_span
+    }
+
+    /// The physical stretch of the shape in millimeters, if the fretboard
+    /// has a scale length. See [FrettedInstrument::stretch_mm].
+    pub fn stretch_mm(&self) -> Option<f64> {
+        if self.size() == 0 {
+            return None;
         }
-        true
+        let (min, max) = self.span();
+        self.fretboard.stretch_mm(min, max)
     }
 
     /// Number of strings not muted.
@@ -155,6 +185,55 @@ impl<'a> FretboardShape<'a> {
         false
     }
 
+    /// The same shape moved down the neck by as many octaves as it will go, keeping its spelling,
+    /// e.g. 12-14-14-13-12-12 becomes 0-2-2-1-0-0. Shapes with a note in the lowest octave
+    /// of its string are unchanged.
+    pub fn transpose_down_octave_if_possible(&self) -> Self {
+        let mut shape = self.clone();
+        while let Some(lower) = shape.down_an_octave() {
+            shape = lower;
+        }
+        shape
+    }
+
+    fn down_an_octave(&self) -> Option<Self> {
+        if self.size() == 0 {
+            return None;
+        }
+        let fretted_notes = self.fretted_notes.iter()
+            .map(|fretted_note| match fretted_note {
+                FrettedNote::Sounded(note) => note_down_an_octave(note).map(FrettedNote::Sounded),
+                FrettedNote::Muted { .. } => Some(fretted_note.clone()),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { fretted_notes, fretboard: self.fretboard })
+    }
+
+    /// Whether the shapes are the same, up to moving one of them by octaves,
+    /// i.e. they sound the same strings at frets an octave apart.
+    pub fn is_octave_equivalent(&self, other: &Self) -> bool {
+        let frets = |shape: &Self| shape.transpose_down_octave_if_possible().fretted_notes
+            .iter()
+            .map(FrettedNote::fret)
+            .collect::<Vec<_>>();
+        self.fretboard == other.fretboard && frets(self) == frets(other)
+    }
+
+    /// The pitches of the shape's sounded notes, e.g. to check a guitar voicing's
+    /// playability at the keyboard. See [FrettedInstrument::realize_voicing] for the reverse.
+    pub fn to_voicing(&self) -> Voicing {
+        Voicing::new(
+            self.fretted_notes.iter()
+                .filter_map(FrettedNote::pitch)
+                .collect()
+        )
+    }
+
+    /// The [VoicingType] of the shape's sounded notes, e.g. whether it's a drop-2 grip.
+    pub fn voicing_type(&self) -> Option<VoicingType> {
+        self.to_voicing().voicing_type()
+    }
+
     pub fn classify(&self) -> ChordShapeClassification {
         if self.is_playable() {
             if self.fretted_notes.iter().all(|value| {
@@ -178,8 +257,37 @@ impl<'a> FretboardShape<'a> {
     }
 }
 
-impl<'a> From<&'a FretboardShape<'a>> for StackedIntervals {
-    fn from(value: &'a FretboardShape<'a>) -> Self {
+/// The note an octave lower on the same string, keeping its spelling, if there is one.
+pub(crate) fn note_down_an_octave<'a, F: FrettedInstrument>(note: &SoundedNote<'a, F>) -> Option<SoundedNote<'a, F>> {
+    let mut lower = note.down_an_octave().ok()?;
+    lower.pitch.note = note.pitch.note;
+    lower.pitch.octave = note.pitch.octave.checked_sub(1)?;
+    Some(lower)
+}
+
+/// The same note an octave higher on its string, keeping its spelling, if the string is long enough.
+pub(crate) fn note_up_an_octave<'a, F: FrettedInstrument>(note: &SoundedNote<'a, F>) -> Option<SoundedNote<'a, F>> {
+    let mut higher = note.up_n_frets(12).ok()?;
+    higher.pitch.note = note.pitch.note;
+    higher.pitch.octave = note.pitch.octave.checked_add(1)?;
+    Some(higher)
+}
+
+/// Removes shapes that are octave equivalent to an earlier one, see [FretboardShape::is_octave_equivalent],
+/// moving those that are left as far down the neck as they go.
+pub fn dedup_octave_equivalent<'a, F: FrettedInstrument>(shapes: Vec<FretboardShape<'a, F>>) -> Vec<FretboardShape<'a, F>> {
+    let mut deduped: Vec<FretboardShape<'a, F>> = vec![];
+    for shape in shapes {
+        let shape = shape.transpose_down_octave_if_possible();
+        if !deduped.iter().any(|other| other.is_octave_equivalent(&shape)) {
+            deduped.push(shape);
+        }
+    }
+    deduped
+}
+
+impl<'a, F: FrettedInstrument> From<&'a FretboardShape<'a, F>> for StackedIntervals {
+    fn from(value: &'a FretboardShape<'a, F>) -> Self {
         let mut pitches: Vec<Pitch> = value
             .fretted_notes
             .iter()
@@ -201,14 +309,9 @@ impl<'a> From<&'a FretboardShape<'a>> for StackedIntervals {
     }
 }
 
-impl<'a> From<&'a FretboardShape<'a>> for Voicing {
-    fn from(value: &'a FretboardShape<'a>) -> Self {
-        Voicing::new(
-            value.fretted_notes.iter()
-                .filter(|item| item.is_sounded())
-                .map(|item| item.pitch().unwrap().clone())
-                .collect()
-        )
+impl<'a, F: FrettedInstrument> From<&'a FretboardShape<'a, F>> for Voicing {
+    fn from(value: &'a FretboardShape<'a, F>) -> Self {
+        value.to_voicing()
     }
 }
 
@@ -222,4 +325,80 @@ pub enum ChordShapeClassification {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+
+    fn shape<'a>(frets: &[(u8, u8)], fretboard: &'a Fretboard) -> FretboardShape<'a> {
+        FretboardShape {
+            fretted_notes: (0..fretboard.num_strings())
+                .map(|string| match frets.iter().find(|(s, _)| *s == string) {
+                    Some((_, fret)) => FrettedNote::fretted(string, *fret, fretboard).unwrap(),
+                    None => FrettedNote::muted(string, fretboard).unwrap(),
+                })
+                .collect(),
+            fretboard,
+        }
+    }
+
+    #[test]
+    fn physical_stretch() {
+        let guitar = STD_6STR_GTR.clone().with_scale_length(648.0);
+        // The same number of frets is a shorter stretch higher up the neck.
+        let low = guitar.stretch_mm(1, 4).unwrap();
+        let high = guitar.stretch_mm(13, 16).unwrap();
+        assert!(high < low);
+        assert!((guitar.fret_position_mm(12).unwrap() - 324.0).abs() < 1e-9);
+        assert_eq!(STD_6STR_GTR.stretch_mm(1, 4), None);
+
+        // Four frets in first position is too far, but fine at the 13th fret.
+        assert!(shape(&[(0, 1), (1, 3), (2, 5)], &STD_6STR_GTR).is_playable());
+        assert!(!shape(&[(0, 1), (1, 3), (2, 5)], &guitar).is_playable());
+        assert!(shape(&[(0, 13), (1, 15), (2, 17)], &guitar).is_playable());
+        // Five frets is only reachable way up the neck.
+        assert!(!shape(&[(0, 12), (1, 14), (2, 17)], &STD_6STR_GTR).is_playable());
+        assert!(shape(&[(0, 12), (1, 14), (2, 17)], &guitar).is_playable());
+    }
+
+    #[test]
+    fn voicing_round_trip() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        // An open C chord, x-3-2-0-1-0.
+        let open_c = shape(&[(1, 3), (2, 2), (3, 0), (4, 1), (5, 0)], fretboard);
+        let voicing = open_c.to_voicing();
+        assert_eq!(voicing, Voicing::new(vec![
+            Pitch::new(Note::C, 4).unwrap(), Pitch::new(Note::E, 4).unwrap(), Pitch::new(Note::G, 4).unwrap(),
+            Pitch::new(Note::C, 5).unwrap(), Pitch::new(Note::E, 5).unwrap(),
+        ]));
+        let shapes = fretboard.realize_voicing(&voicing);
+        assert!(shapes.iter().any(|shape| shape.to_string() == "x-3-2-0-1-0"));
+        assert!(shapes.iter().all(|shape| shape.to_voicing() == voicing));
+
+        // Spelling is kept, and a voicing too wide for one hand has no playable shapes.
+        let voicing = Voicing::new(vec![Pitch::new(Note::Fis, 3).unwrap(), Pitch::new(Note::Des, 6).unwrap()]);
+        let shapes = fretboard.realize_voicing(&voicing);
+        assert!(!shapes.is_empty());
+        assert!(shapes.iter().all(|shape| shape.to_voicing() == voicing));
+        assert!(shapes.iter().all(|shape| !shape.is_playable()));
+        // Too low for the instrument.
+        assert!(fretboard.realize_voicing(&Voicing::new(vec![Pitch::new(Note::C, 2).unwrap()])).is_empty());
+    }
+
+    #[test]
+    fn octave_equivalence() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let open_e = shape(&[(0, 0), (1, 2), (2, 2), (3, 1), (4, 0), (5, 0)], fretboard);
+        let octave_up = shape(&[(0, 12), (1, 14), (2, 14), (3, 13), (4, 12), (5, 12)], fretboard);
+        let two_octaves_up = shape(&[(1, 26), (2, 26), (3, 25)], fretboard);
+        assert!(open_e.is_octave_equivalent(&octave_up));
+        assert!(!open_e.is_octave_equivalent(&two_octaves_up));
+        assert_eq!(octave_up.transpose_down_octave_if_possible().to_string(), "0-2-2-1-0-0");
+        assert_eq!(two_octaves_up.transpose_down_octave_if_possible().to_string(), "x-2-2-1-x-x");
+        assert_eq!(open_e.transpose_down_octave_if_possible().to_string(), open_e.to_string());
+        assert_eq!(
+            dedup_octave_equivalent(vec![octave_up, two_octaves_up, open_e]).iter()
+                .map(|shape| shape.to_string())
+                .collect::<Vec<_>>(),
+            vec!["0-2-2-1-0-0", "x-2-2-1-x-x"],
+        );
+    }
 }
@@ -0,0 +1,113 @@
+use std::collections::HashSet;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::caged::is_standard_tuning;
+use crate::fretboard::fretted_note::FrettedNote;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::chord_name::{ChordName, TonalSpecification};
+
+/// The open chords of standard tuning that guitarists learn first, each by its root,
+/// and the fret of each string from the lowest up, or `None` if the string is muted.
+pub const OPEN_CHORDS: [(Note, [Option<u8>; 6]); 27] = [
+    // Major
+    (Note::C, [None, Some(3), Some(2), Some(0), Some(1), Some(0)]),
+    (Note::A, [None, Some(0), Some(2), Some(2), Some(2), Some(0)]),
+    (Note::G, [Some(3), Some(2), Some(0), Some(0), Some(0), Some(3)]),
+    (Note::E, [Some(0), Some(2), Some(2), Some(1), Some(0), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(3), Some(2)]),
+    // Minor
+    (Note::A, [None, Some(0), Some(2), Some(2), Some(1), Some(0)]),
+    (Note::E, [Some(0), Some(2), Some(2), Some(0), Some(0), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(3), Some(1)]),
+    // Dominant seventh
+    (Note::C, [None, Some(3), Some(2), Some(3), Some(1), Some(0)]),
+    (Note::A, [None, Some(0), Some(2), Some(0), Some(2), Some(0)]),
+    (Note::G, [Some(3), Some(2), Some(0), Some(0), Some(0), Some(1)]),
+    (Note::E, [Some(0), Some(2), Some(0), Some(1), Some(0), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(1), Some(2)]),
+    (Note::B, [None, Some(2), Some(1), Some(2), Some(0), Some(2)]),
+    // Major seventh
+    (Note::C, [None, Some(3), Some(2), Some(0), Some(0), Some(0)]),
+    (Note::A, [None, Some(0), Some(2), Some(1), Some(2), Some(0)]),
+    (Note::E, [Some(0), Some(2), Some(1), Some(1), Some(0), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(2), Some(2)]),
+    (Note::F, [None, None, Some(3), Some(2), Some(1), Some(0)]),
+    // Minor seventh
+    (Note::A, [None, Some(0), Some(2), Some(0), Some(1), Some(0)]),
+    (Note::E, [Some(0), Some(2), Some(0), Some(0), Some(0), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(1), Some(1)]),
+    // Suspended
+    (Note::A, [None, Some(0), Some(2), Some(2), Some(0), Some(0)]),
+    (Note::A, [None, Some(0), Some(2), Some(2), Some(3), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(3), Some(0)]),
+    (Note::D, [None, None, Some(0), Some(2), Some(3), Some(3)]),
+    (Note::E, [Some(0), Some(2), Some(2), Some(2), Some(0), Some(0)]),
+];
+
+/// The shape of one of the [OPEN_CHORDS] that plays a chord, if there is one.
+///
+/// The shape must sound every tone of the chord's quality but perhaps its perfect fifth,
+/// as the open C7 doesn't, and nothing else, so e.g. a C7 isn't played as C, nor a C as Cmaj7.
+/// Only chords in root position on a guitar in standard tuning are played as open chords.
+pub fn open_chord_shape<'a>(chord: &ChordName, fretboard: &'a Fretboard) -> Option<FretboardShape<'a>> {
+    if !is_standard_tuning(fretboard) {
+        return None;
+    }
+    let TonalSpecification::RootPosition(root) = &chord.tonality else {
+        return None;
+    };
+    let root = Pc::from(root);
+    let tones: HashSet<Pc> = chord.quality.to_pc_set().iter().copied().collect();
+    OPEN_CHORDS.iter()
+        .filter(|(open_root, _)| Pc::from(open_root) == root)
+        .find_map(|(_, frets)| {
+            let fretted_notes: Vec<FrettedNote<'a>> = (0u8..)
+                .zip(frets)
+                .map(|(string, fret)| match fret {
+                    Some(fret) => fretboard.sounded_note(string, *fret).ok().map(FrettedNote::Sounded),
+                    None => Some(FrettedNote::Muted { string, fretboard }),
+                })
+                .collect::<Option<_>>()?;
+            let sounded: HashSet<Pc> = fretted_notes.iter()
+                .filter_map(FrettedNote::pitch)
+                .map(|pitch| Pc::from(&root.distance_up_to(&Pc::from(&pitch.note))))
+                .collect();
+            let missing: Vec<&Pc> = tones.difference(&sounded).collect();
+            (sounded.is_subset(&tones) && missing.iter().all(|pc| **pc == Pc::Pc7))
+                .then_some(FretboardShape { fretted_notes, fretboard })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::{FrettedInstrument, STD_6STR_GTR};
+    use crate::note::pitch::Pitch;
+    use crate::note_collections::NoteSet;
+
+    #[test]
+    fn open_chord_shapes() {
+        let shape = |notes: Vec<Note>| {
+            let chord = ChordName::from_note_set(&NoteSet::starting_from_first_note(notes)).unwrap();
+            open_chord_shape(&chord, &STD_6STR_GTR).map(|shape| shape.to_string())
+        };
+        assert_eq!(shape(vec![Note::C, Note::E, Note::G]).as_deref(), Some("x-3-2-0-1-0"));
+        assert_eq!(shape(vec![Note::C, Note::E, Note::G, Note::Bes]).as_deref(), Some("x-3-2-3-1-0"));
+        assert_eq!(shape(vec![Note::C, Note::E, Note::G, Note::B]).as_deref(), Some("x-3-2-0-0-0"));
+        assert_eq!(shape(vec![Note::D, Note::F, Note::A]).as_deref(), Some("x-x-0-2-3-1"));
+        assert_eq!(shape(vec![Note::B, Note::Dis, Note::Fis, Note::A]).as_deref(), Some("x-2-1-2-0-2"));
+        assert_eq!(shape(vec![Note::F, Note::A, Note::C]), None);
+        assert_eq!(shape(vec![Note::Bes, Note::D, Note::F]), None);
+        // Every open chord is named as its root and sounds nothing outside its quality.
+        for (root, frets) in OPEN_CHORDS {
+            let pitches: Vec<Pitch> = (0u8..).zip(frets)
+                .filter_map(|(string, fret)| Some(STD_6STR_GTR.pitch_at(string, fret?).unwrap()))
+                .collect();
+            let mut notes = vec![root];
+            notes.extend(pitches.iter().map(|pitch| pitch.note).filter(|note| Pc::from(note) != Pc::from(&root)));
+            let chord = ChordName::from_note_set(&NoteSet::starting_from_first_note(notes)).unwrap();
+            assert!(open_chord_shape(&chord, &STD_6STR_GTR).is_some(), "{} {:?}", root, frets);
+        }
+    }
+}
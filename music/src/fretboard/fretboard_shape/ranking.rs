@@ -0,0 +1,198 @@
+use std::cmp::Ordering;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::melodic_shape_search::MelodicFretboardShape;
+
+/// One way of deciding which of two shapes is better. [Ordering::Less] means `a` ranks first.
+///
+/// Any closure `Fn(&S, &S) -> Ordering` is a criterion, and so is anything
+/// implementing this trait, boxed or not.
+pub trait RankingCriterion<S> {
+    fn compare(&self, a: &S, b: &S) -> Ordering;
+}
+
+impl<S, T: Fn(&S, &S) -> Ordering> RankingCriterion<S> for T {
+    fn compare(&self, a: &S, b: &S) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// The features of a shape that the built-in criteria of [ShapeRanker] rank by.
+pub trait RankableShape {
+    /// The fret of every sounded note, where 0 is an open string.
+    fn sounded_frets(&self) -> Vec<u8>;
+
+    /// A playability cost, where lower is better. Defaults to 0.
+    fn score(&self) -> usize {
+        0
+    }
+
+    /// Whether the shape asks the index finger to hold down more than one string.
+    /// Defaults to false.
+    fn needs_barre(&self) -> bool {
+        false
+    }
+
+    fn open_strings(&self) -> usize {
+        self.sounded_frets().iter().filter(|fret| **fret == 0).count()
+    }
+
+    /// The lowest fret held down, or 0 if only open strings are played.
+    fn position(&self) -> u8 {
+        self.sounded_frets().into_iter().filter(|fret| *fret > 0).min().unwrap_or(0)
+    }
+}
+
+impl<'a, F: FrettedInstrument> RankableShape for FretboardShape<'a, F> {
+    fn sounded_frets(&self) -> Vec<u8> {
+        self.fretted_notes.iter()
+            .filter_map(|note| match note {
+                FrettedNote::Sounded(SoundedNote { fret, .. }) => Some(*fret),
+                FrettedNote::Muted { .. } => None,
+            })
+            .collect()
+    }
+
+    /// With one note per string, a shape needs a barre when it holds down
+    /// more strings than there are fingers to fret them.
+    fn needs_barre(&self) -> bool {
+        self.sounded_frets().iter().filter(|fret| **fret > 0).count() > 4
+    }
+}
+
+impl<'a> RankableShape for MelodicFretboardShape<'a> {
+    fn sounded_frets(&self) -> Vec<u8> {
+        self.shape.iter().map(|note| note.fret).collect()
+    }
+
+    fn score(&self) -> usize {
+        self.score
+    }
+}
+
+/// Ranks shapes by a list of criteria, each consulted only to break the ties of the ones before.
+/// Shapes tied on every criterion keep their original order.
+/// For example, `ShapeRanker::new().prefer_no_barre().prefer_low_position()`.
+pub struct ShapeRanker<S> {
+    criteria: Vec<Box<dyn RankingCriterion<S>>>,
+}
+
+impl<S> ShapeRanker<S> {
+    /// A ranker with no criteria, under which every shape ties.
+    pub fn new() -> Self {
+        Self { criteria: vec![] }
+    }
+
+    /// Adds a criterion, consulted after all those already added.
+    pub fn then(mut self, criterion: impl RankingCriterion<S> + 'static) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+
+    /// Adds a criterion preferring shapes with lower keys.
+    pub fn then_by_key<K: Ord>(self, key: impl Fn(&S) -> K + 'static) -> Self {
+        self.then(move |a: &S, b: &S| key(a).cmp(&key(b)))
+    }
+
+    pub fn compare(&self, a: &S, b: &S) -> Ordering {
+        self.criteria.iter()
+            .map(|criterion| criterion.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Sorts shapes from best to worst.
+    pub fn rank(&self, shapes: &mut [S]) {
+        shapes.sort_by(|a, b| self.compare(a, b));
+    }
+
+    /// Splits off the `n` best shapes, best first, from the rest, which keep their original order.
+    pub fn best_n(&self, shapes: Vec<S>, n: usize) -> (Vec<S>, Vec<S>) {
+        let mut order: Vec<usize> = (0..shapes.len()).collect();
+        order.sort_by(|a, b| self.compare(&shapes[*a], &shapes[*b]));
+        let mut best: Vec<Option<S>> = (0..n.min(shapes.len())).map(|_| None).collect();
+        let mut rank = vec![None; shapes.len()];
+        for (i, index) in order.into_iter().take(n).enumerate() {
+            rank[index] = Some(i);
+        }
+        let mut the_rest = vec![];
+        for (shape, rank) in shapes.into_iter().zip(rank) {
+            match rank {
+                Some(i) => best[i] = Some(shape),
+                None => the_rest.push(shape),
+            }
+        }
+        (best.into_iter().flatten().collect(), the_rest)
+    }
+}
+
+impl<S: RankableShape> ShapeRanker<S> {
+    pub fn prefer_low_score(self) -> Self {
+        self.then_by_key(|shape: &S| shape.score())
+    }
+
+    pub fn prefer_open_strings(self) -> Self {
+        self.then_by_key(|shape: &S| std::cmp::Reverse(shape.open_strings()))
+    }
+
+    pub fn prefer_low_position(self) -> Self {
+        self.then_by_key(|shape: &S| shape.position())
+    }
+
+    pub fn prefer_no_barre(self) -> Self {
+        self.then_by_key(|shape: &S| shape.needs_barre())
+    }
+}
+
+/// Ranks by [RankableShape::score] alone.
+impl<S: RankableShape> Default for ShapeRanker<S> {
+    fn default() -> Self {
+        Self::new().prefer_low_score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fretboard::STD_6STR_GTR;
+    use super::*;
+
+    fn shape(frets: &[Option<u8>]) -> FretboardShape<'static> {
+        FretboardShape {
+            fretted_notes: frets.iter()
+                .enumerate()
+                .map(|(string, fret)| match fret {
+                    Some(fret) => FrettedNote::Sounded(
+                        STD_6STR_GTR.sounded_note(string as u8, *fret).unwrap()
+                    ),
+                    None => FrettedNote::Muted { string: string as u8, fretboard: &*STD_6STR_GTR },
+                })
+                .collect(),
+            fretboard: &STD_6STR_GTR,
+        }
+    }
+
+    #[test]
+    fn ranking_chord_shapes() {
+        let open_c = shape(&[None, Some(3), Some(2), Some(0), Some(1), Some(0)]);
+        let barre_c = shape(&[None, Some(3), Some(5), Some(5), Some(5), Some(3)]);
+        let high_c = shape(&[Some(8), Some(10), Some(10), Some(9), Some(8), Some(8)]);
+        assert!(!open_c.needs_barre());
+        assert!(barre_c.needs_barre());
+        assert_eq!(high_c.position(), 8);
+
+        let shapes = vec![high_c.clone(), barre_c.clone(), open_c.clone()];
+        let ranker = ShapeRanker::new().prefer_low_position();
+        let (best, rest) = ranker.best_n(shapes.clone(), 1);
+        assert_eq!(best[0].to_string(), "x-3-2-0-1-0");
+        assert_eq!(rest.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                   vec!["8-10-10-9-8-8", "x-3-5-5-5-3"]);
+
+        // A user-supplied criterion preferring the highest shapes.
+        let mut ranked = shapes;
+        ShapeRanker::new()
+            .then(|a: &FretboardShape, b: &FretboardShape| b.position().cmp(&a.position()))
+            .rank(&mut ranked);
+        assert_eq!(ranked[0].to_string(), "8-10-10-9-8-8");
+    }
+}
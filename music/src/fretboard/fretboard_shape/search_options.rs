@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use crate::error::MusicSemanticsError;
+
+/// Limits on how much work a fretboard shape search may do.
+///
+/// Searches over extended-range instruments can take a long time. Exhausting any budget
+/// stops the search early, and returns the shapes found so far. Cancelling via the
+/// [CancellationToken] instead abandons the search with [MusicSemanticsError::SearchCancelled].
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Stop once this many complete shapes have been found.
+    pub max_results: Option<usize>,
+    /// Stop extending any shape once it holds this many notes.
+    pub max_depth: Option<usize>,
+    /// Stop once this much time has elapsed since the search started.
+    pub time_budget: Option<Duration>,
+    /// Checked throughout the search, so that another thread may cancel it.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl SearchOptions {
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+}
+
+/// A cooperative cancellation flag, shared between a search and whoever started it.
+/// Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks any search holding this token to stop as soon as possible.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The running state of a search against its [SearchOptions].
+#[derive(Debug)]
+pub(crate) struct SearchBudget<'o> {
+    options: &'o SearchOptions,
+    deadline: Option<Instant>,
+    results: usize,
+}
+
+impl<'o> SearchBudget<'o> {
+    pub(crate) fn new(options: &'o SearchOptions) -> Self {
+        Self {
+            options,
+            deadline: options.time_budget.map(|budget| Instant::now() + budget),
+            results: 0,
+        }
+    }
+
+    /// Whether the search should stop early and return what it has.
+    /// Errors if the search has been cancelled.
+    pub(crate) fn is_exhausted(&self) -> Result<bool, MusicSemanticsError> {
+        if self.options.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(MusicSemanticsError::SearchCancelled);
+        }
+        Ok(self.options.max_results.is_some_and(|max| self.results >= max)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline))
+    }
+
+    /// Whether a branch of the search holding `depth` notes may be extended.
+    pub(crate) fn can_deepen(&self, depth: usize) -> bool {
+        self.options.max_depth.is_none_or(|max| depth < max)
+    }
+
+    pub(crate) fn record_result(&mut self) {
+        self.results += 1;
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use itertools::Itertools;
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretboard_shape::note_up_an_octave;
+use crate::fretboard::fretboard_shape::melodic_shape_search::{MelodicFretboardShape, ScaleShapeSearchResult};
+use crate::fretboard::fretted_note::SoundedNote;
+use crate::fretboard::grid::FretboardGrid;
+
+/// One of the position shapes of a [ScaleSystem].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemPosition<'a> {
+    /// Numbered from 1 up the neck within the lowest octave,
+    /// and numbered the same again each octave higher.
+    pub number: usize,
+    pub shape: MelodicFretboardShape<'a>,
+}
+
+/// A note of a [ScaleSystem], and the positions it's played in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemNote<'a> {
+    pub note: SoundedNote<'a>,
+    /// Indices into [ScaleSystem::positions], in ascending order.
+    pub positions: Vec<usize>,
+}
+
+impl<'a> SystemNote<'a> {
+    /// Whether the note belongs to more than one position, i.e. it's where the positions connect.
+    pub fn is_overlap(&self) -> bool {
+        self.positions.len() > 1
+    }
+}
+
+/// The position shapes of a scale, e.g. the five pentatonic boxes, connected up the neck
+/// into one map of the whole neck, with the notes where neighbouring positions overlap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleSystem<'a> {
+    /// In order up the neck.
+    pub positions: Vec<SystemPosition<'a>>,
+    /// Every note of every position, once each, by string and then fret.
+    pub notes: Vec<SystemNote<'a>>,
+    pub fretboard: &'a Fretboard,
+}
+
+impl<'a> ScaleSystem<'a> {
+    /// Connects `shapes` up the neck. Each shape is moved as far down the neck as it goes,
+    /// and shapes that are octave equivalent to an earlier one are dropped.
+    /// The positions are then repeated in each higher octave, wherever they fit at or below `max_fret`.
+    pub fn stitch(shapes: &[MelodicFretboardShape<'a>], max_fret: u8) -> Result<Self, MusicSemanticsError> {
+        let mut lowest_octave: Vec<MelodicFretboardShape<'a>> = vec![];
+        for shape in shapes.iter().filter(|shape| !shape.shape.is_empty()) {
+            let shape = shape.transpose_down_octave_if_possible();
+            if !lowest_octave.iter().any(|other| other.is_octave_equivalent(&shape)) {
+                lowest_octave.push(shape);
+            }
+        }
+        let Some(fretboard) = lowest_octave.first().map(|shape| shape.fretboard) else {
+            return Err(MusicSemanticsError::EmptySetOfNotes);
+        };
+        lowest_octave.sort_by_key(MelodicFretboardShape::span);
+        let max_fret = max_fret.min(fretboard.max_fret());
+
+        let mut positions: Vec<SystemPosition<'a>> = vec![];
+        let mut octave: Vec<SystemPosition<'a>> = lowest_octave.into_iter()
+            .enumerate()
+            .map(|(i, shape)| SystemPosition { number: i + 1, shape })
+            .collect();
+        while !octave.is_empty() {
+            positions.extend(octave.iter().filter(|position| position.shape.span().1 <= max_fret).cloned());
+            octave = octave.iter().filter_map(up_an_octave).collect();
+        }
+        positions.sort_by_key(|position| (position.shape.span(), position.number));
+
+        let mut notes: BTreeMap<(u8, u8), SystemNote<'a>> = BTreeMap::new();
+        for (i, position) in positions.iter().enumerate() {
+            for note in &position.shape.shape {
+                notes.entry((note.string, note.fret))
+                    .or_insert_with(|| SystemNote { note: note.clone(), positions: vec![] })
+                    .positions
+                    .push(i);
+            }
+        }
+        for note in notes.values_mut() {
+            note.positions.dedup();
+        }
+        Ok(Self {
+            positions,
+            notes: notes.into_values().collect(),
+            fretboard,
+        })
+    }
+
+    /// Connects the [ScaleShapeSearchResult::simple] shapes of a search up the neck.
+    /// See [ScaleSystem::stitch].
+    pub fn from_search_result(result: &ScaleShapeSearchResult<'a>, max_fret: u8) -> Result<Self, MusicSemanticsError> {
+        Self::stitch(&result.simple, max_fret)
+    }
+
+    /// The notes where each position overlaps the next one up the neck.
+    pub fn overlaps(&self) -> Vec<Vec<&SoundedNote<'a>>> {
+        (1..self.positions.len())
+            .map(|i| self.notes.iter()
+                .filter(|note| note.positions.contains(&(i - 1)) && note.positions.contains(&i))
+                .map(|note| &note.note)
+                .collect())
+            .collect()
+    }
+
+    /// Whether every position shares at least one note with the next,
+    /// so that the whole neck can be played without leaving the system.
+    pub fn is_seamless(&self) -> bool {
+        self.overlaps().iter().all(|overlap| !overlap.is_empty())
+    }
+
+    /// A drawing of the system, with each note marked by the number of its position,
+    /// or e.g. "1/2" where positions 1 and 2 overlap.
+    pub fn grid(&self) -> FretboardGrid<'a> {
+        let (low, high) = self.notes.iter()
+            .map(|note| note.note.fret)
+            .minmax()
+            .into_option()
+            .unwrap_or((0, 0));
+        self.notes.iter().fold(
+            FretboardGrid::new(self.fretboard).with_frets(low, high),
+            |grid, note| {
+                let label = note.positions.iter()
+                    .map(|i| self.positions[*i].number)
+                    .dedup()
+                    .join("/");
+                grid.mark(note.note.string, note.note.fret, &label)
+            },
+        )
+    }
+}
+
+impl<'a> Display for ScaleSystem<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid())
+    }
+}
+
+/// The same position an octave higher, if the strings are long enough.
+fn up_an_octave<'a>(position: &SystemPosition<'a>) -> Option<SystemPosition<'a>> {
+    let shape = position.shape.shape.iter()
+        .map(note_up_an_octave)
+        .collect::<Option<Vec<_>>>()?;
+    Some(SystemPosition {
+        number: position.number,
+        shape: MelodicFretboardShape { shape, ..position.shape.clone() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::fretboard::fretboard_shape::melodic_shape_search::n_note_per_string_shape;
+    use crate::note::note::Note;
+
+    #[test]
+    fn pentatonic_system() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let pentatonic = vec![Note::A, Note::C, Note::D, Note::E, Note::G];
+        let boxes: Vec<MelodicFretboardShape> = pentatonic.iter()
+            .map(|note| n_note_per_string_shape((2, 2), &pentatonic, note, fretboard).unwrap())
+            .collect();
+        // Out of order, and with the first box again an octave up.
+        let octave_up = MelodicFretboardShape {
+            shape: boxes[0].shape.iter().map(|note| note_up_an_octave(note).unwrap()).collect(),
+            ..boxes[0].clone()
+        };
+        let shapes = [&boxes[3], &octave_up, &boxes[4], &boxes[1], &boxes[2], &boxes[0]].map(Clone::clone);
+        let system = ScaleSystem::stitch(&shapes, 15).unwrap();
+        assert_eq!(
+            system.positions.iter().map(|position| position.number).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5, 1],
+        );
+        assert_eq!(system.positions[0].shape.span(), (0, 3));
+        assert_eq!(system.positions[5].shape.span(), (12, 15));
+        assert!(system.is_seamless());
+        let overlaps = system.overlaps();
+        assert!(overlaps.iter().all(|overlap| overlap.len() == 6));
+        assert_eq!(overlaps[1].iter().map(|note| note.to_string()).join(" "), "1:5(A) 2:5(D) 3:5(G) 4:5(C) 5:5(E) 6:5(A)");
+        assert_eq!(system.notes.iter().filter(|note| note.is_overlap()).count(), 30);
+        let lines: Vec<String> = system.to_string().lines().map(str::to_string).collect();
+        assert_eq!(lines[1], "E ─1─║───│───│1/2│───│2/3│───│───│3/4│───│4/5│───│5/1│───│───│─1─│");
+        assert_eq!(lines[3], "G ─1─║───│1/2│───│───│2/3│───│3/4│───│4/5│───│───│5/1│───│─1─│───│");
+
+        // Without the positions between them, the boxes don't connect.
+        let gapped = ScaleSystem::stitch(&[boxes[3].clone(), boxes[0].clone()], 12).unwrap();
+        assert_eq!(gapped.positions.len(), 2);
+        assert!(!gapped.is_seamless());
+        assert!(ScaleSystem::stitch(&[], 12).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt::Debug;
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use crate::error::MusicSemanticsError;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::voicing::Voicing;
+
+/// Common behavior for any instrument with strings that are stopped at frets.
+///
+/// The only thing an implementor must define is its open strings, and how
+/// each fret maps to a distance in semitones above its open string.
+/// Everything else, including the constructors for [SoundedNote],
+/// is derived from that mapping. This allows instruments whose frets are not laid out
+/// chromatically (e.g. a mountain dulcimer) to be used with the same shape search
+/// functionality as a [crate::fretboard::Fretboard].
+pub trait FrettedInstrument: Debug + Clone + PartialEq {
+    /// The tuning of the instrument. Canonically, we use `open_strings()[0]`
+    /// to represent the thickest string on an instrument.
+    fn open_strings(&self) -> &[Pitch];
+
+    /// The highest fret on any string.
+    fn max_fret(&self) -> u8;
+
+    /// The distance in semitones above its open string at which a given fret sounds.
+    fn fret_to_semitones(&self, string: u8, fret: u8) -> Result<u8, MusicSemanticsError>;
+
+    /// The number of strings on the instrument.
+    fn num_strings(&self) -> u8 {
+        u8::try_from(self.open_strings().len()).unwrap()
+    }
+
+    /// Fallible indexing for an element in [FrettedInstrument::open_strings].
+    fn get_string(&self, string: u8) -> Result<&Pitch, MusicSemanticsError> {
+        self.open_strings()
+            .get(string as usize)
+            .ok_or(MusicSemanticsError::StringTooHigh(string, self.num_strings()))
+    }
+
+    /// The [Pitch] sounded at a given string and fret.
+    fn pitch_at(&self, string: u8, fret: u8) -> Result<Pitch, MusicSemanticsError> {
+        if fret > self.max_fret() {
+            return Err(MusicSemanticsError::FretTooHigh(fret));
+        }
+        let open_string = self.get_string(string)?;
+        let distance = self.fret_to_semitones(string, fret)?;
+        if distance == 0 {
+            return Ok(*open_string);
+        }
+        open_string.at_distance_from(distance as isize)
+    }
+
+    /// This is the preferred way to create a [SoundedNote] instance, because it
+    /// validates the initialization parameters against [self].
+    fn sounded_note(&self, string: u8, fret: u8) -> Result<SoundedNote<'_, Self>, MusicSemanticsError> {
+        let pitch = self.pitch_at(string, fret)?;
+        Ok(SoundedNote {
+            string,
+            fret,
+            pitch,
+            fretboard: self,
+        })
+    }
+
+    /// Returns the lowest fret where a given [Note] resides on a given string.
+    /// e.g. "where is the place I can find an F# on the 3rd string of this fretboard?"
+    fn which_fret(&self, note: &Note, string: u8) -> Result<u8, MusicSemanticsError> {
+        let target = Pc::from(note);
+        for fret in 0..=self.max_fret() {
+            let pitch = self.pitch_at(string, fret)?;
+            if Pc::from(&pitch.note) == target {
+                return Ok(fret);
+            }
+        }
+        Err(MusicSemanticsError::NoteNotOnString(*note, string))
+    }
+
+    /// Returns the fret at which an exact [Pitch] sounds on a given string.
+    fn fret_for_pitch(&self, string: u8, pitch: &Pitch) -> Result<u8, MusicSemanticsError> {
+        for fret in 0..=self.max_fret() {
+            if self.pitch_at(string, fret)?.midi_note == pitch.midi_note {
+                return Ok(fret);
+            }
+        }
+        Err(MusicSemanticsError::PitchNotOnString(*pitch, string))
+    }
+
+    /// The length in millimeters of the vibrating strings, from nut to saddle.
+    /// Instruments without a defined scale length can't be modeled physically.
+    fn scale_length_mm(&self) -> Option<f64> {
+        None
+    }
+
+    /// The distance in millimeters from the nut to a given fret wire,
+    /// as given by the equal-tempered placement of frets on the scale length.
+    fn fret_position_mm(&self, fret: u8) -> Option<f64> {
+        let scale_length = self.scale_length_mm()?;
+        let semitones = self.fret_to_semitones(0, fret).ok()?;
+        Some(scale_length * (1.0 - 2f64.powf(-(semitones as f64) / 12.0)))
+    }
+
+    /// The distance in millimeters between two fret wires.
+    fn fret_distance_mm(&self, a: u8, b: u8) -> Option<f64> {
+        Some((self.fret_position_mm(b)? - self.fret_position_mm(a)?).abs())
+    }
+
+    /// The physical distance a hand must stretch to cover the frets `low..=high`.
+    /// We measure from the fret wire behind `low` (where the index finger sits)
+    /// to the fret wire of `high`, so a span of the same number of frets
+    /// becomes shorter the higher up the neck it's played.
+    fn stretch_mm(&self, low: u8, high: u8) -> Option<f64> {
+        let behind = low.max(1) - 1;
+        self.fret_distance_mm(behind, high)
+    }
+
+    /// Given a string and target note, returns the first available
+    /// [SoundedNote] whose fret equals a given [Note].
+    fn note_on_string(&self, note: &Note, string: u8) -> Result<SoundedNote<'_, Self>, MusicSemanticsError> {
+        let fret = self.which_fret(note, string)?;
+        self.sounded_note(string, fret)
+    }
+
+    /// Every chord shape that sounds exactly the pitches of `voicing`, one per string,
+    /// keeping their spelling. Unlike a search by [Note]s, octaves aren't interchangeable,
+    /// so e.g. a voicing designed at the keyboard can be checked for playability,
+    /// with [FretboardShape::classify]. See [FretboardShape::to_voicing] for the reverse.
+    ///
+    /// Shapes are in no particular order, and there are none if the voicing has more pitches
+    /// than there are strings, or pitches beyond the range of the instrument.
+    fn realize_voicing(&self, voicing: &Voicing) -> Vec<FretboardShape<'_, Self>> {
+        if voicing.is_empty() {
+            return vec![];
+        }
+        let mut shapes: Vec<FretboardShape<'_, Self>> = vec![];
+        for strings in (0..self.num_strings()).permutations(voicing.len()) {
+            let frets: Result<Vec<u8>, MusicSemanticsError> = voicing.iter()
+                .zip(&strings)
+                .map(|(pitch, string)| self.fret_for_pitch(*string, pitch))
+                .collect();
+            let Ok(frets) = frets else {
+                continue;
+            };
+            let fretted_notes = (0..self.num_strings())
+                .map(|string| match strings.iter().position(|s| *s == string) {
+                    Some(i) => FrettedNote::Sounded(SoundedNote {
+                        string,
+                        fret: frets[i],
+                        pitch: voicing[i],
+                        fretboard: self,
+                    }),
+                    None => FrettedNote::Muted { string, fretboard: self },
+                })
+                .collect();
+            // Unisons in the voicing would otherwise give each shape more than once.
+            if !shapes.iter().any(|shape| shape.fretted_notes == fretted_notes) {
+                shapes.push(FretboardShape { fretted_notes, fretboard: self });
+            }
+        }
+        shapes
+    }
+}
+
+/// A mountain dulcimer in D-A-D tuning, with the common "6+" fret.
+pub static MOUNTAIN_DULCIMER: Lazy<DiatonicFretboard> = Lazy::new(|| {
+    DiatonicFretboard {
+        open_strings: vec![
+            Pitch::new(Note::D, 3).unwrap(),
+            Pitch::new(Note::A, 3).unwrap(),
+            Pitch::new(Note::D, 4).unwrap(),
+        ],
+        fret_offsets: vec![0, 2, 4, 5, 7, 9, 10, 11, 12, 14, 16, 17, 19, 21, 22, 23, 24],
+    }
+});
+
+/// A fretboard whose frets are not necessarily a semitone apart.
+/// Every string shares the same layout of frets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiatonicFretboard {
+    /// The tuning of the instrument, thickest string first.
+    pub open_strings: Vec<Pitch>,
+    /// The distance in semitones above the open string of each fret,
+    /// starting with the open string itself at `fret_offsets[0] == 0`.
+    pub fret_offsets: Vec<u8>,
+}
+
+impl FrettedInstrument for DiatonicFretboard {
+    fn open_strings(&self) -> &[Pitch] {
+        &self.open_strings
+    }
+
+    fn max_fret(&self) -> u8 {
+        u8::try_from(self.fret_offsets.len()).unwrap().saturating_sub(1)
+    }
+
+    fn fret_to_semitones(&self, _string: u8, fret: u8) -> Result<u8, MusicSemanticsError> {
+        self.fret_offsets
+            .get(fret as usize)
+            .copied()
+            .ok_or(MusicSemanticsError::FretTooHigh(fret))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::fretboard_shape::chord_shape_search::find_chord_shapes;
+
+    #[test]
+    fn diatonic_fret_mapping() {
+        let dulcimer = &*MOUNTAIN_DULCIMER;
+        // The "6+" fret sounds a major seventh above the open string.
+        assert_eq!(dulcimer.pitch_at(0, 7).unwrap(), Pitch::new(Note::Cis, 4).unwrap());
+        assert_eq!(dulcimer.which_fret(&Note::G, 0).unwrap(), 3);
+        assert_eq!(dulcimer.which_fret(&Note::Cis, 0).unwrap(), 7);
+        // There's no F on a D string.
+        assert!(dulcimer.which_fret(&Note::F, 0).is_err());
+        let a = dulcimer.sounded_note(1, 0).unwrap();
+        assert_eq!(a.up_an_octave().unwrap().fret, 8);
+    }
+
+    #[test]
+    fn diatonic_chord_shapes() {
+        let dulcimer = &*MOUNTAIN_DULCIMER;
+        let chord = vec![Note::D, Note::Fis, Note::A];
+        let shapes = find_chord_shapes(&chord, dulcimer).unwrap();
+        assert!(!shapes.playable.is_empty());
+        // A chord containing a note missing from the fretboard has no shapes.
+        let chord = vec![Note::D, Note::F, Note::A];
+        let shapes = find_chord_shapes(&chord, dulcimer).unwrap();
+        assert!(shapes.playable.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 use crate::error::MusicSemanticsError;
 use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
 use crate::note::note::Note;
 use crate::note::pitch::Pitch;
 use crate::note_collections::NoteSet;
@@ -10,37 +11,39 @@ use crate::note_collections::NoteSet;
 /// But if you're never going to have to notated a muted string,
 /// it is better to use a [SoundedNote] directly instead.
 #[derive(Debug, Clone, PartialEq)]
-pub enum FrettedNote<'a> {
+pub enum FrettedNote<'a, F: FrettedInstrument = Fretboard> {
     /// A note that is played on the fretboard.
-    Sounded(SoundedNote<'a>),
+    Sounded(SoundedNote<'a, F>),
     /// Denotes a muted string. Usually most useful for chord diagrams.
     Muted {
         string: u8,
-        fretboard: &'a Fretboard,
+        fretboard: &'a F,
     },
 }
 
 /// A note played on a fretboard. A reference to the fretboard ensures
 /// that each existing [FrettedNote] instance refers in code back to an
 /// actual [Fretboard] instance, which is often useful for performing calculations.
+///
+/// Any other [FrettedInstrument] may stand in for the [Fretboard].
 #[derive(Debug, Clone, PartialEq)]
-pub struct SoundedNote<'a> {
+pub struct SoundedNote<'a, F: FrettedInstrument = Fretboard> {
     pub string: u8,
     pub fret: u8,
     pub pitch: Pitch,
-    pub fretboard: &'a Fretboard,
+    pub fretboard: &'a F,
 }
 
-impl<'a> Display for SoundedNote<'a> {
+impl<'a, F: FrettedInstrument> Display for SoundedNote<'a, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}:{}({})", self.string+1, self.fret, self.pitch.note)
     }
 }
 
-impl<'a> SoundedNote<'a> {
+impl<'a, F: FrettedInstrument> SoundedNote<'a, F> {
     /// Preferred constructor for an open string. Validates using
     /// the methods on the [Fretboard] passed in.
-    pub fn open(string: u8, fretboard: &'a Fretboard) -> Result<Self, MusicSemanticsError> {
+    pub fn open(string: u8, fretboard: &'a F) -> Result<Self, MusicSemanticsError> {
         let open_string = fretboard.get_string(string)?;
         Ok(Self {
             fret: Fretboard::OPEN,
@@ -52,8 +55,8 @@ impl<'a> SoundedNote<'a> {
 
     /// Preferred constructor for a fretted string. Validates using
     /// the methods on the [Fretboard] passed in.
-    pub fn fretted(string: u8, fret: u8, fretboard: &'a Fretboard) -> Result<Self, MusicSemanticsError> {
-        Ok(fretboard.sounded_note(string, fret)?)
+    pub fn fretted(string: u8, fret: u8, fretboard: &'a F) -> Result<Self, MusicSemanticsError> {
+        fretboard.sounded_note(string, fret)
     }
 
     /// Returns a clone of self, but with the pitch spelled according
@@ -83,25 +86,29 @@ impl<'a> SoundedNote<'a> {
         Ok(self.fretboard.sounded_note(self.string, self.fret - n)?)
     }
 
-    /// Moves up the same string to a new fret `n` semitones higher.
+    /// Moves up the same string 12 semitones, if possible.
     pub fn up_an_octave(&self) -> Result<Self, MusicSemanticsError> {
-        self.fretboard.sounded_note(self.string, self.fret + 12)
+        let pitch = self.pitch.at_distance_from(12)?;
+        let fret = self.fretboard.fret_for_pitch(self.string, &pitch)?;
+        self.fretboard.sounded_note(self.string, fret)
     }
 
     /// Moves down the same string 12 semitones, if possible.
     pub fn down_an_octave(&self) -> Result<Self, MusicSemanticsError> {
-        if self.fret < 12 {
+        let open_string = self.fretboard.get_string(self.string)?;
+        if self.pitch.midi_note < open_string.midi_note + 12 {
             return Err(MusicSemanticsError::CantMoveDownFrets(12));
         }
-        Ok(self.fretboard.sounded_note(self.string, self.fret - 12)?)
+        let pitch = self.pitch.at_distance_from(-12)?;
+        let fret = self.fretboard.fret_for_pitch(self.string, &pitch)?;
+        self.fretboard.sounded_note(self.string, fret)
     }
 
     /// Produces a [SoundedNote] on the next chord/scale degree, on the same string.
     pub fn next_note_same_string(&self, notes: &NoteSet) -> Result<Self, MusicSemanticsError> {
         let next_note = notes.up_n_steps(&self.pitch.note, 1)?;
         let pitch = self.pitch.up_to_note(&next_note)?;
-        let this_string = self.fretboard.get_string(self.string).unwrap();
-        let fret = pitch.midi_note - this_string.midi_note;
+        let fret = self.fretboard.fret_for_pitch(self.string, &pitch)?;
         self.fretboard.sounded_note(self.string, fret)
     }
 
@@ -111,18 +118,17 @@ impl<'a> SoundedNote<'a> {
         let pitch = self.pitch.up_to_note(&next_note)?;
         let next_string = self.fretboard.get_string(self.string + 1)?;
         if next_string.midi_note > pitch.midi_note {
-            return Err(MusicSemanticsError::FretBelowZero(pitch, next_string.clone()));
+            return Err(MusicSemanticsError::FretBelowZero(pitch, *next_string));
         }
-        self.fretboard.sounded_note(
-            self.string + 1, pitch.midi_note - next_string.midi_note
-        )
+        let fret = self.fretboard.fret_for_pitch(self.string + 1, &pitch)?;
+        self.fretboard.sounded_note(self.string + 1, fret)
     }
 }
 
-impl<'a> FrettedNote<'a> {
+impl<'a, F: FrettedInstrument> FrettedNote<'a, F> {
 
     /// Constructor for a [FrettedNote::Muted] variant.
-    pub fn muted(string: u8, fretboard: &'a Fretboard) -> Result<Self, MusicSemanticsError> {
+    pub fn muted(string: u8, fretboard: &'a F) -> Result<Self, MusicSemanticsError> {
         let _ = fretboard.get_string(string)?;
         Ok(Self::Muted {
             string,
@@ -131,7 +137,7 @@ impl<'a> FrettedNote<'a> {
     }
 
     /// Constructor for a [FrettedNote::Sounded] variant of an open string.
-    pub fn open(string: u8, fretboard: &'a Fretboard) -> Result<Self, MusicSemanticsError> {
+    pub fn open(string: u8, fretboard: &'a F) -> Result<Self, MusicSemanticsError> {
         let open_string = fretboard.get_string(string)?;
         Ok(Self::Sounded(SoundedNote {
             pitch: open_string.clone(),
@@ -142,7 +148,7 @@ impl<'a> FrettedNote<'a> {
     }
 
     /// Construct a [FrettedNote::Sounded] variant that is fretted.
-    pub fn fretted(string: u8, fret: u8, fretboard: &'a Fretboard) -> Result<Self, MusicSemanticsError> {
+    pub fn fretted(string: u8, fret: u8, fretboard: &'a F) -> Result<Self, MusicSemanticsError> {
         Ok(Self::Sounded(fretboard.sounded_note(string, fret)?))
     }
 
@@ -192,8 +198,8 @@ impl<'a> FrettedNote<'a> {
 }
 
 /// Wrap a [SoundedNote] in a [FrettedNote::Sounded].
-impl<'a> From<SoundedNote<'a>> for FrettedNote<'a> {
-    fn from(value: SoundedNote<'a>) -> Self {
+impl<'a, F: FrettedInstrument> From<SoundedNote<'a, F>> for FrettedNote<'a, F> {
+    fn from(value: SoundedNote<'a, F>) -> Self {
         FrettedNote::Sounded(value)
     }
-}
\ No newline at end of file
+}
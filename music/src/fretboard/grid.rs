@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::fretboard::neck_map::NeckMap;
+
+/// Frets with a single inlay marker. See [FretboardGrid].
+const SINGLE_INLAYS: &[u8] = &[3, 5, 7, 9, 15, 17, 19, 21, 27, 29, 31, 33];
+/// Frets with a double inlay marker, at each octave.
+const DOUBLE_INLAYS: &[u8] = &[12, 24];
+/// The width in characters of the space between two frets.
+const CELL_WIDTH: usize = 3;
+
+/// A drawing of a window of frets on the neck, with box-drawing characters,
+/// for printing to a terminal. The highest string is drawn at the top, as in tab,
+/// labelled by the note of its open string, with fret numbers above the neck,
+/// inlay markers below it, and the nut drawn when the window starts at the open strings.
+///
+/// Marks are drawn on the strings, between the frets, and can be up to three characters,
+/// e.g. "●" for the notes of a chord shape, or "b7" for the degrees of a scale.
+/// For example, an open C chord:
+///
+/// ```text
+///    0   1   2   3
+/// E ─●─║───│───│───│
+/// B ───║─●─│───│───│
+/// G ─●─║───│───│───│
+/// D ───║───│─●─│───│
+/// A ───║───│───│─●─│
+/// E ─×─║───│───│───│
+///                •
+/// ```
+#[derive(Debug, Clone)]
+pub struct FretboardGrid<'a, F: FrettedInstrument = Fretboard> {
+    fretboard: &'a F,
+    first_fret: u8,
+    last_fret: u8,
+    /// Indexed by string and fret.
+    marks: HashMap<(u8, u8), String>,
+}
+
+impl<'a, F: FrettedInstrument> FretboardGrid<'a, F> {
+    /// A grid of every string, from the open strings up to the 12th fret,
+    /// or the last fret of the instrument if it's lower.
+    pub fn new(fretboard: &'a F) -> Self {
+        Self {
+            fretboard,
+            first_fret: 0,
+            last_fret: fretboard.max_fret().min(12),
+            marks: HashMap::new(),
+        }
+    }
+
+    /// Draws the frets from `first` up to `last` instead, e.g. to zoom in on a shape
+    /// higher up the neck. The window is cut off at the last fret of the instrument.
+    pub fn with_frets(mut self, first: u8, last: u8) -> Self {
+        let max_fret = self.fretboard.max_fret();
+        self.first_fret = first.min(last).min(max_fret);
+        self.last_fret = first.max(last).min(max_fret);
+        self
+    }
+
+    /// Marks a fret on a string with a label, which is cut short past three characters.
+    /// Marks outside the window aren't drawn.
+    pub fn mark(mut self, string: u8, fret: u8, label: &str) -> Self {
+        self.marks.insert((string, fret), label.chars().take(CELL_WIDTH).collect());
+        self
+    }
+
+    /// Marks each sounded note of `shape` with a "●", and each muted string with an "×"
+    /// in front of the nut.
+    pub fn with_shape(self, shape: &FretboardShape<'_, F>) -> Self {
+        shape.fretted_notes.iter().fold(self, |grid, fretted_note| match fretted_note {
+            FrettedNote::Sounded(SoundedNote { string, fret, .. }) => grid.mark(*string, *fret, "●"),
+            FrettedNote::Muted { string, .. } => grid.mark(*string, 0, "×"),
+        })
+    }
+
+    /// Marks every note of `map` with its degree above the root, e.g. "R" or "b3".
+    pub fn with_neck_map(self, map: &NeckMap) -> Self {
+        let mut grid = self;
+        for string in 0..map.num_strings() {
+            for fret in 0..map.num_frets() {
+                let (string, fret) = (string as u8, fret as u8);
+                if let Some(cell) = map.get(string, fret) {
+                    grid = grid.mark(string, fret, &cell.degree.to_string());
+                }
+            }
+        }
+        grid
+    }
+
+    /// One line of the grid. `cell` draws the space at each fret, and `wire` the fret wires,
+    /// which are given the fret behind them.
+    fn line(&self, label: &str, cell: impl Fn(u8) -> String, wire: impl Fn(u8) -> char) -> String {
+        let label_width = self.fretboard.open_strings().iter()
+            .map(|pitch| pitch.note.to_string().chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut line = format!("{:>width$} ", label, width = label_width);
+        if self.first_fret > 0 {
+            line.push(wire(self.first_fret - 1));
+        }
+        for fret in self.first_fret..=self.last_fret {
+            line.push_str(&cell(fret));
+            line.push(wire(fret));
+        }
+        line.trim_end().to_string()
+    }
+}
+
+impl<'a, F: FrettedInstrument> Display for FretboardGrid<'a, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header = self.line("", |fret| format!("{:^width$}", fret, width = CELL_WIDTH), |_| ' ');
+        writeln!(f, "{}", header)?;
+        for string in (0..self.fretboard.num_strings()).rev() {
+            let label = self.fretboard.open_strings()[usize::from(string)].note.to_string();
+            let row = self.line(
+                &label,
+                |fret| {
+                    let mark = self.marks.get(&(string, fret)).map_or("", String::as_str);
+                    format!("{:─^width$}", mark, width = CELL_WIDTH)
+                },
+                |fret| if fret == 0 { '║' } else { '│' },
+            );
+            writeln!(f, "{}", row)?;
+        }
+        let inlays = self.line(
+            "",
+            |fret| {
+                let inlay = match fret {
+                    fret if DOUBLE_INLAYS.contains(&fret) => "••",
+                    fret if SINGLE_INLAYS.contains(&fret) => "•",
+                    _ => "",
+                };
+                format!("{:^width$}", inlay, width = CELL_WIDTH)
+            },
+            |_| ' ',
+        );
+        write!(f, "{}", inlays)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::note::note::Note;
+    use crate::note_collections::NoteSet;
+
+    #[test]
+    fn fretboard_grids() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let open_c = FretboardShape {
+            fretted_notes: [None, Some(3), Some(2), Some(0), Some(1), Some(0)].iter()
+                .enumerate()
+                .map(|(string, fret)| match fret {
+                    Some(fret) => FrettedNote::fretted(string as u8, *fret, fretboard).unwrap(),
+                    None => FrettedNote::muted(string as u8, fretboard).unwrap(),
+                })
+                .collect(),
+            fretboard,
+        };
+        let grid = FretboardGrid::new(fretboard).with_frets(0, 3).with_shape(&open_c);
+        assert_eq!(grid.to_string(), [
+            "   0   1   2   3",
+            "E ─●─║───│───│───│",
+            "B ───║─●─│───│───│",
+            "G ─●─║───│───│───│",
+            "D ───║───│─●─│───│",
+            "A ───║───│───│─●─│",
+            "E ─×─║───│───│───│",
+            "               •",
+        ].join("\n"));
+
+        // Higher up the neck there's no nut, and marks can be longer.
+        let a_minor = NoteSet::new(vec![Note::A, Note::C, Note::E], Some(&Note::A));
+        let map = NeckMap::new(fretboard, &a_minor, 24).unwrap();
+        let grid = FretboardGrid::new(fretboard).with_frets(12, 10).with_neck_map(&map);
+        let lines: Vec<String> = grid.to_string().lines().map(str::to_string).collect();
+        assert_eq!(lines[0], "   10  11  12");
+        assert_eq!(lines[1], "E │───│───│─5─│");
+        assert_eq!(lines[6], "E │───│───│─5─│");
+        assert_eq!(lines[4], "D │b3─│───│───│");
+        assert_eq!(lines[5], "A │───│───│─R─│");
+        assert_eq!(lines[7], "           ••");
+        // The window stops at the end of the neck.
+        let long = FretboardGrid::new(fretboard).with_frets(0, 50);
+        assert!(long.to_string().lines().next().unwrap().ends_with("35"));
+    }
+}
@@ -1,29 +1,52 @@
+pub mod fingering;
 pub mod fretboard_shape;
 pub mod fretted_note;
+pub mod fretted_instrument;
+pub mod grid;
+pub mod neck_map;
+pub mod tuning_comparison;
 
 use std::ops::Deref;
 use once_cell::sync::Lazy;
 use crate::note::note::Note;
-use crate::note::pitch_class::Pc;
 use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
 
 pub use fretboard_shape::{FretboardShape, ChordShapeClassification};
+pub use fretboard_shape::caged::{CagedForm, is_standard_tuning};
+pub use fretboard_shape::open_chords::{open_chord_shape, OPEN_CHORDS};
+pub use fretboard_shape::ranking::{ShapeRanker, RankingCriterion, RankableShape};
+pub use fretboard_shape::search_options::{SearchOptions, CancellationToken};
+pub use fretboard_shape::stitching::{ScaleSystem, SystemNote, SystemPosition};
+pub use fingering::{plan_fingering, Fingering, FingeredNote, PositionShift, ShiftKind};
 pub use fretted_note::{SoundedNote, FrettedNote};
+pub use grid::FretboardGrid;
+pub use fretted_instrument::{FrettedInstrument, DiatonicFretboard, MOUNTAIN_DULCIMER};
+pub use neck_map::{NeckMap, NeckCell};
+pub use tuning_comparison::{TuningReport, compare_chord_across_tunings, compare_scale_across_tunings};
 use crate::error::MusicSemanticsError;
 
 // TODO Add more such common guitar tunings as a convenience.
 /// Standard tuning on a 6-string guitar.
 pub static STD_6STR_GTR: Lazy<Fretboard> = Lazy::new(|| {
-    Fretboard {
-        open_strings: vec![
-            Pitch::new(Note::E, 3).unwrap(),
-            Pitch::new(Note::A, 3).unwrap(),
-            Pitch::new(Note::D, 4).unwrap(),
-            Pitch::new(Note::G, 4).unwrap(),
-            Pitch::new(Note::B, 4).unwrap(),
-            Pitch::new(Note::E, 5).unwrap(),
-        ],
-    }
+    Fretboard::new(vec![
+        Pitch::new(Note::E, 3).unwrap(),
+        Pitch::new(Note::A, 3).unwrap(),
+        Pitch::new(Note::D, 4).unwrap(),
+        Pitch::new(Note::G, 4).unwrap(),
+        Pitch::new(Note::B, 4).unwrap(),
+        Pitch::new(Note::E, 5).unwrap(),
+    ])
+});
+
+/// Standard tuning on a 4-string bass guitar.
+pub static STD_4STR_BASS: Lazy<Fretboard> = Lazy::new(|| {
+    Fretboard::new(vec![
+        Pitch::new(Note::E, 1).unwrap(),
+        Pitch::new(Note::A, 1).unwrap(),
+        Pitch::new(Note::D, 2).unwrap(),
+        Pitch::new(Note::G, 2).unwrap(),
+    ])
 });
 
 /// Represents a fretboard with any arbitrary tuning or number of strings.
@@ -33,6 +56,9 @@ pub struct Fretboard {
     /// Canonically, we use `open_strings[0]` to represent the thickest string
     /// on an instrument.
     pub open_strings: Vec<Pitch>,
+    /// The length of the strings in millimeters, from nut to saddle.
+    /// If present, playability is judged by physical stretch rather than fret count.
+    pub scale_length_mm: Option<f64>,
 }
 
 impl Fretboard {
@@ -43,6 +69,21 @@ impl Fretboard {
     const MAX: u8 = 35;
     const OPEN: u8 = u8::MIN;
 
+    /// A fretboard with a given tuning, and no scale length.
+    pub fn new(open_strings: Vec<Pitch>) -> Self {
+        Self {
+            open_strings,
+            scale_length_mm: None,
+        }
+    }
+
+    /// Sets the scale length of the fretboard in millimeters, e.g. 648mm
+    /// for a typical steel-string guitar, or 650mm for a classical guitar.
+    pub fn with_scale_length(mut self, mm: f64) -> Self {
+        self.scale_length_mm = Some(mm);
+        self
+    }
+
     /// The number of strings on the fretboard.
     pub fn num_strings(&self) -> u8 {
         u8::try_from(self.open_strings.len()).unwrap()
@@ -52,39 +93,33 @@ impl Fretboard {
     /// It is important to remember that colloquially, the thickest string on a guitar
     /// is "the 6th string", but it is indexed here as `self.open_strings[0]`.
     pub fn get_string(&self, string: u8) -> Result<&Pitch, MusicSemanticsError> {
-        Ok(self.open_strings.get(string as usize)
-            .ok_or(MusicSemanticsError::StringTooHighForFretboard(string, self.clone()))?
-        )
+        self.open_strings.get(string as usize)
+            .ok_or(MusicSemanticsError::StringTooHighForFretboard(string, self.clone()))
     }
 
     /// This is the preferred way to create a [SoundedNote] instance, because it
     /// validates the initialization parameters against [self].
-    pub fn sounded_note(&self, string: u8, fret: u8) -> Result<SoundedNote, MusicSemanticsError> {
+    pub fn sounded_note(&self, string: u8, fret: u8) -> Result<SoundedNote<'_>, MusicSemanticsError> {
         if fret > Self::MAX {
             return Err(MusicSemanticsError::FretTooHigh(fret));
         }
         let open_string = self.get_string(string)?;
-        if fret == Self::OPEN {
-            return Ok(SoundedNote {
-                string,
-                fret: Self::OPEN,
-                pitch: open_string.clone(),
-                fretboard: self,
-            });
-        }
-        let pitch =
-            open_string.at_distance_from(fret as isize)?;
-        return Ok(SoundedNote {
+        let pitch = if fret == Self::OPEN {
+            *open_string
+        } else {
+            open_string.at_distance_from(fret as isize)?
+        };
+        Ok(SoundedNote {
             string,
-            fret: fret,
-            pitch: pitch,
+            fret,
+            pitch,
             fretboard: self,
-        });
+        })
     }
 
     /// Given a string and target note, returns the first available
     /// [SoundedNote] whose fret equals a given [Note].
-    pub fn note_on_string(&self, note: &Note, string: u8) -> Result<SoundedNote, MusicSemanticsError> {
+    pub fn note_on_string(&self, note: &Note, string: u8) -> Result<SoundedNote<'_>, MusicSemanticsError> {
         let fret = self.which_fret(note, string)?;
         self.sounded_note(string, fret)
     }
@@ -106,6 +141,58 @@ impl Fretboard {
     }
 }
 
+impl FrettedInstrument for Fretboard {
+    fn open_strings(&self) -> &[Pitch] {
+        &self.open_strings
+    }
+
+    fn max_fret(&self) -> u8 {
+        Self::MAX
+    }
+
+    fn scale_length_mm(&self) -> Option<f64> {
+        self.scale_length_mm
+    }
+
+    /// Every fret on a [Fretboard] is a semitone apart.
+    fn fret_to_semitones(&self, _string: u8, fret: u8) -> Result<u8, MusicSemanticsError> {
+        Ok(fret)
+    }
+
+    fn num_strings(&self) -> u8 {
+        Fretboard::num_strings(self)
+    }
+
+    fn get_string(&self, string: u8) -> Result<&Pitch, MusicSemanticsError> {
+        Fretboard::get_string(self, string)
+    }
+
+    fn sounded_note(&self, string: u8, fret: u8) -> Result<SoundedNote<'_>, MusicSemanticsError> {
+        Fretboard::sounded_note(self, string, fret)
+    }
+
+    fn which_fret(&self, note: &Note, string: u8) -> Result<u8, MusicSemanticsError> {
+        Fretboard::which_fret(self, note, string)
+    }
+
+    fn note_on_string(&self, note: &Note, string: u8) -> Result<SoundedNote<'_>, MusicSemanticsError> {
+        Fretboard::note_on_string(self, note, string)
+    }
+
+    /// On a chromatic fretboard, the fret is simply the distance from the open string.
+    fn fret_for_pitch(&self, string: u8, pitch: &Pitch) -> Result<u8, MusicSemanticsError> {
+        let open_string = self.get_string(string)?;
+        if pitch.midi_note < open_string.midi_note {
+            return Err(MusicSemanticsError::FretBelowZero(*pitch, *open_string));
+        }
+        let fret = pitch.midi_note - open_string.midi_note;
+        if fret > Self::MAX {
+            return Err(MusicSemanticsError::FretTooHigh(fret));
+        }
+        Ok(fret)
+    }
+}
+
 impl Deref for Fretboard {
     type Target = Vec<Pitch>;
 
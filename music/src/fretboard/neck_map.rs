@@ -0,0 +1,128 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::chord_tones::Degree;
+use crate::note_collections::spelling::HasSpelling;
+
+/// A fret on a string that sounds one of the notes of a [NeckMap].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NeckCell {
+    /// The sounded pitch, spelled as in the [NoteSet].
+    pub pitch: Pitch,
+    /// The degree of the note above the root of the [NoteSet].
+    pub degree: Degree,
+    /// The number of semitones from the root up to the note.
+    pub semitones: u8,
+}
+
+/// Every place on the neck, from the open strings up to some fret, where
+/// the notes of a [NoteSet] can be played. The first note of the set is taken as its root.
+///
+/// Unlike the shape searches, this says nothing about fingering, and is cheap to build.
+/// It's meant for painting whole-neck scale maps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeckMap {
+    /// Indexed by string, as in [FrettedInstrument::open_strings], and then by fret.
+    cells: Vec<Vec<Option<NeckCell>>>,
+}
+
+impl NeckMap {
+    /// Maps `notes` onto every string of `fretboard`, from the open string up to `max_fret`.
+    /// Frets past the end of the fretboard are left off.
+    pub fn new<F: FrettedInstrument>(
+        fretboard: &F,
+        notes: &NoteSet,
+        max_fret: u8,
+    ) -> Result<Self, MusicSemanticsError> {
+        let spellings: Vec<Note> = notes.to_vec();
+        let root = spellings.first();
+        let max_fret = max_fret.min(fretboard.max_fret());
+        let cells = (0..fretboard.num_strings())
+            .map(|string| (0..=max_fret)
+                .map(|fret| {
+                    let pitch = fretboard.pitch_at(string, fret)?;
+                    let (Some(root), Ok(pitch)) = (root, pitch.spelled_as_in(&spellings)) else {
+                        return Ok(None);
+                    };
+                    Ok(Some(NeckCell {
+                        pitch,
+                        degree: Degree::between(root, &pitch.note),
+                        semitones: Pc::from(root).distance_up_to(&Pc::from(&pitch.note)),
+                    }))
+                })
+                .collect::<Result<Vec<_>, MusicSemanticsError>>())
+            .collect::<Result<Vec<_>, MusicSemanticsError>>()?;
+        Ok(Self { cells })
+    }
+
+    pub fn num_strings(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The number of frets mapped on each string, including the open string.
+    pub fn num_frets(&self) -> usize {
+        self.cells.first().map_or(0, Vec::len)
+    }
+
+    /// The cell at a given string and fret, if it holds a note of the set.
+    pub fn get(&self, string: u8, fret: u8) -> Option<&NeckCell> {
+        self.cells.get(usize::from(string))?.get(usize::from(fret))?.as_ref()
+    }
+
+    /// The cells of one string, from the open string up.
+    pub fn string(&self, string: u8) -> Option<&[Option<NeckCell>]> {
+        self.cells.get(usize::from(string)).map(Vec::as_slice)
+    }
+
+    /// How many notes of the set lie at each fret, across all strings.
+    pub fn fret_density(&self) -> Vec<usize> {
+        (0..self.num_frets())
+            .map(|fret| self.cells.iter()
+                .filter(|string| string[fret].is_some())
+                .count())
+            .collect()
+    }
+
+    /// How many notes of the set lie within reach of a hand positioned at each fret,
+    /// i.e. the frets from there up to `span` frets higher.
+    pub fn position_density(&self, span: u8) -> Vec<usize> {
+        let density = self.fret_density();
+        (0..density.len())
+            .map(|fret| density[fret..].iter().take(usize::from(span) + 1).sum())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+
+    #[test]
+    fn c_major_neck_map() {
+        let c_major = NoteSet::new(
+            vec![Note::C, Note::D, Note::E, Note::F, Note::G, Note::A, Note::B],
+            None,
+        );
+        let map = NeckMap::new(&*STD_6STR_GTR, &c_major, 12).unwrap();
+        assert_eq!(map.num_strings(), 6);
+        assert_eq!(map.num_frets(), 13);
+        // The low E string, open, is the third of C.
+        let open_e = map.get(0, 0).unwrap();
+        assert_eq!(open_e.degree.to_string(), "3");
+        assert_eq!(open_e.semitones, 4);
+        // F# isn't in the scale.
+        assert!(map.get(0, 2).is_none());
+        assert_eq!(map.fret_density()[0], 6);
+        assert_eq!(map.fret_density()[1], 3);
+        assert_eq!(map.position_density(3)[0], 6 + 3 + 3 + 5);
+
+        // Spelling follows the set.
+        let e_flat = NoteSet::new(vec![Note::Ees, Note::G, Note::Bes], None);
+        let map = NeckMap::new(&*STD_6STR_GTR, &e_flat, 12).unwrap();
+        assert_eq!(map.get(1, 1).unwrap().pitch.note, Note::Bes);
+    }
+}
@@ -0,0 +1,142 @@
+use itertools::Itertools;
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::chord_shape_search::find_chord_shapes;
+use crate::fretboard::fretboard_shape::melodic_shape_search::find_all_scale_shapes;
+use crate::fretboard::fretted_note::FrettedNote;
+use crate::note_collections::NoteSet;
+
+/// A summary of how well a chord or scale lies on one particular tuning.
+/// Meant to be compared side by side with the reports for other tunings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningReport<'a> {
+    /// The fretboard whose tuning was searched.
+    pub fretboard: &'a Fretboard,
+    /// The number of playable shapes found.
+    pub num_shapes: usize,
+    /// The lowest (i.e. best) cost of any playable shape, or `None` if nothing was playable.
+    /// For chords, this is the fret span of the fretted notes. For scales,
+    /// it is the score assigned by the melodic shape search.
+    pub best_score: Option<usize>,
+    /// How many of the playable shapes make use of at least one open string.
+    pub open_string_shapes: usize,
+    /// The distinct positions (i.e. lowest frets) at which a playable shape is available,
+    /// sorted from low to high.
+    pub positions: Vec<u8>,
+}
+
+/// Searches for chord shapes of the same chord on each tuning, and produces
+/// a [TuningReport] for each, in the same order as `tunings`.
+///
+/// Shapes classified as playable, as having wide intervals, or as relying on open strings
+/// are all counted as playable.
+pub fn compare_chord_across_tunings<'a>(
+    chord: &NoteSet,
+    tunings: &'a [Fretboard],
+) -> Result<Vec<TuningReport<'a>>, MusicSemanticsError> {
+    tunings.iter()
+        .map(|fretboard| {
+            let result = find_chord_shapes(chord, fretboard)?;
+            let shapes: Vec<&FretboardShape> = result.playable.values()
+                .chain(result.wide_intervals.values())
+                .chain(result.nontransposable.values())
+                .flatten()
+                .collect();
+            Ok(TuningReport {
+                fretboard,
+                num_shapes: shapes.len(),
+                best_score: shapes.iter().map(|shape| chord_shape_cost(shape)).min(),
+                open_string_shapes: shapes.iter()
+                    .filter(|shape| shape.contains_open_strings())
+                    .count(),
+                positions: shapes.iter()
+                    .map(|shape| shape.span().0)
+                    .sorted()
+                    .dedup()
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Searches for melodic shapes of the same scale on each tuning, and produces
+/// a [TuningReport] for each, in the same order as `tunings`.
+pub fn compare_scale_across_tunings<'a>(
+    scale: &NoteSet,
+    tunings: &'a [Fretboard],
+) -> Vec<TuningReport<'a>> {
+    tunings.iter()
+        .map(|fretboard| {
+            let result = find_all_scale_shapes(scale, fretboard);
+            let shapes: Vec<_> = result.values().flatten().collect();
+            TuningReport {
+                fretboard,
+                num_shapes: shapes.len(),
+                best_score: shapes.iter().map(|shape| shape.score).min(),
+                open_string_shapes: shapes.iter()
+                    .filter(|shape| shape.shape.iter().any(|note| note.fret == 0))
+                    .count(),
+                positions: shapes.iter()
+                    .map(|shape| shape.span().0)
+                    .sorted()
+                    .dedup()
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Open strings cost nothing to play, so the cost of a chord shape
+/// is the span of its fretted notes.
+fn chord_shape_cost(shape: &FretboardShape) -> usize {
+    let frets: Vec<u8> = shape.iter()
+        .filter_map(FrettedNote::fret)
+        .filter(|fret| *fret > 0)
+        .collect();
+    match (frets.iter().min(), frets.iter().max()) {
+        (Some(min), Some(max)) => (max - min) as usize,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::note::note::Note;
+    use crate::note::pitch::Pitch;
+
+    #[test]
+    fn chord_across_tunings() {
+        let open_e = Fretboard::new(vec![
+            Pitch::new(Note::E, 3).unwrap(),
+            Pitch::new(Note::B, 3).unwrap(),
+            Pitch::new(Note::E, 4).unwrap(),
+            Pitch::new(Note::Gis, 4).unwrap(),
+            Pitch::new(Note::B, 4).unwrap(),
+            Pitch::new(Note::E, 5).unwrap(),
+        ]);
+        let tunings = vec![STD_6STR_GTR.clone(), open_e];
+        let chord = NoteSet::new(vec![Note::E, Note::Gis, Note::B], None);
+        let reports = compare_chord_across_tunings(&chord, &tunings).unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].fretboard, &tunings[1]);
+        // Strumming the open strings of open E is as cheap as it gets.
+        assert_eq!(reports[1].best_score, Some(0));
+        assert!(reports.iter().all(|report| report.open_string_shapes > 0));
+        assert!(reports.iter().all(|report| report.positions.contains(&0)));
+    }
+
+    #[test]
+    fn scale_across_tunings() {
+        let scale = NoteSet::new(
+            vec![Note::C, Note::D, Note::E, Note::F, Note::G, Note::A, Note::B],
+            None,
+        );
+        let tunings = vec![STD_6STR_GTR.clone()];
+        let reports = compare_scale_across_tunings(&scale, &tunings);
+        assert!(reports[0].num_shapes > 0);
+        assert!(reports[0].best_score.is_some());
+    }
+}
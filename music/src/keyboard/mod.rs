@@ -0,0 +1,128 @@
+use once_cell::sync::Lazy;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note_collections::voicing::Voicing;
+
+/// A standard 88-key piano, A0 to C8, with a hand that comfortably spans an octave.
+pub static STD_88_KEY_PIANO: Lazy<Keyboard> = Lazy::new(|| {
+    Keyboard {
+        lowest: Pitch::new(Note::A, 0).unwrap(),
+        highest: Pitch::new(Note::C, 8).unwrap(),
+        reach: Keyboard::OCTAVE_SPAN,
+    }
+});
+
+/// Represents a keyboard instrument of arbitrary range, along with the
+/// reach of the hands that play it.
+///
+/// This is the keyboard counterpart to a [crate::fretboard::Fretboard]. Where a fretboard
+/// constrains a [Voicing] by strings and frets, a keyboard constrains it by
+/// how many keys a single hand can cover at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyboard {
+    /// The lowest key on the instrument.
+    pub lowest: Pitch,
+    /// The highest key on the instrument.
+    pub highest: Pitch,
+    /// The widest distance in keys (i.e. semitones) that a single hand can cover,
+    /// measured from its lowest to highest note. An octave is a sensible default,
+    /// though many players can reach a ninth or tenth.
+    pub reach: u8,
+}
+
+impl Keyboard {
+    /// A hand spanning an octave, from thumb to pinky.
+    pub const OCTAVE_SPAN: u8 = 12;
+    /// One note per finger.
+    pub const MAX_NOTES_PER_HAND: usize = 5;
+
+    /// Whether a [Pitch] has a key on this instrument.
+    pub fn contains(&self, pitch: &Pitch) -> bool {
+        self.lowest.midi_note <= pitch.midi_note && pitch.midi_note <= self.highest.midi_note
+    }
+
+    /// The distance in keys between the lowest and highest notes of a [Voicing].
+    /// Returns `None` for an empty voicing. A hand can cover it if it's no more than [Keyboard::reach].
+    pub fn hand_span(&self, voicing: &Voicing) -> Option<u8> {
+        voicing.span().map(|(low, high)| high.midi_note - low.midi_note)
+    }
+
+    /// Whether a single hand can play the entire [Voicing] at once, which requires that
+    /// every note is on the keyboard, that there's a finger for each note,
+    /// and that the voicing fits under the hand.
+    pub fn is_playable_by_one_hand(&self, voicing: &Voicing) -> bool {
+        if voicing.len() > Self::MAX_NOTES_PER_HAND
+            || !voicing.iter().all(|pitch| self.contains(pitch)) {
+            return false;
+        }
+        self.hand_span(voicing).unwrap_or(0) <= self.reach
+    }
+
+    /// Suggests how to divide a [Voicing] between the left and right hands.
+    ///
+    /// Since a voicing is sorted low to high, every candidate split gives the left hand
+    /// some bottom portion of the voicing and the right hand the rest.
+    /// We prefer the split whose wider hand has the smallest span, and break ties by
+    /// distributing the notes as evenly as possible.
+    /// Either hand may be left empty, if the other can play the voicing alone.
+    ///
+    /// Returns `None` if no split is playable.
+    pub fn suggest_hand_split(&self, voicing: &Voicing) -> Option<HandSplit> {
+        (0..=voicing.len())
+            .map(|i| HandSplit {
+                left: Voicing::new(voicing[..i].to_vec()),
+                right: Voicing::new(voicing[i..].to_vec()),
+            })
+            .filter(|split| self.is_playable_by_one_hand(&split.left)
+                && self.is_playable_by_one_hand(&split.right)
+            )
+            .min_by_key(|split| {
+                let widest = self.hand_span(&split.left).unwrap_or(0)
+                    .max(self.hand_span(&split.right).unwrap_or(0));
+                let imbalance = split.left.len().abs_diff(split.right.len());
+                (widest, imbalance)
+            })
+    }
+}
+
+/// A [Voicing] divided between two hands on a [Keyboard].
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandSplit {
+    /// The notes played by the left hand, which are the lowest of the voicing.
+    pub left: Voicing,
+    /// The notes played by the right hand.
+    pub right: Voicing,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pitch, voicing};
+
+    #[test]
+    fn one_hand_playability() {
+        let keyboard = &*STD_88_KEY_PIANO;
+        let close = voicing!(pitch!(c, 4), pitch!(e, 4), pitch!(g, 4), pitch!(c, 5));
+        assert_eq!(keyboard.hand_span(&close), Some(12));
+        assert!(keyboard.is_playable_by_one_hand(&close));
+        let open = voicing!(pitch!(c, 4), pitch!(g, 4), pitch!(e, 5));
+        assert!(!keyboard.is_playable_by_one_hand(&open));
+        let off_the_keyboard = voicing!(pitch!(c, 0));
+        assert!(!keyboard.is_playable_by_one_hand(&off_the_keyboard));
+    }
+
+    #[test]
+    fn hand_splits() {
+        let keyboard = &*STD_88_KEY_PIANO;
+        // A spread voicing splits into a shell in the left hand, and upper structure in the right.
+        let spread = voicing!(
+            pitch!(c, 3), pitch!(bes, 3), pitch!(e, 4), pitch!(a, 4), pitch!(d, 5)
+        );
+        let split = keyboard.suggest_hand_split(&spread).unwrap();
+        assert_eq!(split.left, voicing!(pitch!(c, 3), pitch!(bes, 3)));
+        assert_eq!(split.right, voicing!(pitch!(e, 4), pitch!(a, 4), pitch!(d, 5)));
+        // Three notes two octaves apart leave one hand with too wide a stretch.
+        let too_wide = voicing!(pitch!(c, 2), pitch!(c, 4), pitch!(c, 6));
+        assert_eq!(keyboard.suggest_hand_split(&too_wide), None);
+    }
+}
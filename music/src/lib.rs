@@ -1,13 +1,39 @@
-#![feature(concat_idents)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+compile_error!("Either the `std` or the `alloc` feature must be enabled.");
+
+extern crate alloc;
+// The tests print as they go, which needs the standard library even when the crate doesn't.
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+mod prelude;
 pub mod note_collections;
 pub mod note;
+#[cfg(feature = "std")]
 pub mod fretboard;
+#[cfg(feature = "std")]
+pub mod keyboard;
+#[cfg(feature = "std")]
+pub mod progression;
 pub mod error;
+pub mod encoding;
 pub mod notation;
+#[cfg(feature = "osc")]
+pub mod osc;
+#[cfg(feature = "python")]
+mod python;
 
 pub use note::{Note, Pitch, Pc, Spelling};
 pub use note_collections::*;
+#[cfg(feature = "std")]
 pub use fretboard::*;
+#[cfg(feature = "std")]
+pub use keyboard::*;
+#[cfg(feature = "std")]
+pub use progression::*;
 
 pub mod common_chords {
     //use super::*;
@@ -0,0 +1,63 @@
+//! Writing text given by users, e.g. titles, lyrics and the names of sections, into the
+//! documents of each output format, so that it's shown as it's given however it's punctuated,
+//! and can't be read as part of the document around it. Each format gets its own function here.
+use crate::prelude::*;
+
+/// `text` as a Lilypond string, in quotes, with any quotes or backslashes escaped.
+/// Line breaks and other control characters, which would end a header field or lyric early,
+/// are written as spaces.
+pub fn lilypond_string(text: &str) -> String {
+    let mut string = String::with_capacity(text.len() + 2);
+    string.push('"');
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                string.push('\\');
+                string.push(c);
+            },
+            c if c.is_control() => string.push(' '),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads a Lilypond string back, returning it along with whatever follows it.
+    fn read_lilypond_string(document: &str) -> Option<(String, &str)> {
+        let mut chars = document.strip_prefix('"')?.char_indices();
+        let mut string = String::new();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => string.push(chars.next()?.1),
+                '"' => return Some((string, &document[i + 2..])),
+                c => string.push(c),
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn hostile_lilypond_strings() {
+        let hostile = [
+            "Blue in \"Green\"",
+            "\" } \\score { c'1 } \\header { title = \"",
+            "C:\\Users\\",
+            "\\",
+            "\"",
+            "#(system \"rm -rf ~\")",
+            "$(ly:parser-include-string \"\")",
+            "{ } << >> %{ % ~ _",
+            "",
+        ];
+        for text in hostile {
+            let string = lilypond_string(text);
+            assert_eq!(read_lilypond_string(&string), Some((text.to_string(), "")), "{}", string);
+        }
+        assert_eq!(lilypond_string("Verse\n1\t\"A\""), "\"Verse 1 \\\"A\\\"\"");
+    }
+}
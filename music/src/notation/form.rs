@@ -0,0 +1,187 @@
+use crate::prelude::*;
+use crate::error::MusicSemanticsError;
+
+/// A stretch of music, with the name it's marked with in the score, if any, e.g. "A" or "Verse".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section<T> {
+    pub name: Option<String>,
+    pub content: Vec<T>,
+}
+
+impl<T> Section<T> {
+    pub fn new(content: Vec<T>) -> Self {
+        Self { name: None, content }
+    }
+
+    pub fn named(name: &str, content: Vec<T>) -> Self {
+        Self { name: Some(name.to_string()), content }
+    }
+}
+
+/// One part of a [Form], as it's written in the score.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormElement<T> {
+    /// Played straight through.
+    Section(Section<T>),
+    /// Played `times` times through, followed each time by one of its `endings`, or voltas, if it has any.
+    /// With fewer endings than passes, the earliest passes share the first ending, as in Lilypond.
+    Repeat {
+        body: Section<T>,
+        times: usize,
+        endings: Vec<Vec<T>>,
+    },
+    /// The sign that [FormElement::DalSegnoAlCoda] goes back to.
+    Segno,
+    /// Where, after going back to the [FormElement::Segno], the music skips ahead to the [FormElement::Coda].
+    ToCoda,
+    /// Where the coda begins.
+    Coda,
+    /// D.S. al Coda: go back to the [FormElement::Segno], and play on to the [FormElement::ToCoda].
+    DalSegnoAlCoda,
+}
+
+/// The structure of a piece, as it's written in the score: its sections, repeats and
+/// their endings, and any D.S. al Coda, e.g. to be engraved, or [Form::expand]ed to be played back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form<T> {
+    pub elements: Vec<FormElement<T>>,
+}
+
+impl<T> Form<T> {
+    pub fn new() -> Self {
+        Self { elements: vec![] }
+    }
+
+    pub fn section(mut self, section: Section<T>) -> Self {
+        self.elements.push(FormElement::Section(section));
+        self
+    }
+
+    pub fn repeat(mut self, body: Section<T>, times: usize, endings: Vec<Vec<T>>) -> Self {
+        self.elements.push(FormElement::Repeat { body, times, endings });
+        self
+    }
+
+    pub fn segno(mut self) -> Self {
+        self.elements.push(FormElement::Segno);
+        self
+    }
+
+    pub fn to_coda(mut self) -> Self {
+        self.elements.push(FormElement::ToCoda);
+        self
+    }
+
+    pub fn coda(mut self) -> Self {
+        self.elements.push(FormElement::Coda);
+        self
+    }
+
+    pub fn dal_segno_al_coda(mut self) -> Self {
+        self.elements.push(FormElement::DalSegnoAlCoda);
+        self
+    }
+
+    /// Everything in the form, once each, in the order it's written, e.g. to set lyrics to.
+    pub fn written(&self) -> Vec<&T> {
+        self.elements.iter()
+            .flat_map(|element| -> Vec<&T> {
+                match element {
+                    FormElement::Section(section) => section.content.iter().collect(),
+                    FormElement::Repeat { body, endings, .. } => body.content.iter()
+                        .chain(endings.iter().flatten())
+                        .collect(),
+                    _ => vec![],
+                }
+            })
+            .collect()
+    }
+}
+
+impl<T: Clone> Form<T> {
+    /// Everything in the form in the order it's played, with its repeats and jumps taken,
+    /// e.g. to be played back as MIDI.
+    ///
+    /// As is the convention, after going back to the [FormElement::Segno], each repeat is
+    /// played only once, with its last ending. Errors with [MusicSemanticsError::InvalidForm]
+    /// if a jump has nowhere to go, or a repeat has more endings than passes.
+    pub fn expand(&self) -> Result<Vec<T>, MusicSemanticsError> {
+        let mut played = vec![];
+        let mut segno = None;
+        let mut jumped = false;
+        let mut i = 0;
+        while i < self.elements.len() {
+            match &self.elements[i] {
+                FormElement::Section(section) => played.extend(section.content.iter().cloned()),
+                FormElement::Repeat { body, times, endings } => {
+                    if *times == 0 || endings.len() > *times {
+                        return Err(MusicSemanticsError::InvalidForm(format!(
+                            "a repeat played {} times can't have {} endings", times, endings.len(),
+                        )));
+                    }
+                    let passes = if jumped { *times - 1..*times } else { 0..*times };
+                    for pass in passes {
+                        played.extend(body.content.iter().cloned());
+                        // The earliest passes share the first ending.
+                        if let Some(ending) = endings.get(pass.saturating_sub(times - endings.len())) {
+                            played.extend(ending.iter().cloned());
+                        }
+                    }
+                },
+                FormElement::Segno => segno = Some(i),
+                FormElement::ToCoda if jumped => {
+                    i = self.elements.iter()
+                        .skip(i)
+                        .position(|element| matches!(element, FormElement::Coda))
+                        .map(|offset| i + offset)
+                        .ok_or(MusicSemanticsError::InvalidForm("there's no coda to skip to".to_string()))?;
+                },
+                FormElement::DalSegnoAlCoda if !jumped => {
+                    i = segno.ok_or(MusicSemanticsError::InvalidForm("there's no segno to go back to".to_string()))?;
+                    jumped = true;
+                },
+                FormElement::ToCoda | FormElement::Coda | FormElement::DalSegnoAlCoda => {},
+            }
+            i += 1;
+        }
+        Ok(played)
+    }
+}
+
+impl<T> Default for Form<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expanded_forms() {
+        // An intro, a verse repeated with first and second endings, a bridge,
+        // and then D.S. al Coda back to the verse, skipping from the bridge to the coda.
+        let form = Form::new()
+            .section(Section::named("Intro", vec!["i"]))
+            .segno()
+            .repeat(Section::named("Verse", vec!["v"]), 2, vec![vec!["1st"], vec!["2nd"]])
+            .section(Section::new(vec!["b"]))
+            .to_coda()
+            .section(Section::new(vec!["b2"]))
+            .dal_segno_al_coda()
+            .coda()
+            .section(Section::named("Coda", vec!["c"]));
+        assert_eq!(form.expand().unwrap(), vec!["i", "v", "1st", "v", "2nd", "b", "b2", "v", "2nd", "b", "c"]);
+        assert_eq!(form.written().into_iter().copied().collect::<Vec<_>>(), vec!["i", "v", "1st", "2nd", "b", "b2", "c"]);
+
+        // Three times through, with two endings, the first of which is taken twice.
+        let triple = Form::new().repeat(Section::new(vec!["a"]), 3, vec![vec!["x"], vec!["y"]]);
+        assert_eq!(triple.expand().unwrap(), vec!["a", "x", "a", "x", "a", "y"]);
+        assert_eq!(Form::new().repeat(Section::new(vec!["a"]), 2, vec![]).expand().unwrap(), vec!["a", "a"]);
+
+        assert!(Form::new().repeat(Section::new(vec!["a"]), 1, vec![vec!["x"], vec!["y"]]).expand().is_err());
+        assert!(Form::<&str>::new().dal_segno_al_coda().expand().is_err());
+        assert!(Form::<&str>::new().segno().to_coda().dal_segno_al_coda().expand().is_err());
+    }
+}
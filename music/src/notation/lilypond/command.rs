@@ -36,7 +36,7 @@ impl<'a> LilypondCmdBuilder<'a> {
     }
 
     pub fn builder(mut self, builder: LilypondBuilder<'a>) -> Self {
-        self.files.push(LilypondFile::Virtual(builder));
+        self.files.push(LilypondFile::Virtual(Box::new(builder)));
         self
     }
 
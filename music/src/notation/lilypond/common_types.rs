@@ -2,9 +2,14 @@ use itertools::Itertools;
 use crate::notation::lilypond::ToLilypondString;
 use crate::{Note, Pitch, Spelling, Voicing};
 use crate::notation::clef::Clef;
+use crate::notation::escape::lilypond_string;
 use crate::notation::rhythm::duration::{Duration, DurationKind};
 use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent};
+use crate::notation::rhythm::expression::{Articulation, Dynamic, Expression, Hairpin, Phrasing, Technique};
+use crate::notation::rhythm::lyrics::{LyricPlacement, Syllable};
 use crate::notation::rhythm::meter::Meter;
+use crate::notation::rhythm::percussion::{PercussionEvent, PercussionSound};
+use crate::notation::rhythm::tempo::{Tempo, TempoMap};
 use crate::note::pitch::MIDDLE_C;
 use crate::note::spelling::Accidental;
 
@@ -15,29 +20,42 @@ impl ToLilypondString for Meter {
     }
 }
 
-/// The only tricky conversion here is the double-whole note `\breve`,
-/// otherwise everything converts to the integer string value you'd expect.
+/// A metronome mark, e.g. `\tempo "Allegro" 4 = 120`, which also sets the tempo of any MIDI output.
+impl ToLilypondString for Tempo {
+    fn to_lilypond_string(&self) -> String {
+        let text = self.text.as_ref()
+            .map(|text| format!("{} ", lilypond_string(text)))
+            .unwrap_or_default();
+        format!("\\tempo {}{} = {}", text, self.beat.lilypond_token(), self.per_minute)
+    }
+}
+
+/// Each metronome mark, with skips between them, to be written alongside the music.
+impl ToLilypondString for TempoMap {
+    fn to_lilypond_string(&self) -> String {
+        let mut at = 0;
+        self.tempos.iter()
+            .flat_map(|(start, tempo)| {
+                let skips = Duration::from_ticks(start.saturating_sub(at)).into_iter()
+                    .map(|skip| format!("\\skip {}", skip.lilypond_token()));
+                at = at.max(*start);
+                skips.chain([tempo.to_lilypond_string()]).collect::<Vec<_>>()
+            })
+            .join(" ")
+    }
+}
+
+/// See [DurationKind::lilypond_token].
 impl ToLilypondString for DurationKind {
     fn to_lilypond_string(&self) -> String {
-        match &self {
-            DurationKind::Breve => "\\breve",
-            DurationKind::Whole => "1",
-            DurationKind::Half => "2",
-            DurationKind::Qtr => "4",
-            DurationKind::Eighth => "8",
-            DurationKind::Sixteenth => "16",
-            DurationKind::ThirtySecond => "32",
-            DurationKind::SixtyFourth => "64",
-            DurationKind::OneTwentyEighth => "128",
-        }.to_string()
+        self.lilypond_token()
     }
 }
 
+/// See [Duration::lilypond_token].
 impl ToLilypondString for Duration {
     fn to_lilypond_string(&self) -> String {
-        let kind = self.kind().to_lilypond_string();
-        let dots = ".".repeat(self.num_dots() as usize);
-        format!("{}{}", kind, dots)
+        self.lilypond_token()
     }
 }
 
@@ -99,6 +117,151 @@ impl ToLilypondString for Voicing {
     }
 }
 
+impl ToLilypondString for Articulation {
+    fn to_lilypond_string(&self) -> String {
+        match &self {
+            Articulation::Staccato => "-.",
+            Articulation::Accent => "->",
+            Articulation::Tenuto => "--",
+        }.to_string()
+    }
+}
+
+impl ToLilypondString for Dynamic {
+    fn to_lilypond_string(&self) -> String {
+        format!("\\{}", self.marking())
+    }
+}
+
+impl ToLilypondString for Hairpin {
+    fn to_lilypond_string(&self) -> String {
+        match &self {
+            Hairpin::Crescendo => "\\<",
+            Hairpin::Decrescendo => "\\>",
+            Hairpin::End => "\\!",
+        }.to_string()
+    }
+}
+
+impl ToLilypondString for Phrasing {
+    fn to_lilypond_string(&self) -> String {
+        match &self {
+            Phrasing::SlurStart => "(",
+            Phrasing::SlurEnd => ")",
+            Phrasing::PhraseStart => "\\(",
+            Phrasing::PhraseEnd => "\\)",
+        }.to_string()
+    }
+}
+
+/// How far a string is bent, in whole tones, as it's written over the note, e.g. "full" or "1 1/2".
+fn bend_amount(semitones: u8) -> String {
+    match (semitones / 2, semitones % 2) {
+        (0, 0) => "0".to_string(),
+        (0, _) => "1/2".to_string(),
+        (1, 0) => "full".to_string(),
+        (tones, 0) => tones.to_string(),
+        (tones, _) => format!("{} 1/2", tones),
+    }
+}
+
+/// Hammer-ons and pull-offs are left to the voice, which slurs together the notes they join.
+/// A bend is written over the note with how far the string is bent, as in tab.
+impl ToLilypondString for Technique {
+    fn to_lilypond_string(&self) -> String {
+        match &self {
+            Technique::HammerOn | Technique::PullOff => "".to_string(),
+            Technique::Slide => "\\glissando".to_string(),
+            Technique::Bend { semitones } => format!("^\"{}\"", bend_amount(*semitones)),
+            Technique::Vibrato => "^\"vib.\"".to_string(),
+            Technique::PalmMute => "^\"P.M.\"".to_string(),
+        }
+    }
+}
+
+/// Post-event markings, written directly after the duration of a note, chord, or rest.
+impl ToLilypondString for Expression {
+    fn to_lilypond_string(&self) -> String {
+        let articulations = self.articulations.iter()
+            .map(|articulation| articulation.to_lilypond_string());
+        let dynamic = self.dynamic.iter()
+            .map(|dynamic| dynamic.to_lilypond_string());
+        let hairpin = self.hairpin.iter()
+            .map(|hairpin| hairpin.to_lilypond_string());
+        let phrasing = self.phrasing.iter()
+            .map(|phrasing| phrasing.to_lilypond_string());
+        let techniques = self.techniques.iter()
+            .map(|technique| technique.to_lilypond_string());
+        articulations.chain(dynamic).chain(hairpin).chain(phrasing).chain(techniques).join("")
+    }
+}
+
+/// Syllables are quoted unless they're plain words, so that punctuation and digits survive.
+impl ToLilypondString for Syllable {
+    fn to_lilypond_string(&self) -> String {
+        let is_plain = !self.text.is_empty() && self.text.chars().all(|c| c.is_alphabetic() || c == '\'');
+        let mut syllable = if is_plain {
+            self.text.clone()
+        } else {
+            lilypond_string(&self.text)
+        };
+        if self.hyphenated {
+            syllable.push_str(" --");
+        }
+        if self.melisma > 0 {
+            syllable.push_str(" __");
+        }
+        syllable
+    }
+}
+
+/// A lyric block to follow a staff. Every note is given a syllable explicitly,
+/// with `_` holding a syllable over tied notes and melismas, so Lilypond's own
+/// melisma detection is switched off.
+impl<'l> ToLilypondString for Vec<LyricPlacement<'l>> {
+    fn to_lilypond_string(&self) -> String {
+        let syllables = self.iter()
+            .filter_map(|placement| match placement {
+                LyricPlacement::Syllable(syllable) => Some(syllable.to_lilypond_string()),
+                LyricPlacement::Continuation => Some("_".to_string()),
+                LyricPlacement::Rest => None,
+            })
+            .join(" ");
+        format!("\\addlyrics {{ \\set ignoreMelismata = ##t {} }}", syllables)
+    }
+}
+
+/// The names of drum sounds in Lilypond's `\drummode`.
+impl ToLilypondString for PercussionSound {
+    fn to_lilypond_string(&self) -> String {
+        match &self {
+            PercussionSound::Kick => "bd",
+            PercussionSound::Snare => "sn",
+            PercussionSound::ClosedHiHat => "hhc",
+            PercussionSound::ClickStrong => "wbh",
+            PercussionSound::ClickWeak => "wbl",
+        }.to_string()
+    }
+}
+
+/// Sounds struck together are written as a chord, and accents are marked.
+impl ToLilypondString for PercussionEvent {
+    fn to_lilypond_string(&self) -> String {
+        let duration = self.duration.to_lilypond_string();
+        let accent = if self.accent { "->" } else { "" };
+        match self.sounds.as_slice() {
+            [] => format!("r{}", duration),
+            [sound] => format!("{}{}{}", sound.to_lilypond_string(), duration, accent),
+            sounds => format!(
+                "<{}>{}{}",
+                sounds.iter().map(|sound| sound.to_lilypond_string()).join(" "),
+                duration,
+                accent,
+            ),
+        }
+    }
+}
+
 /// This is where the duration and content are combined into an element
 /// that can be rendered by Lilypond.
 impl<'a> ToLilypondString for RhythmicNotatedEvent<'a> {
@@ -106,7 +269,8 @@ impl<'a> ToLilypondString for RhythmicNotatedEvent<'a> {
         match &self.event {
             NotatedEvent::SingleEvent(event, duration) => {
                 let duration = duration.to_lilypond_string();
-                match event {
+                let expression = self.expression.to_lilypond_string();
+                let event = match event {
                     SingleEvent::Pitch(p) => {
                         format!("{}{}", p.to_lilypond_string(), duration)
                     }
@@ -129,7 +293,8 @@ impl<'a> ToLilypondString for RhythmicNotatedEvent<'a> {
                     SingleEvent::Rest => {
                         format!("r{}", duration)
                     }
-                }
+                };
+                format!("{}{}", event, expression)
             }
             NotatedEvent::Tuplet(tuplet) => {
                 let ratio = format!("{}/{}", tuplet.numerator, tuplet.denominator);
@@ -141,4 +306,129 @@ impl<'a> ToLilypondString for RhythmicNotatedEvent<'a> {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ly_expression() {
+        let event = RhythmicNotatedEvent::pitch(Pitch::new(Note::C, 4).unwrap(), Duration::EIGHTH)
+            .with_articulation(Articulation::Accent)
+            .with_articulation(Articulation::Staccato)
+            .with_dynamic(Dynamic::FF)
+            .with_hairpin(Hairpin::Decrescendo)
+            .with_phrasing(Phrasing::SlurStart);
+        assert_eq!(event.to_lilypond_string(), "c'8->-.\\ff\\>(");
+        let rest = RhythmicNotatedEvent::rest(Duration::QTR).with_hairpin(Hairpin::End);
+        assert_eq!(rest.to_lilypond_string(), "r4\\!");
+    }
+
+    #[test]
+    fn ly_techniques() {
+        use crate::fretboard::STD_6STR_GTR;
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+        let note = |fret| STD_6STR_GTR.sounded_note(3, fret).unwrap();
+        let voice: Vec<LilypondVoiceElement> = vec![
+            RhythmicNotatedEvent::fretted(note(5), Duration::EIGHTH).with_technique(Technique::HammerOn).into(),
+            RhythmicNotatedEvent::fretted(note(7), Duration::EIGHTH).with_technique(Technique::PullOff).into(),
+            RhythmicNotatedEvent::fretted(note(5), Duration::EIGHTH).with_technique(Technique::Slide).into(),
+            RhythmicNotatedEvent::fretted(note(7), Duration::QTR)
+                .with_technique(Technique::Bend { semitones: 2 })
+                .with_technique(Technique::Vibrato)
+                .into(),
+        ];
+        assert!(voice.to_lilypond_string()
+            .contains("c''8\\3( d''8\\3 c''8\\3\\glissando) d''4\\3^\"full\"^\"vib.\""));
+        let bend = |semitones| Technique::Bend { semitones }.to_lilypond_string();
+        assert_eq!(bend(1), "^\"1/2\"");
+        assert_eq!(bend(3), "^\"1 1/2\"");
+        assert_eq!(bend(4), "^\"2\"");
+    }
+
+    #[test]
+    fn ly_unfinished_legato() {
+        use crate::fretboard::STD_6STR_GTR;
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+        let note = |fret| RhythmicNotatedEvent::fretted(STD_6STR_GTR.sounded_note(3, fret).unwrap(), Duration::EIGHTH);
+        // The voice ends part way through the hammer-ons, so the slur ends with it.
+        let voice: Vec<LilypondVoiceElement> = vec![
+            note(5).with_technique(Technique::HammerOn).into(),
+            note(7).with_technique(Technique::HammerOn).into(),
+        ];
+        assert!(voice.to_lilypond_string().contains("c''8\\3( d''8\\3)\n"));
+        // A hammer-on from the last note has nothing to slur to.
+        let voice: Vec<LilypondVoiceElement> = vec![
+            note(2).into(),
+            note(5).with_technique(Technique::HammerOn).into(),
+        ];
+        assert!(voice.to_lilypond_string().contains("a'8\\3 c''8\\3\n"));
+    }
+
+    #[test]
+    fn ly_forms() {
+        use crate::notation::form::{Form, Section};
+        let note = |note| RhythmicNotatedEvent::pitch(Pitch::new(note, 4).unwrap(), Duration::HALF);
+        let form = Form::new()
+            .segno()
+            .repeat(Section::named("A", vec![note(Note::C)]), 2, vec![vec![note(Note::D)], vec![note(Note::E)]])
+            .to_coda()
+            .section(Section::new(vec![note(Note::F)]))
+            .dal_segno_al_coda()
+            .coda()
+            .section(Section::named("Coda", vec![note(Note::G)]));
+        assert_eq!(
+            form.to_lilypond_string(),
+            "\\mark \\markup { \\musicglyph #\"scripts.segno\" } \\mark \\markup \\box \"A\" \\repeat volta 2 { c'2 } \\alternative { { d'2 } { e'2 } } \\mark \\markup { \"To Coda\" \\musicglyph #\"scripts.coda\" } f'2 \\mark \"D.S. al Coda\" \\mark \\markup { \\musicglyph #\"scripts.coda\" } \\mark \\markup \\box \"Coda\" g'2",
+        );
+        let played: Vec<String> = form.expand().unwrap().iter().map(|event| event.to_lilypond_string()).collect();
+        assert_eq!(played.join(" "), "c'2 d'2 c'2 e'2 f'2 c'2 e'2 g'2");
+        let named = Form::new().section(Section::named("\"Verse\" \\", vec![note(Note::C)]));
+        assert_eq!(named.to_lilypond_string(), "\\mark \\markup \\box \"\\\"Verse\\\" \\\\\" c'2");
+    }
+
+    #[test]
+    fn ly_polyphonic_staff() {
+        use crate::notation::lilypond::document::staff::LilypondStaff;
+        use crate::notation::lilypond::document::tab_staff::LilypondTabStaff;
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+        let voice = |note, octave| -> Vec<LilypondVoiceElement> {
+            vec![RhythmicNotatedEvent::pitch(Pitch::new(note, octave).unwrap(), Duration::WHOLE).into()]
+        };
+        // A melody, stems up, over a bass line, stems down.
+        let staff = LilypondStaff::new().add_voice(voice(Note::E, 4)).add_voice(voice(Note::C, 3)).to_lilypond_string();
+        assert!(staff.contains("\\new Voice {\n          \\voiceOne e'1\n        }"));
+        assert!(staff.contains("\\new Voice {\n          \\voiceTwo c1\n        }"));
+        let tab = LilypondTabStaff::new().add_voice(voice(Note::E, 4)).add_voice(voice(Note::C, 3)).to_lilypond_string();
+        assert!(tab.contains("\\new TabVoice {\n          \\voiceTwo c1"));
+        // A voice alone keeps its stems as Lilypond would have them.
+        let staff = LilypondStaff::new().add_voice(voice(Note::E, 4)).to_lilypond_string();
+        assert!(staff.contains("\\new Voice {\n          e'1\n        }"));
+    }
+
+    #[test]
+    fn ly_lyrics() {
+        use crate::notation::rhythm::lyrics::Lyrics;
+        let c = Pitch::new(Note::C, 4).unwrap();
+        let events = vec![
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::rest(Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch_tied(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+        ];
+        let lyrics = Lyrics::parse("Hel -- lo, world _");
+        assert_eq!(
+            lyrics.align(&events).unwrap().to_lilypond_string(),
+            "\\addlyrics { \\set ignoreMelismata = ##t Hel -- \"lo,\" world __ _ _ }",
+        );
+        // Quotes, backslashes and braces in a syllable can't end it, or the lyrics, early.
+        let hostile = Lyrics::parse("\"} \\score");
+        assert_eq!(
+            hostile.align(&events[..2]).unwrap().to_lilypond_string(),
+            "\\addlyrics { \\set ignoreMelismata = ##t \"\\\"}\" \"\\\\score\" }",
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use itertools::Itertools;
+use tera::Context;
+use crate::notation::lilypond::error::LilypondError;
+use crate::notation::lilypond::templates::{render_template, OMIT_BAR_NUMBER};
+use crate::notation::lilypond::ToLilypondString;
+use crate::notation::rhythm::percussion::PercussionPattern;
+
+/// For engraving a [PercussionPattern] on a drum staff, with its time signature.
+pub struct LilypondDrumStaff<'p> {
+    pattern: &'p PercussionPattern,
+    show_bar_numbers: bool,
+}
+
+impl<'p> LilypondDrumStaff<'p> {
+    pub fn new(pattern: &'p PercussionPattern) -> Self {
+        Self {
+            pattern,
+            show_bar_numbers: false,
+        }
+    }
+
+    /// Show or hide bar numbers above the staff's barlines.
+    pub fn bar_numbers(mut self, show: bool) -> Self {
+        self.show_bar_numbers = show;
+        self
+    }
+}
+
+impl<'p> ToLilypondString for LilypondDrumStaff<'p> {
+    fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
+        let mut ctx = Context::new();
+        let time_sig = format!("\\time {}", self.pattern.meter.to_lilypond_string());
+        let mut statements = vec![time_sig.as_str()];
+        if !self.show_bar_numbers {
+            statements.push(OMIT_BAR_NUMBER)
+        }
+        ctx.insert("statements", &statements);
+        let content = self.pattern.events.iter()
+            .map(|event| event.to_lilypond_string())
+            .join(" ");
+        ctx.insert("content", &content);
+        render_template("drum_staff", &ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+    use crate::notation::rhythm::percussion::PercussionStyle;
+
+    #[test]
+    fn ly_drum_staff() {
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let pattern = PercussionPattern::new(&meter, PercussionStyle::Kit, 1);
+        let staff = LilypondDrumStaff::new(&pattern).to_lilypond_string();
+        assert!(staff.contains("\\new DrumStaff \\drummode {"));
+        assert!(staff.contains("\\time 4/4"));
+        assert!(staff.contains("<bd hhc>4-> <sn hhc>4 <bd hhc>4-> <sn hhc>4"));
+    }
+}
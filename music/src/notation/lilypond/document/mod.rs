@@ -1,3 +1,5 @@
+pub mod drum_staff;
+pub mod paper;
 pub mod score;
 pub mod staff;
 pub mod tab_staff;
@@ -6,17 +8,20 @@ use std::path::PathBuf;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use tera::Context;
+use paper::LilypondPaper;
 use score::LilypondScore;
+use crate::notation::escape::lilypond_string;
 use crate::notation::lilypond::document::score::LilypondLayout;
 use crate::notation::lilypond::error::LilypondError;
-use crate::notation::lilypond::templates::TEMPLATE_ENGINE;
+use crate::notation::lilypond::format::{format_lilypond, LilypondFormat};
+use crate::notation::lilypond::templates::render_template;
 use crate::notation::lilypond::ToLilypondString;
 
 /// Either a pre-existing lilypond source file,
 /// or one defined in Rust code with a [LilypondBuilder].
 pub enum LilypondFile<'a> {
     Preexisting(PathBuf),
-    Virtual(LilypondBuilder<'a>),
+    Virtual(Box<LilypondBuilder<'a>>),
 }
 
 /// Builder for a Lilypond document.
@@ -24,10 +29,11 @@ pub struct LilypondBuilder<'a> {
     path: Option<PathBuf>,
     includes: Vec<LilypondInclude>,
     header: Option<LilypondHeader>,
+    paper: Option<LilypondPaper>,
     layout: Vec<LilypondLayout>,
     score: Option<LilypondScore<'a>>,
+    format: LilypondFormat,
     //version: String, // default "2.22.2"
-    // TODO page and paper block
 }
 
 impl<'a> LilypondBuilder<'a> {
@@ -36,8 +42,10 @@ impl<'a> LilypondBuilder<'a> {
             path: None,
             includes: vec![],
             header: None,
+            paper: None,
             layout: vec![],
             score: None,
+            format: LilypondFormat::default(),
         }
     }
 
@@ -55,15 +63,37 @@ impl<'a> LilypondBuilder<'a> {
         &self.path
     }
 
+    pub fn header(mut self, header: Option<LilypondHeader>) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn paper(mut self, paper: Option<LilypondPaper>) -> Self {
+        self.paper = paper;
+        self
+    }
+
+    /// Adds a top-level layout block, which applies to every score in the document.
+    pub fn layout(mut self, layout: LilypondLayout) -> Self {
+        self.layout.push(layout);
+        self
+    }
+
     pub fn score(mut self, score: Option<LilypondScore<'a>>) -> Self {
         self.score = score;
         self
     }
 
+    /// How the document is laid out, see [LilypondFormat].
+    pub fn format(mut self, format: LilypondFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     pub fn write_to_file(&self) -> Result<(), LilypondError> {
         let path = self.path.as_ref().ok_or(LilypondError::DocumentHasNoPath)?;
         let path = path.to_str().unwrap();
-        std::fs::write(&path, self.to_lilypond_string())
+        std::fs::write(&path, self.try_to_lilypond_string()?)
             .map_err(|e| LilypondError::DocumentWriteFailure(e))?;
         Ok(())
     }
@@ -71,29 +101,42 @@ impl<'a> LilypondBuilder<'a> {
 
 impl<'a> ToLilypondString for LilypondBuilder<'a> {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let mut content = self.includes.iter()
             .map(|include| include.to_lilypond_string())
             .join("\n");
         if let Some(header) = &self.header {
             content.push('\n');
-            content = content + &header.to_lilypond_string();
+            content = content + &header.try_to_lilypond_string()?;
+        }
+        if let Some(paper) = &self.paper {
+            content.push('\n');
+            content = content + &paper.try_to_lilypond_string()?;
         }
         for layout in &self.layout {
             content.push('\n');
-            content = content + &layout.to_lilypond_string();
+            content = content + &layout.try_to_lilypond_string()?;
         }
         if let Some(score) = &self.score {
             content.push('\n');
-            content = content + &score.to_lilypond_string();
+            content = content + &score.try_to_lilypond_string()?;
         }
-        content
+        Ok(format_lilypond(&content, self.format))
     }
 }
 
 /// A top-level block that defines title, composer, and tagline.
+/// Each field is written as a string, so it's shown as it's given, quotes and backslashes included.
 pub struct LilypondHeader {
     title: Option<String>,
+    subtitle: Option<String>,
     composer: Option<String>,
+    arranger: Option<String>,
+    opus: Option<String>,
+    copyright: Option<String>,
     tagline: Option<String>,
 }
 
@@ -101,7 +144,11 @@ impl LilypondHeader {
     pub fn new() -> Self {
         Self {
             title: None,
+            subtitle: None,
             composer: None,
+            arranger: None,
+            opus: None,
+            copyright: None,
             tagline: None
         }
     }
@@ -111,11 +158,32 @@ impl LilypondHeader {
         self
     }
 
+    pub fn subtitle(mut self, subtitle: Option<String>) -> Self {
+        self.subtitle = subtitle;
+        self
+    }
+
     pub fn composer(mut self, composer: Option<String>) -> Self {
         self.composer = composer;
         self
     }
 
+    pub fn arranger(mut self, arranger: Option<String>) -> Self {
+        self.arranger = arranger;
+        self
+    }
+
+    pub fn opus(mut self, opus: Option<String>) -> Self {
+        self.opus = opus;
+        self
+    }
+
+    pub fn copyright(mut self, copyright: Option<String>) -> Self {
+        self.copyright = copyright;
+        self
+    }
+
+    /// Without a tagline, Lilypond's own is left out.
     pub fn tagline(mut self, tagline: Option<String>) -> Self {
         self.tagline = tagline;
         self
@@ -124,21 +192,25 @@ impl LilypondHeader {
 
 impl ToLilypondString for LilypondHeader {
     fn to_lilypond_string(&self) -> String {
-        let mut content = "".to_string();
-        if let Some(title) = &self.title {
-            content = content + &format!("  title = {}", title);
-        }
-        if let Some(composer) = &self.composer {
-            content = content + &format!("  composer = {}", composer);
-        }
-        if let Some(tagline) = &self.tagline {
-            content = content + &format!("  tagline = {}", tagline);
-        } else {
-            content = content + "  tagline = \"\"";
-        }
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
+        let fields = [
+            ("title", &self.title),
+            ("subtitle", &self.subtitle),
+            ("composer", &self.composer),
+            ("arranger", &self.arranger),
+            ("opus", &self.opus),
+            ("copyright", &self.copyright),
+        ];
+        let mut content = fields.iter()
+            .filter_map(|(name, value)| value.as_ref().map(|value| format!("  {} = {}\n", name, lilypond_string(value))))
+            .join("");
+        content = content + &format!("  tagline = {}\n", lilypond_string(self.tagline.as_deref().unwrap_or("")));
         let mut ctx = Context::new();
         ctx.insert("content", &content);
-        (*TEMPLATE_ENGINE).render("header", &ctx).unwrap()
+        render_template("header", &ctx)
     }
 }
 
@@ -147,7 +219,7 @@ pub struct LilypondInclude(PathBuf);
 
 impl ToLilypondString for LilypondInclude {
     fn to_lilypond_string(&self) -> String {
-        format!("\\include {}\n", &self.0.display())
+        format!("\\include {}\n", lilypond_string(&self.0.display().to_string()))
     }
 }
 
@@ -166,3 +238,26 @@ impl<'a> TryInto<LilypondInclude> for &LilypondBuilder<'a> {
 pub static LILYPOND_BOOK_PREAMBLE: Lazy<LilypondInclude> = Lazy::new(|| {
     LilypondInclude(PathBuf::from("lilypond-book-preamble.ly"))
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::lilypond::document::score::LilypondStaffGroup;
+    use crate::notation::lilypond::document::staff::LilypondStaff;
+
+    #[test]
+    fn ly_document_format() {
+        let document = |format| LilypondBuilder::new()
+            .header(Some(LilypondHeader::new().title(Some("Study".to_string()))))
+            .score(Some(LilypondScore::new().staff_group(LilypondStaffGroup::new(vec![LilypondStaff::new()]))))
+            .format(format)
+            .to_lilypond_string();
+        let pretty = document(LilypondFormat::Pretty);
+        assert!(pretty.starts_with("\\header {\n  title = \"Study\"\n  tagline = \"\"\n}\n\n\\score {\n  <<\n    \\new Staff {\n      \\omit Staff.Clef\n"));
+        assert!(pretty.ends_with("    }\n  >>\n}\n"));
+        assert_eq!(pretty, document(LilypondFormat::Pretty));
+        let compact = document(LilypondFormat::Compact);
+        assert!(compact.lines().all(|line| !line.is_empty() && !line.starts_with(' ')));
+        assert_eq!(compact, format_lilypond(&pretty, LilypondFormat::Compact));
+    }
+}
@@ -0,0 +1,187 @@
+use tera::Context;
+use crate::notation::lilypond::error::LilypondError;
+use crate::notation::lilypond::templates::render_template;
+use crate::notation::lilypond::ToLilypondString;
+
+/// The paper sizes Lilypond knows by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperSize {
+    A3,
+    A4,
+    A5,
+    Letter,
+    Legal,
+    Tabloid,
+}
+
+impl ToLilypondString for PaperSize {
+    fn to_lilypond_string(&self) -> String {
+        match self {
+            PaperSize::A3 => "a3",
+            PaperSize::A4 => "a4",
+            PaperSize::A5 => "a5",
+            PaperSize::Letter => "letter",
+            PaperSize::Legal => "legal",
+            PaperSize::Tabloid => "tabloid",
+        }.to_string()
+    }
+}
+
+/// A top-level block that sets the size and margins of the page, and how the systems are laid out on it.
+/// Margins and the indent are in millimetres. Anything left unset is left to Lilypond's defaults.
+#[derive(Default)]
+pub struct LilypondPaper {
+    size: Option<PaperSize>,
+    landscape: bool,
+    top_margin: Option<f64>,
+    bottom_margin: Option<f64>,
+    left_margin: Option<f64>,
+    right_margin: Option<f64>,
+    indent: Option<f64>,
+    system_spacing: Option<f64>,
+    ragged_last: bool,
+}
+
+impl LilypondPaper {
+    pub fn new() -> Self {
+        Self {
+            size: None,
+            landscape: false,
+            top_margin: None,
+            bottom_margin: None,
+            left_margin: None,
+            right_margin: None,
+            indent: None,
+            system_spacing: None,
+            ragged_last: false,
+        }
+    }
+
+    pub fn size(mut self, size: Option<PaperSize>) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Turn the page on its side. Only applies with a [LilypondPaper::size].
+    pub fn landscape(mut self, landscape: bool) -> Self {
+        self.landscape = landscape;
+        self
+    }
+
+    pub fn top_margin(mut self, margin: Option<f64>) -> Self {
+        self.top_margin = margin;
+        self
+    }
+
+    pub fn bottom_margin(mut self, margin: Option<f64>) -> Self {
+        self.bottom_margin = margin;
+        self
+    }
+
+    pub fn left_margin(mut self, margin: Option<f64>) -> Self {
+        self.left_margin = margin;
+        self
+    }
+
+    pub fn right_margin(mut self, margin: Option<f64>) -> Self {
+        self.right_margin = margin;
+        self
+    }
+
+    /// How far the first system is indented, e.g. 0 to line it up with the rest.
+    pub fn indent(mut self, indent: Option<f64>) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// The least space between systems, in staff spaces.
+    pub fn system_spacing(mut self, padding: Option<f64>) -> Self {
+        self.system_spacing = padding;
+        self
+    }
+
+    /// Let the last system end short of the right margin, rather than being stretched across the page.
+    pub fn ragged_last(mut self, ragged_last: bool) -> Self {
+        self.ragged_last = ragged_last;
+        self
+    }
+}
+
+impl ToLilypondString for LilypondPaper {
+    fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
+        let mut statements: Vec<String> = vec![];
+        if let Some(size) = &self.size {
+            let orientation = if self.landscape { " 'landscape" } else { "" };
+            statements.push(format!("#(set-paper-size \"{}\"{})", size.to_lilypond_string(), orientation));
+        }
+        let lengths = [
+            ("top-margin", self.top_margin),
+            ("bottom-margin", self.bottom_margin),
+            ("left-margin", self.left_margin),
+            ("right-margin", self.right_margin),
+            ("indent", self.indent),
+        ];
+        for (name, length) in lengths {
+            if let Some(length) = length {
+                statements.push(format!("{} = {}\\mm", name, length));
+            }
+        }
+        if let Some(padding) = self.system_spacing {
+            statements.push(format!("system-system-spacing.padding = #{}", padding));
+        }
+        if self.ragged_last {
+            statements.push("ragged-last = ##t".to_string());
+        }
+        let mut ctx = Context::new();
+        ctx.insert("statements", &statements);
+        render_template("paper", &ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::lilypond::document::{LilypondBuilder, LilypondHeader};
+
+    #[test]
+    fn ly_paper_and_header() {
+        let paper = LilypondPaper::new()
+            .size(Some(PaperSize::Letter))
+            .landscape(true)
+            .top_margin(Some(15.0))
+            .left_margin(Some(12.5))
+            .indent(Some(0.0))
+            .system_spacing(Some(4.0))
+            .ragged_last(true);
+        let header = LilypondHeader::new()
+            .title(Some("Blue in \"Green\"".to_string()))
+            .subtitle(Some("for solo guitar".to_string()))
+            .opus(Some("Op. 1".to_string()))
+            .arranger(Some("A. N. Other".to_string()))
+            .copyright(Some("\\copyright".to_string()));
+        let document = LilypondBuilder::new()
+            .header(Some(header))
+            .paper(Some(paper))
+            .to_lilypond_string();
+        assert!(document.contains("\\paper {"));
+        assert!(document.contains("#(set-paper-size \"letter\" 'landscape)"));
+        assert!(document.contains("top-margin = 15\\mm"));
+        assert!(document.contains("left-margin = 12.5\\mm"));
+        assert!(document.contains("indent = 0\\mm"));
+        assert!(!document.contains("bottom-margin"));
+        assert!(document.contains("system-system-spacing.padding = #4"));
+        assert!(document.contains("ragged-last = ##t"));
+        assert!(document.contains("  title = \"Blue in \\\"Green\\\"\"\n"));
+        assert!(document.contains("  subtitle = \"for solo guitar\"\n"));
+        assert!(document.contains("  opus = \"Op. 1\"\n"));
+        assert!(document.contains("  arranger = \"A. N. Other\"\n"));
+        assert!(document.contains("  copyright = \"\\\\copyright\"\n"));
+        assert!(document.contains("  tagline = \"\"\n"));
+        // The header comes before the paper block.
+        assert!(document.find("\\header").unwrap() < document.find("\\paper").unwrap());
+    }
+}
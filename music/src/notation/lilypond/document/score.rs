@@ -1,13 +1,15 @@
-use itertools::Itertools;
 use tera::Context;
 use crate::notation::lilypond::document::staff::LilypondStaff;
+use crate::notation::lilypond::error::LilypondError;
 use crate::notation::lilypond::ToLilypondString;
-use crate::notation::lilypond::templates::TEMPLATE_ENGINE;
+use crate::notation::lilypond::templates::render_template;
+use crate::notation::rhythm::tempo::TempoMap;
 
 pub struct LilypondScore<'a> {
     staff_groups: Vec<LilypondStaffGroup<'a>>,
     layout: Option<LilypondLayout>,
-    // TODO midi block
+    tempo: Option<TempoMap>,
+    midi: bool,
 }
 
 impl<'a> LilypondScore<'a> {
@@ -15,9 +17,23 @@ impl<'a> LilypondScore<'a> {
         Self {
             staff_groups: vec![],
             layout: None,
+            tempo: None,
+            midi: false,
         }
     }
 
+    /// Metronome marks above the score, which also set the tempo of its MIDI output.
+    pub fn tempo(mut self, tempo: Option<TempoMap>) -> Self {
+        self.tempo = tempo;
+        self
+    }
+
+    /// Write MIDI as well as the engraved score when the document is compiled.
+    pub fn midi(mut self, midi: bool) -> Self {
+        self.midi = midi;
+        self
+    }
+
     pub fn layout(mut self, layout: Option<LilypondLayout>) -> Self {
         self.layout = layout;
         self
@@ -31,16 +47,30 @@ impl<'a> LilypondScore<'a> {
 
 impl<'a> ToLilypondString for LilypondScore<'a> {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let mut score_block = self.staff_groups.iter()
-            .map(|group| group.to_lilypond_string())
+            .map(|group| group.try_to_lilypond_string())
+            .collect::<Result<Vec<_>, LilypondError>>()?
             .join("\n");
+        if let Some(tempo) = &self.tempo {
+            score_block = format!("<<\n  \\new Dynamics {{ {} }}\n{}\n>>", tempo.to_lilypond_string(), score_block);
+        }
         if let Some(layout) = &self.layout {
             score_block.push('\n');
-            score_block = score_block + &layout.to_lilypond_string();
+            score_block = score_block + &layout.try_to_lilypond_string()?;
+        } else if self.midi {
+            // Without a layout block, a score with a MIDI block is only written as MIDI.
+            score_block.push_str("\n\\layout { }");
+        }
+        if self.midi {
+            score_block.push_str("\n\\midi { }");
         }
         let mut ctx = Context::new();
         ctx.insert("content", &score_block);
-        (*TEMPLATE_ENGINE).render("score", &ctx).unwrap()
+        render_template("score", &ctx)
     }
 }
 
@@ -55,10 +85,15 @@ impl<'a> LilypondStaffGroup<'a> {
 
 impl<'a> ToLilypondString for LilypondStaffGroup<'a> {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let staves = self.0.iter()
-            .map(|staff| staff.to_lilypond_string())
+            .map(|staff| staff.try_to_lilypond_string())
+            .collect::<Result<Vec<_>, LilypondError>>()?
             .join("\n");
-        format!("<<{}  >>", staves)
+        Ok(format!("<<{}  >>", staves))
     }
 }
 
@@ -88,6 +123,10 @@ impl LilypondLayout {
 
 impl ToLilypondString for LilypondLayout {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let mut statements: Vec<String> = vec![];
         let ragged_right = if self.ragged_right {
             "ragged-right = ##t"
@@ -95,12 +134,12 @@ impl ToLilypondString for LilypondLayout {
             "ragged-right = ##f"
         }.to_string();
         statements.push(ragged_right);
-        self.contexts.iter().for_each(|ctx| {
-            statements.push(ctx.to_lilypond_string());
-        });
+        for ctx in &self.contexts {
+            statements.push(ctx.try_to_lilypond_string()?);
+        }
         let mut ctx = Context::new();
         ctx.insert("statements", &statements);
-        (*TEMPLATE_ENGINE).render("layout", &ctx).unwrap()
+        render_template("layout", &ctx)
     }
 }
 
@@ -135,6 +174,8 @@ impl LilypondLayoutContext {
         self
     }
 
+    /// The statement is Lilypond, and is written as it's given. Any text from users in it
+    /// should be written with [crate::notation::escape::lilypond_string].
     pub fn add_statement(mut self, statement: String) -> Self {
         self.statements.push(statement);
         self
@@ -144,6 +185,10 @@ impl LilypondLayoutContext {
 
 impl ToLilypondString for LilypondLayoutContext {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let mut statements: Vec<String> = vec![];
         if let Some(ty) = &self.ty {
             statements.push(ty.to_lilypond_string())
@@ -157,7 +202,7 @@ impl ToLilypondString for LilypondLayoutContext {
         });
         let mut ctx = Context::new();
         ctx.insert("statements", &statements);
-        (*TEMPLATE_ENGINE).render("layout_context", &ctx).unwrap()
+        render_template("layout_context", &ctx)
     }
 }
 
@@ -174,4 +219,30 @@ impl ToLilypondString for LilypondLayoutContext {
   system-system-spacing = #'((padding . 4))
 }
 
- */
\ No newline at end of file
+ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::Duration;
+    use crate::notation::rhythm::tempo::Tempo;
+
+    #[test]
+    fn ly_tempo_and_midi() {
+        let bar = Duration::WHOLE.ticks();
+        let tempo = TempoMap::new(Tempo::new(Duration::QTR, 120).text("Allegro"))
+            .change(2 * bar + Duration::HALF.ticks(), Tempo::new(Duration::HALF, 40));
+        let score = LilypondScore::new()
+            .staff_group(LilypondStaffGroup::new(vec![LilypondStaff::new()]))
+            .tempo(Some(tempo))
+            .midi(true)
+            .to_lilypond_string();
+        assert!(score.contains("\\new Dynamics { \\tempo \"Allegro\" 4 = 120 \\skip \\breve \\skip 2 \\tempo 2 = 40 }"));
+        assert!(score.contains("\\new Staff"));
+        // Both engraved and played.
+        assert!(score.contains("\\layout { }"));
+        assert!(score.contains("\\midi { }"));
+
+        let engraved = LilypondScore::new().layout(Some(LilypondLayout::new())).to_lilypond_string();
+        assert!(!engraved.contains("\\midi") && !engraved.contains("\\layout { }"));
+    }
+}
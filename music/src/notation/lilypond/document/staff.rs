@@ -1,8 +1,12 @@
 use tera::Context;
 use crate::notation::clef::Clef;
-use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+use crate::notation::lilypond::staff_elements::{render_voices, LilypondVoiceElement};
+use crate::notation::lilypond::error::LilypondError;
 use crate::notation::lilypond::ToLilypondString;
-use crate::notation::lilypond::templates::{NO_AUTOMATIC_BAR_LINES, OMIT_BAR_NUMBER, OMIT_CLEF, OMIT_STRING_NUMBER, OMIT_TIME_SIGNATURE, TEMPLATE_ENGINE};
+use crate::notation::lilypond::templates::{render_template, NO_AUTOMATIC_BAR_LINES, OMIT_BAR_NUMBER, OMIT_CLEF, OMIT_STRING_NUMBER, OMIT_TIME_SIGNATURE};
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::lyrics::Lyrics;
 use crate::notation::rhythm::meter::Meter;
 
 pub struct LilypondStaff<'a> {
@@ -13,6 +17,8 @@ pub struct LilypondStaff<'a> {
     automatic_bar_lines: bool,
     /// Each voice is simply a `Vec<LilypondVoiceElement>`
     voices: Vec<Vec<LilypondVoiceElement<'a>>>,
+    /// Rendered `\addlyrics` blocks, one per verse.
+    lyrics: Vec<String>,
 }
 
 impl<'a> LilypondStaff<'a> {
@@ -23,15 +29,28 @@ impl<'a> LilypondStaff<'a> {
             show_bar_numbers: false,
             show_string_numbers: false,
             automatic_bar_lines: true,
-            voices: vec![]
+            voices: vec![],
+            lyrics: vec![],
         }
     }
 
+    /// Adds a voice to the staff. With more than one, the first is written with its stems up,
+    /// and the second down, e.g. for a melody over its accompaniment.
     pub fn add_voice(mut self, voice: Vec<LilypondVoiceElement<'a>>) -> Self {
         self.voices.push(voice);
         self
     }
 
+    /// Adds a verse of lyrics, sung to the first voice of the staff.
+    /// The voice must already have been added, and have enough notes for every syllable.
+    pub fn add_lyrics(mut self, lyrics: &Lyrics) -> Result<Self, MusicSemanticsError> {
+        let voice = self.voices.first().ok_or(MusicSemanticsError::UnalignedLyrics(lyrics.0.len()))?;
+        let events = written_events(voice.iter());
+        let verse = lyrics.align(&events)?.to_lilypond_string();
+        self.lyrics.push(verse);
+        Ok(self)
+    }
+
     pub fn clef(mut self, clef: Option<Clef>) -> Self {
         self.clef = clef;
         self
@@ -58,8 +77,23 @@ impl<'a> LilypondStaff<'a> {
     }
 }
 
+/// The notes, chords and rests of a voice, in the order they're written, as lyrics are sung to them.
+fn written_events<'e, 'a: 'e>(elements: impl Iterator<Item = &'e LilypondVoiceElement<'a>>) -> Vec<RhythmicNotatedEvent<'a>> {
+    elements
+        .flat_map(|element| match element {
+            LilypondVoiceElement::Common(event) => vec![event.clone()],
+            LilypondVoiceElement::Form(form) => written_events(form.written().into_iter()),
+            LilypondVoiceElement::Other(_) => vec![],
+        })
+        .collect()
+}
+
 impl<'a> ToLilypondString for LilypondStaff<'a> {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let mut ctx = Context::new();
         let mut statements = vec![];
         let clef = self.clef
@@ -86,10 +120,10 @@ impl<'a> ToLilypondString for LilypondStaff<'a> {
             statements.push(NO_AUTOMATIC_BAR_LINES)
         }
         ctx.insert("statements", &statements);
-        let voices = self.voices.iter()
-            .map(|voice| voice.to_lilypond_string())
-            .collect::<Vec<String>>();
+        let voices = render_voices(&self.voices)?;
         ctx.insert("voices", &voices);
-        (*TEMPLATE_ENGINE).render("staff", &ctx).unwrap()
+        let staff = render_template("staff", &ctx)?;
+        Ok(self.lyrics.iter()
+            .fold(staff, |staff, verse| format!("{}    {}\n", staff, verse)))
     }
 }
@@ -1,6 +1,7 @@
 use tera::Context;
-use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
-use crate::notation::lilypond::templates::{NO_AUTOMATIC_BAR_LINES, OMIT_BAR_NUMBER, OMIT_STRING_NUMBER, TEMPLATE_ENGINE};
+use crate::notation::lilypond::staff_elements::{render_voices, LilypondVoiceElement};
+use crate::notation::lilypond::error::LilypondError;
+use crate::notation::lilypond::templates::{render_template, NO_AUTOMATIC_BAR_LINES, OMIT_BAR_NUMBER, OMIT_STRING_NUMBER};
 use crate::notation::lilypond::ToLilypondString;
 
 /// For engraving tablature. This is meant to be populated with
@@ -28,6 +29,8 @@ impl<'a> LilypondTabStaff<'a> {
         }
     }
 
+    /// Adds a voice to the staff. With more than one, each is given its own stem direction,
+    /// see [crate::notation::lilypond::document::staff::LilypondStaff::add_voice].
     pub fn add_voice(mut self, voice: Vec<LilypondVoiceElement<'a>>) -> Self {
         self.voices.push(voice);
         self
@@ -62,6 +65,10 @@ impl<'a> LilypondTabStaff<'a> {
 
 impl<'a> ToLilypondString for LilypondTabStaff<'a> {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         let mut ctx = Context::new();
         let mut statements = vec![];
         if self.modern_tab_clef {
@@ -77,10 +84,10 @@ impl<'a> ToLilypondString for LilypondTabStaff<'a> {
             statements.push(NO_AUTOMATIC_BAR_LINES)
         }
         ctx.insert("statements", &statements);
-        let voices = self.voices.iter()
-            .map(|voice| voice.to_lilypond_string().replace("Voice", "TabVoice"))
+        let voices = render_voices(&self.voices)?.iter()
+            .map(|voice| voice.replace("Voice", "TabVoice"))
             .collect::<Vec<String>>();
         ctx.insert("voices", &voices);
-        (*TEMPLATE_ENGINE).render("tab_staff", &ctx).unwrap()
+        render_template("tab_staff", &ctx)
     }
 }
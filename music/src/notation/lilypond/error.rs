@@ -11,4 +11,10 @@ pub enum LilypondError {
     CompilationFailure(io::Error),
     #[error("Lilypond document does not exist: {0}")]
     DocumentDoesNotExist(String),
+    #[error("Invalid Lilypond template: {0}")]
+    InvalidTemplate(String),
+    #[error("Lilypond template {0} is missing {1} from its context")]
+    MissingTemplateContext(String, String),
+    #[error("Failed to render Lilypond template: {0}")]
+    TemplateRenderFailure(String),
 }
@@ -0,0 +1,105 @@
+/// How a [crate::notation::lilypond::document::LilypondBuilder] lays out the document it writes.
+///
+/// Either way, the same document is always written the same way: lines are kept as they're
+/// emitted, but with any trailing whitespace removed, and the document ends in a single newline,
+/// so that generated documents can be kept under version control and diffed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LilypondFormat {
+    /// Each line is indented two spaces for every `{` or `<<` it's inside of, with its closing
+    /// `}` or `>>` lined up with the line that opened it. Blank lines are removed, except for
+    /// one between each top-level block, e.g. the header and the score.
+    #[default]
+    Pretty,
+    /// No indentation, and no blank lines.
+    Compact,
+}
+
+const INDENT: &str = "  ";
+
+/// How many blocks a line opens and closes, and whether it starts by closing one,
+/// leaving out anything in strings or comments.
+fn block_changes(line: &str) -> (usize, usize, bool) {
+    let (mut opened, mut closed) = (0, 0);
+    let mut starts_closed = false;
+    let mut nothing_before = true;
+    let mut in_string = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let closes = match c {
+            '\\' if in_string => {
+                chars.next();
+                false
+            },
+            '"' => {
+                in_string = !in_string;
+                false
+            },
+            _ if in_string => false,
+            '%' => break,
+            // An escaped bracket, e.g. a hairpin, `\<`.
+            '\\' => {
+                chars.next_if(|next| matches!(next, '<' | '>' | '{' | '}'));
+                false
+            },
+            '{' => {
+                opened += 1;
+                false
+            },
+            '<' if chars.next_if_eq(&'<').is_some() => {
+                opened += 1;
+                false
+            },
+            '}' => true,
+            '>' => chars.next_if_eq(&'>').is_some(),
+            _ => false,
+        };
+        if closes {
+            starts_closed |= nothing_before;
+            closed += 1;
+        }
+        nothing_before &= c.is_whitespace();
+    }
+    (opened, closed, starts_closed)
+}
+
+/// Lays out a Lilypond document, see [LilypondFormat].
+pub fn format_lilypond(document: &str, format: LilypondFormat) -> String {
+    let mut formatted = String::new();
+    let mut depth: usize = 0;
+    for line in document.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (opened, closed, starts_closed) = block_changes(line);
+        if format == LilypondFormat::Pretty {
+            if depth == 0 && !formatted.is_empty() {
+                formatted.push('\n');
+            }
+            formatted.push_str(&INDENT.repeat(depth.saturating_sub(usize::from(starts_closed))));
+        }
+        formatted.push_str(line);
+        formatted.push('\n');
+        depth = (depth + opened).saturating_sub(closed);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatted_documents() {
+        let document = "\\version \"2.22.2\"   \n\n\\header {\n      title = \"{ Not << a block\"  \n}\n\\score {\n<<\n    \\new Staff {\n  c'4 % }\n  } >>\n\n  \\layout { }\n}";
+        let pretty = format_lilypond(document, LilypondFormat::Pretty);
+        assert_eq!(
+            pretty,
+            "\\version \"2.22.2\"\n\n\\header {\n  title = \"{ Not << a block\"\n}\n\n\\score {\n  <<\n    \\new Staff {\n      c'4 % }\n    } >>\n  \\layout { }\n}\n",
+        );
+        assert_eq!(format_lilypond(&pretty, LilypondFormat::Pretty), pretty);
+        assert_eq!(
+            format_lilypond(document, LilypondFormat::Compact),
+            "\\version \"2.22.2\"\n\\header {\ntitle = \"{ Not << a block\"\n}\n\\score {\n<<\n\\new Staff {\nc'4 % }\n} >>\n\\layout { }\n}\n",
+        );
+        // Hairpins and escaped quotes don't open or close anything.
+        assert_eq!(block_changes("c'4\\< d'4\\> e'4\\! \"\\\"}\""), (0, 0, false));
+        assert_eq!(block_changes("} >> { <<"), (2, 2, true));
+    }
+}
@@ -1,12 +1,18 @@
 use tera::Context;
 use serde::Serialize;
 use crate::{FretboardShape, FrettedNote};
-use crate::notation::lilypond::templates::TEMPLATE_ENGINE;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::chord_tones::chord_degrees;
+use crate::notation::lilypond::error::LilypondError;
+use crate::notation::lilypond::templates::render_template;
 
 #[derive(Debug, Serialize)]
 pub struct DiagramFret {
     string: u8,
     fret: u8,
+    /// Written inside the dot, for fretted notes.
+    label: Option<String>,
 }
 
 impl From<(u8, u8)> for DiagramFret {
@@ -14,25 +20,80 @@ impl From<(u8, u8)> for DiagramFret {
         Self {
             string: value.0,
             fret: value.1,
+            label: None,
         }
     }
 }
 
+impl From<(u8, u8, &str)> for DiagramFret {
+    fn from(value: (u8, u8, &str)) -> Self {
+        Self {
+            string: value.0,
+            fret: value.1,
+            label: Some(value.2.to_string()),
+        }
+    }
+}
+
+/// What to write on each dot of a diagram.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagramLabels {
+    #[default]
+    None,
+    /// The degree of the note above the root, e.g. "R", "3", "b7", or "9".
+    Degrees,
+    /// The name of the note, e.g. "Bb".
+    NoteNames,
+}
+
+/// Lilypond numbers the strings from one, at the highest, so the lowest of six is string 6.
+/// Muted strings are given the fret 255.
 impl<'a> Into<Vec<DiagramFret>> for &'a FretboardShape<'a> {
     fn into(self) -> Vec<DiagramFret> {
         self.iter().map(|note| {
             DiagramFret::from(match note {
-                FrettedNote::Sounded(s) => (s.string, s.fret),
-                FrettedNote::Muted {string, ..} => (*string, 255),
+                FrettedNote::Sounded(s) => (s.fretboard.num_strings() - s.string, s.fret),
+                FrettedNote::Muted { string, fretboard } => (fretboard.num_strings() - string, 255),
             })
         }).collect()
     }
 }
 
-pub fn fretboard_diagram(frets: Vec<DiagramFret>) -> String {
+/// The frets of a shape for a chord, whose first note is its root, labelled according to `labels`.
+/// The notes of the shape are spelled as in the chord, e.g. Bb rather than A# in C7.
+pub fn labelled_frets(shape: &FretboardShape, chord: &[Note], labels: DiagramLabels) -> Vec<DiagramFret> {
+    let notes: Vec<Note> = shape.iter()
+        .filter_map(|note| match note {
+            FrettedNote::Sounded(s) => Some(chord.iter()
+                .find(|note| Pc::from(*note) == Pc::from(&s.pitch.note))
+                .copied()
+                .unwrap_or(s.pitch.note)),
+            FrettedNote::Muted { .. } => None,
+        })
+        .collect();
+    let labels: Vec<Option<String>> = match (labels, chord.first()) {
+        (DiagramLabels::Degrees, Some(root)) => chord_degrees(root, &notes).iter()
+            .map(|degree| Some(degree.to_string()))
+            .collect(),
+        (DiagramLabels::NoteNames, _) => notes.iter()
+            .map(|note| Some(note.to_string()))
+            .collect(),
+        _ => vec![None; notes.len()],
+    };
+    let mut frets: Vec<DiagramFret> = shape.into();
+    frets.iter_mut()
+        .filter(|fret| fret.fret != 255)
+        .zip(labels)
+        .for_each(|(fret, label)| fret.label = label);
+    frets
+}
+
+pub fn fretboard_diagram(frets: Vec<DiagramFret>) -> Result<String, LilypondError> {
+    let labelled = frets.iter().any(|fret| fret.label.is_some());
     let mut ctx = Context::new();
     ctx.insert("frets", &frets);
-    (*TEMPLATE_ENGINE).render("fretboard_diagram", &ctx).unwrap()
+    ctx.insert("labelled", &labelled);
+    render_template("fretboard_diagram", &ctx)
 }
 
 #[cfg(test)]
@@ -50,7 +111,46 @@ mod tests {
             (1, 5).into(),
         ];
 
-        let result = fretboard_diagram(frets);
+        let result = fretboard_diagram(frets).unwrap();
         println!("{}", result);
     }
+
+    #[test]
+    fn labelled_diagram() {
+        use crate::fretboard::{Fretboard, STD_6STR_GTR};
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let fretted_notes = [None, Some(3), Some(2), Some(0), Some(1), Some(0)].iter()
+            .enumerate()
+            .map(|(string, fret)| match fret {
+                Some(fret) => FrettedNote::Sounded(fretboard.sounded_note(string as u8, *fret).unwrap()),
+                None => FrettedNote::Muted { string: string as u8, fretboard },
+            })
+            .collect();
+        let c_major = FretboardShape { fretted_notes, fretboard };
+        let frets = labelled_frets(&c_major, &[Note::C, Note::E, Note::G], DiagramLabels::Degrees);
+        let labels: Vec<_> = frets.iter().map(|fret| fret.label.as_deref()).collect();
+        assert_eq!(labels, vec![None, Some("R"), Some("3"), Some("5"), Some("R"), Some("3")]);
+        let result = fretboard_diagram(frets).unwrap();
+        assert!(result.contains("(finger-code . in-dot)"));
+        assert!(result.contains("(place-fret 4 2 \"3\")"));
+        assert!(result.contains("(mute 6)"));
+        assert!(result.contains("(open 1)"));
+        let frets = labelled_frets(&c_major, &[Note::C, Note::E, Note::G], DiagramLabels::NoteNames);
+        assert_eq!(frets[1].label.as_deref(), Some("C"));
+
+        // The shape spells the seventh of C7 as A#, but it's labelled as a flat seventh.
+        let fretted_notes = [None, Some(3), Some(2), Some(3), Some(1), None].iter()
+            .enumerate()
+            .map(|(string, fret)| match fret {
+                Some(fret) => FrettedNote::Sounded(fretboard.sounded_note(string as u8, *fret).unwrap()),
+                None => FrettedNote::Muted { string: string as u8, fretboard },
+            })
+            .collect();
+        let c7 = FretboardShape { fretted_notes, fretboard };
+        let chord = [Note::C, Note::E, Note::G, Note::Bes];
+        let labels: Vec<_> = labelled_frets(&c7, &chord, DiagramLabels::Degrees).into_iter().map(|fret| fret.label).collect();
+        assert_eq!(labels, vec![None, Some("R".to_string()), Some("3".to_string()), Some("b7".to_string()), Some("R".to_string()), None]);
+        let names = labelled_frets(&c7, &chord, DiagramLabels::NoteNames);
+        assert_eq!(names[3].label.as_deref(), Some("Bb"));
+    }
 }
@@ -2,6 +2,7 @@ pub mod scoring;
 pub mod staff_elements;
 pub mod templates;
 pub mod fretboard_diagram;
+pub mod format;
 pub mod command;
 pub mod document;
 pub mod common_types;
@@ -9,5 +10,12 @@ pub mod error;
 
 
 pub trait ToLilypondString {
+    /// Panics where [ToLilypondString::try_to_lilypond_string] would error.
     fn to_lilypond_string(&self) -> String;
+
+    /// Errors if a template fails to render, e.g. one registered with
+    /// [templates::register_template] in place of a built-in one.
+    fn try_to_lilypond_string(&self) -> Result<String, error::LilypondError> {
+        Ok(self.to_lilypond_string())
+    }
 }
@@ -1,5 +1,6 @@
 use tera::Context;
-use crate::notation::lilypond::templates::{OMIT_TIME_SIGNATURE, RAGGED_RIGHT, TEMPLATE_ENGINE};
+use crate::notation::lilypond::error::LilypondError;
+use crate::notation::lilypond::templates::{render_template, OMIT_TIME_SIGNATURE, RAGGED_RIGHT};
 
 /// Wrap content in a markup block
 pub fn markup(content: String) -> String {
@@ -7,7 +8,7 @@ pub fn markup(content: String) -> String {
 }
 
 /// Wrap content in a score block, optionally with ragged-right set to false.
-pub fn score(content: String, ragged_right: bool) -> String {
+pub fn score(content: String, ragged_right: bool) -> Result<String, LilypondError> {
     let format_block = if ragged_right {
         RAGGED_RIGHT
     } else {
@@ -16,7 +17,7 @@ pub fn score(content: String, ragged_right: bool) -> String {
     let mut ctx = Context::new();
     ctx.insert("content", &content);
     ctx.insert("format_block", format_block);
-    (*TEMPLATE_ENGINE).render("score", &ctx).unwrap()
+    render_template("score", &ctx)
 }
 
 /// Replace a time signature with an instruction to omit the time signature.
@@ -28,22 +29,25 @@ pub fn maybe_time_signature(sig: Option<String>) -> String {
     }
 }
 
-/// Wrap content in a staff block
-pub fn staff(content: String, time_signature: Option<String>) -> String {
-    let time_sig = maybe_time_signature(time_signature);
+/// The context of a staff template, with the content as its only voice.
+fn staff_context(content: String, time_signature: Option<String>) -> Result<Context, LilypondError> {
     let mut ctx = Context::new();
-    ctx.insert("time_signature", &time_sig);
-    ctx.insert("content", &content);
-    (*TEMPLATE_ENGINE).render("staff", &ctx).unwrap()
+    ctx.insert("statements", &[maybe_time_signature(time_signature)]);
+    let mut voice = Context::new();
+    voice.insert("content", &content);
+    ctx.insert("voices", &[render_template("voice", &voice)?]);
+    Ok(ctx)
+}
+
+/// Wrap content in a staff block
+pub fn staff(content: String, time_signature: Option<String>) -> Result<String, LilypondError> {
+    render_template("staff", &staff_context(content, time_signature)?)
 }
 
 /// Wrap content in a tab staff block
-pub fn tab_staff(content: String, time_signature: Option<String>) -> String {
-    let time_sig = maybe_time_signature(time_signature);
-    let mut ctx = Context::new();
-    ctx.insert("time_signature", &time_sig);
-    ctx.insert("content", &content);
-    (*TEMPLATE_ENGINE).render("tab_staff", &ctx).unwrap()
+pub fn tab_staff(content: String, time_signature: Option<String>) -> Result<String, LilypondError> {
+    let ctx = staff_context(content, time_signature)?;
+    render_template("tab_staff", &ctx).map(|staff| staff.replace("Voice", "TabVoice"))
 }
 
 #[cfg(test)]
@@ -55,11 +59,14 @@ mod tests {
         let result = staff(
             "c2 d e f g".to_string(),
             Some("3/4".to_string())
-        );
+        ).unwrap();
         let _result = score(
             result,
             true
-        );
+        ).unwrap();
         //println!("{}", result);
+        let tab = tab_staff("c2 d e f g".to_string(), None).unwrap();
+        assert!(tab.contains("\\new TabVoice {"));
+        assert!(tab.contains("\\omit Staff.TimeSignature"));
     }
 }
\ No newline at end of file
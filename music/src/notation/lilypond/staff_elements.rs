@@ -1,28 +1,94 @@
-use itertools::Itertools;
 use tera::Context;
-use crate::notation::lilypond::templates::TEMPLATE_ENGINE;
+use crate::notation::escape::lilypond_string;
+use crate::notation::form::{Form, FormElement};
+use crate::notation::lilypond::error::LilypondError;
+use crate::notation::lilypond::templates::render_template;
 use crate::notation::lilypond::ToLilypondString;
 use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::expression::Technique;
 
 
+/// The commands that set the stem direction of each voice sharing a staff, upper voices first.
+const VOICE_COMMANDS: [&str; 4] = ["\\voiceOne", "\\voiceTwo", "\\voiceThree", "\\voiceFour"];
+
+/// Hammer-ons and pull-offs are written as a slur over each run of notes they join,
+/// which takes the surrounding notes into account, so they're added here.
 impl<'a> ToLilypondString for Vec<LilypondVoiceElement<'a>> {
     fn to_lilypond_string(&self) -> String {
-        let content = self.iter()
-            .map(|item| item.to_lilypond_string())
-            .join(" ");
-        let mut ctx = Context::new();
-        ctx.insert("content", &content);
-        (*TEMPLATE_ENGINE).render("voice", &ctx).unwrap()
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
+        render_voice(voice_content(self)?)
     }
 }
 
+/// The voices of a staff. Where there's more than one, each is given its own stem direction,
+/// the first with stems up and the second down, so that e.g. a melody and its accompaniment
+/// can share a staff. Lilypond only has such commands for the first four voices.
+pub(crate) fn render_voices(voices: &[Vec<LilypondVoiceElement>]) -> Result<Vec<String>, LilypondError> {
+    voices.iter()
+        .enumerate()
+        .map(|(i, voice)| {
+            let content = voice_content(voice)?;
+            match VOICE_COMMANDS.get(i).filter(|_| voices.len() > 1) {
+                Some(command) => render_voice(format!("{} {}", command, content)),
+                None => render_voice(content),
+            }
+        })
+        .collect()
+}
+
+fn render_voice(content: String) -> Result<String, LilypondError> {
+    let mut ctx = Context::new();
+    ctx.insert("content", &content);
+    render_template("voice", &ctx)
+}
+
+fn voice_content(voice: &[LilypondVoiceElement]) -> Result<String, LilypondError> {
+    let mut in_legato = false;
+    // The element the slur in progress starts on, and the last note, to end it on.
+    let mut slur_start = 0;
+    let mut last_note = None;
+    let mut elements = vec![];
+    for item in voice {
+        let mut element = item.try_to_lilypond_string()?;
+        if let LilypondVoiceElement::Common(event) = item {
+            let legato = matches!(
+                event.expression.connection(),
+                Some(Technique::HammerOn | Technique::PullOff),
+            );
+            if legato && !in_legato {
+                element.push('(');
+                slur_start = elements.len();
+            } else if in_legato && !legato {
+                element.push(')');
+            }
+            in_legato = legato;
+            last_note = Some(elements.len());
+        }
+        elements.push(element);
+    }
+    // A voice that ends on a hammer-on or pull-off still ends its slur, on the last note,
+    // unless that's the only note of the slur, in which case there's nothing to slur.
+    if let (true, Some(last)) = (in_legato, last_note) {
+        if last == slur_start {
+            elements[last].pop();
+        } else {
+            elements[last].push(')');
+        }
+    }
+    Ok(elements.join(" "))
+}
+
 /// Abstraction over common elements (things that other engraving systems
 /// should definitely have, like notes and rests),
 /// with the addition of other elements that may be unique to Lilypond.
 pub enum LilypondVoiceElement<'a> {
     /// Notes, chords (fretted or otherwise), and rests.
     Common(RhythmicNotatedEvent<'a>),
-    // TODO Repeat block
+    /// Sections, repeats with their endings, and D.S. al Coda.
+    Form(Form<LilypondVoiceElement<'a>>),
     // TODO \break
     // TODO barline
     // TODO Replace this with definite types
@@ -40,11 +106,56 @@ impl<'a> Into<LilypondVoiceElement<'a>> for RhythmicNotatedEvent<'a> {
 
 impl<'a> ToLilypondString for LilypondVoiceElement<'a> {
     fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
         match &self {
             LilypondVoiceElement::Common(rhythmic_notated_event) => {
-                rhythmic_notated_event.to_lilypond_string()
+                Ok(rhythmic_notated_event.to_lilypond_string())
             },
-            LilypondVoiceElement::Other(ly) => ly.to_lilypond_string()
+            LilypondVoiceElement::Form(form) => form.try_to_lilypond_string(),
+            LilypondVoiceElement::Other(ly) => ly.try_to_lilypond_string()
         }
     }
+}
+
+/// Each repeat is written as a `\repeat volta`, followed by its `\alternative` endings,
+/// and sections are marked with their names, boxed.
+impl<T: ToLilypondString> ToLilypondString for Form<T> {
+    fn to_lilypond_string(&self) -> String {
+        self.try_to_lilypond_string().unwrap()
+    }
+
+    fn try_to_lilypond_string(&self) -> Result<String, LilypondError> {
+        let music = |content: &[T]| -> Result<String, LilypondError> {
+            let items = content.iter()
+                .map(|item| item.try_to_lilypond_string())
+                .collect::<Result<Vec<_>, LilypondError>>()?;
+            Ok(items.join(" "))
+        };
+        let mark = |name: &Option<String>| name.as_ref()
+            .map(|name| format!("\\mark \\markup \\box {} ", lilypond_string(name)))
+            .unwrap_or_default();
+        let elements = self.elements.iter()
+            .map(|element| Ok(match element {
+                FormElement::Section(section) => format!("{}{}", mark(&section.name), music(&section.content)?),
+                FormElement::Repeat { body, times, endings } => {
+                    let mut repeat = format!("{}\\repeat volta {} {{ {} }}", mark(&body.name), times, music(&body.content)?);
+                    if !endings.is_empty() {
+                        let endings = endings.iter()
+                            .map(|ending| Ok(format!("{{ {} }}", music(ending)?)))
+                            .collect::<Result<Vec<_>, LilypondError>>()?;
+                        repeat = format!("{} \\alternative {{ {} }}", repeat, endings.join(" "));
+                    }
+                    repeat
+                },
+                FormElement::Segno => "\\mark \\markup { \\musicglyph #\"scripts.segno\" }".to_string(),
+                FormElement::ToCoda => "\\mark \\markup { \"To Coda\" \\musicglyph #\"scripts.coda\" }".to_string(),
+                FormElement::Coda => "\\mark \\markup { \\musicglyph #\"scripts.coda\" }".to_string(),
+                FormElement::DalSegnoAlCoda => "\\mark \"D.S. al Coda\"".to_string(),
+            }))
+            .collect::<Result<Vec<_>, LilypondError>>()?;
+        Ok(elements.join(" "))
+    }
 }
\ No newline at end of file
@@ -1,10 +1,15 @@
+use std::error::Error;
+use std::sync::{PoisonError, RwLock};
 use once_cell::sync::Lazy;
-use tera::Tera;
+use tera::{Context, Tera, Value};
+use crate::notation::lilypond::error::LilypondError;
 
-pub static TEMPLATE_ENGINE: Lazy<Tera> = Lazy::new(|| {
+/// The templates documents are rendered with, including any registered with [register_template].
+static TEMPLATE_ENGINE: Lazy<RwLock<Tera>> = Lazy::new(|| {
     let mut tera = Tera::default();
     tera.add_raw_template("staff", STAFF).unwrap();
     tera.add_raw_template("tab_staff", TAB_STAFF).unwrap();
+    tera.add_raw_template("drum_staff", DRUM_STAFF).unwrap();
     tera.add_raw_template("score", SCORE).unwrap();
     tera.add_raw_template("header", HEADER).unwrap();
     tera.add_raw_template("voice", VOICE).unwrap();
@@ -12,9 +17,97 @@ pub static TEMPLATE_ENGINE: Lazy<Tera> = Lazy::new(|| {
     tera.add_raw_template("fretboard_diagram", FRET_DIAGRAM).unwrap();
     tera.add_raw_template("layout", LAYOUT).unwrap();
     tera.add_raw_template("layout_context", LAYOUT_CONTEXT).unwrap();
-    tera
+    tera.add_raw_template("paper", PAPER).unwrap();
+    RwLock::new(tera)
 });
 
+/// The kind of value given for a key of a template's context.
+#[derive(Debug, Clone, Copy)]
+enum ContextValue {
+    Text,
+    Number,
+    Flag,
+    List,
+}
+
+impl ContextValue {
+    /// A stand-in, to check that a template renders with it.
+    fn example(&self) -> Value {
+        match self {
+            ContextValue::Text => Value::String(String::new()),
+            ContextValue::Number => Value::from(0),
+            ContextValue::Flag => Value::Bool(false),
+            ContextValue::List => Value::Array(vec![]),
+        }
+    }
+}
+
+/// The keys of the context each built-in template is rendered with.
+const CONTEXT_KEYS: [(&str, &[(&str, ContextValue)]); 11] = [
+    ("staff", &[("statements", ContextValue::List), ("voices", ContextValue::List)]),
+    ("tab_staff", &[("statements", ContextValue::List), ("voices", ContextValue::List)]),
+    ("drum_staff", &[("statements", ContextValue::List), ("content", ContextValue::Text)]),
+    ("score", &[("content", ContextValue::Text)]),
+    ("header", &[("content", ContextValue::Text)]),
+    ("voice", &[("content", ContextValue::Text)]),
+    ("voicing_tab", &[("ly_duration", ContextValue::Number), ("ly_notes", ContextValue::List)]),
+    ("fretboard_diagram", &[("frets", ContextValue::List), ("labelled", ContextValue::Flag)]),
+    ("layout", &[("statements", ContextValue::List)]),
+    ("layout_context", &[("statements", ContextValue::List)]),
+    ("paper", &[("statements", ContextValue::List)]),
+];
+
+fn context_keys(name: &str) -> &'static [(&'static str, ContextValue)] {
+    CONTEXT_KEYS.iter()
+        .find(|(template, _)| *template == name)
+        .map_or(&[], |(_, keys)| keys)
+}
+
+/// Tera only describes what went wrong in the errors its error was caused by.
+fn describe(error: &tera::Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        description = format!("{}: {}", description, cause);
+        source = cause.source();
+    }
+    description
+}
+
+/// Adds a template for [render_template], or replaces a built-in one, e.g. "staff", to change
+/// how every document is engraved. A replacement is given the same context as the built-in
+/// template, and errors with [LilypondError::InvalidTemplate] if it doesn't render with it,
+/// e.g. for using a key the built-in template isn't given.
+pub fn register_template(name: &str, source: &str) -> Result<(), LilypondError> {
+    let mut engine = TEMPLATE_ENGINE.write().unwrap_or_else(PoisonError::into_inner);
+    let mut tera = engine.clone();
+    tera.add_raw_template(name, source)
+        .map_err(|e| LilypondError::InvalidTemplate(describe(&e)))?;
+    let keys = context_keys(name);
+    if !keys.is_empty() {
+        let mut ctx = Context::new();
+        for (key, value) in keys {
+            ctx.insert(*key, &value.example());
+        }
+        tera.render(name, &ctx)
+            .map_err(|e| LilypondError::InvalidTemplate(describe(&e)))?;
+    }
+    *engine = tera;
+    Ok(())
+}
+
+/// Renders a built-in template, or one added with [register_template]. Errors with
+/// [LilypondError::MissingTemplateContext] if the context lacks a key a built-in template is given.
+pub fn render_template(name: &str, ctx: &Context) -> Result<String, LilypondError> {
+    if let Some((key, _)) = context_keys(name).iter().find(|(key, _)| !ctx.contains_key(key)) {
+        return Err(LilypondError::MissingTemplateContext(name.to_string(), key.to_string()));
+    }
+    TEMPLATE_ENGINE.read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .render(name, ctx)
+        .map_err(|e| LilypondError::TemplateRenderFailure(describe(&e)))
+}
+
 /// A top-level element of a lilypond document.
 const SCORE: &str = r#"
 \score {
@@ -55,6 +148,15 @@ pub const LAYOUT: &str = r#"
     }
 "#;
 
+/// A top-level element of a lilypond document, for the size and margins of the page.
+const PAPER: &str = r#"
+\paper {
+  {% for statement in statements %}
+  {{ statement }}
+  {% endfor %}
+}
+"#;
+
 /// Placed in a staff to hide the time signature.
 pub const OMIT_TIME_SIGNATURE: &str = "\\omit Staff.TimeSignature";
 pub const OMIT_CLEF: &str = "\\omit Staff.Clef";
@@ -95,6 +197,16 @@ const TAB_STAFF: &str = r#"
         }
 "#;
 
+/// Intentional double indent here.
+const DRUM_STAFF: &str = r#"
+        \new DrumStaff \drummode {
+          {% for statement in statements %}
+          {{ statement }}
+          {% endfor %}
+          {{ content }}
+        }
+"#;
+
 
 /// A voicing for tablature, specifying string numbers in addition to pitch / duration.
 const VOICING_TAB: &str = r#"
@@ -137,7 +249,7 @@ const VOICING_AND_TAB_FINGERINGS: &str = r#"
 const FRET_DIAGRAM: &str = r#"
     \markup {
         \override #'(fret-diagram-details . (
-        (finger-code . below-string)
+        (finger-code . {% if labelled %}in-dot{% else %}below-string{% endif %})
         (number-type . arabic)
         (label-dir . -1)
         (mute-string . "x")
@@ -147,14 +259,47 @@ const FRET_DIAGRAM: &str = r#"
         (xo-padding . 0.3)))
       \fret-diagram-verbose #'(
         {%- for fret in frets %}
-            {%- if fret.fret == '255' %}
+            {%- if fret.fret == 255 %}
                 (mute {{ fret.string }})
             {%- elif fret.fret == 0 %}
                 (open {{ fret.string }})
             {%- else %}
-                (place-fret {{ fret.string }} {{ fret.fret }})
+                (place-fret {{ fret.string }} {{ fret.fret }}{% if fret.label %} "{{ fret.label }}"{% endif %})
             {%- endif %}
         {%- endfor %}
         )
     }
-"#;
\ No newline at end of file
+"#;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_templates() {
+        let mut ctx = Context::new();
+        ctx.insert("name", "Cmaj7");
+        register_template("chord_name", r#"\markup { "{{ name }}" }"#).unwrap();
+        assert_eq!(render_template("chord_name", &ctx).unwrap(), "\\markup { \"Cmaj7\" }");
+        assert!(matches!(register_template("chord_name", "{{ name"), Err(LilypondError::InvalidTemplate(_))));
+
+        // A built-in template can only be replaced by one that renders with the same context.
+        assert!(matches!(
+            register_template("voicing_tab", "{% for fret in frets %}{{ fret }}{% endfor %}"),
+            Err(LilypondError::InvalidTemplate(_)),
+        ));
+        ctx.insert("ly_notes", &[("c", 5), ("e", 4)]);
+        ctx.insert("ly_duration", &2);
+        assert_eq!(render_template("voicing_tab", &ctx).unwrap().trim(), "< c\\5 e\\4 >2");
+        // Replaced with itself, so that the other tests rendering it are unaffected.
+        register_template("voicing_tab", VOICING_TAB).unwrap();
+        assert_eq!(render_template("voicing_tab", &ctx).unwrap().trim(), "< c\\5 e\\4 >2");
+
+        let mut staff = Context::new();
+        staff.insert("statements", &Vec::<String>::new());
+        assert!(matches!(
+            render_template("staff", &staff),
+            Err(LilypondError::MissingTemplateContext(template, key)) if template == "staff" && key == "voices",
+        ));
+        assert!(matches!(render_template("no_such_template", &ctx), Err(LilypondError::TemplateRenderFailure(_))));
+    }
+}
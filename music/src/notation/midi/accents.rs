@@ -0,0 +1,154 @@
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::meter::Meter;
+
+/// Where a note falls in a bar, from the most to the least stressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MetricPosition {
+    /// The first beat of the bar.
+    Downbeat,
+    /// The start of any other big beat, see [Meter::beat_pattern].
+    BigBeat,
+    /// Any other beat.
+    Beat,
+    /// Anywhere between the beats.
+    Offbeat,
+}
+
+impl MetricPosition {
+    /// Where a note `ticks` from the start of the music falls in a bar of `meter`.
+    pub fn of(meter: &Meter, ticks: DurationTicks) -> Self {
+        let beat: Duration = (&meter.denominator).into();
+        let bar_ticks = beat.ticks() * meter.num_beats;
+        let position = ticks % bar_ticks.max(1);
+        // The beat pattern's ticks scaled to those of a [Duration].
+        let scale = beat.ticks() / meter.denominator.ticks();
+        let on_big_beat = meter.beat_pattern.iter()
+            .scan(0, |start, length| {
+                *start += length * scale;
+                Some(*start)
+            })
+            .any(|start| start == position);
+        if position == 0 {
+            MetricPosition::Downbeat
+        } else if on_big_beat {
+            MetricPosition::BigBeat
+        } else if position.is_multiple_of(beat.ticks()) {
+            MetricPosition::Beat
+        } else {
+            MetricPosition::Offbeat
+        }
+    }
+}
+
+/// How the velocity of a note is shaped by where it falls in the bar.
+/// Any function of a [MetricPosition] and a velocity to a velocity is one.
+pub trait AccentCurve {
+    /// The velocity of a note played at `velocity`, at `position` in the bar.
+    fn accent(&self, position: MetricPosition, velocity: u8) -> u8;
+}
+
+impl<F: Fn(MetricPosition, u8) -> u8> AccentCurve for F {
+    fn accent(&self, position: MetricPosition, velocity: u8) -> u8 {
+        self(position, velocity)
+    }
+}
+
+/// An [AccentCurve] that makes each note louder or softer by a fixed amount for where it falls,
+/// keeping it within the velocities of a sounding note, 1 to 127.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricAccents {
+    pub downbeat: i8,
+    pub big_beat: i8,
+    pub beat: i8,
+    pub offbeat: i8,
+}
+
+impl Default for MetricAccents {
+    /// A step of [crate::notation::rhythm::expression::Dynamic] louder on the downbeat,
+    /// half a step on the other big beats, and somewhat softer off the beat.
+    fn default() -> Self {
+        Self { downbeat: 16, big_beat: 8, beat: 0, offbeat: -12 }
+    }
+}
+
+impl AccentCurve for MetricAccents {
+    fn accent(&self, position: MetricPosition, velocity: u8) -> u8 {
+        let change = match position {
+            MetricPosition::Downbeat => self.downbeat,
+            MetricPosition::BigBeat => self.big_beat,
+            MetricPosition::Beat => self.beat,
+            MetricPosition::Offbeat => self.offbeat,
+        };
+        velocity.saturating_add_signed(change).clamp(1, 127)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::notation::midi::{MidiFile, MidiNote};
+    use crate::notation::rhythm::meter::MeterDenominator;
+
+    #[test]
+    fn metric_accents() {
+        let qtr = Duration::QTR.ticks();
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let positions: Vec<MetricPosition> = (0..8).map(|eighth| MetricPosition::of(&four_four, eighth * qtr / 2)).collect();
+        assert_eq!(positions, vec![
+            MetricPosition::Downbeat, MetricPosition::Offbeat,
+            MetricPosition::Beat, MetricPosition::Offbeat,
+            MetricPosition::BigBeat, MetricPosition::Offbeat,
+            MetricPosition::Beat, MetricPosition::Offbeat,
+        ]);
+        assert_eq!(MetricPosition::of(&four_four, 4 * qtr), MetricPosition::Downbeat);
+        let eighth = Duration::EIGHTH.ticks();
+        let six_eight = Meter::new(6, MeterDenominator::Eight, None);
+        assert_eq!(MetricPosition::of(&six_eight, 3 * eighth), MetricPosition::BigBeat);
+        assert_eq!(MetricPosition::of(&six_eight, 7 * eighth), MetricPosition::Beat);
+
+        let accents = MetricAccents::default();
+        assert_eq!(accents.accent(MetricPosition::Downbeat, 80), 96);
+        assert_eq!(accents.accent(MetricPosition::Downbeat, 120), 127);
+        assert_eq!(accents.accent(MetricPosition::Offbeat, 5), 1);
+
+        // Eighth notes in 4/4, at 480 ticks to the quarter.
+        let notes = (0..8)
+            .map(|i| MidiNote { midi_note: 60, velocity: 80, channel: 0, track: 0, start: i * 240, end: i * 240 + 240 })
+            .collect();
+        let file = MidiFile { ticks_per_quarter: 480, time_signature: Some((4, 4)), micros_per_quarter: None, notes, instruments: HashMap::new() };
+        let velocities = |file: &MidiFile| file.notes.iter().map(|note| note.velocity).collect::<Vec<u8>>();
+        let accented = file.clone().with_accents(&four_four, &accents);
+        assert_eq!(velocities(&accented), vec![96, 68, 80, 68, 88, 68, 80, 68]);
+        // Any function will do, e.g. one that only brings out the downbeat.
+        let downbeats = file.with_accents(&four_four, &|position, velocity| match position {
+            MetricPosition::Downbeat => 110,
+            _ => velocity,
+        });
+        assert_eq!(velocities(&downbeats), vec![110, 80, 80, 80, 80, 80, 80, 80]);
+    }
+
+    #[test]
+    fn accented_dynamics() {
+        use crate::notation::rhythm::RhythmicNotatedEvent;
+        use crate::notation::rhythm::expression::{Articulation, Dynamic};
+        use crate::notation::rhythm::tempo::Tempo;
+        use crate::note::note::Note;
+        use crate::note::pitch::Pitch;
+
+        let c = Pitch::new(Note::C, 4).unwrap();
+        let events = vec![
+            RhythmicNotatedEvent::pitch(c, Duration::QTR).with_dynamic(Dynamic::F),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR).with_dynamic(Dynamic::P),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR).with_articulation(Articulation::Accent),
+        ];
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let file = MidiFile::new(&four_four, &Tempo::new(Duration::QTR, 120))
+            .with_events(0, &events)
+            .with_accents(&four_four, &MetricAccents::default());
+        // The meter's accents shape the dynamics of the events, and their own accents.
+        let velocities: Vec<u8> = file.notes.iter().map(|note| note.velocity).collect();
+        assert_eq!(velocities, vec![112, 96, 57, 65]);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use crate::notation::midi::{MidiFile, MidiNote};
+use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent};
+use crate::notation::rhythm::duration::{Duration, TICKS_PER_WHOLE};
+use crate::notation::rhythm::expression::Dynamic;
+use crate::notation::rhythm::meter::Meter;
+use crate::notation::rhythm::tempo::Tempo;
+
+/// The ticks per quarter note of a file made with [MidiFile::new], fine enough
+/// for [crate::notation::midi::Humanize] to move notes by less than a [Duration::ticks].
+pub const EXPORT_TICKS_PER_QUARTER: u16 = 480;
+
+/// Where the events being played have got to.
+struct Playing {
+    /// The dynamic level marked last.
+    dynamic: Option<Dynamic>,
+    /// The index of each note struck by the last event, for a tie to hold.
+    struck: Vec<usize>,
+}
+
+impl MidiFile {
+    /// An empty file in `meter` and at `tempo`, for [MidiFile::with_events] to add to.
+    pub fn new(meter: &Meter, tempo: &Tempo) -> Self {
+        let beat: Duration = (&meter.denominator).into();
+        Self {
+            ticks_per_quarter: EXPORT_TICKS_PER_QUARTER,
+            time_signature: Some((meter.num_beats as u8, (TICKS_PER_WHOLE / beat.ticks()) as u8)),
+            micros_per_quarter: Some(tempo.micros_per_quarter()),
+            notes: vec![],
+            instruments: HashMap::new(),
+        }
+    }
+
+    /// Plays `events` one after another on `track`, from the start of the file, on the channel
+    /// of the track's instrument, if it has one. Each note is played at the velocity of its event,
+    /// see [RhythmicNotatedEvent::midi_velocity], with the last dynamic marked before it in effect,
+    /// and sounds for [RhythmicNotatedEvent::sounding_duration]. A tied event holds the notes
+    /// of the one before it, rather than striking them again. Hairpins aren't played.
+    pub fn with_events(mut self, track: usize, events: &[RhythmicNotatedEvent]) -> Self {
+        let channel = self.instruments.get(&track).map_or(0, |instrument| instrument.channel);
+        let mut playing = Playing { dynamic: None, struck: vec![] };
+        self.play(track, channel, events, 0, (1, 1), &mut playing);
+        self.notes.sort_by_key(|note| (note.start, note.midi_note, note.track));
+        self
+    }
+
+    /// Plays `events` from `start`, with their durations scaled by the ratio of any tuplets
+    /// they're in, and returns where they end.
+    fn play(
+        &mut self,
+        track: usize,
+        channel: u8,
+        events: &[RhythmicNotatedEvent],
+        mut start: u64,
+        scale: (u64, u64),
+        playing: &mut Playing,
+    ) -> u64 {
+        // Converts a [Duration::ticks] to the file's, as scaled.
+        let ticks_per_quarter = u64::from(self.ticks_per_quarter);
+        let ticks = |duration: usize| duration as u64 * ticks_per_quarter * scale.0
+            / (Duration::QTR.ticks() as u64 * scale.1);
+        for event in events {
+            let length = ticks(event.duration());
+            match &event.event {
+                NotatedEvent::Tuplet(tuplet) => {
+                    let scale = (scale.0 * tuplet.denominator as u64, scale.1 * tuplet.numerator as u64);
+                    self.play(track, channel, &tuplet.events, start, scale, playing);
+                },
+                NotatedEvent::SingleEvent(single, _) => {
+                    let end = start + ticks(event.sounding_duration());
+                    if event.tied {
+                        for i in &playing.struck {
+                            self.notes[*i].end = end;
+                        }
+                    } else {
+                        let velocity = event.midi_velocity(playing.dynamic);
+                        playing.struck = midi_notes(single).into_iter()
+                            .map(|midi_note| {
+                                self.notes.push(MidiNote { midi_note, velocity, channel, track, start, end });
+                                self.notes.len() - 1
+                            })
+                            .collect();
+                    }
+                    playing.dynamic = event.expression.dynamic.or(playing.dynamic);
+                },
+            }
+            start += length;
+        }
+        start
+    }
+}
+
+/// The MIDI note of each pitch sounded by an event.
+fn midi_notes(event: &SingleEvent) -> Vec<u8> {
+    match event {
+        SingleEvent::Pitch(pitch) => vec![pitch.midi_note],
+        SingleEvent::Voicing(voicing) => voicing.iter().map(|pitch| pitch.midi_note).collect(),
+        SingleEvent::Fretted(note) => vec![note.pitch.midi_note],
+        SingleEvent::FrettedMany(notes) => notes.iter().map(|note| note.pitch.midi_note).collect(),
+        SingleEvent::Rest => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::midi::{GeneralMidiProgram, MidiInstrument};
+    use crate::notation::rhythm::Tuplet;
+    use crate::notation::rhythm::duration::DurationKind;
+    use crate::notation::rhythm::expression::Articulation;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::note::Note;
+    use crate::note::pitch::Pitch;
+    use crate::note_collections::voicing::Voicing;
+
+    #[test]
+    fn exported_events() {
+        let pitch = |note, octave| Pitch::new(note, octave).unwrap();
+        let triplet = Tuplet::new(
+            vec![
+                RhythmicNotatedEvent::pitch(pitch(Note::C, 4), Duration::EIGHTH),
+                RhythmicNotatedEvent::pitch(pitch(Note::D, 4), Duration::EIGHTH),
+                RhythmicNotatedEvent::pitch(pitch(Note::E, 4), Duration::EIGHTH),
+            ],
+            3,
+            2,
+            DurationKind::Eighth,
+        );
+        let events = vec![
+            RhythmicNotatedEvent::voicing(Voicing::new(vec![pitch(Note::C, 3), pitch(Note::G, 3)]), Duration::QTR)
+                .with_dynamic(Dynamic::P),
+            RhythmicNotatedEvent::pitch(pitch(Note::E, 4), Duration::QTR)
+                .with_articulation(Articulation::Staccato),
+            RhythmicNotatedEvent::pitch_tied(pitch(Note::E, 4), Duration::QTR),
+            RhythmicNotatedEvent::rest(Duration::QTR),
+            RhythmicNotatedEvent::pitch(pitch(Note::G, 4), Duration::QTR)
+                .with_articulation(Articulation::Accent)
+                .with_articulation(Articulation::Tenuto),
+            triplet.into(),
+        ];
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let file = MidiFile::new(&four_four, &Tempo::new(Duration::QTR, 90))
+            .with_instrument(0, MidiInstrument::new(2, GeneralMidiProgram::AcousticGuitarNylon))
+            .with_events(0, &events);
+        let notes: Vec<(u8, u8, u8, u64, u64)> = file.notes.iter()
+            .map(|note| (note.midi_note, note.velocity, note.channel, note.start, note.end))
+            .collect();
+        assert_eq!(notes, vec![
+            // Played piano, and released a little early.
+            (48, 49, 2, 0, 420),
+            (55, 49, 2, 0, 420),
+            // The staccato note is tied over, so it's held until the tied note is released.
+            (64, 49, 2, 480, 1380),
+            // Accented, and held.
+            (67, 65, 2, 1920, 2400),
+            // The triplet fits three eighths into the space of two.
+            (60, 49, 2, 2400, 2540),
+            (62, 49, 2, 2560, 2700),
+            (64, 49, 2, 2720, 2860),
+        ]);
+        // Written out with the tempo, the time signature, and the guitar.
+        let bytes = file.to_bytes();
+        assert_eq!(&bytes[12..14], &480u16.to_be_bytes());
+        let track = &bytes[22..];
+        assert_eq!(&track[..7], &[0, 0xFF, 0x51, 3, 0x0A, 0x2C, 0x2A]);
+        assert_eq!(&track[7..15], &[0, 0xFF, 0x58, 4, 4, 2, 24, 8]);
+        assert_eq!(&track[15..18], &[0, 0xC2, 24]);
+        assert_eq!(&track[18..22], &[0, 0x92, 48, 49]);
+        assert_eq!(MidiFile::parse(&bytes).unwrap(), file);
+    }
+}
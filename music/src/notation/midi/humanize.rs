@@ -0,0 +1,153 @@
+use itertools::Itertools;
+use crate::notation::midi::MidiFile;
+
+/// How [MidiFile::humanized] roughens the timing and dynamics of a file, so that it sounds
+/// less mechanical. Amounts of time are in the file's ticks. Nothing is changed by default,
+/// and the same options, with the same seed, always change the same file in the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Humanize {
+    seed: u64,
+    timing: u64,
+    velocity: u8,
+    strum: u64,
+}
+
+impl Humanize {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, timing: 0, velocity: 0, strum: 0 }
+    }
+
+    /// The most a chord, or a single note, is moved earlier or later.
+    /// The notes of a chord are moved together, so that they're still struck, or strummed, as one.
+    pub fn timing(mut self, ticks: u64) -> Self {
+        self.timing = ticks;
+        self
+    }
+
+    /// The most each note is played louder or softer.
+    pub fn velocity(mut self, amount: u8) -> Self {
+        self.velocity = amount;
+        self
+    }
+
+    /// Strums each chord, i.e. the notes of a track that start together, e.g. those of a voicing
+    /// played with [MidiFile::with_events], from its lowest note up, each this much after the one below.
+    /// The notes still end together.
+    pub fn strum(mut self, ticks: u64) -> Self {
+        self.strum = ticks;
+        self
+    }
+}
+
+/// A small, fast generator of pseudo-random numbers (SplitMix64),
+/// so that a seed gives the same numbers on every platform and version.
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number from `-most` to `most`, inclusive.
+    fn within(&mut self, most: u64) -> i64 {
+        if most == 0 {
+            return 0;
+        }
+        (self.next() % (2 * most + 1)) as i64 - most as i64
+    }
+}
+
+impl MidiFile {
+    /// The file with its notes moved, played louder or softer, and strummed, as by `options`.
+    pub fn humanized(mut self, options: &Humanize) -> Self {
+        let mut rng = SplitMix(options.seed);
+        // The notes of each chord, from the lowest up.
+        let chords: Vec<Vec<usize>> = (0..self.notes.len())
+            .sorted_by_key(|i| (self.notes[*i].track, self.notes[*i].start, self.notes[*i].midi_note))
+            .group_by(|i| (self.notes[*i].track, self.notes[*i].start))
+            .into_iter()
+            .map(|(_, chord)| chord.collect())
+            .collect();
+        for chord in chords {
+            let shift = rng.within(options.timing);
+            for (n, i) in chord.into_iter().enumerate() {
+                let note = &mut self.notes[i];
+                // Never moved to before the start of the file.
+                let shift = shift.max(-(note.start as i64));
+                note.start = note.start.saturating_add_signed(shift);
+                note.end = note.end.saturating_add_signed(shift);
+                // Strummed, though never past the end of the note.
+                note.start += (n as u64 * options.strum).min(note.end.saturating_sub(note.start + 1));
+                let change = rng.within(u64::from(options.velocity));
+                note.velocity = (i64::from(note.velocity) + change).clamp(1, 127) as u8;
+            }
+        }
+        self.notes.sort_by_key(|note| (note.start, note.midi_note, note.track));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::notation::midi::MidiNote;
+
+    #[test]
+    fn humanized_midi() {
+        let note = |midi_note, start, end| MidiNote { midi_note, velocity: 80, channel: 0, track: 0, start, end };
+        // A C major triad, and then a melody note, at 480 ticks to the quarter.
+        let file = MidiFile {
+            ticks_per_quarter: 480,
+            time_signature: None,
+            micros_per_quarter: None,
+            notes: vec![note(60, 0, 960), note(64, 0, 960), note(67, 0, 960), note(72, 960, 1440)],
+            instruments: HashMap::new(),
+        };
+        assert_eq!(file.clone().humanized(&Humanize::new(7)), file);
+
+        let strummed = file.clone().humanized(&Humanize::new(7).strum(20));
+        let starts: Vec<(u8, u64, u64)> = strummed.notes.iter().map(|note| (note.midi_note, note.start, note.end)).collect();
+        assert_eq!(starts, vec![(60, 0, 960), (64, 20, 960), (67, 40, 960), (72, 960, 1440)]);
+
+        let options = Humanize::new(7).timing(15).velocity(10).strum(20);
+        let humanized = file.clone().humanized(&options);
+        assert_eq!(file.clone().humanized(&options), humanized);
+        assert_ne!(file.clone().humanized(&Humanize::new(8).timing(15).velocity(10).strum(20)), humanized);
+        let chord: Vec<&MidiNote> = humanized.notes.iter().filter(|note| note.midi_note < 72).collect();
+        // The chord moves as one, and isn't moved before the start of the file.
+        assert!(chord.iter().all(|note| note.end == chord[0].end && note.end >= 960 && note.end <= 975));
+        assert_eq!(chord[1].start - chord[0].start, 20);
+        assert_eq!(chord[2].start - chord[1].start, 20);
+        for (before, after) in file.notes.iter().zip(&humanized.notes) {
+            assert!(after.velocity.abs_diff(before.velocity) <= 10);
+        }
+        let melody = humanized.notes.iter().find(|note| note.midi_note == 72).unwrap();
+        assert!(melody.start.abs_diff(960) <= 15 && melody.end - melody.start == 480);
+    }
+
+    #[test]
+    fn strummed_voicing() {
+        use crate::notation::rhythm::RhythmicNotatedEvent;
+        use crate::notation::rhythm::duration::Duration;
+        use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+        use crate::notation::rhythm::tempo::Tempo;
+        use crate::note::note::Note;
+        use crate::note::pitch::Pitch;
+        use crate::note_collections::voicing::Voicing;
+
+        let voicing = Voicing::new([(Note::C, 3), (Note::G, 3), (Note::E, 4), (Note::C, 5)].iter()
+            .map(|(note, octave)| Pitch::new(*note, *octave).unwrap())
+            .collect());
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let file = MidiFile::new(&meter, &Tempo::new(Duration::QTR, 120))
+            .with_events(0, &[RhythmicNotatedEvent::voicing(voicing, Duration::WHOLE)])
+            .humanized(&Humanize::new(7).strum(30));
+        let notes: Vec<(u8, u64, u64)> = file.notes.iter().map(|note| (note.midi_note, note.start, note.end)).collect();
+        assert_eq!(notes, vec![(48, 0, 1680), (55, 30, 1680), (64, 60, 1680), (72, 90, 1680)]);
+    }
+}
@@ -0,0 +1,410 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+use crate::note::pitch_class::Pc;
+use crate::note_collections::WeightedPcSet;
+pub use accents::{AccentCurve, MetricAccents, MetricPosition};
+pub use export::EXPORT_TICKS_PER_QUARTER;
+pub use humanize::Humanize;
+pub use program::{GeneralMidiProgram, MidiInstrument};
+
+pub mod accents;
+pub mod export;
+pub mod humanize;
+pub mod program;
+
+/// A note read from a MIDI file, timed in ticks of the file's [MidiFile::ticks_per_quarter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MidiNote {
+    pub midi_note: u8,
+    pub velocity: u8,
+    pub channel: u8,
+    /// The index of the track the note was read from.
+    pub track: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The notes of a Standard MIDI File, and the little else that's needed to make sense of them.
+/// Every other kind of event, e.g. tempo changes or controllers, is skipped over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiFile {
+    pub ticks_per_quarter: u16,
+    /// The first time signature in the file, as its numerator and denominator, if there is one.
+    pub time_signature: Option<(u8, u8)>,
+    /// The first tempo in the file, as the length of a quarter note in microseconds, if there is one.
+    pub micros_per_quarter: Option<u32>,
+    /// In order of when they start, and then by pitch.
+    pub notes: Vec<MidiNote>,
+    /// The instrument of each track that's been given one, by the index of the track.
+    /// Tracks without one are played on the default program, the piano.
+    pub instruments: HashMap<usize, MidiInstrument>,
+}
+
+impl MidiFile {
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, MusicSemanticsError> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| MusicSemanticsError::FileReadFailure(e.to_string()))?;
+        Self::parse(&bytes)
+    }
+
+    /// Parses a file of any of the three formats. The tracks of a format 2 file are read
+    /// as if they were played together, as in the other formats.
+    /// Files timed in SMPTE frames rather than beats aren't supported.
+    pub fn parse(bytes: &[u8]) -> Result<Self, MusicSemanticsError> {
+        let mut reader = Reader { bytes, position: 0 };
+        if reader.take(4)? != b"MThd" {
+            return Err(invalid("missing header chunk"));
+        }
+        let header_length = reader.u32()? as usize;
+        let header = reader.take(header_length)?;
+        if header.len() < 6 {
+            return Err(invalid("header chunk too short"));
+        }
+        let ticks_per_quarter = u16::from_be_bytes([header[4], header[5]]);
+        if ticks_per_quarter & 0x8000 != 0 || ticks_per_quarter == 0 {
+            return Err(invalid("only files timed in ticks per quarter note are supported"));
+        }
+
+        let mut file = Self { ticks_per_quarter, time_signature: None, micros_per_quarter: None, notes: vec![], instruments: HashMap::new() };
+        let mut track = 0;
+        while reader.position < bytes.len() {
+            let kind = reader.take(4)?;
+            let length = reader.u32()? as usize;
+            let chunk = reader.take(length)?;
+            // Chunks of unknown kinds are to be skipped.
+            if kind == b"MTrk" {
+                file.parse_track(chunk, track)?;
+                track += 1;
+            }
+        }
+        file.notes.sort_by_key(|note| (note.start, note.midi_note, note.track));
+        Ok(file)
+    }
+
+    fn parse_track(&mut self, chunk: &[u8], track: usize) -> Result<(), MusicSemanticsError> {
+        let mut reader = Reader { bytes: chunk, position: 0 };
+        let mut time = 0;
+        let mut running_status = None;
+        // The notes sounding on each channel and key, as their starts and velocities, oldest first.
+        let mut sounding: HashMap<(u8, u8), Vec<(u64, u8)>> = HashMap::new();
+        while reader.position < chunk.len() {
+            time += reader.vlq()?;
+            let status = match reader.peek()? {
+                byte if byte & 0x80 != 0 => {
+                    reader.position += 1;
+                    byte
+                },
+                _ => running_status.ok_or_else(|| invalid("data byte without a status byte"))?,
+            };
+            match status {
+                0xFF => {
+                    let kind = reader.u8()?;
+                    let length = reader.vlq()? as usize;
+                    let data = reader.take(length)?;
+                    if kind == 0x58 && data.len() >= 2 && self.time_signature.is_none() {
+                        let denominator = 1u8.checked_shl(u32::from(data[1]))
+                            .ok_or_else(|| invalid("time signature denominator too large"))?;
+                        self.time_signature = Some((data[0], denominator));
+                    }
+                    if kind == 0x51 && data.len() >= 3 && self.micros_per_quarter.is_none() {
+                        self.micros_per_quarter = Some(u32::from_be_bytes([0, data[0], data[1], data[2]]));
+                    }
+                    if kind == 0x2F {
+                        break;
+                    }
+                },
+                0xF0 | 0xF7 => {
+                    let length = reader.vlq()? as usize;
+                    reader.take(length)?;
+                },
+                0xF1..=0xFE => return Err(invalid("system message in a track")),
+                _ => {
+                    running_status = Some(status);
+                    let channel = status & 0x0F;
+                    match status & 0xF0 {
+                        0x80 | 0x90 => {
+                            let key = reader.u8()?;
+                            let velocity = reader.u8()?;
+                            if status & 0xF0 == 0x90 && velocity > 0 {
+                                sounding.entry((channel, key)).or_default().push((time, velocity));
+                            } else if let Some(starts) = sounding.get_mut(&(channel, key)) {
+                                if !starts.is_empty() {
+                                    let (start, velocity) = starts.remove(0);
+                                    self.notes.push(MidiNote { midi_note: key, velocity, channel, track, start, end: time });
+                                }
+                            }
+                        },
+                        0xC0 => {
+                            // A track's instrument is the first program it chooses.
+                            if let Some(program) = GeneralMidiProgram::from_number(reader.u8()?) {
+                                self.instruments.entry(track).or_insert(MidiInstrument::new(channel, program));
+                            }
+                        },
+                        0xD0 => {
+                            reader.u8()?;
+                        },
+                        _ => {
+                            reader.take(2)?;
+                        },
+                    }
+                },
+            }
+        }
+        // Notes never released last until the end of the track.
+        for ((channel, key), starts) in sounding {
+            for (start, velocity) in starts {
+                self.notes.push(MidiNote { midi_note: key, velocity, channel, track, start, end: time });
+            }
+        }
+        Ok(())
+    }
+
+    /// Plays `track` on `instrument`, moving each of the track's notes onto the instrument's channel.
+    pub fn with_instrument(mut self, track: usize, instrument: MidiInstrument) -> Self {
+        for note in self.notes.iter_mut().filter(|note| note.track == track) {
+            note.channel = instrument.channel;
+        }
+        self.instruments.insert(track, instrument);
+        self
+    }
+
+    /// Shapes the velocity of each note by where it starts in a bar of `meter`, e.g. with
+    /// [MetricAccents] to stress the big beats and play the offbeats more softly.
+    /// The velocities shaped are those the notes are already played at, e.g. from the dynamics
+    /// and accents of the events of [MidiFile::with_events].
+    pub fn with_accents(mut self, meter: &Meter, curve: &impl AccentCurve) -> Self {
+        let positions: Vec<MetricPosition> = self.notes.iter()
+            .map(|note| MetricPosition::of(meter, self.duration_ticks(note.start)))
+            .collect();
+        for (note, position) in self.notes.iter_mut().zip(positions) {
+            note.velocity = curve.accent(position, note.velocity);
+        }
+        self
+    }
+
+    /// The file as a format 1 Standard MIDI File, with one track for each track its notes
+    /// were read from, and the tempo and time signature, if any, at the start of the first.
+    /// Each track with an instrument starts by choosing its program on its channel.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let num_tracks = self.notes.iter().map(|note| note.track)
+            .chain(self.instruments.keys().copied())
+            .map(|track| track + 1)
+            .max()
+            .unwrap_or(1);
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend(6u32.to_be_bytes());
+        bytes.extend(1u16.to_be_bytes());
+        bytes.extend((num_tracks as u16).to_be_bytes());
+        bytes.extend(self.ticks_per_quarter.to_be_bytes());
+        for track in 0..num_tracks {
+            // Each event's time, whether it's a note on, and its bytes, so that
+            // notes are released before any others start at the same time.
+            let mut events: Vec<(u64, bool, Vec<u8>)> = vec![];
+            if let (0, Some(micros)) = (track, self.micros_per_quarter) {
+                events.push((0, false, [&[0xFF, 0x51, 3][..], &micros.to_be_bytes()[1..]].concat()));
+            }
+            if let (0, Some((numerator, denominator))) = (track, self.time_signature) {
+                let power = denominator.trailing_zeros() as u8;
+                events.push((0, false, vec![0xFF, 0x58, 4, numerator, power, 24, 8]));
+            }
+            if let Some(instrument) = self.instruments.get(&track) {
+                events.push((0, false, vec![0xC0 | instrument.channel, instrument.program.number()]));
+            }
+            for note in self.notes.iter().filter(|note| note.track == track) {
+                events.push((note.start, true, vec![0x90 | note.channel, note.midi_note, note.velocity]));
+                events.push((note.end, false, vec![0x80 | note.channel, note.midi_note, 0]));
+            }
+            events.sort_by_key(|(time, on, _)| (*time, *on));
+            let mut chunk = vec![];
+            let mut time = 0;
+            for (event_time, _, event) in events {
+                write_vlq(&mut chunk, event_time - time);
+                chunk.extend(event);
+                time = event_time;
+            }
+            chunk.extend([0, 0xFF, 0x2F, 0]);
+            bytes.extend(b"MTrk");
+            bytes.extend((chunk.len() as u32).to_be_bytes());
+            bytes.extend(chunk);
+        }
+        bytes
+    }
+
+    /// The [Meter] of the file's time signature, if it has one a [Meter] can represent.
+    pub fn meter(&self) -> Option<Meter> {
+        let (numerator, denominator) = self.time_signature?;
+        let denominator = match denominator {
+            1 => MeterDenominator::One,
+            2 => MeterDenominator::Two,
+            4 => MeterDenominator::Four,
+            8 => MeterDenominator::Eight,
+            16 => MeterDenominator::Sixteen,
+            _ => return None,
+        };
+        Some(Meter::new(usize::from(numerator), denominator, None))
+    }
+
+    /// Converts a time or duration in the file's ticks to [DurationTicks], rounding to the nearest.
+    pub fn duration_ticks(&self, ticks: u64) -> DurationTicks {
+        let quarter = Duration::QTR.ticks() as u64;
+        let ticks_per_quarter = u64::from(self.ticks_per_quarter);
+        ((ticks * quarter + ticks_per_quarter / 2) / ticks_per_quarter) as DurationTicks
+    }
+
+    /// How much each pitch class is heard in the file: for how long its notes sound, in [DurationTicks],
+    /// with each note counting for less the more softly it's played.
+    pub fn weighted_pcs(&self) -> WeightedPcSet {
+        self.notes.iter()
+            .map(|note| (
+                Pc::from(&note.midi_note),
+                (note.end - note.start) as f64 * f64::from(note.velocity) / 127.0 * Duration::QTR.ticks() as f64
+                    / f64::from(self.ticks_per_quarter),
+            ))
+            .collect()
+    }
+}
+
+fn invalid(reason: &str) -> MusicSemanticsError {
+    MusicSemanticsError::InvalidMidiFile(reason.to_string())
+}
+
+/// Writes a variable-length quantity, seven bits to a byte, with the high bit set on all but the last.
+fn write_vlq(bytes: &mut Vec<u8>, value: u64) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    bytes.extend(groups.iter().rev());
+}
+
+/// Reads through the bytes of a file or chunk, erroring if they run out.
+struct Reader<'b> {
+    bytes: &'b [u8],
+    position: usize,
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, length: usize) -> Result<&'b [u8], MusicSemanticsError> {
+        let end = self.position.checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("unexpected end of data"))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn peek(&self) -> Result<u8, MusicSemanticsError> {
+        self.bytes.get(self.position).copied().ok_or_else(|| invalid("unexpected end of data"))
+    }
+
+    fn u8(&mut self) -> Result<u8, MusicSemanticsError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, MusicSemanticsError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// A variable-length quantity, of at most four bytes.
+    fn vlq(&mut self) -> Result<u64, MusicSemanticsError> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | u64::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("variable-length quantity longer than four bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midi_files() {
+        // One track in 3/4 at 120 quarter notes a minute, with a C major triad and then a melody note an octave above its root,
+        // written with running status and a note on of velocity zero to release each note.
+        let track: Vec<u8> = vec![
+            0x00, 0xFF, 0x58, 0x04, 0x03, 0x02, 0x18, 0x08,
+            0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20,
+            0x00, 0x90, 60, 100,
+            0x00, 64, 90,
+            0x00, 67, 80,
+            0x83, 0x60, 60, 0,
+            0x00, 64, 0,
+            0x00, 67, 0,
+            0x00, 0xC0, 0x05,
+            0x00, 0x91, 72, 70,
+            0x81, 0x70, 0x81, 72, 0,
+            0x00, 0xFF, 0x2F, 0x00,
+        ];
+        let mut bytes = b"MThd".to_vec();
+        bytes.extend([0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xE0]);
+        bytes.extend(b"MTrk");
+        bytes.extend((track.len() as u32).to_be_bytes());
+        bytes.extend(&track);
+        let file = MidiFile::parse(&bytes).unwrap();
+        assert_eq!(file.ticks_per_quarter, 480);
+        assert_eq!(file.time_signature, Some((3, 4)));
+        assert_eq!(file.micros_per_quarter, Some(500_000));
+        assert_eq!(file.meter(), Some(Meter::new(3, MeterDenominator::Four, None)));
+        let notes: Vec<(u8, u8, u64, u64)> = file.notes.iter()
+            .map(|note| (note.midi_note, note.channel, note.start, note.end))
+            .collect();
+        assert_eq!(notes, vec![(60, 0, 0, 480), (64, 0, 0, 480), (67, 0, 0, 480), (72, 1, 480, 720)]);
+        assert_eq!(file.duration_ticks(720), Duration::QTR.ticks() * 3 / 2);
+        // The melody's C adds to the chord's, and the chord's E is played louder than its G.
+        assert_eq!(file.weighted_pcs().by_weight(), vec![Pc::Pc0, Pc::Pc4, Pc::Pc7]);
+        assert_eq!(file.weighted_pcs().weight(&Pc::Pc7), Duration::QTR.ticks() as f64 * 80.0 / 127.0);
+
+        // The program change part way through the track is taken as its instrument.
+        assert_eq!(file.instruments, HashMap::from([(0, MidiInstrument::new(0, GeneralMidiProgram::ElectricPiano2))]));
+
+        // Written back out, and read in again.
+        assert_eq!(MidiFile::parse(&file.to_bytes()).unwrap(), file);
+        let mut vlq = vec![];
+        write_vlq(&mut vlq, 200);
+        assert_eq!(vlq, vec![0x81, 0x48]);
+
+        assert!(matches!(MidiFile::parse(b"RIFF"), Err(MusicSemanticsError::InvalidMidiFile(_))));
+        assert!(MidiFile::parse(&bytes[..bytes.len() - 5]).is_err());
+        assert!(matches!(
+            MidiFile::read(std::env::temp_dir().join("rust_music_no_such_file.mid")),
+            Err(MusicSemanticsError::FileReadFailure(_)),
+        ));
+    }
+
+    #[test]
+    fn midi_instruments() {
+        let note = |track, midi_note, start, end| MidiNote { midi_note, velocity: 80, channel: 0, track, start, end };
+        let file = MidiFile {
+            ticks_per_quarter: 480,
+            time_signature: None,
+            micros_per_quarter: None,
+            notes: vec![note(1, 48, 0, 480), note(0, 60, 0, 960), note(0, 64, 0, 960), note(1, 43, 480, 960)],
+            instruments: HashMap::new(),
+        };
+        let file = file
+            .with_instrument(0, MidiInstrument::new(0, GeneralMidiProgram::AcousticGrandPiano))
+            .with_instrument(1, MidiInstrument::new(1, GeneralMidiProgram::AcousticBass))
+            .with_instrument(2, MidiInstrument::new(2, GeneralMidiProgram::AcousticGuitarNylon));
+        let channels: Vec<u8> = file.notes.iter().map(|note| note.channel).collect();
+        assert_eq!(channels, vec![1, 0, 0, 1]);
+        let bytes = file.to_bytes();
+        // A track for the guitar, though it has no notes yet.
+        assert_eq!(&bytes[10..12], &[0, 3]);
+        // The bass track starts by choosing its program.
+        let second_track = bytes.windows(4).enumerate().filter(|(_, w)| *w == b"MTrk").nth(1).unwrap().0;
+        assert_eq!(&bytes[second_track + 8..second_track + 11], &[0, 0xC1, 32]);
+        assert_eq!(MidiFile::parse(&bytes).unwrap(), file);
+    }
+}
@@ -0,0 +1,219 @@
+use GeneralMidiProgram::*;
+
+/// The 128 instruments of General MIDI, in order, so that each is numbered
+/// from zero as it's chosen by a MIDI program change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum GeneralMidiProgram {
+    // Piano
+    AcousticGrandPiano,
+    BrightAcousticPiano,
+    ElectricGrandPiano,
+    HonkyTonkPiano,
+    ElectricPiano1,
+    ElectricPiano2,
+    Harpsichord,
+    Clavinet,
+    // Chromatic percussion
+    Celesta,
+    Glockenspiel,
+    MusicBox,
+    Vibraphone,
+    Marimba,
+    Xylophone,
+    TubularBells,
+    Dulcimer,
+    // Organ
+    DrawbarOrgan,
+    PercussiveOrgan,
+    RockOrgan,
+    ChurchOrgan,
+    ReedOrgan,
+    Accordion,
+    Harmonica,
+    TangoAccordion,
+    // Guitar
+    AcousticGuitarNylon,
+    AcousticGuitarSteel,
+    ElectricGuitarJazz,
+    ElectricGuitarClean,
+    ElectricGuitarMuted,
+    OverdrivenGuitar,
+    DistortionGuitar,
+    GuitarHarmonics,
+    // Bass
+    AcousticBass,
+    ElectricBassFinger,
+    ElectricBassPick,
+    FretlessBass,
+    SlapBass1,
+    SlapBass2,
+    SynthBass1,
+    SynthBass2,
+    // Strings
+    Violin,
+    Viola,
+    Cello,
+    Contrabass,
+    TremoloStrings,
+    PizzicatoStrings,
+    OrchestralHarp,
+    Timpani,
+    // Ensemble
+    StringEnsemble1,
+    StringEnsemble2,
+    SynthStrings1,
+    SynthStrings2,
+    ChoirAahs,
+    VoiceOohs,
+    SynthVoice,
+    OrchestraHit,
+    // Brass
+    Trumpet,
+    Trombone,
+    Tuba,
+    MutedTrumpet,
+    FrenchHorn,
+    BrassSection,
+    SynthBrass1,
+    SynthBrass2,
+    // Reed
+    SopranoSax,
+    AltoSax,
+    TenorSax,
+    BaritoneSax,
+    Oboe,
+    EnglishHorn,
+    Bassoon,
+    Clarinet,
+    // Pipe
+    Piccolo,
+    Flute,
+    Recorder,
+    PanFlute,
+    BlownBottle,
+    Shakuhachi,
+    Whistle,
+    Ocarina,
+    // Synth lead
+    Lead1Square,
+    Lead2Sawtooth,
+    Lead3Calliope,
+    Lead4Chiff,
+    Lead5Charang,
+    Lead6Voice,
+    Lead7Fifths,
+    Lead8BassAndLead,
+    // Synth pad
+    Pad1NewAge,
+    Pad2Warm,
+    Pad3Polysynth,
+    Pad4Choir,
+    Pad5Bowed,
+    Pad6Metallic,
+    Pad7Halo,
+    Pad8Sweep,
+    // Synth effects
+    Fx1Rain,
+    Fx2Soundtrack,
+    Fx3Crystal,
+    Fx4Atmosphere,
+    Fx5Brightness,
+    Fx6Goblins,
+    Fx7Echoes,
+    Fx8SciFi,
+    // Ethnic
+    Sitar,
+    Banjo,
+    Shamisen,
+    Koto,
+    Kalimba,
+    Bagpipe,
+    Fiddle,
+    Shanai,
+    // Percussive
+    TinkleBell,
+    Agogo,
+    SteelDrums,
+    Woodblock,
+    TaikoDrum,
+    MelodicTom,
+    SynthDrum,
+    ReverseCymbal,
+    // Sound effects
+    GuitarFretNoise,
+    BreathNoise,
+    Seashore,
+    BirdTweet,
+    TelephoneRing,
+    Helicopter,
+    Applause,
+    Gunshot,
+}
+
+impl GeneralMidiProgram {
+    pub const ALL: [GeneralMidiProgram; 128] = [
+        AcousticGrandPiano, BrightAcousticPiano, ElectricGrandPiano, HonkyTonkPiano,
+        ElectricPiano1, ElectricPiano2, Harpsichord, Clavinet,
+        Celesta, Glockenspiel, MusicBox, Vibraphone, Marimba, Xylophone, TubularBells, Dulcimer,
+        DrawbarOrgan, PercussiveOrgan, RockOrgan, ChurchOrgan, ReedOrgan, Accordion, Harmonica, TangoAccordion,
+        AcousticGuitarNylon, AcousticGuitarSteel, ElectricGuitarJazz, ElectricGuitarClean,
+        ElectricGuitarMuted, OverdrivenGuitar, DistortionGuitar, GuitarHarmonics,
+        AcousticBass, ElectricBassFinger, ElectricBassPick, FretlessBass,
+        SlapBass1, SlapBass2, SynthBass1, SynthBass2,
+        Violin, Viola, Cello, Contrabass, TremoloStrings, PizzicatoStrings, OrchestralHarp, Timpani,
+        StringEnsemble1, StringEnsemble2, SynthStrings1, SynthStrings2, ChoirAahs, VoiceOohs, SynthVoice, OrchestraHit,
+        Trumpet, Trombone, Tuba, MutedTrumpet, FrenchHorn, BrassSection, SynthBrass1, SynthBrass2,
+        SopranoSax, AltoSax, TenorSax, BaritoneSax, Oboe, EnglishHorn, Bassoon, Clarinet,
+        Piccolo, Flute, Recorder, PanFlute, BlownBottle, Shakuhachi, Whistle, Ocarina,
+        Lead1Square, Lead2Sawtooth, Lead3Calliope, Lead4Chiff, Lead5Charang, Lead6Voice, Lead7Fifths, Lead8BassAndLead,
+        Pad1NewAge, Pad2Warm, Pad3Polysynth, Pad4Choir, Pad5Bowed, Pad6Metallic, Pad7Halo, Pad8Sweep,
+        Fx1Rain, Fx2Soundtrack, Fx3Crystal, Fx4Atmosphere, Fx5Brightness, Fx6Goblins, Fx7Echoes, Fx8SciFi,
+        Sitar, Banjo, Shamisen, Koto, Kalimba, Bagpipe, Fiddle, Shanai,
+        TinkleBell, Agogo, SteelDrums, Woodblock, TaikoDrum, MelodicTom, SynthDrum, ReverseCymbal,
+        GuitarFretNoise, BreathNoise, Seashore, BirdTweet, TelephoneRing, Helicopter, Applause, Gunshot,
+    ];
+
+    /// The number of the program, as sent in a program change.
+    pub fn number(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The program of a number sent in a program change, if it's one of the 128.
+    pub fn from_number(number: u8) -> Option<Self> {
+        Self::ALL.get(usize::from(number)).copied()
+    }
+}
+
+/// The instrument a track of a [crate::notation::midi::MidiFile] is played on:
+/// the channel its notes are sent on, and the program chosen on that channel.
+/// General MIDI plays drums on [crate::notation::rhythm::percussion::MIDI_PERCUSSION_CHANNEL]
+/// whatever its program is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MidiInstrument {
+    pub channel: u8,
+    pub program: GeneralMidiProgram,
+}
+
+impl MidiInstrument {
+    pub fn new(channel: u8, program: GeneralMidiProgram) -> Self {
+        Self { channel, program }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn general_midi_programs() {
+        for (i, program) in GeneralMidiProgram::ALL.iter().enumerate() {
+            assert_eq!(usize::from(program.number()), i);
+            assert_eq!(GeneralMidiProgram::from_number(program.number()), Some(*program));
+        }
+        assert_eq!(AcousticGuitarNylon.number(), 24);
+        assert_eq!(ElectricBassFinger.number(), 33);
+        assert_eq!(Gunshot.number(), 127);
+        assert_eq!(GeneralMidiProgram::from_number(128), None);
+    }
+}
@@ -1,5 +1,11 @@
 #[cfg(feature="lilypond")]
 pub mod lilypond;
+#[cfg(feature = "std")]
 pub mod vextab;
 pub mod clef;
+pub mod escape;
+pub mod form;
+#[cfg(feature = "std")]
+pub mod midi;
+#[cfg(feature = "std")]
 pub mod rhythm;
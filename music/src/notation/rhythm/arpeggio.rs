@@ -0,0 +1,133 @@
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::note_collections::voicing::Voicing;
+
+/// An order in which to play the notes of a chord one at a time.
+/// Each pattern describes one cycle, which can be repeated to fill out a bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArpeggioPattern {
+    /// From the lowest note to the highest.
+    Up,
+    /// From the highest note to the lowest.
+    Down,
+    /// Up, and then back down, without repeating the highest or lowest note,
+    /// so that the cycle can be repeated, e.g. C E G E for a C major triad.
+    UpDown,
+    /// The lowest note, the highest, the middle, and the highest again,
+    /// e.g. C G E G for a C major triad.
+    Alberti,
+    /// The bass alternating with a second bass note, each followed by a treble note,
+    /// as the thumb and fingers of Travis picking, e.g. the A, B, D, and high E strings of an open C chord.
+    /// Taken in string order when arpeggiating a [FretboardShape].
+    Travis,
+}
+
+impl ArpeggioPattern {
+    /// The indices of the notes of a chord of `size` notes, ordered from the bass up,
+    /// in the order this pattern plays them.
+    pub fn indices(&self, size: usize) -> Vec<usize> {
+        if size == 0 {
+            return vec![];
+        }
+        let top = size - 1;
+        match self {
+            ArpeggioPattern::Up => (0..size).collect(),
+            ArpeggioPattern::Down => (0..size).rev().collect(),
+            ArpeggioPattern::UpDown => (0..size).chain((1..top).rev()).collect(),
+            // With fewer than three notes, there's no middle, so the ends alternate.
+            ArpeggioPattern::Alberti if size < 3 => vec![0, top, 0, top],
+            ArpeggioPattern::Alberti => vec![0, top, size / 2, top],
+            ArpeggioPattern::Travis if size < 3 => vec![0, top, 0, top],
+            // A lone treble note is picked after both bass notes.
+            ArpeggioPattern::Travis if size == 3 => vec![0, 2, 1, 2],
+            ArpeggioPattern::Travis => vec![0, top - 1, 1, top],
+        }
+    }
+}
+
+impl Voicing {
+    /// Plays the voicing one note at a time, in the order of `pattern`,
+    /// with each note lasting `duration`.
+    pub fn arpeggiate<'a>(&self, pattern: ArpeggioPattern, duration: Duration) -> Vec<RhythmicNotatedEvent<'a>> {
+        pattern.indices(self.len())
+            .into_iter()
+            .map(|i| RhythmicNotatedEvent::pitch(self[i], duration))
+            .collect()
+    }
+}
+
+impl<'a> FretboardShape<'a, Fretboard> {
+    /// Plays the shape's sounded strings one at a time, in the order of `pattern`,
+    /// with each note lasting `duration`.
+    ///
+    /// Strings are ordered from the bass string up, rather than by pitch,
+    /// so that the patterns fall on the same strings in re-entrant tunings.
+    pub fn arpeggiate(&self, pattern: ArpeggioPattern, duration: Duration) -> Vec<RhythmicNotatedEvent<'a>> {
+        let mut sounded: Vec<&SoundedNote<'a>> = self.fretted_notes
+            .iter()
+            .filter_map(|fretted_note| match fretted_note {
+                FrettedNote::Sounded(sounded_note) => Some(sounded_note),
+                FrettedNote::Muted { .. } => None,
+            })
+            .collect();
+        sounded.sort_by_key(|sounded_note| sounded_note.string);
+        pattern.indices(sounded.len())
+            .into_iter()
+            .map(|i| RhythmicNotatedEvent::fretted(sounded[i].clone(), duration))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::notation::rhythm::{NotatedEvent, SingleEvent};
+    use crate::note::{Note, Pitch};
+    use crate::{pitch, voicing};
+
+    fn pitches(events: &[RhythmicNotatedEvent]) -> Vec<String> {
+        events.iter()
+            .map(|event| match &event.event {
+                NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), _) => pitch.to_string(),
+                NotatedEvent::SingleEvent(SingleEvent::Fretted(note), _) => note.to_string(),
+                event => panic!("unexpected event {:?}", event),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arpeggios() {
+        let c_major = voicing!(pitch!(c, 4), pitch!(e, 4), pitch!(g, 4));
+        let arpeggiate = |pattern| pitches(&c_major.arpeggiate(pattern, Duration::EIGHTH));
+        assert_eq!(arpeggiate(ArpeggioPattern::Up), vec!["C4", "E4", "G4"]);
+        assert_eq!(arpeggiate(ArpeggioPattern::Down), vec!["G4", "E4", "C4"]);
+        assert_eq!(arpeggiate(ArpeggioPattern::UpDown), vec!["C4", "E4", "G4", "E4"]);
+        assert_eq!(arpeggiate(ArpeggioPattern::Alberti), vec!["C4", "G4", "E4", "G4"]);
+        assert!(c_major.arpeggiate(ArpeggioPattern::Up, Duration::EIGHTH)
+            .iter()
+            .all(|event| event.duration() == Duration::EIGHTH.ticks()));
+        assert!(Voicing::new(vec![]).arpeggiate(ArpeggioPattern::Alberti, Duration::QTR).is_empty());
+
+        // An open C chord, x-3-2-0-1-0.
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let c_shape = FretboardShape {
+            fretted_notes: vec![
+                FrettedNote::muted(0, fretboard).unwrap(),
+                FrettedNote::fretted(1, 3, fretboard).unwrap(),
+                FrettedNote::fretted(2, 2, fretboard).unwrap(),
+                FrettedNote::fretted(3, 0, fretboard).unwrap(),
+                FrettedNote::fretted(4, 1, fretboard).unwrap(),
+                FrettedNote::fretted(5, 0, fretboard).unwrap(),
+            ],
+            fretboard,
+        };
+        assert_eq!(
+            pitches(&c_shape.arpeggiate(ArpeggioPattern::Travis, Duration::EIGHTH)),
+            vec!["2:3(C)", "5:1(C)", "3:2(E)", "6:0(E)"],
+        );
+    }
+}
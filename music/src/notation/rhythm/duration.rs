@@ -1,5 +1,15 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+use crate::error::MusicSemanticsError;
+
 pub type DurationTicks = usize;
 
+/// The number of [DurationTicks] in a whole note.
+pub const TICKS_PER_WHOLE: DurationTicks = 128;
+
 /// Not every tick value can be represented as a singly notated durational symbol.
 /// For example, a five eighth-note duration (i.e. two-and-a-half beats) can't be represented
 /// except for two or more tied rhythmic values. In contrast, durations of four, six,
@@ -14,7 +24,7 @@ pub type DurationTicks = usize;
 /// The longest duration here is a double-whole-note, and the shortest duration
 /// is a 128th note. This range of values was chosen because it covers what Lilypond
 /// is capable of.
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum DurationKind {
     Breve,
     Whole,
@@ -59,8 +69,52 @@ impl Into<u32> for DurationKind {
     }
 }
 
+impl DurationKind {
+    /// Every kind, longest first.
+    pub const ALL: [DurationKind; 9] = [
+        DurationKind::Breve,
+        DurationKind::Whole,
+        DurationKind::Half,
+        DurationKind::Qtr,
+        DurationKind::Eighth,
+        DurationKind::Sixteenth,
+        DurationKind::ThirtySecond,
+        DurationKind::SixtyFourth,
+        DurationKind::OneTwentyEighth,
+    ];
+
+    /// The `n` of a 1/n note, e.g. 4 for a quarter note, or `None` for a breve.
+    pub fn denominator(&self) -> Option<u32> {
+        let ticks: u32 = (*self).into();
+        (ticks <= TICKS_PER_WHOLE as u32).then(|| TICKS_PER_WHOLE as u32 / ticks)
+    }
+
+    /// The duration as Lilypond writes it, e.g. "4" for a quarter note, or "\\breve".
+    pub fn lilypond_token(&self) -> String {
+        self.denominator().map_or("\\breve".to_string(), |denominator| denominator.to_string())
+    }
+}
+
+/// The American name, e.g. "quarter" or "sixteenth".
+impl Display for DurationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            DurationKind::Breve => "breve",
+            DurationKind::Whole => "whole",
+            DurationKind::Half => "half",
+            DurationKind::Qtr => "quarter",
+            DurationKind::Eighth => "eighth",
+            DurationKind::Sixteenth => "sixteenth",
+            DurationKind::ThirtySecond => "thirty-second",
+            DurationKind::SixtyFourth => "sixty-fourth",
+            DurationKind::OneTwentyEighth => "hundred twenty-eighth",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// A [DurationKind] potentially lengthened with zero to five dots.
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Duration {
     /// Zero to five dots, each of which augment the base value `d`
     /// according to the normal "geometric series" of `d/2 + d/4 + d/8 + ...`
@@ -96,17 +150,7 @@ impl Duration {
     /// If the passed number of ticks can be represented as an "atomic" musical duration,
     /// then this constructor returns such an instance.
     pub fn try_from_ticks(ticks: DurationTicks) -> Option<Self> {
-        for dur in [
-            DurationKind::Breve,
-            DurationKind::Whole,
-            DurationKind::Half,
-            DurationKind::Qtr,
-            DurationKind::Eighth,
-            DurationKind::Sixteenth,
-            DurationKind::ThirtySecond,
-            DurationKind::SixtyFourth,
-            DurationKind::OneTwentyEighth,
-        ] {
+        for dur in DurationKind::ALL {
             let dur_ticks: usize = dur.into();
             if dur_ticks <= ticks {
                 for dot in 0..6 {
@@ -124,6 +168,61 @@ impl Duration {
         None
     }
 
+    /// Like [Duration::try_from_ticks], but for an exact fraction of a whole note.
+    pub fn try_from_rational(duration: RationalDuration) -> Option<Self> {
+        for dur in DurationKind::ALL {
+            let base = RationalDuration::from(dur);
+            if base <= duration {
+                return (0..6)
+                    .map(|dot| Self { dot, dur })
+                    .find(|instance| instance.as_rational() == duration);
+            }
+        }
+        None
+    }
+
+    /// Splits any number of ticks into a series of durations, to be notated tied together.
+    ///
+    /// Durations are chosen greedily, longest first, from those with at most `max_dots` dots
+    /// that fit a whole number of ticks. Five eighth notes, for example, become a half note
+    /// tied to an eighth note, while seven eighth notes become a single double-dotted half
+    /// when `max_dots` is at least 2.
+    pub fn tied_from_ticks(ticks: DurationTicks, max_dots: u8) -> Vec<Self> {
+        let mut candidates: Vec<(DurationTicks, Self)> = DurationKind::ALL.iter()
+            .flat_map(|dur| (0..=max_dots.min(5)).map(|dot| Self { dot, dur: *dur }))
+            .filter_map(|instance| instance.as_rational().ticks().map(|ticks| (ticks, instance)))
+            .collect();
+        candidates.sort_by_key(|(ticks, _)| std::cmp::Reverse(*ticks));
+        let mut remaining = ticks;
+        let mut durations = vec![];
+        while remaining > 0 {
+            // A 128th note is always a candidate, so this always finds something.
+            let (candidate_ticks, candidate) = candidates.iter()
+                .find(|(candidate_ticks, _)| *candidate_ticks <= remaining)
+                .unwrap();
+            durations.push(*candidate);
+            remaining -= candidate_ticks;
+        }
+        durations
+    }
+
+    /// Splits any number of ticks into a series of durations, to be notated tied together,
+    /// with at most two dots each. See [Duration::tied_from_ticks].
+    /// The durations always add back up to `ticks`.
+    pub fn from_ticks(ticks: DurationTicks) -> Vec<Self> {
+        Self::tied_from_ticks(ticks, 2)
+    }
+
+    /// A plain duration with one dot.
+    pub fn dotted(dur: DurationKind) -> Self {
+        Self { dot: 1, dur }
+    }
+
+    /// A plain duration with two dots.
+    pub fn double_dotted(dur: DurationKind) -> Self {
+        Self { dot: 2, dur }
+    }
+
     pub fn new(dur: DurationKind, dot: u8) -> Self {
         Self { dot, dur }
     }
@@ -138,6 +237,9 @@ impl Duration {
 
     /// Returns the duration in beat "ticks", where one tick = a 128th note.
     /// This entails that 1 beat = 32 ticks.
+    ///
+    /// Dots too short to be counted in ticks are floored away.
+    /// See [Duration::as_rational] for the exact value.
     pub fn ticks(&self) -> usize {
         if self.dot == 0 {
             return self.dur.into();
@@ -145,10 +247,193 @@ impl Duration {
         let base_dur: u32 = self.dur.into();
         (0u32..self.dot as u32)
             .fold(base_dur, |acc, n| {
-                println!("{}", acc / 2u32.pow((1 + n).try_into().unwrap()));
                 acc + base_dur / 2u32.pow((n + 1).try_into().unwrap())
             }) as usize
     }
+
+    /// The duration as Lilypond writes it, e.g. "4." for a dotted quarter note.
+    pub fn lilypond_token(&self) -> String {
+        format!("{}{}", self.dur.lilypond_token(), ".".repeat(self.dot as usize))
+    }
+
+    /// The exact duration as a fraction of a whole note.
+    /// Each dot adds half of the value before it, so `n` dots lengthen
+    /// the base value by a factor of `2 - 1/2^n`.
+    pub fn as_rational(&self) -> RationalDuration {
+        let base = RationalDuration::from(self.dur);
+        let scale = 1u64 << self.dot;
+        base * (2 * scale - 1) / scale
+    }
+}
+
+const DOT_NAMES: [&str; 6] = ["", "dotted", "double-dotted", "triple-dotted", "quadruple-dotted", "quintuple-dotted"];
+
+/// e.g. "dotted quarter", or "double-dotted half".
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match DOT_NAMES.get(self.dot as usize) {
+            Some(&"") => write!(f, "{}", self.dur),
+            Some(dots) => write!(f, "{} {}", dots, self.dur),
+            None => write!(f, "{}-dotted {}", self.dot, self.dur),
+        }
+    }
+}
+
+/// Reads a duration as it's displayed, e.g. "dotted quarter".
+impl FromStr for Duration {
+    type Err = MusicSemanticsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (dot, kind) = DOT_NAMES.iter()
+            .enumerate()
+            .skip(1)
+            .find_map(|(dot, dots)| s.strip_prefix(dots)
+                .and_then(|rest| rest.strip_prefix(' '))
+                .map(|kind| (dot as u8, kind)))
+            .unwrap_or((0, s));
+        DurationKind::ALL.iter()
+            .find(|dur| dur.to_string() == kind)
+            .map(|dur| Self { dot, dur: *dur })
+            .ok_or(MusicSemanticsError::InvalidDuration(s.to_string()))
+    }
+}
+
+/// An exact length of musical time, as a fraction of a whole note.
+///
+/// Unlike [DurationTicks], this can represent any duration, including
+/// those that arise from deeply dotted short values or nested tuplets.
+/// The fraction is always kept in lowest terms.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct RationalDuration {
+    num: u64,
+    denom: u64,
+}
+
+impl RationalDuration {
+    pub const ZERO: Self = Self { num: 0, denom: 1 };
+
+    /// `num/denom` of a whole note.
+    ///
+    /// Panics if `denom` is zero.
+    pub fn new(num: u64, denom: u64) -> Self {
+        assert_ne!(denom, 0, "a duration can't have a zero denominator");
+        let divisor = gcd(num, denom);
+        Self { num: num / divisor, denom: denom / divisor }
+    }
+
+    pub fn from_ticks(ticks: DurationTicks) -> Self {
+        Self::new(ticks as u64, TICKS_PER_WHOLE as u64)
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.denom
+    }
+
+    /// The duration in [DurationTicks], if it is a whole number of them.
+    pub fn ticks(&self) -> Option<DurationTicks> {
+        let scaled = self.num * TICKS_PER_WHOLE as u64;
+        scaled.is_multiple_of(self.denom).then(|| (scaled / self.denom) as DurationTicks)
+    }
+
+    /// Subtraction that returns `None` rather than going below zero.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (a, b, denom) = self.over_common_denominator(&other);
+        Some(Self::new(a.checked_sub(b)?, denom))
+    }
+
+    fn over_common_denominator(&self, other: &Self) -> (u64, u64, u64) {
+        let denom = self.denom / gcd(self.denom, other.denom) * other.denom;
+        (self.num * (denom / self.denom), other.num * (denom / other.denom), denom)
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+impl Default for RationalDuration {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Display for RationalDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.num, self.denom)
+    }
+}
+
+impl From<DurationKind> for RationalDuration {
+    fn from(value: DurationKind) -> Self {
+        let ticks: DurationTicks = value.into();
+        Self::from_ticks(ticks)
+    }
+}
+
+impl From<Duration> for RationalDuration {
+    fn from(value: Duration) -> Self {
+        value.as_rational()
+    }
+}
+
+impl Add for RationalDuration {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (a, b, denom) = self.over_common_denominator(&rhs);
+        Self::new(a + b, denom)
+    }
+}
+
+/// Panics if the result would be negative. See [RationalDuration::checked_sub].
+impl Sub for RationalDuration {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).expect("a duration can't be negative")
+    }
+}
+
+impl Mul<u64> for RationalDuration {
+    type Output = Self;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        Self::new(self.num * rhs, self.denom)
+    }
+}
+
+impl Div<u64> for RationalDuration {
+    type Output = Self;
+
+    fn div(self, rhs: u64) -> Self::Output {
+        Self::new(self.num, self.denom.checked_mul(rhs).expect("duration denominator overflowed"))
+    }
+}
+
+impl Sum for RationalDuration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, item| acc + item)
+    }
+}
+
+impl PartialOrd for RationalDuration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RationalDuration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num as u128 * other.denom as u128).cmp(&(other.num as u128 * self.denom as u128))
+    }
 }
 
 
@@ -186,6 +471,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rational_durations() {
+        let dotted_qtr = Duration::dotted(DurationKind::Qtr);
+        assert_eq!(dotted_qtr.as_rational(), RationalDuration::new(3, 8));
+        assert_eq!(Duration::double_dotted(DurationKind::Half).as_rational().to_string(), "7/8");
+        // Exact where ticks are not.
+        let short = Duration::new(DurationKind::SixtyFourth, 2).as_rational();
+        assert_eq!(short, RationalDuration::new(7, 256));
+        assert_eq!(short.ticks(), None);
+
+        let sum: RationalDuration = [Duration::QTR, Duration::EIGHTH, Duration::EIGHTH]
+            .into_iter()
+            .map(RationalDuration::from)
+            .sum();
+        assert_eq!(sum, RationalDuration::new(1, 2));
+        assert_eq!(sum - dotted_qtr.into(), RationalDuration::new(1, 8));
+        assert_eq!(RationalDuration::new(1, 8).checked_sub(sum), None);
+        assert!(RationalDuration::new(1, 3) < RationalDuration::new(3, 8));
+        assert_eq!(RationalDuration::new(1, 4) * 3 / 2, dotted_qtr.as_rational());
+        assert_eq!(Duration::try_from_rational(RationalDuration::new(7, 16)),
+                   Some(Duration::double_dotted(DurationKind::Qtr)));
+        assert_eq!(Duration::try_from_rational(RationalDuration::new(5, 8)), None);
+    }
+
+    #[test]
+    fn tied_durations() {
+        // Five eighth notes
+        assert_eq!(
+            Duration::tied_from_ticks(80, 2),
+            vec![Duration::HALF, Duration::EIGHTH]
+        );
+        // Seven eighth notes
+        assert_eq!(
+            Duration::tied_from_ticks(112, 2),
+            vec![Duration::double_dotted(DurationKind::Half)]
+        );
+        assert_eq!(
+            Duration::tied_from_ticks(112, 0),
+            vec![Duration::HALF, Duration::QTR, Duration::EIGHTH]
+        );
+        assert_eq!(
+            Duration::tied_from_ticks(128 * 3 + 1, 1),
+            vec![Duration::new(DurationKind::Breve, 1), Duration::new(DurationKind::OneTwentyEighth, 0)]
+        );
+        assert!(Duration::tied_from_ticks(0, 2).is_empty());
+    }
+
+    #[test]
+    fn duration_names() {
+        assert_eq!(Duration::dotted(DurationKind::Qtr).to_string(), "dotted quarter");
+        assert_eq!(Duration::double_dotted(DurationKind::Half).to_string(), "double-dotted half");
+        assert_eq!(Duration::SIXTEENTH.to_string(), "sixteenth");
+        for dur in DurationKind::ALL {
+            for dot in 0..6 {
+                let duration = Duration::new(dur, dot);
+                assert_eq!(duration.to_string().parse::<Duration>().unwrap(), duration);
+            }
+        }
+        assert!("dotted crotchet".parse::<Duration>().is_err());
+
+        assert_eq!(Duration::dotted(DurationKind::Qtr).lilypond_token(), "4.");
+        assert_eq!(Duration::new(DurationKind::Breve, 0).lilypond_token(), "\\breve");
+        assert_eq!(DurationKind::OneTwentyEighth.denominator(), Some(128));
+
+        // Any number of ticks is split into durations that add back up to it.
+        assert_eq!(Duration::from_ticks(80), vec![Duration::HALF, Duration::EIGHTH]);
+        for ticks in 0..=4 * TICKS_PER_WHOLE {
+            assert_eq!(Duration::from_ticks(ticks).iter().map(Duration::ticks).sum::<DurationTicks>(), ticks);
+        }
+    }
+
     #[test]
     fn durations_from_ticks() {
         let d = Duration::try_from_ticks(32);
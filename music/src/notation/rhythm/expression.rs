@@ -0,0 +1,168 @@
+use crate::notation::rhythm::duration::DurationTicks;
+
+/// How an individual note is attacked and released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Articulation {
+    /// Short and detached.
+    Staccato,
+    /// Attacked louder than its surroundings.
+    Accent,
+    /// Held for its full value.
+    Tenuto,
+}
+
+/// A written dynamic level, from softest to loudest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dynamic {
+    PP,
+    P,
+    MP,
+    MF,
+    F,
+    FF,
+}
+
+impl Dynamic {
+    /// The MIDI note-on velocity conventionally used for this level.
+    pub fn velocity(&self) -> u8 {
+        match self {
+            Dynamic::PP => 33,
+            Dynamic::P => 49,
+            Dynamic::MP => 64,
+            Dynamic::MF => 80,
+            Dynamic::F => 96,
+            Dynamic::FF => 112,
+        }
+    }
+
+    /// The dynamic marking as written, e.g. "mf".
+    pub fn marking(&self) -> &'static str {
+        match self {
+            Dynamic::PP => "pp",
+            Dynamic::P => "p",
+            Dynamic::MP => "mp",
+            Dynamic::MF => "mf",
+            Dynamic::F => "f",
+            Dynamic::FF => "ff",
+        }
+    }
+}
+
+/// A gradual change of dynamics, beginning or ending on an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hairpin {
+    Crescendo,
+    Decrescendo,
+    /// Ends whichever hairpin is in progress.
+    End,
+}
+
+/// The start or end of a slur or phrase mark.
+/// Slurs join notes played legato, and phrase marks group them into musical sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phrasing {
+    SlurStart,
+    SlurEnd,
+    PhraseStart,
+    PhraseEnd,
+}
+
+/// A technique for playing a note on a fretted instrument.
+///
+/// Hammer-ons, pull-offs, and slides connect a note to the next one, which should be
+/// on the same string. The next note is sounded by the technique rather than picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Technique {
+    HammerOn,
+    PullOff,
+    Slide,
+    /// Bends the string until it sounds some number of semitones higher.
+    Bend { semitones: u8 },
+    Vibrato,
+    PalmMute,
+}
+
+impl Technique {
+    /// Whether the technique leads into the next note.
+    pub fn is_connecting(&self) -> bool {
+        matches!(self, Technique::HammerOn | Technique::PullOff | Technique::Slide)
+    }
+}
+
+/// The expressive markings attached to a [crate::notation::rhythm::RhythmicNotatedEvent].
+/// By default, an event has none.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expression {
+    pub articulations: Vec<Articulation>,
+    /// A new dynamic level, taking effect from this event onward.
+    pub dynamic: Option<Dynamic>,
+    pub hairpin: Option<Hairpin>,
+    pub phrasing: Vec<Phrasing>,
+    pub techniques: Vec<Technique>,
+}
+
+impl Expression {
+    /// The dynamic level assumed before any is marked.
+    pub const DEFAULT_DYNAMIC: Dynamic = Dynamic::MF;
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn has(&self, articulation: Articulation) -> bool {
+        self.articulations.contains(&articulation)
+    }
+
+    /// The technique connecting this note to the next, if any.
+    pub fn connection(&self) -> Option<Technique> {
+        self.techniques.iter().copied().find(Technique::is_connecting)
+    }
+
+    /// The MIDI velocity of the event, given the dynamic level in effect before it.
+    /// A dynamic marked on the event itself takes precedence, and an accent adds a step.
+    pub fn velocity(&self, prevailing: Option<Dynamic>) -> u8 {
+        let dynamic = self.dynamic.or(prevailing).unwrap_or(Self::DEFAULT_DYNAMIC);
+        let velocity = dynamic.velocity();
+        if self.has(Articulation::Accent) {
+            velocity.saturating_add(16).min(127)
+        } else {
+            velocity
+        }
+    }
+
+    /// How long the event actually sounds, out of its written duration.
+    /// Notes are normally released slightly early, staccato notes after half their value,
+    /// and tenuto, slurred, or otherwise connected notes are held for their full value.
+    pub fn sounding_ticks(&self, written: DurationTicks) -> DurationTicks {
+        if self.has(Articulation::Staccato) {
+            written / 2
+        } else if self.has(Articulation::Tenuto)
+            || self.phrasing.contains(&Phrasing::SlurStart)
+            || self.connection().is_some() {
+            written
+        } else {
+            written * 7 / 8
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midi_mapping() {
+        let plain = Expression::default();
+        assert_eq!(plain.velocity(None), 80);
+        assert_eq!(plain.velocity(Some(Dynamic::PP)), 33);
+        assert_eq!(plain.sounding_ticks(32), 28);
+
+        let accented = Expression {
+            articulations: vec![Articulation::Accent, Articulation::Staccato],
+            dynamic: Some(Dynamic::FF),
+            ..Default::default()
+        };
+        assert_eq!(accented.velocity(Some(Dynamic::PP)), 127);
+        assert_eq!(accented.sounding_ticks(32), 16);
+    }
+}
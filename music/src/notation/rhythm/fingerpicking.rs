@@ -0,0 +1,205 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::measure::{fill_measures, Measure};
+use crate::notation::rhythm::meter::Meter;
+
+/// A finger of the picking hand, as written p, i, m, and a in fingerstyle notation.
+///
+/// Fingers are assigned to the strings of a [FretboardShape] as the hand would usually fall:
+/// the thumb on the bass strings, and the other fingers on the three highest strings sounded,
+/// from the index finger on the lowest of them to the ring finger on the highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickingFinger {
+    /// The thumb on one of the bass strings, counting up from the lowest string sounded,
+    /// so that alternating `Thumb(0)` and `Thumb(1)` plays an alternating bass.
+    Thumb(u8),
+    Index,
+    Middle,
+    Ring,
+}
+
+impl PickingFinger {
+    /// Which of `size` sounded strings, counting up from the lowest, the finger plays.
+    fn string_index(&self, size: usize) -> usize {
+        let top = size - 1;
+        match self {
+            PickingFinger::Thumb(n) => usize::from(*n).min(top),
+            PickingFinger::Index => top.saturating_sub(2),
+            PickingFinger::Middle => top.saturating_sub(1),
+            PickingFinger::Ring => top,
+        }
+    }
+}
+
+/// The fingers that pluck together at one point in a [PickingPattern], and for how long.
+/// Plucking with no fingers is a rest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickingStep {
+    pub fingers: Vec<PickingFinger>,
+    pub duration: Duration,
+}
+
+/// A fingerstyle accompaniment pattern, repeated over each chord for a bar of its [Meter].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickingPattern {
+    pub meter: Meter,
+    pub steps: Vec<PickingStep>,
+}
+
+impl PickingPattern {
+    /// Errors unless the steps, repeated, exactly fill a bar of `meter`.
+    pub fn new(meter: Meter, steps: Vec<PickingStep>) -> Result<Self, MusicSemanticsError> {
+        let pattern = Self { meter, steps };
+        let (cycle, bar) = (pattern.cycle_ticks(), pattern.bar_ticks());
+        if cycle == 0 || bar % cycle != 0 {
+            return Err(MusicSemanticsError::PatternDoesNotFitMeasure(cycle, bar));
+        }
+        Ok(pattern)
+    }
+
+    /// Reads a pattern written as space-separated steps of `duration` each.
+    /// Each step names the fingers that pluck together, e.g. "pa" for a pinch of the thumb
+    /// and ring finger. A "p" may be followed by the bass string to play, as in [PickingFinger::Thumb],
+    /// and a "-" is a rest. For example, a Travis pattern in eighth notes is "p i p1 m".
+    pub fn from_template(
+        meter: Meter,
+        template: &str,
+        duration: Duration,
+    ) -> Result<Self, MusicSemanticsError> {
+        let steps = template.split_whitespace()
+            .map(|step| Ok(PickingStep { fingers: parse_fingers(step)?, duration }))
+            .collect::<Result<_, MusicSemanticsError>>()?;
+        Self::new(meter, steps)
+    }
+
+    fn cycle_ticks(&self) -> DurationTicks {
+        self.steps.iter().map(|step| step.duration.ticks()).sum()
+    }
+
+    fn bar_ticks(&self) -> DurationTicks {
+        let beat: Duration = (&self.meter.denominator).into();
+        beat.ticks() * self.meter.num_beats
+    }
+
+    /// One bar of the pattern, picked on the sounded strings of `shape`.
+    /// A shape with fewer strings than the pattern reaches for doubles up its fingers,
+    /// and a shape with no sounded strings gives a bar of rests.
+    pub fn pick<'a>(&self, shape: &FretboardShape<'a>) -> Vec<RhythmicNotatedEvent<'a>> {
+        let mut sounded: Vec<&SoundedNote<'a>> = shape.fretted_notes
+            .iter()
+            .filter_map(|fretted_note| match fretted_note {
+                FrettedNote::Sounded(sounded_note) => Some(sounded_note),
+                FrettedNote::Muted { .. } => None,
+            })
+            .collect();
+        sounded.sort_by_key(|sounded_note| sounded_note.string);
+        let cycles = self.bar_ticks() / self.cycle_ticks();
+        (0..cycles)
+            .flat_map(|_| self.steps.iter())
+            .map(|step| {
+                let mut strings: Vec<usize> = if sounded.is_empty() {
+                    vec![]
+                } else {
+                    step.fingers.iter().map(|finger| finger.string_index(sounded.len())).collect()
+                };
+                strings.sort();
+                strings.dedup();
+                match strings[..] {
+                    [] => RhythmicNotatedEvent::rest(step.duration),
+                    [string] => RhythmicNotatedEvent::fretted(sounded[string].clone(), step.duration),
+                    _ => RhythmicNotatedEvent::fretted_many(
+                        strings.iter().map(|string| sounded[*string].clone()).collect(),
+                        step.duration,
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// A bar of the pattern over each of `shapes` in turn, e.g. the chords of a progression,
+    /// ready to be written out as tab.
+    pub fn accompany<'m, 'a>(
+        &'m self,
+        shapes: &[FretboardShape<'a>],
+    ) -> Result<Vec<Measure<'m, 'a>>, MusicSemanticsError> {
+        fill_measures(&self.meter, shapes.iter().flat_map(|shape| self.pick(shape)).collect())
+    }
+}
+
+/// The fingers of one step of a template, as in [PickingPattern::from_template].
+fn parse_fingers(step: &str) -> Result<Vec<PickingFinger>, MusicSemanticsError> {
+    if step == "-" {
+        return Ok(vec![]);
+    }
+    let mut fingers = vec![];
+    let mut chars = step.chars().peekable();
+    while let Some(c) = chars.next() {
+        let finger = match c.to_ascii_lowercase() {
+            'p' => {
+                let string = chars.next_if(char::is_ascii_digit)
+                    .and_then(|digit| digit.to_digit(10))
+                    .unwrap_or(0);
+                PickingFinger::Thumb(string as u8)
+            },
+            'i' => PickingFinger::Index,
+            'm' => PickingFinger::Middle,
+            'a' => PickingFinger::Ring,
+            _ => return Err(MusicSemanticsError::InvalidPickingPattern(step.to_string())),
+        };
+        fingers.push(finger);
+    }
+    Ok(fingers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::{Fretboard, STD_6STR_GTR};
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::notation::vextab::ToVexTab;
+
+    fn shape<'a>(frets: &[Option<u8>], fretboard: &'a Fretboard) -> FretboardShape<'a> {
+        FretboardShape {
+            fretted_notes: frets.iter()
+                .enumerate()
+                .map(|(string, fret)| match fret {
+                    Some(fret) => FrettedNote::fretted(string as u8, *fret, fretboard).unwrap(),
+                    None => FrettedNote::muted(string as u8, fretboard).unwrap(),
+                })
+                .collect(),
+            fretboard,
+        }
+    }
+
+    #[test]
+    fn picking_patterns() {
+        let fretboard: &Fretboard = &STD_6STR_GTR;
+        let common_time = Meter::new(4, MeterDenominator::Four, None);
+        let travis = PickingPattern::from_template(common_time.clone(), "pa i p1 m", Duration::EIGHTH).unwrap();
+        assert_eq!(travis.steps[2].fingers, vec![PickingFinger::Thumb(1)]);
+        let c = shape(&[None, Some(3), Some(2), Some(0), Some(1), Some(0)], fretboard);
+        let g = shape(&[Some(3), Some(2), Some(0), Some(0), Some(0), Some(3)], fretboard);
+        let measures = travis.accompany(&[c, g]).unwrap();
+        assert_eq!(measures.len(), 2);
+        assert!(measures.iter().all(Measure::is_full));
+        assert_eq!(
+            measures[0].events().to_vec().to_vextab(),
+            ":8 (3/5.0/1) :8 0/3 :8 2/4 :8 1/2 :8 (3/5.0/1) :8 0/3 :8 2/4 :8 1/2",
+        );
+        assert_eq!(measures[1].events()[..4].to_vec().to_vextab(), ":8 (3/6.3/1) :8 0/3 :8 2/5 :8 0/2");
+
+        // Fingers double up on a shape with too few strings for them.
+        let dyad = shape(&[None, Some(3), Some(2), None, None, None], fretboard);
+        let pinch = PickingPattern::from_template(common_time.clone(), "pima - - -", Duration::QTR).unwrap();
+        assert_eq!(pinch.pick(&dyad).to_vextab(), ":q (3/5.2/4) :q ## :q ## :q ##");
+
+        assert!(matches!(
+            PickingPattern::from_template(common_time.clone(), "p i m", Duration::QTR),
+            Err(MusicSemanticsError::PatternDoesNotFitMeasure(96, 128)),
+        ));
+        assert!(PickingPattern::from_template(common_time, "p x", Duration::HALF).is_err());
+    }
+}
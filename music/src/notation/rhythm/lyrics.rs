@@ -0,0 +1,146 @@
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent};
+
+/// A single sung syllable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Syllable {
+    pub text: String,
+    /// Whether the word continues into the next syllable, e.g. the "A" of "A-ma-zing".
+    pub hyphenated: bool,
+    /// The number of further notes the syllable is held over, as a melisma.
+    pub melisma: usize,
+}
+
+impl Syllable {
+    pub fn new(text: &str) -> Self {
+        Self { text: text.to_string(), hyphenated: false, melisma: 0 }
+    }
+}
+
+/// Where a lyric lines up with an event, as given by [Lyrics::align].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LyricPlacement<'l> {
+    /// The event begins a syllable.
+    Syllable(&'l Syllable),
+    /// The event continues the syllable before it, as part of a melisma or a tie.
+    Continuation,
+    /// The event is a rest, and takes no lyric.
+    Rest,
+}
+
+/// A line of lyrics, sung one syllable per articulated note.
+///
+/// Tied notes never begin a new syllable. Otherwise, each syllable is sung on
+/// the next note, and holds on through as many more as its [Syllable::melisma].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lyrics(pub Vec<Syllable>);
+
+impl Lyrics {
+    /// Parses lyrics in the style of Lilypond's lyric mode. Words are separated by spaces,
+    /// `--` joins the syllables of a word, and each `_` holds the previous syllable
+    /// over one more note. A `__` extender line is implied by any melisma, and is ignored.
+    ///
+    /// For example, `"A -- ma -- zing grace _ how sweet"`.
+    pub fn parse(text: &str) -> Self {
+        let mut syllables: Vec<Syllable> = vec![];
+        for token in text.split_whitespace() {
+            match (token, syllables.last_mut()) {
+                ("--", Some(last)) => last.hyphenated = true,
+                ("_", Some(last)) => last.melisma += 1,
+                ("__", _) => {},
+                _ => syllables.push(Syllable::new(token)),
+            }
+        }
+        Self(syllables)
+    }
+
+    /// Pairs each event, looking inside tuplets, with its place in the lyrics.
+    /// Errors if there are syllables left over once the notes run out.
+    pub fn align<'l>(
+        &'l self,
+        events: &[RhythmicNotatedEvent],
+    ) -> Result<Vec<LyricPlacement<'l>>, MusicSemanticsError> {
+        let mut syllables = self.0.iter();
+        let mut held_over = 0;
+        let placements = flatten(events).into_iter()
+            .map(|event| {
+                if is_rest(event) {
+                    return LyricPlacement::Rest;
+                }
+                if event.tied {
+                    return LyricPlacement::Continuation;
+                }
+                if held_over > 0 {
+                    held_over -= 1;
+                    return LyricPlacement::Continuation;
+                }
+                match syllables.next() {
+                    Some(syllable) => {
+                        held_over = syllable.melisma;
+                        LyricPlacement::Syllable(syllable)
+                    },
+                    None => LyricPlacement::Continuation,
+                }
+            })
+            .collect();
+        let left_over = syllables.count();
+        if left_over > 0 {
+            return Err(MusicSemanticsError::UnalignedLyrics(left_over));
+        }
+        Ok(placements)
+    }
+}
+
+fn flatten<'e, 'a>(events: &'e [RhythmicNotatedEvent<'a>]) -> Vec<&'e RhythmicNotatedEvent<'a>> {
+    events.iter()
+        .flat_map(|event| match &event.event {
+            NotatedEvent::SingleEvent(..) => vec![event],
+            NotatedEvent::Tuplet(tuplet) => flatten(&tuplet.events),
+        })
+        .collect()
+}
+
+fn is_rest(event: &RhythmicNotatedEvent) -> bool {
+    matches!(event.event, NotatedEvent::SingleEvent(SingleEvent::Rest, _))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::Duration;
+    use crate::note::note::Note;
+    use crate::note::pitch::Pitch;
+
+    #[test]
+    fn lyric_alignment() {
+        let lyrics = Lyrics::parse("A -- ma -- zing grace _");
+        assert_eq!(lyrics.0.len(), 4);
+        assert!(lyrics.0[1].hyphenated);
+        assert_eq!(lyrics.0[3].melisma, 1);
+
+        let c = Pitch::new(Note::C, 4).unwrap();
+        let events = vec![
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch_tied(c, Duration::QTR),
+            RhythmicNotatedEvent::rest(Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+            RhythmicNotatedEvent::pitch(c, Duration::QTR),
+        ];
+        let placements = lyrics.align(&events).unwrap();
+        assert_eq!(placements, vec![
+            LyricPlacement::Syllable(&lyrics.0[0]),
+            LyricPlacement::Syllable(&lyrics.0[1]),
+            LyricPlacement::Continuation,
+            LyricPlacement::Rest,
+            LyricPlacement::Syllable(&lyrics.0[2]),
+            LyricPlacement::Syllable(&lyrics.0[3]),
+            LyricPlacement::Continuation,
+        ]);
+        assert!(matches!(
+            lyrics.align(&events[..3]),
+            Err(MusicSemanticsError::UnalignedLyrics(2)),
+        ));
+    }
+}
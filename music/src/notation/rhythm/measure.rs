@@ -0,0 +1,229 @@
+use std::collections::VecDeque;
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent};
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::expression::Expression;
+use crate::notation::rhythm::meter::Meter;
+
+/// Where an event begins within a [Measure].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeatPosition {
+    /// The beat of the measure, counting from zero.
+    pub beat: usize,
+    /// How far past the start of the beat, in [DurationTicks].
+    pub offset: DurationTicks,
+}
+
+/// A single bar of [RhythmicNotatedEvent]s, which may never hold more time than its [Meter] allows.
+///
+/// All durations here are measured in [DurationTicks], where a quarter note is 32 ticks.
+#[derive(Debug, Clone)]
+pub struct Measure<'m, 'a> {
+    meter: &'m Meter,
+    events: Vec<RhythmicNotatedEvent<'a>>,
+}
+
+impl<'m, 'a> Measure<'m, 'a> {
+    pub fn new(meter: &'m Meter) -> Self {
+        Self { meter, events: vec![] }
+    }
+
+    pub fn meter(&self) -> &Meter {
+        self.meter
+    }
+
+    pub fn events(&self) -> &[RhythmicNotatedEvent<'a>] {
+        &self.events
+    }
+
+    pub fn into_events(self) -> Vec<RhythmicNotatedEvent<'a>> {
+        self.events
+    }
+
+    /// The length of one beat, as given by the denominator of the time signature.
+    pub fn beat_ticks(&self) -> DurationTicks {
+        let beat: Duration = (&self.meter.denominator).into();
+        beat.ticks()
+    }
+
+    /// The total length of the measure.
+    pub fn capacity(&self) -> DurationTicks {
+        self.beat_ticks() * self.meter.num_beats
+    }
+
+    /// The length of the events in the measure so far.
+    pub fn filled(&self) -> DurationTicks {
+        self.events.iter().map(RhythmicNotatedEvent::duration).sum()
+    }
+
+    pub fn remaining(&self) -> DurationTicks {
+        self.capacity() - self.filled()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Adds an event to the end of the measure, so long as it fits.
+    pub fn push(&mut self, event: RhythmicNotatedEvent<'a>) -> Result<(), MusicSemanticsError> {
+        let remaining = self.remaining();
+        if event.duration() > remaining {
+            return Err(MusicSemanticsError::MeasureOverflow(event.duration(), remaining));
+        }
+        self.events.push(event);
+        Ok(())
+    }
+
+    /// Adds an event to the end of the measure, splitting it at the barline if it doesn't fit.
+    ///
+    /// The part that fits is added, notated as tied durations where necessary. The part that
+    /// doesn't is returned, tied over, to begin the next measure. Tuplets can't be split, so
+    /// a tuplet that doesn't fit is an error.
+    pub fn push_tied(
+        &mut self,
+        event: RhythmicNotatedEvent<'a>,
+    ) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+        let remaining = self.remaining();
+        let duration = event.duration();
+        if duration <= remaining {
+            self.events.push(event);
+            return Ok(vec![]);
+        }
+        if remaining == 0 {
+            return Ok(vec![event]);
+        }
+        let single_event = match &event.event {
+            NotatedEvent::SingleEvent(single_event, _) => single_event,
+            NotatedEvent::Tuplet(_) => {
+                return Err(MusicSemanticsError::MeasureOverflow(duration, remaining));
+            },
+        };
+        let mut pieces = Duration::from_ticks(remaining).into_iter()
+            .chain(Duration::from_ticks(duration - remaining))
+            .enumerate()
+            .map(|(i, piece)| RhythmicNotatedEvent {
+                // Rests are never tied.
+                tied: if i == 0 {
+                    event.tied
+                } else {
+                    !matches!(single_event, SingleEvent::Rest)
+                },
+                event: NotatedEvent::SingleEvent(single_event.clone(), piece),
+                // Markings belong to the attack, so they stay with the first piece.
+                expression: if i == 0 {
+                    event.expression.clone()
+                } else {
+                    Expression::default()
+                },
+            })
+            .collect::<VecDeque<_>>();
+        while self.remaining() > 0 {
+            self.events.push(pieces.pop_front().unwrap());
+        }
+        Ok(pieces.into())
+    }
+
+    /// Where each event begins, in the same order as [Measure::events].
+    pub fn positions(&self) -> Vec<BeatPosition> {
+        let beat_ticks = self.beat_ticks();
+        let mut onset = 0;
+        self.events.iter()
+            .map(|event| {
+                let position = BeatPosition {
+                    beat: onset / beat_ticks,
+                    offset: onset % beat_ticks,
+                };
+                onset += event.duration();
+                position
+            })
+            .collect()
+    }
+}
+
+/// Lays a series of events out into as many measures as they need, tying any event
+/// that crosses a barline into the next measure. The last measure may be left partly empty.
+pub fn fill_measures<'m, 'a>(
+    meter: &'m Meter,
+    events: Vec<RhythmicNotatedEvent<'a>>,
+) -> Result<Vec<Measure<'m, 'a>>, MusicSemanticsError> {
+    let mut measures = vec![Measure::new(meter)];
+    let mut pending: VecDeque<RhythmicNotatedEvent<'a>> = events.into();
+    while let Some(event) = pending.pop_front() {
+        let measure = measures.last_mut().unwrap();
+        if measure.is_full() {
+            measures.push(Measure::new(meter));
+            pending.push_front(event);
+            continue;
+        }
+        for carried in measure.push_tied(event)?.into_iter().rev() {
+            pending.push_front(carried);
+        }
+    }
+    Ok(measures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::Tuplet;
+    use crate::notation::rhythm::duration::DurationKind;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::note::Note;
+    use crate::note::pitch::Pitch;
+
+    fn pitch(duration: Duration) -> RhythmicNotatedEvent<'static> {
+        RhythmicNotatedEvent::pitch(Pitch::new(Note::C, 4).unwrap(), duration)
+    }
+
+    #[test]
+    fn measure_overflow() {
+        let meter = Meter::new(3, MeterDenominator::Four, None);
+        let mut measure = Measure::new(&meter);
+        measure.push(pitch(Duration::HALF)).unwrap();
+        assert!(matches!(
+            measure.push(pitch(Duration::HALF)),
+            Err(MusicSemanticsError::MeasureOverflow(64, 32)),
+        ));
+        measure.push(pitch(Duration::QTR)).unwrap();
+        assert!(measure.is_full());
+        assert_eq!(
+            measure.positions(),
+            vec![BeatPosition { beat: 0, offset: 0 }, BeatPosition { beat: 2, offset: 0 }],
+        );
+    }
+
+    #[test]
+    fn ties_across_barlines() {
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let events = vec![
+            pitch(Duration::dotted(DurationKind::Half)),
+            pitch(Duration::HALF),
+            RhythmicNotatedEvent::rest(Duration::new(DurationKind::Breve, 0)),
+        ];
+        let measures = fill_measures(&meter, events).unwrap();
+        assert_eq!(measures.len(), 4);
+        let durations: Vec<Vec<(bool, DurationTicks)>> = measures.iter()
+            .map(|measure| measure.events().iter()
+                .map(|event| (event.tied, event.duration()))
+                .collect())
+            .collect();
+        assert_eq!(durations, vec![
+            vec![(false, 96), (false, 32)],
+            vec![(true, 32), (false, 96)],
+            vec![(false, 128)],
+            vec![(false, 32)],
+        ]);
+        assert_eq!(measures[1].positions()[1], BeatPosition { beat: 1, offset: 0 });
+
+        // Tuplets can't be split.
+        let mut measure = Measure::new(&meter);
+        measure.push(pitch(Duration::dotted(DurationKind::Half))).unwrap();
+        let triplet = Tuplet::new(
+            vec![pitch(Duration::QTR), pitch(Duration::QTR), pitch(Duration::QTR)],
+            3,
+            2,
+            DurationKind::Qtr,
+        );
+        assert!(measure.push_tied(triplet.into()).is_err());
+    }
+}
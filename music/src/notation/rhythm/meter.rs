@@ -62,6 +62,7 @@ pub fn big_beats_to_durations(
 }
 
 /// The only valid units in the denominator of a time signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MeterDenominator {
     /// Whole-note gets the beat.
     One,
@@ -118,6 +119,7 @@ impl Into<Duration> for &MeterDenominator {
 /// with a bias toward the wider and more evenly spaced beats in the heirarchy.
 /// This is the origin of the term "big beat", and it can be thought of as a kind of
 /// rhythmic middle-ground between that of the measure as a whole, and the beat grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Meter {
     /// Numerator of a time signature, as is.
     pub num_beats: usize,
@@ -0,0 +1,161 @@
+use std::cmp::Ordering;
+use itertools::Itertools;
+use crate::notation::midi::MetricPosition;
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+use crate::notation::rhythm::quantize::QuantizedNote;
+
+/// The number of points of the grid the accents of a performance are laid out on in a whole note,
+/// i.e. sixteenth notes.
+const GRID_PER_WHOLE: usize = 16;
+
+/// The meters [meter_candidates] chooses between, with the big beats of those that can be
+/// grouped more than one way, in ticks of their [MeterDenominator].
+fn candidate_meters() -> Vec<Meter> {
+    vec![
+        Meter::new(4, MeterDenominator::Four, None),
+        Meter::new(3, MeterDenominator::Four, None),
+        Meter::new(2, MeterDenominator::Four, None),
+        Meter::new(6, MeterDenominator::Eight, None),
+        Meter::new(12, MeterDenominator::Eight, None),
+        Meter::new(9, MeterDenominator::Eight, None),
+        Meter::new(3, MeterDenominator::Eight, Some(vec![0])),
+        // 3 + 2, and 2 + 3.
+        Meter::new(5, MeterDenominator::Four, Some(vec![0, 24])),
+        Meter::new(5, MeterDenominator::Four, Some(vec![0, 16])),
+        // 2 + 2 + 3, and 3 + 2 + 2.
+        Meter::new(7, MeterDenominator::Eight, Some(vec![0, 8, 16])),
+        Meter::new(7, MeterDenominator::Eight, Some(vec![0, 12, 20])),
+    ]
+}
+
+/// The most plausible meter of a performance, from where its notes fall and how they're accented,
+/// so that a time signature doesn't have to be given to write it down. See [meter_candidates].
+///
+/// Returns `None` if there are too few notes to tell, i.e. fewer than two bars of any meter.
+pub fn infer_meter(notes: &[QuantizedNote]) -> Option<Meter> {
+    meter_candidates(notes).into_iter().next().map(|(meter, _)| meter)
+}
+
+/// Common meters, from the most plausible for a performance to the least, each with a score.
+/// The performance is taken to start on a downbeat.
+///
+/// The notes are laid out as accents on a grid of sixteenth notes, each as strong as it's loud,
+/// and stronger the longer it lasts, up to a whole note. A meter scores for how much the accents
+/// repeat every bar, by their autocorrelation a bar apart, and for how closely the accents of
+/// an average bar are ordered as the stress of the meter, i.e. strongest on the downbeat, then on
+/// the other big beats, then on the other beats, see [MetricPosition].
+pub fn meter_candidates(notes: &[QuantizedNote]) -> Vec<(Meter, f64)> {
+    let accents = accent_grid(notes);
+    let mut candidates: Vec<(Meter, f64)> = candidate_meters().into_iter()
+        .filter_map(|meter| {
+            let beat: Duration = (&meter.denominator).into();
+            let bar = beat.ticks() * meter.num_beats * GRID_PER_WHOLE / Duration::WHOLE.ticks();
+            if accents.len() < 2 * bar {
+                return None;
+            }
+            // The cosine of the accents and the accents a bar later, so that a longer bar isn't favoured.
+            let (earlier, later) = (&accents[..accents.len() - bar], &accents[bar..]);
+            let energy = |series: &[f64]| series.iter().map(|accent| accent * accent).sum::<f64>().sqrt();
+            let periodicity = match energy(earlier) * energy(later) {
+                0.0 => 0.0,
+                energies => earlier.iter().zip(later).map(|(a, b)| a * b).sum::<f64>() / energies,
+            };
+            let profile: Vec<f64> = (0..bar)
+                .map(|phase| {
+                    let at_phase: Vec<f64> = accents.iter().skip(phase).step_by(bar).copied().collect();
+                    at_phase.iter().sum::<f64>() / at_phase.len() as f64
+                })
+                .collect();
+            let positions: Vec<MetricPosition> = (0..bar)
+                .map(|point| MetricPosition::of(&meter, point * Duration::SIXTEENTH.ticks()))
+                .collect();
+            Some((meter, periodicity + concordance(&profile, &positions)))
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+    candidates
+}
+
+/// The accent at each sixteenth note from the start, with each note moved to the nearest.
+fn accent_grid(notes: &[QuantizedNote]) -> Vec<f64> {
+    let point = |note: &QuantizedNote| {
+        (note.start.numerator() as f64 * GRID_PER_WHOLE as f64 / note.start.denominator() as f64).round() as usize
+    };
+    let mut accents = vec![0.0; notes.iter().map(|note| point(note) + 1).max().unwrap_or(0)];
+    for note in notes {
+        let duration = note.duration();
+        let wholes = (duration.numerator() as f64 / duration.denominator() as f64).min(1.0);
+        accents[point(note)] += f64::from(note.velocity) / 127.0 * (1.0 + 4.0 * wholes);
+    }
+    accents
+}
+
+/// How far the accents of a bar are ordered as the stress of where they fall, from -1 to 1.
+/// Of each pair of points of the bar that differ in stress, the pair counts for the meter if the
+/// more stressed point is more strongly accented, and against it if it's less, as by Kendall's tau.
+fn concordance(profile: &[f64], positions: &[MetricPosition]) -> f64 {
+    let (mut total, mut pairs) = (0.0, 0);
+    for (a, b) in (0..profile.len()).tuple_combinations() {
+        if positions[a] == positions[b] {
+            continue;
+        }
+        pairs += 1;
+        // [MetricPosition]s are ordered from the most stressed.
+        let (stronger, weaker) = if positions[a] < positions[b] { (a, b) } else { (b, a) };
+        total += match profile[stronger].total_cmp(&profile[weaker]) {
+            Ordering::Greater => 1.0,
+            Ordering::Less => -1.0,
+            Ordering::Equal => 0.0,
+        };
+    }
+    match pairs {
+        0 => 0.0,
+        pairs => total / pairs as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::RationalDuration;
+
+    /// Notes at the given sixteenth notes, with their velocities, each lasting until the next.
+    fn performance(bars: usize, bar: usize, accents: &[(usize, u8)]) -> Vec<QuantizedNote> {
+        (0..bars)
+            .flat_map(|n| accents.iter().enumerate().map(move |(i, (point, velocity))| {
+                let end = accents.get(i + 1).map(|(next, _)| *next).unwrap_or(bar);
+                QuantizedNote {
+                    midi_note: 60,
+                    velocity: *velocity,
+                    start: RationalDuration::new((n * bar + point) as u64, 16),
+                    end: RationalDuration::new((n * bar + end) as u64, 16),
+                }
+            }))
+            .collect()
+    }
+
+    #[test]
+    fn inferred_meters() {
+        let signature = |meter: Meter| (meter.num_beats, meter.denominator, meter.beat_pattern);
+        // A waltz: a long, loud bass note, and two soft chords.
+        let waltz = performance(8, 12, &[(0, 110), (4, 60), (8, 60)]);
+        assert_eq!(signature(infer_meter(&waltz).unwrap()), (3, MeterDenominator::Four, vec![8, 8, 8]));
+
+        // A jig, in two groups of three eighth notes.
+        let jig = performance(8, 12, &[(0, 110), (2, 60), (4, 60), (6, 90), (8, 60), (10, 60)]);
+        assert_eq!(signature(infer_meter(&jig).unwrap()), (6, MeterDenominator::Eight, vec![12, 12]));
+
+        // Quarter notes, strongest on the first beat of the bar and then on the third.
+        let march = performance(4, 16, &[(0, 120), (4, 60), (8, 90), (12, 60)]);
+        assert_eq!(signature(infer_meter(&march).unwrap()), (4, MeterDenominator::Four, vec![16, 16]));
+
+        // Seven eighth notes grouped 2 + 2 + 3.
+        let seven = performance(6, 14, &[(0, 120), (2, 50), (4, 90), (6, 50), (8, 90), (10, 50), (12, 50)]);
+        assert_eq!(signature(infer_meter(&seven).unwrap()), (7, MeterDenominator::Eight, vec![8, 8, 12]));
+
+        // Too short to tell.
+        assert!(infer_meter(&performance(1, 16, &[(0, 100)])).is_none());
+        assert!(infer_meter(&[]).is_none());
+    }
+}
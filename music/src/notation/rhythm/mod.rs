@@ -1,85 +1,143 @@
 use duration::Duration;
 use crate::notation::rhythm::duration::{DurationKind, DurationTicks};
+use crate::notation::rhythm::expression::{Articulation, Dynamic, Expression, Hairpin, Phrasing, Technique};
 use crate::note::pitch::Pitch;
 use crate::note_collections::voicing::Voicing;
 use crate::SoundedNote;
 
+pub mod arpeggio;
 pub mod duration;
+pub mod expression;
+pub mod fingerpicking;
+pub mod lyrics;
+pub mod measure;
 pub mod meter;
+pub mod meter_inference;
+pub mod percussion;
+pub mod quantize;
+pub mod tempo;
+pub mod transform;
 
 /// A pitch or voicing with a rhythmic duration.
+#[derive(Debug, Clone)]
 pub struct RhythmicNotatedEvent<'a> {
     /// Whether the event is tied to a previous event, and thus
     /// would not be articulated.
     pub tied: bool,
     /// The data representing the notated event.
     pub event: NotatedEvent<'a>,
+    /// Articulations, dynamics, and slurs marked on the event.
+    pub expression: Expression,
 }
 
 impl<'a> RhythmicNotatedEvent<'a> {
     pub fn pitch(pitch: Pitch, duration: Duration) -> Self {
         Self {
             tied: false,
-            event: NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn pitch_tied(pitch: Pitch, duration: Duration) -> Self {
         Self {
             tied: true,
-            event: NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn voicing(voicing: Voicing, duration: Duration) -> Self {
         Self {
             tied: false,
-            event: NotatedEvent::SingleEvent(SingleEvent::Voicing(voicing), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Voicing(voicing), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn voicing_tied(voicing: Voicing, duration: Duration) -> Self {
         Self {
             tied: true,
-            event: NotatedEvent::SingleEvent(SingleEvent::Voicing(voicing), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Voicing(voicing), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn rest(duration: Duration) -> Self {
         Self {
             tied: false,
-            event: NotatedEvent::SingleEvent(SingleEvent::Rest, duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Rest, duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn fretted(sounded_note: SoundedNote<'a>, duration: Duration) -> Self {
         Self {
             tied: false,
-            event: NotatedEvent::SingleEvent(SingleEvent::Fretted(sounded_note), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Fretted(sounded_note), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn fretted_tied(sounded_note: SoundedNote<'a>, duration: Duration) -> Self {
         Self {
             tied: true,
-            event: NotatedEvent::SingleEvent(SingleEvent::Fretted(sounded_note), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::Fretted(sounded_note), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn fretted_many(notes: Vec<SoundedNote<'a>>, duration: Duration) -> Self {
         Self {
             tied: false,
-            event: NotatedEvent::SingleEvent(SingleEvent::FrettedMany(notes), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::FrettedMany(notes), duration),
+            expression: Expression::default(),
         }
     }
 
     pub fn fretted_many_tied(notes: Vec<SoundedNote<'a>>, duration: Duration) -> Self {
         Self {
             tied: true,
-            event: NotatedEvent::SingleEvent(SingleEvent::FrettedMany(notes), duration)
+            event: NotatedEvent::SingleEvent(SingleEvent::FrettedMany(notes), duration),
+            expression: Expression::default(),
         }
     }
 
+    pub fn with_articulation(mut self, articulation: Articulation) -> Self {
+        self.expression.articulations.push(articulation);
+        self
+    }
+
+    pub fn with_dynamic(mut self, dynamic: Dynamic) -> Self {
+        self.expression.dynamic = Some(dynamic);
+        self
+    }
+
+    pub fn with_hairpin(mut self, hairpin: Hairpin) -> Self {
+        self.expression.hairpin = Some(hairpin);
+        self
+    }
+
+    pub fn with_phrasing(mut self, phrasing: Phrasing) -> Self {
+        self.expression.phrasing.push(phrasing);
+        self
+    }
+
+    pub fn with_technique(mut self, technique: Technique) -> Self {
+        self.expression.techniques.push(technique);
+        self
+    }
+
+    /// The MIDI velocity of the event. See [Expression::velocity].
+    pub fn midi_velocity(&self, prevailing: Option<Dynamic>) -> u8 {
+        self.expression.velocity(prevailing)
+    }
+
+    /// How long the event actually sounds when performed. See [Expression::sounding_ticks].
+    pub fn sounding_duration(&self) -> DurationTicks {
+        self.expression.sounding_ticks(self.duration())
+    }
+
     /// The total duration of the event. In the case of a tuplet, this returns
     /// the real duration (i.e. quarter-note triplets would return 2 beats of ticks).
     pub fn duration(&self) -> DurationTicks {
@@ -92,6 +150,7 @@ impl<'a> RhythmicNotatedEvent<'a> {
 
 /// A composition over single events and tuplets. You should never need to interact
 /// with this type directly.
+#[derive(Debug, Clone)]
 pub enum NotatedEvent<'a> {
     SingleEvent(SingleEvent<'a>, Duration),
     Tuplet(Tuplet<'a>),
@@ -99,6 +158,7 @@ pub enum NotatedEvent<'a> {
 
 /// A wrapper over the various musical events that can be engraved
 /// after pairing with a duration.
+#[derive(Debug, Clone)]
 pub enum SingleEvent<'a> {
     /// Single note, no fretboard information
     Pitch(Pitch),
@@ -123,6 +183,7 @@ pub enum SingleEvent<'a> {
 /// Usually the ratio is implied for the most common tuplets. Triplets are a 3/2 ratio,
 /// and we speak of "eighth note triplets" to denote the magnitude. Similarly,
 /// quintuplets are a 5/4 ratio, and we speak of "quarter-note quintuplets" and so forth.
+#[derive(Debug, Clone)]
 pub struct Tuplet<'a> {
     /// A series of rhythmic events that reside inside the tuplet.
     /// Tuplets can be nested.
@@ -197,7 +258,8 @@ impl<'a> Into<RhythmicNotatedEvent<'a>> for Tuplet<'a> {
     fn into(self) -> RhythmicNotatedEvent<'a> {
         RhythmicNotatedEvent {
             tied: false,
-            event: NotatedEvent::Tuplet(self)
+            event: NotatedEvent::Tuplet(self),
+            expression: Expression::default(),
         }
     }
 }
\ No newline at end of file
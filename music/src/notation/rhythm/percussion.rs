@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::error::MusicSemanticsError;
+use crate::notation::midi::{MidiFile, MidiNote};
+use crate::notation::rhythm::duration::{Duration, TICKS_PER_WHOLE};
+use crate::notation::rhythm::expression::Dynamic;
+use crate::notation::rhythm::meter::Meter;
+use crate::notation::rhythm::tempo::Tempo;
+
+/// The MIDI channel reserved for percussion by General MIDI,
+/// i.e. channel 10, counting from zero.
+pub const MIDI_PERCUSSION_CHANNEL: u8 = 9;
+
+/// MIDI ticks per quarter note, chosen to match [Duration::ticks].
+const MIDI_TICKS_PER_QTR: u16 = (TICKS_PER_WHOLE / 4) as u16;
+
+/// A drum or metronome sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PercussionSound {
+    Kick,
+    Snare,
+    ClosedHiHat,
+    /// The click of a metronome on a strong beat.
+    ClickStrong,
+    /// The click of a metronome on a weak beat.
+    ClickWeak,
+}
+
+impl PercussionSound {
+    /// The key that plays this sound on the General MIDI percussion channel.
+    /// Clicks are played on the high and low wood blocks.
+    pub fn midi_note(&self) -> u8 {
+        match self {
+            PercussionSound::Kick => 36,
+            PercussionSound::Snare => 38,
+            PercussionSound::ClosedHiHat => 42,
+            PercussionSound::ClickStrong => 76,
+            PercussionSound::ClickWeak => 77,
+        }
+    }
+}
+
+/// The sounds struck together at one point in a [PercussionPattern].
+/// An event with no sounds is a rest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercussionEvent {
+    pub sounds: Vec<PercussionSound>,
+    pub duration: Duration,
+    /// Whether the event falls on a big beat, and is played louder.
+    pub accent: bool,
+}
+
+impl PercussionEvent {
+    /// The MIDI velocity of the sounds in the event.
+    pub fn velocity(&self) -> u8 {
+        if self.accent {
+            Dynamic::F.velocity()
+        } else {
+            Dynamic::MF.velocity()
+        }
+    }
+}
+
+/// The kinds of [PercussionPattern] that can be generated from a [Meter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PercussionStyle {
+    /// A metronome, clicking strongly on the downbeat, and weakly on the other beats.
+    Click,
+    /// A basic drum kit groove, with the hi-hat on every beat.
+    /// Each big beat starts with a kick, and the snare plays the backbeat,
+    /// halfway through big beats of an even number of beats.
+    /// Where big beats can't be halved, every other one starts with the snare instead,
+    /// e.g. a kick on 1 and a snare on 4 in 6/8.
+    Kit,
+}
+
+/// A percussion part, one event per beat, for some number of bars of a [Meter].
+/// The big beats of the meter, as in [Meter::beat_pattern], are accented.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercussionPattern {
+    pub meter: Meter,
+    pub events: Vec<PercussionEvent>,
+}
+
+impl PercussionPattern {
+    pub fn new(meter: &Meter, style: PercussionStyle, num_bars: usize) -> Self {
+        let beat: Duration = (&meter.denominator).into();
+        let beat_ticks = meter.denominator.ticks();
+        // The number of beats in each big beat, where a meter without big beats has one long one.
+        let groups: Vec<usize> = if meter.beat_pattern.is_empty() {
+            vec![meter.num_beats]
+        } else {
+            meter.beat_pattern.iter().map(|group| group / beat_ticks).collect()
+        };
+        let bar: Vec<PercussionEvent> = groups.iter()
+            .enumerate()
+            .flat_map(|(group, num_beats)| (0..*num_beats).map(move |i| (group, *num_beats, i)))
+            .enumerate()
+            .map(|(beat_index, (group, num_beats, i))| {
+                let sounds = match style {
+                    PercussionStyle::Click if beat_index == 0 => vec![PercussionSound::ClickStrong],
+                    PercussionStyle::Click => vec![PercussionSound::ClickWeak],
+                    PercussionStyle::Kit => {
+                        let halved = num_beats % 2 == 0;
+                        let drum = if i == 0 && (halved || group % 2 == 0) {
+                            Some(PercussionSound::Kick)
+                        } else if (halved && i == num_beats / 2) || (!halved && i == 0) {
+                            Some(PercussionSound::Snare)
+                        } else {
+                            None
+                        };
+                        drum.into_iter().chain([PercussionSound::ClosedHiHat]).collect()
+                    },
+                };
+                PercussionEvent { sounds, duration: beat, accent: i == 0 }
+            })
+            .collect();
+        Self {
+            meter: meter.clone(),
+            events: bar.iter().cycle().take(bar.len() * num_bars).cloned().collect(),
+        }
+    }
+
+    /// The pattern as a single-track Standard MIDI File, played on
+    /// the [MIDI_PERCUSSION_CHANNEL] at `bpm` quarter notes per minute.
+    pub fn to_midi_file(&self, bpm: u32) -> Vec<u8> {
+        let beat: Duration = (&self.meter.denominator).into();
+        let mut notes = vec![];
+        let mut start = 0;
+        for event in &self.events {
+            let end = start + event.duration.ticks() as u64;
+            notes.extend(event.sounds.iter().map(|sound| MidiNote {
+                midi_note: sound.midi_note(),
+                velocity: event.velocity(),
+                channel: MIDI_PERCUSSION_CHANNEL,
+                track: 0,
+                start,
+                end,
+            }));
+            start = end;
+        }
+        MidiFile {
+            ticks_per_quarter: MIDI_TICKS_PER_QTR,
+            time_signature: Some((self.meter.num_beats as u8, (TICKS_PER_WHOLE / beat.ticks()) as u8)),
+            micros_per_quarter: Some(Tempo::new(Duration::QTR, bpm).micros_per_quarter()),
+            notes,
+            instruments: HashMap::new(),
+        }.to_bytes()
+    }
+
+    /// Writes the pattern to a MIDI file. See [PercussionPattern::to_midi_file].
+    pub fn write_midi_file<P: AsRef<Path>>(&self, bpm: u32, path: P) -> Result<(), MusicSemanticsError> {
+        std::fs::write(path, self.to_midi_file(bpm))
+            .map_err(|e| MusicSemanticsError::FileWriteFailure(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use PercussionSound::*;
+
+    #[test]
+    fn percussion_patterns() {
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let kit = PercussionPattern::new(&four_four, PercussionStyle::Kit, 2);
+        assert_eq!(kit.events.len(), 8);
+        let sounds: Vec<&[PercussionSound]> = kit.events.iter().take(4).map(|e| e.sounds.as_slice()).collect();
+        assert_eq!(sounds, vec![
+            &[Kick, ClosedHiHat][..],
+            &[Snare, ClosedHiHat][..],
+            &[Kick, ClosedHiHat][..],
+            &[Snare, ClosedHiHat][..],
+        ]);
+        assert!(kit.events[2].accent && !kit.events[3].accent);
+
+        let six_eight = Meter::new(6, MeterDenominator::Eight, None);
+        let kit = PercussionPattern::new(&six_eight, PercussionStyle::Kit, 1);
+        assert_eq!(kit.events[0].sounds, vec![Kick, ClosedHiHat]);
+        assert_eq!(kit.events[3].sounds, vec![Snare, ClosedHiHat]);
+        assert_eq!(kit.events[3].duration, Duration::EIGHTH);
+
+        let click = PercussionPattern::new(&four_four, PercussionStyle::Click, 1);
+        let midi = click.to_midi_file(120);
+        assert_eq!(&midi[..4], b"MThd");
+        assert_eq!(&midi[12..14], &[0, 32]);
+        // 500,000 microseconds per quarter note.
+        assert_eq!(&midi[26..29], &[0x07, 0xA1, 0x20]);
+        // A strong click and three weak clicks, each a quarter note long.
+        assert_eq!(&midi[37..42], &[0x00, 0x99, 76, 96, 32]);
+        assert_eq!(midi.iter().filter(|byte| **byte == 0x99).count(), 4);
+        assert_eq!(&midi[midi.len() - 3..], &[0xFF, 0x2F, 0x00]);
+    }
+}
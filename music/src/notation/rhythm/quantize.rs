@@ -0,0 +1,152 @@
+use crate::notation::rhythm::duration::{Duration, RationalDuration, TICKS_PER_WHOLE};
+use crate::notation::rhythm::meter::Meter;
+use crate::notation::rhythm::tempo::TempoMap;
+
+/// A note as it was played, e.g. captured live or read from a MIDI file,
+/// timed in microseconds from the start of the performance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PerformedNote {
+    pub midi_note: u8,
+    pub velocity: u8,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// A note moved onto the grid of a [Quantizer], timed in whole notes from the start of the piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QuantizedNote {
+    pub midi_note: u8,
+    pub velocity: u8,
+    pub start: RationalDuration,
+    pub end: RationalDuration,
+}
+
+impl QuantizedNote {
+    pub fn duration(&self) -> RationalDuration {
+        self.end - self.start
+    }
+}
+
+/// Moves the notes of a performance onto a grid of note values, so that they can be written down.
+///
+/// The grid starts over at each bar line of the meter, so that a triplet grid that doesn't
+/// divide the bar, e.g. quarter note triplets in 3/8, still lines up with the bars.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantizer {
+    meter: Meter,
+    grid: Duration,
+    triplets: bool,
+    strength: f64,
+}
+
+impl Quantizer {
+    /// Quantizes fully onto every `grid` from the start of each bar of `meter`.
+    pub fn new(meter: Meter, grid: Duration) -> Self {
+        Self { meter, grid, triplets: false, strength: 1.0 }
+    }
+
+    /// Quantizes onto triplets of the grid, three in the time of two,
+    /// e.g. eighth note triplets on an eighth note grid.
+    pub fn triplets(mut self, triplets: bool) -> Self {
+        self.triplets = triplets;
+        self
+    }
+
+    /// How far each note is moved toward the nearest point of the grid, from 0.0, not at all,
+    /// to 1.0, all the way onto it, so that some of the feel of the performance can be kept.
+    pub fn strength(mut self, strength: f64) -> Self {
+        self.strength = strength.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The length between the points of the grid.
+    pub fn step(&self) -> RationalDuration {
+        match self.triplets {
+            true => self.grid.as_rational() * 2 / 3,
+            false => self.grid.as_rational(),
+        }
+    }
+
+    /// Where a note `ticks` from the start of the piece is moved to. Short of full strength,
+    /// the note is moved to the nearest third of a tick, so that triplets still land exactly.
+    pub fn quantize_ticks(&self, ticks: f64) -> RationalDuration {
+        let ticks = ticks.max(0.0);
+        let beat: Duration = (&self.meter.denominator).into();
+        let bar = beat.as_rational() * self.meter.num_beats as u64;
+        let step = self.step();
+        let bars = (ticks / as_ticks(bar)).floor();
+        let within = ticks - bars * as_ticks(bar);
+        let below = (within / as_ticks(step)).floor() as u64;
+        // The bar line may be nearer than the last point of the grid before it.
+        let nearest = [step * below, (step * (below + 1)).min(bar)]
+            .into_iter()
+            .min_by(|a, b| (as_ticks(*a) - within).abs().total_cmp(&(as_ticks(*b) - within).abs()))
+            .unwrap();
+        let snapped = bar * bars as u64 + nearest;
+        if self.strength >= 1.0 {
+            return snapped;
+        }
+        let moved = ticks + (as_ticks(snapped) - ticks) * self.strength;
+        RationalDuration::new((moved * 3.0).round() as u64, 3 * TICKS_PER_WHOLE as u64)
+    }
+
+    /// The notes of a performance played at `tempo`, quantized, in the order they're given.
+    /// A note that would be quantized to nothing is kept one step of the grid long.
+    pub fn quantize(&self, notes: &[PerformedNote], tempo: &TempoMap) -> Vec<QuantizedNote> {
+        notes.iter()
+            .map(|note| {
+                let start = self.quantize_ticks(tempo.ticks_at_micros(note.start));
+                let end = self.quantize_ticks(tempo.ticks_at_micros(note.end));
+                QuantizedNote {
+                    midi_note: note.midi_note,
+                    velocity: note.velocity,
+                    start,
+                    end: if end > start { end } else { start + self.step() },
+                }
+            })
+            .collect()
+    }
+}
+
+fn as_ticks(duration: RationalDuration) -> f64 {
+    duration.numerator() as f64 * TICKS_PER_WHOLE as f64 / duration.denominator() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::notation::rhythm::tempo::Tempo;
+
+    #[test]
+    fn quantized_onsets() {
+        // At 120 quarter notes a minute, a sixteenth note is 125 milliseconds.
+        let tempo = TempoMap::new(Tempo::new(Duration::QTR, 120));
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let note = |start, end| PerformedNote { midi_note: 60, velocity: 80, start, end };
+        let sixteenths = Quantizer::new(four_four.clone(), Duration::SIXTEENTH);
+        let notes = sixteenths.quantize(&[note(130_000, 260_000), note(340_000, 350_000)], &tempo);
+        assert_eq!((notes[0].start, notes[0].end), (RationalDuration::new(1, 16), RationalDuration::new(1, 8)));
+        assert_eq!(notes[0].duration(), RationalDuration::new(1, 16));
+        // Too short to last until the next point of the grid.
+        assert_eq!((notes[1].start, notes[1].end), (RationalDuration::new(3, 16), RationalDuration::new(1, 4)));
+
+        let triplets = Quantizer::new(four_four.clone(), Duration::EIGHTH).triplets(true);
+        assert_eq!(triplets.step(), RationalDuration::new(1, 12));
+        assert_eq!(triplets.quantize(&[note(170_000, 330_000)], &tempo)[0].end, RationalDuration::new(1, 6));
+
+        // Halfway from 8.96 ticks to the sixteenth note at 8.
+        let half = sixteenths.clone().strength(0.5);
+        assert_eq!(half.quantize_ticks(8.96), RationalDuration::new(25, 384));
+        assert_eq!(sixteenths.clone().strength(0.0).quantize_ticks(8.0), RationalDuration::new(1, 16));
+
+        // Quarter note triplets don't divide a bar of 3/8, so the bar line is nearer.
+        let three_eight = Quantizer::new(Meter::new(3, MeterDenominator::Eight, None), Duration::QTR).triplets(true);
+        assert_eq!(three_eight.quantize_ticks(46.0), RationalDuration::new(3, 8));
+        assert_eq!(three_eight.quantize_ticks(48.0 + 20.0), RationalDuration::new(3, 8) + RationalDuration::new(1, 6));
+
+        // A bar at 120, and then a quarter note at 60.
+        let slower = tempo.change(Duration::WHOLE.ticks(), Tempo::new(Duration::QTR, 60));
+        assert_eq!(sixteenths.quantize(&[note(2_990_000, 4_000_000)], &slower)[0].start, RationalDuration::new(5, 4));
+    }
+}
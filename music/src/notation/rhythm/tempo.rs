@@ -0,0 +1,101 @@
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+
+/// How fast the music goes, as a number of beats a minute, e.g. 120 quarter notes,
+/// or 60 dotted quarters, with any marking written above it, e.g. "Allegro".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tempo {
+    pub beat: Duration,
+    pub per_minute: u32,
+    pub text: Option<String>,
+}
+
+impl Tempo {
+    pub fn new(beat: Duration, per_minute: u32) -> Self {
+        Self { beat, per_minute, text: None }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    /// The length of a quarter note, in microseconds, as a tempo is given in a MIDI file.
+    pub fn micros_per_quarter(&self) -> u32 {
+        let ticks_per_minute = u64::from(self.per_minute.max(1)) * self.beat.ticks() as u64;
+        (60_000_000 * Duration::QTR.ticks() as u64 / ticks_per_minute.max(1)) as u32
+    }
+}
+
+/// The tempo of a piece, and where it changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempoMap {
+    /// Each tempo, from where it starts, in order.
+    pub tempos: Vec<(DurationTicks, Tempo)>,
+}
+
+impl TempoMap {
+    pub fn new(tempo: Tempo) -> Self {
+        Self { tempos: vec![(0, tempo)] }
+    }
+
+    /// Changes to `tempo` at `at`, replacing any change already there.
+    pub fn change(mut self, at: DurationTicks, tempo: Tempo) -> Self {
+        self.tempos.retain(|(start, _)| *start != at);
+        let i = self.tempos.partition_point(|(start, _)| *start < at);
+        self.tempos.insert(i, (at, tempo));
+        self
+    }
+
+    /// The tempo at `ticks` from the start of the piece, if one has been set by then.
+    pub fn at(&self, ticks: DurationTicks) -> Option<&Tempo> {
+        self.tempos.iter()
+            .take_while(|(start, _)| *start <= ticks)
+            .last()
+            .map(|(_, tempo)| tempo)
+    }
+
+    /// How far into the piece, in [DurationTicks] and fractions of one, a time `micros`
+    /// microseconds from its start falls. The first tempo is taken to hold from the start.
+    pub fn ticks_at_micros(&self, micros: u64) -> f64 {
+        let mut elapsed = 0.0;
+        for (i, (start, tempo)) in self.tempos.iter().enumerate() {
+            let start = if i == 0 { 0 } else { *start };
+            let per_tick = f64::from(tempo.micros_per_quarter()) / Duration::QTR.ticks() as f64;
+            let remaining = micros as f64 - elapsed;
+            match self.tempos.get(i + 1) {
+                Some((next, _)) if remaining > (next - start) as f64 * per_tick => {
+                    elapsed += (next - start) as f64 * per_tick;
+                },
+                _ => return start as f64 + remaining / per_tick,
+            }
+        }
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::DurationKind;
+
+    #[test]
+    fn tempo_changes() {
+        assert_eq!(Tempo::new(Duration::QTR, 120).micros_per_quarter(), 500_000);
+        // 60 dotted quarters a minute is 90 quarters.
+        assert_eq!(Tempo::new(Duration::new(DurationKind::Qtr, 1), 60).micros_per_quarter(), 666_666);
+
+        let bar = Duration::WHOLE.ticks();
+        let map = TempoMap::new(Tempo::new(Duration::QTR, 120).text("Allegro"))
+            .change(4 * bar, Tempo::new(Duration::QTR, 80))
+            .change(2 * bar, Tempo::new(Duration::HALF, 50))
+            .change(4 * bar, Tempo::new(Duration::QTR, 90));
+        let starts: Vec<DurationTicks> = map.tempos.iter().map(|(start, _)| *start).collect();
+        assert_eq!(starts, vec![0, 2 * bar, 4 * bar]);
+        assert_eq!(map.at(bar).unwrap().text.as_deref(), Some("Allegro"));
+        assert_eq!(map.at(3 * bar).unwrap().per_minute, 50);
+        assert_eq!(map.at(10 * bar).unwrap().per_minute, 90);
+        // Two bars of 4/4 at 120, and then half a bar of half notes at 50.
+        assert_eq!(map.ticks_at_micros(4_000_000), 2.0 * bar as f64);
+        assert_eq!(map.ticks_at_micros(5_200_000), 2.5 * bar as f64);
+    }
+}
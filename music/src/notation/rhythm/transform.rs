@@ -0,0 +1,298 @@
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent, Tuplet};
+use crate::notation::rhythm::duration::{Duration, DurationKind, DurationTicks};
+use crate::notation::rhythm::expression::Expression;
+use crate::notation::rhythm::measure::Measure;
+use crate::notation::rhythm::meter::Meter;
+
+/// An event of a series being transformed, with any notes tied to it joined into one.
+enum Held<'a> {
+    Note {
+        event: SingleEvent<'a>,
+        ticks: DurationTicks,
+        tied: bool,
+        expression: Expression,
+    },
+    Tuplet(RhythmicNotatedEvent<'a>),
+}
+
+/// Moves a series of events `ticks` later, after a rest, and ties them over the barlines of `meter`
+/// where they now fall. Notes already tied together are joined first, so that they're tied
+/// at the new barlines rather than the old ones.
+///
+/// Errors if a tuplet would cross a barline, since tuplets can't be split.
+pub fn displace<'a>(
+    events: Vec<RhythmicNotatedEvent<'a>>,
+    ticks: DurationTicks,
+    meter: &Meter,
+) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+    let mut held = vec![];
+    if ticks > 0 {
+        held.push(Held::Note { event: SingleEvent::Rest, ticks, tied: false, expression: Expression::default() });
+    }
+    held.extend(join_ties(events));
+    Ok(lay_out(held, meter)?.into_iter().flat_map(Measure::into_events).collect())
+}
+
+/// Lengthens every event by `numerator / denominator`, e.g. 2 / 1 to double the note values,
+/// or 2 / 3 to play them as triplets would be. An event that can't be written as one note value
+/// once lengthened is written as tied ones, and a tuplet is lengthened by its note value.
+///
+/// Errors if an event wouldn't be a whole number of ticks, or a tuplet wouldn't have a plain
+/// note value, or if `numerator` or `denominator` is zero.
+pub fn augment<'a>(
+    events: Vec<RhythmicNotatedEvent<'a>>,
+    numerator: usize,
+    denominator: usize,
+) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+    if numerator == 0 || denominator == 0 {
+        return Err(MusicSemanticsError::ZeroScale(numerator, denominator));
+    }
+    let mut augmented = vec![];
+    for event in events {
+        match event.event {
+            NotatedEvent::SingleEvent(single_event, duration) => {
+                let scaled = duration.as_rational() * numerator as u64 / denominator as u64;
+                let ticks = scaled.ticks().ok_or(MusicSemanticsError::UnscalableDuration(
+                    duration.as_rational().to_string(), numerator, denominator,
+                ))?;
+                augmented.extend(pieces(&single_event, ticks, event.tied, event.expression));
+            },
+            NotatedEvent::Tuplet(tuplet) => {
+                let base_ticks: DurationTicks = tuplet.base_unit.into();
+                let base_unit = (base_ticks * numerator).is_multiple_of(denominator)
+                    .then(|| base_ticks * numerator / denominator)
+                    .and_then(|ticks| DurationKind::ALL.into_iter().find(|kind| Into::<DurationTicks>::into(*kind) == ticks))
+                    .ok_or(MusicSemanticsError::UnscalableDuration(
+                        Duration::new(tuplet.base_unit, 0).as_rational().to_string(), numerator, denominator,
+                    ))?;
+                let events = augment(tuplet.events, numerator, denominator)?;
+                augmented.push(RhythmicNotatedEvent {
+                    tied: event.tied,
+                    event: NotatedEvent::Tuplet(Tuplet::new(events, tuplet.numerator, tuplet.denominator, base_unit)),
+                    expression: event.expression,
+                });
+            },
+        }
+    }
+    Ok(augmented)
+}
+
+/// Shortens every event by `numerator / denominator`, the inverse of [augment].
+pub fn diminish<'a>(
+    events: Vec<RhythmicNotatedEvent<'a>>,
+    numerator: usize,
+    denominator: usize,
+) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+    augment(events, denominator, numerator)
+}
+
+/// Rotates each bar of a series of events `ticks` later, with what's pushed past the barline
+/// coming around to the start of the same bar. A last bar left partly empty is filled out
+/// with a rest first.
+///
+/// Each bar is rotated on its own, so a note tied over a barline is struck again in each bar,
+/// as is a note split by the rotation. Errors if a tuplet would be split.
+pub fn rotate_in_bar<'a>(
+    events: Vec<RhythmicNotatedEvent<'a>>,
+    ticks: DurationTicks,
+    meter: &Meter,
+) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+    if events.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut measures = lay_out(join_ties(events), meter)?;
+    if let Some(last) = measures.last_mut() {
+        if !last.is_full() {
+            for rest in pieces(&SingleEvent::Rest, last.remaining(), false, Expression::default()) {
+                last.push(rest)?;
+            }
+        }
+    }
+    let mut rotated = vec![];
+    for measure in measures {
+        let split = measure.capacity() - ticks % measure.capacity();
+        let (mut front, mut back) = (vec![], vec![]);
+        let mut onset = 0;
+        for event in measure.into_events() {
+            let end = onset + event.duration();
+            if end <= split {
+                front.push(event);
+            } else if onset >= split {
+                back.push(event);
+            } else {
+                let NotatedEvent::SingleEvent(single_event, _) = &event.event else {
+                    return Err(MusicSemanticsError::MeasureOverflow(event.duration(), split - onset));
+                };
+                front.extend(pieces(single_event, split - onset, event.tied, event.expression.clone()));
+                back.extend(pieces(single_event, end - split, false, Expression::default()));
+            }
+            onset = end;
+        }
+        // What now starts each half of the bar follows something new, so it's struck rather than tied.
+        for half in [&mut front, &mut back] {
+            if let Some(first) = half.first_mut() {
+                first.tied = false;
+            }
+        }
+        rotated.extend(back);
+        rotated.extend(front);
+    }
+    Ok(rotated)
+}
+
+/// Joins each note to any tied to it after.
+fn join_ties(events: Vec<RhythmicNotatedEvent<'_>>) -> Vec<Held<'_>> {
+    let mut held: Vec<Held> = vec![];
+    for event in events {
+        let duration = event.duration();
+        match (event.event, held.last_mut()) {
+            (NotatedEvent::SingleEvent(_, _), Some(Held::Note { ticks, .. })) if event.tied => {
+                *ticks += duration;
+            },
+            (NotatedEvent::SingleEvent(single_event, _), _) => held.push(Held::Note {
+                event: single_event,
+                ticks: duration,
+                tied: event.tied,
+                expression: event.expression,
+            }),
+            (tuplet, _) => held.push(Held::Tuplet(RhythmicNotatedEvent { event: tuplet, ..event })),
+        }
+    }
+    held
+}
+
+/// Lays joined notes out into bars, tying each over the barlines it crosses.
+fn lay_out<'m, 'a>(held: Vec<Held<'a>>, meter: &'m Meter) -> Result<Vec<Measure<'m, 'a>>, MusicSemanticsError> {
+    let mut measures = vec![Measure::new(meter)];
+    for held in held {
+        match held {
+            Held::Note { event, mut ticks, tied, expression } => {
+                let mut first = true;
+                while ticks > 0 {
+                    if measures.last().unwrap().is_full() {
+                        measures.push(Measure::new(meter));
+                    }
+                    let measure = measures.last_mut().unwrap();
+                    let fits = ticks.min(measure.remaining());
+                    let (tied, expression) = match first {
+                        true => (tied, expression.clone()),
+                        false => (!matches!(event, SingleEvent::Rest), Expression::default()),
+                    };
+                    for piece in pieces(&event, fits, tied, expression) {
+                        measure.push(piece)?;
+                    }
+                    ticks -= fits;
+                    first = false;
+                }
+            },
+            Held::Tuplet(tuplet) => {
+                if measures.last().unwrap().is_full() {
+                    measures.push(Measure::new(meter));
+                }
+                measures.last_mut().unwrap().push(tuplet)?;
+            },
+        }
+    }
+    Ok(measures)
+}
+
+/// An event lasting `ticks`, as tied note values where it must be. Only the first is marked
+/// with the `expression`, and tied to what comes before if `tied`.
+fn pieces<'a>(
+    event: &SingleEvent<'a>,
+    ticks: DurationTicks,
+    tied: bool,
+    expression: Expression,
+) -> Vec<RhythmicNotatedEvent<'a>> {
+    let mut expression = Some(expression);
+    Duration::from_ticks(ticks).into_iter()
+        .enumerate()
+        .map(|(i, duration)| RhythmicNotatedEvent {
+            // Rests are never tied.
+            tied: if i == 0 { tied } else { !matches!(event, SingleEvent::Rest) },
+            event: NotatedEvent::SingleEvent(event.clone(), duration),
+            expression: expression.take().unwrap_or_default(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::note::Note;
+    use crate::note::pitch::Pitch;
+
+    fn note(note: Note, duration: Duration) -> RhythmicNotatedEvent<'static> {
+        RhythmicNotatedEvent::pitch(Pitch::new(note, 4).unwrap(), duration)
+    }
+
+    /// The note, or `None` for a rest, of each event, how long it is, and whether it's tied.
+    fn summary(events: &[RhythmicNotatedEvent]) -> Vec<(Option<Note>, DurationTicks, bool)> {
+        events.iter()
+            .map(|event| match &event.event {
+                NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), _) => (Some(pitch.note), event.duration(), event.tied),
+                _ => (None, event.duration(), event.tied),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rhythmic_transforms() {
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let qtr = Duration::QTR.ticks();
+        // A whole note of C, tied over from one half note to another.
+        let mut tied = note(Note::C, Duration::HALF);
+        tied.tied = true;
+        let motif = vec![note(Note::C, Duration::HALF), tied, note(Note::D, Duration::HALF)];
+        let displaced = displace(motif.clone(), qtr, &four_four).unwrap();
+        assert_eq!(summary(&displaced), vec![
+            (None, qtr, false),
+            (Some(Note::C), 3 * qtr, false),
+            (Some(Note::C), qtr, true),
+            (Some(Note::D), 2 * qtr, false),
+        ]);
+        let triplet: RhythmicNotatedEvent = Tuplet::new(
+            vec![note(Note::E, Duration::QTR), note(Note::F, Duration::QTR), note(Note::G, Duration::QTR)],
+            3, 2, DurationKind::Qtr,
+        ).into();
+        assert!(displace(vec![triplet.clone()], 3 * qtr, &four_four).is_err());
+
+        let melody = vec![note(Note::C, Duration::QTR), note(Note::D, Duration::EIGHTH), note(Note::E, Duration::EIGHTH)];
+        let doubled = augment(melody.clone(), 2, 1).unwrap();
+        assert_eq!(summary(&doubled), vec![(Some(Note::C), 2 * qtr, false), (Some(Note::D), qtr, false), (Some(Note::E), qtr, false)]);
+        let dotted = augment(melody.clone(), 3, 2).unwrap();
+        assert_eq!(summary(&dotted)[1], (Some(Note::D), 24, false));
+        // A quarter note and a sixteenth, tied.
+        let longer = augment(melody.clone(), 5, 4).unwrap();
+        assert_eq!(summary(&longer)[..2], [(Some(Note::C), qtr, false), (Some(Note::C), 8, true)]);
+        assert_eq!(summary(&diminish(melody.clone(), 2, 1).unwrap())[0], (Some(Note::C), qtr / 2, false));
+        assert!(matches!(
+            diminish(vec![note(Note::C, Duration::new(DurationKind::OneTwentyEighth, 0))], 2, 1),
+            Err(MusicSemanticsError::UnscalableDuration(_, 1, 2)),
+        ));
+        assert!(matches!(augment(melody.clone(), 1, 0), Err(MusicSemanticsError::ZeroScale(1, 0))));
+        assert!(matches!(diminish(melody.clone(), 0, 1), Err(MusicSemanticsError::ZeroScale(1, 0))));
+        assert!(matches!(augment(melody, 0, 1), Err(MusicSemanticsError::ZeroScale(0, 1))));
+        // Quarter note triplets, halved, become eighth note triplets.
+        let NotatedEvent::Tuplet(augmented) = &augment(vec![triplet], 1, 2).unwrap()[0].event else {
+            panic!("a tuplet stays a tuplet");
+        };
+        assert_eq!((augmented.base_unit, augmented.real_duration()), (DurationKind::Eighth, qtr));
+
+        // The half note is split by the rotation, and its second half starts the bar.
+        let bar = vec![note(Note::C, Duration::QTR), note(Note::D, Duration::QTR), note(Note::E, Duration::HALF)];
+        let rotated = rotate_in_bar(bar.clone(), qtr, &four_four).unwrap();
+        assert_eq!(summary(&rotated), vec![
+            (Some(Note::E), qtr, false),
+            (Some(Note::C), qtr, false),
+            (Some(Note::D), qtr, false),
+            (Some(Note::E), qtr, false),
+        ]);
+        // A partial bar is filled out with a rest, which comes around to the start.
+        let rotated = rotate_in_bar(bar[..2].to_vec(), 2 * qtr, &four_four).unwrap();
+        assert_eq!(summary(&rotated)[0], (None, 2 * qtr, false));
+        assert_eq!(summary(&rotate_in_bar(bar.clone(), 4 * qtr, &four_four).unwrap()), summary(&bar));
+    }
+}
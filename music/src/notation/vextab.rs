@@ -2,6 +2,7 @@ use crate::fretboard::fretboard_shape::FretboardShape;
 use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
 use crate::notation::rhythm::duration::{Duration, DurationKind};
 use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent};
+use crate::notation::rhythm::expression::{Articulation, Expression, Technique};
 use crate::note::pitch::Pitch;
 use crate::note_collections::voicing::Voicing;
 
@@ -66,15 +67,13 @@ impl ToVexTab for Voicing {
 
 impl ToVexTab for DurationKind {
     fn to_vextab(&self) -> String {
-        match self {
-            DurationKind::Whole => ":w",
-            DurationKind::Half => ":h",
-            DurationKind::Qtr => ":q",
-            DurationKind::Eighth => ":8",
-            DurationKind::Sixteenth => ":16",
-            DurationKind::ThirtySecond => ":32",
+        match self.denominator() {
+            Some(1) => ":w".to_string(),
+            Some(2) => ":h".to_string(),
+            Some(4) => ":q".to_string(),
+            Some(denominator @ 8..=32) => format!(":{}", denominator),
             _ => panic!("Unsupported rhythmic duration for Vextab")
-        }.to_string()
+        }
     }
 }
 
@@ -86,19 +85,133 @@ impl ToVexTab for Duration {
     }
 }
 
+impl ToVexTab for Articulation {
+    fn to_vextab(&self) -> String {
+        match self {
+            Articulation::Staccato => "$.a./bottom.$",
+            Articulation::Accent => "$.a>/bottom.$",
+            Articulation::Tenuto => "$.a-/bottom.$",
+        }.to_string()
+    }
+}
+
+/// Articulations, dynamics as italic annotations, and palm muting. VexTab has no notation
+/// for hairpins or slurs, so those are left out. Techniques played on the fret itself,
+/// such as bends, are written with the note instead.
+impl ToVexTab for Expression {
+    fn to_vextab(&self) -> String {
+        self.articulations.iter()
+            .map(|articulation| articulation.to_vextab())
+            .chain(self.dynamic.iter().map(|dynamic| format!("$.italic.{}$", dynamic.marking())))
+            .chain(self.techniques.iter()
+                .filter(|technique| **technique == Technique::PalmMute)
+                .map(|_| "$P.M.$".to_string()))
+            .map(|annotation| format!(" {}", annotation))
+            .collect()
+    }
+}
+
+/// The fret of a note, followed by any bend or vibrato played on it, e.g. `7b9v`.
+fn fret_with_techniques(note: &SoundedNote, techniques: &[Technique]) -> String {
+    let mut fret = note.fret.to_string();
+    for technique in techniques {
+        match technique {
+            Technique::Bend { semitones } => fret.push_str(&format!("b{}", note.fret + semitones)),
+            Technique::Vibrato => fret.push('v'),
+            _ => {},
+        }
+    }
+    fret
+}
+
+/// The letter joining two frets on a string.
+fn connection_symbol(technique: Technique) -> &'static str {
+    match technique {
+        Technique::HammerOn => "h",
+        Technique::PullOff => "p",
+        _ => "s",
+    }
+}
+
+/// The duration, and then the notes, e.g. `:q 5/3`. A tied event is written with a `T`
+/// before its notes, to tie it to the event before it, e.g. `:q T5/3`.
 impl<'a> ToVexTab for RhythmicNotatedEvent<'a> {
     fn to_vextab(&self) -> String {
         match &self.event {
             NotatedEvent::SingleEvent(e, d) => {
-                let pitch_content = e.to_vextab();
+                let pitch_content = match e {
+                    SingleEvent::Fretted(note) => {
+                        let fret = fret_with_techniques(note, &self.expression.techniques);
+                        let string = note.fretboard.num_strings() - note.string;
+                        format!("{}/{}", fret, string)
+                    },
+                    _ => e.to_vextab(),
+                };
                 let duration = d.to_vextab();
-                format!("{}{}", duration, pitch_content)
+                let tie = if self.tied { "T" } else { "" };
+                let expression = self.expression.to_vextab();
+                // On its own, a note can't show what it connects to.
+                let connection = self.expression.connection()
+                    .map_or("".to_string(), |technique| {
+                        format!(" ${}$", connection_symbol(technique).to_uppercase())
+                    });
+                format!("{} {}{}{}{}", duration, tie, pitch_content, expression, connection)
             }
             NotatedEvent::Tuplet(_) => todo!()
         }
     }
 }
 
+fn fretted<'e, 'a>(event: &'e RhythmicNotatedEvent<'a>) -> Option<(&'e SoundedNote<'a>, &'e Duration)> {
+    match &event.event {
+        NotatedEvent::SingleEvent(SingleEvent::Fretted(note), duration) => Some((note, duration)),
+        _ => None,
+    }
+}
+
+/// Space-separated events. Runs of fretted notes joined by hammer-ons, pull-offs, or slides
+/// are written together, e.g. `:8 5h7p5/3`, as long as they share a string and a duration.
+impl<'a> ToVexTab for Vec<RhythmicNotatedEvent<'a>> {
+    fn to_vextab(&self) -> String {
+        let mut tokens = vec![];
+        let mut i = 0;
+        while i < self.len() {
+            let event = &self[i];
+            let mut end = i;
+            if let Some((first, duration)) = fretted(event) {
+                while self[end].expression.connection().is_some() {
+                    match self.get(end + 1).and_then(fretted) {
+                        Some((next, next_duration)) if next.string == first.string
+                            && next_duration == duration
+                            && !self[end + 1].tied => end += 1,
+                        _ => break,
+                    }
+                }
+                if end > i {
+                    let mut frets = fret_with_techniques(first, &event.expression.techniques);
+                    for j in i + 1..=end {
+                        let technique = self[j - 1].expression.connection().unwrap();
+                        let (note, _) = fretted(&self[j]).unwrap();
+                        frets.push_str(connection_symbol(technique));
+                        frets.push_str(&fret_with_techniques(note, &self[j].expression.techniques));
+                    }
+                    let tie = if event.tied { "T" } else { "" };
+                    let string = first.fretboard.num_strings() - first.string;
+                    let expression: String = self[i..=end].iter()
+                        .map(|event| event.expression.to_vextab())
+                        .collect();
+                    tokens.push(format!("{} {}{}/{}{}", duration.to_vextab(), tie, frets, string, expression));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            tokens.push(event.to_vextab());
+            i += 1;
+        }
+        tokens.join(" ")
+    }
+}
+
 impl<'a> ToVexTab for SingleEvent<'a> {
     fn to_vextab(&self) -> String {
         match self {
@@ -125,4 +238,80 @@ pub mod barline {
     pub const REPEAT_END: &str = "=:|";
     pub const DOUBLE_REPEAT: &str = "=::";
     pub const END_BAR: &str = "=|=";
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::expression::Dynamic;
+    use crate::note::note::Note;
+
+    #[test]
+    fn vextab_expression() {
+        let event = RhythmicNotatedEvent::pitch(Pitch::new(Note::Bes, 4).unwrap(), Duration::QTR)
+            .with_articulation(Articulation::Staccato)
+            .with_dynamic(Dynamic::P);
+        assert_eq!(event.to_vextab(), ":q B@/4 $.a./bottom.$ $.italic.p$");
+    }
+
+    #[test]
+    fn vextab_durations() {
+        use crate::fretboard::STD_6STR_GTR;
+        use crate::fretboard::fretboard_shape::melodic_shape_search::MelodicFretboardShape;
+        // Run into the fret after it, a duration of `:8` or `:16` would be read as `:85` or `:165`.
+        let note = |duration| RhythmicNotatedEvent::fretted(STD_6STR_GTR.sounded_note(1, 5).unwrap(), duration);
+        assert_eq!(note(Duration::EIGHTH).to_vextab(), ":8 5/5");
+        assert_eq!(note(Duration::SIXTEENTH).to_vextab(), ":16 5/5");
+        assert_eq!(vec![note(Duration::SIXTEENTH), RhythmicNotatedEvent::rest(Duration::EIGHTH)].to_vextab(), ":16 5/5 :8 ##");
+        // Nor is the duration of a run of hammer-ons and pull-offs run into its first fret.
+        let shape = MelodicFretboardShape {
+            shape: [(1, 5), (1, 7), (1, 5)].iter()
+                .map(|(string, fret)| STD_6STR_GTR.sounded_note(*string, *fret).unwrap())
+                .collect(),
+            score: 0,
+            fretboard: &STD_6STR_GTR,
+        };
+        let run = shape.to_notated_events(Duration::SIXTEENTH, true).to_vextab();
+        assert_eq!(run, ":16 5h7p5/5");
+        assert!(!run.contains(":165"));
+    }
+
+    #[test]
+    fn vextab_ties() {
+        use crate::fretboard::STD_6STR_GTR;
+        let notes = vec![STD_6STR_GTR.sounded_note(1, 5).unwrap(), STD_6STR_GTR.sounded_note(2, 7).unwrap()];
+        let struck = RhythmicNotatedEvent::fretted_many(notes.clone(), Duration::HALF);
+        assert_eq!(struck.to_vextab(), ":h (5/5.7/4)");
+        // Held over from the event before, rather than struck again.
+        let tied = RhythmicNotatedEvent::fretted_many_tied(notes, Duration::HALF);
+        assert_eq!(tied.to_vextab(), ":h T(5/5.7/4)");
+        let tied = RhythmicNotatedEvent::pitch_tied(Pitch::new(Note::Bes, 4).unwrap(), Duration::QTR);
+        assert_eq!(tied.to_vextab(), ":q TB@/4");
+    }
+
+    #[test]
+    fn vextab_techniques() {
+        use crate::fretboard::STD_6STR_GTR;
+        use crate::fretboard::fretboard_shape::melodic_shape_search::MelodicFretboardShape;
+        let shape = MelodicFretboardShape {
+            shape: [(1, 5), (1, 7), (1, 5), (2, 7)].iter()
+                .map(|(string, fret)| STD_6STR_GTR.sounded_note(*string, *fret).unwrap())
+                .collect(),
+            score: 0,
+            fretboard: &STD_6STR_GTR,
+        };
+        let events = shape.to_notated_events(Duration::EIGHTH, true);
+        assert_eq!(events.to_vextab(), ":8 5h7p5/5 :8 7/4");
+        assert_eq!(shape.to_notated_events(Duration::EIGHTH, false).to_vextab(), ":8 5/5 :8 7/5 :8 5/5 :8 7/4");
+
+        let bend = RhythmicNotatedEvent::fretted(STD_6STR_GTR.sounded_note(3, 7).unwrap(), Duration::QTR)
+            .with_technique(Technique::Bend { semitones: 2 })
+            .with_technique(Technique::Vibrato)
+            .with_technique(Technique::PalmMute);
+        assert_eq!(bend.to_vextab(), ":q 7b9v/3 $P.M.$");
+        // A slide with nowhere to go is only annotated.
+        let slide = RhythmicNotatedEvent::fretted(STD_6STR_GTR.sounded_note(3, 7).unwrap(), Duration::QTR)
+            .with_technique(Technique::Slide);
+        assert_eq!(vec![slide, RhythmicNotatedEvent::rest(Duration::QTR)].to_vextab(), ":q 7/3 $S$ :q ##");
+    }
+}
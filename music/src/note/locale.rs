@@ -0,0 +1,127 @@
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use crate::error::MusicSemanticsError;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note::spelling::{Accidental, Letter, Spelling};
+
+/// The conventions for naming notes in different parts of the world.
+/// [Note]'s own [Display] and [core::str::FromStr] always use [NoteNameLocale::English].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoteNameLocale {
+    /// Letters with "#" and "b" for accidentals, e.g. "Bb" or "F#".
+    #[default]
+    English,
+    /// Letters with "is" and "es" suffixes for accidentals, where B natural is "H",
+    /// and B flat is "B". The flats of E and A are "Es" and "As".
+    German,
+    /// As in English, except that B natural is "H", and B flat is "B",
+    /// as in Scandinavia and much of Central Europe.
+    NorthernEuropean,
+    /// Fixed-do solfege, with "#" and "b" for accidentals, e.g. "Sib" or "Fa#".
+    FixedDo,
+}
+
+impl NoteNameLocale {
+    /// The name of a note in this locale.
+    pub fn name(&self, note: &Note) -> String {
+        let spelling = Spelling::from(note);
+        match self {
+            NoteNameLocale::English => note.to_string(),
+            NoteNameLocale::German => {
+                let letter = match spelling.letter {
+                    Letter::B => "H".to_string(),
+                    letter => letter.to_string(),
+                };
+                match (spelling.letter, spelling.acc) {
+                    (Letter::B, Accidental::Flat) => "B".to_string(),
+                    (Letter::E | Letter::A, Accidental::Flat) => letter + "s",
+                    (Letter::E | Letter::A, Accidental::DoubleFlat) => letter + "ses",
+                    (_, acc) => letter + match acc {
+                        Accidental::Natural => "",
+                        Accidental::Sharp => "is",
+                        Accidental::Flat => "es",
+                        Accidental::DoubleSharp => "isis",
+                        Accidental::DoubleFlat => "eses",
+                    },
+                }
+            },
+            NoteNameLocale::NorthernEuropean => match (spelling.letter, spelling.acc) {
+                (Letter::B, Accidental::Flat) => "B".to_string(),
+                (Letter::B, acc) => format!("H{}", acc),
+                (letter, acc) => format!("{}{}", letter, acc),
+            },
+            NoteNameLocale::FixedDo => {
+                let syllable = match spelling.letter {
+                    Letter::C => "Do",
+                    Letter::D => "Ré",
+                    Letter::E => "Mi",
+                    Letter::F => "Fa",
+                    Letter::G => "Sol",
+                    Letter::A => "La",
+                    Letter::B => "Si",
+                };
+                format!("{}{}", syllable, spelling.acc)
+            },
+        }
+    }
+
+    /// Parses the name of a note in this locale. Case is ignored,
+    /// and in [NoteNameLocale::FixedDo], so is the accent of "Ré".
+    pub fn parse(&self, name: &str) -> Result<Note, MusicSemanticsError> {
+        let normalize = |name: &str| name.to_lowercase().replace('é', "e");
+        let target = normalize(name.trim());
+        (0..12u8)
+            .flat_map(|pc| Pc::from(&pc).notes())
+            .find(|note| normalize(&self.name(note)) == target)
+            .ok_or(MusicSemanticsError::InvalidNoteName(name.to_string()))
+    }
+}
+
+/// A [Note] displayed in some [NoteNameLocale].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocalizedNote {
+    pub note: Note,
+    pub locale: NoteNameLocale,
+}
+
+impl Display for LocalizedNote {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.locale.name(&self.note))
+    }
+}
+
+impl Note {
+    /// Displays the note as it's named in some [NoteNameLocale].
+    pub fn localized(&self, locale: NoteNameLocale) -> LocalizedNote {
+        LocalizedNote { note: *self, locale }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localized_note_names() {
+        let names = |locale: NoteNameLocale| [Note::B, Note::Bes, Note::Ees, Note::Fis, Note::Aes]
+            .iter()
+            .map(|note| note.localized(locale).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names(NoteNameLocale::English), vec!["B", "Bb", "Eb", "F#", "Ab"]);
+        assert_eq!(names(NoteNameLocale::German), vec!["H", "B", "Es", "Fis", "As"]);
+        assert_eq!(names(NoteNameLocale::NorthernEuropean), vec!["H", "B", "Eb", "F#", "Ab"]);
+        assert_eq!(names(NoteNameLocale::FixedDo), vec!["Si", "Sib", "Mib", "Fa#", "Lab"]);
+
+        // Every name survives a round trip through every locale.
+        for locale in [NoteNameLocale::English, NoteNameLocale::German, NoteNameLocale::NorthernEuropean, NoteNameLocale::FixedDo] {
+            for note in (0..12u8).flat_map(|pc| Pc::from(&pc).notes()) {
+                let name = locale.name(&note);
+                assert_eq!(locale.name(&locale.parse(&name).unwrap()), name);
+            }
+        }
+        assert_eq!(NoteNameLocale::German.parse("h").unwrap(), Note::B);
+        assert_eq!(NoteNameLocale::FixedDo.parse("Re#").unwrap(), Note::Dis);
+        assert!(NoteNameLocale::German.parse("Bb").is_err());
+    }
+}
@@ -1,8 +1,10 @@
+pub mod locale;
 pub mod note;
 pub mod pitch_class;
 pub mod spelling;
 pub mod pitch;
 
+pub use locale::{LocalizedNote, NoteNameLocale};
 pub use note::Note;
 pub use pitch::Pitch;
 pub use pitch_class::Pc;
@@ -1,6 +1,7 @@
-use std::fmt::{Display, Formatter};
-use std::hash::{Hash, Hasher};
-use std::str::FromStr;
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
 use crate::note::spelling::{Accidental, Letter, Spelling};
 use crate::error::MusicSemanticsError;
 use crate::note::pitch_class::Pc;
@@ -169,7 +170,7 @@ impl FromStr for Note {
 }
 
 impl Display for Note {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let s = Spelling::from(self);
         let s = s.letter.to_string() + &s.acc.to_string();
         f.write_str(&s)
@@ -288,6 +289,8 @@ mod tests {
         assert_eq!(Note::Cisis, Note::from_str("C##").unwrap());
         assert_eq!(Note::C, Note::from_str("C").unwrap());
         assert_eq!(Note::Bes, Note::from_str("Bb").unwrap());
+        assert!(Note::from_str("").is_err());
+        assert!(Note::from_str("é").is_err());
     }
 
     #[test]
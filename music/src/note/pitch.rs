@@ -1,6 +1,7 @@
-use std::cmp::Ordering;
-use std::fmt::{Display, Formatter};
-use std::hash::{Hash, Hasher};
+use crate::prelude::*;
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
 use crate::note::note::Note;
 use crate::note::pitch_class::Pc;
 use crate::error::MusicSemanticsError;
@@ -134,7 +135,7 @@ impl Pitch {
 }
 
 impl Display for Pitch {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}{}", self.note, self.octave)
     }
 }
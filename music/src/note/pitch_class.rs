@@ -1,7 +1,8 @@
+use crate::prelude::*;
 use crate::note::note::Note;
-use std::cmp::Ordering;
-use std::fmt::{Display, Formatter};
-use std::hash::{Hash, Hasher};
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
 
 pub struct PcIter {
     curr: Pc,
@@ -137,7 +138,7 @@ impl Hash for Pc {
 }
 
 impl Display for Pc {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", u8::from(self))
     }
 }
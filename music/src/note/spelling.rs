@@ -1,7 +1,8 @@
+use crate::prelude::*;
 use crate::note::note::Note;
-use std::fmt::{Display, Formatter};
-use std::ops::Add;
-use std::str::FromStr;
+use core::fmt::{Display, Formatter};
+use core::ops::Add;
+use core::str::FromStr;
 use crate::error::MusicSemanticsError;
 use crate::error::MusicSemanticsError::InvalidNoteLetter;
 
@@ -40,7 +41,7 @@ impl FromStr for Accidental {
 // TODO also impl block with a toFancyStr that uses UTF-8 chars.
 
 impl Display for Accidental {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let s = match self {
             Accidental::Natural => "".to_string(),
             Accidental::Flat => "b".to_string(),
@@ -129,7 +130,7 @@ impl FromStr for Letter {
 }
 
 impl Display for Letter {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let s = match self {
             Letter::A => "A".to_string(),
             Letter::B => "B".to_string(),
@@ -157,7 +158,7 @@ impl Spelling {
 }
 
 impl Display for Spelling {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let letter = self.letter.to_string();
         let acc = self.acc.to_string();
         let s = letter.add(&acc);
@@ -169,8 +170,10 @@ impl FromStr for Spelling {
     type Err = MusicSemanticsError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let letter = Letter::from_str(&s[0..1])?;
-        let acc = Accidental::from_str(&s[1..s.len()])?;
+        let letter_len = s.chars().next().map_or(0, char::len_utf8);
+        let (letter, acc) = s.split_at(letter_len);
+        let letter = Letter::from_str(letter)?;
+        let acc = Accidental::from_str(acc)?;
         Ok(Self { letter, acc })
     }
 }
@@ -179,7 +182,7 @@ impl From<&Note> for Spelling {
     fn from(note: &Note) -> Self {
         match note {
             Note::C => Spelling::new(Letter::C, Accidental::Natural),
-            Note::Deses => Spelling::new(Letter::D, Accidental::DoubleSharp),
+            Note::Deses => Spelling::new(Letter::D, Accidental::DoubleFlat),
             Note::Cis => Spelling::new(Letter::C, Accidental::Sharp),
             Note::Des => Spelling::new(Letter::D, Accidental::Flat),
             Note::Cisis => Spelling::new(Letter::C, Accidental::DoubleSharp),
@@ -0,0 +1,159 @@
+use crate::prelude::*;
+use itertools::Itertools;
+#[cfg(feature = "json")]
+use serde::Serialize;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::{NoteSet, PcSet};
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::chord_name::corpus::named_scales;
+use crate::note_collections::geometry::similarity::interval_class_vector;
+use crate::note_collections::geometry::symmetry::collections::SymmetricCollection;
+use crate::note_collections::spelling::spell_pc_set;
+
+/// Everything the crate can say about a collection of notes in one place: what it can be named
+/// as a chord and as part of a scale, its interval content, its symmetries, and how it's spelled.
+///
+/// Notes are written as by their [core::fmt::Display], so that the report can be handed
+/// as it is to an app, e.g. as JSON with the `json` feature.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct AnalysisReport {
+    /// The notes analysed, from the first.
+    pub notes: Vec<String>,
+    /// Their pitch classes, from C as 0.
+    pub pcs: Vec<u8>,
+    /// The names of the notes as a chord, with each of them as the root in turn,
+    /// starting from the first. Notes that can't be the root of a named chord are left out.
+    pub chord_names: Vec<String>,
+    /// The named scales that have every note, on each of the notes as the tonic,
+    /// from the fewest notes to the most.
+    pub scales: Vec<ScaleCandidate>,
+    /// How many times each interval class occurs, as by [interval_class_vector].
+    pub interval_vector: [u32; 6],
+    pub symmetries: Symmetries,
+    /// The notes spelled up from each of them in turn, as by [spell_pc_set].
+    pub spellings: Vec<Vec<String>>,
+}
+
+/// A scale of an [AnalysisReport].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct ScaleCandidate {
+    pub tonic: String,
+    pub name: &'static str,
+    /// The notes of the scale, spelled up from its tonic.
+    pub notes: Vec<String>,
+}
+
+/// The symmetries of an [AnalysisReport].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct Symmetries {
+    /// The semitones less than an octave the notes can be transposed by to give the same
+    /// pitch classes, e.g. 3, 6, and 9 for a diminished seventh chord.
+    pub transpositions: Vec<u8>,
+    /// The symmetric collections that have every note, as "whole-tone", "octatonic",
+    /// or "hexatonic", alongside each transposition of the collection that has them,
+    /// as numbered by [SymmetricCollection].
+    pub collections: Vec<(&'static str, u8)>,
+}
+
+impl AnalysisReport {
+    /// Analyses a collection of notes, in the order they're given.
+    pub fn new(notes: &NoteSet) -> Self {
+        let pcs: Vec<Pc> = notes.iter().map(Pc::from).collect();
+        let names = |notes: &[Note]| notes.iter().map(|note| note.to_string()).collect::<Vec<_>>();
+        // The pitch classes above a note, with it as 0.
+        let from = |root: &Note| PcSet::new(pcs.iter()
+            .map(|pc| Pc::from(&Pc::from(root).distance_up_to(pc)))
+            .collect());
+
+        let chord_names = notes.iter()
+            .filter_map(|root| ChordName::from_note_set(&NoteSet::new(notes.to_vec(), Some(root))))
+            .map(|name| name.symbol(None))
+            .collect();
+
+        let mut scales: Vec<(usize, ScaleCandidate)> = vec![];
+        for tonic in notes.iter() {
+            let intervals = from(tonic);
+            for scale in named_scales() {
+                if intervals.iter().all(|interval| scale.pc_set.contains(interval)) {
+                    let Ok(spelled) = scale.spell(tonic) else {
+                        continue;
+                    };
+                    let candidate = ScaleCandidate { tonic: tonic.to_string(), name: scale.name, notes: names(&spelled) };
+                    scales.push((scale.pc_set.len(), candidate));
+                }
+            }
+        }
+        scales.sort_by_key(|(size, _)| *size);
+
+        let sorted = |pcs: Vec<u8>| pcs.into_iter().sorted().collect::<Vec<u8>>();
+        let transpositions = (1..12u8)
+            .filter(|semitones| {
+                let transposed = pcs.iter().map(|pc| (u8::from(pc) + semitones) % 12).collect();
+                !pcs.is_empty() && sorted(transposed) == sorted(pcs.iter().map(u8::from).collect())
+            })
+            .collect();
+        let collections = [
+            (SymmetricCollection::WholeTone, "whole-tone"),
+            (SymmetricCollection::Octatonic, "octatonic"),
+            (SymmetricCollection::Hexatonic, "hexatonic"),
+        ]
+            .into_iter()
+            .flat_map(|(collection, name)| collection.transpositions_containing(&pcs)
+                .into_iter()
+                .map(move |transposition| (name, transposition)))
+            .collect();
+
+        let spellings = notes.iter()
+            .filter_map(|root| spell_pc_set(root, &from(root)).ok())
+            .map(|spelled| names(&spelled))
+            .collect();
+
+        Self {
+            notes: names(notes),
+            pcs: pcs.iter().map(u8::from).collect(),
+            chord_names,
+            scales: scales.into_iter().map(|(_, candidate)| candidate).collect(),
+            interval_vector: interval_class_vector(&PcSet::new(pcs.clone())),
+            symmetries: Symmetries { transpositions, collections },
+            spellings,
+        }
+    }
+
+    /// The report as a JSON object, with a field for each of the report's.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a report is made of strings and numbers")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analysis_reports() {
+        let report = AnalysisReport::new(&NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G, Note::A]));
+        assert_eq!(report.notes, vec!["C", "E", "G", "A"]);
+        assert_eq!(report.pcs, vec![0, 4, 7, 9]);
+        // There's no chord on E with the others above it.
+        assert_eq!(report.chord_names, vec!["C6", "Gsus4 (13)", "Amin7"]);
+        assert_eq!(report.interval_vector, [0, 1, 2, 1, 2, 0]);
+        assert!(report.symmetries.transpositions.is_empty());
+        assert_eq!(report.symmetries.collections, vec![("octatonic", 0)]);
+        // The pentatonic scale has the fewest notes of any scale with all of them.
+        assert_eq!(report.scales[0].name, "Major Pentatonic");
+        assert_eq!(report.scales[0].notes, vec!["C", "D", "E", "G", "A"]);
+        assert!(report.scales.iter().any(|scale| scale.tonic == "A" && scale.name == "Aeolian"));
+        assert_eq!(report.spellings[3], vec!["A", "C", "E", "G"]);
+
+        let diminished = AnalysisReport::new(&NoteSet::starting_from_first_note(vec![Note::B, Note::D, Note::F, Note::Aes]));
+        assert_eq!(diminished.symmetries.transpositions, vec![3, 6, 9]);
+        assert_eq!(diminished.symmetries.collections, vec![("octatonic", 1), ("octatonic", 2)]);
+        #[cfg(feature = "json")]
+        assert!(diminished.to_json().contains("\"transpositions\":[3,6,9]"));
+    }
+}
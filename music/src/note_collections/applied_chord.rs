@@ -0,0 +1,138 @@
+use crate::prelude::*;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::key::{Key, KeyMode};
+use crate::note_collections::modal_interchange::key_degrees;
+
+/// How an applied chord leads to the chord it tonicizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AppliedFunction {
+    /// As its dominant, a major triad or dominant seventh a fifth above it, e.g. V/V.
+    Dominant,
+    /// As its leading-tone chord, a diminished triad or seventh a half step below it, e.g. vii°/V.
+    LeadingTone,
+}
+
+/// A chromatic chord that acts as the dominant or leading-tone chord of another chord of a key,
+/// e.g. D7 in C major, V/V, and F#dim7, vii°7/V.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AppliedChord {
+    pub function: AppliedFunction,
+    /// The root of the chord tonicized, e.g. G for V/V in C major.
+    pub target: Note,
+    /// The roman numeral of the chord tonicized, e.g. "V", or "iv" in a minor key.
+    pub numeral: &'static str,
+}
+
+impl AppliedChord {
+    /// The chord's roman numeral, e.g. "V/V", or "vii°/ii".
+    pub fn symbol(&self) -> String {
+        let function = match self.function {
+            AppliedFunction::Dominant => "V",
+            AppliedFunction::LeadingTone => "vii°",
+        };
+        format!("{}/{}", function, self.numeral)
+    }
+
+    /// How the chord moves to the chord after it, which is taken to have its first note for its root.
+    pub fn resolution(&self, next: &NoteSet) -> Resolution {
+        let target = Pc::from(&self.target);
+        match next.first().map(|root| target.distance_up_to(&Pc::from(root))) {
+            Some(0) => Resolution::Expected,
+            Some(8 | 9) => Resolution::Deceptive,
+            _ => Resolution::Unresolved,
+        }
+    }
+}
+
+/// How an [AppliedChord] moves to the chord after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// To a chord on its target, e.g. D7 to G in C major.
+    Expected,
+    /// To a chord on the submediant of its target, e.g. D7 to Em in C major, as V moves to vi
+    /// in a deceptive cadence.
+    Deceptive,
+    /// To any other chord, or to none, at the end of a progression.
+    Unresolved,
+}
+
+/// The triads of a key that may be tonicized, by the semitones of their roots above the tonic.
+/// The tonic's own dominant isn't applied, and diminished triads have no dominant of their own.
+fn targets(mode: KeyMode) -> &'static [(u8, &'static str)] {
+    match mode {
+        KeyMode::Major => &[(2, "ii"), (4, "iii"), (5, "IV"), (7, "V"), (9, "vi")],
+        KeyMode::Minor => &[(3, "III"), (5, "iv"), (7, "V"), (8, "VI"), (10, "VII")],
+    }
+}
+
+impl Key {
+    /// The chord of the key that a chord is applied to, if it's an applied chord, i.e. a dominant
+    /// or leading-tone chord, with a note outside the key, whose target is a major or minor triad
+    /// of the key other than the tonic. The chord's root is taken to be its first note,
+    /// as in [crate::note_collections::chord_name::ChordName::from_note_set].
+    ///
+    /// Chords within the key, counting the raised seventh of a minor key as within it, aren't applied,
+    /// e.g. VII in a minor key, though it's the dominant of III.
+    pub fn applied_chord(&self, chord: &NoteSet) -> Option<AppliedChord> {
+        let tonic = Pc::from(&self.tonic);
+        let root = Pc::from(chord.first()?);
+        if chord.iter().all(|note| key_degrees(self.mode).contains(&tonic.distance_up_to(&Pc::from(note)))) {
+            return None;
+        }
+        let intervals: Vec<u8> = chord.iter().map(|note| root.distance_up_to(&Pc::from(note))).collect();
+        let made_of = |tones: &[u8], required: &[u8]| {
+            intervals.iter().all(|interval| tones.contains(interval))
+                && required.iter().all(|interval| intervals.contains(interval))
+        };
+        let (function, to_target) = if made_of(&[0, 4, 7, 10], &[4]) {
+            (AppliedFunction::Dominant, 5)
+        } else if made_of(&[0, 3, 6, 9, 10], &[3, 6]) {
+            (AppliedFunction::LeadingTone, 1)
+        } else {
+            return None;
+        };
+        let degree = (tonic.distance_up_to(&root) + to_target) % 12;
+        let (_, numeral) = targets(self.mode).iter().find(|(target, _)| *target == degree)?;
+        Some(AppliedChord {
+            function,
+            target: self.spell_pc(&Pc::from(&(u8::from(&tonic) + degree))),
+            numeral,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applied_chords() {
+        let chord = |notes: &[Note]| NoteSet::starting_from_first_note(notes.to_vec());
+        let symbol = |key: &Key, notes: &[Note]| key.applied_chord(&chord(notes)).map(|applied| applied.symbol());
+        let c_major = Key::new(Note::C, KeyMode::Major).unwrap();
+        assert_eq!(symbol(&c_major, &[Note::D, Note::Fis, Note::A, Note::C]).as_deref(), Some("V/V"));
+        assert_eq!(symbol(&c_major, &[Note::E, Note::Gis, Note::B]).as_deref(), Some("V/vi"));
+        assert_eq!(symbol(&c_major, &[Note::C, Note::E, Note::G, Note::Bes]).as_deref(), Some("V/IV"));
+        assert_eq!(symbol(&c_major, &[Note::Fis, Note::A, Note::C, Note::Ees]).as_deref(), Some("vii°/V"));
+        assert_eq!(symbol(&c_major, &[Note::Cis, Note::E, Note::G]).as_deref(), Some("vii°/ii"));
+        // Within the key, a dominant of the tonic, or of a chord outside the key, are not applied.
+        assert_eq!(symbol(&c_major, &[Note::G, Note::B, Note::D, Note::F]), None);
+        assert_eq!(symbol(&c_major, &[Note::C, Note::E, Note::G]), None);
+        assert_eq!(symbol(&c_major, &[Note::Bes, Note::D, Note::F, Note::Aes]), None);
+        assert_eq!(symbol(&c_major, &[Note::Fis, Note::Ais, Note::Cis]), None);
+        assert_eq!(symbol(&c_major, &[Note::D, Note::F, Note::A]), None);
+
+        let a_minor = Key::new(Note::A, KeyMode::Minor).unwrap();
+        assert_eq!(symbol(&a_minor, &[Note::A, Note::Cis, Note::E, Note::G]).as_deref(), Some("V/iv"));
+        assert_eq!(symbol(&a_minor, &[Note::E, Note::Gis, Note::B, Note::D]), None);
+        assert_eq!(symbol(&a_minor, &[Note::G, Note::B, Note::D, Note::F]), None);
+
+        let v_of_v = c_major.applied_chord(&chord(&[Note::D, Note::Fis, Note::A, Note::C])).unwrap();
+        assert_eq!(v_of_v.target, Note::G);
+        assert_eq!(v_of_v.resolution(&chord(&[Note::G, Note::B, Note::D])), Resolution::Expected);
+        assert_eq!(v_of_v.resolution(&chord(&[Note::E, Note::G, Note::B])), Resolution::Deceptive);
+        assert_eq!(v_of_v.resolution(&chord(&[Note::C, Note::E, Note::G])), Resolution::Unresolved);
+    }
+}
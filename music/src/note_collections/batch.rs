@@ -0,0 +1,187 @@
+use crate::prelude::*;
+use core::cmp::Reverse;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::PcSet;
+use crate::note_collections::chord_name::naming_heuristics::{infer_rooted_chord_quality, RootPolicy};
+use crate::note_collections::chord_name::quality::chord::ChordQuality;
+use crate::note_collections::geometry::symmetry::collections::SymmetricCollection;
+use crate::note_collections::geometry::symmetry::transpositional::{find_transpositional_symmetries, TranspositionalSymmetry};
+use crate::note_collections::pc_set::deduplicate_pcs;
+
+/// Statistics over a corpus of pitch class sets, e.g. every chord of a collection of MIDI files.
+///
+/// Statistics from separate batches of a corpus can be combined with [CorpusStatistics::merge],
+/// to analyse a corpus too large to hold in memory at once.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorpusStatistics {
+    /// The number of sets analysed.
+    pub num_sets: usize,
+    /// How many sets were named as each chord quality, from the root found by the [RootPolicy]
+    /// they were analysed with.
+    pub qualities: HashMap<ChordQuality, usize>,
+    /// How many sets couldn't be named as a chord.
+    pub num_unnamed: usize,
+    /// How many sets map onto themselves under each transposition smaller than an octave.
+    /// A set with several symmetries, e.g. a diminished seventh chord, is counted under each.
+    pub transpositional_symmetries: HashMap<TranspositionalSymmetry, usize>,
+    /// How many sets lie within some transposition of each symmetric collection.
+    pub collections: HashMap<SymmetricCollection, usize>,
+}
+
+impl CorpusStatistics {
+    /// Adds the analysis of one set, named from the root found according to `policy`.
+    pub fn add(&mut self, pc_set: &PcSet, policy: RootPolicy) {
+        let mut pcs = deduplicate_pcs(pc_set);
+        pcs.sort();
+        self.num_sets += 1;
+
+        let set: HashSet<Pc> = pcs.iter().copied().collect();
+        match infer_rooted_chord_quality(&set, policy) {
+            Some((_, quality)) => *self.qualities.entry(quality).or_default() += 1,
+            None => self.num_unnamed += 1,
+        }
+
+        let symmetries: HashSet<TranspositionalSymmetry> = find_transpositional_symmetries(&pcs)
+            .into_values()
+            .flatten()
+            .collect();
+        for symmetry in symmetries {
+            *self.transpositional_symmetries.entry(symmetry).or_default() += 1;
+        }
+        for collection in [SymmetricCollection::WholeTone, SymmetricCollection::Octatonic, SymmetricCollection::Hexatonic] {
+            if !pcs.is_empty() && collection.parent_of(&pcs).is_some() {
+                *self.collections.entry(collection).or_default() += 1;
+            }
+        }
+    }
+
+    /// Combines the statistics of another part of the corpus into these.
+    pub fn merge(&mut self, other: CorpusStatistics) {
+        self.num_sets += other.num_sets;
+        self.num_unnamed += other.num_unnamed;
+        for (quality, count) in other.qualities {
+            *self.qualities.entry(quality).or_default() += count;
+        }
+        for (symmetry, count) in other.transpositional_symmetries {
+            *self.transpositional_symmetries.entry(symmetry).or_default() += count;
+        }
+        for (collection, count) in other.collections {
+            *self.collections.entry(collection).or_default() += count;
+        }
+    }
+
+    /// The chord qualities named, from the most frequent to the least, with the fraction
+    /// of all the sets named as each.
+    pub fn quality_frequencies(&self) -> Vec<(&ChordQuality, f64)> {
+        let mut frequencies: Vec<(&ChordQuality, usize)> = self.qualities.iter()
+            .map(|(quality, count)| (quality, *count))
+            .collect();
+        frequencies.sort_by_key(|(_, count)| Reverse(*count));
+        frequencies.into_iter()
+            .map(|(quality, count)| (quality, self.fraction(count)))
+            .collect()
+    }
+
+    /// The fraction of the sets with some transpositional symmetry, of any size.
+    pub fn symmetry_prevalence(&self, symmetry: &TranspositionalSymmetry) -> f64 {
+        self.fraction(self.transpositional_symmetries.get(symmetry).copied().unwrap_or(0))
+    }
+
+    /// The fraction of the sets within some transposition of a symmetric collection.
+    pub fn collection_prevalence(&self, collection: &SymmetricCollection) -> f64 {
+        self.fraction(self.collections.get(collection).copied().unwrap_or(0))
+    }
+
+    fn fraction(&self, count: usize) -> f64 {
+        match self.num_sets {
+            0 => 0.0,
+            num_sets => count as f64 / num_sets as f64,
+        }
+    }
+}
+
+/// Names each set of a corpus as a chord and finds its symmetries, and tallies the results.
+/// Each set is named from the first of its pitch classes it can be named from, from the lowest up,
+/// as by [RootPolicy::TryAllRoots].
+///
+/// With the `rayon` feature, the sets are analysed in parallel.
+pub fn analyze_corpus(pc_sets: impl IntoIterator<Item = PcSet>) -> CorpusStatistics {
+    analyze_corpus_with(pc_sets, RootPolicy::TryAllRoots)
+}
+
+/// As [analyze_corpus], but with the root of each set found according to `policy`,
+/// e.g. [RootPolicy::RequireRoot] for a corpus of sets already given from their roots.
+pub fn analyze_corpus_with(pc_sets: impl IntoIterator<Item = PcSet>, policy: RootPolicy) -> CorpusStatistics {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        let pc_sets: Vec<PcSet> = pc_sets.into_iter().collect();
+        pc_sets.par_iter()
+            .fold(CorpusStatistics::default, |mut statistics, pc_set| {
+                statistics.add(pc_set, policy);
+                statistics
+            })
+            .reduce(CorpusStatistics::default, |mut statistics, other| {
+                statistics.merge(other);
+                statistics
+            })
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut statistics = CorpusStatistics::default();
+        for pc_set in pc_sets {
+            statistics.add(&pc_set, policy);
+        }
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note_collections::chord_name::quality::chord::{Alt, Extension, MajorSubtype, MinorSubtype};
+
+    #[test]
+    fn corpus_statistics() {
+        let corpus: Vec<PcSet> = [
+            vec![0, 4, 7],
+            vec![0, 3, 7],
+            vec![0, 4, 7],
+            // A dominant seventh chord, given out of order.
+            vec![10, 0, 7, 4],
+            vec![0, 3, 6, 9],
+            vec![0, 4, 8],
+            // Without its root.
+            vec![4, 7, 10],
+        ].into_iter().map(PcSet::from).collect();
+        let statistics = analyze_corpus_with(corpus.clone(), RootPolicy::RequireRoot);
+        assert_eq!(statistics.num_sets, 7);
+        assert_eq!(statistics.num_unnamed, 1);
+
+        let major = ChordQuality::Major(MajorSubtype::Maj(Alt::empty()));
+        let minor = ChordQuality::Minor(MinorSubtype::Min(Alt::empty()));
+        let dominant = ChordQuality::Major(MajorSubtype::N(vec![Extension::Seventh], Alt::empty()));
+        assert_eq!(statistics.qualities.get(&major), Some(&2));
+        assert_eq!(statistics.qualities.get(&minor), Some(&1));
+        assert_eq!(statistics.qualities.get(&dominant), Some(&1));
+        assert_eq!(statistics.quality_frequencies()[0], (&major, 2.0 / 7.0));
+        // E G Bb can be named from E.
+        assert_eq!(analyze_corpus(corpus.clone()).num_unnamed, 0);
+
+        // The diminished seventh chord maps onto itself up 3, 6, and 9 semitones,
+        // and the augmented triad up 4 and 8.
+        assert_eq!(statistics.transpositional_symmetries.get(&TranspositionalSymmetry::T3), Some(&1));
+        assert_eq!(statistics.transpositional_symmetries.get(&TranspositionalSymmetry::T6), Some(&1));
+        assert_eq!(statistics.transpositional_symmetries.get(&TranspositionalSymmetry::T4), Some(&1));
+        assert_eq!(statistics.symmetry_prevalence(&TranspositionalSymmetry::T2), 0.0);
+        // Only the augmented triad is whole-tone, and every set but it is octatonic.
+        assert_eq!(statistics.collection_prevalence(&SymmetricCollection::WholeTone), 1.0 / 7.0);
+        assert_eq!(statistics.collections.get(&SymmetricCollection::Octatonic), Some(&6));
+
+        // Analysing the corpus in two parts gives the same statistics.
+        let mut parts = analyze_corpus_with(corpus[..3].to_vec(), RootPolicy::RequireRoot);
+        parts.merge(analyze_corpus_with(corpus[3..].to_vec(), RootPolicy::RequireRoot));
+        assert_eq!(parts, statistics);
+        assert_eq!(analyze_corpus(vec![]).collection_prevalence(&SymmetricCollection::WholeTone), 0.0);
+    }
+}
@@ -0,0 +1,508 @@
+use crate::prelude::*;
+use crate::error::MusicSemanticsError;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note::spelling::{Accidental, Spelling};
+use crate::note_collections::chord_name::quality::chord::{
+    Alt, AltChoice, AugSubtype, ChordQuality, DimSubtype, Extension, MajorSubtype, MinorSubtype, SusSubtype,
+};
+use crate::note_collections::geometry::symmetry::transpositional::Modes;
+use crate::note_collections::interval_class::IntervalClass;
+use crate::note_collections::pc_set::PcSet;
+use crate::note_collections::chord_name::naming_heuristics::infer_chord_quality;
+use crate::note_collections::spelling::spell_pc_set;
+
+/// A common chord quality, as it's written in chord symbols.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedChord {
+    /// The usual way of writing the quality, e.g. "maj7" or "m7b5".
+    pub symbol: &'static str,
+    /// Other ways of writing the quality, e.g. "M7" and "Δ7" for "maj7".
+    pub aliases: Vec<&'static str>,
+    pub quality: ChordQuality,
+}
+
+impl NamedChord {
+    fn new(symbol: &'static str, aliases: Vec<&'static str>, quality: ChordQuality) -> Self {
+        Self { symbol, aliases, quality }
+    }
+
+    /// The canonical [PcSet] of the chord, with the root as [Pc::Pc0].
+    pub fn pc_set(&self) -> PcSet {
+        self.quality.to_pc_set()
+    }
+
+    /// The symbol and its aliases, in that order.
+    pub fn spellings(&self) -> impl Iterator<Item = &&'static str> {
+        core::iter::once(&self.symbol).chain(self.aliases.iter())
+    }
+}
+
+/// The chord qualities commonly found in chord symbols, from triads up through
+/// altered thirteenth chords. The major triad's symbol is the empty string.
+pub fn named_chords() -> Vec<NamedChord> {
+    use AltChoice::{FlatNine, FlatThirteenth, SharpEleven, SharpNine};
+    use Extension::{Eleventh, Ninth, Seventh, Thirteenth};
+    let alt = |choices: Vec<AltChoice>| Alt::from(choices);
+    let major = |subtype| ChordQuality::Major(subtype);
+    let minor = |subtype| ChordQuality::Minor(subtype);
+    let sus = |subtype| ChordQuality::Sus(subtype);
+    vec![
+        // Triads
+        NamedChord::new("", vec!["maj", "M", "major"], major(MajorSubtype::Maj(Alt::empty()))),
+        NamedChord::new("m", vec!["min", "-", "minor"], minor(MinorSubtype::Min(Alt::empty()))),
+        NamedChord::new("aug", vec!["+", "#5"], ChordQuality::Aug(AugSubtype::Aug(Alt::empty()))),
+        NamedChord::new("dim", vec!["o", "°"], ChordQuality::Dim(DimSubtype::Dim(Alt::empty()))),
+        NamedChord::new("sus2", vec![], sus(SusSubtype::Sus2(Alt::empty()))),
+        NamedChord::new("sus4", vec!["sus"], sus(SusSubtype::Sus4(Alt::empty()))),
+        NamedChord::new("5", vec!["no3"], ChordQuality::Interval(IntervalClass::Ic7)),
+        // Added tones
+        NamedChord::new("add9", vec!["add2"], major(MajorSubtype::Add9(Alt::empty()))),
+        NamedChord::new("madd9", vec!["madd2", "m(add9)"], minor(MinorSubtype::MinAdd9(Alt::empty()))),
+        NamedChord::new("add11", vec!["add4"], major(MajorSubtype::Add11(Alt::empty()))),
+        NamedChord::new("madd11", vec!["madd4", "m(add11)"], minor(MinorSubtype::MinAdd11(Alt::empty()))),
+        NamedChord::new("6", vec!["maj6", "M6", "add6"], major(MajorSubtype::Maj6(Alt::empty()))),
+        NamedChord::new("m6", vec!["min6", "-6"], minor(MinorSubtype::Min6(Alt::empty()))),
+        NamedChord::new("6/9", vec!["69", "6add9"], major(MajorSubtype::SixNine(Alt::empty()))),
+        NamedChord::new("m6/9", vec!["m69", "-69"], minor(MinorSubtype::Min69(Alt::empty()))),
+        // Sevenths
+        NamedChord::new("maj7", vec!["M7", "Δ", "Δ7"], major(MajorSubtype::MajN(vec![Seventh], Alt::empty()))),
+        NamedChord::new("7", vec!["dom7"], major(MajorSubtype::N(vec![Seventh], Alt::empty()))),
+        NamedChord::new("m7", vec!["min7", "-7", "mi7"], minor(MinorSubtype::MinN(vec![Seventh], Alt::empty()))),
+        NamedChord::new("mMaj7", vec!["m(maj7)", "mM7", "-Δ7", "minmaj7"], minor(MinorSubtype::MinMajN(vec![Seventh], Alt::empty()))),
+        NamedChord::new("m7b5", vec!["ø", "ø7", "-7b5", "min7b5"], ChordQuality::Dim(DimSubtype::MinNb5(vec![Seventh], Alt::empty()))),
+        NamedChord::new("dim7", vec!["o7", "°7"], ChordQuality::Dim(DimSubtype::DimN(vec![Seventh], Alt::empty()))),
+        NamedChord::new("aug7", vec!["+7", "7#5"], ChordQuality::Aug(AugSubtype::AugN(vec![Seventh], Alt::empty()))),
+        NamedChord::new("augMaj7", vec!["+maj7", "maj7#5"], ChordQuality::Aug(AugSubtype::AugMajN(vec![Seventh], Alt::empty()))),
+        NamedChord::new("7sus4", vec!["7sus"], sus(SusSubtype::DomNSus(vec![Seventh], Alt::empty()))),
+        NamedChord::new("maj7sus4", vec!["maj7sus"], sus(SusSubtype::MajNSus(vec![Seventh], Alt::empty()))),
+        // Ninths
+        NamedChord::new("maj9", vec!["M9", "Δ9"], major(MajorSubtype::MajN(vec![Seventh, Ninth], Alt::empty()))),
+        NamedChord::new("9", vec!["dom9"], major(MajorSubtype::N(vec![Seventh, Ninth], Alt::empty()))),
+        NamedChord::new("m9", vec!["min9", "-9"], minor(MinorSubtype::MinN(vec![Seventh, Ninth], Alt::empty()))),
+        NamedChord::new("7b9", vec![], major(MajorSubtype::N(vec![Seventh], alt(vec![FlatNine])))),
+        NamedChord::new("7#9", vec![], major(MajorSubtype::N(vec![Seventh], alt(vec![SharpNine])))),
+        NamedChord::new("9sus4", vec!["9sus"], sus(SusSubtype::DomNSus(vec![Seventh, Ninth], Alt::empty()))),
+        // Elevenths
+        NamedChord::new("11", vec!["dom11"], major(MajorSubtype::N(vec![Seventh, Ninth, Eleventh], Alt::empty()))),
+        NamedChord::new("m11", vec!["min11", "-11"], minor(MinorSubtype::MinN(vec![Seventh, Ninth, Eleventh], Alt::empty()))),
+        NamedChord::new("maj7#11", vec!["M7#11", "Δ#11", "Δ7#11"], major(MajorSubtype::MajN(vec![Seventh], alt(vec![SharpEleven])))),
+        NamedChord::new("7#11", vec!["7b5"], major(MajorSubtype::N(vec![Seventh], alt(vec![SharpEleven])))),
+        NamedChord::new("9#11", vec![], major(MajorSubtype::N(vec![Seventh, Ninth], alt(vec![SharpEleven])))),
+        // Thirteenths
+        NamedChord::new("maj13", vec!["M13", "Δ13"], major(MajorSubtype::MajN(vec![Seventh, Ninth, Thirteenth], Alt::empty()))),
+        NamedChord::new("13", vec!["dom13"], major(MajorSubtype::N(vec![Seventh, Ninth, Thirteenth], Alt::empty()))),
+        NamedChord::new("m13", vec!["min13", "-13"], minor(MinorSubtype::MinN(vec![Seventh, Ninth, Eleventh, Thirteenth], Alt::empty()))),
+        NamedChord::new("13#11", vec![], major(MajorSubtype::N(vec![Seventh, Ninth, Thirteenth], alt(vec![SharpEleven])))),
+        NamedChord::new("7b13", vec![], major(MajorSubtype::N(vec![Seventh], alt(vec![FlatThirteenth])))),
+        NamedChord::new("13b9", vec!["7b9(13)"], major(MajorSubtype::N(vec![Seventh, Thirteenth], alt(vec![FlatNine])))),
+        NamedChord::new("13sus4", vec!["13sus"], sus(SusSubtype::DomNSus(vec![Seventh, Ninth, Thirteenth], Alt::empty()))),
+        // Altered dominants
+        NamedChord::new("7b9b13", vec![], major(MajorSubtype::N(vec![Seventh], alt(vec![FlatNine, FlatThirteenth])))),
+        NamedChord::new("7#9#11", vec![], major(MajorSubtype::N(vec![Seventh], alt(vec![SharpNine, SharpEleven])))),
+        NamedChord::new("alt", vec!["7alt"], major(MajorSubtype::N(vec![Seventh], alt(vec![FlatNine, SharpNine, SharpEleven, FlatThirteenth])))),
+    ]
+}
+
+/// The words and signs that are written in several ways in chord symbols,
+/// longest first, and what they're normalized to. Words are matched regardless of case.
+const WORDS: [(&str, &str); 10] = [
+    ("major", "maj"),
+    ("minor", "m"),
+    ("maj", "maj"),
+    ("min", "m"),
+    ("aug", "+"),
+    ("dim", "o"),
+    ("dom", ""),
+    ("mi", "m"),
+    ("add", "add"),
+    ("sus", "sus"),
+];
+
+/// Reduces a chord symbol's quality to a canonical form, so that different ways of writing
+/// the same quality compare equal, e.g. "Maj7", "M7", and "major7" all become "maj7",
+/// and "min7(b5)" becomes "m7b5".
+///
+/// Case only matters for a lone "M" or "m", which stand for major and minor respectively.
+/// Whitespace, brackets, commas, and slashes are ignored, so "6/9" is the same as "69".
+pub fn normalize_quality_symbol(symbol: &str) -> String {
+    let mut normalized = String::new();
+    let mut rest = symbol;
+    while let Some(c) = rest.chars().next() {
+        let word = WORDS.iter().find(|(word, _)| rest.get(..word.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(word)));
+        if let Some((word, replacement)) = word {
+            normalized.push_str(replacement);
+            rest = &rest[word.len()..];
+            continue;
+        }
+        match c {
+            'M' => normalized.push_str("maj"),
+            '-' | '−' => normalized.push('m'),
+            '♭' => normalized.push('b'),
+            '♯' => normalized.push('#'),
+            '°' => normalized.push('o'),
+            c if c.is_whitespace() || "()[],/".contains(c) => {},
+            c => normalized.extend(c.to_lowercase()),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    normalized
+}
+
+/// Finds the [NamedChord] written as `symbol`, or any other way of writing it,
+/// as judged by [normalize_quality_symbol].
+pub fn find_named_chord(symbol: &str) -> Option<NamedChord> {
+    let symbol = normalize_quality_symbol(symbol);
+    named_chords().into_iter()
+        .find(|chord| chord.spellings().any(|spelling| normalize_quality_symbol(spelling) == symbol))
+}
+
+/// Ranks the chords of `corpus` by how closely any of their spellings resembles `symbol`,
+/// closest first, alongside the number of characters that would have to be inserted,
+/// deleted, or substituted to get from one to the other, after [normalize_quality_symbol].
+/// Useful for suggesting what was meant by a symbol that [find_named_chord] can't find.
+/// Chords at the same distance keep their order in the corpus.
+pub fn closest_named_chords<'c>(symbol: &str, corpus: &'c [NamedChord]) -> Vec<(&'c NamedChord, usize)> {
+    let symbol: Vec<char> = normalize_quality_symbol(symbol).chars().collect();
+    let mut ranked: Vec<(&NamedChord, usize)> = corpus.iter()
+        .map(|chord| {
+            let distance = chord.spellings()
+                .map(|spelling| {
+                    let spelling: Vec<char> = normalize_quality_symbol(spelling).chars().collect();
+                    edit_distance(&symbol, &spelling)
+                })
+                .min()
+                .unwrap_or(usize::MAX);
+            (chord, distance)
+        })
+        .collect();
+    ranked.sort_by_key(|(_, distance)| *distance);
+    ranked
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// The scales that [NamedScale]s are drawn from, each either a parent scale
+/// along with its modes, or a scale that stands alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScaleFamily {
+    Diatonic,
+    MelodicMinor,
+    HarmonicMinor,
+    HarmonicMajor,
+    DoubleHarmonic,
+    HungarianMinor,
+    Pentatonic,
+    Hirajoshi,
+    InSen,
+    /// One of Messiaen's seven modes of limited transposition, numbered from 1.
+    /// Their rotations aren't named, so each stands alone.
+    Messiaen(u8),
+}
+
+/// A scale, or a mode of one, known by name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedScale {
+    pub name: &'static str,
+    /// Other names for the scale, e.g. "Super Locrian" for "Altered".
+    pub aliases: Vec<&'static str>,
+    pub family: ScaleFamily,
+    /// Which mode of its family's parent scale this is, counting the parent itself as 0.
+    pub mode: usize,
+    /// The scale's pitch classes, with the tonic as [Pc::Pc0].
+    pub pc_set: PcSet,
+}
+
+impl NamedScale {
+    /// The names of the modes of `parent`, in order, starting with the parent itself.
+    fn modes_of(family: ScaleFamily, parent: &[u8], names: Vec<Vec<&'static str>>) -> Vec<Self> {
+        let parent = PcSet::from(parent.to_vec());
+        names.into_iter()
+            .enumerate()
+            .map(|(mode, mut names)| Self {
+                name: names.remove(0),
+                aliases: names,
+                family,
+                mode,
+                pc_set: parent.rotate(mode as isize),
+            })
+            .collect()
+    }
+
+    /// Every mode of the scale, starting with the scale itself, alongside the [NamedScale]
+    /// it's known as, if any. As with [Modes::modes], a symmetrical scale repeats itself,
+    /// e.g. every mode of the whole tone scale is another whole tone scale.
+    pub fn modes(&self) -> Vec<(PcSet, Option<NamedScale>)> {
+        self.pc_set.modes().into_iter()
+            .map(|pcs| {
+                let named = find_named_scale(&pcs);
+                (pcs, named)
+            })
+            .collect()
+    }
+
+    /// Spells the scale up from `root`. Seven note scales are spelled with one note
+    /// on every letter, as in e.g. A B C D# E F G# for A Hungarian minor,
+    /// unless that would take more than a double sharp or flat.
+    /// Anything else is spelled by [spell_pc_set].
+    pub fn spell(&self, root: &Note) -> Result<Vec<Note>, MusicSemanticsError> {
+        let spelled = spell_pc_set(root, &self.pc_set)?;
+        if self.pc_set.len() != 7 {
+            return Ok(spelled);
+        }
+        let root_pc = Pc::from(root);
+        let mut letter = Spelling::from(root).letter;
+        let mut notes = vec![];
+        for pc in self.pc_set.iter() {
+            let target = Pc::from(&(u8::from(&root_pc) + u8::from(pc)));
+            let accidentals = [
+                Accidental::Natural,
+                Accidental::Sharp,
+                Accidental::Flat,
+                Accidental::DoubleSharp,
+                Accidental::DoubleFlat,
+            ];
+            let Some(note) = accidentals.into_iter()
+                .filter_map(|acc| Note::try_from(Spelling::new(letter, acc)).ok())
+                .find(|note| Pc::from(note) == target)
+            else {
+                return Ok(spelled);
+            };
+            notes.push(note);
+            letter = letter.next();
+        }
+        Ok(notes)
+    }
+}
+
+/// A curated collection of scales, from the modes of the major, melodic minor,
+/// and harmonic minor scales, through to scales from outside the Western tradition,
+/// and Messiaen's modes of limited transposition.
+pub fn named_scales() -> Vec<NamedScale> {
+    let mut scales = vec![];
+    scales.extend(NamedScale::modes_of(ScaleFamily::Diatonic, &[0, 2, 4, 5, 7, 9, 11], vec![
+        vec!["Ionian", "Major"],
+        vec!["Dorian"],
+        vec!["Phrygian"],
+        vec!["Lydian"],
+        vec!["Mixolydian"],
+        vec!["Aeolian", "Natural Minor"],
+        vec!["Locrian"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::MelodicMinor, &[0, 2, 3, 5, 7, 9, 11], vec![
+        vec!["Melodic Minor", "Jazz Minor"],
+        vec!["Dorian b2", "Phrygian #6"],
+        vec!["Lydian Augmented"],
+        vec!["Lydian Dominant", "Overtone"],
+        vec!["Mixolydian b6", "Aeolian Dominant"],
+        vec!["Locrian #2", "Half Diminished"],
+        vec!["Altered", "Super Locrian"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::HarmonicMinor, &[0, 2, 3, 5, 7, 8, 11], vec![
+        vec!["Harmonic Minor"],
+        vec!["Locrian #6"],
+        vec!["Ionian #5", "Ionian Augmented"],
+        vec!["Dorian #4", "Ukrainian Dorian"],
+        vec!["Phrygian Dominant", "Spanish Phrygian"],
+        vec!["Lydian #2"],
+        vec!["Altered Diminished", "Super Locrian bb7"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::HarmonicMajor, &[0, 2, 4, 5, 7, 8, 11], vec![
+        vec!["Harmonic Major"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::DoubleHarmonic, &[0, 1, 4, 5, 7, 8, 11], vec![
+        vec!["Double Harmonic", "Byzantine", "Arabic"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::HungarianMinor, &[0, 2, 3, 6, 7, 8, 11], vec![
+        vec!["Hungarian Minor", "Gypsy Minor"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::Pentatonic, &[0, 2, 4, 7, 9], vec![
+        vec!["Major Pentatonic"],
+        vec!["Suspended Pentatonic", "Egyptian"],
+        vec!["Blues Minor Pentatonic", "Man Gong"],
+        vec!["Blues Major Pentatonic", "Ritusen"],
+        vec!["Minor Pentatonic"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::Hirajoshi, &[0, 2, 3, 7, 8], vec![
+        vec!["Hirajoshi"],
+        vec!["Iwato"],
+        vec!["Kumoi"],
+    ]));
+    scales.extend(NamedScale::modes_of(ScaleFamily::InSen, &[0, 1, 5, 7, 10], vec![
+        vec!["In-Sen"],
+    ]));
+    let messiaen: [(&str, &str, &[u8]); 7] = [
+        ("Whole Tone", "Messiaen Mode 1", &[0, 2, 4, 6, 8, 10]),
+        ("Octatonic", "Messiaen Mode 2", &[0, 1, 3, 4, 6, 7, 9, 10]),
+        ("Messiaen Mode 3", "", &[0, 2, 3, 4, 6, 7, 8, 10, 11]),
+        ("Messiaen Mode 4", "", &[0, 1, 2, 5, 6, 7, 8, 11]),
+        ("Messiaen Mode 5", "", &[0, 1, 5, 6, 7, 11]),
+        ("Messiaen Mode 6", "", &[0, 2, 4, 5, 6, 8, 10, 11]),
+        ("Messiaen Mode 7", "", &[0, 1, 2, 3, 5, 6, 7, 8, 9, 11]),
+    ];
+    for (i, (name, alias, pcs)) in messiaen.into_iter().enumerate() {
+        let names = core::iter::once(name).chain((!alias.is_empty()).then_some(alias)).collect();
+        scales.extend(NamedScale::modes_of(ScaleFamily::Messiaen(i as u8 + 1), pcs, vec![names]));
+    }
+    scales
+}
+
+/// Finds the [NamedScale] with the same pitch classes as `pcs`, which is taken
+/// to start from the tonic.
+pub fn find_named_scale(pcs: &PcSet) -> Option<NamedScale> {
+    let pcs = PcSet::new(pcs.to_vec());
+    named_scales().into_iter().find(|scale| scale.pc_set == pcs)
+}
+
+/// Finds the [NamedScale] known by `name`, or any of its aliases, ignoring case.
+pub fn find_named_scale_by_name(name: &str) -> Option<NamedScale> {
+    let name = name.trim().to_lowercase();
+    named_scales().into_iter()
+        .find(|scale| core::iter::once(&scale.name)
+            .chain(scale.aliases.iter())
+            .any(|alias| alias.to_lowercase() == name))
+}
+
+/// What the complement of a collection can be named as, from each of its notes.
+/// See [name_complement].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedComplement {
+    /// The pitch classes missing from the collection, as by [PcSet::complement].
+    pub pcs: PcSet,
+    /// The [NamedScale]s the complement forms, alongside the tonic each starts from.
+    pub scales: Vec<(Pc, NamedScale)>,
+    /// The [ChordQuality]s the complement forms, alongside the root each is built on,
+    /// as inferred by [infer_chord_quality].
+    pub chords: Vec<(Pc, ChordQuality)>,
+}
+
+/// Names the complement of a collection, i.e. the pitch classes it leaves out,
+/// as a scale or chord from each of its notes. E.g. the complement of a C major pentatonic scale
+/// forms every mode of the major scale, such as F# Ionian and C# Mixolydian.
+/// `pcs` is taken as it is, without being zeroed, so the roots are relative to it.
+pub fn name_complement(pcs: &PcSet) -> NamedComplement {
+    let complement = pcs.complement();
+    let scales = named_scales();
+    let mut named = NamedComplement { pcs: complement.clone(), scales: vec![], chords: vec![] };
+    for (i, root) in complement.iter().enumerate() {
+        let from_root = complement.rotate(i as isize);
+        if let Some(scale) = scales.iter().find(|scale| scale.pc_set == from_root) {
+            named.scales.push((*root, scale.clone()));
+        }
+        if let Some((_, Some(quality))) = infer_chord_quality(&from_root.into()) {
+            named.chords.push((*root, quality));
+        }
+    }
+    named
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chord_symbol_lookup() {
+        let symbol = |spelling: &str| find_named_chord(spelling).map(|chord| chord.symbol);
+        assert_eq!(symbol("Maj7"), Some("maj7"));
+        assert_eq!(symbol("M7"), Some("maj7"));
+        assert_eq!(symbol("m7"), Some("m7"));
+        assert_eq!(symbol("-7"), Some("m7"));
+        assert_eq!(symbol("min7(b5)"), Some("m7b5"));
+        assert_eq!(symbol("ø7"), Some("m7b5"));
+        assert_eq!(symbol("7(♭9)"), Some("7b9"));
+        assert_eq!(symbol("m(Maj7)"), Some("mMaj7"));
+        assert_eq!(symbol("69"), Some("6/9"));
+        assert_eq!(symbol("13(#11)"), Some("13#11"));
+        assert_eq!(symbol("ALT"), Some("alt"));
+        assert_eq!(symbol(""), Some(""));
+        assert_eq!(symbol("7b10"), None);
+        // Letters that lowercase to more than one character are left as they are.
+        assert_eq!(normalize_quality_symbol("mİn7"), "mi̇n7");
+
+        let m11 = find_named_chord("m11").unwrap();
+        assert_eq!(m11.pc_set(), PcSet::from(vec![0, 2, 3, 5, 7, 10]));
+
+        let corpus = named_chords();
+        let closest = closest_named_chords("maj8", &corpus);
+        assert_eq!(closest[0].1, 1);
+        assert!(closest.iter().take_while(|(_, distance)| *distance == 1).any(|(chord, _)| chord.symbol == "maj7"));
+    }
+
+    #[test]
+    fn corpus_is_consistent() {
+        let corpus = named_chords();
+        for (i, chord) in corpus.iter().enumerate() {
+            // No two chords share a spelling, nor a pitch class set.
+            for other in &corpus[i + 1..] {
+                assert_ne!(chord.pc_set(), other.pc_set(), "{} and {}", chord.symbol, other.symbol);
+                for spelling in chord.spellings() {
+                    assert!(
+                        other.spellings().all(|s| normalize_quality_symbol(s) != normalize_quality_symbol(spelling)),
+                        "{} and {}", chord.symbol, other.symbol,
+                    );
+                }
+            }
+            // Every chord can be named by inference.
+            let pcs = chord.pc_set().into();
+            assert!(matches!(infer_chord_quality(&pcs), Some((_, Some(_)))), "{}", chord.symbol);
+        }
+    }
+
+    #[test]
+    fn scale_corpus() {
+        let names = |notes: Vec<Note>| notes.iter().map(|note| note.to_string()).collect::<Vec<_>>();
+        let hungarian = find_named_scale_by_name("gypsy minor").unwrap();
+        assert_eq!(names(hungarian.spell(&Note::A).unwrap()), vec!["A", "B", "C", "D#", "E", "F", "G#"]);
+        let altered = find_named_scale_by_name("Altered").unwrap();
+        assert_eq!(names(altered.spell(&Note::G).unwrap()), vec!["G", "Ab", "Bb", "Cb", "Db", "Eb", "F"]);
+        let octatonic = find_named_scale_by_name("Messiaen Mode 2").unwrap();
+        assert_eq!(octatonic.spell(&Note::C).unwrap().len(), 8);
+
+        // The modes of melodic minor are named for what they are.
+        let melodic_minor = find_named_scale_by_name("Melodic Minor").unwrap();
+        let modes: Vec<_> = melodic_minor.modes().into_iter()
+            .map(|(_, named)| named.unwrap().name)
+            .collect();
+        assert_eq!(modes[3], "Lydian Dominant");
+        assert_eq!(modes[6], "Altered");
+        assert_eq!(find_named_scale(&PcSet::from(vec![0, 2, 3, 7, 8])).unwrap().name, "Hirajoshi");
+        assert_eq!(find_named_scale(&PcSet::from(vec![0, 3, 5, 7, 10])).unwrap().name, "Minor Pentatonic");
+        // Every mode of the whole tone scale is the whole tone scale.
+        let whole_tone = find_named_scale_by_name("whole tone").unwrap();
+        assert!(whole_tone.modes().iter().all(|(_, named)| named.as_ref() == Some(&whole_tone)));
+
+        let scales = named_scales();
+        for (i, scale) in scales.iter().enumerate() {
+            assert!(scales[i + 1..].iter().all(|other| other.pc_set != scale.pc_set), "{}", scale.name);
+        }
+    }
+
+    #[test]
+    fn complement_names() {
+        let pentatonic = PcSet::from(vec![0, 2, 4, 7, 9]);
+        let named = name_complement(&pentatonic);
+        assert_eq!(named.pcs, PcSet::from(vec![1, 3, 5, 6, 8, 10, 11]));
+        let scales: Vec<(Pc, &str)> = named.scales.iter().map(|(root, scale)| (*root, scale.name)).collect();
+        assert_eq!(scales.len(), 7);
+        assert!(scales.contains(&(Pc::Pc6, "Ionian")));
+        assert!(scales.contains(&(Pc::Pc1, "Mixolydian")));
+
+        // Leaving out all but a triad names the triad.
+        let named = name_complement(&PcSet::from(vec![1, 2, 3, 5, 6, 8, 9, 10, 11]));
+        assert_eq!(named.chords, vec![(Pc::Pc0, find_named_chord("").unwrap().quality)]);
+        assert!(named.scales.is_empty());
+    }
+}
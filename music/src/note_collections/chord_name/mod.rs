@@ -1,11 +1,24 @@
+pub mod corpus;
 pub mod quality;
 pub mod naming_heuristics;
+pub mod profile;
+pub mod session;
 
+use crate::prelude::*;
+use crate::error::MusicSemanticsError;
 use crate::note_collections::pc_set::PcSet;
+use crate::note_collections::{NoteSet, Voicing};
+use crate::note_collections::spelling::{spell_pc_set_with, SpellingPreference};
+use crate::note_collections::geometry::symmetry::transpositional::Transpose;
+use crate::note::locale::NoteNameLocale;
 use crate::note::note::Note;
 use crate::note::pitch_class::Pc;
+use crate::note::spelling::{Accidental, Spelling};
 
-pub use quality::chord::ChordQuality;
+pub use quality::chord::{ChordQuality, Omission};
+pub use profile::AnalysisProfile;
+pub use session::ChordNameSession;
+use naming_heuristics::{infer_chord_quality, infer_chord_quality_with, HeuristicRegistry};
 
 /// The means by which to stylize the text that denotes
 /// a chord's extensions. There are a number of mutually incompatible
@@ -25,6 +38,20 @@ pub enum ExtensionStyle {
     HighestUnlessOne,
 }
 
+/// Whether, and how, to show the tones of a chord's triad that are missing from it,
+/// e.g. the fifth of CMaj7 (omit5). Naming tolerates missing thirds and fifths, so
+/// without them, a shell voicing or a grip that drops tones is named as the full chord.
+#[derive(Debug, Default, Copy, Clone)]
+pub enum OmissionStyle {
+    /// Name the chord as though nothing were missing.
+    #[default]
+    Hidden,
+    /// e.g. CMaj7 (omit5)
+    Omit,
+    /// e.g. C7 (no3)
+    No,
+}
+
 /// Chords can be displayed in a number of ways, and users might have different
 /// preferences over the matter.
 /// This configuration struct provides fine-grained control over a number
@@ -36,7 +63,7 @@ pub struct ChordNameDisplayConfig {
     /// Whether or not to express sus4, 7sus4, 9sus4, etc.
     /// as sus, 7sus, 9sus.
     pub explicit_sus4: bool,
-    /// Use fancy utf-8 chars for notes.
+    /// Write sharps and flats as ♯ and ♭, in the names of notes and of alterations, e.g. B♭7 (♯11).
     pub uft8_accidentals: bool,
     /// Number of space chars to put between the root note and the chord quality.
     pub space_between_root_and_quality: usize,
@@ -48,6 +75,125 @@ pub struct ChordNameDisplayConfig {
     /// This is a practical assumption that usually doesn't apply in settings
     /// outside of classical music theory.
     pub extension_style: ExtensionStyle,
+    /// How to spell the root and bass notes.
+    pub spelling: SpellingPreference,
+    /// How to name the root and bass notes.
+    pub locale: NoteNameLocale,
+    /// Whether to name dyads as guitarists do: a root and perfect fifth as a power chord, e.g. C5,
+    /// rather than as an interval, e.g. C(P5), and a root and tritone as C(b5), rather than C(TT).
+    pub power_chords: bool,
+    /// How to show the tones of the triad missing from a chord, if at all.
+    pub omissions: OmissionStyle,
+}
+
+impl ChordNameDisplayConfig {
+    /// Starts from the default configuration, see [ChordNameDisplayConfigBuilder].
+    pub fn builder() -> ChordNameDisplayConfigBuilder {
+        ChordNameDisplayConfigBuilder::from(Self::default())
+    }
+
+    /// As in a jazz fake book: named by the highest extension, with ♯ and ♭, and nothing shown as omitted.
+    pub fn real_book() -> Self {
+        Self::builder()
+            .utf8(true)
+            .extension_style(ExtensionStyle::Highest)
+            .build()
+    }
+
+    /// As on a quick chart for a session band: power chords, and as few extensions as will do.
+    pub fn nashville() -> Self {
+        Self::builder()
+            .power_chords(true)
+            .extension_style(ExtensionStyle::HighestUnlessOne)
+            .build()
+    }
+
+    /// Plain ASCII, with "#" and "b" for sharps and flats, e.g. for text files and terminals.
+    /// This is the default.
+    pub fn ascii() -> Self {
+        Self::default()
+    }
+}
+
+/// Builds a [ChordNameDisplayConfig], one setting at a time,
+/// from the default or from any other configuration, e.g. one of its presets.
+#[derive(Debug, Clone)]
+pub struct ChordNameDisplayConfigBuilder {
+    cfg: ChordNameDisplayConfig,
+}
+
+impl ChordNameDisplayConfigBuilder {
+    /// See [ChordNameDisplayConfig::uft8_accidentals].
+    pub fn utf8(mut self, utf8: bool) -> Self {
+        self.cfg.uft8_accidentals = utf8;
+        self
+    }
+
+    pub fn space_between_root_and_quality(mut self, spaces: usize) -> Self {
+        self.cfg.space_between_root_and_quality = spaces;
+        self
+    }
+
+    pub fn space_between_quality_and_slash(mut self, spaces: usize) -> Self {
+        self.cfg.space_between_quality_and_slash = spaces;
+        self
+    }
+
+    pub fn space_after_slash(mut self, spaces: usize) -> Self {
+        self.cfg.space_after_slash = spaces;
+        self
+    }
+
+    pub fn extension_style(mut self, style: ExtensionStyle) -> Self {
+        self.cfg.extension_style = style;
+        self
+    }
+
+    pub fn spelling(mut self, spelling: SpellingPreference) -> Self {
+        self.cfg.spelling = spelling;
+        self
+    }
+
+    pub fn locale(mut self, locale: NoteNameLocale) -> Self {
+        self.cfg.locale = locale;
+        self
+    }
+
+    /// See [ChordNameDisplayConfig::power_chords].
+    pub fn power_chords(mut self, power_chords: bool) -> Self {
+        self.cfg.power_chords = power_chords;
+        self
+    }
+
+    pub fn omissions(mut self, style: OmissionStyle) -> Self {
+        self.cfg.omissions = style;
+        self
+    }
+
+    pub fn build(self) -> ChordNameDisplayConfig {
+        self.cfg
+    }
+}
+
+impl From<ChordNameDisplayConfig> for ChordNameDisplayConfigBuilder {
+    fn from(cfg: ChordNameDisplayConfig) -> Self {
+        Self { cfg }
+    }
+}
+
+/// `name`, the name of a note, or a chord's quality, with its sharps and flats written as ♯ and ♭.
+/// A "b" is only taken for a flat after the start, and at the end or before a number or another flat,
+/// as in Bb, Bbb, or b9.
+pub(crate) fn utf8_accidentals(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    chars.iter()
+        .enumerate()
+        .map(|(i, c)| match c {
+            '#' => '♯',
+            'b' if i > 0 && chars.get(i + 1).is_none_or(|next| next.is_ascii_digit() || *next == 'b') => '♭',
+            c => *c,
+        })
+        .collect()
 }
 
 /// Describes a [PcSet] using the chord lexicon fleshed out in [ChordQuality].
@@ -62,17 +208,197 @@ pub struct ChordName {
     pub quality: ChordQuality,
     /// Underlying set of pitch classes on which the name is being asserted.
     pub pc_set: PcSet,
+    /// The tones of the quality's triad missing from [ChordName::pc_set],
+    /// e.g. the fifth of a Maj7 shell voicing. See [ChordQuality::omissions].
+    pub omissions: Vec<Omission>,
 }
 
 impl ChordName {
-    pub fn to_string(&self, cfg: Option<&ChordNameDisplayConfig>) -> String {
-        let cfg = cfg
-            .map(|cfg| cfg.clone())
-            .unwrap_or_default();
-        self.quality.to_string(&cfg)
+    /// Names a [NoteSet] as a chord, treating its first note as the root.
+    /// Returns `None` if no chord quality can be inferred from the notes.
+    pub fn from_note_set(notes: &NoteSet) -> Option<Self> {
+        Self::from_note_set_with(notes, &HeuristicRegistry::new())
+    }
+
+    /// As [ChordName::from_note_set], but inferring the quality with the heuristics of a
+    /// [HeuristicRegistry], which may include some of one's own.
+    pub fn from_note_set_with(notes: &NoteSet, registry: &HeuristicRegistry) -> Option<Self> {
+        let root = notes.first()?;
+        let root_pc = Pc::from(root);
+        let pcs: HashSet<Pc> = notes.iter()
+            .map(|note| Pc::from(&root_pc.distance_up_to(&Pc::from(note))))
+            .collect();
+        let quality = infer_chord_quality_with(&pcs, registry)?.1?;
+        Some(Self {
+            tonality: TonalSpecification::RootPosition(*root),
+            omissions: quality.omissions(&pcs),
+            quality,
+            pc_set: PcSet::from(notes),
+        })
+    }
+
+    /// Spells out the notes of the chord from its root and [ChordName::quality],
+    /// rather than from [ChordName::pc_set], so that a parsed chord symbol can be
+    /// turned back into notes. The bass of a slash chord is included if it isn't
+    /// already a chord tone. Errors if the name has no root.
+    pub fn to_note_set(&self) -> Result<NoteSet, MusicSemanticsError> {
+        let (root, bass) = match &self.tonality {
+            TonalSpecification::RootPosition(root) => (root, None),
+            TonalSpecification::SlashChord { bass, root } => (root, Some(bass)),
+            TonalSpecification::None(_) => return Err(MusicSemanticsError::NoChordRoot),
+        };
+        let mut notes = self.quality.to_pc_set().try_spell(root)?;
+        if let Some(bass) = bass {
+            if notes.iter().all(|note| Pc::from(note) != Pc::from(bass)) {
+                notes.push(*bass);
+            }
+        }
+        Ok(NoteSet::new(notes, Some(root)))
+    }
+
+    /// The chord transposed up, or down if negative, some number of semitones, with the same
+    /// quality and omissions. The root is respelled according to a [SpellingPreference].
+    ///
+    /// Under [SpellingPreference::Contextual], a root is spelled as a natural if it can be,
+    /// and otherwise with the same kind of accidental it had, e.g. Bb7 up a whole step is C7,
+    /// not B#7, and Eb up a whole step is F. A natural root that becomes a black key is spelled
+    /// as [SpellingPreference::MinimizeAccidentals] would, from whichever spelling gives the chord
+    /// the fewest accidentals. The bass of a slash chord keeps its letter distance from the root,
+    /// e.g. C/E up a semitone is Db/F.
+    pub fn transpose(&self, semitones: i8, preference: &SpellingPreference) -> Self {
+        let tonality = match &self.tonality {
+            TonalSpecification::RootPosition(root) => TonalSpecification::RootPosition(
+                self.transposed_root(root, semitones, preference),
+            ),
+            TonalSpecification::SlashChord { bass, root } => {
+                let new_root = self.transposed_root(root, semitones, preference);
+                let new_bass = match preference {
+                    SpellingPreference::Contextual | SpellingPreference::MinimizeAccidentals => {
+                        Pc::from(bass).transpose(semitones).notes().into_iter()
+                            .find(|note| {
+                                new_root.diatonic_distance_up(note) == root.diatonic_distance_up(bass)
+                                    && !Spelling::from(note).acc.is_double()
+                            })
+                            .unwrap_or_else(|| preference.spell_pc(&Pc::from(bass).transpose(semitones)))
+                    },
+                    _ => preference.spell_pc(&Pc::from(bass).transpose(semitones)),
+                };
+                TonalSpecification::SlashChord { bass: new_bass, root: new_root }
+            },
+            TonalSpecification::None(bass) => TonalSpecification::None(bass.map(|pc| pc.transpose(semitones))),
+        };
+        Self { tonality, ..self.clone() }
+    }
+
+    fn transposed_root(&self, root: &Note, semitones: i8, preference: &SpellingPreference) -> Note {
+        let pc = Pc::from(root).transpose(semitones);
+        let notes = pc.notes();
+        let spelled_with = |accidental: Accidental| notes.iter()
+            .find(|note| Spelling::from(*note).acc == accidental)
+            .copied();
+        let fewest_accidentals = || {
+            spell_pc_set_with(&notes[0], &self.quality.to_pc_set(), &SpellingPreference::MinimizeAccidentals)
+                .ok()
+                .and_then(|spelled| spelled.first().copied())
+                .unwrap_or(notes[0])
+        };
+        match preference {
+            SpellingPreference::Contextual => spelled_with(Accidental::Natural)
+                .or_else(|| match Spelling::from(root).acc {
+                    Accidental::Natural => None,
+                    accidental => spelled_with(accidental),
+                })
+                .unwrap_or_else(fewest_accidentals),
+            SpellingPreference::MinimizeAccidentals => spelled_with(Accidental::Natural)
+                .unwrap_or_else(fewest_accidentals),
+            _ => preference.spell_pc(&pc),
+        }
+    }
+
+    /// Renders the quality of the chord, as it's written after the root, e.g. "Maj7",
+    /// with any omissions. See [ChordName::symbol] for the whole name.
+    pub fn to_string<'a>(&self, cfg: impl Into<Option<&'a ChordNameDisplayConfig>>) -> String {
+        self.quality.to_string_with_omissions(&self.omissions, cfg)
+    }
+
+    /// Renders the chord name, including any root and bass note
+    /// given by [ChordName::tonality], and any omissions, see [OmissionStyle].
+    /// Takes a configuration, `Some` configuration, or `None` for the default.
+    pub fn symbol<'a>(&self, cfg: impl Into<Option<&'a ChordNameDisplayConfig>>) -> String {
+        let default = ChordNameDisplayConfig::default();
+        let cfg = cfg.into().unwrap_or(&default);
+        // A single note is named by its root alone.
+        let quality = match self.quality {
+            ChordQuality::SingleNote => String::new(),
+            _ => self.quality.to_string_with_omissions(&self.omissions, cfg).trim_end().to_string(),
+        };
+        let name = |note: &Note| {
+            let name = cfg.locale.name(&cfg.spelling.spell_note(note));
+            if cfg.uft8_accidentals { utf8_accidentals(&name) } else { name }
+        };
+        match &self.tonality {
+            TonalSpecification::RootPosition(root) => format!(
+                "{}{}{}",
+                name(root),
+                " ".repeat(cfg.space_between_root_and_quality),
+                quality,
+            ),
+            TonalSpecification::SlashChord { bass, root } => format!(
+                "{}{}{}{}/{}{}",
+                name(root),
+                " ".repeat(cfg.space_between_root_and_quality),
+                quality,
+                " ".repeat(cfg.space_between_quality_and_slash),
+                " ".repeat(cfg.space_after_slash),
+                name(bass),
+            ),
+            TonalSpecification::None(_) => quality,
+        }
     }
 }
 
+/// Names a [Voicing] as a chord, using its register to choose between the roots
+/// a set of pitch classes could be named from, e.g. C6 or Am7 for C E G A.
+///
+/// Of the roots that can be named, one in the bass is preferred. Otherwise, the root
+/// whose lowest pitch, or that of the perfect fifth above it, is closest to the bass wins,
+/// and the chord is named as a slash chord over the bass. Returns `None` for an empty voicing,
+/// or one that can't be named from any root.
+pub fn infer_chord_quality_voiced(voicing: &Voicing) -> Option<ChordName> {
+    let bass = voicing.first()?.note;
+    // The lowest pitch of each pitch class, in the order they appear from the bottom.
+    let mut roots: Vec<(usize, Note)> = vec![];
+    for (i, pitch) in voicing.iter().enumerate() {
+        if roots.iter().all(|(_, note)| Pc::from(note) != Pc::from(&pitch.note)) {
+            roots.push((i, pitch.note));
+        }
+    }
+    let lowest = |pc: Pc| roots.iter().find(|(_, note)| Pc::from(note) == pc).map(|(i, _)| *i);
+    roots.iter()
+        .filter_map(|(i, root)| {
+            let root_pc = Pc::from(root);
+            let pcs: HashSet<Pc> = roots.iter()
+                .map(|(_, note)| Pc::from(&root_pc.distance_up_to(&Pc::from(note))))
+                .collect();
+            let quality = infer_chord_quality(&pcs)?.1?;
+            let omissions = quality.omissions(&pcs);
+            let fifth = lowest(Pc::from(&(u8::from(&root_pc) + 7)));
+            let support = fifth.map_or(*i, |fifth| fifth.min(*i));
+            Some(((*i != 0, support, *i), *root, quality, omissions))
+        })
+        .min_by_key(|(score, ..)| *score)
+        .map(|(_, root, quality, omissions)| ChordName {
+            tonality: if Pc::from(&root) == Pc::from(&bass) {
+                TonalSpecification::RootPosition(root)
+            } else {
+                TonalSpecification::SlashChord { bass, root }
+            },
+            quality,
+            pc_set: PcSet::new(roots.iter().map(|(_, note)| Pc::from(note)).collect()),
+            omissions,
+        })
+}
+
 /// Whether or not something is a slash chord.
 /// All specified notes are assumed to be members of their associated `Vec<Pc>`.
 #[derive(Debug, Clone)]
@@ -87,4 +413,151 @@ pub enum TonalSpecification {
     /// No tonal specification. The `Option<Pc>` specifies any possible bass note.
     /// The relevant bass note must be an element in the `Vec<Pc>` being named.
     None(Option<Pc>)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::pitch::Pitch;
+
+    fn voicing(notes: &[(Note, u8)]) -> Voicing {
+        Voicing::new(notes.iter().map(|(note, octave)| Pitch::new(*note, *octave).unwrap()).collect())
+    }
+
+    #[test]
+    fn voiced_chord_names() {
+        let name = |notes: &[(Note, u8)]| infer_chord_quality_voiced(&voicing(notes))
+            .map(|name| name.symbol(None));
+        // The same pitch classes, named from whichever root is in the bass.
+        assert_eq!(name(&[(Note::C, 3), (Note::E, 3), (Note::G, 3), (Note::A, 3)]), Some("C6".to_string()));
+        assert_eq!(name(&[(Note::A, 2), (Note::E, 3), (Note::G, 3), (Note::C, 4)]), Some("Amin7".to_string()));
+        assert_eq!(name(&[(Note::A, 2), (Note::E, 3), (Note::Fis, 3), (Note::C, 4)]), Some("Amin6".to_string()));
+        // With E in the bass, it's the fifth of A that supports A as the root.
+        assert_eq!(name(&[(Note::E, 2), (Note::A, 2), (Note::C, 3), (Note::G, 3)]), Some("Amin7/E".to_string()));
+        // Without the root and bass, as the quality alone.
+        let slash_chord = infer_chord_quality_voiced(&voicing(&[(Note::E, 2), (Note::A, 2), (Note::C, 3), (Note::G, 3)]));
+        assert_eq!(slash_chord.unwrap().to_string(None), "min7");
+        assert_eq!(name(&[]), None);
+
+        // A power chord with its octave, and a tritone, as a guitarist would name them.
+        let cfg = ChordNameDisplayConfig { power_chords: true, ..Default::default() };
+        let power_chord = infer_chord_quality_voiced(&voicing(&[(Note::A, 2), (Note::E, 3), (Note::A, 3)])).unwrap();
+        assert_eq!(power_chord.symbol(Some(&cfg)), "A5");
+        assert_eq!(power_chord.symbol(None), "A(P5)");
+        let tritone = infer_chord_quality_voiced(&voicing(&[(Note::E, 2), (Note::Bes, 2)])).unwrap();
+        assert_eq!(tritone.symbol(Some(&cfg)), "E(b5)");
+    }
+
+    #[test]
+    fn six_chord_qualities() {
+        use quality::chord::{Alt, AltChoice, MajorSubtype, MinorSubtype};
+        let sharp_eleven = || Alt::from(vec![AltChoice::SharpEleven]);
+        // 6 and m6 chords were written "Maj" and "min", the same as the triads without the sixth.
+        assert_eq!(ChordQuality::Major(MajorSubtype::Maj6(Alt::empty())).to_string(None), "6");
+        assert_eq!(ChordQuality::Minor(MinorSubtype::Min6(Alt::empty())).to_string(None), "min6");
+        assert_eq!(ChordQuality::Major(MajorSubtype::Maj6(sharp_eleven())).to_string(None), "6 (#11)");
+        assert_eq!(ChordQuality::Minor(MinorSubtype::Min6(sharp_eleven())).to_string(None), "min6 (#11)");
+        // The triads are written as they were.
+        assert_eq!(ChordQuality::Major(MajorSubtype::Maj(Alt::empty())).to_string(None), "Maj");
+        assert_eq!(ChordQuality::Minor(MinorSubtype::Min(Alt::empty())).to_string(None), "min");
+        assert_eq!(ChordQuality::Major(MajorSubtype::Maj(sharp_eleven())).to_string(None), "Maj (#11)");
+
+        // C E G A was named "CMaj (13)", with the sixth taken as an alteration of the 6 chord.
+        let c_six = ChordName::from_note_set(&NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G, Note::A]));
+        assert_eq!(c_six.unwrap().symbol(None), "C6");
+        let a_min_six = ChordName::from_note_set(&NoteSet::starting_from_first_note(vec![Note::A, Note::C, Note::E, Note::Fis]));
+        assert_eq!(a_min_six.unwrap().symbol(None), "Amin6");
+    }
+
+    #[test]
+    fn omitted_tones() {
+        let name = |notes: Vec<Note>, omissions| {
+            let cfg = ChordNameDisplayConfig { omissions, ..Default::default() };
+            ChordName::from_note_set(&NoteSet::starting_from_first_note(notes)).unwrap().symbol(Some(&cfg))
+        };
+        // A shell voicing, and a grip without its third.
+        assert_eq!(name(vec![Note::C, Note::E, Note::B], OmissionStyle::Hidden), "CMaj7");
+        assert_eq!(name(vec![Note::C, Note::E, Note::B], OmissionStyle::Omit), "CMaj7 (omit5)");
+        assert_eq!(name(vec![Note::C, Note::G, Note::Bes], OmissionStyle::No), "C7 (no3)");
+        assert_eq!(name(vec![Note::C, Note::Bes, Note::D], OmissionStyle::No), "C7 (9, no3, no5)");
+        // Nothing is missing from a full chord, or from sus chords without their thirds.
+        assert_eq!(name(vec![Note::C, Note::E, Note::G, Note::B], OmissionStyle::Omit), "CMaj7");
+        assert_eq!(name(vec![Note::C, Note::F, Note::G], OmissionStyle::Omit), "Csus4");
+
+        let voiced = infer_chord_quality_voiced(&voicing(&[(Note::E, 2), (Note::D, 3), (Note::Gis, 3)])).unwrap();
+        assert_eq!(voiced.omissions, vec![Omission::Fifth]);
+        let cfg = ChordNameDisplayConfig { omissions: OmissionStyle::No, ..Default::default() };
+        assert_eq!(voiced.symbol(Some(&cfg)), "E7 (no5)");
+    }
+
+    #[test]
+    fn display_presets() {
+        let chord = |notes: Vec<Note>| ChordName::from_note_set(&NoteSet::starting_from_first_note(notes)).unwrap();
+        let b_flat_13 = chord(vec![Note::Bes, Note::D, Note::F, Note::Aes, Note::C, Note::G]);
+        let sharp_eleven = chord(vec![Note::Fis, Note::Ais, Note::Cis, Note::E, Note::Bis]);
+        assert_eq!(b_flat_13.symbol(&ChordNameDisplayConfig::real_book()), "B♭13 (9)");
+        assert_eq!(sharp_eleven.symbol(&ChordNameDisplayConfig::real_book()), "F♯7 (♯11)");
+        assert_eq!(b_flat_13.symbol(&ChordNameDisplayConfig::ascii()), "Bb7 (9, 13)");
+        assert_eq!(sharp_eleven.symbol(&ChordNameDisplayConfig::ascii()), "F#7 (#11)");
+        assert_eq!(chord(vec![Note::E, Note::B]).symbol(&ChordNameDisplayConfig::nashville()), "E5");
+
+        // A configuration, `Some` configuration, or `None` will do.
+        let cfg = ChordNameDisplayConfig::builder()
+            .utf8(true)
+            .extension_style(ExtensionStyle::Highest)
+            .space_between_root_and_quality(1)
+            .build();
+        assert_eq!(b_flat_13.symbol(&cfg), "B♭ 13 (9)");
+        assert_eq!(b_flat_13.symbol(Some(&cfg)), "B♭ 13 (9)");
+        assert_eq!(b_flat_13.symbol(None), b_flat_13.symbol(&ChordNameDisplayConfig::default()));
+        assert_eq!(b_flat_13.quality.to_string(&cfg), "13 (9)");
+        // A preset, changed.
+        let german = ChordNameDisplayConfigBuilder::from(ChordNameDisplayConfig::real_book())
+            .locale(NoteNameLocale::German)
+            .build();
+        assert_eq!(b_flat_13.symbol(&german), "B13 (9)");
+
+        assert_eq!(utf8_accidentals("Bbb"), "B♭♭");
+        assert_eq!(utf8_accidentals("min7b5 (b9)"), "min7♭5 (♭9)");
+        assert_eq!(utf8_accidentals("sus (add b)"), "sus (add b)");
+    }
+
+    #[test]
+    fn transposed_chord_names() {
+        let chord = |notes: Vec<Note>| ChordName::from_note_set(&NoteSet::starting_from_first_note(notes)).unwrap();
+        let transposed = |name: &ChordName, semitones: i8, preference: SpellingPreference| {
+            name.transpose(semitones, &preference).symbol(None)
+        };
+        use SpellingPreference::*;
+        let b_flat_7 = chord(vec![Note::Bes, Note::D, Note::F, Note::Aes]);
+        assert_eq!(transposed(&b_flat_7, 2, Contextual), "C7");
+        assert_eq!(transposed(&b_flat_7, 1, Contextual), "B7");
+        assert_eq!(transposed(&b_flat_7, 5, Contextual), "Eb7");
+        assert_eq!(transposed(&b_flat_7, -2, Contextual), "Ab7");
+        assert_eq!(transposed(&b_flat_7, 2, PreferSharps), "C7");
+        assert_eq!(transposed(&b_flat_7, 3, PreferSharps), "C#7");
+        assert_eq!(transposed(&b_flat_7, 12, Contextual), "Bb7");
+        // A natural root becomes whichever black key spells the chord more simply.
+        let c_major = chord(vec![Note::C, Note::E, Note::G]);
+        assert_eq!(transposed(&c_major, 1, Contextual), "DbMaj");
+        assert_eq!(transposed(&c_major, 1, PreferSharps), "C#Maj");
+        let a_minor_7 = chord(vec![Note::A, Note::C, Note::E, Note::G]);
+        assert_eq!(transposed(&a_minor_7, 1, Contextual), "Bbmin7");
+        assert_eq!(transposed(&a_minor_7, -1, Contextual), "G#min7");
+        // The quality, omissions, and pitch classes are unchanged.
+        let shell = chord(vec![Note::C, Note::E, Note::B]);
+        let up = shell.transpose(5, &Contextual);
+        assert_eq!(up.symbol(&ChordNameDisplayConfig::builder().omissions(OmissionStyle::Omit).build()), "FMaj7 (omit5)");
+        assert_eq!(up.pc_set, shell.pc_set);
+
+        // Major triads over their thirds.
+        let slash = |notes: Vec<Note>| ChordName {
+            tonality: TonalSpecification::SlashChord { bass: notes[1], root: notes[0] },
+            ..chord(notes)
+        };
+        assert_eq!(transposed(&slash(vec![Note::C, Note::E, Note::G]), 1, Contextual), "DbMaj/F");
+        assert_eq!(transposed(&slash(vec![Note::Bes, Note::D, Note::F]), 2, Contextual), "CMaj/E");
+        assert_eq!(transposed(&slash(vec![Note::D, Note::Fis, Note::A]), -2, Contextual), "CMaj/E");
+        assert_eq!(transposed(&slash(vec![Note::F, Note::A, Note::C]), 1, PreferSharps), "F#Maj/A#");
+        assert_eq!(transposed(&slash(vec![Note::F, Note::A, Note::C]), 1, PreferFlats), "GbMaj/Bb");
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use crate::prelude::*;
 use crate::note_collections::chord_name::quality::chord::{Alt, AltChoice, Extension};
 use crate::note::pitch_class::Pc;
 
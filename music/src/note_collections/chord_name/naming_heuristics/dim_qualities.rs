@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use crate::prelude::*;
 use crate::note_collections::chord_name::naming_heuristics::alts_and_extensions::{generate_alt, generate_alt_and_extensions, TriadContext};
 use crate::note_collections::chord_name::naming_heuristics::NamingHeuristic;
 use crate::note_collections::chord_name::quality::chord::{ChordQuality, DimSubtype};
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use crate::prelude::*;
 use crate::note_collections::chord_name::naming_heuristics::{maj_and_min_qualities, NamingHeuristic};
 use crate::note_collections::chord_name::quality::chord::ChordQuality;
 use crate::note::pitch_class::Pc;
@@ -0,0 +1,37 @@
+use crate::prelude::*;
+use crate::note_collections::chord_name::naming_heuristics::NamingHeuristic;
+use crate::note_collections::chord_name::quality::chord::ChordQuality;
+use crate::note_collections::interval_class::IntervalClass;
+use crate::note::pitch_class::Pc;
+use crate::note::pitch_class::Pc::*;
+
+/// Names a lone root as [ChordQuality::SingleNote].
+#[derive(Debug)]
+pub struct SingleNote;
+impl NamingHeuristic for SingleNote {
+    type T = ChordQuality;
+
+    fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ChordQuality> {
+        Some(ChordQuality::SingleNote)
+    }
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        pcs.iter().all(|pc| *pc == Pc0)
+    }
+}
+
+/// Names a root and one other pitch class as a [ChordQuality::Interval].
+#[derive(Debug)]
+pub struct Dyad;
+impl NamingHeuristic for Dyad {
+    type T = ChordQuality;
+
+    fn generate_name(&self, pcs: &HashSet<Pc>) -> Option<ChordQuality> {
+        let pc = pcs.iter().find(|pc| **pc != Pc0)?;
+        Some(ChordQuality::Interval(IntervalClass::from(&u8::from(pc))))
+    }
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        pcs.iter().filter(|pc| **pc != Pc0).count() == 1
+    }
+}
@@ -1,7 +1,7 @@
-use std::collections::HashSet;
+use crate::prelude::*;
 use crate::note_collections::chord_name::naming_heuristics::alts_and_extensions::{generate_alt, generate_alt_and_extensions, TriadContext};
 use crate::note_collections::chord_name::naming_heuristics::NamingHeuristic;
-use crate::note_collections::chord_name::quality::chord::{ChordQuality, MajorSubtype, MinorSubtype};
+use crate::note_collections::chord_name::quality::chord::{Alt, AltChoice, ChordQuality, MajorSubtype, MinorSubtype};
 use crate::note::pitch_class::Pc;
 use crate::note::pitch_class::Pc::*;
 
@@ -15,12 +15,14 @@ pub fn common_prefix(pcs: &HashSet<Pc>) -> Option<ChordQuality> {
             let (alt, ext) = generate_alt_and_extensions(pcs, TriadContext::Major);
             return Some(ChordQuality::Major(MajorSubtype::N(ext, alt)));
         }
-        if pcs.contains(&Pc9) {
-            let alt = generate_alt(pcs, TriadContext::Major);
-            return Some(ChordQuality::Major(MajorSubtype::Maj6(alt)));
-        }
         let alt = generate_alt(pcs, TriadContext::Major);
-        return Some(ChordQuality::Major(MajorSubtype::Maj(alt)));
+        return Some(ChordQuality::Major(match added_notes(pcs, alt) {
+            AddedNotes::SixNine(alt) => MajorSubtype::SixNine(alt),
+            AddedNotes::Six(alt) => MajorSubtype::Maj6(alt),
+            AddedNotes::Nine(alt) => MajorSubtype::Add9(alt),
+            AddedNotes::Eleven(alt) => MajorSubtype::Add11(alt),
+            AddedNotes::None(alt) => MajorSubtype::Maj(alt),
+        }));
     }
     if pcs.contains(&Pc3) {
         if pcs.contains(&Pc11) {
@@ -31,16 +33,48 @@ pub fn common_prefix(pcs: &HashSet<Pc>) -> Option<ChordQuality> {
             let (alt, ext) = generate_alt_and_extensions(pcs, TriadContext::Minor);
             return Some(ChordQuality::Minor(MinorSubtype::MinN(ext, alt)));
         }
-        if pcs.contains(&Pc9) {
-            let alt = generate_alt(pcs, TriadContext::Minor);
-            return Some(ChordQuality::Minor(MinorSubtype::Min6(alt)));
-        }
         let alt = generate_alt(pcs, TriadContext::Minor);
-        return Some(ChordQuality::Minor(MinorSubtype::Min(alt)));
+        return Some(ChordQuality::Minor(match added_notes(pcs, alt) {
+            AddedNotes::SixNine(alt) => MinorSubtype::Min69(alt),
+            AddedNotes::Six(alt) => MinorSubtype::Min6(alt),
+            AddedNotes::Nine(alt) => MinorSubtype::MinAdd9(alt),
+            AddedNotes::Eleven(alt) => MinorSubtype::MinAdd11(alt),
+            AddedNotes::None(alt) => MinorSubtype::Min(alt),
+        }));
     }
     None
 }
 
+/// The notes added to a major or minor triad with no seventh, which name it as e.g. a 6/9 or add9 chord,
+/// alongside whatever alterations are left over once those notes are taken out of `alt`.
+enum AddedNotes {
+    SixNine(Alt),
+    Six(Alt),
+    Nine(Alt),
+    Eleven(Alt),
+    None(Alt),
+}
+
+fn added_notes(pcs: &HashSet<Pc>, mut alt: Alt) -> AddedNotes {
+    if pcs.contains(&Pc9) && pcs.contains(&Pc2) {
+        alt.retain(|choice| *choice != AltChoice::Nine && *choice != AltChoice::Thirteenth);
+        return AddedNotes::SixNine(alt);
+    }
+    if pcs.contains(&Pc9) {
+        alt.retain(|choice| *choice != AltChoice::Thirteenth);
+        return AddedNotes::Six(alt);
+    }
+    if pcs.contains(&Pc2) {
+        alt.retain(|choice| *choice != AltChoice::Nine);
+        return AddedNotes::Nine(alt);
+    }
+    if pcs.contains(&Pc5) {
+        alt.retain(|choice| *choice != AltChoice::Eleven);
+        return AddedNotes::Eleven(alt);
+    }
+    AddedNotes::None(alt)
+}
+
 /// Common Logic across all heuristics based on diminished chords.
 pub fn search_for_maj_min_quality(pcs: &HashSet<Pc>) -> Option<ChordQuality> {
     if pcs.len() == 7 {
@@ -1,7 +1,8 @@
-use std::collections::HashSet;
+use crate::prelude::*;
 use crate::note_collections::chord_name::quality::chord::ChordQuality;
-use crate::note_collections::chord_name::quality::scale::ScaleQuality;
+use crate::note_collections::chord_name::quality::scale::{ScaleDescription, ScaleQuality};
 use crate::note::pitch_class::Pc;
+use crate::note_collections::weighted_pc_set::WeightedPcSet;
 use crate::note::pitch_class::Pc::*;
 
 pub mod maj_and_min_qualities;
@@ -10,6 +11,7 @@ pub mod aug_qualities;
 pub mod dim_qualities;
 pub mod sus_qualities;
 pub mod inferred_third_qualities;
+pub mod interval_qualities;
 pub mod scale_qualities;
 
 /// A Chord Naming Heuristic contains two sets:
@@ -23,7 +25,7 @@ pub mod scale_qualities;
 /// [NamingHeuristic::validate] returns true, we can then call [NamingHeuristic::generate_name].
 ///
 /// It is not required that a heuristic generate a name.
-pub trait NamingHeuristic: std::fmt::Debug {
+pub trait NamingHeuristic: core::fmt::Debug {
     /// For our purposes, either a [ChordQuality] or a [ScaleQuality].
     /// In principle, one could build their own naming system and put anything here,
     /// even a simple string.
@@ -53,6 +55,9 @@ pub trait NamingHeuristic: std::fmt::Debug {
     /// 1. All intersections with required `HashSet`s have only one element.
     /// 2. All elements in `pcs` are matched,
     ///    whether through required or optional `HashSet` intersections.
+    ///
+    /// [Pc0] is the root, and is ignored, so a set validates the same with or without it.
+    /// Whether a set has to contain its root is up to the caller, see [RootPolicy].
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
         // Clone and remove any Pc0.
         let mut pcs = pcs.clone();
@@ -84,13 +89,21 @@ pub trait NamingHeuristic: std::fmt::Debug {
 }
 
 /// A naming heuristic that produces a [ChordQuality].
-type ChordHeuristic = Box<dyn NamingHeuristic<T=ChordQuality>>;
+pub type ChordHeuristic = Box<dyn NamingHeuristic<T=ChordQuality>>;
 
 /// An order-sensitive list of all the various naming heuristics.
 /// The first heuristic to match on the content is applied to generating a name.
 pub fn chord_heuristics() -> Vec<ChordHeuristic> {
+    built_in_chord_heuristics(true)
+}
+
+/// The built-in heuristics, with those that name chords missing their thirds only if `infer_thirds`.
+fn built_in_chord_heuristics(infer_thirds: bool) -> Vec<ChordHeuristic> {
     // Order matters here! The first match will be dispatched to name generation.
-    vec![
+    let mut heuristics: Vec<ChordHeuristic> = vec![
+        // Single notes and intervals
+        Box::new(interval_qualities::SingleNote),
+        Box::new(interval_qualities::Dyad),
         // Major / minor
         Box::new(maj_and_min_qualities::MajOrMin69),
         Box::new(maj_and_min_qualities::MajSharpNine),
@@ -101,11 +114,15 @@ pub fn chord_heuristics() -> Vec<ChordHeuristic> {
         Box::new(maj_and_min_qualities::RootToThirdCluster),
         Box::new(maj_and_min_qualities::ThirdAndFourth),
         Box::new(maj_and_min_qualities::ThirdAndSharpFourth),
+    ];
+    if infer_thirds {
         // Inferred Major / minor (no third in the actual set)
-        Box::new(inferred_third_qualities::FifthAndUpperNotes),
-        Box::new(inferred_third_qualities::NinthAndSixthNoThird),
-        Box::new(inferred_third_qualities::TritoneAndSeventh),
-        Box::new(inferred_third_qualities::NinthAndSeventh),
+        heuristics.push(Box::new(inferred_third_qualities::FifthAndUpperNotes));
+        heuristics.push(Box::new(inferred_third_qualities::NinthAndSixthNoThird));
+        heuristics.push(Box::new(inferred_third_qualities::TritoneAndSeventh));
+        heuristics.push(Box::new(inferred_third_qualities::NinthAndSeventh));
+    }
+    let others: Vec<ChordHeuristic> = vec![
         // Aug chords
         Box::new(aug_qualities::AugChordQualities),
         // Dim chords
@@ -118,12 +135,89 @@ pub fn chord_heuristics() -> Vec<ChordHeuristic> {
         Box::new(sus_qualities::Altered13Sus),
         Box::new(sus_qualities::FourthAndSeventh),
         Box::new(sus_qualities::FlatSecondAndFourth),
-    ]
+    ];
+    heuristics.extend(others);
+    heuristics
+}
+
+/// Where a heuristic added to a [HeuristicRegistry] is tried, relative to the built-in ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeuristicPriority {
+    /// Tried before any of the built-in heuristics, so it overrides them wherever it matches.
+    BeforeBuiltIns,
+    /// Tried only once none of the built-in heuristics match.
+    AfterBuiltIns,
+}
+
+/// The chord naming heuristics used for inference, in the order they're tried.
+/// Starts out with [chord_heuristics], to which heuristics of one's own
+/// can be added, for naming chords in a house style.
+/// Heuristics registered at the same [HeuristicPriority] are tried in the order they were added.
+#[derive(Debug)]
+pub struct HeuristicRegistry {
+    before: Vec<ChordHeuristic>,
+    built_ins: Vec<ChordHeuristic>,
+    after: Vec<ChordHeuristic>,
+}
+
+impl HeuristicRegistry {
+    /// A registry of the built-in heuristics alone.
+    pub fn new() -> Self {
+        Self {
+            before: vec![],
+            built_ins: chord_heuristics(),
+            after: vec![],
+        }
+    }
+
+    /// A registry of the built-in heuristics, except those that name a chord missing its third,
+    /// e.g. C G Bb as C7, so that such chords go unnamed, unless they can be named otherwise.
+    pub fn without_inferred_thirds() -> Self {
+        Self {
+            before: vec![],
+            built_ins: built_in_chord_heuristics(false),
+            after: vec![],
+        }
+    }
+
+    /// A registry with no heuristics at all, not even the built-in ones.
+    pub fn empty() -> Self {
+        Self {
+            before: vec![],
+            built_ins: vec![],
+            after: vec![],
+        }
+    }
+
+    /// Adds a heuristic, to be tried at the given priority.
+    pub fn register(&mut self, heuristic: ChordHeuristic, priority: HeuristicPriority) -> &mut Self {
+        match priority {
+            HeuristicPriority::BeforeBuiltIns => self.before.push(heuristic),
+            HeuristicPriority::AfterBuiltIns => self.after.push(heuristic),
+        }
+        self
+    }
+
+    /// Every heuristic in the registry, in the order they're tried.
+    pub fn iter(&self) -> impl Iterator<Item = &ChordHeuristic> {
+        self.before.iter()
+            .chain(self.built_ins.iter())
+            .chain(self.after.iter())
+    }
+}
+
+impl Default for HeuristicRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Infer a [ChordQuality] from a `HashSet<Pc>`. This is a not guaranteed to produce a quality.
-/// Assumes at least three unique [crate::note::Pc] in `pcs`.
-/// Other possibilities should be screened out ahead of time
+/// The root, [Pc0], is taken to be in `pcs` whether or not it's included,
+/// so e.g. E G B is named as CMaj7. To treat sets without [Pc0] otherwise,
+/// see [infer_rooted_chord_quality].
+/// A root alone is named as [ChordQuality::SingleNote], and a root and one other
+/// [crate::note::Pc] as a [ChordQuality::Interval], so there's no need to screen them out ahead of time.
 pub fn infer_chord_quality(pcs: &HashSet<Pc>) -> Option<(ChordHeuristic, Option<ChordQuality>)> {
 
     // This way would be how I could collect answers from all the heuristics,
@@ -142,6 +236,101 @@ pub fn infer_chord_quality(pcs: &HashSet<Pc>) -> Option<(ChordHeuristic, Option<
     None
 }
 
+/// As [infer_chord_quality], but with the heuristics of a [HeuristicRegistry],
+/// which may include some of one's own.
+pub fn infer_chord_quality_with<'a>(
+    pcs: &HashSet<Pc>,
+    registry: &'a HeuristicRegistry,
+) -> Option<(&'a ChordHeuristic, Option<ChordQuality>)> {
+    registry.iter()
+        .find(|heuristic| heuristic.validate(pcs))
+        .map(|heuristic| (heuristic, heuristic.generate_name(pcs)))
+}
+
+/// How to find the root of a set of pitch classes being named as a chord, see [infer_rooted_chord_quality].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RootPolicy {
+    /// The root is [Pc0], which must be in the set, or no name is inferred.
+    RequireRoot,
+    /// The root is the lowest pitch class in the set, e.g. E for E G B, which is named as a minor triad.
+    AssumeFirstIsRoot,
+    /// Each pitch class in the set is tried as the root, from the lowest up,
+    /// and the first one the set can be named from is the root.
+    TryAllRoots,
+}
+
+/// As [infer_chord_quality], but with the root found according to `policy`,
+/// rather than always taken to be [Pc0]. Returns the root and the quality named from it.
+pub fn infer_rooted_chord_quality(pcs: &HashSet<Pc>, policy: RootPolicy) -> Option<(Pc, ChordQuality)> {
+    infer_rooted_chord_quality_with(pcs, policy, &HeuristicRegistry::new())
+}
+
+/// As [infer_rooted_chord_quality], but with the heuristics of a [HeuristicRegistry].
+pub fn infer_rooted_chord_quality_with(
+    pcs: &HashSet<Pc>,
+    policy: RootPolicy,
+    registry: &HeuristicRegistry,
+) -> Option<(Pc, ChordQuality)> {
+    let mut roots: Vec<Pc> = pcs.iter().copied().collect();
+    roots.sort();
+    let roots = match policy {
+        RootPolicy::RequireRoot => if pcs.contains(&Pc0) { vec![Pc0] } else { vec![] },
+        RootPolicy::AssumeFirstIsRoot => roots.into_iter().take(1).collect(),
+        RootPolicy::TryAllRoots => roots,
+    };
+    roots.into_iter().find_map(|root| {
+        let (_, quality) = infer_chord_quality_with(&from_root(pcs, &root), registry)?;
+        Some((root, quality?))
+    })
+}
+
+/// As [infer_rooted_chord_quality], but for pitch classes weighted by how much each is heard,
+/// so that light tones, e.g. passing or chromatic ones, don't get in the way of naming the chord.
+///
+/// Only the [WeightedPcSet::salient_pcs] are named, for `min_weight`. If they can't be named,
+/// the lightest is left out and the rest are tried again, for as long as there are more than three.
+/// Under [RootPolicy::TryAllRoots], the roots are tried from the heaviest down, rather than from the lowest up,
+/// since the root is most often the bass or the longest held note.
+pub fn infer_weighted_chord_quality(
+    pcs: &WeightedPcSet,
+    policy: RootPolicy,
+    min_weight: f64,
+) -> Option<(Pc, ChordQuality)> {
+    infer_weighted_chord_quality_with(pcs, policy, min_weight, &HeuristicRegistry::new())
+}
+
+/// As [infer_weighted_chord_quality], but with the heuristics of a [HeuristicRegistry].
+pub fn infer_weighted_chord_quality_with(
+    pcs: &WeightedPcSet,
+    policy: RootPolicy,
+    min_weight: f64,
+    registry: &HeuristicRegistry,
+) -> Option<(Pc, ChordQuality)> {
+    let mut salient: Vec<Pc> = pcs.salient_pcs(min_weight);
+    salient.sort_by(|a, b| pcs.weight(b).total_cmp(&pcs.weight(a)));
+    loop {
+        let tones: HashSet<Pc> = salient.iter().copied().collect();
+        let named = match policy {
+            RootPolicy::TryAllRoots => salient.iter().find_map(|root| {
+                let (_, quality) = infer_chord_quality_with(&from_root(&tones, root), registry)?;
+                Some((*root, quality?))
+            }),
+            _ => infer_rooted_chord_quality_with(&tones, policy, registry),
+        };
+        if named.is_some() || salient.len() <= 3 {
+            return named;
+        }
+        salient.pop();
+    }
+}
+
+/// `pcs` as intervals above `root`.
+fn from_root(pcs: &HashSet<Pc>, root: &Pc) -> HashSet<Pc> {
+    pcs.iter()
+        .map(|pc| Pc::from(&root.distance_up_to(pc)))
+        .collect()
+}
+
 /// A naming heuristic that produces a [ScaleQuality].
 type ScaleHeuristic = Box<dyn NamingHeuristic<T=ScaleQuality>>;
 
@@ -157,6 +346,12 @@ pub fn scale_heuristics() -> Vec<ScaleHeuristic> {
         Box::new(scale_qualities::HarmonicMinor),
         Box::new(scale_qualities::HarmonicMajor),
         Box::new(scale_qualities::AlteredScale),
+        Box::new(scale_qualities::BebopDominant),
+        Box::new(scale_qualities::BebopMajor),
+        Box::new(scale_qualities::BebopDorian),
+        Box::new(scale_qualities::BebopMelodicMinor),
+        // Any other eight note scale, before it's taken for a seven note scale with alterations
+        Box::new(scale_qualities::EightNoteScale),
         // Scales with possible alterations
         Box::new(scale_qualities::MajorScale),
         Box::new(scale_qualities::IonianAug),
@@ -187,10 +382,22 @@ pub fn infer_scale_quality(pcs: &HashSet<Pc>) -> Option<(ScaleHeuristic, Option<
     None
 }
 
+/// Describes `pcs`, with the tonic as [Pc0], as the [crate::note_collections::chord_name::quality::scale::ScaleMode]
+/// of the quality [infer_scale_quality] names it as, with alterations. See [ScaleDescription::new].
+pub fn describe_scale(pcs: &HashSet<Pc>) -> Option<ScaleDescription> {
+    let (_, quality) = infer_scale_quality(pcs)?;
+    Some(ScaleDescription::new(quality?.mode(), pcs))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::note_collections::chord_name::quality::chord::{Alt, Extension, MajorSubtype};
-    use crate::note_collections::PcSet;
+    use crate::note_collections::chord_name::quality::chord::{Alt, Extension, MajorSubtype, MinorSubtype};
+    use crate::note_collections::chord_name::quality::scale::{ScaleAlteration, ScaleMode};
+    use crate::note_collections::chord_name::corpus::ScaleFamily;
+    use crate::note::note::Note;
+    use crate::note_collections::chord_name::{ChordName, ChordNameDisplayConfig};
+    use crate::note_collections::interval_class::IntervalClass;
+    use crate::note_collections::{NoteSet, PcSet};
     use super::*;
 
     #[test]
@@ -200,7 +407,7 @@ mod tests {
         let quality = infer_chord_quality(&notes);
         let quality = quality.unwrap();
         let quality = quality.1.unwrap();
-        assert_eq!(quality.to_string(&Default::default()), "Maj7 (11)");
+        assert_eq!(quality.to_string(None), "Maj7 (11)");
         assert_eq!(
             quality,
              ChordQuality::Major(MajorSubtype::MajN(
@@ -232,5 +439,188 @@ mod tests {
         let quality = infer_scale_quality(&notes);
         println!("{:?}", quality);
     }
-}
+    #[test]
+    fn scale_descriptions() {
+        let set = |pcs: Vec<Pc>| -> HashSet<Pc> { pcs.into_iter().collect() };
+        let major = describe_scale(&set(vec![Pc0, Pc2, Pc4, Pc5, Pc7, Pc9, Pc11])).unwrap();
+        assert!(major.is_exact());
+        assert_eq!(major.confidence, 1.0);
+        assert_eq!(major.to_string(), "Major");
+        assert_eq!(major.mode.family(), Some((ScaleFamily::Diatonic, 0)));
 
+        let mixolydian_b6 = describe_scale(&set(vec![Pc0, Pc2, Pc4, Pc5, Pc7, Pc8, Pc10])).unwrap();
+        assert_eq!(mixolydian_b6.mode, ScaleMode::Mixolydian);
+        assert_eq!(mixolydian_b6.alterations, vec![ScaleAlteration::Altered { degree: 6, semitones: -1 }]);
+        assert_eq!(mixolydian_b6.to_string(), "Mixolydian (b6)");
+
+        let lydian_b3 = ScaleDescription::new(ScaleMode::Lydian, &set(vec![Pc0, Pc2, Pc3, Pc6, Pc7, Pc9, Pc11]));
+        assert_eq!(lydian_b3.to_string(), "Lydian (b3)");
+        assert_eq!(lydian_b3.confidence, 0.75);
+        // Tones with no degree of the mode to alter are added, and degrees with no tone near them omitted.
+        let dorian = ScaleDescription::new(ScaleMode::Dorian, &set(vec![Pc2, Pc3, Pc4, Pc5, Pc7, Pc10]));
+        assert_eq!(dorian.to_string(), "Dorian (add 3, no 6)");
+        assert_eq!(dorian.added(), vec![Pc4]);
+        assert_eq!(ScaleMode::ALL.iter().filter(|mode| mode.family().is_none()).count(), 7);
+    }
+
+    #[test]
+    fn eight_note_scales() {
+        let infer = |pcs: Vec<Pc>| infer_scale_quality(&pcs.into_iter().collect()).unwrap().1.unwrap();
+        assert_eq!(infer(vec![Pc0, Pc2, Pc4, Pc5, Pc7, Pc9, Pc10, Pc11]), ScaleQuality::BebopDominant);
+        assert_eq!(infer(vec![Pc0, Pc2, Pc3, Pc4, Pc5, Pc7, Pc9, Pc10]), ScaleQuality::BebopDorian);
+        assert_eq!(infer(vec![Pc0, Pc1, Pc3, Pc4, Pc6, Pc7, Pc9, Pc10]), ScaleQuality::DimHW);
+        // Otherwise, the seven note scale with a chromatic tone added.
+        let lydian_sharp_five = vec![Pc0, Pc2, Pc4, Pc6, Pc7, Pc8, Pc9, Pc11];
+        assert_eq!(infer(lydian_sharp_five.clone()), ScaleQuality::AddedTone(ScaleMode::Lydian, Pc8));
+        let description = describe_scale(&lydian_sharp_five.into_iter().collect()).unwrap();
+        assert_eq!(description.to_string(), "Lydian (add b6)");
+        let bebop_major = [Pc0, Pc2, Pc4, Pc5, Pc7, Pc8, Pc9, Pc11];
+        assert_eq!(describe_scale(&bebop_major.into_iter().collect()).unwrap().to_string(), "Bebop Major");
+    }
+
+    #[test]
+    fn chord_qualities_to_pc_sets() {
+        let chords = vec![
+            vec![Pc0, Pc2, Pc4, Pc7, Pc11],
+            vec![Pc0, Pc3, Pc7, Pc10],
+            vec![Pc0, Pc3, Pc6, Pc9],
+            vec![Pc0, Pc3, Pc4, Pc7, Pc10],
+            vec![Pc0, Pc5, Pc7, Pc10],
+            vec![Pc0, Pc2, Pc5, Pc7, Pc9],
+        ];
+        for pcs in chords {
+            let set: HashSet<Pc> = PcSet::from(&pcs).into();
+            let quality = infer_chord_quality(&set).unwrap().1.unwrap();
+            assert_eq!(quality.to_pc_set(), PcSet::from(pcs));
+        }
+        // Omitted fifths are restored.
+        let set: HashSet<Pc> = PcSet::from(vec![Pc0, Pc4, Pc10]).into();
+        let quality = infer_chord_quality(&set).unwrap().1.unwrap();
+        assert_eq!(quality.to_pc_set(), PcSet::from(vec![Pc0, Pc4, Pc7, Pc10]));
+    }
+
+    #[test]
+    fn dyads_and_single_notes() {
+        let infer = |pcs: Vec<Pc>| infer_chord_quality(&PcSet::from(pcs).into()).unwrap().1.unwrap();
+        assert_eq!(infer(vec![Pc0, Pc3]), ChordQuality::Interval(IntervalClass::Ic3));
+        assert_eq!(infer(vec![Pc0]), ChordQuality::SingleNote);
+        assert_eq!(infer(vec![]), ChordQuality::SingleNote);
+
+        let fifth = ChordName::from_note_set(&NoteSet::starting_from_first_note(vec![Note::C, Note::G])).unwrap();
+        assert_eq!(fifth.symbol(None), "C(P5)");
+        let cfg = ChordNameDisplayConfig { power_chords: true, ..Default::default() };
+        assert_eq!(fifth.symbol(Some(&cfg)), "C5");
+        assert_eq!(fifth.to_note_set().unwrap(), NoteSet::starting_from_first_note(vec![Note::C, Note::G]));
+        let single = ChordName::from_note_set(&NoteSet::new(vec![Note::E], None)).unwrap();
+        assert_eq!(single.symbol(None), "E");
+        assert!(ChordName::from_note_set(&NoteSet::new(vec![], None)).is_none());
+    }
+
+    #[test]
+    fn added_note_chords() {
+        let name = |notes: Vec<Note>| ChordName::from_note_set(&NoteSet::starting_from_first_note(notes))
+            .unwrap()
+            .symbol(None);
+        assert_eq!(name(vec![Note::C, Note::E, Note::G, Note::D]), "Cadd9");
+        assert_eq!(name(vec![Note::C, Note::Ees, Note::G, Note::D]), "Cmin(add9)");
+        assert_eq!(name(vec![Note::C, Note::E, Note::G, Note::F]), "Cadd11");
+        assert_eq!(name(vec![Note::C, Note::Ees, Note::G, Note::F]), "Cmin(add11)");
+        assert_eq!(name(vec![Note::C, Note::E, Note::G, Note::A, Note::D]), "C6/9");
+        assert_eq!(name(vec![Note::C, Note::Ees, Note::G, Note::A, Note::D]), "Cmin6/9");
+        // Anything else added is an alteration, and with a seventh, a ninth is an extension.
+        assert_eq!(name(vec![Note::C, Note::E, Note::G, Note::D, Note::Fis]), "Cadd9 (#11)");
+        assert_eq!(name(vec![Note::C, Note::E, Note::G, Note::B, Note::D]), "CMaj7 (9)");
+
+        for pcs in [vec![Pc0, Pc2, Pc4, Pc7], vec![Pc0, Pc3, Pc5, Pc7], vec![Pc0, Pc2, Pc3, Pc7, Pc9]] {
+            let quality = infer_chord_quality(&PcSet::from(&pcs).into()).unwrap().1.unwrap();
+            assert_eq!(quality.to_pc_set(), PcSet::from(pcs));
+        }
+    }
+
+    #[test]
+    fn root_policies() {
+        let infer = |pcs: Vec<Pc>, policy| infer_rooted_chord_quality(&pcs.into_iter().collect(), policy);
+        let e_minor = Some((Pc4, ChordQuality::Minor(MinorSubtype::Min(Alt::empty()))));
+        // Without the root, E G B is only named as CMaj7 when it's implied.
+        assert!(infer(vec![Pc4, Pc7, Pc11], RootPolicy::RequireRoot).is_none());
+        assert_eq!(infer(vec![Pc4, Pc7, Pc11], RootPolicy::AssumeFirstIsRoot), e_minor);
+        assert_eq!(infer(vec![Pc4, Pc7, Pc11], RootPolicy::TryAllRoots), e_minor);
+        assert_eq!(
+            infer(vec![Pc0, Pc4, Pc7], RootPolicy::RequireRoot),
+            Some((Pc0, ChordQuality::Major(MajorSubtype::Maj(Alt::empty())))),
+        );
+        assert!(infer(vec![], RootPolicy::AssumeFirstIsRoot).is_none());
+
+        // C D F# can't be named from C, but can as D7, from its seventh.
+        assert!(infer(vec![Pc0, Pc2, Pc6], RootPolicy::AssumeFirstIsRoot).is_none());
+        assert_eq!(
+            infer(vec![Pc0, Pc2, Pc6], RootPolicy::TryAllRoots),
+            Some((Pc2, ChordQuality::Major(MajorSubtype::N(vec![Extension::Seventh], Alt::empty())))),
+        );
+    }
+
+    #[test]
+    fn weighted_chord_names() {
+        let infer = |pcs: &[(Pc, f64)], policy| infer_weighted_chord_quality(&pcs.iter().copied().collect(), policy, 0.2);
+        let major = ChordQuality::Major(MajorSubtype::Maj(Alt::empty()));
+        // A brief Bb is too light to make a seventh chord of C E G.
+        assert_eq!(infer(&[(Pc0, 4.0), (Pc4, 3.0), (Pc7, 3.0), (Pc10, 0.5)], RootPolicy::RequireRoot), Some((Pc0, major)));
+        // Held longest, A is tried as the root before C.
+        let a_minor_seventh = [(Pc0, 3.0), (Pc4, 3.0), (Pc7, 2.0), (Pc9, 5.0)];
+        assert_eq!(
+            infer(&a_minor_seventh, RootPolicy::TryAllRoots),
+            Some((Pc9, ChordQuality::Minor(MinorSubtype::MinN(vec![Extension::Seventh], Alt::empty())))),
+        );
+        assert_eq!(
+            infer_rooted_chord_quality(&a_minor_seventh.iter().map(|(pc, _)| *pc).collect(), RootPolicy::TryAllRoots).map(|(root, _)| root),
+            Some(Pc0),
+        );
+        // C D D# E G can't be named, but without its lightest tone, the D#, it can.
+        assert_eq!(
+            infer(&[(Pc0, 4.0), (Pc2, 2.0), (Pc3, 1.5), (Pc4, 3.0), (Pc7, 3.0)], RootPolicy::RequireRoot),
+            Some((Pc0, ChordQuality::Major(MajorSubtype::Add9(Alt::empty())))),
+        );
+        assert!(infer(&[], RootPolicy::TryAllRoots).is_none());
+    }
+
+    /// Names a root, fifth and flat seventh as a minor seventh chord, rather than a dominant one.
+    #[derive(Debug)]
+    struct MinorSeventhShell;
+    impl NamingHeuristic for MinorSeventhShell {
+        type T = ChordQuality;
+
+        fn required(&self) -> Vec<HashSet<Pc>> {
+            vec![
+                HashSet::from([Pc7]),
+                HashSet::from([Pc10]),
+            ]
+        }
+
+        fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ChordQuality> {
+            Some(ChordQuality::Minor(MinorSubtype::MinN(vec![Extension::Seventh], Alt::from(vec![]))))
+        }
+    }
+
+    #[test]
+    fn registered_heuristics() {
+        let shell = NoteSet::starting_from_first_note(vec![Note::C, Note::G, Note::Bes]);
+        let name = |registry: &HeuristicRegistry| ChordName::from_note_set_with(&shell, registry)
+            .unwrap()
+            .symbol(None);
+        assert_eq!(name(&HeuristicRegistry::new()), "C7");
+
+        let mut registry = HeuristicRegistry::new();
+        registry.register(Box::new(MinorSeventhShell), HeuristicPriority::AfterBuiltIns);
+        assert_eq!(name(&registry), "C7");
+        registry.register(Box::new(MinorSeventhShell), HeuristicPriority::BeforeBuiltIns);
+        assert_eq!(name(&registry), "Cmin7");
+        assert_eq!(registry.iter().count(), chord_heuristics().len() + 2);
+
+        // Only the registered heuristic applies, and only to its own chord.
+        let mut registry = HeuristicRegistry::empty();
+        registry.register(Box::new(MinorSeventhShell), HeuristicPriority::AfterBuiltIns);
+        assert_eq!(name(&registry), "Cmin7");
+        let triad = PcSet::from(vec![Pc0, Pc4, Pc7]).into();
+        assert!(infer_chord_quality_with(&triad, &registry).is_none());
+    }
+}
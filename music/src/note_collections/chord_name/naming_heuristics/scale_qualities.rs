@@ -1,20 +1,22 @@
-use std::collections::HashSet;
-use once_cell::sync::Lazy;
+use crate::prelude::*;
 use crate::note_collections::chord_name::naming_heuristics::NamingHeuristic;
-use crate::note_collections::chord_name::quality::scale::{Alt2nd, Alt2ndMinor, Alt4th, Alt4thMinor, Alt6thAugMaj7, Alt6thDom7, Alt6thMaj7, ScaleQuality};
+use crate::note_collections::chord_name::quality::scale::{Alt2nd, Alt2ndMinor, Alt4th, Alt4thMinor, Alt6thAugMaj7, Alt6thDom7, Alt6thMaj7, ScaleMode, ScaleQuality};
 use crate::note::pitch_class::Pc;
 use crate::note::pitch_class::Pc::*;
 
-static WT_SCALE_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc2, Pc4, Pc6, Pc8, Pc10])
-});
+/// Whether `pcs` are exactly the pitch classes of `scale`, which are all different.
+fn is_exactly(pcs: &HashSet<Pc>, scale: &[Pc]) -> bool {
+    pcs.len() == scale.len() && scale.iter().all(|pc| pcs.contains(pc))
+}
+
+const WT_SCALE_NO_ROOT: [Pc; 5] = [Pc2, Pc4, Pc6, Pc8, Pc10];
 #[derive(Debug)]
 pub struct WholetoneScale;
 impl NamingHeuristic for WholetoneScale {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *WT_SCALE_NO_ROOT
+        is_exactly(pcs, &WT_SCALE_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -22,16 +24,14 @@ impl NamingHeuristic for WholetoneScale {
     }
 }
 
-static AUG_AH_SCALE_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc3, Pc4, Pc7, Pc8, Pc11])
-});
+const AUG_AH_SCALE_NO_ROOT: [Pc; 5] = [Pc3, Pc4, Pc7, Pc8, Pc11];
 #[derive(Debug)]
 pub struct AugAHScale;
 impl NamingHeuristic for AugAHScale {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *AUG_AH_SCALE_NO_ROOT
+        is_exactly(pcs, &AUG_AH_SCALE_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -39,16 +39,14 @@ impl NamingHeuristic for AugAHScale {
     }
 }
 
-static AUG_HA_SCALE_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc1, Pc4, Pc5, Pc8, Pc9])
-});
+const AUG_HA_SCALE_NO_ROOT: [Pc; 5] = [Pc1, Pc4, Pc5, Pc8, Pc9];
 #[derive(Debug)]
 pub struct AugHAScale;
 impl NamingHeuristic for AugHAScale {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *AUG_HA_SCALE_NO_ROOT
+        is_exactly(pcs, &AUG_HA_SCALE_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -56,16 +54,14 @@ impl NamingHeuristic for AugHAScale {
     }
 }
 
-static DIM_HW_SCALE_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc1, Pc3, Pc4, Pc6, Pc7, Pc9, Pc10])
-});
+const DIM_HW_SCALE_NO_ROOT: [Pc; 7] = [Pc1, Pc3, Pc4, Pc6, Pc7, Pc9, Pc10];
 #[derive(Debug)]
 pub struct DimHWScale;
 impl NamingHeuristic for DimHWScale {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *DIM_HW_SCALE_NO_ROOT
+        is_exactly(pcs, &DIM_HW_SCALE_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -73,16 +69,14 @@ impl NamingHeuristic for DimHWScale {
     }
 }
 
-static DIM_WH_SCALE_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc2, Pc3, Pc5, Pc6, Pc8, Pc9, Pc11])
-});
+const DIM_WH_SCALE_NO_ROOT: [Pc; 7] = [Pc2, Pc3, Pc5, Pc6, Pc8, Pc9, Pc11];
 #[derive(Debug)]
 pub struct DimWHScale;
 impl NamingHeuristic for DimWHScale {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *DIM_WH_SCALE_NO_ROOT
+        is_exactly(pcs, &DIM_WH_SCALE_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -90,16 +84,106 @@ impl NamingHeuristic for DimWHScale {
     }
 }
 
-static HARMONIC_MINOR_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc2, Pc3, Pc5, Pc7, Pc8, Pc11])
-});
+const BEBOP_DOMINANT_NO_ROOT: [Pc; 7] = [Pc2, Pc4, Pc5, Pc7, Pc9, Pc10, Pc11];
+#[derive(Debug)]
+pub struct BebopDominant;
+impl NamingHeuristic for BebopDominant {
+    type T = ScaleQuality;
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        is_exactly(pcs, &BEBOP_DOMINANT_NO_ROOT)
+    }
+
+    fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
+        Some(ScaleQuality::BebopDominant)
+    }
+}
+
+const BEBOP_MAJOR_NO_ROOT: [Pc; 7] = [Pc2, Pc4, Pc5, Pc7, Pc8, Pc9, Pc11];
+#[derive(Debug)]
+pub struct BebopMajor;
+impl NamingHeuristic for BebopMajor {
+    type T = ScaleQuality;
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        is_exactly(pcs, &BEBOP_MAJOR_NO_ROOT)
+    }
+
+    fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
+        Some(ScaleQuality::BebopMajor)
+    }
+}
+
+const BEBOP_DORIAN_NO_ROOT: [Pc; 7] = [Pc2, Pc3, Pc4, Pc5, Pc7, Pc9, Pc10];
+#[derive(Debug)]
+pub struct BebopDorian;
+impl NamingHeuristic for BebopDorian {
+    type T = ScaleQuality;
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        is_exactly(pcs, &BEBOP_DORIAN_NO_ROOT)
+    }
+
+    fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
+        Some(ScaleQuality::BebopDorian)
+    }
+}
+
+const BEBOP_MELODIC_MINOR_NO_ROOT: [Pc; 7] = [Pc2, Pc3, Pc5, Pc7, Pc8, Pc9, Pc11];
+#[derive(Debug)]
+pub struct BebopMelodicMinor;
+impl NamingHeuristic for BebopMelodicMinor {
+    type T = ScaleQuality;
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        is_exactly(pcs, &BEBOP_MELODIC_MINOR_NO_ROOT)
+    }
+
+    fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
+        Some(ScaleQuality::BebopMelodicMinor)
+    }
+}
+
+/// Any eight note scale, as the first seven note [ScaleMode] it contains, in the order of [ScaleMode::ALL],
+/// along with the chromatic tone added to it. e.g. a major scale with a sharp fourth added to it.
+#[derive(Debug)]
+pub struct EightNoteScale;
+impl EightNoteScale {
+    fn contained_mode(pcs: &HashSet<Pc>) -> Option<(ScaleMode, Pc)> {
+        ScaleMode::ALL.into_iter()
+            .filter(|mode| mode.pcs().len() == 7)
+            .find_map(|mode| {
+                let degrees = mode.pcs();
+                if !degrees.iter().all(|pc| *pc == Pc0 || pcs.contains(pc)) {
+                    return None;
+                }
+                pcs.iter()
+                    .find(|pc| !degrees.contains(pc))
+                    .map(|added| (mode, *added))
+            })
+    }
+}
+
+impl NamingHeuristic for EightNoteScale {
+    type T = ScaleQuality;
+
+    fn validate(&self, pcs: &HashSet<Pc>) -> bool {
+        pcs.len() == 7 && Self::contained_mode(pcs).is_some()
+    }
+
+    fn generate_name(&self, pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
+        Self::contained_mode(pcs).map(|(mode, added)| ScaleQuality::AddedTone(mode, added))
+    }
+}
+
+const HARMONIC_MINOR_NO_ROOT: [Pc; 6] = [Pc2, Pc3, Pc5, Pc7, Pc8, Pc11];
 #[derive(Debug)]
 pub struct HarmonicMinor;
 impl NamingHeuristic for HarmonicMinor {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *HARMONIC_MINOR_NO_ROOT
+        is_exactly(pcs, &HARMONIC_MINOR_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -107,16 +191,14 @@ impl NamingHeuristic for HarmonicMinor {
     }
 }
 
-static HARMONIC_MAJOR_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc2, Pc4, Pc5, Pc7, Pc8, Pc11])
-});
+const HARMONIC_MAJOR_NO_ROOT: [Pc; 6] = [Pc2, Pc4, Pc5, Pc7, Pc8, Pc11];
 #[derive(Debug)]
 pub struct HarmonicMajor;
 impl NamingHeuristic for HarmonicMajor {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *HARMONIC_MAJOR_NO_ROOT
+        is_exactly(pcs, &HARMONIC_MAJOR_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
@@ -124,16 +206,14 @@ impl NamingHeuristic for HarmonicMajor {
     }
 }
 
-static ALTERED_NO_ROOT: Lazy<HashSet<Pc>> = Lazy::new(|| {
-    HashSet::from([Pc1, Pc3, Pc4, Pc6, Pc8, Pc10])
-});
+const ALTERED_NO_ROOT: [Pc; 6] = [Pc1, Pc3, Pc4, Pc6, Pc8, Pc10];
 #[derive(Debug)]
 pub struct AlteredScale;
 impl NamingHeuristic for AlteredScale {
     type T = ScaleQuality;
 
     fn validate(&self, pcs: &HashSet<Pc>) -> bool {
-        *pcs == *ALTERED_NO_ROOT
+        is_exactly(pcs, &ALTERED_NO_ROOT)
     }
 
     fn generate_name(&self, _pcs: &HashSet<Pc>) -> Option<ScaleQuality> {
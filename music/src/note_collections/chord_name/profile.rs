@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::chord_name::{ChordName, ChordNameDisplayConfig, ChordQuality, ExtensionStyle, OmissionStyle};
+use crate::note_collections::chord_name::naming_heuristics::{infer_weighted_chord_quality_with, HeuristicRegistry, RootPolicy};
+use crate::note_collections::weighted_pc_set::WeightedPcSet;
+
+/// Settings for naming chords as a kind of music is usually analysed, so that an application
+/// can start from one of these rather than from every setting of chord naming and display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnalysisProfile {
+    /// Every tone counts, and is accounted for: chords missing their thirds go unnamed,
+    /// missing fifths are shown, and an extension is only named if the ones below it are there too.
+    StrictClassical,
+    /// Shells and other grips missing tones are named as the full chords they stand for,
+    /// each by its highest extension.
+    JazzLeadSheet,
+    /// Power chords are named as such, missing tones are shown, and passing tones are readily ignored.
+    PopRock,
+}
+
+impl AnalysisProfile {
+    /// How to display the chords named.
+    pub fn display_config(&self) -> ChordNameDisplayConfig {
+        match self {
+            AnalysisProfile::StrictClassical => ChordNameDisplayConfig {
+                extension_style: ExtensionStyle::Strict,
+                omissions: OmissionStyle::Omit,
+                ..Default::default()
+            },
+            AnalysisProfile::JazzLeadSheet => ChordNameDisplayConfig {
+                extension_style: ExtensionStyle::Highest,
+                omissions: OmissionStyle::Hidden,
+                ..Default::default()
+            },
+            AnalysisProfile::PopRock => ChordNameDisplayConfig {
+                extension_style: ExtensionStyle::HighestUnlessOne,
+                omissions: OmissionStyle::No,
+                power_chords: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// The heuristics that chords are named by.
+    pub fn registry(&self) -> HeuristicRegistry {
+        match self {
+            AnalysisProfile::StrictClassical => HeuristicRegistry::without_inferred_thirds(),
+            AnalysisProfile::JazzLeadSheet | AnalysisProfile::PopRock => HeuristicRegistry::new(),
+        }
+    }
+
+    /// How heavy a pitch class must be, relative to the heaviest, to be heard as part of the chord,
+    /// see [WeightedPcSet::salient_pcs].
+    pub fn min_weight(&self) -> f64 {
+        match self {
+            AnalysisProfile::StrictClassical => 0.1,
+            AnalysisProfile::JazzLeadSheet => 0.2,
+            AnalysisProfile::PopRock => 0.3,
+        }
+    }
+
+    /// Names a [NoteSet] from its first note, as by [ChordName::from_note_set], and displays it.
+    pub fn name(&self, notes: &NoteSet) -> Option<String> {
+        ChordName::from_note_set_with(notes, &self.registry())
+            .map(|name| name.symbol(Some(&self.display_config())))
+    }
+
+    /// Names weighted pitch classes from whichever root they can be named from,
+    /// as by [infer_weighted_chord_quality_with] with [RootPolicy::TryAllRoots].
+    pub fn infer_weighted(&self, pcs: &WeightedPcSet) -> Option<(Pc, ChordQuality)> {
+        infer_weighted_chord_quality_with(pcs, RootPolicy::TryAllRoots, self.min_weight(), &self.registry())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::note::Note;
+    use crate::note::pitch_class::Pc::*;
+
+    #[test]
+    fn analysis_profiles() {
+        let name = |profile: AnalysisProfile, notes: &[Note]| {
+            profile.name(&NoteSet::starting_from_first_note(notes.to_vec()))
+        };
+        use AnalysisProfile::*;
+        // A shell voicing of C7, with no third.
+        let no_third = [Note::C, Note::G, Note::Bes];
+        assert_eq!(name(StrictClassical, &no_third), None);
+        assert_eq!(name(JazzLeadSheet, &no_third).as_deref(), Some("C7"));
+        assert_eq!(name(PopRock, &no_third).as_deref(), Some("C7 (no3)"));
+        // A major seventh without its fifth.
+        let shell = [Note::C, Note::E, Note::B];
+        assert_eq!(name(StrictClassical, &shell).as_deref(), Some("CMaj7 (omit5)"));
+        assert_eq!(name(JazzLeadSheet, &shell).as_deref(), Some("CMaj7"));
+        // A thirteenth chord, without its eleventh.
+        let thirteenth = [Note::C, Note::E, Note::G, Note::Bes, Note::D, Note::A];
+        assert_eq!(name(StrictClassical, &thirteenth).as_deref(), Some("C9 (13)"));
+        assert_eq!(name(JazzLeadSheet, &thirteenth).as_deref(), Some("C13 (9)"));
+        assert_eq!(name(PopRock, &[Note::A, Note::E]).as_deref(), Some("A5"));
+
+        // A G major triad with a brief F, which only the strictest profile hears as part of the chord.
+        let heard: WeightedPcSet = [(Pc7, 4.0), (Pc11, 3.0), (Pc2, 3.0), (Pc5, 0.6)].into_iter().collect();
+        let display = |profile: AnalysisProfile| profile.infer_weighted(&heard)
+            .map(|(root, quality)| (root, quality.to_string(&profile.display_config()).trim().to_string()));
+        assert_eq!(display(StrictClassical), Some((Pc7, "7".to_string())));
+        assert_eq!(display(JazzLeadSheet), Some((Pc7, "Maj".to_string())));
+        assert_eq!(display(PopRock), Some((Pc7, "Maj".to_string())));
+    }
+}
@@ -1,11 +1,14 @@
-use std::fmt::{Display, Formatter};
-use std::ops::{Deref, DerefMut};
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use core::ops::{Deref, DerefMut};
 use crate::error::MusicSemanticsError;
-use crate::note_collections::chord_name::{ChordNameDisplayConfig, ExtensionStyle};
+use crate::note_collections::chord_name::{utf8_accidentals, ChordNameDisplayConfig, ExtensionStyle, OmissionStyle};
 use crate::note_collections::interval_class::IntervalClass;
+use crate::note_collections::pc_set::PcSet;
+use crate::note::pitch_class::Pc;
 
 /// The "ninth", "eleventh", etc in Maj9th or min11th chords, etc.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AltChoice {
     FlatNine,
     Nine,
@@ -19,7 +22,7 @@ pub enum AltChoice {
 }
 
 impl Display for AltChoice {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", match self {
             AltChoice::FlatNine => "b9".to_string(),
             AltChoice::Nine => "9".to_string(),
@@ -34,6 +37,23 @@ impl Display for AltChoice {
     }
 }
 
+impl AltChoice {
+    /// The alteration's distance above the root, the inverse of `AltChoice::try_from`.
+    pub fn to_pc(&self) -> Pc {
+        match self {
+            AltChoice::FlatNine => Pc::Pc1,
+            AltChoice::Nine => Pc::Pc2,
+            AltChoice::SharpNine => Pc::Pc3,
+            AltChoice::FlatEleven => Pc::Pc4,
+            AltChoice::Eleven => Pc::Pc5,
+            AltChoice::SharpEleven => Pc::Pc6,
+            AltChoice::FlatThirteenth => Pc::Pc8,
+            AltChoice::Thirteenth => Pc::Pc9,
+            AltChoice::SharpThirteenth => Pc::Pc10,
+        }
+    }
+}
+
 impl TryFrom<usize> for AltChoice {
     type Error = MusicSemanticsError;
 
@@ -54,7 +74,7 @@ impl TryFrom<usize> for AltChoice {
 }
 
 /// Chord Quality Alterations
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Alt(pub(crate) Vec<AltChoice>);
 
 impl Alt {
@@ -64,7 +84,7 @@ impl Alt {
 }
 
 impl Display for Alt {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         if self.is_empty() {
             return write!(f, "");
         }
@@ -96,7 +116,7 @@ impl DerefMut for Alt {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Extension {
     Seventh,
     Ninth,
@@ -119,6 +139,12 @@ impl Extension {
 }
 
 impl Extension {
+    /// The extension's distance above the root. Sevenths are left to the chord quality,
+    /// which decides between a major and minor seventh.
+    pub fn to_pc(&self) -> Option<Pc> {
+        self.to_alt_choice().map(|alt| alt.to_pc())
+    }
+
     pub fn to_string(&self) -> String {
         match &self {
             Extension::Seventh => "7".to_string(),
@@ -205,26 +231,60 @@ pub fn resolve_extension(
     }
 }
 
+/// A tone of a chord's triad that's left out of a voicing of it,
+/// e.g. the fifth of a shell voicing, or the third of a guitar grip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Omission {
+    Third,
+    Fifth,
+}
+
+impl Omission {
+    pub fn to_string(&self, style: OmissionStyle) -> String {
+        let degree = match self {
+            Omission::Third => "3",
+            Omission::Fifth => "5",
+        };
+        match style {
+            OmissionStyle::Hidden => String::new(),
+            OmissionStyle::Omit => format!("omit{}", degree),
+            OmissionStyle::No => format!("no{}", degree),
+        }
+    }
+}
+
 /// Chords based around a Major triad.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MajorSubtype {
     Maj(Alt),
     Maj6(Alt),
+    /// e.g. Cadd9, a triad with a ninth but no seventh.
+    Add9(Alt),
+    /// e.g. Cadd11, a triad with an eleventh but no seventh.
+    Add11(Alt),
+    /// e.g. C6/9
+    SixNine(Alt),
     MajN(Vec<Extension>, Alt),
     N(Vec<Extension>, Alt),
 }
 
 /// Chords based around a minor triad.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MinorSubtype {
     Min(Alt),
     Min6(Alt),
+    /// e.g. Cmin(add9), a triad with a ninth but no seventh.
+    MinAdd9(Alt),
+    /// e.g. Cmin(add11), a triad with an eleventh but no seventh.
+    MinAdd11(Alt),
+    /// e.g. Cmin6/9
+    Min69(Alt),
     MinMajN(Vec<Extension>, Alt),
     MinN(Vec<Extension>, Alt),
 }
 
 /// Chords based around an Augmented triad.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AugSubtype {
     /// e.g. C+
     Aug(Alt),
@@ -235,7 +295,7 @@ pub enum AugSubtype {
 }
 
 /// Chords based around a diminished triad.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DimSubtype {
     /// e.g. Cdim
     Dim(Alt),
@@ -248,7 +308,7 @@ pub enum DimSubtype {
 }
 
 /// Chords based around a diminished triad.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SusSubtype {
     Sus2(Alt),
     Sus4(Alt),
@@ -260,7 +320,7 @@ pub enum SusSubtype {
 /// Basic categories for chords >=3 pitch classes,
 /// and special variants for the trivial cases of
 /// [ChordQuality::Interval] and [ChordQuality::SingleNote].
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ChordQuality {
     Major(MajorSubtype),
     Minor(MinorSubtype),
@@ -273,7 +333,118 @@ pub enum ChordQuality {
 }
 
 impl ChordQuality {
-    pub fn to_string(&self, cfg: &ChordNameDisplayConfig) -> String {
+    /// The canonical [PcSet] of the quality, with the root as [Pc::Pc0].
+    ///
+    /// This is the inverse of naming a [PcSet]. Where naming tolerates missing notes,
+    /// such as an omitted fifth, the canonical set includes them.
+    pub fn to_pc_set(&self) -> PcSet {
+        use Pc::*;
+        let with_additions = |mut pcs: Vec<Pc>, ext: &Vec<Extension>, alt: &Alt| {
+            pcs.extend(ext.iter().filter_map(Extension::to_pc));
+            pcs.extend(alt.iter().map(AltChoice::to_pc));
+            pcs
+        };
+        let none = vec![];
+        let pcs = match &self {
+            ChordQuality::Major(subtype) => match subtype {
+                MajorSubtype::Maj(alt) => with_additions(vec![Pc0, Pc4, Pc7], &none, alt),
+                MajorSubtype::Maj6(alt) => with_additions(vec![Pc0, Pc4, Pc7, Pc9], &none, alt),
+                MajorSubtype::Add9(alt) => with_additions(vec![Pc0, Pc2, Pc4, Pc7], &none, alt),
+                MajorSubtype::Add11(alt) => with_additions(vec![Pc0, Pc4, Pc5, Pc7], &none, alt),
+                MajorSubtype::SixNine(alt) => with_additions(vec![Pc0, Pc2, Pc4, Pc7, Pc9], &none, alt),
+                MajorSubtype::MajN(ext, alt) => with_additions(vec![Pc0, Pc4, Pc7, Pc11], ext, alt),
+                MajorSubtype::N(ext, alt) => with_additions(vec![Pc0, Pc4, Pc7, Pc10], ext, alt),
+            },
+            ChordQuality::Minor(subtype) => match subtype {
+                MinorSubtype::Min(alt) => with_additions(vec![Pc0, Pc3, Pc7], &none, alt),
+                MinorSubtype::Min6(alt) => with_additions(vec![Pc0, Pc3, Pc7, Pc9], &none, alt),
+                MinorSubtype::MinAdd9(alt) => with_additions(vec![Pc0, Pc2, Pc3, Pc7], &none, alt),
+                MinorSubtype::MinAdd11(alt) => with_additions(vec![Pc0, Pc3, Pc5, Pc7], &none, alt),
+                MinorSubtype::Min69(alt) => with_additions(vec![Pc0, Pc2, Pc3, Pc7, Pc9], &none, alt),
+                MinorSubtype::MinMajN(ext, alt) => with_additions(vec![Pc0, Pc3, Pc7, Pc11], ext, alt),
+                MinorSubtype::MinN(ext, alt) => with_additions(vec![Pc0, Pc3, Pc7, Pc10], ext, alt),
+            },
+            ChordQuality::Aug(subtype) => match subtype {
+                AugSubtype::Aug(alt) => with_additions(vec![Pc0, Pc4, Pc8], &none, alt),
+                AugSubtype::AugMajN(ext, alt) => with_additions(vec![Pc0, Pc4, Pc8, Pc11], ext, alt),
+                AugSubtype::AugN(ext, alt) => with_additions(vec![Pc0, Pc4, Pc8, Pc10], ext, alt),
+            },
+            ChordQuality::Dim(subtype) => match subtype {
+                DimSubtype::Dim(alt) => with_additions(vec![Pc0, Pc3, Pc6], &none, alt),
+                DimSubtype::MinNb5(ext, alt) => with_additions(vec![Pc0, Pc3, Pc6, Pc10], ext, alt),
+                // The thirteenth of a diminished seventh chord is its seventh.
+                DimSubtype::DimN(ext, alt) => with_additions(vec![Pc0, Pc3, Pc6, Pc9], ext, alt),
+                DimSubtype::DimMajN(ext, alt) => with_additions(vec![Pc0, Pc3, Pc6, Pc11], ext, alt),
+            },
+            ChordQuality::Sus(subtype) => match subtype {
+                SusSubtype::Sus2(alt) => with_additions(vec![Pc0, Pc2, Pc7], &none, alt),
+                SusSubtype::Sus4(alt) => with_additions(vec![Pc0, Pc5, Pc7], &none, alt),
+                SusSubtype::DomNSus(ext, alt) => with_additions(vec![Pc0, Pc5, Pc7, Pc10], ext, alt),
+                SusSubtype::MajNSus(ext, alt) => with_additions(vec![Pc0, Pc5, Pc7, Pc11], ext, alt),
+                SusSubtype::SixNineSus(alt) => with_additions(vec![Pc0, Pc2, Pc5, Pc7, Pc9], &none, alt),
+            },
+            ChordQuality::Interval(ic) => vec![Pc0, Pc::from(ic)],
+            ChordQuality::SingleNote => vec![Pc0],
+        };
+        PcSet::new(pcs)
+    }
+
+    /// The third and fifth of the quality's triad, with the root as [Pc::Pc0].
+    /// Sus chords have no third to leave out, and intervals and single notes have no triad.
+    fn triad_tones(&self) -> Vec<(Omission, Pc)> {
+        use Pc::*;
+        match &self {
+            ChordQuality::Major(_) => vec![(Omission::Third, Pc4), (Omission::Fifth, Pc7)],
+            ChordQuality::Minor(_) => vec![(Omission::Third, Pc3), (Omission::Fifth, Pc7)],
+            ChordQuality::Aug(_) => vec![(Omission::Third, Pc4), (Omission::Fifth, Pc8)],
+            ChordQuality::Dim(_) => vec![(Omission::Third, Pc3), (Omission::Fifth, Pc6)],
+            ChordQuality::Sus(_) => vec![(Omission::Fifth, Pc7)],
+            ChordQuality::Interval(_) | ChordQuality::SingleNote => vec![],
+        }
+    }
+
+    /// The tones of the quality's triad that are missing from `pcs`, with the root as [Pc::Pc0].
+    ///
+    /// Naming tolerates missing thirds and fifths, e.g. C G Bb is named C7, so this is
+    /// what's needed to tell the name of a shell voicing or grip apart from that of the full chord.
+    pub fn omissions(&self, pcs: &HashSet<Pc>) -> Vec<Omission> {
+        self.triad_tones()
+            .into_iter()
+            .filter(|(_, pc)| !pcs.contains(pc))
+            .map(|(omission, _)| omission)
+            .collect()
+    }
+
+    /// As [ChordQuality::to_string], with any `omissions` written alongside its alterations,
+    /// e.g. "7 (no3)" or "7 (9, no3, no5)", unless [ChordNameDisplayConfig::omissions] hides them.
+    pub fn to_string_with_omissions<'a>(
+        &self,
+        omissions: &[Omission],
+        cfg: impl Into<Option<&'a ChordNameDisplayConfig>>,
+    ) -> String {
+        let default = ChordNameDisplayConfig::default();
+        let cfg = cfg.into().unwrap_or(&default);
+        if omissions.is_empty() || matches!(cfg.omissions, OmissionStyle::Hidden) {
+            return self.to_string(cfg);
+        }
+        let (name, alt) = self.name_and_alterations(cfg);
+        let notes: Vec<String> = alt.iter()
+            .map(|alteration| alteration.to_string())
+            .chain(omissions.iter().map(|omission| omission.to_string(cfg.omissions)))
+            .collect();
+        written(format!("{} ({})", name, notes.join(", ")), cfg)
+    }
+
+    /// The quality as it's written after the root, e.g. "Maj7", as set out by `cfg`, or by default.
+    pub fn to_string<'a>(&self, cfg: impl Into<Option<&'a ChordNameDisplayConfig>>) -> String {
+        let default = ChordNameDisplayConfig::default();
+        let cfg = cfg.into().unwrap_or(&default);
+        let (name, alt) = self.name_and_alterations(cfg);
+        written(format!("{} {}", name, alt), cfg)
+    }
+
+    /// The quality as it's written, e.g. "7", and the alterations written after it, e.g. "(9)".
+    fn name_and_alterations(&self, cfg: &ChordNameDisplayConfig) -> (String, Alt) {
         let style = cfg.extension_style;
         let ext_and_alts = |alt: &Alt, ext: &Vec<Extension>, style| {
             let (ext, mut alts) = resolve_extension(ext, style);
@@ -284,96 +455,126 @@ impl ChordQuality {
             ChordQuality::Major(subtype) => {
                 match subtype {
                     MajorSubtype::Maj(alt) => {
-                        format!("Maj {}", alt.to_string())
+                        ("Maj".to_owned(), alt.clone())
                     }
                     MajorSubtype::Maj6(alt) => {
-                        format!("Maj {}", alt.to_string())
+                        ("6".to_owned(), alt.clone())
+                    }
+                    MajorSubtype::Add9(alt) => {
+                        ("add9".to_owned(), alt.clone())
+                    }
+                    MajorSubtype::Add11(alt) => {
+                        ("add11".to_owned(), alt.clone())
+                    }
+                    MajorSubtype::SixNine(alt) => {
+                        ("6/9".to_owned(), alt.clone())
                     }
                     MajorSubtype::MajN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("Maj{} {}", ext.to_string(), alt.to_string())
+                        (format!("Maj{}", ext.to_string()), alt)
                     }
                     MajorSubtype::N(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("{} {}", ext.to_string(), alt.to_string())
+                        (ext.to_string(), alt)
                     }
                 }
             },
             ChordQuality::Minor(subtype) => {
                 match subtype {
                     MinorSubtype::Min(alt) => {
-                        format!("min {}", alt.to_string())
+                        ("min".to_owned(), alt.clone())
                     }
                     MinorSubtype::Min6(alt) => {
-                        format!("min {}", alt.to_string())
+                        ("min6".to_owned(), alt.clone())
+                    }
+                    MinorSubtype::MinAdd9(alt) => {
+                        ("min(add9)".to_owned(), alt.clone())
+                    }
+                    MinorSubtype::MinAdd11(alt) => {
+                        ("min(add11)".to_owned(), alt.clone())
+                    }
+                    MinorSubtype::Min69(alt) => {
+                        ("min6/9".to_owned(), alt.clone())
                     }
                     MinorSubtype::MinMajN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("minMaj{} {}", ext.to_string(), alt.to_string())
+                        (format!("minMaj{}", ext.to_string()), alt)
                     }
                     MinorSubtype::MinN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("min{} {}", ext.to_string(), alt.to_string())
+                        (format!("min{}", ext.to_string()), alt)
                     }
                 }
             },
             ChordQuality::Aug(subtype) => {
                 match subtype {
                     AugSubtype::Aug(alt) => {
-                        format!("Aug {}", alt.to_string())
+                        ("Aug".to_owned(), alt.clone())
                     }
                     AugSubtype::AugMajN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("+Maj{} {}", ext.to_string(), alt.to_string())
+                        (format!("+Maj{}", ext.to_string()), alt)
                     }
                     AugSubtype::AugN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("+{} {}", ext.to_string(), alt.to_string())
+                        (format!("+{}", ext.to_string()), alt)
                     }
                 }
             },
             ChordQuality::Dim(subtype) => {
                 match subtype {
                     DimSubtype::Dim(alt) => {
-                        format!("dim {}", alt.to_string())
+                        ("dim".to_owned(), alt.clone())
                     }
                     DimSubtype::MinNb5(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("min{}b5 {}", ext.to_string(), alt.to_string())
+                        (format!("min{}b5", ext.to_string()), alt)
                     }
                     DimSubtype::DimN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("dim{} {}", ext.to_string(), alt.to_string())
+                        (format!("dim{}", ext.to_string()), alt)
                     }
                     DimSubtype::DimMajN(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("dimMaj{} {}", ext.to_string(), alt.to_string())
+                        (format!("dimMaj{}", ext.to_string()), alt)
                     }
                 }
             },
             ChordQuality::Sus(subtype) => {
                 match subtype {
                     SusSubtype::Sus2(alt) => {
-                        format!("sus2 {}", alt.to_string())
+                        ("sus2".to_owned(), alt.clone())
                     }
                     SusSubtype::Sus4(alt) => {
-                        format!("sus4 {}", alt.to_string())
+                        ("sus4".to_owned(), alt.clone())
                     }
                     SusSubtype::DomNSus(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("{}sus {}", ext.to_string(), alt.to_string())
+                        (format!("{}sus", ext.to_string()), alt)
                     }
                     SusSubtype::MajNSus(ext, alt) => {
                         let (ext, alt) = ext_and_alts(alt, ext, style);
-                        format!("Maj{}sus {}", ext.to_string(), alt.to_string())
+                        (format!("Maj{}sus", ext.to_string()), alt)
                     }
                     SusSubtype::SixNineSus(alt) => {
-                        format!("6/9sus {}", alt.to_string())
+                        ("6/9sus".to_owned(), alt.clone())
                     }
                 }
             },
-            ChordQuality::Interval(ic) => ic.to_string(),
-            ChordQuality::SingleNote => "note".to_owned(),
-        }.trim().to_string()
+            ChordQuality::Interval(IntervalClass::Ic7) if cfg.power_chords => ("5".to_owned(), Alt::empty()),
+            ChordQuality::Interval(IntervalClass::Ic6) if cfg.power_chords => ("(b5)".to_owned(), Alt::empty()),
+            ChordQuality::Interval(ic) => (format!("({})", ic), Alt::empty()),
+            ChordQuality::SingleNote => ("note".to_owned(), Alt::empty()),
+        }
+    }
+}
+
+/// A quality written out in full, as set out by `cfg`.
+fn written(quality: String, cfg: &ChordNameDisplayConfig) -> String {
+    let quality = quality.trim().to_string();
+    if cfg.uft8_accidentals {
+        utf8_accidentals(&quality)
+    } else {
+        quality
     }
 }
\ No newline at end of file
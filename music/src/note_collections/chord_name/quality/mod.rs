@@ -1,2 +1,3 @@
 pub mod scale;
-pub mod chord;
\ No newline at end of file
+pub mod chord;
+pub mod registry;
@@ -0,0 +1,141 @@
+use crate::prelude::*;
+use core::hash::Hash;
+use crate::note_collections::chord_name::quality::chord::ChordQuality;
+use crate::note_collections::chord_name::quality::scale::ScaleQuality;
+
+/// A handle on a [ChordQuality] interned in a [QualityRegistry].
+///
+/// Two handles from the same registry are equal exactly when their qualities are,
+/// so that a large number of analysed chords can be stored and compared as plain integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChordQualityId(pub u32);
+
+/// A handle on a [ScaleQuality] interned in a [QualityRegistry].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScaleQualityId(pub u32);
+
+/// Holds one copy of each chord and scale quality it's given, and hands out a compact id
+/// for each in its place.
+///
+/// Ids are given out from 0 in the order the qualities are first interned. Interning the
+/// qualities of [QualityRegistry::chord_qualities] and [QualityRegistry::scale_qualities]
+/// in order into a new registry gives back the same ids, so the tables can be stored
+/// alongside the ids and the registry rebuilt from them.
+#[derive(Debug, Clone, Default)]
+pub struct QualityRegistry {
+    chords: Interner<ChordQuality>,
+    scales: Interner<ScaleQuality>,
+}
+
+impl QualityRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of a chord quality, interning it if it hasn't been seen before.
+    pub fn intern_chord(&mut self, quality: &ChordQuality) -> ChordQualityId {
+        ChordQualityId(self.chords.intern(quality))
+    }
+
+    /// The id of a chord quality, if it has been interned.
+    pub fn chord_id(&self, quality: &ChordQuality) -> Option<ChordQualityId> {
+        self.chords.id(quality).map(ChordQualityId)
+    }
+
+    /// The chord quality of an id, or `None` for an id this registry didn't give out.
+    pub fn chord(&self, id: ChordQualityId) -> Option<&ChordQuality> {
+        self.chords.values.get(id.0 as usize)
+    }
+
+    /// Every chord quality interned, indexed by its id.
+    pub fn chord_qualities(&self) -> &[ChordQuality] {
+        &self.chords.values
+    }
+
+    /// The id of a scale quality, interning it if it hasn't been seen before.
+    pub fn intern_scale(&mut self, quality: &ScaleQuality) -> ScaleQualityId {
+        ScaleQualityId(self.scales.intern(quality))
+    }
+
+    /// The id of a scale quality, if it has been interned.
+    pub fn scale_id(&self, quality: &ScaleQuality) -> Option<ScaleQualityId> {
+        self.scales.id(quality).map(ScaleQualityId)
+    }
+
+    /// The scale quality of an id, or `None` for an id this registry didn't give out.
+    pub fn scale(&self, id: ScaleQualityId) -> Option<&ScaleQuality> {
+        self.scales.values.get(id.0 as usize)
+    }
+
+    /// Every scale quality interned, indexed by its id.
+    pub fn scale_qualities(&self) -> &[ScaleQuality] {
+        &self.scales.values
+    }
+}
+
+/// The values interned so far, and the index of each.
+#[derive(Debug, Clone)]
+struct Interner<T> {
+    values: Vec<T>,
+    ids: HashMap<T, u32>,
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Self { values: vec![], ids: HashMap::new() }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Interner<T> {
+    fn intern(&mut self, value: &T) -> u32 {
+        if let Some(id) = self.ids.get(value) {
+            return *id;
+        }
+        let id = u32::try_from(self.values.len()).expect("fewer than 2^32 distinct qualities");
+        self.values.push(value.clone());
+        self.ids.insert(value.clone(), id);
+        id
+    }
+
+    fn id(&self, value: &T) -> Option<u32> {
+        self.ids.get(value).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::pitch_class::Pc;
+    use crate::note_collections::chord_name::quality::chord::{Alt, AltChoice, Extension, MajorSubtype, MinorSubtype};
+    use crate::note_collections::chord_name::quality::scale::{Alt2nd, Alt6thMaj7, ScaleMode};
+
+    #[test]
+    fn quality_registry() {
+        let mut registry = QualityRegistry::new();
+        let maj7 = ChordQuality::Major(MajorSubtype::MajN(vec![Extension::Seventh], Alt::empty()));
+        let maj7_sharp_11 = ChordQuality::Major(MajorSubtype::MajN(vec![Extension::Seventh], Alt(vec![AltChoice::SharpEleven])));
+        let min = ChordQuality::Minor(MinorSubtype::Min(Alt::empty()));
+        let ids: Vec<ChordQualityId> = [&maj7, &min, &maj7, &maj7_sharp_11, &min].into_iter()
+            .map(|quality| registry.intern_chord(quality))
+            .collect();
+        assert_eq!(ids, vec![ChordQualityId(0), ChordQualityId(1), ChordQualityId(0), ChordQualityId(2), ChordQualityId(1)]);
+        assert_eq!(registry.chord(ids[3]), Some(&maj7_sharp_11));
+        assert_eq!(registry.chord(ChordQualityId(3)), None);
+        assert_eq!(registry.chord_id(&ChordQuality::SingleNote), None);
+        assert_eq!(registry.chord_qualities().len(), 3);
+
+        let lydian = ScaleQuality::Lydian(vec![Alt2nd::Natural], vec![Alt6thMaj7::Natural]);
+        let added = ScaleQuality::AddedTone(ScaleMode::Major, Pc::Pc8);
+        assert_eq!(registry.intern_scale(&added), ScaleQualityId(0));
+        assert_eq!(registry.intern_scale(&lydian), ScaleQualityId(1));
+        assert_eq!(registry.scale_id(&added), Some(ScaleQualityId(0)));
+        assert_eq!(registry.scale(ScaleQualityId(1)), Some(&lydian));
+
+        // A registry rebuilt from the tables gives the same ids.
+        let mut rebuilt = QualityRegistry::new();
+        for quality in registry.chord_qualities() {
+            rebuilt.intern_chord(quality);
+        }
+        assert_eq!(rebuilt.chord_id(&maj7_sharp_11), Some(ChordQualityId(2)));
+    }
+}
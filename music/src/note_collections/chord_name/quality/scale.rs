@@ -1,9 +1,11 @@
-use std::collections::HashSet;
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
 use crate::note::pitch_class::Pc;
 use crate::note::pitch_class::Pc::*;
+use crate::note_collections::chord_name::corpus::ScaleFamily;
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt2nd {
     Sharp,
     Natural,
@@ -43,7 +45,7 @@ impl Alt2nd {
 }
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt2ndMinor {
     Natural,
     Flat
@@ -79,7 +81,7 @@ impl Alt2ndMinor {
 }
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt4th {
     Sharp,
     Natural,
@@ -115,7 +117,7 @@ impl Alt4th {
 }
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt4thMinor {
     Sharp,
     Natural,
@@ -155,7 +157,7 @@ impl Alt4thMinor {
 }
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt6thMaj7 {
     Sharp,
     Natural,
@@ -195,7 +197,7 @@ impl Alt6thMaj7 {
 }
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt6thDom7 {
     Natural,
     Flat,
@@ -231,7 +233,7 @@ impl Alt6thDom7 {
 }
 
 /// Scale alteration.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Alt6thAugMaj7 {
     Sharp,
     Natural,
@@ -268,7 +270,7 @@ impl Alt6thAugMaj7 {
 
 /// The primary categories of scales, modes, which we can then further characterize
 /// by alterations.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ScaleQuality {
     Major(Vec<Alt2nd>, Vec<Alt6thMaj7>),
     IonianAug(Vec<Alt2nd>, Vec<Alt6thAugMaj7>),
@@ -291,6 +293,293 @@ pub enum ScaleQuality {
     // Eight Notes
     DimHW,
     DimWH,
+    BebopDominant,
+    BebopMajor,
+    BebopDorian,
+    BebopMelodicMinor,
+    /// Any other eight note scale, as the seven note scale it contains, and the tone added to it.
+    AddedTone(ScaleMode, Pc),
     // TODO Major and minor pentatonic scale I guess?
     // TODO Any other scales to more-or-less manually index?
 }
+
+impl ScaleQuality {
+    /// The scale the quality is based on, before any alterations.
+    pub fn mode(&self) -> ScaleMode {
+        match self {
+            ScaleQuality::Major(..) => ScaleMode::Major,
+            ScaleQuality::IonianAug(..) => ScaleMode::IonianAug,
+            ScaleQuality::Dorian(..) => ScaleMode::Dorian,
+            ScaleQuality::Phrygian(..) => ScaleMode::Phrygian,
+            ScaleQuality::Lydian(..) => ScaleMode::Lydian,
+            ScaleQuality::LydianAug(..) => ScaleMode::LydianAug,
+            ScaleQuality::Mixolydian(..) => ScaleMode::Mixolydian,
+            ScaleQuality::MixolydianAug(..) => ScaleMode::MixolydianAug,
+            ScaleQuality::NaturalMinor(..) => ScaleMode::NaturalMinor,
+            ScaleQuality::MelodicMinor(..) => ScaleMode::MelodicMinor,
+            ScaleQuality::HarmonicMajor => ScaleMode::HarmonicMajor,
+            ScaleQuality::HarmonicMinor => ScaleMode::HarmonicMinor,
+            ScaleQuality::Locrian(..) => ScaleMode::Locrian,
+            ScaleQuality::Altered => ScaleMode::Altered,
+            ScaleQuality::WholeTone => ScaleMode::WholeTone,
+            ScaleQuality::AugAH => ScaleMode::AugAH,
+            ScaleQuality::AugHA => ScaleMode::AugHA,
+            ScaleQuality::DimHW => ScaleMode::DimHW,
+            ScaleQuality::DimWH => ScaleMode::DimWH,
+            ScaleQuality::BebopDominant => ScaleMode::BebopDominant,
+            ScaleQuality::BebopMajor => ScaleMode::BebopMajor,
+            ScaleQuality::BebopDorian => ScaleMode::BebopDorian,
+            ScaleQuality::BebopMelodicMinor => ScaleMode::BebopMelodicMinor,
+            ScaleQuality::AddedTone(mode, _) => *mode,
+        }
+    }
+}
+
+/// The unaltered scales that each [ScaleQuality] is based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScaleMode {
+    Major,
+    Dorian,
+    Phrygian,
+    Lydian,
+    Mixolydian,
+    NaturalMinor,
+    Locrian,
+    IonianAug,
+    LydianAug,
+    MixolydianAug,
+    MelodicMinor,
+    HarmonicMajor,
+    HarmonicMinor,
+    Altered,
+    WholeTone,
+    AugAH,
+    AugHA,
+    DimHW,
+    DimWH,
+    BebopDominant,
+    BebopMajor,
+    BebopDorian,
+    BebopMelodicMinor,
+}
+
+impl ScaleMode {
+    pub const ALL: [ScaleMode; 23] = [
+        ScaleMode::Major,
+        ScaleMode::Dorian,
+        ScaleMode::Phrygian,
+        ScaleMode::Lydian,
+        ScaleMode::Mixolydian,
+        ScaleMode::NaturalMinor,
+        ScaleMode::Locrian,
+        ScaleMode::IonianAug,
+        ScaleMode::LydianAug,
+        ScaleMode::MixolydianAug,
+        ScaleMode::MelodicMinor,
+        ScaleMode::HarmonicMajor,
+        ScaleMode::HarmonicMinor,
+        ScaleMode::Altered,
+        ScaleMode::WholeTone,
+        ScaleMode::AugAH,
+        ScaleMode::AugHA,
+        ScaleMode::DimHW,
+        ScaleMode::DimWH,
+        ScaleMode::BebopDominant,
+        ScaleMode::BebopMajor,
+        ScaleMode::BebopDorian,
+        ScaleMode::BebopMelodicMinor,
+    ];
+
+    /// The pitch classes of the scale, with the tonic as [Pc0].
+    pub fn pcs(&self) -> Vec<Pc> {
+        let pcs: &[u8] = match self {
+            ScaleMode::Major => &[0, 2, 4, 5, 7, 9, 11],
+            ScaleMode::Dorian => &[0, 2, 3, 5, 7, 9, 10],
+            ScaleMode::Phrygian => &[0, 1, 3, 5, 7, 8, 10],
+            ScaleMode::Lydian => &[0, 2, 4, 6, 7, 9, 11],
+            ScaleMode::Mixolydian => &[0, 2, 4, 5, 7, 9, 10],
+            ScaleMode::NaturalMinor => &[0, 2, 3, 5, 7, 8, 10],
+            ScaleMode::Locrian => &[0, 1, 3, 5, 6, 8, 10],
+            ScaleMode::IonianAug => &[0, 2, 4, 5, 8, 9, 11],
+            ScaleMode::LydianAug => &[0, 2, 4, 6, 8, 9, 11],
+            ScaleMode::MixolydianAug => &[0, 2, 4, 5, 8, 9, 10],
+            ScaleMode::MelodicMinor => &[0, 2, 3, 5, 7, 9, 11],
+            ScaleMode::HarmonicMajor => &[0, 2, 4, 5, 7, 8, 11],
+            ScaleMode::HarmonicMinor => &[0, 2, 3, 5, 7, 8, 11],
+            ScaleMode::Altered => &[0, 1, 3, 4, 6, 8, 10],
+            ScaleMode::WholeTone => &[0, 2, 4, 6, 8, 10],
+            ScaleMode::AugAH => &[0, 3, 4, 7, 8, 11],
+            ScaleMode::AugHA => &[0, 1, 4, 5, 8, 9],
+            ScaleMode::DimHW => &[0, 1, 3, 4, 6, 7, 9, 10],
+            ScaleMode::DimWH => &[0, 2, 3, 5, 6, 8, 9, 11],
+            ScaleMode::BebopDominant => &[0, 2, 4, 5, 7, 9, 10, 11],
+            ScaleMode::BebopMajor => &[0, 2, 4, 5, 7, 8, 9, 11],
+            ScaleMode::BebopDorian => &[0, 2, 3, 4, 5, 7, 9, 10],
+            ScaleMode::BebopMelodicMinor => &[0, 2, 3, 5, 7, 8, 9, 11],
+        };
+        pcs.iter().map(Pc::from).collect()
+    }
+
+    /// The scale family the mode belongs to, and which mode of the family's parent scale
+    /// it is, counting the parent itself as 0, as in [crate::note_collections::chord_name::corpus::NamedScale].
+    /// e.g. Dorian is mode 1 of the diatonic family. `None` for scales outside those families.
+    pub fn family(&self) -> Option<(ScaleFamily, usize)> {
+        Some(match self {
+            ScaleMode::Major => (ScaleFamily::Diatonic, 0),
+            ScaleMode::Dorian => (ScaleFamily::Diatonic, 1),
+            ScaleMode::Phrygian => (ScaleFamily::Diatonic, 2),
+            ScaleMode::Lydian => (ScaleFamily::Diatonic, 3),
+            ScaleMode::Mixolydian => (ScaleFamily::Diatonic, 4),
+            ScaleMode::NaturalMinor => (ScaleFamily::Diatonic, 5),
+            ScaleMode::Locrian => (ScaleFamily::Diatonic, 6),
+            ScaleMode::MelodicMinor => (ScaleFamily::MelodicMinor, 0),
+            ScaleMode::LydianAug => (ScaleFamily::MelodicMinor, 2),
+            ScaleMode::Altered => (ScaleFamily::MelodicMinor, 6),
+            ScaleMode::HarmonicMinor => (ScaleFamily::HarmonicMinor, 0),
+            ScaleMode::IonianAug => (ScaleFamily::HarmonicMinor, 2),
+            ScaleMode::HarmonicMajor => (ScaleFamily::HarmonicMajor, 0),
+            ScaleMode::WholeTone => (ScaleFamily::Messiaen(1), 0),
+            ScaleMode::DimHW => (ScaleFamily::Messiaen(2), 0),
+            ScaleMode::DimWH => (ScaleFamily::Messiaen(2), 1),
+            ScaleMode::MixolydianAug
+            | ScaleMode::AugAH
+            | ScaleMode::AugHA
+            | ScaleMode::BebopDominant
+            | ScaleMode::BebopMajor
+            | ScaleMode::BebopDorian
+            | ScaleMode::BebopMelodicMinor => return None,
+        })
+    }
+}
+
+impl Display for ScaleMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            ScaleMode::Major => "Major",
+            ScaleMode::Dorian => "Dorian",
+            ScaleMode::Phrygian => "Phrygian",
+            ScaleMode::Lydian => "Lydian",
+            ScaleMode::Mixolydian => "Mixolydian",
+            ScaleMode::NaturalMinor => "Natural Minor",
+            ScaleMode::Locrian => "Locrian",
+            ScaleMode::IonianAug => "Ionian Augmented",
+            ScaleMode::LydianAug => "Lydian Augmented",
+            ScaleMode::MixolydianAug => "Mixolydian Augmented",
+            ScaleMode::MelodicMinor => "Melodic Minor",
+            ScaleMode::HarmonicMajor => "Harmonic Major",
+            ScaleMode::HarmonicMinor => "Harmonic Minor",
+            ScaleMode::Altered => "Altered",
+            ScaleMode::WholeTone => "Whole Tone",
+            ScaleMode::AugAH => "Augmented",
+            ScaleMode::AugHA => "Augmented Inverse",
+            ScaleMode::DimHW => "Diminished Half-Whole",
+            ScaleMode::DimWH => "Diminished Whole-Half",
+            ScaleMode::BebopDominant => "Bebop Dominant",
+            ScaleMode::BebopMajor => "Bebop Major",
+            ScaleMode::BebopDorian => "Bebop Dorian",
+            ScaleMode::BebopMelodicMinor => "Bebop Melodic Minor",
+        })
+    }
+}
+
+/// How a scale differs from the [ScaleMode] it's described by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScaleAlteration {
+    /// A degree of the mode, counting the tonic as 1, raised or lowered by a semitone, e.g. the b3 of Lydian b3.
+    Altered { degree: usize, semitones: i8 },
+    /// A tone added alongside those of the mode, e.g. the passing tone of a bebop scale.
+    Added(Pc),
+    /// A degree of the mode, counting the tonic as 1, that's missing.
+    Omitted(usize),
+}
+
+/// The names of the tones of the chromatic scale above a tonic, as scale degrees.
+const CHROMATIC_DEGREES: [&str; 12] = ["1", "b2", "2", "b3", "3", "4", "#4", "5", "b6", "6", "b7", "7"];
+
+impl Display for ScaleAlteration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ScaleAlteration::Altered { degree, semitones } => {
+                write!(f, "{}{}", if *semitones < 0 { "b" } else { "#" }, degree)
+            }
+            ScaleAlteration::Added(pc) => write!(f, "add {}", CHROMATIC_DEGREES[usize::from(u8::from(pc))]),
+            ScaleAlteration::Omitted(degree) => write!(f, "no {}", degree),
+        }
+    }
+}
+
+/// A scale described as a [ScaleMode] with alterations, e.g. Lydian with a b3.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleDescription {
+    pub mode: ScaleMode,
+    /// In order up from the tonic.
+    pub alterations: Vec<ScaleAlteration>,
+    /// How much of the scale the mode accounts for, from 0 to 1: the tones the scale and mode share,
+    /// out of all the tones of either. 1 when the scale is the mode, unaltered.
+    pub confidence: f64,
+}
+
+impl ScaleDescription {
+    /// Describes `pcs`, with the tonic as [Pc0], as `mode` with alterations.
+    ///
+    /// Each degree of the mode that's missing from `pcs`, where the tone a semitone below or above
+    /// it is there instead, is altered. Other tones of `pcs` outside the mode are added,
+    /// and other degrees of the mode missing from `pcs` are omitted.
+    pub fn new(mode: ScaleMode, pcs: &HashSet<Pc>) -> Self {
+        let mut pcs = pcs.clone();
+        pcs.insert(Pc0);
+        let degrees = mode.pcs();
+        let mut extra: Vec<Pc> = pcs.iter().filter(|pc| !degrees.contains(pc)).copied().collect();
+        let mut alterations: Vec<(Pc, ScaleAlteration)> = vec![];
+        for (i, degree) in degrees.iter().enumerate().filter(|(_, degree)| !pcs.contains(*degree)) {
+            let neighbour = [-1i8, 1].into_iter().find_map(|semitones| {
+                let pc = Pc::from(&(i32::from(degree) + i32::from(semitones)));
+                extra.iter().position(|extra| *extra == pc).map(|position| (position, semitones))
+            });
+            match neighbour {
+                Some((position, semitones)) => {
+                    let pc = extra.remove(position);
+                    alterations.push((pc, ScaleAlteration::Altered { degree: i + 1, semitones }));
+                }
+                None => alterations.push((*degree, ScaleAlteration::Omitted(i + 1))),
+            }
+        }
+        alterations.extend(extra.iter().map(|pc| (*pc, ScaleAlteration::Added(*pc))));
+        alterations.sort_by_key(|(pc, _)| *pc);
+
+        let shared = degrees.iter().filter(|pc| pcs.contains(*pc)).count();
+        let either = degrees.len() + pcs.len() - shared;
+        Self {
+            mode,
+            alterations: alterations.into_iter().map(|(_, alteration)| alteration).collect(),
+            confidence: shared as f64 / either as f64,
+        }
+    }
+
+    /// Whether the scale is its mode, unaltered.
+    pub fn is_exact(&self) -> bool {
+        self.alterations.is_empty()
+    }
+
+    /// The tones added alongside those of the mode.
+    pub fn added(&self) -> Vec<Pc> {
+        self.alterations.iter()
+            .filter_map(|alteration| match alteration {
+                ScaleAlteration::Added(pc) => Some(*pc),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Display for ScaleDescription {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.is_exact() {
+            return write!(f, "{}", self.mode);
+        }
+        let alterations: Vec<String> = self.alterations.iter()
+            .map(|alteration| alteration.to_string())
+            .collect();
+        write!(f, "{} ({})", self.mode, alterations.join(", "))
+    }
+}
@@ -0,0 +1,187 @@
+use crate::prelude::*;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::chord_name::{ChordName, TonalSpecification};
+use crate::note_collections::chord_name::naming_heuristics::infer_chord_quality;
+use crate::note_collections::chord_name::quality::chord::ChordQuality;
+use crate::note_collections::pc_set::PcSet;
+
+/// Names a chord that is edited one pitch class at a time, as in an interactive editor.
+///
+/// The session holds the current pitch classes, relative to a root of [Pc::Pc0],
+/// along with the [ChordQuality] inferred from them.
+/// Every set of pitch classes that has been analyzed is remembered, so toggling a note
+/// on and off again never re-runs the naming heuristics, and edits that leave the set
+/// unchanged do no work at all. There are only 2048 sets containing the root,
+/// so the cache is naturally bounded.
+#[derive(Debug, Clone)]
+pub struct ChordNameSession {
+    /// The pitch classes currently in the chord.
+    pcs: HashSet<Pc>,
+    /// The analysis of `pcs`.
+    quality: Option<ChordQuality>,
+    /// Every analysis performed so far, indexed by sorted pitch classes.
+    cache: HashMap<Vec<Pc>, Option<ChordQuality>>,
+    /// How many times the naming heuristics have been run.
+    evaluations: usize,
+    /// Whether a root and one other pitch class is named, as an interval or power chord.
+    dyads: bool,
+}
+
+impl ChordNameSession {
+    /// Starts a session with just the root of the chord.
+    pub fn new() -> Self {
+        Self::from_pcs(&[Pc::Pc0])
+    }
+
+    /// Starts a session with some initial pitch classes. The root, [Pc::Pc0],
+    /// is always included.
+    pub fn from_pcs(pcs: &[Pc]) -> Self {
+        let mut session = Self {
+            pcs: pcs.iter().copied().collect(),
+            quality: None,
+            cache: HashMap::new(),
+            evaluations: 0,
+            dyads: false,
+        };
+        session.pcs.insert(Pc::Pc0);
+        session.reevaluate();
+        session
+    }
+
+    /// Names a root and one other pitch class too, e.g. as C5 for guitar, see
+    /// [crate::note_collections::chord_name::ChordNameDisplayConfig::power_chords].
+    /// By default, only chords of at least three pitch classes are named.
+    pub fn with_dyads(mut self) -> Self {
+        self.dyads = true;
+        self.cache.clear();
+        self.reevaluate();
+        self
+    }
+
+    /// Adds a pitch class to the chord and returns the updated analysis.
+    pub fn add_pc(&mut self, pc: Pc) -> Option<&ChordQuality> {
+        if self.pcs.insert(pc) {
+            self.reevaluate();
+        }
+        self.quality()
+    }
+
+    /// Removes a pitch class from the chord and returns the updated analysis.
+    /// The root can't be removed.
+    pub fn remove_pc(&mut self, pc: Pc) -> Option<&ChordQuality> {
+        if pc != Pc::Pc0 && self.pcs.remove(&pc) {
+            self.reevaluate();
+        }
+        self.quality()
+    }
+
+    /// The analysis of the current pitch classes, if they can be named as a chord.
+    pub fn quality(&self) -> Option<&ChordQuality> {
+        self.quality.as_ref()
+    }
+
+    /// The current pitch classes.
+    pub fn pc_set(&self) -> PcSet {
+        PcSet::new(self.pcs.iter().copied().collect())
+    }
+
+    /// Names the current chord over a given root note.
+    pub fn name(&self, root: &Note) -> Option<ChordName> {
+        let quality = self.quality.clone()?;
+        Some(ChordName {
+            tonality: TonalSpecification::RootPosition(*root),
+            omissions: quality.omissions(&self.pcs),
+            quality,
+            pc_set: self.pc_set(),
+        })
+    }
+
+    fn reevaluate(&mut self) {
+        let mut key: Vec<Pc> = self.pcs.iter().copied().collect();
+        key.sort();
+        if let Some(quality) = self.cache.get(&key) {
+            self.quality = quality.clone();
+            return;
+        }
+        // Only chords of at least three notes are named, not single notes, nor intervals unless asked for.
+        let smallest = if self.dyads { 2 } else { 3 };
+        let quality = if self.pcs.len() < smallest {
+            None
+        } else {
+            self.evaluations += 1;
+            infer_chord_quality(&self.pcs).and_then(|(_, quality)| quality)
+        };
+        self.cache.insert(key, quality.clone());
+        self.quality = quality;
+    }
+}
+
+impl Default for ChordNameSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MusicSemanticsError;
+    use crate::note_collections::chord_name::ChordNameDisplayConfig;
+    use crate::note_collections::spelling::SpellingPreference;
+    use crate::note::locale::NoteNameLocale;
+    use crate::note::pitch_class::Pc::*;
+
+    #[test]
+    fn incremental_naming() {
+        let mut session = ChordNameSession::new();
+        assert_eq!(session.add_pc(Pc4), None);
+        let major = session.add_pc(Pc7).cloned().unwrap();
+        assert_eq!(session.evaluations, 1);
+        let maj7 = session.add_pc(Pc11).cloned().unwrap();
+        assert_ne!(major, maj7);
+        assert_eq!(session.name(&Note::C).unwrap().symbol(None), "CMaj7");
+        // Taking the 7th back off again is answered from the cache.
+        assert_eq!(session.remove_pc(Pc11), Some(&major));
+        assert_eq!(session.add_pc(Pc11), Some(&maj7));
+        assert_eq!(session.evaluations, 2);
+        // Redundant edits do nothing.
+        session.add_pc(Pc4);
+        session.remove_pc(Pc0);
+        session.remove_pc(Pc2);
+        assert_eq!(session.pc_set(), PcSet::from(vec![Pc0, Pc4, Pc7, Pc11]));
+        assert_eq!(session.evaluations, 2);
+    }
+
+    #[test]
+    fn dyads() {
+        let cfg = ChordNameDisplayConfig { power_chords: true, ..Default::default() };
+        let mut session = ChordNameSession::new();
+        assert_eq!(session.add_pc(Pc7), None);
+        let mut session = session.with_dyads();
+        assert!(session.quality().is_some());
+        assert_eq!(session.name(&Note::E).unwrap().symbol(Some(&cfg)), "E5");
+        session.remove_pc(Pc7);
+        session.add_pc(Pc6);
+        assert_eq!(session.name(&Note::E).unwrap().symbol(Some(&cfg)), "E(b5)");
+        assert_eq!(session.name(&Note::E).unwrap().symbol(None), "E(TT)");
+    }
+
+    #[test]
+    fn chord_names_to_notes() {
+        let mut session = ChordNameSession::new();
+        session.add_pc(Pc3);
+        session.add_pc(Pc7);
+        session.add_pc(Pc10);
+        let mut name = session.name(&Note::Ees).unwrap();
+        assert_eq!(name.to_note_set().unwrap().to_vec(), vec![Note::Ees, Note::Ges, Note::Bes, Note::Des]);
+        let sharps = ChordNameDisplayConfig { spelling: SpellingPreference::PreferSharps, ..Default::default() };
+        assert!(name.symbol(Some(&sharps)).starts_with("D#"));
+        let german = ChordNameDisplayConfig { locale: NoteNameLocale::German, ..Default::default() };
+        assert!(name.symbol(Some(&german)).starts_with("Es"));
+        name.tonality = TonalSpecification::SlashChord { bass: Note::F, root: Note::Ees };
+        assert_eq!(name.to_note_set().unwrap().to_vec(), vec![Note::Ees, Note::F, Note::Ges, Note::Bes, Note::Des]);
+        name.tonality = TonalSpecification::None(None);
+        assert!(matches!(name.to_note_set(), Err(MusicSemanticsError::NoChordRoot)));
+    }
+}
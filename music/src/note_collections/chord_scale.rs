@@ -0,0 +1,65 @@
+use crate::prelude::*;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::chord_name::quality::scale::ScaleMode;
+
+/// The scales played over chords, most preferred first, as in jazz chord-scale theory: e.g. major
+/// over a major seventh chord, Lydian once it has a #11, Dorian over a minor seventh, Locrian over
+/// a half-diminished seventh, the whole-half diminished scale over a diminished seventh, and the
+/// altered scale over an altered dominant.
+pub const CHORD_SCALES: [ScaleMode; 14] = [
+    ScaleMode::Major,
+    ScaleMode::Mixolydian,
+    ScaleMode::Dorian,
+    ScaleMode::Locrian,
+    ScaleMode::MelodicMinor,
+    ScaleMode::LydianAug,
+    ScaleMode::Lydian,
+    ScaleMode::WholeTone,
+    ScaleMode::DimWH,
+    ScaleMode::DimHW,
+    ScaleMode::Altered,
+    ScaleMode::NaturalMinor,
+    ScaleMode::Phrygian,
+    ScaleMode::HarmonicMinor,
+];
+
+/// The scale to play over a chord, from its root, which is taken to be its first note:
+/// the first of [CHORD_SCALES] that has every note of the chord.
+/// Returns `None` for an empty chord, and for one that none of them has every note of.
+pub fn chord_scale(chord: &NoteSet) -> Option<ScaleMode> {
+    let root = Pc::from(chord.first()?);
+    let intervals: Vec<Pc> = chord.iter()
+        .map(|note| Pc::from(&root.distance_up_to(&Pc::from(note))))
+        .collect();
+    CHORD_SCALES.into_iter().find(|scale| {
+        let pcs = scale.pcs();
+        intervals.iter().all(|interval| pcs.contains(interval))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::note::Note;
+
+    #[test]
+    fn chord_scales() {
+        let scale = |notes: &[Note]| chord_scale(&NoteSet::starting_from_first_note(notes.to_vec()));
+        assert_eq!(scale(&[Note::C, Note::E, Note::G, Note::B]), Some(ScaleMode::Major));
+        assert_eq!(scale(&[Note::C, Note::E, Note::G, Note::B, Note::Fis]), Some(ScaleMode::Lydian));
+        assert_eq!(scale(&[Note::G, Note::B, Note::D, Note::F]), Some(ScaleMode::Mixolydian));
+        assert_eq!(scale(&[Note::D, Note::F, Note::A, Note::C]), Some(ScaleMode::Dorian));
+        assert_eq!(scale(&[Note::B, Note::D, Note::F, Note::A]), Some(ScaleMode::Locrian));
+        assert_eq!(scale(&[Note::C, Note::Ees, Note::G, Note::B]), Some(ScaleMode::MelodicMinor));
+        assert_eq!(scale(&[Note::B, Note::D, Note::F, Note::Aes]), Some(ScaleMode::DimWH));
+        assert_eq!(scale(&[Note::G, Note::B, Note::D, Note::F, Note::Aes]), Some(ScaleMode::DimHW));
+        assert_eq!(scale(&[Note::G, Note::B, Note::Dis, Note::F]), Some(ScaleMode::WholeTone));
+        assert_eq!(scale(&[Note::G, Note::B, Note::F, Note::Aes, Note::Dis]), Some(ScaleMode::Altered));
+        assert_eq!(scale(&[Note::A, Note::C, Note::E, Note::F]), Some(ScaleMode::NaturalMinor));
+        assert_eq!(scale(&[Note::E, Note::G, Note::B, Note::F, Note::C]), Some(ScaleMode::Phrygian));
+        assert_eq!(scale(&[Note::C, Note::Ees, Note::G, Note::Aes, Note::B]), Some(ScaleMode::HarmonicMinor));
+        assert_eq!(scale(&[]), None);
+        assert_eq!(scale(&[Note::C, Note::Cis, Note::D]), None);
+    }
+}
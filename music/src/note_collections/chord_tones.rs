@@ -0,0 +1,92 @@
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use crate::note::note::Note;
+
+/// Semitones above the root of each degree of the major scale, from 1 to 7.
+const MAJOR_SCALE_SEMITONES: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// The function of a note relative to a root, such as the root itself, a flat third,
+/// or a sharp eleventh. Degrees are measured against the major scale of the root.
+///
+/// The degree comes from the spelling of the note, so e.g. D# above C is a sharp second,
+/// while Eb above C is a flat third.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Degree {
+    /// From 1 for the root, up to 7, or up to 13 for compound degrees.
+    pub number: u8,
+    /// Semitones above (positive) or below (negative) the major scale degree.
+    pub alteration: i8,
+}
+
+impl Degree {
+    pub const ROOT: Degree = Degree { number: 1, alteration: 0 };
+
+    /// The degree of `note` above `root`, as a simple degree from 1 to 7.
+    pub fn between(root: &Note, note: &Note) -> Self {
+        let index = root.diatonic_distance_up(note);
+        let semitones = i8::try_from(root.distance_up_to_note(note)).unwrap();
+        let expected = i8::try_from(MAJOR_SCALE_SEMITONES[usize::from(index)]).unwrap();
+        // Keep alterations small, e.g. Cb above C is a flat octave, not a major seventh.
+        let alteration = (semitones - expected + 6).rem_euclid(12) - 6;
+        Self { number: index + 1, alteration }
+    }
+
+    /// The second, fourth, and sixth, as the ninth, eleventh, and thirteenth.
+    /// Other degrees are unchanged.
+    pub fn compound(&self) -> Self {
+        match self.number {
+            2 | 4 | 6 => Self { number: self.number + 7, ..*self },
+            _ => *self,
+        }
+    }
+}
+
+/// The root is written "R", and other degrees with their accidentals, e.g. "b7" or "#11".
+impl Display for Degree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if *self == Self::ROOT {
+            return write!(f, "R");
+        }
+        let accidental = match self.alteration {
+            a if a < 0 => "b".repeat(a.unsigned_abs().into()),
+            a => "#".repeat(a.unsigned_abs().into()),
+        };
+        write!(f, "{}{}", accidental, self.number)
+    }
+}
+
+/// Labels each note of a chord with its degree above `root`.
+///
+/// Once a chord has a seventh, its seconds, fourths, and sixths are extensions,
+/// and are labelled as ninths, elevenths, and thirteenths. Otherwise they're added tones,
+/// as in a sus2 or a 6 chord, and keep their simple degrees.
+pub fn chord_degrees(root: &Note, notes: &[Note]) -> Vec<Degree> {
+    let degrees: Vec<Degree> = notes.iter()
+        .map(|note| Degree::between(root, note))
+        .collect();
+    let has_seventh = degrees.iter().any(|degree| degree.number == 7);
+    if !has_seventh {
+        return degrees;
+    }
+    degrees.iter().map(Degree::compound).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degree_labels() {
+        let labels = |root, notes: &[Note]| chord_degrees(&root, notes).iter()
+            .map(|degree| degree.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(labels(Note::C, &[Note::C, Note::E, Note::G, Note::Bes, Note::D]),
+                   vec!["R", "3", "5", "b7", "9"]);
+        assert_eq!(labels(Note::A, &[Note::A, Note::C, Note::Ees, Note::Ges]),
+                   vec!["R", "b3", "b5", "bb7"]);
+        assert_eq!(labels(Note::C, &[Note::C, Note::D, Note::G, Note::A]),
+                   vec!["R", "2", "5", "6"]);
+        assert_eq!(Degree::between(&Note::C, &Note::Fis).compound().to_string(), "#11");
+        assert_eq!(Degree::between(&Note::C, &Note::Dis).to_string(), "#2");
+    }
+}
@@ -1,7 +1,10 @@
 pub mod symmetry;
 pub mod sets;
+pub mod similarity;
 pub mod contour;
 
+use crate::prelude::*;
+
 // TODO Voiceleading search built off of this type? Or different search method
 pub struct IntervalMatrix(Vec<Vec<i8>>);
 
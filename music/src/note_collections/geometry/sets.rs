@@ -1,3 +1,4 @@
+use crate::prelude::*;
 use itertools::Itertools;
 use crate::error::MusicSemanticsError;
 use crate::note::Pc;
@@ -7,7 +8,7 @@ pub fn get_subchords(pcs: &PcSet, size: u8) -> Result<Vec<Vec<Pc>>, MusicSemanti
     if size < 3 {
         return Err(MusicSemanticsError::SizeTooSmallForChords(size as usize));
     }
-    if size as usize > pcs.len() - 1 {
+    if size as usize >= pcs.len() {
         return Err(MusicSemanticsError::SizeTooLargeForSubchords(size, pcs.clone()));
     }
     Ok((**pcs).clone()
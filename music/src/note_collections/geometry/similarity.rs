@@ -0,0 +1,118 @@
+use crate::prelude::*;
+use itertools::Itertools;
+use crate::note_collections::PcSet;
+
+/// How many times each interval class, from the semitone (ic1) up to the tritone (ic6),
+/// occurs between the pitch classes of a set.
+pub fn interval_class_vector(pcs: &PcSet) -> [u32; 6] {
+    let mut vector = [0; 6];
+    for (a, b) in pcs.iter().tuple_combinations() {
+        let distance = a.distance_up_to(b);
+        let interval_class = distance.min(12 - distance);
+        if interval_class > 0 {
+            vector[usize::from(interval_class - 1)] += 1;
+        }
+    }
+    vector
+}
+
+/// Ways of measuring how far apart the interval content of two sets is,
+/// where 0 means that their interval class vectors are identical.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalSimilarity {
+    /// The sum of the differences between each interval class count,
+    /// i.e. Morris's SIM measure.
+    #[default]
+    AbsoluteDifference,
+    /// The sum of the squared differences between each interval class count.
+    /// This ranks the same as Euclidean distance, and weighs one large difference
+    /// over several small ones.
+    SquaredDifference,
+}
+
+impl IntervalSimilarity {
+    pub fn distance(&self, a: &[u32; 6], b: &[u32; 6]) -> u32 {
+        let differences = a.iter().zip(b).map(|(a, b)| a.abs_diff(*b));
+        match self {
+            IntervalSimilarity::AbsoluteDifference => differences.sum(),
+            IntervalSimilarity::SquaredDifference => differences.map(|d| d * d).sum(),
+        }
+    }
+}
+
+/// The most compact transposition of a set. See [PcSet::normal_form].
+pub fn normal_form(pcs: &PcSet) -> PcSet {
+    pcs.normal_form()
+}
+
+/// Every set of three to seven pitch classes, up to transposition, each in its [normal_form].
+/// Ordered by size, and then from the most to the least compact.
+pub fn set_class_corpus() -> Vec<PcSet> {
+    (3..=7)
+        .flat_map(|size| (1..12u8)
+            .combinations(size - 1)
+            .map(|upper| normal_form(&PcSet::from([0].into_iter().chain(upper).collect::<Vec<u8>>())))
+            .sorted_by_key(|pcs| pcs.iter().rev().map(u8::from).collect::<Vec<u8>>())
+            .dedup())
+        .collect()
+}
+
+/// Ranks the sets of `corpus` by how similar their interval content is to `pcs`,
+/// closest first, alongside their distance as measured by `metric`.
+/// Sets with the same distance keep their order in the corpus.
+///
+/// Any set sharing the interval content of `pcs`, including its own transpositions,
+/// inversions, and Z-related sets, comes first with a distance of 0.
+/// [set_class_corpus] provides a corpus of all chords and scales of three to seven notes.
+pub fn find_similar<'c>(
+    pcs: &PcSet,
+    corpus: &'c [PcSet],
+    metric: IntervalSimilarity,
+) -> Vec<(&'c PcSet, u32)> {
+    let target = interval_class_vector(pcs);
+    corpus.iter()
+        .map(|candidate| (candidate, metric.distance(&target, &interval_class_vector(candidate))))
+        .sorted_by_key(|(_, distance)| *distance)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::Pc::*;
+
+    #[test]
+    fn interval_content_similarity() {
+        let major = PcSet::from(vec![Pc0, Pc4, Pc7]);
+        assert_eq!(interval_class_vector(&major), [0, 0, 1, 1, 1, 0]);
+        let diatonic = PcSet::from(vec![Pc0, Pc2, Pc4, Pc5, Pc7, Pc9, Pc11]);
+        assert_eq!(interval_class_vector(&diatonic), [2, 5, 4, 3, 6, 1]);
+        assert_eq!(normal_form(&PcSet::from(vec![Pc2, Pc7, Pc11])), major);
+
+        let corpus = set_class_corpus();
+        let sizes: Vec<usize> = (3..=7)
+            .map(|size| corpus.iter().filter(|pcs| pcs.len() == size).count())
+            .collect();
+        assert_eq!(sizes, vec![19, 43, 66, 80, 66]);
+
+        // Major and minor triads share their interval content.
+        let similar = find_similar(&major, &corpus, IntervalSimilarity::AbsoluteDifference);
+        let identical: Vec<&PcSet> = similar.iter()
+            .take_while(|(_, distance)| *distance == 0)
+            .map(|(pcs, _)| *pcs)
+            .collect();
+        assert_eq!(identical, vec![&PcSet::from(vec![Pc0, Pc3, Pc7]), &major]);
+        assert_eq!(similar[2].1, 2);
+
+        // The Z-related "all-interval" tetrachords, and their inversions.
+        let z_4_15 = PcSet::from(vec![Pc0, Pc1, Pc4, Pc6]);
+        let z_4_29 = PcSet::from(vec![Pc0, Pc1, Pc3, Pc7]);
+        let similar = find_similar(&z_4_15, &corpus, IntervalSimilarity::SquaredDifference);
+        let identical: Vec<&PcSet> = similar.iter()
+            .take_while(|(_, distance)| *distance == 0)
+            .map(|(pcs, _)| *pcs)
+            .collect();
+        assert_eq!(identical.len(), 4);
+        assert!(identical.contains(&&normal_form(&z_4_29)));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::prelude::*;
+use crate::note::pitch_class::Pc;
+
+/// The symmetric parent collections most often used in analysis. Each is a limited transposition:
+/// it maps onto itself under some transpositions, so only a few distinct transpositions exist.
+///
+/// Transposition `n` is transposition 0 moved up `n` semitones, not the one whose lowest [Pc] is `n`,
+/// e.g. the whole-tone collection containing C# is transposition 1, and the octatonic collection
+/// D Eb F F# ... is transposition 2, though it contains C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymmetricCollection {
+    /// Two transpositions of six notes, a whole step apart.
+    WholeTone,
+    /// Three transpositions of eight notes, alternating half and whole steps.
+    Octatonic,
+    /// Four transpositions of six notes, alternating half steps and minor thirds.
+    Hexatonic,
+}
+
+impl SymmetricCollection {
+    /// The collection at transposition 0, as a bitmask where bit `n` is [Pc] `n`.
+    const fn mask(&self) -> u16 {
+        match self {
+            // 0, 2, 4, 6, 8, 10
+            SymmetricCollection::WholeTone => 0b0101_0101_0101,
+            // 0, 1, 3, 4, 6, 7, 9, 10
+            SymmetricCollection::Octatonic => 0b0110_1101_1011,
+            // 0, 1, 4, 5, 8, 9
+            SymmetricCollection::Hexatonic => 0b0011_0011_0011,
+        }
+    }
+
+    /// The number of distinct transpositions.
+    pub fn num_transpositions(&self) -> u8 {
+        match self {
+            SymmetricCollection::WholeTone => 2,
+            SymmetricCollection::Octatonic => 3,
+            SymmetricCollection::Hexatonic => 4,
+        }
+    }
+
+    /// The pitch classes of one transposition of the collection, from low to high.
+    pub fn pcs(&self, transposition: u8) -> Vec<Pc> {
+        let mask = transposed_mask(self.mask(), transposition);
+        (0..12u8)
+            .filter(|pc| mask & (1 << pc) != 0)
+            .map(|pc| Pc::from(&pc))
+            .collect()
+    }
+
+    /// Every transposition of the collection containing all of `pcs`, from low to high.
+    /// Small sets may belong to several, e.g. a tritone lies in two of the three octatonic collections.
+    pub fn transpositions_containing(&self, pcs: &[Pc]) -> Vec<u8> {
+        let set = pc_mask(pcs);
+        (0..self.num_transpositions())
+            .filter(|transposition| set & !transposed_mask(self.mask(), *transposition) == 0)
+            .collect()
+    }
+
+    /// The lowest transposition of the collection containing all of `pcs`, if any.
+    pub fn parent_of(&self, pcs: &[Pc]) -> Option<u8> {
+        self.transpositions_containing(pcs).into_iter().next()
+    }
+}
+
+fn pc_mask(pcs: &[Pc]) -> u16 {
+    pcs.iter().fold(0, |mask, pc| mask | (1 << u8::from(pc)))
+}
+
+/// Rotates a mask of pitch classes up some number of semitones.
+fn transposed_mask(mask: u16, semitones: u8) -> u16 {
+    let semitones = semitones % 12;
+    ((mask << semitones) | (mask >> (12 - semitones))) & 0x0FFF
+}
+
+/// Which whole-tone collection, if any, contains all of `pcs`.
+pub fn is_subset_of_wholetone(pcs: &[Pc]) -> Option<u8> {
+    SymmetricCollection::WholeTone.parent_of(pcs)
+}
+
+/// Which octatonic collection, if any, contains all of `pcs`.
+pub fn is_subset_of_octatonic(pcs: &[Pc]) -> Option<u8> {
+    SymmetricCollection::Octatonic.parent_of(pcs)
+}
+
+/// Which hexatonic collection, if any, contains all of `pcs`.
+pub fn is_subset_of_hexatonic(pcs: &[Pc]) -> Option<u8> {
+    SymmetricCollection::Hexatonic.parent_of(pcs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::pitch_class::Pc::*;
+
+    #[test]
+    fn symmetric_collection_membership() {
+        assert_eq!(SymmetricCollection::Octatonic.pcs(2), vec![Pc0, Pc2, Pc3, Pc5, Pc6, Pc8, Pc9, Pc11]);
+        // C7(b9) lies in the octatonic collection containing C and C#.
+        assert_eq!(is_subset_of_octatonic(&[Pc0, Pc4, Pc7, Pc10, Pc1]), Some(0));
+        // A C+7 chord, D E F# A# C.
+        assert_eq!(is_subset_of_wholetone(&[Pc2, Pc4, Pc6, Pc10, Pc0]), Some(0));
+        assert_eq!(is_subset_of_wholetone(&[Pc1, Pc5, Pc9]), Some(1));
+        assert_eq!(is_subset_of_wholetone(&[Pc0, Pc7]), None);
+        // The C, E, and Ab major triads share a hexatonic collection.
+        assert_eq!(is_subset_of_hexatonic(&[Pc8, Pc0, Pc3, Pc4, Pc7, Pc11]), Some(3));
+        assert_eq!(is_subset_of_hexatonic(&[Pc0, Pc4, Pc7, Pc2]), None);
+        // A tritone lies in two octatonic collections.
+        assert_eq!(SymmetricCollection::Octatonic.transpositions_containing(&[Pc0, Pc6]), vec![0, 2]);
+    }
+}
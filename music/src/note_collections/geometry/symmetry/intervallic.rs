@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use core::ops::Deref;
 use crate::note_collections::{OctavePartition, PcSet};
 
 pub trait IntervallicSymmetry: Sized {
@@ -0,0 +1,105 @@
+//! Laws that implementations of [Transpose] and [Modes] are expected to obey,
+//! as assertions to be called from property tests, e.g. with `proptest`,
+//! both here and by crates that implement the traits for their own types.
+//!
+//! Each function panics, naming the law, if it doesn't hold for the given inputs.
+use core::fmt::Debug;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::geometry::symmetry::transpositional::{
+    find_transpositional_symmetries, Modes, Transpose, TranspositionalSymmetryMap,
+};
+
+/// Asserts that transposing by an octave changes nothing, and that transposing by `a`
+/// and then by `b` is the same as transposing by `a + b`, which must fit in an [i8].
+pub fn assert_transpose_laws<T: Transpose + Clone + Debug>(item: &T, a: i8, b: i8) {
+    assert_transpose_laws_by(item, a, b, T::clone);
+}
+
+/// As [assert_transpose_laws], but only up to equality of `key`. For types whose
+/// transpositions are respelled, e.g. [crate::note::Note], whose transpositions
+/// use the first spelling of their [Pc], so that Db up an octave is C#.
+pub fn assert_transpose_laws_by<T, K, F>(item: &T, a: i8, b: i8, key: F)
+where
+    T: Transpose + Debug,
+    K: PartialEq + Debug,
+    F: Fn(&T) -> K,
+{
+    let sum = a.checked_add(b).expect("the transpositions should sum to an i8");
+    for octave in [12, -12] {
+        assert_eq!(key(&item.transpose(octave)), key(item),
+            "transposing {:?} by {} semitones should leave it unchanged", item, octave);
+    }
+    assert_eq!(key(&item.transpose(a).transpose(b)), key(&item.transpose(sum)),
+        "transposing {:?} by {} and then {} semitones should equal transposing it by {}", item, a, b, sum);
+}
+
+/// Asserts that `item` has `size` modes, i.e. one per element,
+/// and that it and each of its modes are modes of it.
+pub fn assert_modes_laws<T: Modes + Debug>(item: &T, size: usize) {
+    let modes = item.modes();
+    assert_eq!(modes.len(), size, "{:?} should have one mode per element", item);
+    assert!(item.is_mode(item).is_some(), "{:?} should be a mode of itself", item);
+    for mode in &modes {
+        assert!(item.is_mode(mode).is_some(), "{:?} should be a mode of {:?}", mode, item);
+    }
+}
+
+/// Asserts that the transpositional symmetries found in a sorted, deduplicated set of [Pc]s
+/// move with it when it's transposed by `semitones`, i.e. that the same symmetries are found
+/// about each of its transposed points of symmetry.
+pub fn assert_symmetries_transpose(pcs: &[Pc], semitones: i8) {
+    let pcs = pcs.to_vec();
+    let mut transposed = pcs.transpose(semitones);
+    transposed.sort();
+    let expected: TranspositionalSymmetryMap = find_transpositional_symmetries(&pcs)
+        .into_iter()
+        .map(|(pc, symmetries)| (pc.transpose(semitones), symmetries))
+        .collect();
+    assert_eq!(find_transpositional_symmetries(&transposed), expected,
+        "the symmetries of {:?} should move with it when transposed by {} semitones", pcs, semitones);
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use crate::prelude::*;
+    use crate::note::Note;
+    use crate::note_collections::{NoteSet, PcSet};
+    use super::*;
+
+    fn pcs() -> impl Strategy<Value = Vec<Pc>> {
+        proptest::collection::btree_set(0..12u8, 0..=12)
+            .prop_map(|pcs| pcs.iter().map(Pc::from).collect())
+    }
+
+    fn transposition() -> impl Strategy<Value = i8> {
+        -64..=63i8
+    }
+
+    proptest! {
+        #[test]
+        fn transpose_laws(pcs in pcs(), a in transposition(), b in transposition()) {
+            assert_transpose_laws(&pcs, a, b);
+            if let Some(pc) = pcs.first() {
+                assert_transpose_laws(pc, a, b);
+            }
+            let notes: Vec<Note> = pcs.iter().map(|pc| pc.notes()[pc.notes().len() - 1]).collect();
+            if let Some(note) = notes.first() {
+                assert_transpose_laws_by(note, a, b, |note| Pc::from(note));
+            }
+            assert_transpose_laws_by(&NoteSet::new(notes, None), a, b, |notes| {
+                notes.iter().map(Pc::from).collect::<Vec<_>>()
+            });
+        }
+
+        #[test]
+        fn modes_laws(pcs in pcs().prop_filter("a set with modes", |pcs| !pcs.is_empty())) {
+            assert_modes_laws(&PcSet::new(pcs.clone()), pcs.len());
+        }
+
+        #[test]
+        fn symmetries_transpose(pcs in pcs(), semitones in transposition()) {
+            assert_symmetries_transpose(&pcs, semitones);
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod transpositional;
+pub mod laws;
 pub mod intervallic;
 pub mod voiceleading;
+pub mod collections;
@@ -1,5 +1,5 @@
-use std::hash::{Hash, Hasher};
-use std::collections::{HashMap, HashSet};
+use core::hash::{Hash, Hasher};
+use crate::prelude::*;
 use crate::error::MusicSemanticsError;
 use crate::note::{Note, Pitch};
 use crate::note::pitch_class::Pc;
@@ -245,42 +245,19 @@ impl Into<i8> for &TranspositionalSymmetry {
 pub fn check_for_symmetry(pcs: &Vec<Pc>, symmetry: TranspositionalSymmetry) -> HashMap<Pc, HashSet<TranspositionalSymmetry>> {
     let symmetry_u8: u8 = symmetry.clone().into();
     let mut symmetries = HashMap::new();
-    // Performing this check ahead of time prevents both unnecessary computation and
-    // potential index errors that would occur in the logic below.
+    // Too few notes can't be spread evenly enough to map onto themselves.
     if pcs.len() < (12 / symmetry_u8) as usize {
         return symmetries;
     };
-    // We only need to check half of the "chromatic circle" as we rotate through the vector.
-    let mut checked_through: i32 = 0;
-    // We'll rotate this
-    let mut rotated = pcs.clone();
-    for pc in pcs {
-        // We should technically always hit this before completing iteration over pcs,
-        // but we need access to the nth [Pc], and thus cannot use a while loop.
-        if checked_through >= 6 {
-            return symmetries;
+    let mut transposed = pcs.transpose(i8::try_from(symmetry_u8).unwrap());
+    transposed.sort();
+    // A collection that maps onto itself does so about each of its notes.
+    if *pcs == transposed {
+        for pc in pcs {
+            symmetries.entry(*pc)
+                .or_insert_with(HashSet::new)
+                .insert(symmetry.clone());
         }
-        let mut maybe_same = rotated.transpose(i8::try_from(symmetry_u8).unwrap());
-        maybe_same.sort();
-        if rotated == maybe_same {
-            let pt_of_symmetry = u8::from(pc);
-            let related_points_of_symmetry: Vec<Pc> = (0u8..(12/symmetry_u8))
-                .map(|i| (pt_of_symmetry + symmetry_u8 * i))
-                .map(|i| Pc::from(&i))
-                .collect();
-            for pc in related_points_of_symmetry {
-                let entry = symmetries.entry(pc.clone())
-                    .or_insert_with(|| {
-                        HashSet::new()
-                    });
-                if !entry.contains(&symmetry) {
-                    entry.insert(symmetry.clone());
-                }
-            }
-        }
-        checked_through += (i32::from(&rotated[1]) - i32::from(&rotated[0])).rem_euclid(12);
-        rotated.rotate_left(1);
-        rotated.sort();
     }
     symmetries
 }
@@ -334,5 +311,12 @@ mod tests {
         should_be.insert(Pc9, HashSet::from([TranspositionalSymmetry::T3, TranspositionalSymmetry::T6]));
         should_be.insert(Pc10, HashSet::from([TranspositionalSymmetry::T3, TranspositionalSymmetry::T6]));
         assert_eq!(find_transpositional_symmetries(&pc_set), should_be);
+
+        // Symmetrical about notes more than a tritone above the lowest, too.
+        let pc_set = vec![Pc0, Pc4, Pc5, Pc6, Pc10, Pc11];
+        let should_be: HashMap<Pc, HashSet<TranspositionalSymmetry>> = pc_set.iter()
+            .map(|pc| (*pc, HashSet::from([TranspositionalSymmetry::T6])))
+            .collect();
+        assert_eq!(find_transpositional_symmetries(&pc_set), should_be);
     }
 }
@@ -1,4 +1,5 @@
-use std::fmt::Debug;
+use crate::prelude::*;
+use core::fmt::Debug;
 use itertools::Itertools;
 use crate::error::MusicSemanticsError;
 use crate::note::{Note, Pitch};
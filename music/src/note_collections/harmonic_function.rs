@@ -0,0 +1,167 @@
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::key::{Key, KeyMode};
+
+/// The role a chord plays in a key: at rest, moving away from rest, or pulling back to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HarmonicFunction {
+    Tonic,
+    Subdominant,
+    Dominant,
+}
+
+impl HarmonicFunction {
+    /// T, S, or D, as a function is marked under a chord.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            HarmonicFunction::Tonic => "T",
+            HarmonicFunction::Subdominant => "S",
+            HarmonicFunction::Dominant => "D",
+        }
+    }
+}
+
+/// How [Key::function] hears the chords that theories of harmonic function disagree on.
+///
+/// Chords on the tonic are tonic, on the supertonic and subdominant subdominant,
+/// and on the dominant and leading tone dominant, by every convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FunctionConventions {
+    /// The chords on the mediant, e.g. Em in C major, or Eb in C minor.
+    pub mediant: HarmonicFunction,
+    /// The chords on the submediant, e.g. Am in C major, or Ab in C minor.
+    pub submediant: HarmonicFunction,
+    /// The chords on the lowered seventh degree, e.g. Bb in C minor, or borrowed into C major.
+    pub subtonic: HarmonicFunction,
+    /// Whether chords borrowed from the parallel key, e.g. Fm and Ab in C major, are given the function
+    /// of their root. Otherwise, only chords within the key are, counting the raised sixth and seventh
+    /// of a minor key as within it.
+    pub modal_mixture: bool,
+}
+
+impl Default for FunctionConventions {
+    /// As in most harmony textbooks: the mediant and submediant stand in for the tonic,
+    /// and the subtonic, as in VII–i, is a dominant.
+    fn default() -> Self {
+        Self {
+            mediant: HarmonicFunction::Tonic,
+            submediant: HarmonicFunction::Tonic,
+            subtonic: HarmonicFunction::Dominant,
+            modal_mixture: true,
+        }
+    }
+}
+
+impl FunctionConventions {
+    /// As in Riemann's function theory, where the mediant of a major key is the parallel of the dominant.
+    pub fn riemann() -> Self {
+        Self { mediant: HarmonicFunction::Dominant, ..Self::default() }
+    }
+
+    /// As in rock and pop, where the subtonic leads back to the tonic as a subdominant, e.g. bVII–IV–I.
+    pub fn pop() -> Self {
+        Self { subtonic: HarmonicFunction::Subdominant, ..Self::default() }
+    }
+}
+
+/// The pitch classes of a key's scale, in semitones above its tonic, with the raised sixth
+/// and seventh of a minor key.
+fn scale(mode: KeyMode) -> &'static [u8] {
+    match mode {
+        KeyMode::Major => &[0, 2, 4, 5, 7, 9, 11],
+        KeyMode::Minor => &[0, 2, 3, 5, 7, 8, 9, 10, 11],
+    }
+}
+
+impl Key {
+    /// The harmonic function of a chord in the key, by the degree of its root, which is taken
+    /// to be its first note, as in [crate::note_collections::chord_name::ChordName::from_note_set].
+    ///
+    /// Returns `None` for an empty chord, and for chords with notes outside the key,
+    /// or outside both it and its parallel key with [FunctionConventions::modal_mixture].
+    /// So chromatic chords, e.g. secondary dominants and the Neapolitan, aren't given a function.
+    pub fn function(&self, chord: &NoteSet, conventions: &FunctionConventions) -> Option<HarmonicFunction> {
+        let tonic = Pc::from(&self.tonic);
+        let degree = |pc: Pc| tonic.distance_up_to(&pc);
+        let root = degree(Pc::from(chord.first()?));
+        let parallel = match self.mode {
+            KeyMode::Major => KeyMode::Minor,
+            KeyMode::Minor => KeyMode::Major,
+        };
+        let in_key = |mode: KeyMode| chord.iter().all(|note| scale(mode).contains(&degree(Pc::from(note))));
+        let borrowed = conventions.modal_mixture && in_key(parallel);
+        if !in_key(self.mode) && !borrowed {
+            return None;
+        }
+        Some(match root {
+            0 => HarmonicFunction::Tonic,
+            2 | 5 => HarmonicFunction::Subdominant,
+            3 | 4 => conventions.mediant,
+            8 | 9 => conventions.submediant,
+            10 => conventions.subtonic,
+            7 | 11 => HarmonicFunction::Dominant,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::note::note::Note;
+    use HarmonicFunction::*;
+
+    #[test]
+    fn harmonic_functions() {
+        let chord = |notes: &[Note]| NoteSet::starting_from_first_note(notes.to_vec());
+        let functions = |key: &Key, chords: &[&[Note]], conventions: &FunctionConventions| chords.iter()
+            .map(|notes| key.function(&chord(notes), conventions))
+            .collect::<Vec<_>>();
+        let c_major = Key::new(Note::C, KeyMode::Major).unwrap();
+        let diatonic: [&[Note]; 7] = [
+            &[Note::C, Note::E, Note::G],
+            &[Note::D, Note::F, Note::A],
+            &[Note::E, Note::G, Note::B],
+            &[Note::F, Note::A, Note::C],
+            &[Note::G, Note::B, Note::D, Note::F],
+            &[Note::A, Note::C, Note::E],
+            &[Note::B, Note::D, Note::F],
+        ];
+        let textbook = FunctionConventions::default();
+        assert_eq!(functions(&c_major, &diatonic, &textbook), [
+            Tonic, Subdominant, Tonic, Subdominant, Dominant, Tonic, Dominant,
+        ].map(Some));
+        assert_eq!(c_major.function(&chord(diatonic[2]), &FunctionConventions::riemann()), Some(Dominant));
+
+        // Borrowed from C minor, unless modal mixture is turned off.
+        let borrowed: [&[Note]; 3] = [
+            &[Note::F, Note::Aes, Note::C],
+            &[Note::Aes, Note::C, Note::Ees],
+            &[Note::Bes, Note::D, Note::F],
+        ];
+        assert_eq!(functions(&c_major, &borrowed, &textbook), vec![Some(Subdominant), Some(Tonic), Some(Dominant)]);
+        assert_eq!(functions(&c_major, &borrowed, &FunctionConventions::pop())[2], Some(Subdominant));
+        let strict = FunctionConventions { modal_mixture: false, ..textbook };
+        assert_eq!(functions(&c_major, &borrowed, &strict), vec![None, None, None]);
+
+        // A secondary dominant, and the Neapolitan, are chromatic.
+        assert_eq!(c_major.function(&chord(&[Note::D, Note::Fis, Note::A, Note::C]), &textbook), None);
+        assert_eq!(c_major.function(&chord(&[Note::Des, Note::F, Note::Aes]), &textbook), None);
+        assert_eq!(c_major.function(&chord(&[]), &textbook), None);
+
+        // The raised seventh of A minor makes for a major dominant.
+        let a_minor = Key::new(Note::A, KeyMode::Minor).unwrap();
+        let minor: [&[Note]; 5] = [
+            &[Note::A, Note::C, Note::E],
+            &[Note::D, Note::F, Note::A],
+            &[Note::E, Note::Gis, Note::B],
+            &[Note::F, Note::A, Note::C],
+            &[Note::G, Note::B, Note::D],
+        ];
+        assert_eq!(functions(&a_minor, &minor, &textbook), vec![
+            Some(Tonic), Some(Subdominant), Some(Dominant), Some(Tonic), Some(Dominant),
+        ]);
+        assert_eq!(Dominant.symbol(), "D");
+    }
+}
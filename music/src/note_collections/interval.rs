@@ -0,0 +1,220 @@
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note_collections::interval_class::IntervalClass;
+
+/// The semitones spanned by the major and perfect intervals, from a unison up to a seventh.
+const MAJOR_OR_PERFECT: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+/// The quality of an [Interval]. Augmented and diminished intervals
+/// carry how many times they're augmented or diminished, e.g. 2 for a doubly augmented fourth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntervalQuality {
+    Perfect,
+    Major,
+    Minor,
+    Augmented(u8),
+    Diminished(u8),
+}
+
+/// A named interval, such as a minor third or an augmented sixth.
+///
+/// Unlike an [IntervalClass], an interval depends on spelling as well as semitones,
+/// so C up to Eb is a minor third, while C up to D# is an augmented second.
+/// Intervals greater than an octave are compound, e.g. a minor tenth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    pub quality: IntervalQuality,
+    /// The number of letters spanned, counting both ends, e.g. 3 for a third,
+    /// 1 for a unison and 8 for an octave.
+    pub number: u8,
+}
+
+impl Interval {
+    /// The interval from one note up to another, within an octave.
+    /// A note up to a lowered spelling of itself is a diminished octave, e.g. C up to Cb.
+    pub fn between(from: &Note, to: &Note) -> Self {
+        let steps = from.diatonic_distance_up(to);
+        let semitones = from.distance_up_to_note(to);
+        let (number, reference) = if steps == 0 && semitones > 6 {
+            (8, 12)
+        } else {
+            (steps + 1, MAJOR_OR_PERFECT[usize::from(steps)])
+        };
+        // Within an octave, spellings can't alter an interval by more than half of one.
+        let mut alteration = i16::from(semitones) - i16::from(reference);
+        if alteration > 6 {
+            alteration -= 12;
+        } else if alteration < -6 {
+            alteration += 12;
+        }
+        Self::from_alteration(number, alteration)
+    }
+
+    /// The interval spanned by two pitches, whichever of them is higher,
+    /// including any octaves between them, e.g. C4 and Eb5 span a minor tenth.
+    pub fn between_pitches(a: &Pitch, b: &Pitch) -> Self {
+        let (low, high) = if a.midi_note <= b.midi_note { (a, b) } else { (b, a) };
+        let mut simple = Self::between(&low.note, &high.note);
+        let mut semitones = high.midi_note - low.midi_note;
+        if semitones < simple.semitones() {
+            // The same sounding pitch, spelled with the higher letter first, e.g. B#3 and C4.
+            simple = Self::between(&high.note, &low.note);
+            semitones = simple.semitones();
+        }
+        let octaves = (semitones - simple.semitones()) / 12;
+        Self { quality: simple.quality, number: simple.number + 7 * octaves }
+    }
+
+    fn from_alteration(number: u8, alteration: i16) -> Self {
+        let perfect = matches!((number - 1) % 7, 0 | 3 | 4);
+        let quality = match (perfect, alteration) {
+            (true, 0) => IntervalQuality::Perfect,
+            (false, 0) => IntervalQuality::Major,
+            (false, -1) => IntervalQuality::Minor,
+            (_, alteration) if alteration > 0 => IntervalQuality::Augmented(alteration as u8),
+            (true, alteration) => IntervalQuality::Diminished(alteration.unsigned_abs() as u8),
+            (false, alteration) => IntervalQuality::Diminished((alteration.unsigned_abs() - 1) as u8),
+        };
+        Self { quality, number }
+    }
+
+    /// The number of semitones spanned by the interval.
+    pub fn semitones(&self) -> u8 {
+        let steps = usize::from(self.number - 1);
+        let reference = MAJOR_OR_PERFECT[steps % 7] as i16 + 12 * (steps / 7) as i16;
+        let perfect = matches!(steps % 7, 0 | 3 | 4);
+        let alteration = match self.quality {
+            IntervalQuality::Perfect | IntervalQuality::Major => 0,
+            IntervalQuality::Minor => -1,
+            IntervalQuality::Augmented(n) => n as i16,
+            IntervalQuality::Diminished(n) if perfect => -(n as i16),
+            IntervalQuality::Diminished(n) => -(n as i16) - 1,
+        };
+        (reference + alteration).max(0) as u8
+    }
+
+    /// Whether the interval is greater than an octave.
+    pub fn is_compound(&self) -> bool {
+        self.number > 8
+    }
+
+    /// The interval reduced to within an octave, e.g. a major third for a major tenth.
+    /// Octaves are left as they are.
+    pub fn simple(&self) -> Self {
+        if self.is_compound() {
+            Self { quality: self.quality, number: (self.number - 2) % 7 + 2 }
+        } else {
+            *self
+        }
+    }
+
+    /// The [IntervalClass] of the interval, whatever its spelling, e.g. [IntervalClass::Ic3] for an augmented 9th.
+    pub fn interval_class(&self) -> IntervalClass {
+        IntervalClass::from(&self.semitones())
+    }
+
+    /// The abbreviated name of the interval, e.g. "m3", "P8" or "A6".
+    pub fn short_name(&self) -> String {
+        let quality = match self.quality {
+            IntervalQuality::Perfect => "P".to_string(),
+            IntervalQuality::Major => "M".to_string(),
+            IntervalQuality::Minor => "m".to_string(),
+            IntervalQuality::Augmented(n) => "A".repeat(usize::from(n)),
+            IntervalQuality::Diminished(n) => "d".repeat(usize::from(n)),
+        };
+        format!("{}{}", quality, self.number)
+    }
+}
+
+impl Display for IntervalQuality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let times = |n: &u8| match n {
+            1 => "",
+            2 => "doubly ",
+            3 => "triply ",
+            _ => "quadruply ",
+        };
+        match self {
+            IntervalQuality::Perfect => f.write_str("perfect"),
+            IntervalQuality::Major => f.write_str("major"),
+            IntervalQuality::Minor => f.write_str("minor"),
+            IntervalQuality::Augmented(n) => write!(f, "{}augmented", times(n)),
+            IntervalQuality::Diminished(n) => write!(f, "{}diminished", times(n)),
+        }
+    }
+}
+
+/// The full name of the interval, e.g. "minor 3rd", "perfect octave" or "major 10th".
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let number = match self.number {
+            1 => "unison".to_string(),
+            8 => "octave".to_string(),
+            n if (11..=13).contains(&(n % 100)) => format!("{}th", n),
+            n => format!("{}{}", n, match n % 10 {
+                1 => "st",
+                2 => "nd",
+                3 => "rd",
+                _ => "th",
+            }),
+        };
+        write!(f, "{} {}", self.quality, number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pitch(note: Note, octave: u8) -> Pitch {
+        Pitch::new(note, octave).unwrap()
+    }
+
+    #[test]
+    fn named_intervals() {
+        let names = |pairs: &[(Note, Note)]| pairs.iter()
+            .map(|(a, b)| Interval::between(a, b).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names(&[
+            (Note::C, Note::Ees),
+            (Note::C, Note::Dis),
+            (Note::Aes, Note::Fis),
+            (Note::E, Note::B),
+            (Note::B, Note::F),
+            (Note::Bis, Note::C),
+            (Note::C, Note::Ces),
+            (Note::C, Note::C),
+            (Note::Ces, Note::Gis),
+            (Note::Deses, Note::D),
+        ]), vec![
+            "minor 3rd",
+            "augmented 2nd",
+            "augmented 6th",
+            "perfect 5th",
+            "diminished 5th",
+            "diminished 2nd",
+            "diminished octave",
+            "perfect unison",
+            "doubly augmented 5th",
+            "doubly augmented unison",
+        ]);
+        assert_eq!(Interval::between(&Note::Aes, &Note::Fis).short_name(), "A6");
+        assert_eq!(Interval::between(&Note::E, &Note::C).semitones(), 8);
+
+        let tenth = Interval::between_pitches(&pitch(Note::C, 4), &pitch(Note::Ees, 5));
+        assert_eq!(tenth.to_string(), "minor 10th");
+        assert!(tenth.is_compound());
+        assert_eq!(tenth.semitones(), 15);
+        assert_eq!(tenth.simple(), Interval::between(&Note::C, &Note::Ees));
+        assert_eq!(Interval::between_pitches(&pitch(Note::G, 5), &pitch(Note::G, 3)).short_name(), "P15");
+        assert_eq!(Interval::between_pitches(&pitch(Note::C, 4), &pitch(Note::Fis, 5)).to_string(), "augmented 11th");
+        assert_eq!(Interval::between_pitches(&pitch(Note::C, 4), &pitch(Note::C, 5)).to_string(), "perfect octave");
+        // The same sounding pitch, in either order.
+        let c = Pitch::from_midi(60).unwrap();
+        let b_sharp = Pitch::new_spelled_as_in(60, &vec![Note::Bis]).unwrap();
+        assert_eq!(Interval::between_pitches(&c, &b_sharp).short_name(), "d2");
+        assert_eq!(Interval::between_pitches(&b_sharp, &c).short_name(), "d2");
+    }
+}
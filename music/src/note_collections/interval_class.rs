@@ -1,4 +1,4 @@
-use std::fmt::{Display, Formatter};
+use core::fmt::{Display, Formatter};
 use crate::note::pitch_class::Pc;
 
 // TODO Need to be able to derive these from two Pc instances
@@ -11,7 +11,7 @@ use crate::note::pitch_class::Pc;
 ///
 /// Technically, in musical set theory, this is called an unordered Pitch Interval Class.
 /// We're being a bit loose with names here for brevity.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IntervalClass {
     /// Unison, Octave
     Ic0,
@@ -39,9 +39,23 @@ pub enum IntervalClass {
     Ic11,
 }
 
+/// Displays the most common interval of the class, abbreviated, e.g. "m3" for [IntervalClass::Ic3].
 impl Display for IntervalClass {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", Into::<IntervalClass>::into(*self))
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            IntervalClass::Ic0 => "P1",
+            IntervalClass::Ic1 => "m2",
+            IntervalClass::Ic2 => "M2",
+            IntervalClass::Ic3 => "m3",
+            IntervalClass::Ic4 => "M3",
+            IntervalClass::Ic5 => "P4",
+            IntervalClass::Ic6 => "TT",
+            IntervalClass::Ic7 => "P5",
+            IntervalClass::Ic8 => "m6",
+            IntervalClass::Ic9 => "M6",
+            IntervalClass::Ic10 => "m7",
+            IntervalClass::Ic11 => "M7",
+        })
     }
 }
 
@@ -0,0 +1,305 @@
+use crate::prelude::*;
+use crate::error::MusicSemanticsError;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note::spelling::{Accidental, Letter, Spelling};
+use crate::note_collections::chord_name::naming_heuristics::infer_chord_quality;
+use crate::note_collections::pc_set::PcSet;
+use crate::note_collections::spelling::{key_signature_notes, spell_pc_set, HasSpelling};
+use crate::note_collections::weighted_pc_set::WeightedPcSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyMode {
+    Major,
+    Minor,
+}
+
+/// A major or minor key, used as context for spelling notes that
+/// come without any, such as those read from MIDI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub tonic: Note,
+    pub mode: KeyMode,
+}
+
+impl Key {
+    /// Errors if the key's signature would need more than seven sharps or flats, e.g. G# major.
+    pub fn new(tonic: Note, mode: KeyMode) -> Result<Self, MusicSemanticsError> {
+        let key = Self { tonic, mode };
+        if key.fifths().abs() > 7 {
+            return Err(MusicSemanticsError::TheoreticalKey(tonic));
+        }
+        Ok(key)
+    }
+
+    /// The number of sharps in the key signature, or of flats if negative.
+    pub fn fifths(&self) -> i8 {
+        let spelling = Spelling::from(&self.tonic);
+        let letter = match spelling.letter {
+            Letter::F => -1,
+            Letter::C => 0,
+            Letter::G => 1,
+            Letter::D => 2,
+            Letter::A => 3,
+            Letter::E => 4,
+            Letter::B => 5,
+        };
+        let acc = match spelling.acc {
+            Accidental::Natural => 0,
+            Accidental::Sharp => 7,
+            Accidental::Flat => -7,
+            Accidental::DoubleSharp => 14,
+            Accidental::DoubleFlat => -14,
+        };
+        let mode = match self.mode {
+            KeyMode::Major => 0,
+            // The relative major is a minor third up, three fifths flatwards.
+            KeyMode::Minor => -3,
+        };
+        letter + acc + mode
+    }
+
+    /// The seven notes of the key's natural scale, starting from the tonic.
+    pub fn notes(&self) -> Vec<Note> {
+        let tonic = Spelling::from(&self.tonic).letter;
+        let mut notes = key_signature_notes(self.fifths());
+        notes.sort_by_key(|note| tonic.diatonic_distance_up(&Spelling::from(note).letter));
+        notes
+    }
+
+    /// Spells a [Pc] as it would most likely be written in the key.
+    ///
+    /// Notes of the key are spelled as in its signature, and the raised sixth and seventh
+    /// of a minor key are spelled as sharpened degrees, e.g. G# in A minor.
+    /// Other notes are spelled as lowered degrees, e.g. Ab rather than G# in C major,
+    /// unless that would take a double flat, e.g. A rather than Bbb in Gb major.
+    pub fn spell_pc(&self, pc: &Pc) -> Note {
+        let notes = self.notes();
+        if let Some(note) = notes.iter().find(|note| Pc::from(*note) == *pc) {
+            return *note;
+        }
+        if self.mode == KeyMode::Minor {
+            let mut raised = notes[5..].iter().filter_map(|note| altered(note, true));
+            if let Some(note) = raised.find(|note| Pc::from(note) == *pc) {
+                return note;
+            }
+        }
+        // Notes outside a diatonic scale always lie a half step from the notes on either side.
+        let above = notes.iter().find(|note| Pc::from(*note) == pc.next());
+        let below = notes.iter().find(|note| Pc::from(*note) == pc.previous());
+        above.and_then(|note| altered(note, false))
+            .or_else(|| below.and_then(|note| altered(note, true)))
+            .unwrap_or(pc.notes()[0])
+    }
+}
+
+/// Krumhansl and Kessler's ratings of how well each pitch class, by semitones above the tonic,
+/// fits the context of a major key. See [Key::detect].
+const MAJOR_PROFILE: [f64; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+/// As [MAJOR_PROFILE], for a minor key.
+const MINOR_PROFILE: [f64; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+impl Key {
+    /// Estimates the key of some music from how much each pitch class sounds in it,
+    /// e.g. the total duration of its notes, indexed by [Pc] from C.
+    ///
+    /// This is the Krumhansl-Schmuckler algorithm: the key whose profile of pitch classes
+    /// correlates best with the weights wins, preferring major keys, then lower tonics, in a tie.
+    /// The tonic is spelled with as few sharps or flats as possible, e.g. Db rather than C# major.
+    /// Returns `None` if the weights are all the same, e.g. for silence.
+    pub fn detect(weights: &[f64; 12]) -> Option<Self> {
+        let mean = |values: &[f64; 12]| values.iter().sum::<f64>() / 12.0;
+        let weight_mean = mean(weights);
+        if weights.iter().all(|weight| *weight == weight_mean) {
+            return None;
+        }
+        let mut best: Option<(f64, usize, KeyMode)> = None;
+        for (mode, profile) in [(KeyMode::Major, &MAJOR_PROFILE), (KeyMode::Minor, &MINOR_PROFILE)] {
+            let profile_mean = mean(profile);
+            let profile_variance: f64 = profile.iter().map(|p| (p - profile_mean) * (p - profile_mean)).sum();
+            for tonic in 0..12 {
+                let covariance: f64 = (0..12)
+                    .map(|i| (weights[(tonic + i) % 12] - weight_mean) * (profile[i] - profile_mean))
+                    .sum();
+                // The variance of the weights is the same for every key, so this orders
+                // the keys as their correlations would, without taking square roots.
+                let score = if covariance < 0.0 { -1.0 } else { 1.0 } * covariance * covariance / profile_variance;
+                if best.is_none_or(|(best, _, _)| score > best) {
+                    best = Some((score, tonic, mode));
+                }
+            }
+        }
+        let (_, tonic, mode) = best?;
+        Pc::from(&(tonic as i32)).notes()
+            .into_iter()
+            .filter_map(|note| Key::new(note, mode).ok())
+            .min_by_key(|key| key.fifths().abs())
+    }
+
+    /// As [Key::detect], with the weights of a [WeightedPcSet].
+    pub fn detect_weighted(pcs: &WeightedPcSet) -> Option<Self> {
+        Self::detect(pcs.weights())
+    }
+}
+
+/// A note raised or lowered by a half step, keeping its letter,
+/// as long as that doesn't take a double accidental.
+fn altered(note: &Note, raise: bool) -> Option<Note> {
+    let acc = match (Spelling::from(note).acc, raise) {
+        (Accidental::Natural, true) => Accidental::Sharp,
+        (Accidental::Natural, false) => Accidental::Flat,
+        (Accidental::Flat, true) | (Accidental::Sharp, false) => Accidental::Natural,
+        _ => return None,
+    };
+    Note::try_from(Spelling::new(Spelling::from(note).letter, acc)).ok()
+}
+
+impl Pitch {
+    /// Produce a pitch from a MIDI note value, spelled as it would be in a [Key].
+    /// See [Key::spell_pc].
+    pub fn from_midi_in_key(midi_note_value: u8, key: &Key) -> Result<Self, MusicSemanticsError> {
+        let pitch = Self::from_midi(midi_note_value)?;
+        pitch.spelled_as_in(&vec![key.spell_pc(&Pc::from(&pitch.note))])
+    }
+}
+
+/// Spells a stream of MIDI notes, such as one read from a MIDI file, in a [Key].
+/// Each element of `onsets` holds the MIDI notes struck together at one point in time.
+///
+/// Wherever at least three pitch classes are struck together and can be named as a chord,
+/// they're spelled as that chord, by [spell_pc_set] from a root spelled in the key.
+/// The root is taken to be the note with the most of a third, fifth, and seventh above it,
+/// preferring lower notes, so that e.g. E G# B is spelled with a G# in C major.
+/// The chord stays in effect for the notes that follow until the next one,
+/// so a melody outlining it is spelled the same way.
+/// Any other notes are spelled by [Pitch::from_midi_in_key].
+pub fn spell_midi_in_key(
+    onsets: &[Vec<u8>],
+    key: &Key,
+) -> Result<Vec<Vec<Pitch>>, MusicSemanticsError> {
+    let mut chord: Option<Vec<Note>> = None;
+    onsets.iter()
+        .map(|onset| {
+            let mut midi_notes = onset.clone();
+            midi_notes.sort();
+            if let Some(notes) = chord_spelling(&midi_notes, key)? {
+                chord = Some(notes);
+            }
+            midi_notes.iter()
+                .map(|midi_note| match &chord {
+                    Some(notes) if notes.iter().any(|note| Pc::from(note) == Pc::from(midi_note)) => {
+                        Pitch::from_midi(*midi_note)?.spelled_as_in(notes)
+                    },
+                    _ => Pitch::from_midi_in_key(*midi_note, key),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The notes of the chord formed by some MIDI notes, spelled from its root, if they form one.
+pub(crate) fn chord_spelling(midi_notes: &[u8], key: &Key) -> Result<Option<Vec<Note>>, MusicSemanticsError> {
+    let mut pcs: Vec<Pc> = midi_notes.iter().map(Pc::from).collect();
+    // Keep the lowest instance of each pitch class, so the bass is tried as the root first.
+    let mut seen = HashSet::new();
+    pcs.retain(|pc| seen.insert(*pc));
+    // Intervals are too ambiguous to spell as chords.
+    if pcs.len() < 3 {
+        return Ok(None);
+    }
+    let intervals_above = |root: &Pc| -> HashSet<Pc> {
+        pcs.iter().map(|pc| Pc::from(&root.distance_up_to(pc))).collect()
+    };
+    let score = |intervals: &HashSet<Pc>| {
+        let third = intervals.contains(&Pc::Pc3) || intervals.contains(&Pc::Pc4);
+        let fifth = intervals.contains(&Pc::Pc7)
+            || (intervals.contains(&Pc::Pc3) && intervals.contains(&Pc::Pc6))
+            || (intervals.contains(&Pc::Pc4) && intervals.contains(&Pc::Pc8));
+        let seventh = intervals.contains(&Pc::Pc10) || intervals.contains(&Pc::Pc11);
+        if third {
+            1 + usize::from(fifth) + usize::from(seventh)
+        } else {
+            0
+        }
+    };
+    let mut root: Option<(&Pc, HashSet<Pc>, usize)> = None;
+    for pc in &pcs {
+        let intervals = intervals_above(pc);
+        let pc_score = score(&intervals);
+        let named = matches!(infer_chord_quality(&intervals), Some((_, Some(_))));
+        if named && root.as_ref().is_none_or(|(_, _, best)| pc_score > *best) {
+            root = Some((pc, intervals, pc_score));
+        }
+    }
+    let Some((root, intervals, _)) = root else {
+        return Ok(None);
+    };
+    let intervals = PcSet::new(intervals.into_iter().collect());
+    spell_pc_set(&key.spell_pc(root), &intervals).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(pitches: &[Pitch]) -> Vec<String> {
+        pitches.iter().map(|pitch| pitch.to_string()).collect()
+    }
+
+    #[test]
+    fn spelling_in_keys() {
+        let c_major = Key::new(Note::C, KeyMode::Major).unwrap();
+        let d_minor = Key::new(Note::D, KeyMode::Minor).unwrap();
+        let e_major = Key::new(Note::E, KeyMode::Major).unwrap();
+        assert_eq!(d_minor.fifths(), -1);
+        assert_eq!(e_major.notes(), vec![Note::E, Note::Fis, Note::Gis, Note::A, Note::B, Note::Cis, Note::Dis]);
+        assert!(Key::new(Note::Gis, KeyMode::Major).is_err());
+
+        let spell = |key: &Key, midi_note: u8| Pitch::from_midi_in_key(midi_note, key).unwrap().note;
+        assert_eq!(spell(&c_major, 68), Note::Aes);
+        assert_eq!(spell(&d_minor, 61), Note::Cis);
+        assert_eq!(spell(&d_minor, 70), Note::Bes);
+        assert_eq!(spell(&e_major, 70), Note::Bes);
+        assert_eq!(spell(&e_major, 63), Note::Dis);
+        // A, rather than Bbb.
+        assert_eq!(spell(&Key::new(Note::Ges, KeyMode::Major).unwrap(), 69), Note::A);
+
+        // A secondary dominant, then a melody outlining it, then a borrowed chord.
+        let spelled = spell_midi_in_key(&[
+            vec![64, 56, 59],
+            vec![68],
+            vec![56, 60, 63],
+            vec![68],
+        ], &c_major).unwrap();
+        assert_eq!(names(&spelled[0]), vec!["G#3", "B3", "E4"]);
+        assert_eq!(names(&spelled[1]), vec!["G#4"]);
+        assert_eq!(names(&spelled[2]), vec!["Ab3", "C4", "Eb4"]);
+        assert_eq!(names(&spelled[3]), vec!["Ab4"]);
+        // A first inversion D7 in C major, with the root in the middle.
+        let spelled = spell_midi_in_key(&[vec![54, 60, 62, 69]], &c_major).unwrap();
+        assert_eq!(names(&spelled[0]), vec!["F#3", "C4", "D4", "A4"]);
+    }
+
+    #[test]
+    fn key_detection() {
+        let weights = |pcs: &[(usize, f64)]| {
+            let mut weights = [0.0; 12];
+            for (pc, weight) in pcs {
+                weights[*pc] = *weight;
+            }
+            weights
+        };
+        // A C major scale, with the tonic triad held longest.
+        let c_major = weights(&[(0, 4.0), (2, 1.0), (4, 3.0), (5, 1.0), (7, 3.0), (9, 1.0), (11, 1.0)]);
+        assert_eq!(Key::detect(&c_major), Key::new(Note::C, KeyMode::Major).ok());
+        // The same notes, centered on A, with a raised seventh.
+        let a_minor = weights(&[(9, 4.0), (11, 1.0), (0, 3.0), (2, 1.0), (4, 3.0), (5, 1.0), (8, 1.0)]);
+        assert_eq!(Key::detect(&a_minor), Key::new(Note::A, KeyMode::Minor).ok());
+        assert_eq!(Key::detect_weighted(&WeightedPcSet::from_weights(a_minor)), Key::new(Note::A, KeyMode::Minor).ok());
+        // Spelled as Db, rather than C#, major.
+        let d_flat = weights(&[(1, 4.0), (3, 1.0), (5, 3.0), (6, 1.0), (8, 3.0), (10, 1.0), (0, 1.0)]);
+        assert_eq!(Key::detect(&d_flat), Key::new(Note::Des, KeyMode::Major).ok());
+        assert_eq!(Key::detect(&[0.0; 12]), None);
+    }
+}
@@ -1,21 +1,42 @@
-use std::collections::{HashMap, HashSet};
-use std::hash::{Hash, Hasher};
-use std::ops::Deref;
+use crate::prelude::*;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
 use crate::note::note::Note;
 use crate::note::pitch_class::Pc;
 
+pub mod analysis;
+pub mod applied_chord;
+pub mod batch;
 pub mod chord_name;
+pub mod chord_scale;
+pub mod chord_tones;
 pub mod octave_partition;
 pub mod pc_set;
+pub mod sequence;
 pub mod spelling;
 pub mod voicing;
+pub mod voicing_spread;
 pub mod geometry;
+pub mod harmonic_function;
+pub mod interval;
 pub mod interval_class;
+pub mod key;
+pub mod modal_interchange;
+pub mod weighted_pc_set;
 
 pub use pc_set::PcSet;
+pub use analysis::AnalysisReport;
+pub use applied_chord::{AppliedChord, AppliedFunction, Resolution};
+pub use batch::{analyze_corpus, analyze_corpus_with, CorpusStatistics};
+pub use harmonic_function::{FunctionConventions, HarmonicFunction};
+pub use interval::{Interval, IntervalQuality};
 pub use interval_class::IntervalClass;
+pub use key::{spell_midi_in_key, Key, KeyMode};
 pub use octave_partition::OctavePartition;
-pub use voicing::{StackedIntervals, Voicing};
+pub use sequence::MelodicSequence;
+pub use voicing::{enumerate_voicings, StackedIntervals, Voicing};
+pub use voicing_spread::{Register, SpreadReport, SpreadViolation};
+pub use weighted_pc_set::WeightedPcSet;
 use crate::error::MusicSemanticsError;
 use crate::note_collections::geometry::symmetry::transpositional::TranspositionalSymmetry;
 
@@ -89,7 +110,7 @@ impl NoteSet {
         for (i, note) in self.iter().enumerate() {
             indexed_by_note.insert(
                 note.clone(),
-                symmetries.remove(&pcs[i]).unwrap(),
+                symmetries.remove(&pcs[i]).unwrap_or_default(),
             );
         }
         indexed_by_note
@@ -121,6 +142,36 @@ impl Deref for NoteSet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::str::FromStr;
+    use music_test_vectors::{CHORDS, ROOTS, SCALES};
+    use crate::note_collections::chord_name::ChordName;
+    use crate::note_collections::chord_name::corpus::find_named_scale;
+
+    fn spellings(pcs: &PcSet) -> Vec<Vec<String>> {
+        ROOTS.iter()
+            .map(|root| pcs.try_spell(&Note::from_str(root).unwrap()).unwrap()
+                .iter()
+                .map(|note| note.to_string())
+                .collect())
+            .collect()
+    }
+
+    // Results shared with musical-combinatorics, so the two crates agree.
+    #[test]
+    fn shared_test_vectors() {
+        for vector in CHORDS {
+            let pcs = PcSet::from(vector.pcs.to_vec());
+            let notes = NoteSet::new(pcs.try_spell(&Note::C).unwrap(), Some(&Note::C));
+            let name = ChordName::from_note_set(&notes).map(|name| name.symbol(None));
+            assert_eq!(name.as_deref(), vector.name, "{:?}", vector.pcs);
+            assert_eq!(spellings(&pcs), vector.spellings.to_vec(), "{:?}", vector.pcs);
+        }
+        for vector in SCALES {
+            let pcs = PcSet::from(vector.pcs.to_vec());
+            assert_eq!(find_named_scale(&pcs).map(|scale| scale.name), vector.name, "{:?}", vector.pcs);
+            assert_eq!(spellings(&pcs), vector.spellings.to_vec(), "{:?}", vector.pcs);
+        }
+    }
 
     #[test]
     fn note_set_constructor() {
@@ -134,6 +185,12 @@ mod tests {
         let notes = NoteSet::new(vec![Note::D, Note::Cis, Note::C], Some(&Note::Cis));
         let should_be = NoteSet(vec![Note::Cis, Note::D, Note::C]);
         assert_eq!(notes, should_be);
+        // Degenerate collections are fine too.
+        let single = NoteSet::new(vec![Note::E, Note::E], None);
+        assert_eq!(single, NoteSet(vec![Note::E]));
+        assert!(single.find_transpositional_symmetries()[&Note::E].is_empty());
+        assert!(NoteSet::new(vec![], None).find_transpositional_symmetries().is_empty());
+        assert!(PcSet::from(&NoteSet::new(vec![], None)).rotate(1).is_empty());
     }
 
     #[test]
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::chord_name::quality::scale::ScaleMode;
+use crate::note_collections::key::{Key, KeyMode};
+
+/// The pitch classes of a key, in semitones above its tonic. A minor key's raised seventh
+/// is counted within it, so that its dominant isn't borrowed, but its raised sixth isn't.
+pub(crate) fn key_degrees(mode: KeyMode) -> &'static [u8] {
+    match mode {
+        KeyMode::Major => &[0, 2, 4, 5, 7, 9, 11],
+        KeyMode::Minor => &[0, 2, 3, 5, 7, 8, 10, 11],
+    }
+}
+
+/// The modes a major key most often borrows from, most common first.
+const BORROWED_INTO_MAJOR: [ScaleMode; 6] = [
+    ScaleMode::NaturalMinor,
+    ScaleMode::Mixolydian,
+    ScaleMode::Dorian,
+    ScaleMode::Phrygian,
+    ScaleMode::Lydian,
+    ScaleMode::Locrian,
+];
+
+/// The modes a minor key most often borrows from, most common first.
+const BORROWED_INTO_MINOR: [ScaleMode; 6] = [
+    ScaleMode::Dorian,
+    ScaleMode::Major,
+    ScaleMode::Phrygian,
+    ScaleMode::Mixolydian,
+    ScaleMode::Lydian,
+    ScaleMode::Locrian,
+];
+
+impl Key {
+    /// The mode on the same tonic that a chord outside the key is borrowed from, if it's borrowed
+    /// from one, e.g. Fm, Ab, and Bb in C major from C natural minor.
+    ///
+    /// Of the modes a chord lies in, the one it's most often borrowed from is chosen: into a major key,
+    /// natural minor, then Mixolydian, Dorian, Phrygian, Lydian, and Locrian. Into a minor key, Dorian,
+    /// then major, Phrygian, Mixolydian, Lydian, and Locrian. So Bb in C major is from natural minor,
+    /// though C Mixolydian has it too, but C7 is from Mixolydian.
+    ///
+    /// Returns `None` for chords within the key, counting the raised seventh of a minor key
+    /// as within it, and for chords in none of the modes, e.g. secondary dominants.
+    pub fn borrowed_from(&self, chord: &NoteSet) -> Option<ScaleMode> {
+        let tonic = Pc::from(&self.tonic);
+        let degrees: Vec<u8> = chord.iter().map(|note| tonic.distance_up_to(&Pc::from(note))).collect();
+        if degrees.is_empty() || degrees.iter().all(|degree| key_degrees(self.mode).contains(degree)) {
+            return None;
+        }
+        let modes = match self.mode {
+            KeyMode::Major => BORROWED_INTO_MAJOR,
+            KeyMode::Minor => BORROWED_INTO_MINOR,
+        };
+        modes.into_iter().find(|mode| {
+            let pcs = mode.pcs();
+            degrees.iter().all(|degree| pcs.contains(&Pc::from(degree)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::note::Note;
+
+    #[test]
+    fn borrowed_chords() {
+        let borrowed = |key: &Key, notes: &[Note]| key.borrowed_from(&NoteSet::starting_from_first_note(notes.to_vec()));
+        let c_major = Key::new(Note::C, KeyMode::Major).unwrap();
+        assert_eq!(borrowed(&c_major, &[Note::F, Note::Aes, Note::C]), Some(ScaleMode::NaturalMinor));
+        assert_eq!(borrowed(&c_major, &[Note::Aes, Note::C, Note::Ees]), Some(ScaleMode::NaturalMinor));
+        assert_eq!(borrowed(&c_major, &[Note::Bes, Note::D, Note::F]), Some(ScaleMode::NaturalMinor));
+        assert_eq!(borrowed(&c_major, &[Note::G, Note::Bes, Note::D]), Some(ScaleMode::NaturalMinor));
+        assert_eq!(borrowed(&c_major, &[Note::C, Note::E, Note::G, Note::Bes]), Some(ScaleMode::Mixolydian));
+        assert_eq!(borrowed(&c_major, &[Note::F, Note::A, Note::C, Note::Ees]), Some(ScaleMode::Dorian));
+        assert_eq!(borrowed(&c_major, &[Note::Des, Note::F, Note::Aes]), Some(ScaleMode::Phrygian));
+        assert_eq!(borrowed(&c_major, &[Note::D, Note::Fis, Note::A]), Some(ScaleMode::Lydian));
+        // Within the key, or in none of its modes.
+        assert_eq!(borrowed(&c_major, &[Note::F, Note::A, Note::C]), None);
+        assert_eq!(borrowed(&c_major, &[Note::E, Note::Gis, Note::B]), None);
+
+        let a_minor = Key::new(Note::A, KeyMode::Minor).unwrap();
+        // A Picardy third, and the Dorian IV.
+        assert_eq!(borrowed(&a_minor, &[Note::A, Note::Cis, Note::E]), Some(ScaleMode::Major));
+        assert_eq!(borrowed(&a_minor, &[Note::D, Note::Fis, Note::A]), Some(ScaleMode::Dorian));
+        assert_eq!(borrowed(&a_minor, &[Note::Bes, Note::D, Note::F]), Some(ScaleMode::Phrygian));
+        assert_eq!(borrowed(&a_minor, &[Note::E, Note::Gis, Note::B]), None);
+    }
+}
@@ -1,4 +1,5 @@
-use std::ops::Deref;
+use crate::prelude::*;
+use core::ops::Deref;
 use crate::note_collections::pc_set::PcSet;
 use crate::note::pitch_class::Pc;
 use crate::error::MusicSemanticsError;
@@ -1,9 +1,9 @@
 use crate::note::pitch_class::Pc;
-use std::collections::HashSet;
-use std::ops::Deref;
+use crate::prelude::*;
+use core::ops::Deref;
 use crate::error::MusicSemanticsError;
 use crate::note::note::Note;
-use crate::note_collections::geometry::symmetry::transpositional::{find_transpositional_symmetries, TranspositionalSymmetryMap};
+use crate::note_collections::geometry::symmetry::transpositional::{find_transpositional_symmetries, Modes, TranspositionalSymmetryMap};
 use crate::note_collections::NoteSet;
 use crate::note_collections::spelling::spell_pc_set;
 
@@ -33,7 +33,7 @@ pub fn zeroed_pcs(pcs: &[Pc]) -> Vec<Pc> {
 }
 
 /// Represents a set of pitch-classes.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PcSet(Vec<Pc>);
 
 impl PcSet {
@@ -78,6 +78,52 @@ impl PcSet {
         Self(zeroed_pcs(&copy))
     }
 
+    /// The most compact rotation of the set, i.e. the one spanning the fewest semitones,
+    /// with ties going to whichever has its notes packed lowest, e.g. [0, 4, 7] for any major triad.
+    ///
+    /// Every mode and transposition of a set shares its normal form, so the normal form
+    /// serves as a key for caching and comparing sets regardless of mode or transposition.
+    /// Unlike the prime form of set theory, the normal form doesn't also consider the set's inversion,
+    /// so major and minor triads have different normal forms, [0, 4, 7] and [0, 3, 7].
+    pub fn normal_form(&self) -> Self {
+        let pcs = Self::new(self.0.clone());
+        (0..pcs.len())
+            .map(|i| pcs.rotate(i as isize))
+            .min_by_key(|rotation| rotation.iter().rev().map(u8::from).collect::<Vec<u8>>())
+            .unwrap_or(pcs)
+    }
+
+    /// Whether `other` is a mode of self, and if so, which one, counting self as mode 0,
+    /// e.g. Dorian is mode 1 of Ionian. Both sets are compared as by [PcSet::new],
+    /// sorted and zeroed, whatever [Pc] they start from. A symmetrical set gives its first matching mode.
+    pub fn eq_up_to_mode(&self, other: &PcSet) -> Option<usize> {
+        let other = Self::new(other.0.clone());
+        Self::new(self.0.clone()).is_mode(&other)
+    }
+
+    /// Whether `other` holds the same pitch classes as self, once transposed up some number
+    /// of semitones, and if so, the fewest semitones it takes, e.g. 2 from [0, 4, 7] to [2, 6, 9].
+    /// Unlike [PcSet::eq_up_to_mode], the sets are compared as they are, without being zeroed.
+    pub fn eq_up_to_transposition(&self, other: &PcSet) -> Option<u8> {
+        let other: HashSet<Pc> = other.into();
+        (0..12u8).find(|semitones| {
+            let transposed: HashSet<Pc> = self.iter()
+                .map(|pc| Pc::from(&(u8::from(pc) + semitones)))
+                .collect();
+            transposed == other
+        })
+    }
+
+    /// The pitch classes missing from the set, in order, e.g. [1, 3, 6, 8, 10] for [0, 2, 4, 5, 7, 9, 11].
+    /// As with [PcSet::eq_up_to_transposition], the set is taken as it is, without being zeroed,
+    /// so the complement keeps its place relative to the set.
+    pub fn complement(&self) -> Self {
+        Self((0..12u8)
+            .map(|pc| Pc::from(&pc))
+            .filter(|pc| !self.contains(pc))
+            .collect())
+    }
+
     /// Returns a `HashMap` of all the transpositional symmetries
     /// that self might have.
     /// For more detailss, see
@@ -96,12 +142,7 @@ impl PcSet {
         if pcs_len != other.len() {
             return false;
         }
-        if pcs_len == 1 {
-            return true;
-        }
-        let other = PcSet::new(other.clone());
-        (0..pcs_len)
-            .any(|i| other.rotate(isize::try_from(i).unwrap()) == *self)
+        self.normal_form() == PcSet::from(other).normal_form()
     }
 
     /// Attempt to spell a [PcSet] using this library's provided spelling function,
@@ -241,4 +282,44 @@ mod tests {
         let pc_set2 = vec![Pc0, Pc3, Pc9];
         assert!(!pc_set.is_transposed_version_of(&pc_set2));
     }
+
+    #[test]
+    fn normal_forms() {
+        let major = pcs!(0, 4, 7);
+        assert_eq!(pcs!(7, 11, 2).normal_form(), major);
+        assert_eq!(major.rotate(2).normal_form(), major);
+        assert_eq!(pcs!(0, 3, 7).normal_form(), pcs!(0, 3, 7));
+        // A dominant seventh chord is most compact from its third up to its root, B D F G.
+        assert_eq!(pcs!(11, 2, 5, 7).normal_form(), pcs!(0, 3, 6, 8));
+        assert!(PcSet::new(vec![]).normal_form().is_empty());
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(pcs!(2, 5, 9).normal_form()));
+        assert!(!seen.insert(pcs!(9, 0, 4).normal_form()));
+    }
+
+    #[test]
+    fn equality_up_to_mode_and_transposition() {
+        let ionian = pcs!(0, 2, 4, 5, 7, 9, 11);
+        let dorian = pcs!(0, 2, 3, 5, 7, 9, 10);
+        assert_eq!(ionian.eq_up_to_mode(&dorian), Some(1));
+        assert_eq!(ionian.eq_up_to_mode(&pcs!(1, 3, 4, 6, 8, 10, 11)), Some(1));
+        assert_eq!(ionian.eq_up_to_mode(&ionian), Some(0));
+        assert_eq!(ionian.eq_up_to_mode(&pcs!(0, 2, 3, 5, 7, 9, 11)), None);
+        // Every mode of a whole tone scale is the first.
+        assert_eq!(pcs!(0, 2, 4, 6, 8, 10).eq_up_to_mode(&pcs!(0, 2, 4, 6, 8, 10)), Some(0));
+
+        assert_eq!(pcs!(0, 4, 7).eq_up_to_transposition(&pcs!(2, 6, 9)), Some(2));
+        assert_eq!(pcs!(2, 6, 9).eq_up_to_transposition(&pcs!(0, 4, 7)), Some(10));
+        assert_eq!(pcs!(0, 4, 7).eq_up_to_transposition(&pcs!(0, 3, 7)), None);
+        assert_eq!(pcs!(0, 4, 8).eq_up_to_transposition(&pcs!(4, 8, 0)), Some(0));
+    }
+
+    #[test]
+    fn complements() {
+        assert_eq!(pcs!(0, 2, 4, 5, 7, 9, 11).complement(), pcs!(1, 3, 6, 8, 10));
+        assert_eq!(pcs!(1, 3, 6, 8, 10).complement(), pcs!(0, 2, 4, 5, 7, 9, 11));
+        assert_eq!(PcSet::new(vec![]).complement().len(), 12);
+        assert!(pcs!(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11).complement().is_empty());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+use crate::prelude::*;
+use crate::error::MusicSemanticsError;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+
+/// A melodic sequence, i.e. a small group of scale steps that is played from
+/// each note of a scale in turn, as a way of practicing the scale.
+/// The examples below are in C major.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MelodicSequence {
+    /// Each note followed by the note a diatonic third above it, e.g. C E, D F, E G.
+    Thirds,
+    /// Each note followed by the note a diatonic fourth above it, e.g. C F, D G, E A.
+    Fourths,
+    /// Each note approached from the notes a step above and below it, e.g. E C D, F D E.
+    Enclosures,
+    /// A group of scale degrees, counted up from 1, played from each note,
+    /// e.g. 1-2-3-5 plays C D E G, D E F A, E F G B.
+    Digital(Vec<u8>),
+}
+
+impl MelodicSequence {
+    /// The scale steps, relative to the note a group is played from, of each note of a group.
+    pub fn offsets(&self) -> Vec<isize> {
+        match self {
+            MelodicSequence::Thirds => vec![0, 2],
+            MelodicSequence::Fourths => vec![0, 3],
+            MelodicSequence::Enclosures => vec![1, -1, 0],
+            MelodicSequence::Digital(degrees) => degrees.iter()
+                .map(|degree| isize::from(*degree) - 1)
+                .collect(),
+        }
+    }
+
+    /// Plays the sequence over `scale`, given as its notes in ascending order,
+    /// e.g. pitches, or the sounded notes of a fretboard scale shape.
+    /// A group is played from every note for which the whole group lies within `scale`,
+    /// so e.g. enclosures start from the second note.
+    pub fn apply<T: Clone>(&self, scale: &[T]) -> Vec<T> {
+        let offsets = self.offsets();
+        let (Some(lowest), Some(highest)) = (offsets.iter().min(), offsets.iter().max()) else {
+            return vec![];
+        };
+        let len = scale.len() as isize;
+        (0..len)
+            .filter(|i| i + lowest >= 0 && i + highest < len)
+            .flat_map(|i| offsets.iter().map(move |offset| scale[(i + offset) as usize].clone()))
+            .collect()
+    }
+
+    /// Plays the sequence over the pitches of `scale` from `low` up to `high`, inclusive,
+    /// spelled as in `scale`.
+    pub fn over_scale(
+        &self,
+        scale: &[Note],
+        low: &Pitch,
+        high: &Pitch,
+    ) -> Result<Vec<Pitch>, MusicSemanticsError> {
+        if scale.is_empty() {
+            return Err(MusicSemanticsError::EmptySetOfNotes);
+        }
+        let notes = scale.to_vec();
+        let pitches = (low.midi_note..=high.midi_note)
+            .filter(|midi_note| notes.iter().any(|note| Pc::from(note) == Pc::from(midi_note)))
+            .map(|midi_note| Pitch::new_spelled_as_in(midi_note, &notes))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.apply(&pitches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pitch;
+
+    #[test]
+    fn melodic_sequences() {
+        let c_major = [Note::C, Note::D, Note::E, Note::F, Note::G, Note::A, Note::B];
+        let sequence = |sequence: MelodicSequence| sequence
+            .over_scale(&c_major, &pitch!(c, 4), &pitch!(c, 5))
+            .unwrap()
+            .iter()
+            .map(|pitch| pitch.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(sequence(MelodicSequence::Thirds), "C4 E4 D4 F4 E4 G4 F4 A4 G4 B4 A4 C5");
+        assert_eq!(sequence(MelodicSequence::Fourths), "C4 F4 D4 G4 E4 A4 F4 B4 G4 C5");
+        assert_eq!(sequence(MelodicSequence::Enclosures), "E4 C4 D4 F4 D4 E4 G4 E4 F4 A4 F4 G4 B4 G4 A4 C5 A4 B4");
+        assert_eq!(
+            sequence(MelodicSequence::Digital(vec![1, 2, 3, 5])),
+            "C4 D4 E4 G4 D4 E4 F4 A4 E4 F4 G4 B4 F4 G4 A4 C5",
+        );
+        // Descending patterns are fine too.
+        assert_eq!(
+            MelodicSequence::Digital(vec![3, 2, 1]).apply(&[1, 2, 3, 4]),
+            vec![3, 2, 1, 4, 3, 2],
+        );
+        assert!(MelodicSequence::Digital(vec![]).apply(&[1, 2, 3]).is_empty());
+        assert!(MelodicSequence::Thirds.apply(&[1, 2]).is_empty());
+        assert!(MelodicSequence::Thirds.over_scale(&[], &pitch!(c, 4), &pitch!(c, 5)).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::prelude::*;
 use crate::error::MusicSemanticsError;
 /// This module solves the problem of crossing the gap from the
 /// "integer world" of [Pc] to the "alphabetical world" of [Note]s.
@@ -25,8 +26,9 @@ use crate::note_collections::pc_set::PcSet;
 use crate::note::note::*;
 use crate::note::pitch_class::Pc;
 use crate::note::Pitch;
-use crate::note::spelling::Spelling;
+use crate::note::spelling::{Accidental, Letter, Spelling};
 use crate::note_collections::Voicing;
+use crate::note_collections::geometry::symmetry::transpositional::Transpose;
 
 pub trait HasSpelling: Sized {
     fn spelled_as_in(&self, notes: &Vec<Note>) -> Result<Self, MusicSemanticsError>;
@@ -80,6 +82,142 @@ pub fn spell_pc_set(root: &Note, pc_set: &PcSet) -> Result<Vec<Note>, MusicSeman
         .collect())
 }
 
+/// A consistent orthography for an application to spell notes with. Pass the same preference to
+/// [spell_pc_set_with], [transpose_spelled], and [crate::note_collections::chord_name::ChordNameDisplayConfig]
+/// to have every note come out spelled the same way.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpellingPreference {
+    /// Spell each collection from its root, by the heuristics of [spell_pc_set].
+    #[default]
+    Contextual,
+    /// Spell the black keys as flats, and every other note as a natural.
+    PreferFlats,
+    /// Spell the black keys as sharps, and every other note as a natural.
+    PreferSharps,
+    /// Spell each collection from whichever of its root's spellings gives it the fewest accidentals,
+    /// e.g. Db major rather than C# major.
+    MinimizeAccidentals,
+    /// Spell notes as they appear in a key signature, given as its number of sharps,
+    /// or of flats if negative. Notes outside the key take sharps in sharp keys and in C,
+    /// and flats in flat keys.
+    KeySignature(i8),
+}
+
+impl SpellingPreference {
+    /// Spells a single [Pc]. Without a collection for context, [SpellingPreference::Contextual]
+    /// and [SpellingPreference::MinimizeAccidentals] take the first of [Pc::notes].
+    pub fn spell_pc(&self, pc: &Pc) -> Note {
+        let notes = pc.notes();
+        match self {
+            SpellingPreference::PreferFlats => notes.iter()
+                .find(|note| matches!(Spelling::from(*note).acc, Accidental::Natural | Accidental::Flat))
+                .copied()
+                .unwrap_or(notes[0]),
+            SpellingPreference::KeySignature(fifths) => key_signature_notes(*fifths).into_iter()
+                .find(|note| Pc::from(note) == *pc)
+                .unwrap_or_else(|| if *fifths < 0 {
+                    SpellingPreference::PreferFlats.spell_pc(pc)
+                } else {
+                    notes[0]
+                }),
+            _ => notes[0],
+        }
+    }
+
+    /// Respells a single note, e.g. the root of a chord name. [SpellingPreference::Contextual]
+    /// leaves it as it is, and [SpellingPreference::MinimizeAccidentals] only removes
+    /// accidentals that aren't needed, as in B# or Ebb.
+    pub fn spell_note(&self, note: &Note) -> Note {
+        match self {
+            SpellingPreference::Contextual => *note,
+            SpellingPreference::MinimizeAccidentals => {
+                let simplest = Pc::from(note).notes()[0];
+                if Spelling::from(&simplest).acc == Accidental::Natural || Spelling::from(note).acc.is_double() {
+                    simplest
+                } else {
+                    *note
+                }
+            },
+            _ => self.spell_pc(&Pc::from(note)),
+        }
+    }
+}
+
+/// The seven notes of the major key with a given number of sharps, or of flats if negative.
+pub(crate) fn key_signature_notes(fifths: i8) -> Vec<Note> {
+    const SHARPS: [Letter; 7] = [Letter::F, Letter::C, Letter::G, Letter::D, Letter::A, Letter::E, Letter::B];
+    let (order, accidental) = if fifths < 0 {
+        (SHARPS.iter().rev().collect::<Vec<_>>(), Accidental::Flat)
+    } else {
+        (SHARPS.iter().collect::<Vec<_>>(), Accidental::Sharp)
+    };
+    let altered = &order[..usize::from(fifths.unsigned_abs().min(7))];
+    SHARPS.iter()
+        .map(|letter| {
+            let acc = if altered.contains(&letter) { accidental } else { Accidental::Natural };
+            // A single accidental on any letter is always a valid note.
+            Note::try_from(Spelling::new(*letter, acc)).unwrap()
+        })
+        .collect()
+}
+
+fn num_accidentals(notes: &[Note]) -> usize {
+    notes.iter()
+        .map(|note| match Spelling::from(note).acc {
+            Accidental::Natural => 0,
+            Accidental::Sharp | Accidental::Flat => 1,
+            Accidental::DoubleSharp | Accidental::DoubleFlat => 2,
+        })
+        .sum()
+}
+
+/// Spell a [PcSet] from a root [Note] as [spell_pc_set] does, and then
+/// respell it according to a [SpellingPreference]. The root may be respelled too.
+pub fn spell_pc_set_with(
+    root: &Note,
+    pc_set: &PcSet,
+    preference: &SpellingPreference,
+) -> Result<Vec<Note>, MusicSemanticsError> {
+    let spelled = spell_pc_set(root, pc_set)?;
+    match preference {
+        SpellingPreference::Contextual => Ok(spelled),
+        SpellingPreference::MinimizeAccidentals => {
+            let enharmonic = root.enharmonic();
+            if enharmonic == *root {
+                return Ok(spelled);
+            }
+            let alternative = spell_pc_set(&enharmonic, pc_set)?;
+            if num_accidentals(&alternative) < num_accidentals(&spelled) {
+                Ok(alternative)
+            } else {
+                Ok(spelled)
+            }
+        },
+        _ => Ok(spelled.iter().map(|note| preference.spell_note(note)).collect()),
+    }
+}
+
+/// Transposes notes up (or down, if negative) some number of semitones, keeping their order,
+/// and spells them according to a [SpellingPreference]. The first note is taken as the root
+/// of the collection, so that e.g. a transposed chord is spelled as a chord.
+pub fn transpose_spelled(
+    notes: &[Note],
+    semitones: i8,
+    preference: &SpellingPreference,
+) -> Result<Vec<Note>, MusicSemanticsError> {
+    let Some(first) = notes.first() else {
+        return Ok(vec![]);
+    };
+    let root = preference.spell_pc(&Pc::from(first).transpose(semitones));
+    let intervals = PcSet::new(notes.iter()
+        .map(|note| Pc::from(&Pc::from(first).distance_up_to(&Pc::from(note))))
+        .collect());
+    let palette = spell_pc_set_with(&root, &intervals, preference)?;
+    notes.iter()
+        .map(|note| note.transpose(semitones).spelled_as_in(&palette))
+        .collect()
+}
+
 /// A data descriptor for the logical pieces that make up a "rule" for whether or not
 /// one should alter a [Pc] to [Note] spelling from its [default_spelling] to an enharmonic.
 pub struct SpellingRule {
@@ -932,4 +1070,31 @@ mod tests {
             vec![Note::D, Note::Fis, Note::A, Note::Cis],
         );
     }
+
+    #[test]
+    fn spelling_preferences() {
+        let major = PcSet::new(vec![Pc::Pc0, Pc::Pc4, Pc::Pc7]);
+        let spell = |root, preference| spell_pc_set_with(&root, &major, &preference).unwrap();
+        assert_eq!(spell(Note::Cis, SpellingPreference::Contextual), vec![Note::Cis, Note::Eis, Note::Gis]);
+        assert_eq!(spell(Note::Cis, SpellingPreference::MinimizeAccidentals), vec![Note::Des, Note::F, Note::Aes]);
+        assert_eq!(spell(Note::Cis, SpellingPreference::PreferFlats), vec![Note::Des, Note::F, Note::Aes]);
+        assert_eq!(spell(Note::Ees, SpellingPreference::PreferSharps), vec![Note::Dis, Note::G, Note::Ais]);
+        // Eb major, in the key of Bb.
+        assert_eq!(spell(Note::Dis, SpellingPreference::KeySignature(-2)), vec![Note::Ees, Note::G, Note::Bes]);
+        // Outside the key of D, the chromatic Bb is written as A#.
+        assert_eq!(SpellingPreference::KeySignature(2).spell_pc(&Pc::Pc10), Note::Ais);
+        assert_eq!(SpellingPreference::KeySignature(-1).spell_pc(&Pc::Pc10), Note::Bes);
+        assert_eq!(SpellingPreference::MinimizeAccidentals.spell_note(&Note::Bis), Note::C);
+
+        // A C major triad up a half step, in the order given.
+        let c_major = [Note::C, Note::G, Note::E];
+        assert_eq!(
+            transpose_spelled(&c_major, 1, &SpellingPreference::MinimizeAccidentals).unwrap(),
+            vec![Note::Des, Note::Aes, Note::F],
+        );
+        assert_eq!(
+            transpose_spelled(&c_major, 1, &SpellingPreference::PreferSharps).unwrap(),
+            vec![Note::Cis, Note::Gis, Note::F],
+        );
+    }
 }
\ No newline at end of file
@@ -1,10 +1,14 @@
-use std::hash::{Hash, Hasher};
-use std::ops::Deref;
+use crate::prelude::*;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use itertools::Itertools;
 use crate::error::MusicSemanticsError;
 use crate::notation::clef::Clef;
 use crate::note::Note;
+use crate::note::pitch_class::Pc;
 use crate::note_collections::pc_set::PcSet;
 use crate::note_collections::spelling::{HasSpelling, spell_pc_set};
+use crate::note_collections::voicing_spread::SpreadReport;
 use crate::note::pitch::Pitch;
 use crate::note_collections::geometry::symmetry::transpositional::TryTranspose;
 use crate::NoteSet;
@@ -12,11 +16,15 @@ use crate::NoteSet;
 
 /// Returns a vector of increasing midi note values, based on a series of
 /// vertically stacked intervals and a starting pitch.
-fn stack_midi_from_intervals(pitch: &Pitch, intervals: &StackedIntervals) -> Vec<u8> {
+/// Errors if the stack rises past the highest MIDI note.
+fn stack_midi_from_intervals(pitch: &Pitch, intervals: &StackedIntervals) -> Result<Vec<u8>, MusicSemanticsError> {
     let mut midi_notes = vec![pitch.midi_note];
-    intervals.iter()
-        .for_each(|i| midi_notes.push(midi_notes.last().unwrap() + i));
-    midi_notes
+    let mut top = pitch.midi_note;
+    for interval in intervals.iter() {
+        top = top.checked_add(*interval).ok_or(MusicSemanticsError::MidiTooHigh(u8::MAX))?;
+        midi_notes.push(top);
+    }
+    Ok(midi_notes)
 }
 
 /// A collection of [crate::note::Pitch] with no guarantees on its contents, except
@@ -88,12 +96,12 @@ impl Voicing {
 
     /// Given a [Pitch], we can infer the others using a [StackedIntervals] instance.
     pub fn from_intervals(root: &Pitch, intervals: &StackedIntervals) -> Result<Self, MusicSemanticsError> {
-        let midi_notes = stack_midi_from_intervals(root, intervals);
+        let midi_notes = stack_midi_from_intervals(root, intervals)?;
         let pc_set = PcSet::from(&midi_notes);
         let spelling = spell_pc_set(&root.note, &pc_set)?;
         let mut pitches = midi_notes.iter()
-            .map(|m| Pitch::new_spelled_as_in(*m, &spelling).unwrap())
-            .collect::<Vec<_>>();
+            .map(|m| Pitch::new_spelled_as_in(*m, &spelling))
+            .collect::<Result<Vec<_>, _>>()?;
         pitches.sort_by(|a,b| a.partial_cmp(b).unwrap());
         Ok(Self(pitches))
     }
@@ -104,6 +112,18 @@ impl Voicing {
         s.has_wide_intervals()
     }
 
+    /// How well the pitches are spread out for an ensemble, to rank voicings by, e.g.
+    /// those from [enumerate_voicings]. See [SpreadReport].
+    pub fn spread(&self) -> SpreadReport {
+        SpreadReport::new(self)
+    }
+
+    /// See [StackedIntervals::voicing_type].
+    pub fn voicing_type(&self) -> Option<VoicingType> {
+        let s: StackedIntervals = self.into();
+        s.voicing_type()
+    }
+
     /// Tries to return an instance of self moved up/down a number of octaves to optimize
     /// its presentation toward the middle of a given clef.
     /// In very extreme cases, this attempt can fail, but those have to be very contrived
@@ -151,11 +171,8 @@ impl Into<StackedIntervals> for Voicing {
 impl Into<StackedIntervals> for &Voicing {
     fn into(self) -> StackedIntervals {
         StackedIntervals(
-            self.0.iter()
-                .zip(&self.0[1..])
-                .map(|(a,b)| {
-                    b.midi_note - a.midi_note
-                })
+            self.0.windows(2)
+                .map(|pair| pair[1].midi_note - pair[0].midi_note)
                 .collect()
         )
     }
@@ -229,6 +246,108 @@ impl StackedIntervals {
     pub fn has_wide_intervals(&self) -> bool {
         self.iter().any(|interval| *interval >= 12)
     }
+
+    /// Classifies a voicing of three or more distinct pitch-classes by its spacing.
+    /// Voicings that double a pitch-class, or that can't be closed up
+    /// by raising their lowest one or two voices an octave, have no [VoicingType].
+    pub fn voicing_type(&self) -> Option<VoicingType> {
+        let mut offsets = vec![0usize];
+        for interval in self.iter() {
+            offsets.push(offsets.last().unwrap() + usize::from(*interval));
+        }
+        let pcs: HashSet<usize> = offsets.iter().map(|offset| offset % 12).collect();
+        if offsets.len() < 3 || pcs.len() < offsets.len() {
+            return None;
+        }
+        let is_closed = |offsets: &[usize]| offsets[offsets.len() - 1] - offsets[0] < 12;
+        if is_closed(&offsets) {
+            return Some(VoicingType::Closed);
+        }
+        if offsets.len() == 3 {
+            return Some(VoicingType::SpreadTriad);
+        }
+        // Undo the drop, by raising the dropped voices back up an octave.
+        for dropped in 1..=2 {
+            let mut raised: Vec<(usize, bool)> = offsets.iter()
+                .enumerate()
+                .map(|(i, offset)| if i < dropped { (offset + 12, true) } else { (*offset, false) })
+                .collect();
+            raised.sort();
+            let closed: Vec<usize> = raised.iter().map(|(offset, _)| *offset).collect();
+            if !is_closed(&closed) {
+                continue;
+            }
+            // Voices counted down from the top, starting at one.
+            let drops: Vec<usize> = raised.iter()
+                .rev()
+                .enumerate()
+                .filter(|(_, (_, was_dropped))| *was_dropped)
+                .map(|(i, _)| i + 1)
+                .collect();
+            return match drops[..] {
+                [2] => Some(VoicingType::Drop2),
+                [3] => Some(VoicingType::Drop3),
+                [2, 4] => Some(VoicingType::Drop2And4),
+                _ => None,
+            };
+        }
+        None
+    }
+}
+
+/// The common ways of spacing the notes of a chord, as named by arrangers and guitarists.
+/// See [StackedIntervals::voicing_type].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoicingType {
+    /// Every note within an octave of the lowest, e.g. C E G B.
+    Closed,
+    /// A closed voicing with the second voice from the top dropped an octave, e.g. G C B E.
+    Drop2,
+    /// A closed voicing with the third voice from the top dropped an octave, e.g. B C E G.
+    Drop3,
+    /// A closed voicing with the second and fourth voices from the top dropped an octave, e.g. C G E B.
+    Drop2And4,
+    /// A triad spanning more than an octave, e.g. C G E.
+    SpreadTriad,
+}
+
+/// Lazily enumerates every [Voicing] of a collection of pitch-classes that fits
+/// inside the register window `low..=high`.
+///
+/// Each yielded voicing contains every [Pc] of `pc_set` at least once, no two pitches
+/// share a MIDI note, and there are no more than `max_notes` pitches in total.
+/// Voicings are yielded in order of increasing size, and are spelled with the
+/// default spelling of [Pitch::from_midi]. The pitch-classes are taken as absolute
+/// (i.e. [Pc::Pc0] is C), so pass a transposed collection to voice a different root.
+///
+/// The results are meant to be filtered further by an instrument layer, such as
+/// a fretboard or keyboard.
+///
+/// Errors if `pc_set` is empty, or if the window reaches above the highest pitch
+/// [Pitch::from_midi] gives.
+pub fn enumerate_voicings(
+    pc_set: &[Pc],
+    low: &Pitch,
+    high: &Pitch,
+    max_notes: usize,
+) -> Result<impl Iterator<Item=Voicing>, MusicSemanticsError> {
+    let pcs: HashSet<Pc> = pc_set.iter().cloned().collect();
+    if pcs.is_empty() {
+        return Err(MusicSemanticsError::EmptySetOfNotes);
+    }
+    let candidates: Vec<Pitch> = (low.midi_note..=high.midi_note)
+        .filter(|midi_note| pcs.contains(&Pc::from(midi_note)))
+        .map(Pitch::from_midi)
+        .collect::<Result<_, _>>()?;
+    let min_notes = pcs.len();
+    let max_notes = max_notes.min(candidates.len());
+    Ok((min_notes..=max_notes)
+        .flat_map(move |size| candidates.clone().into_iter().combinations(size))
+        .filter(move |pitches| {
+            let covered: HashSet<Pc> = pitches.iter().map(|pitch| Pc::from(&pitch.midi_note)).collect();
+            covered == pcs
+        })
+        .map(Voicing::new))
 }
 
 #[macro_export]
@@ -300,4 +419,66 @@ mod tests {
             v4.normalize_register_to_clef(Clef::Treble).unwrap()
         );
     }
+
+    #[test]
+    fn voicings_in_register_window() {
+        let pcs = [Pc::Pc0, Pc::Pc4, Pc::Pc7];
+        // A single octave window holds exactly one C major triad.
+        let voicings: Vec<Voicing> = enumerate_voicings(
+            &pcs, &pitch!(c, 4), &pitch!(b, 4), 4,
+        ).unwrap().collect();
+        assert_eq!(voicings, vec![voicing!(pitch!(c, 4), pitch!(e, 4), pitch!(g, 4))]);
+        // Two octaves, capped at three notes: one choice of octave per chord tone.
+        let voicings: Vec<Voicing> = enumerate_voicings(
+            &pcs, &pitch!(c, 4), &pitch!(b, 5), 3,
+        ).unwrap().collect();
+        assert_eq!(voicings.len(), 8);
+        assert!(voicings.iter().all(|v| v.len() == 3));
+        // Allowing doublings yields larger voicings afterward.
+        let voicings: Vec<Voicing> = enumerate_voicings(
+            &pcs, &pitch!(c, 4), &pitch!(b, 5), 6,
+        ).unwrap().collect();
+        assert_eq!(voicings.len(), 27);
+        assert!(voicings.windows(2).all(|w| w[0].len() <= w[1].len()));
+        // A window that can't hold every pitch-class yields nothing.
+        assert_eq!(
+            enumerate_voicings(&pcs, &pitch!(c, 4), &pitch!(f, 4), 4).unwrap().count(),
+            0
+        );
+        assert!(enumerate_voicings(&[], &pitch!(c, 4), &pitch!(c, 5), 4).is_err());
+        // A window reaching beyond the highest MIDI pitch errors, rather than leaving out its top.
+        assert!(matches!(
+            enumerate_voicings(&pcs, &pitch!(c, 7), &pitch!(e, 8), 3),
+            Err(MusicSemanticsError::MidiTooHigh(108)),
+        ));
+    }
+
+    #[test]
+    fn empty_and_single_note_voicings() {
+        let empty = Voicing::new(vec![]);
+        let intervals: StackedIntervals = (&empty).into();
+        assert!(intervals.is_empty());
+        assert_eq!(empty.span(), None);
+        let single = voicing!(pitch!(e, 4));
+        assert_eq!(single.span(), Some((pitch!(e, 4), pitch!(e, 4))));
+        assert!(!single.has_wide_intervals());
+        // Stacking past the top of the MIDI range is an error, rather than an overflow.
+        assert!(Voicing::from_intervals(&pitch!(c, 8), &StackedIntervals(vec![200])).is_err());
+    }
+
+    #[test]
+    fn voicing_types() {
+        let voicing_type = |pitches: Vec<Pitch>| Voicing::new(pitches).voicing_type();
+        assert_eq!(voicing_type(vec![pitch!(c, 4), pitch!(e, 4), pitch!(g, 4), pitch!(b, 4)]), Some(VoicingType::Closed));
+        assert_eq!(voicing_type(vec![pitch!(e, 4), pitch!(g, 4), pitch!(b, 4), pitch!(c, 5)]), Some(VoicingType::Closed));
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(g, 3), pitch!(b, 3), pitch!(e, 4)]), Some(VoicingType::Drop2));
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(b, 3), pitch!(e, 4), pitch!(g, 4)]), Some(VoicingType::Drop3));
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(g, 3), pitch!(e, 4), pitch!(b, 4)]), Some(VoicingType::Drop2And4));
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(g, 3), pitch!(e, 4)]), Some(VoicingType::SpreadTriad));
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(e, 3), pitch!(g, 3)]), Some(VoicingType::Closed));
+        // Doublings, dyads, and voicings too wide to close up aren't classified.
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(g, 3), pitch!(c, 4), pitch!(e, 4)]), None);
+        assert_eq!(voicing_type(vec![pitch!(c, 3), pitch!(e, 3)]), None);
+        assert_eq!(voicing_type(vec![pitch!(c, 2), pitch!(e, 4), pitch!(g, 4), pitch!(b, 4)]), None);
+    }
 }
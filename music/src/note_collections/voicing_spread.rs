@@ -0,0 +1,170 @@
+use crate::prelude::*;
+use crate::note::pitch::Pitch;
+use crate::note_collections::interval_class::IntervalClass;
+use crate::note_collections::voicing::Voicing;
+
+/// The lowest MIDI note of C3, below which a pitch is in the [Register::Low] register.
+const LOW_REGISTER_TOP: u8 = 48;
+/// The MIDI note of C5, from which a pitch is in the [Register::High] register.
+const HIGH_REGISTER_BOTTOM: u8 = 72;
+
+/// The lowest MIDI note the lower pitch of an interval can be on before the interval
+/// sounds muddy, as orchestration texts give them, e.g. a minor third no lower than C3.
+/// Unisons and octaves have no such limit.
+pub fn low_interval_limit(interval_class: &IntervalClass) -> Option<u8> {
+    match interval_class {
+        IntervalClass::Ic0 => None,
+        IntervalClass::Ic1 => Some(52),
+        IntervalClass::Ic2 => Some(51),
+        IntervalClass::Ic3 => Some(48),
+        IntervalClass::Ic4 => Some(46),
+        IntervalClass::Ic5 => Some(45),
+        IntervalClass::Ic6 => Some(46),
+        IntervalClass::Ic7 => Some(34),
+        IntervalClass::Ic8 | IntervalClass::Ic9 | IntervalClass::Ic10 | IntervalClass::Ic11 => Some(41),
+    }
+}
+
+/// Where a pitch lies, broadly: below C3, from C3 up to C5, or from C5 up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Register {
+    Low,
+    Middle,
+    High,
+}
+
+impl Register {
+    pub fn of(pitch: &Pitch) -> Self {
+        match pitch.midi_note {
+            midi_note if midi_note < LOW_REGISTER_TOP => Register::Low,
+            midi_note if midi_note < HIGH_REGISTER_BOTTOM => Register::Middle,
+            _ => Register::High,
+        }
+    }
+}
+
+/// The interval between two neighbouring pitches of a [Voicing].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacentInterval {
+    pub lower: Pitch,
+    pub upper: Pitch,
+    pub semitones: u8,
+    pub interval_class: IntervalClass,
+}
+
+/// A way a [Voicing] goes against the guidelines for spacing a chord,
+/// with each interval given by its index from the bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpreadViolation {
+    /// A simple interval with its lower pitch below its [low_interval_limit].
+    BelowLowIntervalLimit(usize),
+    /// A gap of more than an octave above a closer interval,
+    /// where the upper voices should be the closest.
+    GapAboveCloserVoices(usize),
+}
+
+/// How the pitches of a [Voicing] are spread out, against the orchestration guideline
+/// of keeping the wide intervals low and the close ones high, as in the harmonic series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadReport {
+    /// Each interval, from the bottom up.
+    pub intervals: Vec<AdjacentInterval>,
+    /// The number of pitches in each [Register], from the lowest.
+    pub registers: Vec<(Register, usize)>,
+    pub violations: Vec<SpreadViolation>,
+    /// From 0.0 to 1.0, how well spread the voicing is.
+    /// See [SpreadReport::new].
+    pub score: f64,
+}
+
+impl SpreadReport {
+    /// The score is the product of how far the intervals narrow going up, i.e. one less the
+    /// semitones by which each interval is wider than the one below it over the span,
+    /// and the share of the simple intervals that clear their [low_interval_limit].
+    /// A voicing of fewer than two pitches scores 1.0.
+    pub fn new(voicing: &Voicing) -> Self {
+        let intervals: Vec<AdjacentInterval> = voicing.windows(2)
+            .map(|pair| {
+                let semitones = pair[1].midi_note - pair[0].midi_note;
+                AdjacentInterval {
+                    lower: pair[0],
+                    upper: pair[1],
+                    semitones,
+                    interval_class: IntervalClass::from(&semitones),
+                }
+            })
+            .collect();
+        let registers = [Register::Low, Register::Middle, Register::High].into_iter()
+            .map(|register| (register, voicing.iter().filter(|pitch| Register::of(pitch) == register).count()))
+            .collect();
+
+        let mut violations = vec![];
+        let (mut limited, mut muddy) = (0, 0);
+        for (i, interval) in intervals.iter().enumerate() {
+            if let (true, Some(limit)) = (interval.semitones < 12, low_interval_limit(&interval.interval_class)) {
+                limited += 1;
+                if interval.lower.midi_note < limit {
+                    muddy += 1;
+                    violations.push(SpreadViolation::BelowLowIntervalLimit(i));
+                }
+            }
+        }
+        for (i, interval) in intervals.iter().enumerate() {
+            if interval.semitones > 12 && intervals[..i].iter().any(|below| below.semitones < interval.semitones) {
+                violations.push(SpreadViolation::GapAboveCloserVoices(i));
+            }
+        }
+
+        let span = intervals.iter().map(|interval| usize::from(interval.semitones)).sum::<usize>();
+        let widening = intervals.windows(2)
+            .map(|pair| usize::from(pair[1].semitones.saturating_sub(pair[0].semitones)))
+            .sum::<usize>();
+        let narrowing = match span {
+            0 => 1.0,
+            span => 1.0 - widening as f64 / span as f64,
+        };
+        let clearance = match limited {
+            0 => 1.0,
+            limited => 1.0 - muddy as f64 / limited as f64,
+        };
+        Self { intervals, registers, violations, score: narrowing * clearance }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::note::Note;
+    use crate::pitch;
+    use crate::voicing;
+
+    #[test]
+    fn voicing_spreads() {
+        // The harmonic series, from a low C, is as well spread as can be.
+        let harmonics = voicing!(pitch!(c, 2), pitch!(c, 3), pitch!(g, 3), pitch!(c, 4), pitch!(e, 4));
+        let report = harmonics.spread();
+        let semitones: Vec<u8> = report.intervals.iter().map(|interval| interval.semitones).collect();
+        assert_eq!(semitones, vec![12, 7, 5, 4]);
+        assert_eq!(report.intervals[1].interval_class, IntervalClass::Ic7);
+        assert_eq!(report.registers, vec![(Register::Low, 1), (Register::Middle, 4), (Register::High, 0)]);
+        assert!(report.violations.is_empty());
+        assert_eq!(report.score, 1.0);
+
+        // A closed triad down low is muddy.
+        let muddy = voicing!(pitch!(c, 2), pitch!(e, 2), pitch!(g, 2)).spread();
+        assert_eq!(muddy.violations, vec![
+            SpreadViolation::BelowLowIntervalLimit(0),
+            SpreadViolation::BelowLowIntervalLimit(1),
+        ]);
+        assert_eq!(muddy.score, 0.0);
+
+        // The same triad, with its root taken up past a gap of two octaves.
+        let gap = voicing!(pitch!(c, 4), pitch!(e, 4), pitch!(g, 4), pitch!(c, 6)).spread();
+        assert_eq!(gap.violations, vec![SpreadViolation::GapAboveCloserVoices(2)]);
+        assert_eq!(gap.registers[2], (Register::High, 1));
+        assert_eq!(gap.score, 1.0 - 14.0 / 24.0);
+        assert!(gap.score < voicing!(pitch!(c, 4), pitch!(e, 4), pitch!(g, 4), pitch!(c, 5)).spread().score);
+
+        assert_eq!(voicing!(pitch!(c, 4)).spread().score, 1.0);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::prelude::*;
+use crate::note::pitch_class::Pc;
+
+/// A multiset of pitch classes, each weighted by how much it's heard, e.g. by the total
+/// duration of its notes, their velocity, or whether they fall on the beat.
+///
+/// Music is rarely an unweighted set of pitch classes: a held bass note and a grace note
+/// a semitone from it both count once in a [crate::note_collections::PcSet], but not here.
+/// Unlike a [crate::note_collections::PcSet], the pitch classes aren't zeroed, so [Pc::Pc0] is C.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WeightedPcSet([f64; 12]);
+
+impl WeightedPcSet {
+    /// Every pitch class weighing nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The weight of each pitch class, indexed by [Pc] from C.
+    pub fn from_weights(weights: [f64; 12]) -> Self {
+        Self(weights)
+    }
+
+    /// Adds `weight` to that of `pc`, e.g. for another note of it being heard.
+    pub fn add(&mut self, pc: Pc, weight: f64) {
+        self.0[usize::from(u8::from(&pc))] += weight;
+    }
+
+    pub fn weight(&self, pc: &Pc) -> f64 {
+        self.0[usize::from(u8::from(pc))]
+    }
+
+    /// The weight of each pitch class, indexed by [Pc] from C.
+    pub fn weights(&self) -> &[f64; 12] {
+        &self.0
+    }
+
+    pub fn total(&self) -> f64 {
+        self.0.iter().sum()
+    }
+
+    /// Whether no pitch class weighs anything.
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|weight| *weight <= 0.0)
+    }
+
+    /// The pitch classes that weigh anything, from the heaviest, and from the lowest in a tie.
+    pub fn by_weight(&self) -> Vec<Pc> {
+        let mut pcs = self.pcs();
+        pcs.sort_by(|a, b| self.weight(b).total_cmp(&self.weight(a)));
+        pcs
+    }
+
+    /// The pitch classes that weigh anything, from the lowest.
+    pub fn pcs(&self) -> Vec<Pc> {
+        (0..12u8)
+            .filter(|pc| self.0[usize::from(*pc)] > 0.0)
+            .map(|pc| Pc::from(&pc))
+            .collect()
+    }
+
+    /// The pitch classes that are heard as part of the harmony, rather than as passing or
+    /// chromatic tones, from the lowest.
+    ///
+    /// A pitch class is kept if its weight is at least `min_weight` times that of the heaviest,
+    /// and at least half that of either pitch class a semitone away, since a light tone
+    /// a semitone from a heavy one is most likely a chromatic approach to it.
+    pub fn salient_pcs(&self, min_weight: f64) -> Vec<Pc> {
+        let heaviest = self.0.iter().cloned().fold(0.0, f64::max);
+        self.pcs()
+            .into_iter()
+            .filter(|pc| {
+                let weight = self.weight(pc);
+                weight >= min_weight * heaviest
+                    && [Pc::from(&(i32::from(pc) - 1)), Pc::from(&(i32::from(pc) + 1))].iter()
+                        .all(|neighbour| 2.0 * weight >= self.weight(neighbour))
+            })
+            .collect()
+    }
+
+    /// The same weights, with `root` as [Pc::Pc0], e.g. to name a chord from that root.
+    pub fn from_root(&self, root: &Pc) -> Self {
+        let root = usize::from(u8::from(root));
+        Self(core::array::from_fn(|pc| self.0[(root + pc) % 12]))
+    }
+}
+
+impl FromIterator<(Pc, f64)> for WeightedPcSet {
+    fn from_iter<T: IntoIterator<Item = (Pc, f64)>>(iter: T) -> Self {
+        let mut pcs = Self::new();
+        for (pc, weight) in iter {
+            pcs.add(pc, weight);
+        }
+        pcs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::pitch_class::Pc::*;
+
+    #[test]
+    fn weighted_pcs() {
+        // Mostly C major, with a brief F and the C# of a chromatic approach to D.
+        let pcs: WeightedPcSet = [(Pc0, 4.0), (Pc1, 0.5), (Pc2, 1.5), (Pc4, 3.0), (Pc5, 0.5), (Pc7, 2.0), (Pc7, 1.0)]
+            .into_iter()
+            .collect();
+        assert_eq!(pcs.weight(&Pc7), 3.0);
+        assert_eq!(pcs.total(), 12.5);
+        assert_eq!(pcs.pcs(), vec![Pc0, Pc1, Pc2, Pc4, Pc5, Pc7]);
+        assert_eq!(pcs.by_weight(), vec![Pc0, Pc4, Pc7, Pc2, Pc1, Pc5]);
+        assert_eq!(pcs.salient_pcs(0.2), vec![Pc0, Pc2, Pc4, Pc7]);
+        assert_eq!(pcs.salient_pcs(0.5), vec![Pc0, Pc4, Pc7]);
+        assert_eq!(pcs.from_root(&Pc7).weight(&Pc5), 4.0);
+
+        assert!(WeightedPcSet::new().is_empty());
+        assert!(WeightedPcSet::new().salient_pcs(0.2).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use crate::error::MusicSemanticsError;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::key::{chord_spelling, Key, KeyMode};
+use crate::note_collections::{NoteSet, WeightedPcSet};
+
+/// An argument of an [OscMessage].
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+}
+
+/// An Open Sound Control message: an address, e.g. `/music/chord`, and its arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    pub fn new(address: &str, args: Vec<OscArg>) -> Self {
+        Self { address: address.to_string(), args }
+    }
+
+    /// The message as sent in a packet, as by the OSC 1.0 specification.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        push_osc_string(&mut bytes, &self.address);
+        let tags: String = [','].into_iter()
+            .chain(self.args.iter().map(|arg| match arg {
+                OscArg::Int(_) => 'i',
+                OscArg::Float(_) => 'f',
+                OscArg::Str(_) => 's',
+            }))
+            .collect();
+        push_osc_string(&mut bytes, &tags);
+        for arg in &self.args {
+            match arg {
+                OscArg::Int(int) => bytes.extend(int.to_be_bytes()),
+                OscArg::Float(float) => bytes.extend(float.to_be_bytes()),
+                OscArg::Str(string) => push_osc_string(&mut bytes, string),
+            }
+        }
+        bytes
+    }
+}
+
+/// Writes a string ended by a null, padded with more nulls to a multiple of four bytes.
+fn push_osc_string(bytes: &mut Vec<u8>, string: &str) {
+    bytes.extend(string.as_bytes());
+    bytes.extend(std::iter::repeat_n(0, 4 - string.len() % 4));
+}
+
+/// Sends what's being heard, as it's heard, over UDP to e.g. a patch in Max or SuperCollider:
+/// the name of the chord, the estimated key, and the pitch classes of the harmony.
+///
+/// Each call to [OscSender::send] analyses the pitch classes heard so far, weighted as by a
+/// [WeightedPcSet], and sends one message for each result, under the sender's address prefix:
+/// - `/music/chord s`, the name of the chord, if the harmony can be named as one.
+/// - `/music/key s s`, the tonic and `major` or `minor`, see [Key::detect].
+/// - `/music/pcs i...`, the pitch classes of the harmony, from C as 0, see [WeightedPcSet::salient_pcs].
+pub struct OscSender {
+    socket: UdpSocket,
+    target: SocketAddr,
+    prefix: String,
+    min_weight: f64,
+}
+
+impl OscSender {
+    /// Sends to `target` from any free local port.
+    pub fn new<A: ToSocketAddrs>(target: A) -> Result<Self, MusicSemanticsError> {
+        let target = target.to_socket_addrs()
+            .map_err(|e| MusicSemanticsError::OscFailure(e.to_string()))?
+            .next()
+            .ok_or_else(|| MusicSemanticsError::OscFailure("no address to send to".to_string()))?;
+        let local = if target.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local).map_err(|e| MusicSemanticsError::OscFailure(e.to_string()))?;
+        Ok(Self { socket, target, prefix: "/music".to_string(), min_weight: 0.2 })
+    }
+
+    /// The start of the address of every message, `/music` by default.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// How heavy a pitch class must be, relative to the heaviest, to be part of the harmony.
+    /// See [WeightedPcSet::salient_pcs].
+    pub fn min_weight(mut self, min_weight: f64) -> Self {
+        self.min_weight = min_weight;
+        self
+    }
+
+    /// The messages [OscSender::send] would send for `pcs`. Nothing is sent for silence.
+    pub fn messages(&self, pcs: &WeightedPcSet) -> Result<Vec<OscMessage>, MusicSemanticsError> {
+        let Some(key) = Key::detect_weighted(pcs) else {
+            return Ok(vec![]);
+        };
+        let salient = pcs.salient_pcs(self.min_weight);
+        let mut messages = vec![];
+        // The heaviest pitch classes are tried as the root first.
+        let harmony: Vec<u8> = pcs.by_weight().iter()
+            .filter(|pc| salient.contains(pc))
+            .map(u8::from)
+            .collect();
+        if let Some(notes) = chord_spelling(&harmony, &key)? {
+            if let Some(name) = ChordName::from_note_set(&NoteSet::starting_from_first_note(notes)) {
+                messages.push(self.message("chord", vec![OscArg::Str(name.symbol(None))]));
+            }
+        }
+        let mode = match key.mode {
+            KeyMode::Major => "major",
+            KeyMode::Minor => "minor",
+        };
+        messages.push(self.message("key", vec![OscArg::Str(key.tonic.to_string()), OscArg::Str(mode.to_string())]));
+        let pcs = salient.iter().map(|pc| OscArg::Int(i32::from(u8::from(pc)))).collect();
+        messages.push(self.message("pcs", pcs));
+        Ok(messages)
+    }
+
+    /// Sends the analysis of `pcs`, e.g. the pitch classes heard so far, each time another note is heard.
+    pub fn send(&self, pcs: &WeightedPcSet) -> Result<(), MusicSemanticsError> {
+        for message in self.messages(pcs)? {
+            self.socket.send_to(&message.to_bytes(), self.target)
+                .map_err(|e| MusicSemanticsError::OscFailure(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn message(&self, name: &str, args: Vec<OscArg>) -> OscMessage {
+        OscMessage::new(&format!("{}/{}", self.prefix, name), args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::note::pitch_class::Pc::*;
+
+    #[test]
+    fn osc_analysis() {
+        let message = OscMessage::new("/oscillator/4/frequency", vec![OscArg::Float(440.0)]);
+        assert_eq!(message.to_bytes(), [
+            &b"/oscillator/4/frequency\0"[..],
+            &b",f\0\0"[..],
+            &[0x43, 0xDC, 0x00, 0x00][..],
+        ].concat());
+        let message = OscMessage::new("/a", vec![OscArg::Int(-1), OscArg::Str("four".to_string())]);
+        assert_eq!(message.to_bytes(), [&b"/a\0\0,is\0"[..], &[0xFF; 4][..], &b"four\0\0\0\0"[..]].concat());
+
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let sender = OscSender::new(receiver.local_addr().unwrap()).unwrap().prefix("/live/");
+        assert!(sender.messages(&WeightedPcSet::new()).unwrap().is_empty());
+
+        // A Cmaj7 chord, with a passing D.
+        let heard: WeightedPcSet = [(Pc0, 4.0), (Pc4, 3.0), (Pc7, 3.0), (Pc11, 2.0), (Pc2, 0.5)].into_iter().collect();
+        let messages = sender.messages(&heard).unwrap();
+        assert_eq!(messages.iter().map(|message| message.address.as_str()).collect::<Vec<_>>(), vec![
+            "/live/chord", "/live/key", "/live/pcs",
+        ]);
+        assert_eq!(messages[0].args, vec![OscArg::Str("CMaj7".to_string())]);
+        assert_eq!(messages[1].args, vec![OscArg::Str("C".to_string()), OscArg::Str("major".to_string())]);
+        assert_eq!(messages[2].args, vec![OscArg::Int(0), OscArg::Int(4), OscArg::Int(7), OscArg::Int(11)]);
+
+        sender.send(&heard).unwrap();
+        let mut buffer = [0; 256];
+        for message in &messages {
+            let (length, _) = receiver.recv_from(&mut buffer).unwrap();
+            assert_eq!(&buffer[..length], message.to_bytes().as_slice());
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretted_note::SoundedNote;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::meter::Meter;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::progression::Progression;
+use crate::progression::render::{bar_durations, big_beat_durations};
+
+/// The patterns a [BassLine] can be built from, over each chord of a [Progression].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BassStyle {
+    /// The root of each chord, held for the bar.
+    Roots,
+    /// The root on the first big beat of the bar, alternating with the fifth on the rest.
+    RootFifth,
+    /// A note on every beat, walking from the root up through the chord tones.
+    /// The last beat of each bar approaches the next root by a half step.
+    Walking,
+}
+
+/// The lowest and highest pitches an instrument can play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstrumentRange {
+    pub lowest: Pitch,
+    pub highest: Pitch,
+}
+
+impl InstrumentRange {
+    pub fn new(lowest: Pitch, highest: Pitch) -> Self {
+        Self { lowest, highest }
+    }
+
+    /// The range of a fretted instrument, from its lowest open string
+    /// up to its highest string at `max_fret`.
+    pub fn of_fretted<F: FrettedInstrument>(
+        instrument: &F,
+        max_fret: u8,
+    ) -> Result<Self, MusicSemanticsError> {
+        let max_fret = max_fret.min(instrument.max_fret());
+        let lowest = instrument.open_strings().iter()
+            .min_by_key(|pitch| pitch.midi_note)
+            .ok_or(MusicSemanticsError::EmptySetOfNotes)?;
+        let highest = (0..instrument.num_strings())
+            .map(|string| instrument.pitch_at(string, max_fret))
+            .collect::<Result<Vec<Pitch>, MusicSemanticsError>>()?
+            .into_iter()
+            .max_by_key(|pitch| pitch.midi_note)
+            .ok_or(MusicSemanticsError::EmptySetOfNotes)?;
+        Ok(Self::new(*lowest, highest))
+    }
+
+    pub fn contains(&self, pitch: &Pitch) -> bool {
+        self.lowest.midi_note <= pitch.midi_note && pitch.midi_note <= self.highest.midi_note
+    }
+
+    /// The pitch of `note` within the range closest to the MIDI note `target`,
+    /// with ties going to the lower pitch.
+    fn nearest(&self, note: &Note, target: u8) -> Result<Pitch, MusicSemanticsError> {
+        let pc = Pc::from(note);
+        let midi_note = (self.lowest.midi_note..=self.highest.midi_note)
+            .filter(|midi_note| Pc::from(midi_note) == pc)
+            .min_by_key(|midi_note| midi_note.abs_diff(target))
+            .ok_or(MusicSemanticsError::NoteOutOfRange(*note, self.lowest, self.highest))?;
+        Pitch::new_spelled_as_in(midi_note, &vec![*note])
+    }
+}
+
+/// A note of a [BassLine].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BassNote {
+    pub pitch: Pitch,
+    pub duration: Duration,
+    /// Whether the note is tied from the previous one, rather than played again.
+    pub tied: bool,
+}
+
+/// A bass line over a [Progression], one bar for each chord, whatever its [Progression::durations].
+///
+/// Each note is placed in the octave closest to the note before it, so the line moves
+/// as little as possible within its [InstrumentRange]. The first note is placed as low as possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BassLine {
+    pub bars: Vec<Vec<BassNote>>,
+}
+
+impl BassLine {
+    /// Generates a bass line in some [BassStyle], with a bar of `meter` for each chord.
+    ///
+    /// With [BassStyle::Walking], the last bar approaches the first chord,
+    /// as though the progression were repeated.
+    pub fn new(
+        progression: &Progression,
+        meter: &Meter,
+        style: BassStyle,
+        range: &InstrumentRange,
+    ) -> Result<Self, MusicSemanticsError> {
+        let roots: Vec<Note> = progression.chords.iter()
+            .map(|chord| chord.first().copied().ok_or(MusicSemanticsError::EmptySetOfNotes))
+            .collect::<Result<_, _>>()?;
+        let beat: Duration = (&meter.denominator).into();
+        let mut previous: Option<Pitch> = None;
+        let mut bars = vec![];
+        for (i, chord) in progression.chords.iter().enumerate() {
+            let root = roots[i];
+            let notes: Vec<(Note, Vec<Duration>)> = match style {
+                BassStyle::Roots => vec![(root, bar_durations(meter))],
+                BassStyle::RootFifth => big_beat_durations(meter).into_iter()
+                    .enumerate()
+                    .map(|(j, durations)| if j % 2 == 0 {
+                        (root, durations)
+                    } else {
+                        (fifth(chord), durations)
+                    })
+                    .collect(),
+                BassStyle::Walking => chord.iter()
+                    .cycle()
+                    .take(meter.num_beats.saturating_sub(1).max(1))
+                    .map(|note| (*note, vec![beat]))
+                    .collect(),
+            };
+            let mut bar = vec![];
+            for (note, durations) in notes {
+                let target = previous.map_or(range.lowest.midi_note, |pitch| pitch.midi_note);
+                let pitch = range.nearest(&note, target)?;
+                previous = Some(pitch);
+                bar.extend(durations.into_iter()
+                    .enumerate()
+                    .map(|(j, duration)| BassNote { pitch, duration, tied: j > 0 }));
+            }
+            if style == BassStyle::Walking && meter.num_beats > 1 {
+                // Checked above, as every bar has at least one note.
+                let from = previous.ok_or(MusicSemanticsError::Unreachable)?;
+                let next_root = roots[(i + 1) % roots.len()];
+                let pitch = approach(&from, &next_root, range)?;
+                previous = Some(pitch);
+                bar.push(BassNote { pitch, duration: beat, tied: false });
+            }
+            bars.push(bar);
+        }
+        Ok(Self { bars })
+    }
+
+    /// Every note of the line, from the first bar to the last.
+    pub fn notes(&self) -> impl Iterator<Item = &BassNote> {
+        self.bars.iter().flatten()
+    }
+
+    /// The line as notated events, for engraving on a staff.
+    pub fn to_events(&self) -> Vec<RhythmicNotatedEvent<'static>> {
+        self.notes()
+            .map(|note| if note.tied {
+                RhythmicNotatedEvent::pitch_tied(note.pitch, note.duration)
+            } else {
+                RhythmicNotatedEvent::pitch(note.pitch, note.duration)
+            })
+            .collect()
+    }
+
+    /// The line as fretted events on `fretboard`, for engraving as tab.
+    ///
+    /// Each note is played on whichever string keeps the hand closest to the fret of
+    /// the note before it. The first note is played as low on the neck as possible.
+    pub fn to_fretted_events<'a>(
+        &self,
+        fretboard: &'a Fretboard,
+    ) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+        let mut previous: Option<u8> = None;
+        self.notes()
+            .map(|note| {
+                let (string, fret) = (0..fretboard.num_strings())
+                    .filter_map(|string| fretboard.fret_for_pitch(string, &note.pitch).ok()
+                        .map(|fret| (string, fret)))
+                    .min_by_key(|(_, fret)| previous.map_or(*fret, |previous| previous.abs_diff(*fret)))
+                    .ok_or(MusicSemanticsError::PitchOutOfRange(note.pitch))?;
+                previous = Some(fret);
+                let sounded = SoundedNote { string, fret, pitch: note.pitch, fretboard };
+                Ok(if note.tied {
+                    RhythmicNotatedEvent::fretted_tied(sounded, note.duration)
+                } else {
+                    RhythmicNotatedEvent::fretted(sounded, note.duration)
+                })
+            })
+            .collect()
+    }
+}
+
+/// The fifth of a chord, taken from its notes where possible, as with the diminished
+/// fifth of a diminished chord. Otherwise, the perfect fifth above its root.
+pub(crate) fn fifth(chord: &NoteSet) -> Note {
+    let root = chord[0];
+    [7, 6, 8].iter()
+        .find_map(|semitones| chord.iter().find(|note| root.distance_up_to_note(note) == *semitones))
+        .copied()
+        .unwrap_or_else(|| {
+            let pc = Pc::from(&(u8::from(&Pc::from(&root)) + 7));
+            pc.notes()[0]
+        })
+}
+
+/// A half step from the pitch of `next_root` nearest to `from`. The line is led on
+/// in the direction it's moving, from below when rising and from above when falling,
+/// unless that would repeat `from` or leave the range.
+fn approach(
+    from: &Pitch,
+    next_root: &Note,
+    range: &InstrumentRange,
+) -> Result<Pitch, MusicSemanticsError> {
+    let target = range.nearest(next_root, from.midi_note)?;
+    let below = target.at_distance_from(-1);
+    let above = target.at_distance_from(1);
+    let (first, second) = if from.midi_note <= target.midi_note {
+        (below, above)
+    } else {
+        (above, below)
+    };
+    [first, second].into_iter()
+        .flatten()
+        .filter(|pitch| range.contains(pitch))
+        .find(|pitch| pitch.midi_note != from.midi_note)
+        .ok_or(MusicSemanticsError::NoteOutOfRange(*next_root, range.lowest, range.highest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_4STR_BASS;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::notation::vextab::ToVexTab;
+
+    fn progression() -> Progression {
+        Progression::new(vec![
+            NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G]),
+            NoteSet::starting_from_first_note(vec![Note::A, Note::C, Note::E]),
+            NoteSet::starting_from_first_note(vec![Note::F, Note::A, Note::C]),
+            NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D]),
+        ])
+    }
+
+    fn midi_notes(bar: &[BassNote]) -> Vec<u8> {
+        bar.iter().map(|note| note.pitch.midi_note).collect()
+    }
+
+    #[test]
+    fn bass_line_styles() {
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let range = InstrumentRange::of_fretted(&*STD_4STR_BASS, 12).unwrap();
+        assert_eq!(range.lowest, Pitch::new(Note::E, 1).unwrap());
+        assert_eq!(range.highest, Pitch::new(Note::G, 3).unwrap());
+
+        let roots = BassLine::new(&progression(), &meter, BassStyle::Roots, &range).unwrap();
+        let pitches: Vec<u8> = roots.notes().map(|note| note.pitch.midi_note).collect();
+        // C2, then the closest A, F, and G below it.
+        assert_eq!(pitches, vec![36, 33, 29, 31]);
+        assert_eq!(roots.bars[0][0].duration, Duration::WHOLE);
+
+        let root_fifth = BassLine::new(&progression(), &meter, BassStyle::RootFifth, &range).unwrap();
+        assert_eq!(midi_notes(&root_fifth.bars[0]), vec![36, 31]);
+        assert_eq!(root_fifth.bars[0][1].duration, Duration::HALF);
+
+        let walking = BassLine::new(&progression(), &meter, BassStyle::Walking, &range).unwrap();
+        // C E G, then G# leading up to A.
+        assert_eq!(midi_notes(&walking.bars[0]), vec![36, 40, 43, 44]);
+        assert_eq!(walking.bars[0][3].pitch.note, Note::Gis);
+        assert!(walking.notes().all(|note| range.contains(&note.pitch)));
+        for bars in walking.bars.windows(2) {
+            assert_eq!(bars[0].len(), 4);
+            assert_eq!(bars[0][3].pitch.midi_note.abs_diff(bars[1][0].pitch.midi_note), 1);
+        }
+
+        let tab = walking.to_fretted_events(&STD_4STR_BASS).unwrap();
+        assert_eq!(tab.len(), 16);
+        // C2 is played on the A string, rather than high up the E string.
+        assert_eq!(tab[0].to_vextab(), ":q 3/3");
+        assert_eq!(walking.to_events().len(), 16);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::chord_shape_search::find_chord_shapes;
+use crate::fretboard::fretboard_shape::open_chords::open_chord_shape;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::spelling::SpellingPreference;
+use crate::progression::Progression;
+
+/// A chord of a progression, as it's played with a capo on.
+#[derive(Debug, Clone)]
+pub struct CapoChord<'a> {
+    /// The chord whose shape is fingered, e.g. G for an A with a capo on the second fret.
+    pub name: ChordName,
+    /// Whether the chord is played as one of [crate::fretboard::OPEN_CHORDS].
+    pub open: bool,
+    /// The open chord, if there is one, and otherwise the lowest playable shape of the chord,
+    /// with its frets counted from the capo, as on a chord chart.
+    pub shape: Option<FretboardShape<'a>>,
+}
+
+/// The chords of a progression, as they're played with a capo on a given fret.
+#[derive(Debug, Clone)]
+pub struct CapoSuggestion<'a> {
+    /// The fret the capo is on, or 0 for no capo at all.
+    pub capo: u8,
+    /// How many of the chords are played as open chords.
+    pub num_open: usize,
+    /// Each chord of the progression, in order.
+    pub chords: Vec<CapoChord<'a>>,
+}
+
+/// Ranks each place to put a capo, from no capo up to `max_capo`, by how many of the chords
+/// of a progression can then be played as open chords, see [open_chord_shape]. The best come first,
+/// and of those equally good, the lowest capo. E.g. Bb, Eb, and F are played as A, D, and E
+/// with a capo on the first fret.
+///
+/// Errors if a chord of the progression can't be named, see [Progression::chord_names].
+pub fn suggest_capo<'a>(
+    progression: &Progression,
+    fretboard: &'a Fretboard,
+    max_capo: u8,
+) -> Result<Vec<CapoSuggestion<'a>>, MusicSemanticsError> {
+    let names = progression.chord_names()?;
+    let mut suggestions = (0..=max_capo)
+        .map(|capo| {
+            let chords = names.iter()
+                .map(|name| {
+                    let name = name.transpose(-((capo % 12) as i8), &SpellingPreference::Contextual);
+                    let open_shape = open_chord_shape(&name, fretboard);
+                    let open = open_shape.is_some();
+                    let shape = match open_shape {
+                        Some(shape) => Some(shape),
+                        None => find_chord_shapes(&name.to_note_set()?, fretboard)?
+                            .playable
+                            .into_values()
+                            .flatten()
+                            .min_by_key(|shape| shape.span()),
+                    };
+                    Ok(CapoChord { name, open, shape })
+                })
+                .collect::<Result<Vec<_>, MusicSemanticsError>>()?;
+            Ok(CapoSuggestion {
+                capo,
+                num_open: chords.iter().filter(|chord| chord.open).count(),
+                chords,
+            })
+        })
+        .collect::<Result<Vec<_>, MusicSemanticsError>>()?;
+    suggestions.sort_by_key(|suggestion| (std::cmp::Reverse(suggestion.num_open), suggestion.capo));
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::note::note::Note;
+    use crate::note_collections::NoteSet;
+
+    #[test]
+    fn capo_suggestions() {
+        let progression = |chords: Vec<Vec<Note>>| Progression::new(chords.into_iter()
+            .map(NoteSet::starting_from_first_note)
+            .collect());
+        let names = |suggestion: &CapoSuggestion| suggestion.chords.iter()
+            .map(|chord| chord.name.symbol(None))
+            .collect::<Vec<_>>();
+
+        // Bb, Eb, and F are all barre chords, but with a capo on the first fret, they're A, D, and E.
+        let flats = progression(vec![
+            vec![Note::Bes, Note::D, Note::F],
+            vec![Note::Ees, Note::G, Note::Bes],
+            vec![Note::F, Note::A, Note::C],
+        ]);
+        let suggestions = suggest_capo(&flats, &STD_6STR_GTR, 7).unwrap();
+        assert_eq!(suggestions.len(), 8);
+        assert_eq!((suggestions[0].capo, suggestions[0].num_open), (1, 3));
+        assert_eq!(names(&suggestions[0]), vec!["AMaj", "DMaj", "EMaj"]);
+        assert_eq!(suggestions[0].chords[0].shape.as_ref().unwrap().to_string(), "x-0-2-2-2-0");
+        // G, C, and D, with a capo on the third fret, are as good, but higher up.
+        assert_eq!((suggestions[1].capo, suggestions[1].num_open), (3, 3));
+        assert_eq!(names(&suggestions[1]), vec!["GMaj", "CMaj", "DMaj"]);
+        let no_capo = suggestions.iter().find(|suggestion| suggestion.capo == 0).unwrap();
+        assert_eq!(no_capo.num_open, 0);
+        assert!(no_capo.chords.iter().all(|chord| !chord.open && chord.shape.is_some()));
+
+        // F, C, G, and Dm have one barre chord without a capo, and none as C, G, D, and Am.
+        let in_c = progression(vec![
+            vec![Note::F, Note::A, Note::C],
+            vec![Note::C, Note::E, Note::G],
+            vec![Note::G, Note::B, Note::D],
+            vec![Note::D, Note::F, Note::A],
+        ]);
+        let suggestions = suggest_capo(&in_c, &STD_6STR_GTR, 7).unwrap();
+        assert_eq!((suggestions[0].capo, suggestions[0].num_open), (5, 4));
+        assert_eq!(names(&suggestions[0]), vec!["CMaj", "GMaj", "DMaj", "Amin"]);
+        assert_eq!((suggestions[1].capo, suggestions[1].num_open), (0, 3));
+        assert!(!suggestions[1].chords[0].open);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::meter::Meter;
+use crate::progression::Progression;
+use crate::progression::render::bar_durations;
+
+/// The part of a chord that falls within one bar of a chord chart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSlot {
+    /// The index of the chord in [Progression::chords].
+    pub chord: usize,
+    /// The durations the chord is held for within the bar, tied together.
+    pub durations: Vec<Duration>,
+    /// Whether the chord is held over from the previous bar, rather than struck anew.
+    pub tied: bool,
+}
+
+/// Lays the chords of a [Progression] out over the bars of `meter`, according to
+/// [Progression::durations], or one chord per bar if there are none.
+///
+/// Chords may share a bar, or be held across several, in which case they are tied over
+/// the bar lines. A [Progression::pickup] makes the first bar incomplete, and
+/// the last bar is left incomplete if the chords run out before it ends.
+pub fn chart_bars(
+    progression: &Progression,
+    meter: &Meter,
+) -> Result<Vec<Vec<ChartSlot>>, MusicSemanticsError> {
+    let beat: Duration = (&meter.denominator).into();
+    let bar_ticks = beat.ticks() * meter.num_beats;
+    let lengths: Vec<DurationTicks> = if progression.durations.is_empty() {
+        vec![bar_ticks; progression.chords.len()]
+    } else if progression.durations.len() == progression.chords.len() {
+        progression.durations.clone()
+    } else {
+        return Err(MusicSemanticsError::MismatchedCollectionSize(
+            progression.chords.len(),
+            progression.durations.len(),
+        ));
+    };
+    let mut remaining = match progression.pickup {
+        Some(pickup) if pickup >= bar_ticks => {
+            return Err(MusicSemanticsError::MeasureOverflow(pickup, bar_ticks));
+        },
+        Some(pickup) if pickup > 0 => pickup,
+        _ => bar_ticks,
+    };
+    let mut bars = vec![];
+    let mut bar = vec![];
+    for (chord, length) in lengths.into_iter().enumerate() {
+        let mut left = length;
+        let mut tied = false;
+        while left > 0 {
+            let ticks = left.min(remaining);
+            let durations = if ticks == bar_ticks {
+                bar_durations(meter)
+            } else {
+                Duration::from_ticks(ticks)
+            };
+            bar.push(ChartSlot { chord, durations, tied });
+            tied = true;
+            left -= ticks;
+            remaining -= ticks;
+            if remaining == 0 {
+                bars.push(std::mem::take(&mut bar));
+                remaining = bar_ticks;
+            }
+        }
+    }
+    if !bar.is_empty() {
+        bars.push(bar);
+    }
+    Ok(bars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::note::Note;
+    use crate::note_collections::NoteSet;
+
+    #[test]
+    fn harmonic_rhythm() {
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let bar = Duration::WHOLE.ticks();
+        let progression = Progression::new(vec![
+            NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D]),
+            NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G]),
+            NoteSet::starting_from_first_note(vec![Note::A, Note::C, Note::E]),
+            NoteSet::starting_from_first_note(vec![Note::F, Note::A, Note::C]),
+        ]);
+        let bars = chart_bars(&progression, &meter).unwrap();
+        assert_eq!(bars.len(), 4);
+        assert!(bars.iter().all(|bar| bar.len() == 1 && bar[0].durations == vec![Duration::WHOLE]));
+
+        // A quarter note pickup, then a bar and a half of C, sharing a bar with A minor.
+        let progression = progression
+            .with_durations(vec![bar / 4, bar * 3 / 2, bar / 2, bar * 2])
+            .with_pickup(bar / 4);
+        let bars = chart_bars(&progression, &meter).unwrap();
+        let chords: Vec<Vec<(usize, bool)>> = bars.iter()
+            .map(|bar| bar.iter().map(|slot| (slot.chord, slot.tied)).collect())
+            .collect();
+        assert_eq!(chords, vec![
+            vec![(0, false)],
+            vec![(1, false)],
+            vec![(1, true), (2, false)],
+            vec![(3, false)],
+            vec![(3, true)],
+        ]);
+        assert_eq!(bars[0][0].durations, vec![Duration::QTR]);
+        assert_eq!(bars[2][1].durations, vec![Duration::HALF]);
+
+        let mismatched = progression.clone().with_durations(vec![bar]);
+        assert!(chart_bars(&mismatched, &meter).is_err());
+        let overlong = progression.with_pickup(bar);
+        assert!(chart_bars(&overlong, &meter).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretted_note::SoundedNote;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::meter::Meter;
+use crate::notation::vextab::ToVexTab;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::NoteSet;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::chord_tones::{chord_degrees, Degree};
+use crate::progression::Progression;
+use crate::progression::render::vextab_staves;
+
+/// How many frets a position spans, one for each finger of the fretting hand.
+pub(crate) const POSITION_SPAN: u8 = 4;
+
+/// A note of a [ChordToneEtude].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtudeNote<'a> {
+    pub note: SoundedNote<'a>,
+    /// The note's degree above the root of the chord it's played over,
+    /// or `None` for an approach note, which lies outside the chord.
+    pub degree: Option<Degree>,
+}
+
+/// An etude for practicing the chord tones of a [Progression] in one position on the neck,
+/// one bar for each chord, whatever its [Progression::durations].
+///
+/// Each bar arpeggiates its chord in notes of one value, up and down through every chord tone
+/// within the position. The arpeggio carries on in the same direction from one bar to the next,
+/// from the nearest tone of the next chord. The last note of each bar is replaced by a chromatic
+/// approach note, a half step below the first note of the next bar, or above it if that's outside
+/// the position. The last bar approaches the first, as though the etude were repeated.
+#[derive(Debug, Clone)]
+pub struct ChordToneEtude<'a> {
+    /// The name of each chord, written over its bar.
+    pub chords: Vec<ChordName>,
+    pub bars: Vec<Vec<EtudeNote<'a>>>,
+    pub meter: Meter,
+    /// The value of every note of the etude.
+    pub note_value: Duration,
+}
+
+impl<'a> ChordToneEtude<'a> {
+    /// Generates an etude played from the fret `position` up to three frets above it,
+    /// with open strings only in the open position, i.e. 0.
+    ///
+    /// Errors if a chord can't be named, if it has no tones within the position,
+    /// or if `note_value` doesn't evenly divide a bar of `meter`.
+    pub fn new(
+        progression: &Progression,
+        meter: &Meter,
+        fretboard: &'a Fretboard,
+        position: u8,
+        note_value: Duration,
+    ) -> Result<Self, MusicSemanticsError> {
+        let chords = progression.chord_names()?;
+        let beat: Duration = (&meter.denominator).into();
+        let bar_ticks = beat.ticks() * meter.num_beats;
+        if !bar_ticks.is_multiple_of(note_value.ticks()) {
+            return Err(MusicSemanticsError::PatternDoesNotFitMeasure(note_value.ticks(), bar_ticks));
+        }
+        let tones = progression.chords.iter()
+            .map(|chord| tones_in_position(chord, fretboard, position))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut arpeggios: Vec<Vec<EtudeNote<'a>>> = vec![];
+        let mut rising = true;
+        for chord_tones in &tones {
+            let mut index = match arpeggios.last().and_then(|bar| bar.last()) {
+                Some(previous) => next_index(chord_tones, &previous.note.pitch, &mut rising),
+                None => chord_tones.iter()
+                    .position(|tone| tone.degree == Some(Degree::ROOT))
+                    .unwrap_or(0),
+            };
+            let mut bar = vec![];
+            for _ in 0..bar_ticks / note_value.ticks() {
+                bar.push(chord_tones[index].clone());
+                if chord_tones.len() > 1 {
+                    if (rising && index + 1 == chord_tones.len()) || (!rising && index == 0) {
+                        rising = !rising;
+                    }
+                    index = if rising { index + 1 } else { index - 1 };
+                }
+            }
+            arpeggios.push(bar);
+        }
+
+        let mut bars = arpeggios.clone();
+        for (i, bar) in bars.iter_mut().enumerate() {
+            let next = &arpeggios[(i + 1) % arpeggios.len()][0].note.pitch;
+            if bar.len() > 1 {
+                if let Some(approach) = approach_note(next, fretboard, position) {
+                    *bar.last_mut().unwrap() = approach;
+                }
+            }
+        }
+        Ok(Self { chords, bars, meter: meter.clone(), note_value })
+    }
+
+    /// Every note of the etude, from the first bar to the last.
+    pub fn notes(&self) -> impl Iterator<Item = &EtudeNote<'a>> {
+        self.bars.iter().flatten()
+    }
+
+    /// The etude as fretted events, for engraving as notation and tab.
+    pub fn to_events(&self) -> Vec<RhythmicNotatedEvent<'a>> {
+        self.notes()
+            .map(|note| RhythmicNotatedEvent::fretted(note.note.clone(), self.note_value))
+            .collect()
+    }
+
+    /// The etude as a score of notation over tab, to be placed in a Lilypond document.
+    #[cfg(feature = "lilypond")]
+    pub fn to_lilypond(&self) -> Result<String, crate::notation::lilypond::error::LilypondError> {
+        use crate::notation::clef::Clef;
+        use crate::notation::lilypond::ToLilypondString;
+        use crate::notation::lilypond::document::staff::LilypondStaff;
+        use crate::notation::lilypond::document::tab_staff::LilypondTabStaff;
+        use crate::notation::lilypond::scoring::score;
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+
+        let voice = || self.to_events().into_iter().map(Into::into).collect::<Vec<LilypondVoiceElement>>();
+        let staff = LilypondStaff::new()
+            .clef(Some(Clef::Treble8ba))
+            .meter(Some(self.meter.clone()))
+            .add_voice(voice())
+            .try_to_lilypond_string()?;
+        let tab = LilypondTabStaff::new().add_voice(voice()).try_to_lilypond_string()?;
+        score(format!("<<\n{}\n{}\n>>", staff, tab), false)
+    }
+}
+
+/// Notation and tab, with each chord's name over the first note of its bar.
+impl<'a> ToVexTab for ChordToneEtude<'a> {
+    fn to_vextab(&self) -> String {
+        let bars: Vec<String> = self.bars.iter()
+            .zip(&self.chords)
+            .map(|(bar, chord)| {
+                let mut tokens: Vec<String> = bar.iter()
+                    .map(|note| RhythmicNotatedEvent::fretted(note.note.clone(), self.note_value).to_vextab())
+                    .collect();
+                if let Some(first) = tokens.first_mut() {
+                    first.push_str(&format!(" ${}$", chord.symbol(None)));
+                }
+                tokens.join(" ")
+            })
+            .collect();
+        vextab_staves(&bars, &self.meter)
+    }
+}
+
+/// The tones of a chord within a position, from lowest to highest, spelled as in the chord.
+fn tones_in_position<'a>(
+    chord: &NoteSet,
+    fretboard: &'a Fretboard,
+    position: u8,
+) -> Result<Vec<EtudeNote<'a>>, MusicSemanticsError> {
+    let root = chord.first().ok_or(MusicSemanticsError::EmptySetOfNotes)?;
+    let degrees = chord_degrees(root, chord);
+    let mut tones: Vec<EtudeNote<'a>> = (0..fretboard.num_strings())
+        .flat_map(|string| (position..position + POSITION_SPAN).map(move |fret| (string, fret)))
+        .filter_map(|(string, fret)| fretboard.sounded_note(string, fret).ok())
+        .filter_map(|note| {
+            let i = chord.iter().position(|tone| Pc::from(tone) == Pc::from(&note.pitch.note))?;
+            Some(EtudeNote { note: note.spelled_as_in(&vec![chord[i]]).ok()?, degree: Some(degrees[i]) })
+        })
+        .collect();
+    tones.sort_by_key(|tone| tone.note.pitch.midi_note);
+    tones.dedup_by_key(|tone| tone.note.pitch.midi_note);
+    if tones.is_empty() {
+        return Err(MusicSemanticsError::NoPlayableShape(chord.to_vec()));
+    }
+    Ok(tones)
+}
+
+/// Where the arpeggio carries on from `previous` into the next chord: its nearest tone
+/// in the direction the arpeggio is moving, or turning back if there's none.
+fn next_index(tones: &[EtudeNote], previous: &Pitch, rising: &mut bool) -> usize {
+    let above = tones.iter().position(|tone| tone.note.pitch.midi_note > previous.midi_note);
+    let below = tones.iter().rposition(|tone| tone.note.pitch.midi_note < previous.midi_note);
+    let (ahead, behind) = if *rising { (above, below) } else { (below, above) };
+    match (ahead, behind) {
+        (Some(index), _) => index,
+        (None, Some(index)) => {
+            *rising = !*rising;
+            index
+        },
+        // Every tone is the same pitch as the last note.
+        (None, None) => 0,
+    }
+}
+
+/// A note a half step below `target`, or above it, within the position,
+/// spelled on the letter next to the target's, e.g. F# below G, or Ab above G.
+fn approach_note<'a>(target: &Pitch, fretboard: &'a Fretboard, position: u8) -> Option<EtudeNote<'a>> {
+    [-1, 1].into_iter().find_map(|semitones| {
+        let pitch = target.at_distance_from(semitones).ok()?;
+        let note = (0..fretboard.num_strings())
+            .filter_map(|string| fretboard.fret_for_pitch(string, &pitch).ok()
+                .filter(|fret| (position..position + POSITION_SPAN).contains(fret))
+                .and_then(|fret| fretboard.sounded_note(string, fret).ok()))
+            .next()?;
+        let spelling = Pc::from(&pitch.note).notes().into_iter()
+            .find(|note| match semitones {
+                -1 => note.diatonic_distance_up(&target.note) == 1,
+                _ => target.note.diatonic_distance_up(note) == 1,
+            })
+            .unwrap_or(pitch.note);
+        Some(EtudeNote { note: note.spelled_as_in(&vec![spelling]).ok()?, degree: None })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::notation::rhythm::duration::DurationKind;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::note::Note;
+
+    #[test]
+    fn chord_tone_etudes() {
+        let progression = Progression::new(vec![
+            NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G, Note::B]),
+            NoteSet::starting_from_first_note(vec![Note::A, Note::C, Note::E, Note::G]),
+            NoteSet::starting_from_first_note(vec![Note::D, Note::F, Note::A, Note::C]),
+            NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D, Note::F]),
+        ]);
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let etude = ChordToneEtude::new(&progression, &meter, &STD_6STR_GTR, 7, Duration::EIGHTH).unwrap();
+        assert_eq!(etude.bars.len(), 4);
+        assert!(etude.bars.iter().all(|bar| bar.len() == 8));
+        assert!(etude.notes().all(|note| (7..=10).contains(&note.note.fret)));
+        // From the lowest C in the position, up through Cmaj7.
+        let first: Vec<String> = etude.bars[0][..4].iter().map(|note| note.note.to_string()).collect();
+        assert_eq!(first, vec!["1:8(C)", "2:7(E)", "2:10(G)", "3:9(B)"]);
+        assert_eq!(etude.bars[1][0].degree.map(|degree| degree.to_string()).as_deref(), Some("b3"));
+        // Each bar ends on a note outside the chord, a half step from the start of the next.
+        for (i, bar) in etude.bars.iter().enumerate() {
+            let approach = bar.last().unwrap();
+            let next = &etude.bars[(i + 1) % 4][0];
+            assert_eq!(approach.degree, None);
+            assert_eq!(approach.note.pitch.midi_note.abs_diff(next.note.pitch.midi_note), 1);
+        }
+        assert_eq!(etude.bars[1][7].note.pitch.note, Note::Cis);
+        assert_eq!(etude.to_events().len(), 32);
+        assert!(etude.to_vextab().starts_with("tabstave notation=true time=4/4\nnotes :8 8/6 $CMaj7$ :8 7/5 :8 10/5"));
+
+        let dotted = Duration::dotted(DurationKind::Qtr);
+        assert!(matches!(
+            ChordToneEtude::new(&progression, &meter, &STD_6STR_GTR, 7, dotted),
+            Err(MusicSemanticsError::PatternDoesNotFitMeasure(48, 128)),
+        ));
+        #[cfg(feature = "lilypond")]
+        assert!(etude.to_lilypond().unwrap().contains("\\new TabStaff"));
+    }
+}
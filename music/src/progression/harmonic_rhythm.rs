@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::meter::Meter;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::Voicing;
+
+/// A change of chord found by [harmonic_rhythm].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChordChange {
+    /// The index in the timeline of the voicing the new chord starts with.
+    pub voicing: usize,
+    /// When the change is heard, from the start of the timeline.
+    pub heard_at: DurationTicks,
+    /// When the change falls in a chart: on the big beat of the meter nearest to where it's heard.
+    pub at: DurationTicks,
+    /// The bar that `at` falls in, from 0.
+    pub bar: usize,
+    /// The big beat that `at` falls on, from 0 for the downbeat.
+    pub big_beat: usize,
+}
+
+impl ChordChange {
+    /// Whether the change is heard off the big beat it's charted on.
+    pub fn is_syncopated(&self) -> bool {
+        self.heard_at != self.at
+    }
+
+    /// Whether the change is heard ahead of the big beat it's charted on, e.g. a push into the next bar.
+    pub fn is_anticipated(&self) -> bool {
+        self.heard_at < self.at
+    }
+}
+
+/// How often the chords of a performance change, relative to a [Meter], as found by [harmonic_rhythm].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicRhythm {
+    pub changes: Vec<ChordChange>,
+    /// How many bars the changes are charted over, counting an incomplete last bar as a whole one.
+    pub bars: usize,
+    /// The length of a bar of the meter.
+    pub bar_ticks: DurationTicks,
+}
+
+impl HarmonicRhythm {
+    /// The mean number of chord changes in a bar.
+    pub fn chords_per_bar(&self) -> f64 {
+        if self.bars == 0 {
+            return 0.0;
+        }
+        self.changes.len() as f64 / self.bars as f64
+    }
+
+    /// How many chord changes are charted in each bar.
+    pub fn changes_per_bar(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.bars];
+        for change in &self.changes {
+            counts[change.bar] += 1;
+        }
+        counts
+    }
+
+    /// The changes heard off the big beats they're charted on.
+    pub fn syncopated(&self) -> Vec<&ChordChange> {
+        self.changes.iter().filter(|change| change.is_syncopated()).collect()
+    }
+
+    /// How long each chord lasts in a chart, from its change to the next, and the last to the end
+    /// of its bar, e.g. for [crate::progression::Progression::with_durations].
+    /// Anything before the first change is left out, to be given to the first chord, or made a pickup.
+    pub fn durations(&self) -> Vec<DurationTicks> {
+        self.changes.iter()
+            .map(|change| change.at)
+            .chain([self.bars * self.bar_ticks])
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect()
+    }
+}
+
+/// Finds where the chords of a timeline of voicings, each with how long it lasts, change,
+/// and charts each change on the nearest big beat of `meter`, so that e.g. a chord pushed
+/// an eighth ahead of the bar line is charted on the downbeat it anticipates.
+///
+/// The chord changes wherever a voicing's pitch classes differ from those of the chord before,
+/// so that a chord voiced anew isn't a change. An empty voicing, i.e. a rest, continues the chord before it.
+/// A change halfway between two big beats is charted on the later one. Where two changes
+/// are charted on the same big beat, the earlier is lost, as it's too brief to be charted,
+/// and so is the later if that leaves it the same as the chord before.
+pub fn harmonic_rhythm(timeline: &[(Voicing, DurationTicks)], meter: &Meter) -> HarmonicRhythm {
+    let beat: Duration = (&meter.denominator).into();
+    let bar_ticks = beat.ticks() * meter.num_beats;
+    // The big beats of a bar, from its start, with the beat pattern's ticks scaled to those of a [Duration].
+    let scale = beat.ticks() / meter.denominator.ticks();
+    let big_beats: Vec<DurationTicks> = [0].into_iter()
+        .chain(meter.beat_pattern.iter().scan(0, |start, length| {
+            *start += length * scale;
+            Some(*start)
+        }))
+        .filter(|start| *start < bar_ticks)
+        .collect();
+    let nearest_big_beat = |ticks: DurationTicks| {
+        let bar_start = ticks / bar_ticks * bar_ticks;
+        big_beats.iter()
+            .map(|big_beat| bar_start + big_beat)
+            .chain([bar_start + bar_ticks])
+            .min_by_key(|start| (start.abs_diff(ticks), std::cmp::Reverse(*start)))
+            .unwrap_or(bar_start)
+    };
+
+    let mut changes: Vec<(ChordChange, BTreeSet<Pc>)> = vec![];
+    let mut chord: Option<BTreeSet<Pc>> = None;
+    let mut heard_at = 0;
+    for (voicing, (pitches, length)) in timeline.iter().enumerate() {
+        let pcs: BTreeSet<Pc> = pitches.iter().map(|pitch| Pc::from(&pitch.midi_note)).collect();
+        if !pcs.is_empty() && chord.as_ref() != Some(&pcs) {
+            let at = nearest_big_beat(heard_at);
+            if changes.last().is_some_and(|(previous, _)| previous.at == at) {
+                changes.pop();
+            }
+            if changes.last().is_none_or(|(_, previous)| *previous != pcs) {
+                let big_beat = big_beats.iter().position(|start| *start == at % bar_ticks).unwrap_or(0);
+                let change = ChordChange { voicing, heard_at, at, bar: at / bar_ticks, big_beat };
+                changes.push((change, pcs.clone()));
+            }
+            chord = Some(pcs);
+        }
+        heard_at += length;
+    }
+    let end = changes.last().map_or(heard_at, |(last, _)| heard_at.max(last.at + 1));
+    HarmonicRhythm {
+        changes: changes.into_iter().map(|(change, _)| change).collect(),
+        bars: end.div_ceil(bar_ticks),
+        bar_ticks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::pitch::Pitch;
+
+    #[test]
+    fn harmonic_rhythms() {
+        let voicing = |midi_notes: &[u8]| Voicing::new(midi_notes.iter().map(|note| Pitch::from_midi(*note).unwrap()).collect());
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let quarter = Duration::QTR.ticks();
+        let eighth = Duration::EIGHTH.ticks();
+        // A bar and a half of C, voiced anew and then resting; F on the third beat, pushed
+        // an eighth early into G; and a brief A minor, lost to the D minor struck just after it.
+        let timeline = [
+            (voicing(&[48, 52, 55]), 4 * quarter),
+            (voicing(&[52, 55, 60]), 3 * eighth),
+            (voicing(&[]), eighth),
+            (voicing(&[53, 57, 60]), 2 * quarter - eighth),
+            (voicing(&[55, 59, 62]), 2 * quarter + eighth),
+            (voicing(&[57, 60, 64]), eighth / 2),
+            (voicing(&[50, 53, 57]), 2 * quarter - eighth / 2),
+        ];
+        let rhythm = harmonic_rhythm(&timeline, &meter);
+        let changes: Vec<(usize, usize, usize)> = rhythm.changes.iter()
+            .map(|change| (change.voicing, change.bar, change.big_beat))
+            .collect();
+        assert_eq!(changes, vec![(0, 0, 0), (3, 1, 1), (4, 2, 0), (6, 2, 1)]);
+        assert!(rhythm.changes[2].is_anticipated());
+        assert!(rhythm.changes[3].is_syncopated() && !rhythm.changes[3].is_anticipated());
+        assert_eq!(rhythm.syncopated().len(), 2);
+        assert_eq!(rhythm.bars, 3);
+        assert_eq!(rhythm.changes_per_bar(), vec![1, 1, 2]);
+        assert_eq!(rhythm.chords_per_bar(), 4.0 / 3.0);
+        assert_eq!(rhythm.durations(), vec![6 * quarter, 2 * quarter, 2 * quarter, 2 * quarter]);
+
+        // In 6/8, the big beats are a dotted quarter apart.
+        let compound = Meter::new(6, MeterDenominator::Eight, None);
+        let rhythm = harmonic_rhythm(&[(timeline[0].0.clone(), 2 * eighth), timeline[3].clone()], &compound);
+        assert_eq!((rhythm.changes[1].bar, rhythm.changes[1].big_beat), (0, 1));
+        assert!(harmonic_rhythm(&[], &meter).changes.is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+use std::path::Path;
+use crate::error::MusicSemanticsError;
+use crate::notation::midi::MidiFile;
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::{NoteSet, PcSet};
+use crate::note_collections::geometry::symmetry::transpositional::Transpose;
+use crate::note_collections::key::{chord_spelling, Key};
+use crate::note_collections::spelling::spell_pc_set;
+use crate::progression::Progression;
+use crate::progression::chart::chart_bars;
+use crate::progression::passing_tones::remove_passing_notes;
+use crate::progression::segmentation::{segment_harmony, TimedNote};
+
+/// How [lead_sheet_from_midi] reads the chords of a MIDI file.
+#[derive(Debug, Clone, Default)]
+pub struct LeadSheetOptions {
+    /// The meter the chords are laid out in. Defaults to the file's time signature, or 4/4 without one.
+    pub meter: Option<Meter>,
+    /// How often the chord may change, e.g. every half bar. Defaults to every bar.
+    pub window: Option<DurationTicks>,
+    /// The key the chords are spelled in. Defaults to the key detected from every note of the file,
+    /// weighted as by [MidiFile::weighted_pcs], see [Key::detect].
+    pub key: Option<Key>,
+    /// The tracks whose notes make up the chords, e.g. to leave out the melody.
+    /// Defaults to every track.
+    pub tracks: Option<Vec<usize>>,
+    /// Finds where the chords change with [segment_harmony], rather than naming the chord of every window,
+    /// so that chords may change anywhere. The changes are then moved to the nearest multiple of
+    /// [LeadSheetOptions::window], which defaults to a beat instead.
+    pub segmented: bool,
+    /// Leaves out notes that are likely passing tones before finding the chords, see [remove_passing_notes].
+    pub skip_passing_tones: bool,
+}
+
+impl LeadSheetOptions {
+    pub fn meter(mut self, meter: Meter) -> Self {
+        self.meter = Some(meter);
+        self
+    }
+
+    pub fn window(mut self, window: DurationTicks) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    pub fn key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn tracks(mut self, tracks: Vec<usize>) -> Self {
+        self.tracks = Some(tracks);
+        self
+    }
+
+    pub fn segmented(mut self) -> Self {
+        self.segmented = true;
+        self
+    }
+
+    pub fn skip_passing_tones(mut self) -> Self {
+        self.skip_passing_tones = true;
+        self
+    }
+}
+
+/// The chords of a piece, spelled in its key, with how long each lasts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeadSheet {
+    pub key: Key,
+    pub meter: Meter,
+    /// Each chord starts from its root, as in [Progression].
+    pub progression: Progression,
+}
+
+impl LeadSheet {
+    /// The lead sheet in the key of the same mode on `tonic`, with every chord
+    /// respelled in the new key. Errors if the new key is theoretical, e.g. G# major.
+    pub fn transposed_to(&self, tonic: Note) -> Result<Self, MusicSemanticsError> {
+        let key = Key::new(tonic, self.key.mode)?;
+        let semitones = Pc::from(&self.key.tonic).distance_up_to(&Pc::from(&tonic)) as i8;
+        let chords = self.progression.chords.iter()
+            .map(|chord| {
+                let root = Pc::from(chord.first().ok_or(MusicSemanticsError::EmptySetOfNotes)?);
+                let intervals = PcSet::new(chord.iter()
+                    .map(|note| Pc::from(&root.distance_up_to(&Pc::from(note))))
+                    .collect());
+                let notes = spell_pc_set(&key.spell_pc(&root.transpose(semitones)), &intervals)?;
+                Ok(NoteSet::starting_from_first_note(notes))
+            })
+            .collect::<Result<Vec<_>, MusicSemanticsError>>()?;
+        Ok(Self {
+            key,
+            meter: self.meter.clone(),
+            progression: Progression { chords, ..self.progression.clone() },
+        })
+    }
+
+    /// The chord chart of the lead sheet, with `bars_per_line` bars to a line, e.g.
+    ///
+    /// ```text
+    /// | CMaj | Amin | FMaj G7 | CMaj |
+    /// | % |
+    /// ```
+    ///
+    /// Each chord is named where it's struck, and in any bar it shares with another chord.
+    /// A bar held over entirely from the one before is written as a `%`.
+    pub fn chart(&self, bars_per_line: usize) -> Result<String, MusicSemanticsError> {
+        let names = self.progression.chord_names()?;
+        let bars: Vec<String> = chart_bars(&self.progression, &self.meter)?
+            .iter()
+            .map(|bar| match bar.as_slice() {
+                [slot] if slot.tied => "%".to_string(),
+                slots => slots.iter()
+                    .map(|slot| names[slot.chord].symbol(None))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            })
+            .collect();
+        Ok(bars.chunks(bars_per_line.max(1))
+            .map(|line| format!("| {} |", line.join(" | ")))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Reads the chords of a MIDI file into a [LeadSheet]. See [lead_sheet_from_midi_file].
+pub fn lead_sheet_from_midi<P: AsRef<Path>>(
+    path: P,
+    options: &LeadSheetOptions,
+) -> Result<LeadSheet, MusicSemanticsError> {
+    lead_sheet_from_midi_file(&MidiFile::read(path)?, options)
+}
+
+/// Reads the chords of a [MidiFile] into a [LeadSheet].
+///
+/// The notes are cut into windows of [LeadSheetOptions::window], and the notes sounding in each
+/// window named as a chord, if they can be, and spelled in the key as by [crate::note_collections::spell_midi_in_key].
+/// Or, if [LeadSheetOptions::segmented], the chords are found wherever they change.
+/// A window with the same chord as the one before, or with no chord, continues it,
+/// and any windows before the first chord are given to it. Errors with
+/// [MusicSemanticsError::NoChordsFound] if no window holds a chord.
+pub fn lead_sheet_from_midi_file(
+    file: &MidiFile,
+    options: &LeadSheetOptions,
+) -> Result<LeadSheet, MusicSemanticsError> {
+    let meter = options.meter.clone()
+        .or_else(|| file.meter())
+        .unwrap_or_else(|| Meter::new(4, MeterDenominator::Four, None));
+    let beat: Duration = (&meter.denominator).into();
+    let window = options.window
+        .filter(|window| *window > 0)
+        .unwrap_or(if options.segmented { beat.ticks() } else { beat.ticks() * meter.num_beats });
+    let key = match options.key {
+        Some(key) => key,
+        None => Key::detect_weighted(&file.weighted_pcs()).ok_or(MusicSemanticsError::NoChordsFound)?,
+    };
+
+    let mut notes: Vec<TimedNote> = file.notes.iter()
+        .filter(|note| options.tracks.as_ref().is_none_or(|tracks| tracks.contains(&note.track)))
+        .map(|note| TimedNote {
+            midi_note: note.midi_note,
+            start: file.duration_ticks(note.start),
+            end: file.duration_ticks(note.end),
+        })
+        .collect();
+    if options.skip_passing_tones {
+        notes = remove_passing_notes(&notes, beat.ticks());
+    }
+    // Where each chord is struck, and where the last one ends.
+    let mut changes: Vec<(DurationTicks, NoteSet)> = vec![];
+    let end;
+    if options.segmented {
+        let snap = |ticks: DurationTicks| (ticks + window / 2) / window * window;
+        let regions = segment_harmony(&notes, window);
+        for region in &regions {
+            let start = snap(region.start);
+            // A region shorter than half a window is lost to the one after it.
+            if changes.last().is_some_and(|(previous, _)| *previous == start) {
+                changes.pop();
+            }
+            changes.push((start, region.chord_in_key(&key)?));
+        }
+        end = regions.last().map_or(0, |region| snap(region.end).max(snap(region.start) + window));
+    } else {
+        end = notes.iter().map(|note| note.end).max().unwrap_or(0).div_ceil(window) * window;
+        for start in (0..end).step_by(window) {
+            let mut sounding: Vec<u8> = notes.iter()
+                .filter(|note| note.start < start + window && note.end > start)
+                .map(|note| note.midi_note)
+                .collect();
+            sounding.sort();
+            sounding.dedup();
+            if let Some(chord) = chord_spelling(&sounding, &key)? {
+                changes.push((start, NoteSet::starting_from_first_note(chord)));
+            }
+        }
+    }
+    changes.dedup_by(|(_, chord), (_, previous)| chord == previous);
+    if changes.is_empty() {
+        return Err(MusicSemanticsError::NoChordsFound);
+    }
+    // Anything before the first chord is given to it.
+    changes[0].0 = 0;
+    let durations = changes.iter()
+        .map(|(start, _)| *start)
+        .chain([end])
+        .collect::<Vec<_>>()
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect();
+    let chords = changes.into_iter().map(|(_, chord)| chord).collect();
+    Ok(LeadSheet {
+        key,
+        meter,
+        progression: Progression::new(chords).with_durations(durations),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::notation::midi::MidiNote;
+    use crate::note_collections::KeyMode;
+
+    #[test]
+    fn lead_sheet_from_chord_track() {
+        let bar = 1920;
+        let note = |track, midi_note, start, end| MidiNote { midi_note, velocity: 80, channel: 0, track, start, end };
+        let chord = |midi_notes: &[u8], start, end| midi_notes.iter()
+            .map(|midi_note| note(0, *midi_note, start, end))
+            .collect::<Vec<_>>();
+        // C, Am, F and G7 sharing a bar, then C for two bars, under a melody.
+        let notes = [
+            chord(&[60, 64, 67], 0, bar),
+            chord(&[57, 60, 64], bar, 2 * bar),
+            chord(&[53, 57, 60], 2 * bar, 5 * bar / 2),
+            chord(&[55, 59, 62, 65], 5 * bar / 2, 3 * bar),
+            chord(&[48, 55, 64], 3 * bar, 5 * bar),
+            vec![note(1, 74, 0, bar / 2), note(1, 71, bar, 2 * bar), note(1, 72, 3 * bar, 4 * bar)],
+        ].concat();
+        let file = MidiFile { ticks_per_quarter: 480, time_signature: Some((4, 4)), micros_per_quarter: None, notes, instruments: HashMap::new() };
+        let options = LeadSheetOptions::default()
+            .window(Duration::HALF.ticks())
+            .tracks(vec![0]);
+        let sheet = lead_sheet_from_midi_file(&file, &options).unwrap();
+        assert_eq!(sheet.key, Key::new(Note::C, KeyMode::Major).unwrap());
+        let whole = Duration::WHOLE.ticks();
+        assert_eq!(sheet.progression.durations, vec![whole, whole, whole / 2, whole / 2, 2 * whole]);
+        assert_eq!(sheet.chart(4).unwrap(), "| CMaj | Amin | FMaj G7 | CMaj |\n| % |");
+
+        // Found wherever the chords change, rather than every half bar.
+        let segmented = LeadSheetOptions::default().segmented().tracks(vec![0]);
+        let segmented = lead_sheet_from_midi_file(&file, &segmented).unwrap();
+        assert_eq!(segmented, sheet);
+
+        let e_flat = sheet.transposed_to(Note::Ees).unwrap();
+        assert_eq!(e_flat.progression.chords[3].to_vec(), vec![Note::Bes, Note::D, Note::F, Note::Aes]);
+        assert_eq!(e_flat.chart(8).unwrap(), "| EbMaj | Cmin | AbMaj Bb7 | EbMaj | % |");
+        assert!(sheet.transposed_to(Note::Gis).is_err());
+
+        // Read back from a file. By default, the melody is heard as part of the chords.
+        let path = std::env::temp_dir().join("rust_music_lead_sheet.mid");
+        std::fs::write(&path, file.to_bytes()).unwrap();
+        assert_eq!(lead_sheet_from_midi(&path, &options).unwrap(), sheet);
+        let every_track = lead_sheet_from_midi(&path, &LeadSheetOptions::default()).unwrap();
+        assert_eq!(every_track.key, sheet.key);
+        assert!(every_track.chart(4).unwrap().starts_with("| Cadd9 | Amin(add9) |"));
+
+        let melody = MidiFile { ticks_per_quarter: 480, time_signature: None, micros_per_quarter: None, notes: vec![note(1, 74, 0, bar)], instruments: HashMap::new() };
+        assert!(matches!(lead_sheet_from_midi_file(&melody, &options), Err(MusicSemanticsError::NoChordsFound)));
+    }
+}
@@ -0,0 +1,112 @@
+pub mod bass_line;
+pub mod capo;
+pub mod chart;
+pub mod etude;
+pub mod harmonic_rhythm;
+pub mod lead_sheet;
+pub mod part_writing;
+pub mod passing_tones;
+pub mod render;
+pub mod scale_sheet;
+pub mod segmentation;
+pub mod song;
+pub mod voicing_ladder;
+
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::duration::DurationTicks;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::chord_name::quality::scale::ScaleMode;
+use crate::note_collections::{AppliedChord, FunctionConventions, HarmonicFunction, Key, NoteSet, Resolution};
+
+pub use bass_line::{BassLine, BassNote, BassStyle, InstrumentRange};
+pub use capo::{suggest_capo, CapoChord, CapoSuggestion};
+pub use chart::{ChartSlot, chart_bars};
+pub use etude::{ChordToneEtude, EtudeNote};
+pub use harmonic_rhythm::{harmonic_rhythm, ChordChange, HarmonicRhythm};
+pub use lead_sheet::{lead_sheet_from_midi, lead_sheet_from_midi_file, LeadSheet, LeadSheetOptions};
+pub use part_writing::{ChoirVoice, PartWritingViolation, SatbChord, SatbChorale};
+pub use passing_tones::{remove_passing_notes, salient_pcs};
+pub use segmentation::{segment_harmony, weigh_notes, HarmonicRegion, TimedNote};
+pub use scale_sheet::{ScaleSheet, SheetScale};
+pub use song::{Song, SongSection};
+pub use render::{progression_to_midi, progression_to_vextab, render_progression_tab, voice_led_shapes};
+pub use voicing_ladder::VoicingLadder;
+
+/// An ordered sequence of chords.
+///
+/// Each chord is a [NoteSet] whose first note is taken to be its root,
+/// so chords should be built with [NoteSet::starting_from_first_note],
+/// or with [NoteSet::new] and the root as its starting note.
+///
+/// By default, each chord lasts a bar. See [chart_bars] for how chords with
+/// other durations are laid out over the bars of a meter.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Progression {
+    pub chords: Vec<NoteSet>,
+    /// How long each chord lasts, one per chord. If empty, each chord lasts a bar.
+    pub durations: Vec<DurationTicks>,
+    /// The length of an incomplete bar before the first full bar, if any.
+    pub pickup: Option<DurationTicks>,
+}
+
+impl Progression {
+    pub fn new(chords: Vec<NoteSet>) -> Self {
+        Self {
+            chords,
+            durations: vec![],
+            pickup: None,
+        }
+    }
+
+    /// Sets how long each chord lasts, e.g. two chords to a bar, or one chord over several.
+    pub fn with_durations(mut self, durations: Vec<DurationTicks>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Starts the progression with a pickup bar of the given length.
+    pub fn with_pickup(mut self, pickup: DurationTicks) -> Self {
+        self.pickup = Some(pickup);
+        self
+    }
+
+    /// Names every chord in the progression, relative to its root.
+    pub fn chord_names(&self) -> Result<Vec<ChordName>, MusicSemanticsError> {
+        self.chords.iter()
+            .map(|chord| ChordName::from_note_set(chord)
+                .ok_or(MusicSemanticsError::UnnamedChord((**chord).clone()))
+            )
+            .collect()
+    }
+
+    /// The harmonic function of every chord in the progression, in a key, see [Key::function].
+    pub fn functions(&self, key: &Key, conventions: &FunctionConventions) -> Vec<Option<HarmonicFunction>> {
+        self.chords.iter()
+            .map(|chord| key.function(chord, conventions))
+            .collect()
+    }
+
+    /// The mode each chord of the progression is borrowed from, in a key, see [Key::borrowed_from].
+    pub fn borrowed_chords(&self, key: &Key) -> Vec<Option<ScaleMode>> {
+        self.chords.iter()
+            .map(|chord| key.borrowed_from(chord))
+            .collect()
+    }
+
+    /// Each applied chord of the progression, see [Key::applied_chord], with how it resolves
+    /// to the chord after it, so that e.g. deceptive resolutions can be reported.
+    /// The last chord has nothing to resolve to, so it's [Resolution::Unresolved] if it's applied.
+    pub fn applied_chords(&self, key: &Key) -> Vec<Option<(AppliedChord, Resolution)>> {
+        self.chords.iter()
+            .enumerate()
+            .map(|(i, chord)| {
+                let applied = key.applied_chord(chord)?;
+                let resolution = match self.chords.get(i + 1) {
+                    Some(next) => applied.resolution(next),
+                    None => Resolution::Unresolved,
+                };
+                Some((applied, resolution))
+            })
+            .collect()
+    }
+}
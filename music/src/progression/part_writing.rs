@@ -0,0 +1,399 @@
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::meter::Meter;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::{Key, NoteSet, Voicing};
+use crate::progression::Progression;
+use crate::progression::bass_line::{fifth, InstrumentRange};
+use crate::progression::render::bar_durations;
+
+/// How much worse a voicing is for each rule of voice-leading it breaks, against each
+/// semitone the voices move, so that the rules are only broken when they can't be kept.
+const VIOLATION_COST: usize = 50;
+
+/// A voice of a four-part choir.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChoirVoice {
+    Soprano,
+    Alto,
+    Tenor,
+    Bass,
+}
+
+impl ChoirVoice {
+    /// Every voice, from the highest.
+    pub const ALL: [ChoirVoice; 4] = [ChoirVoice::Soprano, ChoirVoice::Alto, ChoirVoice::Tenor, ChoirVoice::Bass];
+
+    /// The range the voice is written in, as given by most texts on harmony.
+    pub fn range(&self) -> InstrumentRange {
+        let pitch = |note, octave, midi_note| Pitch { note, octave, midi_note };
+        match self {
+            ChoirVoice::Soprano => InstrumentRange::new(pitch(Note::C, 4, 60), pitch(Note::G, 5, 79)),
+            ChoirVoice::Alto => InstrumentRange::new(pitch(Note::G, 3, 55), pitch(Note::C, 5, 72)),
+            ChoirVoice::Tenor => InstrumentRange::new(pitch(Note::C, 3, 48), pitch(Note::G, 4, 67)),
+            ChoirVoice::Bass => InstrumentRange::new(pitch(Note::E, 2, 40), pitch(Note::C, 4, 60)),
+        }
+    }
+}
+
+/// A chord written for four voices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SatbChord {
+    pub soprano: Pitch,
+    pub alto: Pitch,
+    pub tenor: Pitch,
+    pub bass: Pitch,
+}
+
+impl SatbChord {
+    pub fn new(soprano: Pitch, alto: Pitch, tenor: Pitch, bass: Pitch) -> Self {
+        Self { soprano, alto, tenor, bass }
+    }
+
+    pub fn pitch(&self, voice: ChoirVoice) -> Pitch {
+        match voice {
+            ChoirVoice::Soprano => self.soprano,
+            ChoirVoice::Alto => self.alto,
+            ChoirVoice::Tenor => self.tenor,
+            ChoirVoice::Bass => self.bass,
+        }
+    }
+
+    pub fn to_voicing(&self) -> Voicing {
+        Voicing::new(vec![self.bass, self.tenor, self.alto, self.soprano])
+    }
+}
+
+/// A rule of four-part writing broken in a [SatbChorale], at the index of the chord that
+/// breaks it, or for a rule of voice-leading, of the chord the voices move to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartWritingViolation {
+    /// A voice outside its [ChoirVoice::range].
+    OutOfRange(usize, ChoirVoice),
+    /// A voice below the voice under it.
+    VoiceCrossing(usize, ChoirVoice),
+    /// More than an octave between a voice and the one under it. The tenor and bass
+    /// may be further apart.
+    Spacing(usize, ChoirVoice),
+    /// A note of the chord that no voice sings. Only the fifth of a chord may be left out.
+    MissingNote(usize, Note),
+    /// The leading tone of the key in more than one voice.
+    DoubledLeadingTone(usize),
+    /// Two voices a perfect fifth apart, or a fifth and some octaves, moving to another.
+    ParallelFifths(usize, ChoirVoice, ChoirVoice),
+    /// Two voices in unison or octaves moving to another unison or octave.
+    ParallelOctaves(usize, ChoirVoice, ChoirVoice),
+    /// A leap wider than a fifth in an upper voice, or an octave in the bass.
+    LargeLeap(usize, ChoirVoice),
+    /// The leading tone in the soprano or bass, not rising to the tonic of the next chord.
+    UnresolvedLeadingTone(usize, ChoirVoice),
+}
+
+/// A [Progression] written out for soprano, alto, tenor, and bass, in root position,
+/// one bar for each chord, whatever its [Progression::durations].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatbChorale {
+    pub key: Key,
+    pub meter: Meter,
+    /// The chords of the progression, each starting from its root.
+    pub chords: Vec<NoteSet>,
+    /// How each chord is written.
+    pub parts: Vec<SatbChord>,
+}
+
+impl SatbChorale {
+    /// Writes each chord of `progression` for four voices in `key`, with the root in the bass,
+    /// so that the voices move as little as they can while keeping to the rules of part-writing.
+    ///
+    /// Every voicing keeps to the rules of range, spacing, and doubling, see [PartWritingViolation].
+    /// Of those, the root is doubled in preference to the fifth, and the fifth to the third.
+    /// The rules of voice-leading are only broken where no voicing keeps to them,
+    /// which can be found from [SatbChorale::violations].
+    ///
+    /// Errors if a chord is empty, has more than four notes other than its fifth,
+    /// or can't be voiced within the ranges of the voices.
+    pub fn new(progression: &Progression, key: &Key, meter: &Meter) -> Result<Self, MusicSemanticsError> {
+        let candidates = progression.chords.iter()
+            .map(|chord| voicings(chord, key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (Some(first), Some(last)) = (candidates.first(), candidates.last()) else {
+            return Ok(Self { key: *key, meter: meter.clone(), chords: vec![], parts: vec![] });
+        };
+        // The least cost of reaching each voicing of each chord, and the voicing before it.
+        let mut costs: Vec<Vec<(usize, usize)>> = vec![
+            first.iter().map(|voicing| (doubling_cost(voicing, &progression.chords[0]), 0)).collect(),
+        ];
+        for (i, voicings) in candidates.iter().enumerate().skip(1) {
+            let previous = &candidates[i - 1];
+            let reached = voicings.iter()
+                .map(|voicing| {
+                    previous.iter()
+                        .zip(&costs[i - 1])
+                        .enumerate()
+                        .map(|(j, (from, (cost, _)))| {
+                            let moved: usize = ChoirVoice::ALL.iter()
+                                .map(|voice| usize::from(from.pitch(*voice).midi_note.abs_diff(voicing.pitch(*voice).midi_note)))
+                                .sum();
+                            let broken = motion_violations(i, &progression.chords[i], from, voicing, key).len();
+                            (cost + moved + broken * VIOLATION_COST + doubling_cost(voicing, &progression.chords[i]), j)
+                        })
+                        .min()
+                        .unwrap_or((usize::MAX, 0))
+                })
+                .collect();
+            costs.push(reached);
+        }
+        let mut index = (0..last.len()).min_by_key(|j| costs[costs.len() - 1][*j].0).unwrap_or(0);
+        let mut parts = vec![];
+        for i in (0..candidates.len()).rev() {
+            parts.push(candidates[i][index]);
+            index = costs[i][index].1;
+        }
+        parts.reverse();
+        Ok(Self { key: *key, meter: meter.clone(), chords: progression.chords.clone(), parts })
+    }
+
+    /// The pitches sung by one voice, one for each chord.
+    pub fn voice(&self, voice: ChoirVoice) -> Vec<Pitch> {
+        self.parts.iter().map(|chord| chord.pitch(voice)).collect()
+    }
+
+    /// Every rule of part-writing broken, in order of the chords, see [PartWritingViolation].
+    /// The parts can be changed, or written by hand, to be checked again.
+    pub fn violations(&self) -> Vec<PartWritingViolation> {
+        let mut violations = vec![];
+        for (i, (chord, part)) in self.chords.iter().zip(&self.parts).enumerate() {
+            if i > 0 {
+                violations.extend(motion_violations(i, chord, &self.parts[i - 1], part, &self.key));
+            }
+            violations.extend(chord_violations(i, chord, part, &self.key));
+        }
+        violations
+    }
+
+    /// One voice as notated events, each chord held for a bar of the meter, for engraving on a staff.
+    pub fn to_events(&self, voice: ChoirVoice) -> Vec<RhythmicNotatedEvent<'static>> {
+        self.voice(voice).into_iter()
+            .flat_map(|pitch| bar_durations(&self.meter).into_iter()
+                .enumerate()
+                .map(move |(j, duration)| if j > 0 {
+                    RhythmicNotatedEvent::pitch_tied(pitch, duration)
+                } else {
+                    RhythmicNotatedEvent::pitch(pitch, duration)
+                }))
+            .collect()
+    }
+
+    /// The chorale as a choral score, to be placed in a Lilypond document, with the soprano and alto
+    /// on a treble staff over the tenor and bass on a bass staff, the upper voice of each with its stems up.
+    #[cfg(feature = "lilypond")]
+    pub fn to_lilypond(&self) -> Result<String, crate::notation::lilypond::error::LilypondError> {
+        use crate::notation::clef::Clef;
+        use crate::notation::lilypond::ToLilypondString;
+        use crate::notation::lilypond::document::staff::LilypondStaff;
+        use crate::notation::lilypond::scoring::score;
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+
+        let voice = |voice| self.to_events(voice).into_iter().map(Into::into).collect::<Vec<LilypondVoiceElement>>();
+        let staff = |clef, upper, lower| LilypondStaff::new()
+            .clef(Some(clef))
+            .meter(Some(self.meter.clone()))
+            .add_voice(voice(upper))
+            .add_voice(voice(lower))
+            .try_to_lilypond_string();
+        let treble = staff(Clef::Treble, ChoirVoice::Soprano, ChoirVoice::Alto)?;
+        let bass = staff(Clef::Bass, ChoirVoice::Tenor, ChoirVoice::Bass)?;
+        score(format!("\\new ChoirStaff <<\n{}\n{}\n>>", treble, bass), false)
+    }
+}
+
+/// Every voicing of `chord` with its root in the bass that keeps to the rules of range,
+/// spacing, and doubling.
+fn voicings(chord: &NoteSet, key: &Key) -> Result<Vec<SatbChord>, MusicSemanticsError> {
+    let root = *chord.first().ok_or(MusicSemanticsError::EmptySetOfNotes)?;
+    let required = required_notes(chord);
+    if required.len() > 4 {
+        return Err(MusicSemanticsError::TooManyNotes(4, required.len()));
+    }
+    let pitches = |voice: ChoirVoice, notes: &[Note]| {
+        let range = voice.range();
+        (range.lowest.midi_note..=range.highest.midi_note)
+            .filter_map(|midi_note| notes.iter().find(|note| Pc::from(*note) == Pc::from(&midi_note))
+                .map(|note| Pitch::new_spelled_as_in(midi_note, &vec![*note])))
+            .collect::<Result<Vec<Pitch>, MusicSemanticsError>>()
+    };
+    let basses = pitches(ChoirVoice::Bass, &[root])?;
+    let tenors = pitches(ChoirVoice::Tenor, chord)?;
+    let altos = pitches(ChoirVoice::Alto, chord)?;
+    let sopranos = pitches(ChoirVoice::Soprano, chord)?;
+    let mut voicings = vec![];
+    for bass in &basses {
+        for tenor in tenors.iter().filter(|tenor| tenor.midi_note >= bass.midi_note) {
+            for alto in altos.iter().filter(|alto| alto.midi_note >= tenor.midi_note) {
+                for soprano in sopranos.iter().filter(|soprano| soprano.midi_note >= alto.midi_note) {
+                    let voicing = SatbChord::new(*soprano, *alto, *tenor, *bass);
+                    if chord_violations(0, chord, &voicing, key).is_empty() {
+                        voicings.push(voicing);
+                    }
+                }
+            }
+        }
+    }
+    if voicings.is_empty() {
+        return Err(MusicSemanticsError::NoPlayableShape(chord.to_vec()));
+    }
+    Ok(voicings)
+}
+
+/// The notes of a chord that have to be sung, i.e. all but its fifth.
+fn required_notes(chord: &NoteSet) -> Vec<Note> {
+    let fifth = fifth(chord);
+    chord.iter().filter(|note| **note != fifth).copied().collect()
+}
+
+/// The rules of range, spacing, and doubling broken by the `i`th chord of a chorale.
+fn chord_violations(i: usize, chord: &NoteSet, part: &SatbChord, key: &Key) -> Vec<PartWritingViolation> {
+    let mut violations = vec![];
+    for voice in ChoirVoice::ALL {
+        if !voice.range().contains(&part.pitch(voice)) {
+            violations.push(PartWritingViolation::OutOfRange(i, voice));
+        }
+    }
+    for pair in ChoirVoice::ALL.windows(2) {
+        let (upper, lower) = (part.pitch(pair[0]).midi_note, part.pitch(pair[1]).midi_note);
+        if upper < lower {
+            violations.push(PartWritingViolation::VoiceCrossing(i, pair[0]));
+        } else if upper - lower > 12 && pair[1] != ChoirVoice::Bass {
+            violations.push(PartWritingViolation::Spacing(i, pair[0]));
+        }
+    }
+    let sung: Vec<Pc> = ChoirVoice::ALL.iter().map(|voice| Pc::from(&part.pitch(*voice).note)).collect();
+    for note in required_notes(chord) {
+        if !sung.contains(&Pc::from(&note)) {
+            violations.push(PartWritingViolation::MissingNote(i, note));
+        }
+    }
+    if sung.iter().filter(|pc| **pc == leading_tone(key)).count() > 1 {
+        violations.push(PartWritingViolation::DoubledLeadingTone(i));
+    }
+    violations
+}
+
+/// The rules of voice-leading broken moving from `from` to `to`, the `i`th chord of a chorale.
+fn motion_violations(i: usize, chord: &NoteSet, from: &SatbChord, to: &SatbChord, key: &Key) -> Vec<PartWritingViolation> {
+    let mut violations = vec![];
+    for (a, upper) in ChoirVoice::ALL.iter().enumerate() {
+        for lower in &ChoirVoice::ALL[a + 1..] {
+            let moved = from.pitch(*upper).midi_note != to.pitch(*upper).midi_note
+                && from.pitch(*lower).midi_note != to.pitch(*lower).midi_note;
+            let interval = |part: &SatbChord| part.pitch(*upper).midi_note.abs_diff(part.pitch(*lower).midi_note) % 12;
+            match (moved, interval(from), interval(to)) {
+                (true, 7, 7) => violations.push(PartWritingViolation::ParallelFifths(i, *upper, *lower)),
+                (true, 0, 0) => violations.push(PartWritingViolation::ParallelOctaves(i, *upper, *lower)),
+                _ => {},
+            }
+        }
+    }
+    for voice in ChoirVoice::ALL {
+        let leap = from.pitch(voice).midi_note.abs_diff(to.pitch(voice).midi_note);
+        if leap > if voice == ChoirVoice::Bass { 12 } else { 7 } {
+            violations.push(PartWritingViolation::LargeLeap(i, voice));
+        }
+    }
+    let tonic = Pc::from(&key.tonic);
+    if chord.iter().any(|note| Pc::from(note) == tonic) {
+        for voice in [ChoirVoice::Soprano, ChoirVoice::Bass] {
+            let (from, to) = (from.pitch(voice), to.pitch(voice));
+            if Pc::from(&from.note) == leading_tone(key) && to.midi_note != from.midi_note + 1 {
+                violations.push(PartWritingViolation::UnresolvedLeadingTone(i, voice));
+            }
+        }
+    }
+    violations
+}
+
+/// The note a half step below the tonic, raised in a minor key.
+fn leading_tone(key: &Key) -> Pc {
+    Pc::from(&key.tonic).previous()
+}
+
+/// How far a voicing strays from doubling the root: nothing for the root, then more for the
+/// fifth, and the most for any other note.
+fn doubling_cost(part: &SatbChord, chord: &NoteSet) -> usize {
+    let sung: Vec<Pc> = ChoirVoice::ALL.iter().map(|voice| Pc::from(&part.pitch(*voice).note)).collect();
+    let fifth = Pc::from(&fifth(chord));
+    chord.iter()
+        .map(|note| {
+            let doubled = sung.iter().filter(|pc| **pc == Pc::from(note)).count().saturating_sub(1);
+            match Pc::from(note) {
+                pc if pc == Pc::from(&chord[0]) => 0,
+                pc if pc == fifth => doubled,
+                _ => 3 * doubled,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::Duration;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note_collections::KeyMode;
+
+    fn triad(notes: [Note; 3]) -> NoteSet {
+        NoteSet::starting_from_first_note(notes.to_vec())
+    }
+
+    #[test]
+    fn four_part_writing() {
+        let key = Key::new(Note::C, KeyMode::Major).unwrap();
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        // I IV V I.
+        let progression = Progression::new(vec![
+            triad([Note::C, Note::E, Note::G]),
+            triad([Note::F, Note::A, Note::C]),
+            triad([Note::G, Note::B, Note::D]),
+            triad([Note::C, Note::E, Note::G]),
+        ]);
+        let chorale = SatbChorale::new(&progression, &key, &meter).unwrap();
+        assert!(chorale.violations().is_empty(), "{:?}", chorale.violations());
+        let lines: Vec<String> = ChoirVoice::ALL.iter()
+            .map(|voice| chorale.voice(*voice).iter().map(|pitch| pitch.to_string()).collect::<Vec<_>>().join(" "))
+            .collect();
+        // The leading tone, in the tenor, rises to the tonic.
+        assert_eq!(lines, vec!["G4 A4 G4 G4", "E4 F4 D4 E4", "C4 C4 B3 C4", "C3 F3 G3 C4"]);
+        assert_eq!(chorale.to_events(ChoirVoice::Alto)[0].duration(), Duration::WHOLE.ticks());
+
+        // Parallel fifths from the soprano, octaves from the tenor, and a doubled leading tone.
+        let pitch = |note, octave| Pitch::new(note, octave).unwrap();
+        let mut written = chorale.clone();
+        written.chords.truncate(3);
+        written.parts = vec![
+            SatbChord::new(pitch(Note::G, 4), pitch(Note::E, 4), pitch(Note::C, 4), pitch(Note::C, 3)),
+            SatbChord::new(pitch(Note::C, 5), pitch(Note::A, 4), pitch(Note::F, 4), pitch(Note::F, 3)),
+            SatbChord::new(pitch(Note::B, 4), pitch(Note::B, 3), pitch(Note::D, 4), pitch(Note::G, 2)),
+        ];
+        assert_eq!(written.violations(), vec![
+            PartWritingViolation::ParallelFifths(1, ChoirVoice::Soprano, ChoirVoice::Tenor),
+            PartWritingViolation::ParallelFifths(1, ChoirVoice::Soprano, ChoirVoice::Bass),
+            PartWritingViolation::ParallelOctaves(1, ChoirVoice::Tenor, ChoirVoice::Bass),
+            PartWritingViolation::LargeLeap(2, ChoirVoice::Alto),
+            PartWritingViolation::VoiceCrossing(2, ChoirVoice::Alto),
+            PartWritingViolation::DoubledLeadingTone(2),
+        ]);
+
+        // A ninth chord has five notes, four of which have to be sung.
+        let ninth = NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D, Note::F, Note::A]);
+        assert!(SatbChorale::new(&Progression::new(vec![ninth]), &key, &meter).is_ok());
+        let eleventh = NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D, Note::F, Note::A, Note::C]);
+        assert!(matches!(
+            SatbChorale::new(&Progression::new(vec![eleventh]), &key, &meter),
+            Err(MusicSemanticsError::TooManyNotes(4, 5)),
+        ));
+        #[cfg(feature = "lilypond")]
+        assert!(chorale.to_lilypond().unwrap().contains("\\new ChoirStaff"));
+    }
+}
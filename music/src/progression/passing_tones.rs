@@ -0,0 +1,82 @@
+use crate::notation::rhythm::duration::DurationTicks;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::WeightedPcSet;
+use crate::progression::segmentation::TimedNote;
+
+/// The pitch classes of a histogram that are heard as part of the harmony, rather than as
+/// passing or chromatic tones, e.g. to name a chord or scale from a melody.
+///
+/// `weights` is how much each pitch class sounds, e.g. for how long, from [Pc::Pc0] up, as for
+/// [crate::note_collections::Key::detect]. See [WeightedPcSet::salient_pcs].
+pub fn salient_pcs(weights: &[f64; 12], min_weight: f64) -> Vec<Pc> {
+    WeightedPcSet::from_weights(*weights).salient_pcs(min_weight)
+}
+
+/// The notes of `notes` that aren't likely passing tones, judged by where they fall in the meter.
+///
+/// A passing tone is shorter than a `beat`, starts off the beat, and moves by step, of a tone
+/// or semitone, to a note that starts as it ends. It must also have been reached by step from
+/// a note that ended as it started, unless it's a chromatic approach, a semitone from the note it moves to.
+/// Where several notes start or end together, e.g. under a chord, the closest in pitch is taken.
+pub fn remove_passing_notes(notes: &[TimedNote], beat: DurationTicks) -> Vec<TimedNote> {
+    // The smallest distance in semitones, other than a unison, to a note passing the test.
+    let step = |note: &TimedNote, touching: &dyn Fn(&TimedNote) -> bool| notes.iter()
+        .filter(|other| touching(other) && other.midi_note != note.midi_note)
+        .map(|other| other.midi_note.abs_diff(note.midi_note))
+        .min();
+    notes.iter()
+        .filter(|note| {
+            if beat == 0 || note.start % beat == 0 || note.end - note.start >= beat {
+                return true;
+            }
+            let next = step(note, &|other| other.start == note.end);
+            let previous = step(note, &|other| other.end == note.start);
+            let passing = match next {
+                Some(1) => true,
+                Some(2) => previous.is_some_and(|previous| previous <= 2),
+                _ => false,
+            };
+            !passing
+        })
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::Duration;
+    use crate::note::pitch_class::Pc::*;
+
+    #[test]
+    fn passing_tones() {
+        // Mostly C major, with a brief F and the C# of a chromatic approach to D.
+        let mut weights = [0.0; 12];
+        for (pc, weight) in [(0, 4.0), (1, 0.5), (2, 1.5), (4, 3.0), (5, 0.5), (7, 3.0)] {
+            weights[pc] = weight;
+        }
+        assert_eq!(salient_pcs(&weights, 0.2), vec![Pc0, Pc2, Pc4, Pc7]);
+        assert_eq!(salient_pcs(&weights, 0.5), vec![Pc0, Pc4, Pc7]);
+        assert!(salient_pcs(&[0.0; 12], 0.2).is_empty());
+
+        let eighth = Duration::EIGHTH.ticks();
+        let beat = Duration::QTR.ticks();
+        let note = |midi_note, start: usize, length: usize| TimedNote {
+            midi_note,
+            start: start * eighth,
+            end: (start + length) * eighth,
+        };
+        // C D E on the beat, passing through D; a leap to A, then G#, approaching A chromatically;
+        // and an F, leapt to and from, which is kept.
+        let melody = [
+            note(60, 0, 1), note(62, 1, 1), note(64, 2, 2),
+            note(57, 4, 1), note(68, 5, 1), note(69, 6, 2),
+            note(60, 8, 1), note(65, 9, 1), note(72, 10, 2),
+        ];
+        let kept: Vec<u8> = remove_passing_notes(&melody, beat).iter().map(|note| note.midi_note).collect();
+        assert_eq!(kept, vec![60, 64, 57, 69, 60, 65, 72]);
+        // Of the notes of a chord ending as the D starts, it's reached by step from the closest, the C.
+        let with_chord = [&melody[..3], &[note(48, 0, 1), note(55, 0, 1)]].concat();
+        assert_eq!(remove_passing_notes(&with_chord, beat).len(), 4);
+    }
+}
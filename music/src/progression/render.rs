@@ -0,0 +1,351 @@
+use std::path::Path;
+use itertools::Itertools;
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::chord_shape_search::find_chord_shapes;
+use crate::fretboard::fretted_note::{FrettedNote, SoundedNote};
+use crate::notation::midi::{MidiFile, MidiInstrument};
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::meter::Meter;
+use crate::notation::rhythm::tempo::Tempo;
+use crate::notation::vextab::ToVexTab;
+use crate::note::pitch_class::Pc;
+use crate::progression::Progression;
+use crate::progression::chart::chart_bars;
+
+/// How many bars to engrave on each line of tab.
+const BARS_PER_STAVE: usize = 4;
+
+/// Renders a [Progression] as tab, one chord per bar, and writes it to a VexTab file.
+///
+/// This is a full pipeline over the crate: each chord is named, a playable shape is
+/// chosen for it on `fretboard` with [voice_led_shapes], and the shape is held
+/// for a bar of `meter`. See [progression_to_vextab] for the resulting notation.
+pub fn render_progression_tab<P: AsRef<Path>>(
+    progression: &Progression,
+    fretboard: &Fretboard,
+    meter: &Meter,
+    path: P,
+) -> Result<(), MusicSemanticsError> {
+    let vextab = progression_to_vextab(progression, fretboard, meter)?;
+    std::fs::write(path, vextab)
+        .map_err(|e| MusicSemanticsError::FileWriteFailure(e.to_string()))
+}
+
+/// Renders a [Progression] as VexTab source, with its chords laid out over
+/// the bars of `meter` by [chart_bars], and each chord's name annotated where it's struck.
+///
+/// A bar held by one chord is written as a single duration where possible. Otherwise it is
+/// divided along the big beats of `meter`, and the chord is tied across them.
+/// A chord held over the end of a stave is struck again, and named, at the start of the next.
+pub fn progression_to_vextab(
+    progression: &Progression,
+    fretboard: &Fretboard,
+    meter: &Meter,
+) -> Result<String, MusicSemanticsError> {
+    let names = progression.chord_names()?;
+    let shapes = voice_led_shapes(progression, fretboard)?;
+    let bars: Vec<String> = chart_bars(progression, meter)?.iter()
+        .enumerate()
+        .map(|(i, bar)| bar.iter()
+            .enumerate()
+            .map(|(j, slot)| {
+                // A tie can't be carried over the break to a new stave, so the chord is struck again.
+                let tied = slot.tied && !(i % BARS_PER_STAVE == 0 && j == 0);
+                let notes = sounded_notes(&shapes[slot.chord]);
+                let events = slot.durations.iter()
+                    .enumerate()
+                    .map(|(k, duration)| if k == 0 && !tied {
+                        RhythmicNotatedEvent::fretted_many(notes.clone(), *duration)
+                    } else {
+                        RhythmicNotatedEvent::fretted_many_tied(notes.clone(), *duration)
+                    })
+                    .map(|event| event.to_vextab())
+                    .join(" ");
+                if tied {
+                    events
+                } else {
+                    format!("{} ${}$", events, names[slot.chord].symbol(None))
+                }
+            })
+            .join(" "))
+        .collect();
+    Ok(vextab_staves(&bars, meter))
+}
+
+/// Plays a [Progression] on `fretboard` as the first track of a MIDI file at `tempo`, on `instrument`,
+/// with the same shapes, laid out over the bars of `meter` in the same way, as [progression_to_vextab].
+/// A chord held over a bar line sounds on, rather than being struck again.
+///
+/// Other parts, e.g. a [crate::progression::BassLine], can be added on tracks of their own,
+/// each on its own instrument, with [MidiFile::with_instrument] and [MidiFile::with_events].
+pub fn progression_to_midi(
+    progression: &Progression,
+    fretboard: &Fretboard,
+    meter: &Meter,
+    tempo: &Tempo,
+    instrument: MidiInstrument,
+) -> Result<MidiFile, MusicSemanticsError> {
+    let shapes = voice_led_shapes(progression, fretboard)?;
+    let mut events = vec![];
+    for slot in chart_bars(progression, meter)?.iter().flatten() {
+        let notes = sounded_notes(&shapes[slot.chord]);
+        for (k, duration) in slot.durations.iter().enumerate() {
+            events.push(if k == 0 && !slot.tied {
+                RhythmicNotatedEvent::fretted_many(notes.clone(), *duration)
+            } else {
+                RhythmicNotatedEvent::fretted_many_tied(notes.clone(), *duration)
+            });
+        }
+    }
+    Ok(MidiFile::new(meter, tempo)
+        .with_instrument(0, instrument)
+        .with_events(0, &events))
+}
+
+fn sounded_notes<'a>(shape: &FretboardShape<'a>) -> Vec<SoundedNote<'a>> {
+    shape.iter()
+        .filter_map(|note| match note {
+            FrettedNote::Sounded(sounded) => Some(sounded.clone()),
+            FrettedNote::Muted { .. } => None,
+        })
+        .collect()
+}
+
+/// Bars of VexTab notes, [BARS_PER_STAVE] to a stave of notation and tab,
+/// with the time signature of `meter` on the first.
+pub(crate) fn vextab_staves(bars: &[String], meter: &Meter) -> String {
+    let time = format!("{}/{}", meter.num_beats, meter.denominator.to_string());
+    bars.chunks(BARS_PER_STAVE)
+        .enumerate()
+        .map(|(i, line)| {
+            let stave = if i == 0 {
+                format!("tabstave notation=true time={}", time)
+            } else {
+                "tabstave notation=true".to_string()
+            };
+            format!("{}\nnotes {} |", stave, line.join(" | "))
+        })
+        .join("\n\n")
+}
+
+/// Chooses one playable shape for each chord of a [Progression], such that the hand
+/// moves as little as possible from one chord to the next.
+///
+/// Shapes with the root of the chord in the bass are always preferred.
+/// The first chord takes the shape lowest on the neck.
+/// Each subsequent chord takes the shape closest to the previous choice,
+/// as measured by the distance in frets that each string moves.
+pub fn voice_led_shapes<'a>(
+    progression: &Progression,
+    fretboard: &'a Fretboard,
+) -> Result<Vec<FretboardShape<'a>>, MusicSemanticsError> {
+    let mut shapes: Vec<FretboardShape<'a>> = vec![];
+    for chord in &progression.chords {
+        let root = chord.first()
+            .map(Pc::from)
+            .ok_or(MusicSemanticsError::EmptySetOfNotes)?;
+        let result = find_chord_shapes(chord, fretboard)?;
+        let shape = result.playable.into_values()
+            .chain(result.nontransposable.into_values())
+            .flatten()
+            .min_by_key(|shape| {
+                let root_in_bass = Pc::from(&shape.range().0.note) == root;
+                let cost = match shapes.last() {
+                    Some(previous) => movement(previous, shape),
+                    None => shape.span().1 as usize,
+                };
+                (!root_in_bass, cost, frets(shape))
+            })
+            .ok_or(MusicSemanticsError::NoPlayableShape((**chord).clone()))?;
+        shapes.push(shape);
+    }
+    Ok(shapes)
+}
+
+/// The total distance in frets that each string moves between two chord shapes.
+/// A string that stops sounding counts as one fret of movement. A string that
+/// starts sounding counts as one fret, plus how far the hand must reach from
+/// the frets it held in the previous shape.
+fn movement(from: &FretboardShape, to: &FretboardShape) -> usize {
+    let held: Vec<u8> = frets(from).into_iter()
+        .flatten()
+        .filter(|fret| *fret > 0)
+        .collect();
+    let reach = |fret: u8| match (held.iter().min(), held.iter().max()) {
+        _ if fret == 0 => 0,
+        (Some(low), Some(high)) => low.saturating_sub(fret).max(fret.saturating_sub(*high)),
+        _ => fret,
+    };
+    frets(from).iter()
+        .zip(frets(to).iter())
+        .map(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) => a.abs_diff(*b) as usize,
+            (None, Some(b)) => 1 + reach(*b) as usize,
+            (Some(_), None) => 1,
+            (None, None) => 0,
+        })
+        .sum()
+}
+
+fn frets(shape: &FretboardShape) -> Vec<Option<u8>> {
+    shape.iter().map(FrettedNote::fret).collect()
+}
+
+/// The durations that make up a single bar of `meter`.
+pub(crate) fn bar_durations(meter: &Meter) -> Vec<Duration> {
+    let beat: Duration = (&meter.denominator).into();
+    if let Some(bar) = Duration::try_from_ticks(beat.ticks() * meter.num_beats) {
+        return vec![bar];
+    }
+    big_beat_durations(meter).concat()
+}
+
+/// The durations that make up each big beat of a bar of `meter`.
+/// A big beat that can't be written as a single duration is divided, to be tied across.
+pub(crate) fn big_beat_durations(meter: &Meter) -> Vec<Vec<Duration>> {
+    let beat: Duration = (&meter.denominator).into();
+    // The beat pattern is measured in the units of [crate::notation::rhythm::meter::MeterDenominator::ticks].
+    let durations: Vec<Vec<Duration>> = meter.beat_pattern.iter()
+        .map(|group| {
+            let num_beats = group / meter.denominator.ticks();
+            Duration::from_ticks(beat.ticks() * num_beats)
+        })
+        .filter(|durations| !durations.is_empty())
+        .collect();
+    if durations.is_empty() {
+        return vec![vec![beat]; meter.num_beats];
+    }
+    durations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::{STD_4STR_BASS, STD_6STR_GTR};
+    use crate::notation::midi::GeneralMidiProgram;
+    use crate::notation::rhythm::meter::MeterDenominator;
+    use crate::note::note::Note;
+    use crate::note_collections::NoteSet;
+    use crate::progression::{BassLine, BassStyle, InstrumentRange};
+
+    fn progression() -> Progression {
+        Progression::new(vec![
+            NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G]),
+            NoteSet::starting_from_first_note(vec![Note::A, Note::C, Note::E]),
+            NoteSet::starting_from_first_note(vec![Note::F, Note::A, Note::C]),
+            NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D]),
+            NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G]),
+        ])
+    }
+
+    #[test]
+    fn voice_led_progression() {
+        let shapes = voice_led_shapes(&progression(), &STD_6STR_GTR).unwrap();
+        assert_eq!(shapes.len(), 5);
+        for (shape, chord) in shapes.iter().zip(progression().chords.iter()) {
+            assert_eq!(Pc::from(&shape.range().0.note), Pc::from(&chord[0]));
+        }
+        // Each change of chord stays in the neighborhood of the last.
+        for pair in shapes.windows(2) {
+            assert!(movement(&pair[0], &pair[1]) <= 8);
+        }
+    }
+
+    #[test]
+    fn progression_tab_pipeline() {
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let vextab = progression_to_vextab(&progression(), &STD_6STR_GTR, &meter).unwrap();
+        let lines: Vec<&str> = vextab.lines().collect();
+        assert_eq!(lines[0], "tabstave notation=true time=4/4");
+        assert!(lines[1].starts_with("notes :w ("));
+        assert_eq!(lines[1].matches(" | ").count(), 3);
+        assert!(lines[1].contains("$C"));
+        assert!(lines[1].contains("$A"));
+        // The fifth bar wraps onto a new stave.
+        assert_eq!(lines[3], "tabstave notation=true");
+
+        // Five beats don't fit in a single note, so the chord is tied across the big beats.
+        let meter = Meter::new(5, MeterDenominator::Four, None);
+        let vextab = progression_to_vextab(&progression(), &STD_6STR_GTR, &meter).unwrap();
+        assert!(vextab.contains(":hd ("));
+        assert!(vextab.contains(":h T("));
+
+        let path = std::env::temp_dir().join("rust_music_progression_tab.vextab");
+        render_progression_tab(&progression(), &STD_6STR_GTR, &meter, &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), vextab);
+        std::fs::remove_file(&path).unwrap();
+
+        // Two chords to a bar, and a chord held over the bar line.
+        let bar = Duration::WHOLE.ticks();
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let split = progression().with_durations(vec![bar / 2, bar / 2, bar * 2, bar, bar]);
+        let vextab = progression_to_vextab(&split, &STD_6STR_GTR, &meter).unwrap();
+        let lines: Vec<&str> = vextab.lines().collect();
+        let bars: Vec<&str> = lines[1].split(" | ").collect();
+        assert!(bars[0].starts_with("notes :h ("));
+        assert_eq!(bars[0].matches('$').count(), 4);
+        assert!(bars[2].starts_with(":w T("));
+        assert!(!bars[2].contains('$'));
+
+        // A chord held from the fourth bar into the fifth, over the break between staves.
+        let held = progression().with_durations(vec![bar, bar, bar, bar * 2, bar]);
+        let vextab = progression_to_vextab(&held, &STD_6STR_GTR, &meter).unwrap();
+        let lines: Vec<&str> = vextab.lines().collect();
+        assert_eq!(lines[3], "tabstave notation=true");
+        assert!(lines[4].starts_with("notes :w ("));
+        assert!(lines[4].contains("$GMaj$"));
+    }
+
+    #[test]
+    fn progression_midi() {
+        let bar = Duration::WHOLE.ticks();
+        let meter = Meter::new(4, MeterDenominator::Four, None);
+        let held = progression().with_durations(vec![bar, bar, bar, bar * 2, bar]);
+        let tempo = Tempo::new(Duration::QTR, 120);
+        let guitar = MidiInstrument::new(0, GeneralMidiProgram::AcousticGuitarSteel);
+        let file = progression_to_midi(&held, &STD_6STR_GTR, &meter, &tempo, guitar).unwrap();
+        let shapes = voice_led_shapes(&held, &STD_6STR_GTR).unwrap();
+        // Each chord is struck once, with the pitches of its shape, and released a little before the next.
+        let chords: Vec<(u64, u64, Vec<u8>)> = file.notes.iter()
+            .group_by(|note| (note.start, note.end))
+            .into_iter()
+            .map(|((start, end), notes)| (start, end, notes.map(|note| note.midi_note).collect()))
+            .collect();
+        let pitches = |shape: &FretboardShape| sounded_notes(shape).iter().map(|note| note.pitch.midi_note).sorted().collect();
+        assert_eq!(chords, vec![
+            (0, 1680, pitches(&shapes[0])),
+            (1920, 3600, pitches(&shapes[1])),
+            (3840, 5520, pitches(&shapes[2])),
+            // Held over the bar line.
+            (5760, 9360, pitches(&shapes[3])),
+            (9600, 11280, pitches(&shapes[4])),
+        ]);
+        assert!(file.notes.iter().all(|note| note.velocity == 80 && note.channel == 0));
+        let bytes = file.to_bytes();
+        assert_eq!(&bytes[22..40], &[0, 0xFF, 0x51, 3, 0x07, 0xA1, 0x20, 0, 0xFF, 0x58, 4, 4, 2, 24, 8, 0, 0xC0, 25]);
+        assert_eq!(MidiFile::parse(&bytes).unwrap(), file);
+
+        // With a bass line on a track, and an instrument, of its own.
+        let piano = MidiInstrument::new(0, GeneralMidiProgram::AcousticGrandPiano);
+        let bass = MidiInstrument::new(1, GeneralMidiProgram::ElectricBassFinger);
+        let range = InstrumentRange::of_fretted(&*STD_4STR_BASS, 12).unwrap();
+        let bass_line = BassLine::new(&progression(), &meter, BassStyle::Roots, &range).unwrap();
+        let file = progression_to_midi(&progression(), &STD_6STR_GTR, &meter, &tempo, piano).unwrap()
+            .with_instrument(1, bass)
+            .with_events(1, &bass_line.to_events());
+        let roots: Vec<(u8, u8, u64)> = file.notes.iter()
+            .filter(|note| note.track == 1)
+            .map(|note| (note.midi_note, note.channel, note.start))
+            .collect();
+        assert_eq!(roots, vec![(36, 1, 0), (33, 1, 1920), (29, 1, 3840), (31, 1, 5760), (36, 1, 7680)]);
+        assert!(file.notes.iter().filter(|note| note.track == 0).all(|note| note.channel == 0));
+        let bytes = file.to_bytes();
+        assert_eq!(&bytes[10..12], &[0, 2]);
+        let second_track = bytes.windows(4).enumerate().filter(|(_, w)| *w == b"MTrk").nth(1).unwrap().0;
+        assert_eq!(&bytes[second_track + 8..second_track + 11], &[0, 0xC1, 33]);
+        assert_eq!(MidiFile::parse(&bytes).unwrap(), file);
+    }
+}
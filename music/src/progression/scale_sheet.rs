@@ -0,0 +1,173 @@
+use itertools::Itertools;
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretted_instrument::FrettedInstrument;
+use crate::fretboard::fretted_note::SoundedNote;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::Duration;
+use crate::note::note::Note;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::PcSet;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::chord_name::quality::scale::ScaleMode;
+use crate::note_collections::chord_scale::chord_scale;
+use crate::note_collections::spelling::spell_pc_set;
+use crate::progression::Progression;
+use crate::progression::etude::POSITION_SPAN;
+
+/// The scale to play over one chord of a [ScaleSheet].
+#[derive(Debug, Clone)]
+pub struct SheetScale<'a> {
+    pub chord: ChordName,
+    pub scale: ScaleMode,
+    /// The notes of the scale, spelled from the root of the chord.
+    pub notes: Vec<Note>,
+    /// One octave of the scale, from its root up to the root an octave above, within the position.
+    pub octave: Vec<SoundedNote<'a>>,
+}
+
+impl<'a> SheetScale<'a> {
+    /// The octave of the scale in eighth notes, a bar of 4/4 for a seven note scale.
+    pub fn to_events(&self) -> Vec<RhythmicNotatedEvent<'a>> {
+        self.octave.iter()
+            .map(|note| RhythmicNotatedEvent::fretted(note.clone(), Duration::EIGHTH))
+            .collect()
+    }
+}
+
+/// What to play over the changes: for each chord of a [Progression], the scale recommended
+/// over it by [chord_scale], and one octave of the scale in one position on the neck.
+#[derive(Debug, Clone)]
+pub struct ScaleSheet<'a> {
+    pub scales: Vec<SheetScale<'a>>,
+}
+
+impl<'a> ScaleSheet<'a> {
+    /// The scales over a progression, played from the fret `position` up to three frets above it,
+    /// with open strings only in the open position, i.e. 0. Each octave is played from the lowest
+    /// root in the position that the whole octave can be played up from, crossing to higher
+    /// strings only when it must.
+    ///
+    /// Errors if a chord can't be named, if no scale has every note of a chord,
+    /// or if no octave of a scale can be played within the position.
+    pub fn new(
+        progression: &Progression,
+        fretboard: &'a Fretboard,
+        position: u8,
+    ) -> Result<Self, MusicSemanticsError> {
+        let names = progression.chord_names()?;
+        let scales = progression.chords.iter()
+            .zip(names)
+            .map(|(chord, name)| {
+                let root = chord.first().ok_or(MusicSemanticsError::EmptySetOfNotes)?;
+                let scale = chord_scale(chord).ok_or(MusicSemanticsError::NoChordScale(chord.to_vec()))?;
+                let notes = spell_pc_set(root, &PcSet::from(scale.pcs()))?;
+                let octave = octave_in_position(&notes, fretboard, position)
+                    .ok_or(MusicSemanticsError::ScaleOutOfPosition(*root, position))?;
+                Ok(SheetScale { chord: name, scale, notes, octave })
+            })
+            .collect::<Result<Vec<_>, MusicSemanticsError>>()?;
+        Ok(Self { scales })
+    }
+
+    /// The sheet as a Lilypond document: each chord's name and scale, over its octave
+    /// in notation and tab.
+    #[cfg(feature = "lilypond")]
+    pub fn to_lilypond(&self, title: &str) -> Result<String, crate::notation::lilypond::error::LilypondError> {
+        use crate::notation::clef::Clef;
+        use crate::notation::escape::lilypond_string;
+        use crate::notation::lilypond::ToLilypondString;
+        use crate::notation::lilypond::document::LilypondHeader;
+        use crate::notation::lilypond::document::staff::LilypondStaff;
+        use crate::notation::lilypond::document::tab_staff::LilypondTabStaff;
+        use crate::notation::lilypond::format::{format_lilypond, LilypondFormat};
+        use crate::notation::lilypond::scoring::{markup, score};
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+        use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+
+        let mut blocks = vec![LilypondHeader::new().title(Some(title.to_string())).try_to_lilypond_string()?];
+        for scale in &self.scales {
+            let heading = format!("{}: {} {}", scale.chord.symbol(None), scale.notes[0], scale.scale);
+            blocks.push(markup(format!("\\bold {}", lilypond_string(&heading))));
+            let voice = || scale.to_events().into_iter().map(Into::into).collect::<Vec<LilypondVoiceElement>>();
+            let staff = LilypondStaff::new()
+                .clef(Some(Clef::Treble8ba))
+                .meter(Some(Meter::new(4, MeterDenominator::Four, None)))
+                .add_voice(voice())
+                .try_to_lilypond_string()?;
+            let tab = LilypondTabStaff::new().add_voice(voice()).try_to_lilypond_string()?;
+            blocks.push(score(format!("<<\n{}\n{}\n>>", staff, tab), false)?);
+        }
+        Ok(format_lilypond(&blocks.join("\n"), LilypondFormat::Pretty))
+    }
+}
+
+/// One octave of a scale, given from its root, within the frets of a position.
+fn octave_in_position<'a>(notes: &[Note], fretboard: &'a Fretboard, position: u8) -> Option<Vec<SoundedNote<'a>>> {
+    let root = notes.first()?;
+    let frets = position..position + POSITION_SPAN;
+    let intervals: Vec<u8> = notes.iter()
+        .map(|note| root.distance_up_to_note(note))
+        .chain([12])
+        .collect();
+    (0..fretboard.num_strings())
+        .cartesian_product(frets.clone())
+        .filter_map(|(string, fret)| fretboard.sounded_note(string, fret).ok())
+        .filter(|note| Pc::from(&note.pitch.note) == Pc::from(root))
+        .sorted_by_key(|note| note.pitch.midi_note)
+        .find_map(|start| {
+            let mut string = start.string;
+            intervals.iter()
+                .zip(notes.iter().chain([root]))
+                .map(|(interval, note)| {
+                    let pitch = start.pitch.at_distance_from(*interval as isize).ok()?;
+                    let (on, fret) = (string..fretboard.num_strings())
+                        .find_map(|on| fretboard.fret_for_pitch(on, &pitch).ok()
+                            .filter(|fret| frets.contains(fret))
+                            .map(|fret| (on, fret)))?;
+                    string = on;
+                    fretboard.sounded_note(on, fret).ok()?.spelled_as_in(&vec![*note]).ok()
+                })
+                .collect()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::{STD_4STR_BASS, STD_6STR_GTR};
+    use crate::note_collections::NoteSet;
+
+    #[test]
+    fn scale_sheets() {
+        let progression = Progression::new(vec![
+            NoteSet::starting_from_first_note(vec![Note::D, Note::F, Note::A, Note::C]),
+            NoteSet::starting_from_first_note(vec![Note::G, Note::B, Note::D, Note::F]),
+            NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G, Note::B]),
+            NoteSet::starting_from_first_note(vec![Note::A, Note::Cis, Note::E, Note::G, Note::Bes]),
+        ]);
+        let sheet = ScaleSheet::new(&progression, &STD_6STR_GTR, 2).unwrap();
+        let scales: Vec<ScaleMode> = sheet.scales.iter().map(|scale| scale.scale).collect();
+        assert_eq!(scales, vec![ScaleMode::Dorian, ScaleMode::Mixolydian, ScaleMode::Major, ScaleMode::DimHW]);
+        for scale in &sheet.scales {
+            assert_eq!(scale.octave.len(), scale.notes.len() + 1);
+            assert!(scale.octave.iter().all(|note| (2..=5).contains(&note.fret)));
+            assert!(scale.octave.windows(2).all(|notes| notes[0].string <= notes[1].string));
+            let (first, last) = (&scale.octave[0].pitch, &scale.octave[scale.notes.len()].pitch);
+            assert_eq!(last.midi_note - first.midi_note, 12);
+        }
+        // D Dorian from the D on the A string, as the low E string has no D in the position.
+        let dorian: Vec<String> = sheet.scales[0].octave.iter().map(|note| note.to_string()).collect();
+        assert_eq!(dorian.join(" "), "2:5(D) 3:2(E) 3:3(F) 3:5(G) 4:2(A) 4:4(B) 4:5(C) 5:3(D)");
+        assert_eq!(sheet.scales[1].octave[0].to_string(), "1:3(G)");
+        assert_eq!(sheet.scales[0].to_events().len(), 8);
+
+        // A bass, tuned in fourths throughout, can't reach an octave of D Dorian within frets 2 to 5.
+        assert!(matches!(
+            ScaleSheet::new(&progression, &STD_4STR_BASS, 2),
+            Err(MusicSemanticsError::ScaleOutOfPosition(Note::D, 2)),
+        ));
+        #[cfg(feature = "lilypond")]
+        assert!(sheet.to_lilypond("Over the Changes").unwrap().contains("Dmin7: D Dorian"));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::MusicSemanticsError;
+use crate::notation::rhythm::duration::DurationTicks;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::{Key, NoteSet, PcSet, WeightedPcSet};
+use crate::note_collections::spelling::spell_pc_set;
+
+/// The chords a stretch of music is fitted to by [segment_harmony], as semitones above the root:
+/// the four triads, and the five common seventh chords.
+const CHORD_TEMPLATES: [&[u8]; 9] = [
+    &[0, 4, 7],
+    &[0, 3, 7],
+    &[0, 3, 6],
+    &[0, 4, 8],
+    &[0, 4, 7, 10],
+    &[0, 4, 7, 11],
+    &[0, 3, 7, 10],
+    &[0, 3, 6, 10],
+    &[0, 3, 6, 9],
+];
+/// The most stretches between consecutive note onsets or releases that one region may span,
+/// which bounds the search on long performances.
+const MAX_REGION_SPAN: usize = 64;
+
+/// A note of a performance, timed from its start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimedNote {
+    pub midi_note: u8,
+    pub start: DurationTicks,
+    pub end: DurationTicks,
+}
+
+/// A stretch of music over which one chord holds, as found by [segment_harmony].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicRegion {
+    pub start: DurationTicks,
+    pub end: DurationTicks,
+    pub root: Pc,
+    /// The chord, as semitones above the root.
+    pub chord: PcSet,
+    /// Every MIDI note sounding in the region, chord tone or not, from the lowest.
+    pub midi_notes: Vec<u8>,
+}
+
+impl HarmonicRegion {
+    /// The notes of the chord, spelled from its root as the root would be in `key`.
+    pub fn chord_in_key(&self, key: &Key) -> Result<NoteSet, MusicSemanticsError> {
+        Ok(NoteSet::starting_from_first_note(spell_pc_set(&key.spell_pc(&self.root), &self.chord)?))
+    }
+}
+
+/// Splits a stream of notes into regions of stable harmony, without it having to be
+/// cut into bars or beats first, so that chords may change anywhere, e.g. mid-bar.
+///
+/// The stream is cut at every note onset and release, and the cuts are joined into regions,
+/// each fitted to whichever chord of [CHORD_TEMPLATES] explains it best. A chord's fit gains
+/// for as long as each of its tones sounds, and loses for as long as any other note sounds,
+/// so that passing tones are outweighed by the chord around them. It loses half as much
+/// for as long as each of its tones is silent, so that e.g. C E G followed by A C E is heard as
+/// two chords rather than one Am7, but an arpeggio is still heard as one. And it loses the whole
+/// length of the region for each of its tones that never sounds, so that e.g. G B F is heard as G7 rather than G.
+/// The regions are chosen to fit best overall, each costing `change_penalty`, which
+/// keeps brief departures from the harmony, e.g. an arpeggio or a run, from becoming chords of their own.
+///
+/// Silence counts for nothing, and is taken into the region before it.
+pub fn segment_harmony(notes: &[TimedNote], change_penalty: DurationTicks) -> Vec<HarmonicRegion> {
+    let mut times: Vec<DurationTicks> = notes.iter()
+        .filter(|note| note.end > note.start)
+        .flat_map(|note| [note.start, note.end])
+        .collect();
+    times.sort();
+    times.dedup();
+    // The pitch classes sounding throughout each stretch between consecutive times, and its length.
+    let stretches: Vec<([bool; 12], DurationTicks)> = times.windows(2)
+        .map(|pair| {
+            let mut sounding = [false; 12];
+            for note in notes.iter().filter(|note| note.start <= pair[0] && note.end >= pair[1]) {
+                sounding[usize::from(u8::from(&Pc::from(&note.midi_note)))] = true;
+            }
+            (sounding, pair[1] - pair[0])
+        })
+        .collect();
+
+    // The best segmentation of the first `j` stretches, as its total fit, the start of its
+    // last region, and the root and template that region is fitted to.
+    let penalty = 2 * change_penalty as i64;
+    let mut best: Vec<(i64, usize, u8, usize)> = vec![(0, 0, 0, 0)];
+    for j in 1..=stretches.len() {
+        let mut weights = [0i64; 12];
+        let mut sounding_length = 0;
+        let mut best_here: Option<(i64, usize, u8, usize)> = None;
+        for i in (j.saturating_sub(MAX_REGION_SPAN)..j).rev() {
+            let (sounding, length) = &stretches[i];
+            let length = *length as i64;
+            if sounding.iter().any(|pc| *pc) {
+                sounding_length += length;
+            }
+            for (weight, sounds) in weights.iter_mut().zip(sounding) {
+                if *sounds {
+                    *weight += length;
+                }
+            }
+            let (fit, root, template) = best_fit(&weights, sounding_length);
+            let total = best[i].0 + fit - penalty;
+            // Ties go to the shorter region, so that silence is taken into the region before it.
+            if best_here.is_none_or(|(best_total, ..)| total > best_total) {
+                best_here = Some((total, i, root, template));
+            }
+        }
+        best.push(best_here.unwrap());
+    }
+
+    let mut regions = vec![];
+    let mut j = stretches.len();
+    while j > 0 {
+        let (_, i, root, template) = best[j];
+        let start = times[i];
+        let end = times[j];
+        let mut midi_notes: Vec<u8> = notes.iter()
+            .filter(|note| note.start < end && note.end > start)
+            .map(|note| note.midi_note)
+            .collect();
+        midi_notes.sort();
+        midi_notes.dedup();
+        regions.push(HarmonicRegion {
+            start,
+            end,
+            root: Pc::from(&root),
+            chord: PcSet::from(CHORD_TEMPLATES[template].to_vec()),
+            midi_notes,
+        });
+        j = i;
+    }
+    regions.reverse();
+    regions
+}
+
+/// How much each pitch class of `notes` is heard: for how long its notes sound, with those struck
+/// on a `beat` counting half as much again, since they're more likely chord tones than passing ones.
+pub fn weigh_notes(notes: &[TimedNote], beat: DurationTicks) -> WeightedPcSet {
+    notes.iter()
+        .map(|note| {
+            let length = note.end.saturating_sub(note.start) as f64;
+            let on_beat = beat > 0 && note.start % beat == 0;
+            (Pc::from(&note.midi_note), if on_beat { 1.5 * length } else { length })
+        })
+        .collect()
+}
+
+/// How well the best fitting chord explains some pitch classes, each sounding for as long
+/// as its weight in a region where notes sound for `length`, in half ticks, and that chord's root
+/// and index in [CHORD_TEMPLATES]. See [segment_harmony].
+fn best_fit(weights: &[i64; 12], length: i64) -> (i64, u8, usize) {
+    let total: i64 = weights.iter().sum();
+    let mut best = (i64::MIN, 0, 0);
+    for (template, intervals) in CHORD_TEMPLATES.iter().enumerate() {
+        for root in 0..12u8 {
+            let tones = intervals.iter().map(|interval| weights[usize::from((root + interval) % 12)]);
+            let in_chord: i64 = tones.clone().sum();
+            let silent: i64 = tones.clone().map(|weight| length - weight).sum();
+            let missing = tones.filter(|weight| *weight == 0).count() as i64;
+            // A half tick less for each tone beyond a triad decides ties in favour of the simpler chord.
+            let fit = 2 * (in_chord - (total - in_chord) - missing * length) - silent - (intervals.len() as i64 - 3);
+            if fit > best.0 {
+                best = (fit, root, template);
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::rhythm::duration::Duration;
+    use crate::note::note::Note;
+    use crate::note_collections::KeyMode;
+
+    #[test]
+    fn harmonic_regions() {
+        let eighth = Duration::EIGHTH.ticks();
+        let note = |midi_note, start: usize, length: usize| TimedNote {
+            midi_note,
+            start: start * eighth,
+            end: (start + length) * eighth,
+        };
+        // A bar of C major arpeggiated, with a passing D, that changes to G7 after three beats,
+        // then, after a rest, A minor played as a block chord.
+        let notes = [
+            note(48, 0, 6), note(60, 0, 1), note(64, 1, 1), note(67, 2, 1), note(62, 3, 1), note(64, 4, 1), note(72, 5, 1),
+            note(43, 6, 4), note(71, 6, 1), note(74, 7, 1), note(65, 8, 1), note(71, 9, 1),
+            note(45, 12, 4), note(60, 12, 4), note(64, 12, 4),
+        ];
+        let regions = segment_harmony(&notes, eighth);
+        let found: Vec<(usize, usize, Pc, PcSet)> = regions.iter()
+            .map(|region| (region.start / eighth, region.end / eighth, region.root, region.chord.clone()))
+            .collect();
+        assert_eq!(found, vec![
+            (0, 6, Pc::Pc0, PcSet::from(vec![0, 4, 7])),
+            (6, 12, Pc::Pc7, PcSet::from(vec![0, 4, 7, 10])),
+            (12, 16, Pc::Pc9, PcSet::from(vec![0, 3, 7])),
+        ]);
+        assert_eq!(regions[1].midi_notes, vec![43, 65, 71, 74]);
+        let key = Key::new(Note::C, KeyMode::Major).unwrap();
+        assert_eq!(regions[1].chord_in_key(&key).unwrap().to_vec(), vec![Note::G, Note::B, Note::D, Note::F]);
+        // The G7's D, off the beat, weighs as much as its length, and its B, once on the beat, half as much again.
+        let weights = weigh_notes(&notes[7..12], 2 * eighth);
+        assert_eq!(weights.weight(&Pc::Pc2), eighth as f64);
+        assert_eq!(weights.weight(&Pc::Pc11), 2.5 * eighth as f64);
+
+        // Without a cost for each change, every note onset may start a new chord.
+        assert!(segment_harmony(&notes, 0).len() > 3);
+        assert!(segment_harmony(&[], eighth).is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::fretted_note::SoundedNote;
+use crate::notation::rhythm::{NotatedEvent, RhythmicNotatedEvent, SingleEvent};
+use crate::notation::rhythm::duration::{Duration, DurationTicks};
+use crate::notation::rhythm::meter::Meter;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::Voicing;
+use crate::note_collections::geometry::symmetry::transpositional::Transpose;
+use crate::note_collections::key::{Key, KeyMode};
+use crate::progression::Progression;
+use crate::progression::lead_sheet::LeadSheet;
+
+/// A named part of a [Song], e.g. a verse or a chorus, in its own key and meter.
+#[derive(Debug, Clone)]
+pub struct SongSection<'a> {
+    pub name: String,
+    /// The key, meter, and chords of the section.
+    pub lead_sheet: LeadSheet,
+    /// The melody sung or played over the chords, from the start of the section.
+    pub melody: Vec<RhythmicNotatedEvent<'a>>,
+}
+
+impl<'a> SongSection<'a> {
+    pub fn new(name: &str, key: Key, meter: Meter, progression: Progression) -> Self {
+        Self {
+            name: name.to_string(),
+            lead_sheet: LeadSheet { key, meter, progression },
+            melody: vec![],
+        }
+    }
+
+    pub fn with_melody(mut self, melody: Vec<RhythmicNotatedEvent<'a>>) -> Self {
+        self.melody = melody;
+        self
+    }
+
+    /// The section moved up, or down if negative, some number of semitones, into the key
+    /// of the same mode whose signature has the fewest sharps or flats, e.g. Db rather than C# major.
+    /// Chords are respelled as by [LeadSheet::transposed_to], and the melody in the new key.
+    /// Fretted notes of the melody stay on their strings. Errors if a note of the melody
+    /// would leave the range of MIDI, or its string.
+    pub fn transposed(&self, semitones: i8) -> Result<Self, MusicSemanticsError> {
+        let key = &self.lead_sheet.key;
+        let tonic = Pc::from(&key.tonic).transpose(semitones).notes().into_iter()
+            .filter_map(|tonic| Key::new(tonic, key.mode).ok())
+            .min_by_key(|key| key.fifths().abs())
+            .ok_or(MusicSemanticsError::TheoreticalKey(key.tonic))?
+            .tonic;
+        let lead_sheet = self.lead_sheet.transposed_to(tonic)?;
+        let melody = transpose_events(&self.melody, semitones, &lead_sheet.key)?;
+        Ok(Self { name: self.name.clone(), lead_sheet, melody })
+    }
+}
+
+fn transpose_events<'a>(
+    events: &[RhythmicNotatedEvent<'a>],
+    semitones: i8,
+    key: &Key,
+) -> Result<Vec<RhythmicNotatedEvent<'a>>, MusicSemanticsError> {
+    let pitch = |pitch: &Pitch| Pitch::from_midi_in_key(pitch.at_distance_from(semitones as isize)?.midi_note, key);
+    let fretted = |note: &SoundedNote<'a>| {
+        let moved = if semitones < 0 {
+            note.down_n_frets(semitones.unsigned_abs())?
+        } else {
+            note.up_n_frets(semitones as u8)?
+        };
+        moved.spelled_as_in(&vec![key.spell_pc(&Pc::from(&moved.pitch.note))])
+    };
+    events.iter()
+        .map(|event| {
+            let transposed = match &event.event {
+                NotatedEvent::SingleEvent(single, duration) => NotatedEvent::SingleEvent(match single {
+                    SingleEvent::Pitch(p) => SingleEvent::Pitch(pitch(p)?),
+                    SingleEvent::Voicing(voicing) => SingleEvent::Voicing(Voicing::new(voicing.iter()
+                        .map(pitch)
+                        .collect::<Result<_, _>>()?)),
+                    SingleEvent::Fretted(note) => SingleEvent::Fretted(fretted(note)?),
+                    SingleEvent::FrettedMany(notes) => SingleEvent::FrettedMany(notes.iter()
+                        .map(fretted)
+                        .collect::<Result<_, _>>()?),
+                    SingleEvent::Rest => SingleEvent::Rest,
+                }, *duration),
+                NotatedEvent::Tuplet(tuplet) => {
+                    let mut tuplet = tuplet.clone();
+                    tuplet.events = transpose_events(&tuplet.events, semitones, key)?;
+                    NotatedEvent::Tuplet(tuplet)
+                },
+            };
+            Ok(RhythmicNotatedEvent { event: transposed, ..event.clone() })
+        })
+        .collect()
+}
+
+/// A whole song: its sections, in the order they're played, each with its own key, meter,
+/// chords, and melody, so that the key or meter may change from one section to the next.
+#[derive(Debug, Clone, Default)]
+pub struct Song<'a> {
+    pub sections: Vec<SongSection<'a>>,
+}
+
+impl<'a> Song<'a> {
+    pub fn new() -> Self {
+        Self { sections: vec![] }
+    }
+
+    pub fn section(mut self, section: SongSection<'a>) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    /// The first section with the given name.
+    pub fn get(&self, name: &str) -> Option<&SongSection<'a>> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Every section transposed by the same number of semitones, see [SongSection::transposed].
+    pub fn transposed(&self, semitones: i8) -> Result<Self, MusicSemanticsError> {
+        Ok(Self {
+            sections: self.sections.iter()
+                .map(|section| section.transposed(semitones))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The chords of the whole song, one section after another, each lasting as long as it does
+    /// in its section's meter. Only the first section's pickup is kept.
+    /// Errors if a section's progression has a duration for some of its chords, but not all.
+    pub fn chords(&self) -> Result<Progression, MusicSemanticsError> {
+        let mut chords = vec![];
+        let mut durations: Vec<DurationTicks> = vec![];
+        for section in &self.sections {
+            let progression = &section.lead_sheet.progression;
+            let meter = &section.lead_sheet.meter;
+            if progression.durations.is_empty() {
+                let beat: Duration = (&meter.denominator).into();
+                durations.extend(progression.chords.iter().map(|_| beat.ticks() * meter.num_beats));
+            } else if progression.durations.len() == progression.chords.len() {
+                durations.extend(&progression.durations);
+            } else {
+                return Err(MusicSemanticsError::MismatchedCollectionSize(
+                    progression.chords.len(),
+                    progression.durations.len(),
+                ));
+            }
+            chords.extend(progression.chords.iter().cloned());
+        }
+        Ok(Progression {
+            chords,
+            durations,
+            pickup: self.sections.first().and_then(|section| section.lead_sheet.progression.pickup),
+        })
+    }
+
+    /// The melody of the whole song, one section after another.
+    pub fn melody(&self) -> Vec<RhythmicNotatedEvent<'a>> {
+        self.sections.iter()
+            .flat_map(|section| section.melody.iter().cloned())
+            .collect()
+    }
+
+    /// The lead sheet of the whole song: each section's name, and its chord chart, see [LeadSheet::chart].
+    /// The key and meter are written by the name of the first section, and of any other they change in, e.g.
+    ///
+    /// ```text
+    /// Verse (G major, 4/4)
+    /// | GMaj | CMaj | DMaj | GMaj |
+    ///
+    /// Chorus
+    /// | CMaj | DMaj | GMaj | % |
+    /// ```
+    pub fn chart(&self, bars_per_line: usize) -> Result<String, MusicSemanticsError> {
+        let mut previous: Option<&LeadSheet> = None;
+        self.sections.iter()
+            .map(|section| {
+                let sheet = &section.lead_sheet;
+                let mut changes = vec![];
+                if previous.is_none_or(|previous| previous.key != sheet.key) {
+                    let mode = match sheet.key.mode {
+                        KeyMode::Major => "major",
+                        KeyMode::Minor => "minor",
+                    };
+                    changes.push(format!("{} {}", sheet.key.tonic, mode));
+                }
+                if previous.is_none_or(|previous| previous.meter != sheet.meter) {
+                    changes.push(format!("{}/{}", sheet.meter.num_beats, sheet.meter.denominator.to_string()));
+                }
+                previous = Some(sheet);
+                let heading = match changes.is_empty() {
+                    true => section.name.clone(),
+                    false => format!("{} ({})", section.name, changes.join(", ")),
+                };
+                Ok(format!("{}\n{}", heading, sheet.chart(bars_per_line)?))
+            })
+            .collect::<Result<Vec<_>, MusicSemanticsError>>()
+            .map(|sections| sections.join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::note::Note;
+    use crate::note_collections::NoteSet;
+    use crate::notation::rhythm::meter::MeterDenominator;
+
+    #[test]
+    fn song_sections() {
+        let chords = |chords: Vec<Vec<Note>>| Progression::new(chords.into_iter()
+            .map(NoteSet::starting_from_first_note)
+            .collect());
+        let (g, c, d) = (vec![Note::G, Note::B, Note::D], vec![Note::C, Note::E, Note::G], vec![Note::D, Note::Fis, Note::A]);
+        let in_g = Key::new(Note::G, KeyMode::Major).unwrap();
+        let four_four = Meter::new(4, MeterDenominator::Four, None);
+        let three_four = Meter::new(3, MeterDenominator::Four, None);
+        let pitch = |note, octave| RhythmicNotatedEvent::pitch(Pitch::new(note, octave).unwrap(), Duration::HALF);
+        let song = Song::new()
+            .section(SongSection::new("Verse", in_g, four_four.clone(), chords(vec![g.clone(), c.clone(), d.clone(), g.clone()]))
+                .with_melody(vec![pitch(Note::B, 4), pitch(Note::Fis, 4), RhythmicNotatedEvent::rest(Duration::WHOLE)]))
+            .section(SongSection::new("Chorus", in_g, four_four.clone(), chords(vec![c.clone(), d.clone(), g.clone(), g.clone()])))
+            .section(SongSection::new(
+                "Bridge",
+                Key::new(Note::Bes, KeyMode::Major).unwrap(),
+                three_four,
+                chords(vec![vec![Note::Bes, Note::D, Note::F], vec![Note::Ees, Note::G, Note::Bes]]),
+            ));
+        assert_eq!(song.chart(4).unwrap(), [
+            "Verse (G major, 4/4)\n| GMaj | CMaj | DMaj | GMaj |",
+            "Chorus\n| CMaj | DMaj | GMaj | GMaj |",
+            "Bridge (Bb major, 3/4)\n| BbMaj | EbMaj |",
+        ].join("\n\n"));
+        assert_eq!(song.get("Bridge").unwrap().lead_sheet.progression.chords.len(), 2);
+        assert!(song.get("Outro").is_none());
+
+        // Each section's chords last a bar of its own meter.
+        let whole_song = song.chords().unwrap();
+        assert_eq!(whole_song.chords.len(), 10);
+        let (bar, short_bar) = (Duration::WHOLE.ticks(), 3 * Duration::QTR.ticks());
+        assert_eq!(whole_song.durations, [vec![bar; 8], vec![short_bar; 2]].concat());
+        assert_eq!(song.melody().len(), 3);
+
+        // Up a semitone, G major becomes Ab major rather than G# major, and Bb major becomes B major.
+        let up = song.transposed(1).unwrap();
+        assert_eq!(up.chart(4).unwrap(), [
+            "Verse (Ab major, 4/4)\n| AbMaj | DbMaj | EbMaj | AbMaj |",
+            "Chorus\n| DbMaj | EbMaj | AbMaj | AbMaj |",
+            "Bridge (B major, 3/4)\n| BMaj | EMaj |",
+        ].join("\n\n"));
+        let melody: Vec<Option<Pitch>> = up.melody().iter()
+            .map(|event| match &event.event {
+                NotatedEvent::SingleEvent(SingleEvent::Pitch(pitch), _) => Some(*pitch),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(melody, vec![Some(Pitch::new(Note::C, 5).unwrap()), Some(Pitch::new(Note::G, 4).unwrap()), None]);
+        assert!(song.transposed(-127).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::error::MusicSemanticsError;
+use crate::fretboard::Fretboard;
+use crate::fretboard::fretboard_shape::FretboardShape;
+use crate::fretboard::fretboard_shape::chord_shape_search::chord_shapes_on_strings;
+use crate::fretboard::fretted_note::FrettedNote;
+use crate::keyboard::Keyboard;
+use crate::notation::rhythm::RhythmicNotatedEvent;
+use crate::notation::rhythm::duration::Duration;
+use crate::notation::rhythm::meter::{Meter, MeterDenominator};
+use crate::notation::vextab::ToVexTab;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::voicing::{enumerate_voicings, Voicing, VoicingType};
+use crate::progression::render::vextab_staves;
+
+/// One chord voiced in each of its inversions in turn, ascending in register, for practicing
+/// a chord in every inversion, e.g. Cmaj7 in drop 2 on the top four strings, up the neck.
+///
+/// Each voicing has the same [VoicingType], and the bass of each is the next chord tone above
+/// the bass of the one before. The ladder climbs from its lowest voicing up to the same
+/// inversion an octave higher, or as far as the instrument goes.
+#[derive(Debug, Clone)]
+pub struct VoicingLadder<'a> {
+    pub chord: ChordName,
+    /// The voicings, from lowest to highest.
+    pub voicings: Vec<Voicing>,
+    /// The shape of each voicing on the neck, for a ladder on a fretboard.
+    pub shapes: Option<Vec<FretboardShape<'a>>>,
+}
+
+impl<'a> VoicingLadder<'a> {
+    /// The ladder on a fretboard, played on the given `strings`, counting from zero at the lowest.
+    /// Give as many strings as the chord has notes, to keep the whole ladder on one string set.
+    ///
+    /// Errors if the chord has no root, if any of the strings isn't on the fretboard,
+    /// or if the chord has no playable shape of `voicing_type` on the strings.
+    pub fn on_fretboard(
+        chord: &ChordName,
+        fretboard: &'a Fretboard,
+        strings: &[u8],
+        voicing_type: VoicingType,
+    ) -> Result<Self, MusicSemanticsError> {
+        let notes = chord.to_note_set()?;
+        let mut candidates = vec![];
+        for candidate in chord_shapes_on_strings(&notes, fretboard, strings)? {
+            let (_, shape) = candidate?;
+            if shape.is_playable() && shape.voicing_type() == Some(voicing_type) {
+                candidates.push(shape.spelled_as_in(&notes)?);
+            }
+        }
+        // Of the shapes with the same bass, the most compact, and then the lowest on the neck.
+        candidates.sort_by_key(|shape| {
+            let (low, high) = shape.span();
+            (shape.range().0.midi_note, high - low, low)
+        });
+        let tones: Vec<Pc> = notes.iter().map(Pc::from).collect();
+        let shapes = climb(&candidates, &tones, |shape| shape.range().0);
+        if shapes.is_empty() {
+            return Err(MusicSemanticsError::NoPlayableShape(notes.to_vec()));
+        }
+        Ok(Self {
+            chord: chord.clone(),
+            voicings: shapes.iter().map(|shape| shape.to_voicing()).collect(),
+            shapes: Some(shapes),
+        })
+    }
+
+    /// The ladder on a keyboard, from the lowest voicing with its bass at or above `lowest`,
+    /// keeping only voicings that can be played by one hand, or divided between two.
+    ///
+    /// Errors if the chord has no root, or if it has no playable voicing of `voicing_type`
+    /// within two octaves above `lowest`.
+    pub fn on_keyboard(
+        chord: &ChordName,
+        keyboard: &Keyboard,
+        lowest: &Pitch,
+        voicing_type: VoicingType,
+    ) -> Result<Self, MusicSemanticsError> {
+        let notes = chord.to_note_set()?;
+        let pcs: Vec<Pc> = notes.iter().map(Pc::from).collect();
+        let low = if keyboard.contains(lowest) { lowest } else { &keyboard.lowest };
+        // Room for a voicing spread over two octaves, at the top of the ladder.
+        let high = Pitch::from_midi(keyboard.highest.midi_note.min(low.midi_note.saturating_add(36)))?;
+        let mut candidates = vec![];
+        for voicing in enumerate_voicings(&pcs, low, &high, notes.len())? {
+            if voicing.len() == notes.len()
+                && voicing.voicing_type() == Some(voicing_type)
+                && keyboard.suggest_hand_split(&voicing).is_some() {
+                let pitches = voicing.iter()
+                    .map(|pitch| Pitch::new_spelled_as_in(pitch.midi_note, &notes))
+                    .collect::<Result<Vec<_>, _>>()?;
+                candidates.push(Voicing::new(pitches));
+            }
+        }
+        candidates.sort_by_key(|voicing| voicing[0].midi_note);
+        let voicings = climb(&candidates, &pcs, |voicing| voicing[0]);
+        if voicings.is_empty() {
+            return Err(MusicSemanticsError::NoPlayableShape(notes.to_vec()));
+        }
+        Ok(Self { chord: chord.clone(), voicings, shapes: None })
+    }
+
+    /// Each voicing of the ladder held for `duration`, for engraving. A ladder on a fretboard
+    /// is given as fretted notes, so that it can be written as tab.
+    pub fn to_events(&self, duration: Duration) -> Vec<RhythmicNotatedEvent<'a>> {
+        match &self.shapes {
+            Some(shapes) => shapes.iter()
+                .map(|shape| {
+                    let notes = shape.iter()
+                        .filter_map(|note| match note {
+                            FrettedNote::Sounded(sounded) => Some(sounded.clone()),
+                            FrettedNote::Muted { .. } => None,
+                        })
+                        .collect();
+                    RhythmicNotatedEvent::fretted_many(notes, duration)
+                })
+                .collect(),
+            None => self.voicings.iter()
+                .map(|voicing| RhythmicNotatedEvent::voicing(voicing.clone(), duration))
+                .collect(),
+        }
+    }
+
+    /// The ladder as a score, each voicing a whole note in its own bar, to be placed in a
+    /// Lilypond document. A ladder on a fretboard is written in notation over tab.
+    #[cfg(feature = "lilypond")]
+    pub fn to_lilypond(&self) -> Result<String, crate::notation::lilypond::error::LilypondError> {
+        use crate::notation::clef::Clef;
+        use crate::notation::lilypond::ToLilypondString;
+        use crate::notation::lilypond::document::staff::LilypondStaff;
+        use crate::notation::lilypond::document::tab_staff::LilypondTabStaff;
+        use crate::notation::lilypond::scoring::score;
+        use crate::notation::lilypond::staff_elements::LilypondVoiceElement;
+
+        let voice = || self.to_events(Duration::WHOLE).into_iter().map(Into::into).collect::<Vec<LilypondVoiceElement>>();
+        let clef = match (&self.shapes, self.voicings[0][0].midi_note) {
+            (Some(_), _) => Clef::Treble8ba,
+            (None, bass) if bass >= 60 => Clef::Treble,
+            (None, _) => Clef::Bass,
+        };
+        let staff = LilypondStaff::new()
+            .clef(Some(clef))
+            .meter(Some(Meter::new(4, MeterDenominator::Four, None)))
+            .add_voice(voice())
+            .try_to_lilypond_string()?;
+        match &self.shapes {
+            Some(_) => {
+                let tab = LilypondTabStaff::new().add_voice(voice()).try_to_lilypond_string()?;
+                score(format!("<<\n{}\n{}\n>>", staff, tab), false)
+            },
+            None => score(staff, false),
+        }
+    }
+}
+
+/// Notation and tab, each voicing a whole note in its own bar, with the chord's name over the first.
+impl<'a> ToVexTab for VoicingLadder<'a> {
+    fn to_vextab(&self) -> String {
+        let bars: Vec<String> = self.to_events(Duration::WHOLE).iter()
+            .enumerate()
+            .map(|(i, event)| match i {
+                0 => format!("{} ${}$", event.to_vextab(), self.chord.symbol(None)),
+                _ => event.to_vextab(),
+            })
+            .collect();
+        vextab_staves(&bars, &Meter::new(4, MeterDenominator::Four, None))
+    }
+}
+
+/// Climbs from the lowest of some candidate voicings, sorted by their bass, taking the first
+/// with its bass on the next of the chord's `tones` above each time, until it's back on the
+/// inversion it started from, an octave higher.
+fn climb<T: Clone>(candidates: &[T], tones: &[Pc], bass: impl Fn(&T) -> Pitch) -> Vec<T> {
+    let Some(first) = candidates.first() else {
+        return vec![];
+    };
+    let start = bass(first).midi_note;
+    let mut ladder = vec![first.clone()];
+    loop {
+        let previous = bass(ladder.last().unwrap());
+        let from = Pc::from(&previous.note);
+        let step = tones.iter()
+            .map(|tone| from.distance_up_to(tone))
+            .filter(|step| *step > 0)
+            .min()
+            .unwrap_or(12);
+        let next = candidates.iter().find(|candidate| bass(candidate).midi_note == previous.midi_note + step);
+        match next {
+            Some(next) => ladder.push(next.clone()),
+            None => break,
+        }
+        if previous.midi_note + step >= start + 12 {
+            break;
+        }
+    }
+    ladder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fretboard::STD_6STR_GTR;
+    use crate::keyboard::STD_88_KEY_PIANO;
+    use crate::note::note::Note;
+    use crate::note_collections::NoteSet;
+
+    #[test]
+    fn voicing_ladders() {
+        let cmaj7 = ChordName::from_note_set(
+            &NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G, Note::B])
+        ).unwrap();
+        let ladder = VoicingLadder::on_fretboard(&cmaj7, &STD_6STR_GTR, &[2, 3, 4, 5], VoicingType::Drop2).unwrap();
+        let shapes: Vec<String> = ladder.shapes.as_ref().unwrap().iter().map(|shape| shape.to_string()).collect();
+        assert_eq!(shapes, vec!["x-x-2-4-1-3", "x-x-5-5-5-7", "x-x-9-9-8-8", "x-x-10-12-12-12", "x-x-14-16-13-15"]);
+        let basses: Vec<Note> = ladder.voicings.iter().map(|voicing| voicing[0].note).collect();
+        assert_eq!(basses, vec![Note::E, Note::G, Note::B, Note::C, Note::E]);
+        assert!(ladder.voicings.windows(2).all(|pair| pair[0][0].midi_note < pair[1][0].midi_note));
+        assert!(ladder.to_vextab().starts_with("tabstave notation=true time=4/4\nnotes :w (2/4.4/3.1/2.3/1) $CMaj7$"));
+
+        let ladder = VoicingLadder::on_keyboard(&cmaj7, &STD_88_KEY_PIANO, &Pitch::new(Note::C, 4).unwrap(), VoicingType::Closed).unwrap();
+        let voicings: Vec<String> = ladder.voicings.iter()
+            .map(|voicing| voicing.iter().map(|pitch| pitch.to_string()).collect::<Vec<_>>().join(" "))
+            .collect();
+        assert_eq!(voicings, vec![
+            "C4 E4 G4 B4", "E4 G4 B4 C5", "G4 B4 C5 E5", "B4 C5 E5 G5", "C5 E5 G5 B5",
+        ]);
+        assert_eq!(ladder.to_events(Duration::WHOLE).len(), 5);
+
+        // A triad has no drop 2 voicings.
+        let c = ChordName::from_note_set(&NoteSet::starting_from_first_note(vec![Note::C, Note::E, Note::G])).unwrap();
+        assert!(matches!(
+            VoicingLadder::on_fretboard(&c, &STD_6STR_GTR, &[3, 4, 5], VoicingType::Drop2),
+            Err(MusicSemanticsError::NoPlayableShape(_)),
+        ));
+        #[cfg(feature = "lilypond")]
+        assert!(ladder.to_lilypond().unwrap().contains("\\clef treble"));
+    }
+}
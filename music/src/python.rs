@@ -0,0 +1,90 @@
+//! Python bindings, built as the `music` extension module.
+//!
+//! Notes are passed to and from Python as strings (e.g. `"Bb"`, `"F#"`),
+//! and pitches as MIDI note numbers. Any [MusicSemanticsError] is raised
+//! as a `ValueError`.
+use itertools::Itertools;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use crate::error::MusicSemanticsError;
+use crate::fretboard::{Fretboard, STD_6STR_GTR};
+use crate::fretboard::fretboard_shape::chord_shape_search;
+use crate::note::note::Note;
+use crate::note::pitch::Pitch;
+use crate::note::pitch_class::Pc;
+use crate::note_collections::chord_name::ChordName;
+use crate::note_collections::geometry::symmetry::transpositional::Transpose;
+use crate::note_collections::spelling::spell_pc_set;
+use crate::note_collections::{NoteSet, PcSet};
+
+impl From<MusicSemanticsError> for PyErr {
+    fn from(err: MusicSemanticsError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+fn parse_notes(notes: &[String]) -> Result<Vec<Note>, MusicSemanticsError> {
+    notes.iter().map(|note| note.parse()).collect()
+}
+
+/// Names a chord, treating the first note as its root.
+/// Returns `None` if the notes can't be named as a chord.
+#[pyfunction]
+fn name_chord(notes: Vec<String>) -> PyResult<Option<String>> {
+    let notes = NoteSet::starting_from_first_note(parse_notes(&notes)?);
+    Ok(ChordName::from_note_set(&notes).map(|name| name.symbol(None)))
+}
+
+/// Spells a set of pitch classes (integers 0 to 11) over a root note.
+#[pyfunction]
+fn spell(root: String, pcs: Vec<u8>) -> PyResult<Vec<String>> {
+    let root: Note = root.parse()?;
+    let pcs = PcSet::new(pcs.iter().map(Pc::from).collect());
+    Ok(spell_pc_set(&root, &pcs)?
+        .iter()
+        .map(Note::to_string)
+        .collect())
+}
+
+/// Transposes each note up (or down, if negative) some number of semitones.
+#[pyfunction]
+fn transpose(notes: Vec<String>, semitones: i8) -> PyResult<Vec<String>> {
+    Ok(parse_notes(&notes)?
+        .iter()
+        .map(|note| note.transpose(semitones).to_string())
+        .collect())
+}
+
+/// Finds playable shapes of a chord on a fretboard, given as the MIDI notes
+/// of its open strings from thickest to thinnest. Defaults to a guitar in standard tuning.
+///
+/// Shapes are written one fret per string, thickest string first, with muted strings as "x",
+/// e.g. `"x-3-2-0-1-0"`. They are sorted from lowest to highest on the neck.
+#[pyfunction]
+#[pyo3(signature = (notes, tuning=None))]
+fn find_chord_shapes(notes: Vec<String>, tuning: Option<Vec<u8>>) -> PyResult<Vec<String>> {
+    let fretboard = match tuning {
+        Some(tuning) => Fretboard::new(tuning.into_iter()
+            .map(Pitch::from_midi)
+            .collect::<Result<_, _>>()?
+        ),
+        None => STD_6STR_GTR.clone(),
+    };
+    let result = chord_shape_search::find_chord_shapes(&parse_notes(&notes)?, &fretboard)?;
+    Ok(result.playable.values()
+        .chain(result.nontransposable.values())
+        .flatten()
+        .map(|shape| (shape.span().1, shape.to_string()))
+        .sorted()
+        .map(|(_, shape)| shape)
+        .collect())
+}
+
+#[pymodule]
+fn music(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(name_chord, m)?)?;
+    m.add_function(wrap_pyfunction!(spell, m)?)?;
+    m.add_function(wrap_pyfunction!(transpose, m)?)?;
+    m.add_function(wrap_pyfunction!(find_chord_shapes, m)?)?;
+    Ok(())
+}
@@ -2,6 +2,8 @@ use music::note_collections::octave_partition::OctavePartition;
 use music::note_collections::pc_set::PcSet;
 use music::note::pitch_class::Pc;
 use music::note::pitch_class::Pc::*;
+use music::note_collections::chord_name::ChordQuality;
+use music::note_collections::chord_name::quality::chord::{Alt, AltChoice, AugSubtype, DimSubtype, Extension, MajorSubtype, MinorSubtype};
 use anyhow::anyhow;
 use crate::canonical_voicings::CanonicalVoicings;
 
@@ -60,25 +62,65 @@ pub enum FourNoteChordQuality {
 }
 
 impl FourNoteChordQuality {
+    /// Every quality, in declaration order.
+    pub const ALL: &'static [FourNoteChordQuality] = &[
+        FourNoteChordQuality::Maj7,
+        FourNoteChordQuality::Dom7,
+        FourNoteChordQuality::Min7,
+        FourNoteChordQuality::MinMaj7,
+        FourNoteChordQuality::Dim7,
+        FourNoteChordQuality::Min7Flat5,
+        FourNoteChordQuality::Aug7,
+        FourNoteChordQuality::AugMaj7,
+        FourNoteChordQuality::Dom7Flat5,
+        FourNoteChordQuality::Maj9,
+        FourNoteChordQuality::MinFlat9,
+        FourNoteChordQuality::MajFlat9,
+        FourNoteChordQuality::MajSharp9,
+        FourNoteChordQuality::Min9,
+        FourNoteChordQuality::Dim9,
+        FourNoteChordQuality::DimFlat9,
+        FourNoteChordQuality::Maj11,
+        FourNoteChordQuality::MajSharp11,
+        FourNoteChordQuality::Min11,
+        FourNoteChordQuality::MinSharp11,
+        FourNoteChordQuality::Dim11,
+        FourNoteChordQuality::DimFlat11,
+        FourNoteChordQuality::PPP,
+        FourNoteChordQuality::APP,
+        FourNoteChordQuality::PAP,
+        FourNoteChordQuality::PPA,
+        FourNoteChordQuality::WWW,
+        FourNoteChordQuality::HWW,
+        FourNoteChordQuality::WHW,
+        FourNoteChordQuality::WWH,
+        FourNoteChordQuality::HAH,
+        FourNoteChordQuality::AHH,
+        FourNoteChordQuality::HHA,
+        FourNoteChordQuality::HWH,
+        FourNoteChordQuality::WHH,
+        FourNoteChordQuality::HHW,
+        FourNoteChordQuality::HHM,
+        FourNoteChordQuality::MHH,
+        FourNoteChordQuality::HAW,
+        FourNoteChordQuality::WAH,
+        FourNoteChordQuality::HHH,
+        FourNoteChordQuality::PHP,
+        FourNoteChordQuality::PPH,
+    ];
+
     pub fn identify(pcs: &PcSet) -> anyhow::Result<(usize, FourNoteChordQuality)> {
         if pcs.len() != 4 {
             return Err(anyhow!("wrong size for four note chord: {:?}", pcs));
         }
-        // try the first mode
-        if let Ok(quality) = FourNoteChordQuality::try_from(pcs) {
-            return Ok((0, quality));
-        }
-        // then try the other two, rotating each time
-        let mut copied = pcs.clone();
-        for i in 0usize..3 {
-            copied = copied.rotate_fwd();
-            if let Ok(quality) = FourNoteChordQuality::try_from(&copied) {
-                return Ok((i+1, quality));
-            }
-        }
-        // this chord quality is combinatorically exhaustive, it should always
-        // find a quality at inversion 0, 1, 2, or 3.
-        unreachable!()
+        // this chord quality is combinatorically exhaustive, so every chord
+        // is a mode of exactly one of its canonical sets.
+        FourNoteChordQuality::ALL.iter()
+            .find_map(|quality| {
+                let canonical = PcSet::from(&OctavePartition::from(quality));
+                pcs.eq_up_to_mode(&canonical).map(|mode| (mode, quality.clone()))
+            })
+            .ok_or_else(|| anyhow!("4NC not recognized: {:?}", pcs))
     }
 }
 
@@ -239,6 +281,52 @@ impl TryFrom<&PcSet> for FourNoteChordQuality {
     }
 }
 
+/// The conventional chord name for a quality in its canonical mode, where there is one.
+/// Seventh chords name as such, triads with an added ninth or eleventh as added-note chords,
+/// e.g. "add9", and triads with any other added note as the triad with an alteration, e.g. "Maj (b9)".
+///
+/// Qualities that only name as some chord with a note missing are an error,
+/// as is [FourNoteChordQuality::DimFlat11], whose flat eleventh is heard as a major third.
+impl TryFrom<&FourNoteChordQuality> for ChordQuality {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &FourNoteChordQuality) -> Result<Self, Self::Error> {
+        let seventh = || vec![Extension::Seventh];
+        let alt = |choice: AltChoice| Alt::from(vec![choice]);
+        Ok(match value {
+            FourNoteChordQuality::Maj7 => ChordQuality::Major(MajorSubtype::MajN(seventh(), Alt::empty())),
+            FourNoteChordQuality::Dom7 => ChordQuality::Major(MajorSubtype::N(seventh(), Alt::empty())),
+            FourNoteChordQuality::Min7 => ChordQuality::Minor(MinorSubtype::MinN(seventh(), Alt::empty())),
+            FourNoteChordQuality::MinMaj7 => ChordQuality::Minor(MinorSubtype::MinMajN(seventh(), Alt::empty())),
+            FourNoteChordQuality::Dim7 => ChordQuality::Dim(DimSubtype::DimN(seventh(), Alt::empty())),
+            FourNoteChordQuality::Min7Flat5 => ChordQuality::Dim(DimSubtype::MinNb5(seventh(), Alt::empty())),
+            FourNoteChordQuality::Aug7 => ChordQuality::Aug(AugSubtype::AugN(seventh(), Alt::empty())),
+            FourNoteChordQuality::AugMaj7 => ChordQuality::Aug(AugSubtype::AugMajN(seventh(), Alt::empty())),
+            FourNoteChordQuality::Maj9 => ChordQuality::Major(MajorSubtype::Add9(Alt::empty())),
+            FourNoteChordQuality::MinFlat9 => ChordQuality::Minor(MinorSubtype::Min(alt(AltChoice::FlatNine))),
+            FourNoteChordQuality::MajFlat9 => ChordQuality::Major(MajorSubtype::Maj(alt(AltChoice::FlatNine))),
+            FourNoteChordQuality::MajSharp9 => ChordQuality::Major(MajorSubtype::Maj(alt(AltChoice::SharpNine))),
+            FourNoteChordQuality::Min9 => ChordQuality::Minor(MinorSubtype::MinAdd9(Alt::empty())),
+            FourNoteChordQuality::Dim9 => ChordQuality::Dim(DimSubtype::Dim(alt(AltChoice::Nine))),
+            FourNoteChordQuality::DimFlat9 => ChordQuality::Dim(DimSubtype::Dim(alt(AltChoice::FlatNine))),
+            FourNoteChordQuality::Maj11 => ChordQuality::Major(MajorSubtype::Add11(Alt::empty())),
+            FourNoteChordQuality::MajSharp11 => ChordQuality::Major(MajorSubtype::Maj(alt(AltChoice::SharpEleven))),
+            FourNoteChordQuality::Min11 => ChordQuality::Minor(MinorSubtype::MinAdd11(Alt::empty())),
+            FourNoteChordQuality::MinSharp11 => ChordQuality::Minor(MinorSubtype::Min(alt(AltChoice::SharpEleven))),
+            FourNoteChordQuality::Dim11 => ChordQuality::Dim(DimSubtype::Dim(alt(AltChoice::Eleven))),
+            _ => return Err(anyhow!("no chord quality for 4NC: {:?}", value)),
+        })
+    }
+}
+
+impl TryFrom<FourNoteChordQuality> for ChordQuality {
+    type Error = anyhow::Error;
+
+    fn try_from(value: FourNoteChordQuality) -> Result<Self, Self::Error> {
+        ChordQuality::try_from(&value)
+    }
+}
+
 impl CanonicalVoicings for FourNoteChordQuality {
     const N: usize = 4;
     const FAMILIES: &'static [&'static[usize]] = &[
@@ -254,6 +342,8 @@ impl CanonicalVoicings for FourNoteChordQuality {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
+    use music::note_collections::chord_name::naming_heuristics::infer_chord_quality;
 
     // Convert to and from intervallically descriptive types.
     fn test_quality(quality: FourNoteChordQuality) {
@@ -314,4 +404,34 @@ mod tests {
         test_quality(FourNoteChordQuality::PHP);
         test_quality(FourNoteChordQuality::PPH);
     }
+
+    #[test]
+    fn chord_quality_modes() {
+        // A first inversion dominant seventh, and a diminished seventh in any inversion.
+        assert_eq!(FourNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc3, Pc6, Pc8])).unwrap(), (3, FourNoteChordQuality::Dom7));
+        assert_eq!(FourNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc3, Pc6, Pc9])).unwrap(), (0, FourNoteChordQuality::Dim7));
+        assert!(FourNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc4, Pc7])).is_err());
+    }
+
+    // Chord names agree with the pitch classes of the qualities they name.
+    #[test]
+    fn chord_quality_naming() {
+        let mut named = 0;
+        for quality in FourNoteChordQuality::ALL {
+            let pc_set = PcSet::from(&OctavePartition::from(quality));
+            let pcs: HashSet<Pc> = pc_set.clone().into();
+            let inferred = infer_chord_quality(&pcs).and_then(|(_, quality)| quality);
+            if let Ok(chord_quality) = ChordQuality::try_from(quality) {
+                assert_eq!(chord_quality.to_pc_set(), pc_set, "{:?}", quality);
+                assert_eq!(inferred.as_ref(), Some(&chord_quality), "{:?}", quality);
+                named += 1;
+            }
+            // Naming may imply notes that aren't there, like an omitted fifth, but never drops any.
+            if let Some(inferred) = inferred {
+                let implied = inferred.to_pc_set();
+                assert!(pc_set.iter().all(|pc| implied.contains(pc)), "{:?}", quality);
+            }
+        }
+        assert_eq!(named, 20);
+    }
 }
\ No newline at end of file
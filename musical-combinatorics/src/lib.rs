@@ -2,7 +2,47 @@ pub mod four_note_chords;
 pub mod three_note_chords;
 pub mod canonical_voicings;
 pub mod seven_note_scales;
+pub mod triad_pairs;
 
 pub use crate::three_note_chords::ThreeNoteChordQuality;
 pub use crate::four_note_chords::FourNoteChordQuality;
 pub use crate::seven_note_scales::SevenNoteScaleQuality;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use music::note::Note;
+    use music::note_collections::PcSet;
+    use music::note_collections::chord_name::{ChordName, ChordQuality, TonalSpecification};
+    use music_test_vectors::{CHORDS, SCALES};
+
+    // Results shared with the music crate, so the two crates agree.
+    #[test]
+    fn shared_test_vectors() {
+        for vector in CHORDS {
+            let pcs = PcSet::from(vector.pcs.to_vec());
+            let (mode, name, quality) = match pcs.len() {
+                3 => ThreeNoteChordQuality::identify(&pcs)
+                    .map(|(mode, quality)| (mode, format!("{:?}", quality), ChordQuality::try_from(&quality).ok())),
+                _ => FourNoteChordQuality::identify(&pcs)
+                    .map(|(mode, quality)| (mode, format!("{:?}", quality), ChordQuality::try_from(&quality).ok())),
+            }.unwrap();
+            assert_eq!((name.as_str(), mode), vector.combinatorial, "{:?}", vector.pcs);
+            // Wherever this crate names a chord in its canonical mode, it names it as music does.
+            if let (0, Some(chord_quality)) = (mode, quality) {
+                assert_eq!(chord_quality.to_pc_set(), pcs, "{:?}", vector.pcs);
+                let name = ChordName {
+                    tonality: TonalSpecification::RootPosition(Note::C),
+                    quality: chord_quality,
+                    pc_set: pcs.clone(),
+                    omissions: vec![],
+                };
+                assert_eq!(Some(name.symbol(None).as_str()), vector.name, "{:?}", vector.pcs);
+            }
+        }
+        for vector in SCALES {
+            let quality = SevenNoteScaleQuality::try_from(&PcSet::from(vector.pcs.to_vec())).unwrap();
+            assert_eq!(format!("{:?}", quality), vector.combinatorial);
+        }
+    }
+}
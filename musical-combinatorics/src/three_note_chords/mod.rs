@@ -2,6 +2,8 @@ use music::note_collections::octave_partition::OctavePartition;
 use music::note_collections::pc_set::PcSet;
 use music::note::pitch_class::Pc;
 use music::note::pitch_class::Pc::*;
+use music::note_collections::chord_name::ChordQuality;
+use music::note_collections::chord_name::quality::chord::{Alt, AugSubtype, DimSubtype, MajorSubtype, MinorSubtype, SusSubtype};
 use anyhow::anyhow;
 use crate::canonical_voicings::CanonicalVoicings;
 
@@ -34,25 +36,41 @@ pub enum ThreeNoteChordQuality {
 }
 
 impl ThreeNoteChordQuality {
+    /// Every quality, in declaration order.
+    pub const ALL: &'static [ThreeNoteChordQuality] = &[
+        ThreeNoteChordQuality::Major,
+        ThreeNoteChordQuality::Minor,
+        ThreeNoteChordQuality::Aug,
+        ThreeNoteChordQuality::Dim,
+        ThreeNoteChordQuality::PP,
+        ThreeNoteChordQuality::AP,
+        ThreeNoteChordQuality::PA,
+        ThreeNoteChordQuality::MW,
+        ThreeNoteChordQuality::WM,
+        ThreeNoteChordQuality::MH,
+        ThreeNoteChordQuality::HM,
+        ThreeNoteChordQuality::AW,
+        ThreeNoteChordQuality::WA,
+        ThreeNoteChordQuality::HA,
+        ThreeNoteChordQuality::AH,
+        ThreeNoteChordQuality::WW,
+        ThreeNoteChordQuality::WH,
+        ThreeNoteChordQuality::HW,
+        ThreeNoteChordQuality::HH,
+    ];
+
     pub fn identify(pcs: &PcSet) -> anyhow::Result<(usize, ThreeNoteChordQuality)> {
         if pcs.len() != 3 {
             return Err(anyhow!("wrong size for three note chord: {:?}", pcs));
         }
-        // try the first mode
-        if let Ok(quality) = ThreeNoteChordQuality::try_from(pcs) {
-            return Ok((0, quality));
-        }
-        // then try the other two, rotating each time
-        let mut copied = pcs.clone();
-        for i in 0usize..2 {
-            copied = copied.rotate_fwd();
-            if let Ok(quality) = ThreeNoteChordQuality::try_from(&copied) {
-                return Ok((i+1, quality));
-            }
-        }
-        // this chord quality is combinatorically exhaustive, it should always
-        // find a quality at inversion 0, 1, or 2.
-        unreachable!()
+        // this chord quality is combinatorically exhaustive, so every chord
+        // is a mode of exactly one of its canonical sets.
+        ThreeNoteChordQuality::ALL.iter()
+            .find_map(|quality| {
+                let canonical = PcSet::from(&OctavePartition::from(quality));
+                pcs.eq_up_to_mode(&canonical).map(|mode| (mode, quality.clone()))
+            })
+            .ok_or_else(|| anyhow!("3NC not recognized: {:?}", pcs))
     }
 }
 
@@ -149,37 +167,39 @@ impl CanonicalVoicings for ThreeNoteChordQuality {
     const FAMILIES: &'static [&'static[usize]] = &[&[0,1,2], &[0,2,1]];
 }
 
-// TODO Should this be something more like a vector of possible qualities, one on each mode?
-// impl From<ThreeNoteChordQuality> for ChordQuality {
-//     fn from(chord_quality: ThreeNoteChordQuality) -> Self {
-//         match chord_quality {
-//             ThreeNoteChordQuality::Major => ChordQuality::Major(MajorSubtype::Major(None)),
-//             ThreeNoteChordQuality::Minor => ChordQuality::Minor(MinorSubtype::Min(None)),
-//             ThreeNoteChordQuality::Aug => ChordQuality::Aug(AugSubtype::Aug(None)),
-//             ThreeNoteChordQuality::Dim => ChordQuality::Dim(DimSubtype::Dim(None)),
-//             ThreeNoteChordQuality::PP => {},
-//             ThreeNoteChordQuality::AP => {},
-//             ThreeNoteChordQuality::PA => {},
-//             ThreeNoteChordQuality::MW => {},
-//             ThreeNoteChordQuality::WM => {},
-//             ThreeNoteChordQuality::MH => {},
-//             ThreeNoteChordQuality::HM => {},
-//             ThreeNoteChordQuality::AW => {},
-//             ThreeNoteChordQuality::WA => {},
-//             ThreeNoteChordQuality::HA => {},
-//             ThreeNoteChordQuality::AH => {},
-//             ThreeNoteChordQuality::WW => {},
-//             ThreeNoteChordQuality::WH => {},
-//             ThreeNoteChordQuality::HW => {},
-//             ThreeNoteChordQuality::HH => {},
-//         }
-//     }
-// }
+/// The conventional chord name for a quality in its canonical mode, where there is one.
+/// Other modes of the same quality, e.g. a sus2 chord as a mode of [ThreeNoteChordQuality::PP],
+/// are named by identifying them first with [ThreeNoteChordQuality::identify].
+///
+/// Clusters and other qualities that only name as some chord with a note missing are an error.
+impl TryFrom<&ThreeNoteChordQuality> for ChordQuality {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &ThreeNoteChordQuality) -> Result<Self, Self::Error> {
+        match value {
+            ThreeNoteChordQuality::Major => Ok(ChordQuality::Major(MajorSubtype::Maj(Alt::empty()))),
+            ThreeNoteChordQuality::Minor => Ok(ChordQuality::Minor(MinorSubtype::Min(Alt::empty()))),
+            ThreeNoteChordQuality::Aug => Ok(ChordQuality::Aug(AugSubtype::Aug(Alt::empty()))),
+            ThreeNoteChordQuality::Dim => Ok(ChordQuality::Dim(DimSubtype::Dim(Alt::empty()))),
+            ThreeNoteChordQuality::PP => Ok(ChordQuality::Sus(SusSubtype::Sus4(Alt::empty()))),
+            _ => Err(anyhow!("no chord quality for 3NC: {:?}", value)),
+        }
+    }
+}
 
+impl TryFrom<ThreeNoteChordQuality> for ChordQuality {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ThreeNoteChordQuality) -> Result<Self, Self::Error> {
+        ChordQuality::try_from(&value)
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
+    use music::note_collections::chord_name::naming_heuristics::infer_chord_quality;
     use music::note::Note;
 
     // Convert to and from intervallically descriptive types.
@@ -212,10 +232,42 @@ mod tests {
         test_quality(ThreeNoteChordQuality::HH);
     }
 
+    #[test]
+    fn chord_quality_modes() {
+        // A first inversion major triad, and a sus2 chord as a mode of PP.
+        assert_eq!(ThreeNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc3, Pc8])).unwrap(), (2, ThreeNoteChordQuality::Major));
+        assert_eq!(ThreeNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc2, Pc7])).unwrap(), (2, ThreeNoteChordQuality::PP));
+        assert_eq!(ThreeNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc4, Pc8])).unwrap(), (0, ThreeNoteChordQuality::Aug));
+        assert!(ThreeNoteChordQuality::identify(&PcSet::new(vec![Pc0, Pc4])).is_err());
+    }
+
     #[test]
     fn voicings_3nc() {
         let notes = vec![Note::C, Note::E, Note::G];
         let _ = ThreeNoteChordQuality::voicings(&notes);
         //println!("{:#?}", voicings);
     }
+
+    // Chord names agree with the pitch classes of the qualities they name.
+    #[test]
+    fn chord_quality_naming() {
+        let mut named = 0;
+        for quality in ThreeNoteChordQuality::ALL {
+            let pc_set = PcSet::from(&OctavePartition::from(quality));
+            let pcs: HashSet<Pc> = pc_set.clone().into();
+            let inferred = infer_chord_quality(&pcs).and_then(|(_, quality)| quality);
+            if let Ok(chord_quality) = ChordQuality::try_from(quality) {
+                assert_eq!(chord_quality.to_pc_set(), pc_set, "{:?}", quality);
+                assert_eq!(inferred.as_ref(), Some(&chord_quality), "{:?}", quality);
+                named += 1;
+            }
+            // Naming may imply notes that aren't there, like an omitted fifth, but never drops any.
+            // Except that C D F is named Csus4, without its second, which naming doesn't yet hear as a ninth.
+            if let Some(inferred) = inferred.filter(|_| *quality != ThreeNoteChordQuality::WA) {
+                let implied = inferred.to_pc_set();
+                assert!(pc_set.iter().all(|pc| implied.contains(pc)), "{:?}", quality);
+            }
+        }
+        assert_eq!(named, 5);
+    }
 }
@@ -0,0 +1,180 @@
+use anyhow::anyhow;
+use music::note::{Note, Pc, Pitch};
+use music::note_collections::{OctavePartition, PcSet};
+use music::note_collections::spelling::spell_pc_set;
+use crate::three_note_chords::ThreeNoteChordQuality;
+
+/// A [ThreeNoteChordQuality] built on a particular root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triad {
+    pub root: Note,
+    pub quality: ThreeNoteChordQuality,
+}
+
+impl Triad {
+    pub fn new(root: Note, quality: ThreeNoteChordQuality) -> Self {
+        Self { root, quality }
+    }
+
+    /// The notes of the triad, spelled from its root.
+    pub fn notes(&self) -> anyhow::Result<Vec<Note>> {
+        let pc_set = PcSet::from(&OctavePartition::from(&self.quality));
+        Ok(spell_pc_set(&self.root, &pc_set)?)
+    }
+
+    fn pcs(&self) -> anyhow::Result<Vec<Pc>> {
+        Ok(self.notes()?.iter().map(Pc::from).collect())
+    }
+}
+
+/// The ways of practicing a [TriadPair] that [TriadPair::exercise] generates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriadPairPattern {
+    /// The hexatonic scale of the pair, ascending.
+    Scale,
+    /// Each triad arpeggiated upward in turn, each starting on the next chord tone
+    /// above where the last arpeggio started, so that both triads climb through
+    /// their inversions, e.g. C E G, D F# A, E G C, F# A D for C and D major.
+    Ascending,
+    /// As [TriadPairPattern::Ascending], except that the second triad descends
+    /// from its lowest note above the first, e.g. C E G, A F# D, E G C, D A F#.
+    UpDown,
+}
+
+/// Two triads with no notes in common, which together make up a six-note, hexatonic, scale.
+/// Improvisers alternate between the triads of a pair to outline the scale
+/// with more intervallic variety than playing the scale stepwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriadPair {
+    pub first: Triad,
+    pub second: Triad,
+}
+
+impl TriadPair {
+    /// Errors if the triads have any pitch-class in common.
+    pub fn new(first: Triad, second: Triad) -> anyhow::Result<Self> {
+        let first_pcs = first.pcs()?;
+        if let Some(shared) = second.pcs()?.iter().find(|pc| first_pcs.contains(pc)) {
+            return Err(anyhow!("triads {:?} and {:?} share the pitch-class {:?}", first, second, shared));
+        }
+        Ok(Self { first, second })
+    }
+
+    /// The six notes of the pair, spelled as in their triads,
+    /// in ascending order from the root of the first triad.
+    pub fn hexatonic(&self) -> anyhow::Result<Vec<Note>> {
+        let mut notes = self.first.notes()?;
+        notes.extend(self.second.notes()?);
+        let root = Pc::from(&self.first.root);
+        notes.sort_by_key(|note| root.distance_up_to(&Pc::from(note)));
+        Ok(notes)
+    }
+
+    /// The hexatonic scale of the pair as a [PcSet].
+    pub fn pc_set(&self) -> anyhow::Result<PcSet> {
+        Ok(PcSet::new(self.hexatonic()?.iter().map(Pc::from).collect()))
+    }
+
+    /// Generates `groups` groups of three notes in some [TriadPairPattern],
+    /// beginning on the lowest note of the first triad at or above `start`.
+    /// Groups alternate between the triads, starting with the first.
+    pub fn exercise(&self, pattern: TriadPairPattern, start: &Pitch, groups: usize) -> anyhow::Result<Vec<Pitch>> {
+        let triads = [self.first.notes()?, self.second.notes()?];
+        let mut pitches = vec![];
+        match pattern {
+            TriadPairPattern::Scale => {
+                let scale = self.hexatonic()?;
+                let mut pitch = at_or_above(start.midi_note, &triads[0])?;
+                for _ in 0..groups * 3 {
+                    pitches.push(pitch);
+                    pitch = above(pitch.midi_note, &scale)?;
+                }
+            },
+            TriadPairPattern::Ascending | TriadPairPattern::UpDown => {
+                // Where each triad's last group started.
+                let mut starts: [Option<Pitch>; 2] = [None, None];
+                for group in 0..groups {
+                    let i = group % 2;
+                    let notes = &triads[i];
+                    let descending = pattern == TriadPairPattern::UpDown && i == 1;
+                    let first = if descending {
+                        above(pitches.last().map_or(start.midi_note, |pitch: &Pitch| pitch.midi_note), notes)?
+                    } else {
+                        match (starts[i], starts[1 - i]) {
+                            (Some(previous), _) | (None, Some(previous)) => above(previous.midi_note, notes)?,
+                            (None, None) => at_or_above(start.midi_note, notes)?,
+                        }
+                    };
+                    starts[i] = Some(first);
+                    let mut last = first;
+                    pitches.push(first);
+                    for _ in 1..3 {
+                        last = if descending { below(last.midi_note, notes)? } else { above(last.midi_note, notes)? };
+                        pitches.push(last);
+                    }
+                }
+            },
+        }
+        Ok(pitches)
+    }
+}
+
+/// The lowest pitch of one of `notes` at or above the MIDI note `midi_note`.
+fn at_or_above(midi_note: u8, notes: &Vec<Note>) -> anyhow::Result<Pitch> {
+    let found = (midi_note..=127)
+        .find(|midi_note| notes.iter().any(|note| Pc::from(note) == Pc::from(midi_note)))
+        .ok_or_else(|| anyhow!("no pitch of {:?} at or above MIDI note {}", notes, midi_note))?;
+    Ok(Pitch::new_spelled_as_in(found, notes)?)
+}
+
+/// The lowest pitch of one of `notes` above the MIDI note `midi_note`.
+fn above(midi_note: u8, notes: &Vec<Note>) -> anyhow::Result<Pitch> {
+    at_or_above(midi_note + 1, notes)
+}
+
+/// The highest pitch of one of `notes` below the MIDI note `midi_note`.
+fn below(midi_note: u8, notes: &Vec<Note>) -> anyhow::Result<Pitch> {
+    let found = (0..midi_note).rev()
+        .find(|midi_note| notes.iter().any(|note| Pc::from(note) == Pc::from(midi_note)))
+        .ok_or_else(|| anyhow!("no pitch of {:?} below MIDI note {}", notes, midi_note))?;
+    Ok(Pitch::new_spelled_as_in(found, notes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(pitches: &[Pitch]) -> Vec<String> {
+        pitches.iter().map(|pitch| pitch.to_string()).collect()
+    }
+
+    #[test]
+    fn triad_pairs() {
+        let c = Triad::new(Note::C, ThreeNoteChordQuality::Major);
+        let d = Triad::new(Note::D, ThreeNoteChordQuality::Major);
+        assert!(TriadPair::new(c.clone(), Triad::new(Note::E, ThreeNoteChordQuality::Minor)).is_err());
+        let pair = TriadPair::new(c, d).unwrap();
+        assert_eq!(pair.hexatonic().unwrap(), vec![Note::C, Note::D, Note::E, Note::Fis, Note::G, Note::A]);
+
+        let c4 = Pitch::new(Note::C, 4).unwrap();
+        assert_eq!(
+            names(&pair.exercise(TriadPairPattern::Scale, &c4, 2).unwrap()),
+            vec!["C4", "D4", "E4", "F#4", "G4", "A4"],
+        );
+        assert_eq!(
+            names(&pair.exercise(TriadPairPattern::Ascending, &c4, 4).unwrap()),
+            vec!["C4", "E4", "G4", "D4", "F#4", "A4", "E4", "G4", "C5", "F#4", "A4", "D5"],
+        );
+        assert_eq!(
+            names(&pair.exercise(TriadPairPattern::UpDown, &c4, 4).unwrap()),
+            vec!["C4", "E4", "G4", "A4", "F#4", "D4", "E4", "G4", "C5", "D5", "A4", "F#4"],
+        );
+
+        // Pairs needn't be of the same quality, e.g. C major and G# minor, from the augmented scale.
+        let pair = TriadPair::new(
+            Triad::new(Note::C, ThreeNoteChordQuality::Major),
+            Triad::new(Note::Gis, ThreeNoteChordQuality::Minor),
+        ).unwrap();
+        assert_eq!(pair.pc_set().unwrap(), PcSet::from(vec![0, 3, 4, 7, 8, 11]));
+    }
+}